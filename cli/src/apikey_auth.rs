@@ -1,4 +1,5 @@
 use crate::config::AppConfig;
+use crate::config::secret_store::{default_secret_store, store_api_key};
 use std::io::Write;
 use tokio::sync::mpsc;
 
@@ -86,12 +87,28 @@ async fn render_and_save_api_key(api_key: &str, config: &mut AppConfig) {
         std::process::exit(1);
     }
 
-    config.api_key = Some(api_key.trim().to_string());
+    let api_key = api_key.trim().to_string();
+
+    // With the `keyring` feature the key goes to the OS keychain and the
+    // config file only records the sentinel
+    let stored = match store_api_key(
+        api_key.clone(),
+        &config.profile_name,
+        default_secret_store().as_deref(),
+    ) {
+        Ok(stored) => stored,
+        Err(e) => {
+            eprintln!("Failed to store API key: {}", e);
+            std::process::exit(1);
+        }
+    };
+    config.api_key = Some(stored);
 
     if let Err(e) = config.save() {
         eprintln!("Failed to save config: {}", e);
         std::process::exit(1);
     }
+    config.api_key = Some(api_key);
 
     success_message();
     // add timeout for 2 seconds
@@ -1,5 +1,7 @@
-use stakpak_api::models::{BuildCodeIndexInput, BuildCodeIndexOutput, CodeIndex, SimpleDocument};
-use stakpak_api::{AgentClient, AgentClientConfig, AgentProvider, StakpakConfig};
+use stakpak_api::models::{
+    BuildCodeIndexInput, BuildCodeIndexOutput, CODE_INDEX_FILE, CodeIndex, INDEXING_STATUS_FILE,
+    SimpleDocument,
+};
 use stakpak_shared::file_watcher::{FileWatchEvent, create_and_start_watcher};
 use stakpak_shared::local_store::LocalStore;
 use stakpak_shared::models::indexing::IndexingStatus;
@@ -16,6 +18,7 @@ use stakpak_shared::utils::{
 
 use crate::config::AppConfig;
 use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::sync::OnceLock;
 use tokio::sync::mpsc;
 use tokio::time::{Duration, Instant, interval};
@@ -177,6 +180,7 @@ pub async fn get_or_build_local_code_index(
             .map(|p| p.to_string_lossy().to_string())
             .unwrap_or_else(|_| ".".to_string())
     });
+    let session_dir = LocalStore::get_local_session_store_path();
 
     // First, count supported files to see if we should proceed
     let file_count = count_supported_files(&dir)?;
@@ -192,15 +196,33 @@ pub async fn get_or_build_local_code_index(
             file_count,
             timestamp: Utc::now(),
         };
-        store_indexing_status(&status)?;
+        store_indexing_status(&session_dir, &status)?;
 
         warn!("Skipping code indexing: {}", status.reason);
         return Err(status.reason);
     }
 
-    // Try to load existing index
-    match load_existing_index() {
-        Ok(index) if is_index_fresh(&index) => {
+    load_or_rebuild_index(&session_dir, file_count, || {
+        build_local_code_index(app_config, dir)
+    })
+    .await
+}
+
+/// Serve the stored index if it is intact and fresh, otherwise rebuild it.
+///
+/// A corrupt index is never served: if it cannot be rebuilt it is cleared
+/// and the indexing status records why local_code_search is disabled.
+async fn load_or_rebuild_index<F, Fut>(
+    session_dir: &Path,
+    file_count: usize,
+    rebuild: F,
+) -> Result<CodeIndex, String>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<CodeIndex, String>>,
+{
+    let corruption = match load_existing_index(session_dir) {
+        Ok(Some(index)) if is_index_fresh(&index) => {
             // Index exists and is fresh (less than 10 minutes old)
             let status = IndexingStatus {
                 indexed: true,
@@ -208,17 +230,62 @@ pub async fn get_or_build_local_code_index(
                 file_count,
                 timestamp: index.last_updated,
             };
-            store_indexing_status(&status)?;
-            Ok(index)
+            store_indexing_status(session_dir, &status)?;
+            return Ok(index);
         }
-        Ok(_) => {
+        Ok(Some(_)) => {
             // Index exists but is stale, rebuild it
             warn!("Code index is older than 10 minutes, rebuilding...");
-            rebuild_and_load_index(app_config, Some(dir), file_count).await
+            None
         }
-        Err(_) => {
-            // No index exists or failed to load, build a new one
-            rebuild_and_load_index(app_config, Some(dir), file_count).await
+        // No index exists yet, build a new one
+        Ok(None) => None,
+        Err(corruption) => {
+            warn!("{}, rebuilding...", corruption);
+            Some(corruption)
+        }
+    };
+
+    match rebuild()
+        .await
+        .and_then(|index| save_index(session_dir, &index).map(|()| index))
+    {
+        Ok(index) => {
+            let status = IndexingStatus {
+                indexed: true,
+                reason: format!("Successfully indexed {} files", file_count),
+                file_count,
+                timestamp: index.last_updated,
+            };
+            store_indexing_status(session_dir, &status)?;
+            Ok(index)
+        }
+        Err(e) => {
+            let reason = match corruption {
+                Some(corruption) => {
+                    // Never serve results from a corrupt index
+                    write_session_file(session_dir, CODE_INDEX_FILE, "")
+                        .map_err(|e| format!("Failed to clear code index: {}", e))?;
+                    format!(
+                        "{} and rebuilding failed ({}). local_code_search is disabled until the index is rebuilt.",
+                        corruption, e
+                    )
+                }
+                None => format!(
+                    "Building the code index failed ({}). local_code_search is disabled until the index is rebuilt.",
+                    e
+                ),
+            };
+            store_indexing_status(
+                session_dir,
+                &IndexingStatus {
+                    indexed: false,
+                    reason: reason.clone(),
+                    file_count,
+                    timestamp: Utc::now(),
+                },
+            )?;
+            Err(reason)
         }
     }
 }
@@ -245,32 +312,54 @@ fn count_supported_files(base_dir: &str) -> Result<usize, String> {
     Ok(count)
 }
 
+/// Read a file from the session store; a missing file reads as empty
+fn read_session_file(session_dir: &Path, name: &str) -> String {
+    std::fs::read_to_string(session_dir.join(name)).unwrap_or_default()
+}
+
+/// Write a file to the session store
+fn write_session_file(session_dir: &Path, name: &str, data: &str) -> Result<(), String> {
+    std::fs::create_dir_all(session_dir)
+        .map_err(|e| format!("Failed to create session directory: {}", e))?;
+    let path = session_dir.join(name);
+    std::fs::write(&path, data)
+        .map_err(|e| format!("Failed to write session data to {}: {}", path.display(), e))
+}
+
 /// Store indexing status for use by tools
-fn store_indexing_status(status: &IndexingStatus) -> Result<(), String> {
+fn store_indexing_status(session_dir: &Path, status: &IndexingStatus) -> Result<(), String> {
     let status_json = serde_json::to_string_pretty(status)
         .map_err(|e| format!("Failed to serialize indexing status: {}", e))?;
-    LocalStore::write_session_data("indexing_status.json", &status_json)
-        .map_err(|e| format!("Failed to store indexing status: {}", e))?;
-    Ok(())
+    write_session_file(session_dir, INDEXING_STATUS_FILE, &status_json)
+        .map_err(|e| format!("Failed to store indexing status: {}", e))
 }
 
 /// Load existing index from local storage
-fn load_existing_index() -> Result<CodeIndex, String> {
-    let index_str = LocalStore::read_session_data("code_index.json")
-        .map_err(|e| format!("Failed to read code index: {}", e))?;
-
-    if index_str.is_empty() {
-        return Err("Code index is empty".to_string());
+///
+/// Returns `Ok(None)` when no index has been written yet and `Err` when the
+/// stored index fails its version or checksum check.
+fn load_existing_index(session_dir: &Path) -> Result<Option<CodeIndex>, String> {
+    let index_str = read_session_file(session_dir, CODE_INDEX_FILE);
+
+    if index_str.trim().is_empty() {
+        return Ok(None);
     }
 
-    parse_code_index(&index_str)
+    parse_code_index(&index_str).map(Some)
 }
 
-/// Parse code index from JSON string
+/// Write the index, with its checksum, to local storage
+fn save_index(session_dir: &Path, index: &CodeIndex) -> Result<(), String> {
+    let index_json = index.to_stored_json().inspect_err(|e| {
+        error!("{}", e);
+    })?;
+    write_session_file(session_dir, CODE_INDEX_FILE, &index_json)
+}
+
+/// Parse code index from JSON string, verifying its integrity
 fn parse_code_index(index_str: &str) -> Result<CodeIndex, String> {
-    serde_json::from_str(index_str).map_err(|e| {
-        error!("Failed to parse code index: {}", e);
-        format!("Failed to parse code index: {}", e)
+    CodeIndex::parse_verified(index_str).inspect_err(|e| {
+        error!("Failed to load code index: {}", e);
     })
 }
 
@@ -281,77 +370,26 @@ fn is_index_fresh(index: &CodeIndex) -> bool {
     index.last_updated >= ten_minutes_ago
 }
 
-/// Rebuild the index and load it from storage
-async fn rebuild_and_load_index(
-    app_config: &AppConfig,
-    directory: Option<String>,
-    file_count: usize,
-) -> Result<CodeIndex, String> {
-    build_local_code_index(app_config, directory).await?;
-    let index = load_existing_index()?;
-
-    // Store successful indexing status
-    let status = IndexingStatus {
-        indexed: true,
-        reason: format!("Successfully indexed {} files", file_count),
-        file_count,
-        timestamp: index.last_updated,
-    };
-    store_indexing_status(&status)?;
-
-    Ok(index)
-}
-
 /// Build local code index
 async fn build_local_code_index(
     app_config: &AppConfig,
-    directory: Option<String>,
-) -> Result<usize, String> {
-    let directory = directory.unwrap_or_else(|| {
-        std::env::current_dir()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|_| ".".to_string())
-    });
-
-    let stakpak = app_config
-        .get_stakpak_api_key()
-        .map(|api_key| StakpakConfig {
-            api_key,
-            api_endpoint: app_config.api_endpoint.clone(),
-        });
-
-    let client = AgentClient::new(AgentClientConfig {
-        stakpak,
-        providers: app_config.get_llm_provider_config(),
-        store_path: None,
-        hook_registry: None,
-    })
-    .await
-    .map_err(|e| format!("Failed to create agent client: {}", e))?;
-
+    directory: String,
+) -> Result<CodeIndex, String> {
     let documents = process_directory(&directory)?;
+    let index = index_documents(app_config, documents).await?;
 
-    // TODO: build_code_index is not yet implemented in AgentProvider trait
-    // This feature is currently disabled (code_index module is commented out in main.rs)
-    let _ = client; // Suppress unused warning
-    let _ = documents; // Suppress unused warning
-    return Err("Code indexing is not yet supported with AgentClient".to_string());
-
-    // Create CodeIndex with timestamp
-    let code_index = CodeIndex {
-        last_updated: Utc::now(),
-        index,
-    };
-
-    // Write code_index to .stakpak/code_index.json
-    let index_json = serde_json::to_string_pretty(&code_index).map_err(|e| {
-        error!("Failed to serialize code index: {}", e);
-        format!("Failed to serialize code index: {}", e)
-    })?;
-
-    LocalStore::write_session_data("code_index.json", &index_json)?;
+    // Create CodeIndex with timestamp and version
+    Ok(CodeIndex::new(index))
+}
 
-    Ok(code_index.index.blocks.len())
+/// Index documents through the agent client
+async fn index_documents(
+    _app_config: &AppConfig,
+    documents: Vec<SimpleDocument>,
+) -> Result<BuildCodeIndexOutput, String> {
+    // TODO: build_code_index is not yet implemented in AgentProvider trait
+    let _input = BuildCodeIndexInput { documents };
+    Err("Code indexing is not yet supported with AgentClient".to_string())
 }
 
 fn process_directory(base_dir: &str) -> Result<Vec<SimpleDocument>, String> {
@@ -626,9 +664,15 @@ async fn execute_code_index_update(
         operation, file_uri
     );
 
+    let session_dir = LocalStore::get_local_session_store_path();
+
     // Load existing index
-    let mut existing_index = match load_existing_index() {
-        Ok(index) => index,
+    let mut existing_index = match load_existing_index(&session_dir) {
+        Ok(Some(index)) => index,
+        Ok(None) => {
+            warn!("No existing index for incremental update, skipping");
+            return Ok(()); // Let the next request trigger a full rebuild
+        }
         Err(e) => {
             warn!(
                 "Failed to load existing index for incremental update: {}. Building fresh index.",
@@ -684,27 +728,7 @@ async fn execute_code_index_update(
             }
 
             // Call the indexing API
-            let stakpak = app_config
-                .get_stakpak_api_key()
-                .map(|api_key| StakpakConfig {
-                    api_key,
-                    api_endpoint: app_config.api_endpoint.clone(),
-                });
-
-            let client = AgentClient::new(AgentClientConfig {
-                stakpak,
-                providers: app_config.get_llm_provider_config(),
-                store_path: None,
-                hook_registry: None,
-            })
-            .await
-            .map_err(|e| format!("Failed to create agent client: {}", e))?;
-
-            // TODO: build_code_index is not yet implemented in AgentProvider trait
-            // This feature is currently disabled (code_index module is commented out in main.rs)
-            let _ = client; // Suppress unused warning
-            let _ = documents; // Suppress unused warning
-            return Err("Code indexing is not yet supported with AgentClient".to_string());
+            let new_index = index_documents(app_config, documents).await?;
 
             // Merge the results
             merge_index_results(&mut existing_index.index, new_index, &documents_to_reindex);
@@ -765,14 +789,8 @@ async fn execute_code_index_update(
         }
     }
 
-    // Update timestamp
-    existing_index.last_updated = Utc::now();
-
-    // Save updated index
-    let index_json = serde_json::to_string_pretty(&existing_index)
-        .map_err(|e| format!("Failed to serialize updated code index: {}", e))?;
-
-    LocalStore::write_session_data("code_index.json", &index_json)?;
+    // Update timestamp, then save with a fresh checksum
+    save_index(&session_dir, &CodeIndex::new(existing_index.index))?;
 
     info!(
         "Successfully updated code index for {} operation on {}",
@@ -780,3 +798,86 @@ async fn execute_code_index_update(
     );
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_index() -> CodeIndex {
+        CodeIndex::new(BuildCodeIndexOutput {
+            blocks: Vec::new(),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn read_status(session_dir: &Path) -> IndexingStatus {
+        serde_json::from_str(&read_session_file(session_dir, INDEXING_STATUS_FILE)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn corrupt_index_is_rebuilt() {
+        let session_dir = tempfile::tempdir().unwrap();
+        write_session_file(
+            session_dir.path(),
+            CODE_INDEX_FILE,
+            "{\"version\": 1, \"ind",
+        )
+        .unwrap();
+
+        let index = load_or_rebuild_index(session_dir.path(), 3, || async { Ok(sample_index()) })
+            .await
+            .unwrap();
+
+        assert_eq!(index.version, stakpak_api::models::CODE_INDEX_VERSION);
+        let stored = read_session_file(session_dir.path(), CODE_INDEX_FILE);
+        assert!(CodeIndex::parse_verified(&stored).is_ok());
+        assert!(read_status(session_dir.path()).indexed);
+    }
+
+    #[tokio::test]
+    async fn corrupt_index_that_cannot_be_rebuilt_disables_code_search() {
+        let session_dir = tempfile::tempdir().unwrap();
+        let tampered = sample_index()
+            .to_stored_json()
+            .unwrap()
+            .replace(r#""errors":[]"#, r#""errors":[{}]"#);
+        write_session_file(session_dir.path(), CODE_INDEX_FILE, &tampered).unwrap();
+
+        let err = load_or_rebuild_index(session_dir.path(), 3, || async {
+            Err("indexing service unavailable".to_string())
+        })
+        .await
+        .unwrap_err();
+
+        assert!(err.contains("checksum mismatch"), "{err}");
+        assert!(err.contains("indexing service unavailable"), "{err}");
+        assert_eq!(read_session_file(session_dir.path(), CODE_INDEX_FILE), "");
+
+        let status = read_status(session_dir.path());
+        assert!(!status.indexed);
+        let search_err = CodeIndex::load_for_search("", Some(&status)).unwrap_err();
+        assert!(
+            search_err.contains("local_code_search is disabled until the index is rebuilt"),
+            "{search_err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn fresh_index_is_served_without_rebuilding() {
+        let session_dir = tempfile::tempdir().unwrap();
+        save_index(session_dir.path(), &sample_index()).unwrap();
+
+        let index = load_or_rebuild_index(session_dir.path(), 3, || async {
+            Err("should not rebuild".to_string())
+        })
+        .await
+        .unwrap();
+
+        assert!(is_index_fresh(&index));
+        assert_eq!(
+            read_status(session_dir.path()).reason,
+            "Using existing fresh index"
+        );
+    }
+}
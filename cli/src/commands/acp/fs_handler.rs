@@ -1,4 +1,5 @@
 use agent_client_protocol::{self as acp, Client as AcpClient};
+use stakpak_mcp_server::local_tools::{content_sha256, file_exists_error};
 use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::{mpsc, oneshot};
@@ -155,15 +156,42 @@ pub async fn execute_acp_fs_tool(
                 .and_then(|c| c.as_str())
                 .ok_or_else(|| "Missing 'file_text' parameter".to_string())?;
 
+            let overwrite = args
+                .get("overwrite")
+                .and_then(|o| o.as_bool())
+                .unwrap_or(false);
+
             log::info!("Creating file via ACP: {}", path);
 
-            let (response_tx, response_rx) = oneshot::channel();
             let absolute_path = resolve_absolute_path(path);
             log::info!(
                 "Resolved path '{}' to absolute path: {:?}",
                 path,
                 absolute_path
             );
+
+            if !overwrite {
+                // A failed read means there is nothing to overwrite
+                let (response_tx, response_rx) = oneshot::channel();
+                fs_tx
+                    .send(FsOperation::ReadTextFile {
+                        session_id: session_id.clone(),
+                        path: absolute_path.clone(),
+                        line: None,
+                        limit: None,
+                        response_tx,
+                    })
+                    .map_err(|_| "Failed to send filesystem operation".to_string())?;
+
+                if let Ok(Ok(existing)) = response_rx.await {
+                    return Ok(Some(file_exists_error(
+                        &format!("File already exists: {}", path),
+                        &content_sha256(existing.as_bytes()),
+                    )));
+                }
+            }
+
+            let (response_tx, response_rx) = oneshot::channel();
             fs_tx
                 .send(FsOperation::WriteTextFile {
                     session_id: session_id.clone(),
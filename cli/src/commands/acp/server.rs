@@ -117,9 +117,8 @@ impl StakpakAcpAgent {
             }
 
             // Use credential resolution with auth.toml fallback chain
-            let stakpak = stakpak_api_key.map(|api_key| StakpakConfig {
-                api_key,
-                api_endpoint: config.api_endpoint.clone(),
+            let stakpak = stakpak_api_key.map(|api_key| {
+                StakpakConfig::new(api_key).with_endpoint(config.api_endpoint.clone())
             });
 
             let client = AgentClient::new(AgentClientConfig {
@@ -127,6 +126,9 @@ impl StakpakAcpAgent {
                 providers: config.get_llm_provider_config(),
                 store_path: None,
                 hook_registry: None,
+                title_model: config.get_title_model(),
+                context_limits: config.context_limits.unwrap_or_default(),
+                search_api_url: config.search_api_url.clone(),
             })
             .await
             .map_err(|e| format!("Failed to create agent client: {}", e))?;
@@ -476,7 +478,9 @@ impl StakpakAcpAgent {
 
     // Helper method to determine if a tool should use Diff content type
     fn should_use_diff_content(&self, tool_name: &str) -> bool {
+        // apply_patch edits several files at once, so it has no single-file diff
         super::tool_names::is_fs_file_write(tool_name)
+            && tool_name != super::tool_names::APPLY_PATCH
     }
 
     // Helper method to determine if a tool is a file creation tool
@@ -926,7 +930,9 @@ impl StakpakAcpAgent {
 
             let is_read_tool =
                 super::tool_names::is_fs_file_read(stripped_name) && !is_view_directory;
-            let is_write_tool = super::tool_names::is_fs_file_write(stripped_name);
+            // apply_patch stays local so a multi-file patch applies all-or-nothing
+            let is_write_tool = super::tool_names::is_fs_file_write(stripped_name)
+                && stripped_name != super::tool_names::APPLY_PATCH;
 
             // Delegate fs operations to the client so it can access unsaved editor
             // state and track modifications. Per ACP spec, both read and write
@@ -961,14 +967,16 @@ impl StakpakAcpAgent {
                 // Create cancellation receiver for this tool call
                 let tool_cancel_rx = self.tool_cancel_tx.as_ref().map(|tx| tx.subscribe());
 
-                crate::commands::agent::run::tooling::run_tool_call(
+                let client = self.client.read().await.clone();
+                let model = self.model.read().await.clone();
+                crate::commands::agent::run::tooling::run_tool_call_with_hooks(
+                    client.as_ref(),
+                    &model,
                     mcp_client,
                     &self.mcp_tools,
                     &tool_call,
                     tool_cancel_rx,
                     self.current_session_id.get(),
-                    Some(self.model.read().await.id.clone()),
-                    Some(self.model.read().await.provider.clone()),
                 )
                 .await
                 .map_err(|e| {
@@ -1638,15 +1646,17 @@ impl acp::Agent for StakpakAcpAgent {
             // requests use the authenticated Stakpak provider
             {
                 let config = self.config.read().await;
-                let stakpak = Some(StakpakConfig {
-                    api_key: api_key.clone(),
-                    api_endpoint: config.api_endpoint.clone(),
-                });
+                let stakpak = Some(
+                    StakpakConfig::new(api_key.clone()).with_endpoint(config.api_endpoint.clone()),
+                );
                 let new_client = AgentClient::new(AgentClientConfig {
                     stakpak,
                     providers: config.get_llm_provider_config(),
                     hook_registry: None,
                     store_path: None,
+                    title_model: config.get_title_model(),
+                    context_limits: config.context_limits.unwrap_or_default(),
+                    search_api_url: config.search_api_url.clone(),
                 })
                 .await
                 .map_err(|e| {
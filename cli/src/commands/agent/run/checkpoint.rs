@@ -3,8 +3,9 @@ use rmcp::model::CallToolResult;
 use stakpak_api::AgentProvider;
 use stakpak_shared::models::integrations::{
     mcp::CallToolResultExt,
-    openai::{ChatMessage, MessageContent, Role, ToolCall, ToolCallResult},
+    openai::{ChatMessage, ContentPart, MessageContent, Role, ToolCall, ToolCallResult},
 };
+use stakpak_tui::services::auto_approve::ExactToolApproval;
 use stakpak_tui::{InputEvent, LoadingOperation};
 use uuid::Uuid;
 
@@ -27,27 +28,40 @@ pub async fn get_checkpoint_messages(
     Ok((checkpoint.state.messages, checkpoint.state.metadata))
 }
 
+/// Replays a checkpoint's messages into the TUI and returns them with the
+/// tool calls still waiting for a result. With `checkpoint_markers` the last
+/// assistant message also gets a `<checkpoint_id>` marker appended to its
+/// content, for consumers that still read the id from there; otherwise the
+/// content is left as saved.
 pub async fn extract_checkpoint_messages_and_tool_calls(
     checkpoint_id: &str,
     input_tx: &tokio::sync::mpsc::Sender<InputEvent>,
     messages: Vec<ChatMessage>,
+    checkpoint_markers: bool,
 ) -> Result<(Vec<ChatMessage>, Vec<ToolCall>), String> {
     let mut checkpoint_messages = messages;
-    // Append checkpoint_id to the last assistant message if present
-    if let Some(last_message) = checkpoint_messages
-        .iter_mut()
-        .rev()
-        .find(|message| message.role != Role::User && message.role != Role::Tool)
+    if checkpoint_markers
+        && let Some(last_message) = checkpoint_messages
+            .iter_mut()
+            .rev()
+            .find(|message| message.role != Role::User && message.role != Role::Tool)
         && last_message.role == Role::Assistant
     {
-        last_message.content = Some(MessageContent::String(format!(
-            "{}\n<checkpoint_id>{}</checkpoint_id>",
-            last_message
-                .content
-                .as_ref()
-                .unwrap_or(&MessageContent::String(String::new())),
-            checkpoint_id
-        )));
+        let checkpoint_tag = format!("<checkpoint_id>{checkpoint_id}</checkpoint_id>");
+        last_message.content = Some(match last_message.content.take() {
+            // Flattening to a string would drop any image parts.
+            Some(MessageContent::Array(mut parts)) => {
+                parts.push(ContentPart {
+                    r#type: "text".to_string(),
+                    text: Some(checkpoint_tag),
+                    image_url: None,
+                });
+                MessageContent::Array(parts)
+            }
+            content => {
+                MessageContent::String(format!("{}\n{checkpoint_tag}", content.unwrap_or_default()))
+            }
+        });
     }
 
     for message in &checkpoint_messages {
@@ -178,6 +192,7 @@ pub async fn resume_session_from_checkpoint(
     client: &dyn AgentProvider,
     session_id: &str,
     input_tx: &tokio::sync::mpsc::Sender<InputEvent>,
+    checkpoint_markers: bool,
 ) -> Result<
     (
         Vec<ChatMessage>,
@@ -196,6 +211,7 @@ pub async fn resume_session_from_checkpoint(
                 &checkpoint.id.to_string(),
                 input_tx,
                 checkpoint.state.messages,
+                checkpoint_markers,
             )
             .await?;
 
@@ -212,3 +228,181 @@ pub async fn resume_session_from_checkpoint(
         }
     }
 }
+
+/// Checkpoint metadata key holding the session's exact tool approvals.
+const SESSION_APPROVALS_KEY: &str = "session_approvals";
+/// Checkpoint metadata key holding the model picked with `/model`, as
+/// `provider/short_name`.
+const SESSION_MODEL_KEY: &str = "session_model";
+
+/// Exact tool approvals recorded in checkpoint metadata, if any.
+pub fn session_approvals_from_metadata(
+    metadata: Option<&serde_json::Value>,
+) -> Vec<ExactToolApproval> {
+    metadata
+        .and_then(|meta| meta.get(SESSION_APPROVALS_KEY))
+        .and_then(|approvals| serde_json::from_value(approvals.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Record an exact tool approval in the metadata sent with the next turn, so
+/// it is saved in the checkpoint and restored on resume.
+pub fn record_session_approval(
+    metadata: &mut Option<serde_json::Value>,
+    approval: ExactToolApproval,
+) {
+    let mut approvals = session_approvals_from_metadata(metadata.as_ref());
+    if approvals.contains(&approval) {
+        return;
+    }
+    approvals.push(approval);
+
+    insert_metadata(
+        metadata,
+        SESSION_APPROVALS_KEY,
+        serde_json::to_value(approvals).unwrap_or_default(),
+    );
+}
+
+/// The model recorded for the session, if one was picked mid-session.
+pub fn session_model_from_metadata(metadata: Option<&serde_json::Value>) -> Option<String> {
+    metadata
+        .and_then(|meta| meta.get(SESSION_MODEL_KEY))
+        .and_then(|model| model.as_str())
+        .filter(|model| !model.is_empty())
+        .map(ToString::to_string)
+}
+
+/// Record the model the user switched to in the metadata sent with the next
+/// turn, so resuming the session switches back to it.
+pub fn record_session_model(metadata: &mut Option<serde_json::Value>, model_id: &str) {
+    insert_metadata(metadata, SESSION_MODEL_KEY, serde_json::json!(model_id));
+}
+
+fn insert_metadata(metadata: &mut Option<serde_json::Value>, key: &str, value: serde_json::Value) {
+    let meta = metadata.get_or_insert_with(|| serde_json::json!({}));
+    if !meta.is_object() {
+        *meta = serde_json::json!({});
+    }
+    if let Some(object) = meta.as_object_mut() {
+        object.insert(key.to_string(), value);
+    }
+}
+
+/// Hand the per-session state saved in checkpoint metadata back to the TUI
+/// after a resume: exact tool approvals and the session's model.
+pub async fn restore_session_state(
+    input_tx: &tokio::sync::mpsc::Sender<InputEvent>,
+    metadata: Option<&serde_json::Value>,
+) -> Result<(), String> {
+    send_input_event(
+        input_tx,
+        InputEvent::SessionApprovalsLoaded(session_approvals_from_metadata(metadata)),
+    )
+    .await?;
+    if let Some(model_id) = session_model_from_metadata(metadata) {
+        send_input_event(input_tx, InputEvent::SwitchModel(model_id, true)).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_approvals_round_trip_through_metadata() {
+        let approval = ExactToolApproval {
+            tool_name: "run_command".to_string(),
+            arguments: serde_json::json!({"command": "make test"}),
+        };
+        let mut metadata = Some(serde_json::json!({"trimmed_up_to_message_index": 4}));
+
+        record_session_approval(&mut metadata, approval.clone());
+        record_session_approval(&mut metadata, approval.clone());
+
+        assert_eq!(
+            session_approvals_from_metadata(metadata.as_ref()),
+            vec![approval]
+        );
+        assert_eq!(
+            metadata
+                .as_ref()
+                .and_then(|m| m.get("trimmed_up_to_message_index")),
+            Some(&serde_json::json!(4))
+        );
+    }
+
+    #[test]
+    fn session_model_round_trips_through_metadata() {
+        let mut metadata = None;
+        assert_eq!(session_model_from_metadata(metadata.as_ref()), None);
+
+        record_session_model(&mut metadata, "anthropic/claude-sonnet-4-5");
+        record_session_model(&mut metadata, "openai/gpt-5");
+
+        assert_eq!(
+            session_model_from_metadata(metadata.as_ref()).as_deref(),
+            Some("openai/gpt-5")
+        );
+    }
+
+    #[test]
+    fn missing_or_malformed_session_approvals_are_empty() {
+        assert!(session_approvals_from_metadata(None).is_empty());
+        let metadata = serde_json::json!({"session_approvals": "nope"});
+        assert!(session_approvals_from_metadata(Some(&metadata)).is_empty());
+    }
+
+    async fn resumed_messages(checkpoint_markers: bool) -> (Vec<ChatMessage>, Vec<String>) {
+        let (input_tx, mut input_rx) = tokio::sync::mpsc::channel(16);
+        let (messages, _) = extract_checkpoint_messages_and_tool_calls(
+            "7f8e4c1a-2b3d-4e5f-8a9b-0c1d2e3f4a5b",
+            &input_tx,
+            vec![
+                ChatMessage {
+                    role: Role::User,
+                    content: Some(MessageContent::String("deploy".to_string())),
+                    ..Default::default()
+                },
+                ChatMessage {
+                    role: Role::Assistant,
+                    content: Some(MessageContent::String("Deployed.".to_string())),
+                    ..Default::default()
+                },
+            ],
+            checkpoint_markers,
+        )
+        .await
+        .unwrap();
+        drop(input_tx);
+
+        let mut rendered = Vec::new();
+        while let Some(event) = input_rx.recv().await {
+            if let InputEvent::StreamAssistantMessage(_, content) = event {
+                rendered.push(content);
+            }
+        }
+        (messages, rendered)
+    }
+
+    #[tokio::test]
+    async fn resumed_messages_have_no_checkpoint_marker_by_default() {
+        let (messages, rendered) = resumed_messages(false).await;
+        assert_eq!(rendered, vec!["Deployed.".to_string()]);
+        assert_eq!(extract_checkpoint_id_from_messages(&messages), None);
+    }
+
+    #[tokio::test]
+    async fn checkpoint_markers_flag_appends_the_id_to_the_last_answer() {
+        let (messages, rendered) = resumed_messages(true).await;
+        assert!(
+            rendered[0]
+                .ends_with("<checkpoint_id>7f8e4c1a-2b3d-4e5f-8a9b-0c1d2e3f4a5b</checkpoint_id>")
+        );
+        assert_eq!(
+            extract_checkpoint_id_from_messages(&messages).as_deref(),
+            Some("7f8e4c1a-2b3d-4e5f-8a9b-0c1d2e3f4a5b")
+        );
+    }
+}
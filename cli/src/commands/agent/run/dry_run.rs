@@ -0,0 +1,244 @@
+//! Dry-run mode: run the agent without letting it change anything.
+//!
+//! Read-only tools run for real so the agent can still inspect the system.
+//! Every other tool call is recorded as a step of the proposed plan and
+//! answered with a synthetic "would execute" result, and the consolidated
+//! plan is printed when the run ends.
+
+use serde::Serialize;
+use stakpak_shared::models::integrations::openai::ToolCall;
+use stakpak_shared::utils::strip_tool_name;
+use std::future::Future;
+
+/// Tools that only read state and are safe to run during a dry run.
+///
+/// Anything not listed here, including tools from external MCP servers, is
+/// treated as mutating.
+const READ_ONLY_TOOLS: &[&str] = &[
+    "view",
+    "search_files",
+    "git_status",
+    "git_diff",
+    "search_docs",
+    "load_skill",
+    "view_web_page",
+    "get_all_tasks",
+    "get_task_details",
+    "wait_for_tasks",
+    "generate_password",
+];
+
+/// A tool call the agent intended to make.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedToolCall {
+    pub tool_name: String,
+    pub arguments: serde_json::Value,
+}
+
+/// The tool calls intercepted during a dry run, in the order they were made.
+#[derive(Debug, Default, Serialize)]
+pub struct DryRunPlan {
+    pub steps: Vec<PlannedToolCall>,
+}
+
+impl DryRunPlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_read_only(tool_name: &str) -> bool {
+        READ_ONLY_TOOLS.contains(&strip_tool_name(tool_name))
+    }
+
+    /// Record `tool_call` if it is mutating, returning the synthetic result to
+    /// send back to the model. Returns `None` for read-only tools, which should
+    /// be executed normally.
+    pub fn intercept(&mut self, tool_call: &ToolCall) -> Option<String> {
+        let tool_name = &tool_call.function.name;
+        if Self::is_read_only(tool_name) {
+            return None;
+        }
+
+        let arguments = serde_json::from_str(&tool_call.function.arguments)
+            .unwrap_or_else(|_| serde_json::Value::String(tool_call.function.arguments.clone()));
+        self.steps.push(PlannedToolCall {
+            tool_name: strip_tool_name(tool_name).to_string(),
+            arguments,
+        });
+
+        Some(format!(
+            "DRY RUN: `{}` was not executed. It was recorded as step {} of the proposed plan. \
+             Assume it would succeed and continue with the remaining steps; do not retry it.",
+            strip_tool_name(tool_name),
+            self.steps.len()
+        ))
+    }
+
+    /// The consolidated plan as plain text.
+    pub fn render(&self) -> String {
+        if self.steps.is_empty() {
+            return "Proposed plan: no changes\n".to_string();
+        }
+
+        let mut out = format!("Proposed plan ({} steps):\n", self.steps.len());
+        for (index, step) in self.steps.iter().enumerate() {
+            out.push_str(&format!(
+                "{:>3}. {} {}\n",
+                index + 1,
+                step.tool_name,
+                summarize_arguments(&step.arguments)
+            ));
+        }
+        out
+    }
+}
+
+/// What happened to a tool call passed through [`dispatch`].
+pub enum Dispatched<T> {
+    /// The dry run recorded the call; holds the synthetic result for the model
+    Planned(String),
+    /// The call ran; holds the output of the executor
+    Executed(T),
+}
+
+/// Run `execute` for `tool_call`, unless the dry run records it instead.
+///
+/// `execute` is only awaited when there is no plan or the tool is read-only,
+/// so a recorded call never reaches the tool.
+pub async fn dispatch<T>(
+    plan: Option<&mut DryRunPlan>,
+    tool_call: &ToolCall,
+    execute: impl Future<Output = T>,
+) -> Dispatched<T> {
+    if let Some(plan) = plan
+        && let Some(result) = plan.intercept(tool_call)
+    {
+        return Dispatched::Planned(result);
+    }
+    Dispatched::Executed(execute.await)
+}
+
+/// The most telling argument of common tools, or the compact JSON otherwise.
+fn summarize_arguments(arguments: &serde_json::Value) -> String {
+    for key in ["command", "path"] {
+        if let Some(value) = arguments.get(key).and_then(|value| value.as_str()) {
+            return value.to_string();
+        }
+    }
+    arguments.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stakpak_shared::models::integrations::openai::FunctionCall;
+
+    fn tool_call(name: &str, arguments: serde_json::Value) -> ToolCall {
+        ToolCall {
+            id: format!("call_{}", name),
+            r#type: "function".to_string(),
+            function: FunctionCall {
+                name: name.to_string(),
+                arguments: arguments.to_string(),
+            },
+            metadata: None,
+        }
+    }
+
+    /// Stand-in for the tool server: really applies `create` and
+    /// `str_replace` to disk, so any call that reaches it is visible.
+    async fn execute(tool_call: &ToolCall) -> String {
+        let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments).unwrap();
+        let path = args["path"].as_str().unwrap();
+        match strip_tool_name(&tool_call.function.name) {
+            "create" => std::fs::write(path, args["file_text"].as_str().unwrap()).unwrap(),
+            "str_replace" => {
+                let content = std::fs::read_to_string(path).unwrap();
+                let replaced = content.replace(
+                    args["old_str"].as_str().unwrap(),
+                    args["new_str"].as_str().unwrap(),
+                );
+                std::fs::write(path, replaced).unwrap();
+            }
+            "view" => return std::fs::read_to_string(path).unwrap(),
+            other => panic!("unexpected tool {other}"),
+        }
+        "ok".to_string()
+    }
+
+    #[tokio::test]
+    async fn mutating_calls_are_recorded_and_leave_files_untouched() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let created = dir.path().join("new.txt");
+        let existing = dir.path().join("existing.txt");
+        std::fs::write(&existing, "original").unwrap();
+
+        let mut plan = DryRunPlan::new();
+        let calls = [
+            tool_call(
+                "stakpak__create",
+                serde_json::json!({ "path": created.display().to_string(), "file_text": "hi" }),
+            ),
+            tool_call(
+                "str_replace",
+                serde_json::json!({
+                    "path": existing.display().to_string(),
+                    "old_str": "original",
+                    "new_str": "changed"
+                }),
+            ),
+            tool_call(
+                "view",
+                serde_json::json!({ "path": existing.display().to_string() }),
+            ),
+        ];
+        let mut outcomes = Vec::new();
+        for call in &calls {
+            outcomes.push(dispatch(Some(&mut plan), call, execute(call)).await);
+        }
+
+        assert!(!created.exists());
+        assert_eq!(std::fs::read_to_string(&existing).unwrap(), "original");
+        assert!(
+            matches!(&outcomes[0], Dispatched::Planned(result) if result.starts_with("DRY RUN:"))
+        );
+        assert!(matches!(&outcomes[1], Dispatched::Planned(_)));
+        // Read-only tools still run, and see the untouched file
+        assert!(matches!(&outcomes[2], Dispatched::Executed(content) if content == "original"));
+
+        let rendered = plan.render();
+        assert!(rendered.starts_with("Proposed plan (2 steps):"));
+        assert!(rendered.contains(&format!("  1. create {}", created.display())));
+        assert!(rendered.contains(&format!("  2. str_replace {}", existing.display())));
+    }
+
+    #[tokio::test]
+    async fn calls_execute_without_a_plan() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let created = dir.path().join("new.txt");
+        let call = tool_call(
+            "create",
+            serde_json::json!({ "path": created.display().to_string(), "file_text": "hi" }),
+        );
+
+        let outcome = dispatch(None, &call, execute(&call)).await;
+
+        assert!(matches!(outcome, Dispatched::Executed(_)));
+        assert_eq!(std::fs::read_to_string(&created).unwrap(), "hi");
+    }
+
+    #[test]
+    fn read_only_calls_pass_through() {
+        let mut plan = DryRunPlan::new();
+        assert!(
+            plan.intercept(&tool_call("view", serde_json::json!({ "path": "." })))
+                .is_none()
+        );
+        assert!(
+            plan.intercept(&tool_call("stakpak__read_rulebooks", serde_json::json!({})))
+                .is_none()
+        );
+        assert!(plan.steps.is_empty());
+        assert_eq!(plan.render(), "Proposed plan: no changes\n");
+    }
+}
@@ -0,0 +1,277 @@
+//! Headless frontend for interactive mode.
+//!
+//! Stands in for the TUI when there is no terminal: it consumes the same
+//! `InputEvent` stream the TUI would, answers tool approvals from a
+//! `ToolApprovalPolicy`, and streams plain text to the given writer.
+
+use crate::commands::agent::run::renderer::{OutputFormat, OutputRenderer};
+use serde_json::Value;
+use stakpak_server::{ToolApprovalAction, ToolApprovalPolicy, strip_tool_prefix};
+use stakpak_shared::models::integrations::openai::ToolCall;
+use stakpak_tui::{InputEvent, OutputEvent};
+use std::collections::VecDeque;
+use std::io::Write;
+use tokio::sync::{broadcast, mpsc};
+
+#[derive(Debug, Clone)]
+pub struct HeadlessConfig {
+    /// Prompts sent in order; each one is sent after the previous turn completes.
+    pub prompts: Vec<String>,
+    pub approval_policy: ToolApprovalPolicy,
+}
+
+impl HeadlessConfig {
+    /// Default approval rules with the profile's `auto_approve` tools layered on top.
+    pub fn new(prompts: Vec<String>, auto_approve: Option<&Vec<String>>) -> Self {
+        let overrides = auto_approve
+            .into_iter()
+            .flatten()
+            .map(|name| strip_tool_prefix(name.trim()).to_string())
+            .filter(|name| !name.is_empty())
+            .map(|name| (name, ToolApprovalAction::Approve));
+
+        Self {
+            prompts,
+            approval_policy: ToolApprovalPolicy::with_defaults().with_overrides(overrides),
+        }
+    }
+}
+
+/// Drive an interactive session without a TTY.
+///
+/// Returns once every prompt has been answered, or with an error when a tool
+/// call needs approval that the policy does not grant.
+pub async fn run_headless<W: Write>(
+    mut input_rx: mpsc::Receiver<InputEvent>,
+    output_tx: mpsc::Sender<OutputEvent>,
+    shutdown_tx: broadcast::Sender<()>,
+    config: HeadlessConfig,
+    out: &mut W,
+) -> Result<(), String> {
+    let result = drive_session(&mut input_rx, &output_tx, config, out).await;
+    let _ = shutdown_tx.send(());
+    result
+}
+
+async fn drive_session<W: Write>(
+    input_rx: &mut mpsc::Receiver<InputEvent>,
+    output_tx: &mpsc::Sender<OutputEvent>,
+    config: HeadlessConfig,
+    out: &mut W,
+) -> Result<(), String> {
+    let renderer = OutputRenderer::new(OutputFormat::Text, true);
+    let mut prompts: VecDeque<String> = config.prompts.into();
+
+    let Some(first_prompt) = prompts.pop_front() else {
+        return Err("Headless mode requires a prompt".to_string());
+    };
+    send_user_message(output_tx, first_prompt).await?;
+
+    while let Some(event) = input_rx.recv().await {
+        match event {
+            InputEvent::StreamAssistantMessage(_, delta) => {
+                write!(out, "{}", delta).map_err(|e| e.to_string())?;
+                out.flush().map_err(|e| e.to_string())?;
+            }
+            InputEvent::RunToolCall(tool_call) => {
+                let tool_name = strip_tool_prefix(&tool_call.function.name).to_string();
+                let event = resolve_tool_call(&config.approval_policy, tool_call)?;
+                let label = match event {
+                    OutputEvent::RejectTool(..) => "rejected",
+                    _ => "tool",
+                };
+                writeln!(out, "\n[{}] {}", label, tool_name).map_err(|e| e.to_string())?;
+                output_tx.send(event).await.map_err(|e| e.to_string())?;
+            }
+            InputEvent::ShowAskUserPopup(tool_call, _) => {
+                return Err(format!(
+                    "Tool '{}' needs answers from the user, which headless mode cannot provide",
+                    strip_tool_prefix(&tool_call.function.name)
+                ));
+            }
+            InputEvent::ToolResult(result) => {
+                write!(out, "{}", renderer.render_tool_result(&result.result))
+                    .map_err(|e| e.to_string())?;
+            }
+            InputEvent::Error(message) if message.starts_with("RETRY_ATTEMPT_") => {
+                write!(out, "{}", renderer.render_warning(&message)).map_err(|e| e.to_string())?;
+            }
+            InputEvent::Error(message) => return Err(message),
+            InputEvent::AssistantTurnComplete => {
+                writeln!(out).map_err(|e| e.to_string())?;
+                match prompts.pop_front() {
+                    Some(prompt) => send_user_message(output_tx, prompt).await?,
+                    None => return Ok(()),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+async fn send_user_message(
+    output_tx: &mpsc::Sender<OutputEvent>,
+    prompt: String,
+) -> Result<(), String> {
+    output_tx
+        .send(OutputEvent::UserMessage(prompt, None, Vec::new(), None))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Map the policy decision for a tool call to the event the TUI would have sent.
+fn resolve_tool_call(
+    policy: &ToolApprovalPolicy,
+    tool_call: ToolCall,
+) -> Result<OutputEvent, String> {
+    let arguments = serde_json::from_str::<Value>(&tool_call.function.arguments).ok();
+    match policy.action_for(&tool_call.function.name, arguments.as_ref()) {
+        ToolApprovalAction::Approve => Ok(OutputEvent::AcceptTool(tool_call)),
+        ToolApprovalAction::Deny => Ok(OutputEvent::RejectTool(tool_call, false)),
+        ToolApprovalAction::Ask => Err(format!(
+            "Tool '{}' requires approval, but no headless approval policy covers it. \
+             Add it to the profile's auto_approve list to run it without a TTY.",
+            strip_tool_prefix(&tool_call.function.name)
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stakpak_shared::models::integrations::openai::{
+        FunctionCall, ToolCallResult, ToolCallResultStatus,
+    };
+    use uuid::Uuid;
+
+    fn tool_call(name: &str, arguments: &str) -> ToolCall {
+        ToolCall {
+            id: format!("call_{}", name),
+            r#type: "function".to_string(),
+            function: FunctionCall {
+                name: name.to_string(),
+                arguments: arguments.to_string(),
+            },
+            metadata: None,
+        }
+    }
+
+    fn expect_user_message(event: Option<OutputEvent>, expected: &str) {
+        match event {
+            Some(OutputEvent::UserMessage(prompt, ..)) => assert_eq!(prompt, expected),
+            other => panic!("expected user message, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn scripted_session_runs_to_completion() {
+        let (input_tx, input_rx) = mpsc::channel(16);
+        let (output_tx, mut output_rx) = mpsc::channel(16);
+        let (shutdown_tx, mut shutdown_rx) = broadcast::channel(1);
+        let config = HeadlessConfig::new(
+            vec!["show the readme".to_string(), "thanks".to_string()],
+            Some(&vec!["stakpak__view".to_string()]),
+        );
+        let mut out = Vec::new();
+
+        let agent = async {
+            expect_user_message(output_rx.recv().await, "show the readme");
+            let message_id = Uuid::new_v4();
+            let view = tool_call("stakpak__view", r#"{"path":"README.md"}"#);
+            input_tx
+                .send(InputEvent::StreamAssistantMessage(
+                    message_id,
+                    "Reading".into(),
+                ))
+                .await
+                .unwrap();
+            input_tx
+                .send(InputEvent::RunToolCall(view.clone()))
+                .await
+                .unwrap();
+
+            match output_rx.recv().await {
+                Some(OutputEvent::AcceptTool(call)) => assert_eq!(call.id, view.id),
+                other => panic!("expected accepted tool, got {:?}", other),
+            }
+            input_tx
+                .send(InputEvent::ToolResult(ToolCallResult {
+                    call: view,
+                    result: "# Stakpak".to_string(),
+                    status: ToolCallResultStatus::Success,
+                }))
+                .await
+                .unwrap();
+            input_tx
+                .send(InputEvent::StreamAssistantMessage(
+                    message_id,
+                    " done".into(),
+                ))
+                .await
+                .unwrap();
+            input_tx
+                .send(InputEvent::AssistantTurnComplete)
+                .await
+                .unwrap();
+
+            expect_user_message(output_rx.recv().await, "thanks");
+            input_tx
+                .send(InputEvent::AssistantTurnComplete)
+                .await
+                .unwrap();
+            assert!(output_rx.recv().await.is_none());
+        };
+
+        let (result, ()) = tokio::join!(
+            run_headless(input_rx, output_tx, shutdown_tx, config, &mut out),
+            agent
+        );
+
+        assert!(result.is_ok());
+        assert!(shutdown_rx.try_recv().is_ok());
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Reading"));
+        assert!(out.contains("[tool] view"));
+        assert!(out.contains("# Stakpak"));
+        assert!(out.contains(" done"));
+    }
+
+    #[tokio::test]
+    async fn tool_without_covering_policy_errors() {
+        let (input_tx, input_rx) = mpsc::channel(16);
+        let (output_tx, mut output_rx) = mpsc::channel(16);
+        let (shutdown_tx, _shutdown_rx) = broadcast::channel(1);
+        let config = HeadlessConfig::new(vec!["clean up".to_string()], None);
+        let mut out = Vec::new();
+
+        let agent = async {
+            expect_user_message(output_rx.recv().await, "clean up");
+            input_tx
+                .send(InputEvent::RunToolCall(tool_call(
+                    "stakpak__run_command",
+                    r#"{"command":"rm -rf build"}"#,
+                )))
+                .await
+                .unwrap();
+            assert!(output_rx.recv().await.is_none());
+        };
+
+        let (result, ()) = tokio::join!(
+            run_headless(input_rx, output_tx, shutdown_tx, config, &mut out),
+            agent
+        );
+
+        let error = result.unwrap_err();
+        assert!(error.contains("run_command"));
+        assert!(error.contains("requires approval"));
+    }
+
+    #[test]
+    fn denied_tools_are_rejected_without_stopping() {
+        let policy = ToolApprovalPolicy::from_allowlist(&["view".to_string()]);
+        let event = resolve_tool_call(&policy, tool_call("stakpak__create", "{}")).unwrap();
+        assert!(matches!(event, OutputEvent::RejectTool(_, false)));
+    }
+}
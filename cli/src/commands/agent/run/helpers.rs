@@ -1,6 +1,11 @@
+use stakpak_api::local::hooks::tool_argument_redaction::ToolArgumentRedactionHook;
+use stakpak_api::models::AgentState;
+use stakpak_shared::hooks::{HookRegistry, LifecycleEvent};
 use stakpak_shared::models::integrations::openai::{
-    ChatMessage, FunctionDefinition, MessageContent, Role, Tool, ToolCallResult,
+    ChatMessage, FunctionDefinition, MessageContent, Role, Tool, ToolCall, ToolCallResult,
 };
+use stakpak_shared::secret_manager::SecretManager;
+use stakpak_shared::utils::is_tool_denied;
 use uuid::Uuid;
 
 /// Resolve a short model name against the provider's model catalog.
@@ -93,12 +98,18 @@ pub fn is_first_non_system_message(messages: &[ChatMessage]) -> bool {
 pub fn convert_tools_with_filter(
     tools: &[rmcp::model::Tool],
     allowed_tools: Option<&Vec<String>>,
+    denied_tools: Option<&Vec<String>>,
 ) -> Vec<Tool> {
     tools
         .iter()
         .filter_map(|tool| {
             let tool_name = tool.name.as_ref();
 
+            // Denied tools are dropped even when also allow-listed
+            if denied_tools.is_some_and(|denied| is_tool_denied(denied, tool_name)) {
+                return None;
+            }
+
             // Filter tools based on allowed_tools if specified
             if let Some(allowed) = allowed_tools
                 && !allowed.is_empty()
@@ -155,6 +166,40 @@ pub fn tool_result(tool_call_id: String, result: String) -> ChatMessage {
     }
 }
 
+/// Assistant turn issuing a tool call the user retried from the TUI, so the
+/// retried call's result has a matching tool call in the history
+pub fn retried_tool_call_message(tool_call: ToolCall) -> ChatMessage {
+    ChatMessage {
+        role: Role::Assistant,
+        content: None,
+        name: None,
+        tool_calls: Some(vec![tool_call]),
+        tool_call_id: None,
+        usage: None,
+        ..Default::default()
+    }
+}
+
+/// Hooks that redact secrets in tool call arguments before checkpoints store them
+pub fn tool_argument_redaction_hooks(secret_manager: SecretManager) -> HookRegistry<AgentState> {
+    let mut registry = HookRegistry::default();
+    registry.register(
+        LifecycleEvent::AfterInference,
+        Box::new(ToolArgumentRedactionHook::new(secret_manager)),
+    );
+    registry
+}
+
+/// Redacts secrets in the arguments of a message's tool calls so approval
+/// prompts and history only show placeholders. The MCP proxy restores the
+/// real values from the session redaction map when the tool runs.
+pub fn redact_tool_call_arguments(message: &mut ChatMessage, secret_manager: &SecretManager) {
+    for tool_call in message.tool_calls.iter_mut().flatten() {
+        tool_call.function.arguments =
+            secret_manager.redact_and_store_tool_arguments(&tool_call.function.arguments);
+    }
+}
+
 pub fn tool_call_history_string(tool_calls: &[ToolCallResult]) -> Option<String> {
     if tool_calls.is_empty() {
         return None;
@@ -224,6 +269,54 @@ pub async fn refresh_billing_info(
 mod tests {
     use super::*;
 
+    fn mcp_tools(names: &[&str]) -> Vec<rmcp::model::Tool> {
+        names
+            .iter()
+            .map(|name| {
+                serde_json::from_value(serde_json::json!({
+                    "name": name,
+                    "inputSchema": { "type": "object" }
+                }))
+                .expect("valid tool")
+            })
+            .collect()
+    }
+
+    fn tool_names(tools: &[Tool]) -> Vec<&str> {
+        tools
+            .iter()
+            .map(|tool| tool.function.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_denied_tool_is_removed_even_when_allow_listed() {
+        let tools = mcp_tools(&["view", "run_command", "create"]);
+        let allowed = vec!["view".to_string(), "run_command".to_string()];
+        let denied = vec!["run_command".to_string()];
+
+        let filtered = convert_tools_with_filter(&tools, Some(&allowed), Some(&denied));
+        assert_eq!(tool_names(&filtered), vec!["view"]);
+    }
+
+    #[test]
+    fn test_deny_list_matches_prefixed_tool_names() {
+        let tools = mcp_tools(&["stakpak__view", "stakpak__run_command"]);
+        let denied = vec!["run_command".to_string()];
+
+        let filtered = convert_tools_with_filter(&tools, None, Some(&denied));
+        assert_eq!(tool_names(&filtered), vec!["stakpak__view"]);
+    }
+
+    #[test]
+    fn test_deny_list_without_allow_list_keeps_everything_else() {
+        let tools = mcp_tools(&["view", "run_command", "create"]);
+        let denied = vec!["run_command".to_string()];
+
+        let filtered = convert_tools_with_filter(&tools, None, Some(&denied));
+        assert_eq!(tool_names(&filtered), vec!["view", "create"]);
+    }
+
     #[test]
     fn test_build_plan_mode_instructions_contains_key_sections() {
         let instructions = build_plan_mode_instructions();
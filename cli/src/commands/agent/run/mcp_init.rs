@@ -16,11 +16,14 @@ use stakpak_mcp_client::McpClient;
 use stakpak_mcp_proxy::client::{ClientPoolConfig, ServerConfig};
 use stakpak_mcp_proxy::server::start_proxy_server;
 use stakpak_mcp_server::{
-    EnabledToolsConfig, MCPServerConfig, SubagentConfig, ToolMode, start_server,
+    DEFAULT_MAX_FILE_SIZE, EnabledToolsConfig, MCPServerConfig, SubagentConfig, ToolMode,
+    drain::DEFAULT_SHUTDOWN_GRACE_PERIOD, start_server,
 };
 use stakpak_shared::cert_utils::CertificateChain;
 use stakpak_shared::models::integrations::openai::ToolCallResultProgress;
+use stakpak_shared::secret_manager::ToolRedactionPolicy;
 use stakpak_shared::task_manager::TaskManagerHandle;
+use stakpak_shared::utils::is_tool_denied;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::net::TcpListener;
@@ -147,6 +150,8 @@ async fn start_mcp_server(
     let enable_subagents = mcp_config.enable_subagents;
     let subagent_config = mcp_config.subagent_config.clone();
     let task_manager_handle = mcp_config.task_manager_handle.clone();
+    let run_command_env = app_config.run_command_env.clone().unwrap_or_default();
+    let max_file_size = app_config.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE);
 
     tokio::spawn(async move {
         let server_config = MCPServerConfig {
@@ -158,8 +163,11 @@ async fn start_mcp_server(
             certificate_chain: cert_chain,
             skill_directories: default_skill_directories(),
             subagent_config,
+            run_command_env,
+            max_file_size,
             server_tls_config: None,
             task_manager_handle,
+            shutdown_grace_period: DEFAULT_SHUTDOWN_GRACE_PERIOD,
         };
 
         // Signal that we're about to start
@@ -276,6 +284,7 @@ fn load_external_servers(config_path: &str) -> Result<HashMap<String, ServerConf
 async fn start_proxy(
     pool_config: ClientPoolConfig,
     mcp_config: &McpInitConfig,
+    tool_redaction: ToolRedactionPolicy,
     binding: ServerBinding,
     cert_chain: Arc<CertificateChain>,
     shutdown_rx: broadcast::Receiver<()>,
@@ -295,6 +304,7 @@ async fn start_proxy(
             cert_chain,
             redact_secrets,
             privacy_mode,
+            tool_redaction,
             Some(shutdown_rx),
         )
         .await
@@ -396,6 +406,7 @@ pub async fn initialize_mcp_server_and_tools(
     start_proxy(
         pool_config,
         &mcp_config,
+        app_config.tool_redaction_policy(),
         proxy_binding,
         certs.proxy_chain.clone(),
         proxy_shutdown_rx,
@@ -406,16 +417,26 @@ pub async fn initialize_mcp_server_and_tools(
     let mcp_client = connect_to_proxy(&proxy_url, certs.proxy_chain, progress_tx).await?;
 
     // 7. Get tools from MCP client
-    let mcp_tools = stakpak_mcp_client::get_tools(&mcp_client)
+    let mut mcp_tools = stakpak_mcp_client::get_tools(&mcp_client)
         .await
         .map_err(|e| format!("Failed to get tools: {}", e))?;
 
+    // Denied tools are dropped from the dispatch list too, so a call to one is
+    // rejected even if the model names it without it being offered
+    if let Some(denied) = app_config.denied_tools.as_ref() {
+        mcp_tools.retain(|tool| !is_tool_denied(denied, &tool.name));
+    }
+
     // Use allowed_tools from mcp_config if provided, otherwise fall back to app_config
     let allowed_tools_ref = mcp_config
         .allowed_tools
         .as_ref()
         .or(app_config.allowed_tools.as_ref());
-    let tools = convert_tools_with_filter(&mcp_tools, allowed_tools_ref);
+    let tools = convert_tools_with_filter(
+        &mcp_tools,
+        allowed_tools_ref,
+        app_config.denied_tools.as_ref(),
+    );
 
     Ok(McpInitResult {
         client: mcp_client,
@@ -1,4 +1,6 @@
 pub mod checkpoint;
+pub mod dry_run;
+pub mod headless;
 pub mod helpers;
 pub mod mcp_init;
 pub mod mode_async;
@@ -10,6 +12,7 @@ pub mod stream;
 pub mod tooling;
 pub mod tui;
 
+pub use headless::HeadlessConfig;
 pub use mode_async::{RunAsyncConfig, run_async};
 pub use mode_interactive::{RunInteractiveConfig, run_interactive};
 pub use pause::{AsyncOutcome, ResumeInput};
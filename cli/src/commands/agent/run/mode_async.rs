@@ -1,17 +1,22 @@
 use crate::agent::run::helpers::system_message;
+use crate::commands::agent::run::dry_run::{Dispatched, DryRunPlan, dispatch};
 use crate::commands::agent::run::helpers::{
-    build_plan_mode_instructions, build_resume_command, is_first_non_system_message, tool_result,
-    user_message,
+    build_plan_mode_instructions, build_resume_command, is_first_non_system_message,
+    tool_argument_redaction_hooks, tool_result, user_message,
 };
 use crate::commands::agent::run::mcp_init::{McpInitConfig, initialize_mcp_server_and_tools};
 use crate::commands::agent::run::pause::{
-    AsyncOutcome, ResumeInput, build_resume_hint, detect_pending_tool_calls, write_pause_manifest,
+    AsyncOutcome, AsyncStopReason, EXIT_CODE_CANCELLED, ResumeInput, build_resume_hint,
+    detect_pending_tool_calls, write_pause_manifest,
 };
 use crate::commands::agent::run::renderer::{OutputFormat, OutputRenderer};
-use crate::commands::agent::run::tooling::run_tool_call;
+use crate::commands::agent::run::tooling::{record_tool_audit, run_tool_call_with_hooks};
 use crate::config::AppConfig;
 use crate::utils::agent_context::AgentContext;
-use stakpak_api::{AgentClient, AgentClientConfig, AgentProvider, Model, SessionStorage};
+use stakpak_api::{
+    AgentClient, AgentClientConfig, AgentProvider, Model, RecordToolAuditRequest, SessionStorage,
+    ToolAuditStatus,
+};
 use stakpak_mcp_server::EnabledToolsConfig;
 use stakpak_shared::local_store::LocalStore;
 use stakpak_shared::models::async_manifest::{AsyncManifest, PauseReason, PendingToolCall};
@@ -21,6 +26,7 @@ use stakpak_shared::secret_manager::SecretManager;
 use stakpak_shared::utils::{backward_compatibility_mapping, strip_tool_name};
 use std::collections::HashMap;
 use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 pub struct RunAsyncConfig {
@@ -55,6 +61,8 @@ pub struct RunAsyncConfig {
     pub auto_approve_tools: Option<Vec<String>>,
     /// When true, display session stats and browser URL after completion.
     pub show_session_stats: bool,
+    /// Record mutating tool calls as a proposed plan instead of executing them.
+    pub dry_run: bool,
 }
 
 // All print functions have been moved to the renderer module and are no longer needed here
@@ -90,6 +98,9 @@ impl AsyncAutoApproveConfig {
         // Auto-approve tools (read-only, safe):
         for name in &[
             "view",
+            "search_files",
+            "git_status",
+            "git_diff",
             "generate_password",
             "search_docs",
             "search_memory",
@@ -106,6 +117,7 @@ impl AsyncAutoApproveConfig {
         for name in &[
             "create",
             "str_replace",
+            "apply_patch",
             "generate_code",
             "run_command",
             "run_command_task",
@@ -189,9 +201,12 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
     let mut total_usage = LLMTokenUsage::default();
     let renderer = OutputRenderer::new(config.output_format.clone(), config.verbose);
     let secret_manager = SecretManager::new(config.redact_secrets, config.privacy_mode);
+    let tool_redaction = ctx.tool_redaction_policy();
+    let mut dry_run_plan = config.dry_run.then(DryRunPlan::new);
 
-    // Build auto-approve config if pause_on_approval is enabled
-    let auto_approve = if config.pause_on_approval {
+    // Build auto-approve config if pause_on_approval is enabled; a dry run
+    // never executes mutating tools, so there is nothing to approve
+    let auto_approve = if config.pause_on_approval && !config.dry_run {
         Some(AsyncAutoApproveConfig::new(
             config.auto_approve_tools.as_ref(),
         ))
@@ -231,7 +246,21 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
 
     // Build unified AgentClient config
     let providers = ctx.get_llm_provider_config();
-    let mut client_config = AgentClientConfig::new().with_providers(providers);
+    let mut client_config = AgentClientConfig::new()
+        .with_providers(providers)
+        .with_hook_registry(tool_argument_redaction_hooks(secret_manager.clone()));
+
+    if let Some(title_model) = ctx.get_title_model() {
+        client_config = client_config.with_title_model(title_model);
+    }
+
+    if let Some(context_limits) = ctx.context_limits {
+        client_config = client_config.with_context_limits(context_limits);
+    }
+
+    if let Some(search_api_url) = ctx.search_api_url.clone() {
+        client_config = client_config.with_search_api_url(search_api_url);
+    }
 
     if let Some(api_key) = ctx.get_stakpak_api_key() {
         client_config = client_config.with_stakpak(
@@ -333,31 +362,50 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                     );
 
                     let tool_execution = async {
-                        run_tool_call(
+                        run_tool_call_with_hooks(
+                            &client,
+                            &config.model,
                             &mcp_client,
                             &mcp_tools,
                             tool_call,
                             None,
                             current_session_id,
-                            Some(config.model.id.clone()),
-                            Some(config.model.provider.clone()),
                         )
                         .await
                     };
 
-                    let result = match tokio::time::timeout(
-                        std::time::Duration::from_secs(60 * 60),
-                        tool_execution,
+                    let result = match dispatch(
+                        dry_run_plan.as_mut(),
+                        tool_call,
+                        tokio::time::timeout(
+                            std::time::Duration::from_secs(60 * 60),
+                            tool_execution,
+                        ),
                     )
                     .await
                     {
-                        Ok(result) => result?,
-                        Err(_) => {
+                        Dispatched::Planned(result) => {
+                            print!("{}", renderer.render_tool_result(&result));
+                            chat_messages.push(tool_result(tool_call.id.clone(), result));
+                            continue;
+                        }
+                        Dispatched::Executed(Ok(result)) => result?,
+                        Dispatched::Executed(Err(_)) => {
                             let error_msg = format!(
                                 "Tool '{}' timed out after 60 minutes",
                                 tool_call.function.name
                             );
                             print!("{}", renderer.render_error(&error_msg));
+                            record_tool_audit(
+                                &client,
+                                current_session_id,
+                                RecordToolAuditRequest::approved(
+                                    tool_call,
+                                    ToolAuditStatus::Failed,
+                                    &error_msg,
+                                ),
+                            )
+                            .await;
                             chat_messages.push(tool_result(tool_call.id.clone(), error_msg));
                             continue;
                         }
@@ -374,7 +422,11 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                             .collect::<Vec<String>>()
                             .join("\n");
 
-                        print!("{}", renderer.render_tool_result(&result_content));
+                        let display_content = secret_manager.user_visible_tool_output(
+                            &result_content,
+                            tool_redaction.for_tool(&tool_call.function.name),
+                        );
+                        print!("{}", renderer.render_tool_result(&display_content));
                         chat_messages.push(tool_result(tool_call.id.clone(), result_content));
                     } else {
                         chat_messages
@@ -389,6 +441,12 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                             tool_call.function.name, tool_call.id
                         ))
                     );
+                    record_tool_audit(
+                        &client,
+                        current_session_id,
+                        RecordToolAuditRequest::rejected(tool_call),
+                    )
+                    .await;
                     chat_messages.push(tool_result(
                         tool_call.id.clone(),
                         "TOOL_CALL_REJECTED".to_string(),
@@ -484,8 +542,32 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
     print!("{}", renderer.render_info("Starting execution..."));
     print!("{}", renderer.render_section_break());
 
+    // The first Ctrl+C stops the run before its next model request so the
+    // checkpoint is still saved; a second one exits immediately.
+    let cancel = CancellationToken::new();
+    let interrupt_listener = tokio::spawn({
+        let cancel = cancel.clone();
+        async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                cancel.cancel();
+            }
+            if tokio::signal::ctrl_c().await.is_ok() {
+                std::process::exit(EXIT_CODE_CANCELLED);
+            }
+        }
+    });
+    let mut stop_reason = AsyncStopReason::Completed;
+
     loop {
         step += 1;
+        if cancel.is_cancelled() {
+            print!(
+                "{}",
+                renderer.render_warning("Interrupted, stopping execution")
+            );
+            stop_reason = AsyncStopReason::Cancelled;
+            break;
+        }
         if step > max_steps {
             print!(
                 "{}",
@@ -494,21 +576,29 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                     max_steps
                 ))
             );
+            stop_reason = AsyncStopReason::MaxStepsReached;
             break;
         }
 
         // Make chat completion request
         let llm_start = Instant::now();
-        let response = client
-            .chat_completion(
+        let response = tokio::select! {
+            response = client.chat_completion(
                 config.model.clone(),
                 chat_messages.clone(),
                 Some(tools.clone()),
                 current_session_id,
                 current_metadata.clone(),
-            )
-            .await
-            .map_err(|e| e.to_string())?;
+            ) => response.map_err(|e| e.to_string())?,
+            _ = cancel.cancelled() => {
+                print!(
+                    "{}",
+                    renderer.render_warning("Interrupted, stopping execution")
+                );
+                stop_reason = AsyncStopReason::Cancelled;
+                break;
+            }
+        };
         llm_response_time += llm_start.elapsed();
 
         // Accumulate token usage
@@ -659,6 +749,7 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                     let _ = server_shutdown_tx.send(());
                     let _ = proxy_shutdown_tx.send(());
                     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                    interrupt_listener.abort();
 
                     return Ok(AsyncOutcome::Paused {
                         checkpoint_id: checkpoint_id_str,
@@ -684,31 +775,51 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
 
                 // Add timeout for tool execution
                 let tool_execution = async {
-                    run_tool_call(
+                    run_tool_call_with_hooks(
+                        &client,
+                        &config.model,
                         &mcp_client,
                         &mcp_tools,
                         tool_call,
                         None,
                         current_session_id,
-                        Some(config.model.id.clone()),
-                        Some(config.model.provider.clone()),
                     )
                     .await
                 };
 
-                let result = match tokio::time::timeout(
-                    std::time::Duration::from_secs(60 * 60), // 60 minute timeout
-                    tool_execution,
+                // Dry run: record mutating tools instead of executing them
+                let result = match dispatch(
+                    dry_run_plan.as_mut(),
+                    tool_call,
+                    tokio::time::timeout(
+                        std::time::Duration::from_secs(60 * 60), // 60 minute timeout
+                        tool_execution,
+                    ),
                 )
                 .await
                 {
-                    Ok(result) => result?,
-                    Err(_) => {
+                    Dispatched::Planned(result) => {
+                        print!("{}", renderer.render_tool_result(&result));
+                        chat_messages.push(tool_result(tool_call.id.clone(), result));
+                        continue;
+                    }
+                    Dispatched::Executed(Ok(result)) => result?,
+                    Dispatched::Executed(Err(_)) => {
                         let error_msg = format!(
                             "Tool '{}' timed out after 60 minutes",
                             tool_call.function.name
                         );
                         print!("{}", renderer.render_error(&error_msg));
+                        record_tool_audit(
+                            &client,
+                            current_session_id,
+                            RecordToolAuditRequest::approved(
+                                tool_call,
+                                ToolAuditStatus::Failed,
+                                &error_msg,
+                            ),
+                        )
+                        .await;
                         chat_messages.push(tool_result(tool_call.id.clone(), error_msg));
                         continue;
                     }
@@ -725,8 +836,13 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                         .collect::<Vec<String>>()
                         .join("\n");
 
-                    // Print tool result
-                    print!("{}", renderer.render_tool_result(&result_content));
+                    // Print tool result, with secrets restored for tools that show
+                    // their output to the user
+                    let display_content = secret_manager.user_visible_tool_output(
+                        &result_content,
+                        tool_redaction.for_tool(&tool_call.function.name),
+                    );
+                    print!("{}", renderer.render_tool_result(&display_content));
 
                     chat_messages.push(tool_result(tool_call.id.clone(), result_content.clone()));
                 } else {
@@ -806,6 +922,7 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
             }
         }
     }
+    interrupt_listener.abort();
 
     let elapsed = start_time.elapsed();
     let tool_execution_time = elapsed.saturating_sub(llm_response_time);
@@ -927,6 +1044,28 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
         }
     }
 
+    if let Some(plan) = &dry_run_plan {
+        if config.output_format != OutputFormat::Json {
+            print!("{}", renderer.render_section_break());
+            print!("{}", plan.render());
+        }
+        let plan_json = serde_json::to_string_pretty(plan).unwrap_or_default();
+        match LocalStore::write_session_data("dry_run_plan.json", &plan_json) {
+            Ok(path) => {
+                print!(
+                    "{}",
+                    renderer.render_success(&format!("Saved proposed plan to {}", path))
+                );
+            }
+            Err(e) => {
+                print!(
+                    "{}",
+                    renderer.render_error(&format!("Failed to save proposed plan: {}", e))
+                );
+            }
+        }
+    }
+
     // Save conversation to file
     let conversation_json = serde_json::to_string_pretty(&chat_messages).unwrap_or_default();
     match LocalStore::write_session_data("messages.json", &conversation_json) {
@@ -988,6 +1127,7 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
         session_id: session_id_str,
         agent_message: final_message,
         steps: step - 1,
+        stop_reason,
     };
 
     Ok(outcome)
@@ -1,27 +1,33 @@
 use crate::agent::run::helpers::system_message;
 use crate::commands::agent::run::checkpoint::{
     extract_checkpoint_id_from_messages, extract_checkpoint_messages_and_tool_calls,
-    get_checkpoint_messages, resume_session_from_checkpoint,
+    get_checkpoint_messages, record_session_approval, record_session_model, restore_session_state,
+    resume_session_from_checkpoint,
 };
+use crate::commands::agent::run::headless::{self, HeadlessConfig};
 use crate::commands::agent::run::helpers::{
     build_plan_mode_instructions, build_resume_command, extract_last_checkpoint_id,
-    is_first_non_system_message, refresh_billing_info, tool_call_history_string, tool_result,
-    user_message,
+    is_first_non_system_message, redact_tool_call_arguments, refresh_billing_info,
+    retried_tool_call_message, tool_argument_redaction_hooks, tool_call_history_string,
+    tool_result, user_message,
 };
 use crate::commands::agent::run::mcp_init;
 use crate::commands::agent::run::renderer::{OutputFormat, OutputRenderer};
 use crate::commands::agent::run::stream::process_responses_stream;
-use crate::commands::agent::run::tooling::{list_sessions, run_tool_call};
+use crate::commands::agent::run::tooling::{
+    list_sessions, record_tool_audit, run_tool_call_with_hooks,
+};
 use crate::commands::agent::run::tui::{send_input_event, send_tool_call};
 use crate::commands::warden;
 use crate::config::AppConfig;
 use crate::utils::agent_context::AgentContext;
 use crate::utils::check_update::get_latest_cli_version;
 use crate::utils::cli_colors::CliColors;
+use crate::utils::prompt_template::{merge_prompt_vars, render_prompt_template};
 use reqwest::header::HeaderMap;
 use stakpak_api::local::skills::{default_skill_directories, discover_skills};
 use stakpak_api::models::{ApiStreamError, Skill};
-use stakpak_api::{AgentClient, AgentClientConfig, AgentProvider, Model};
+use stakpak_api::{AgentClient, AgentClientConfig, AgentProvider, Model, RecordToolAuditRequest};
 
 use stakpak_mcp_server::EnabledToolsConfig;
 use stakpak_shared::models::integrations::mcp::CallToolResultExt;
@@ -29,6 +35,7 @@ use stakpak_shared::models::integrations::openai::{
     ChatMessage, MessageContent, Role, ToolCall, ToolCallResultStatus,
 };
 use stakpak_shared::models::llm::{LLMTokenUsage, PromptTokensDetails};
+use stakpak_shared::run_id::start_new_run;
 use stakpak_shared::secret_manager::SecretManager;
 use stakpak_shared::task_manager::TaskManager;
 
@@ -36,7 +43,8 @@ use stakpak_shared::task_manager::TaskManager;
 /// analyze the infrastructure and provide a summary of the current state
 const INIT_PROMPT: &str = include_str!("../../../../../libs/api/src/prompts/init.v4.md");
 use stakpak_shared::telemetry::{TelemetryEvent, capture_event};
-use stakpak_tui::{InputEvent, LoadingOperation, OutputEvent};
+use stakpak_tui::{BannerStyle, InputEvent, LoadingOperation, OutputEvent};
+use std::path::PathBuf;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -204,6 +212,15 @@ pub struct RunInteractiveConfig {
     pub send_init_prompt_on_start: bool,
     /// Theme override: None = auto-detect, Some(theme) = use specified theme
     pub theme: Option<stakpak_tui::services::detect_term::Theme>,
+    /// When set, drive the session without a TTY instead of starting the TUI
+    pub headless: Option<HeadlessConfig>,
+    /// `--var` flags, applied on top of the active profile's `prompt_vars`
+    pub prompt_var_overrides: Vec<(String, String)>,
+    /// When set (`--debug`), append the TUI transcript to this file
+    pub transcript_log: Option<PathBuf>,
+    /// Append `<checkpoint_id>` markers to resumed assistant messages
+    /// (`--checkpoint-markers`); otherwise the id is only read from metadata
+    pub checkpoint_markers: bool,
 }
 
 #[allow(unused_assignments)] // plan_mode_active: written in PlanModeActivated, read in later phases
@@ -212,7 +229,9 @@ pub async fn run_interactive(
     mut config: RunInteractiveConfig,
 ) -> Result<(), String> {
     // Initialize theme detection before starting TUI
-    stakpak_tui::services::detect_term::init_theme(config.theme);
+    if config.headless.is_none() {
+        stakpak_tui::services::detect_term::init_theme(config.theme);
+    }
 
     // Outer loop for profile switching
     'profile_switch_loop: loop {
@@ -241,6 +260,8 @@ pub async fn run_interactive(
         let mut agent_context = config.agent_context.clone();
         let mut all_available_remote_skills: Option<Vec<Skill>> = None;
         let system_prompt = config.system_prompt.clone();
+        // Re-read per profile: a profile switch brings its own prompt_vars
+        let prompt_vars = merge_prompt_vars(&ctx.prompt_vars, &config.prompt_var_overrides);
         let enable_subagents = config.enable_subagents;
         let checkpoint_id = config.checkpoint_id.clone();
         let session_id = config.session_id.clone();
@@ -249,7 +270,9 @@ pub async fn run_interactive(
         let enabled_tools = config.enabled_tools.clone();
         let redact_secrets = config.redact_secrets;
         let privacy_mode = config.privacy_mode;
+        let checkpoint_markers = config.checkpoint_markers;
         let secret_manager = SecretManager::new(redact_secrets, privacy_mode);
+        let tool_redaction = ctx.tool_redaction_policy();
         let enable_mtls = config.enable_mtls;
         let is_git_repo = config.is_git_repo;
         let study_mode = config.study_mode;
@@ -319,7 +342,26 @@ pub async fn run_interactive(
             None
         };
 
+        let headless_config = config.headless.clone();
+        let transcript_log_for_tui = config.transcript_log.clone();
+        let is_headless = headless_config.is_some();
+
         let tui_handle = tokio::spawn(async move {
+            if let Some(mut headless_config) = headless_config {
+                if send_init_prompt_on_start && let Some(init_prompt) = init_prompt_content_for_tui
+                {
+                    headless_config.prompts.insert(0, init_prompt);
+                }
+                return headless::run_headless(
+                    input_rx,
+                    output_tx,
+                    shutdown_tx_for_tui,
+                    headless_config,
+                    &mut std::io::stdout(),
+                )
+                .await;
+            }
+
             let latest_version = get_latest_cli_version().await;
             stakpak_tui::run_tui(
                 input_rx,
@@ -342,6 +384,7 @@ pub async fn run_interactive(
                 recent_models_for_tui,
                 banner_message,
                 task_manager_handle_for_tui,
+                transcript_log_for_tui,
             )
             .await
             .map_err(|e| e.to_string())
@@ -379,7 +422,21 @@ pub async fn run_interactive(
 
             // Build unified AgentClient config
             let providers = ctx_clone.get_llm_provider_config();
-            let mut client_config = AgentClientConfig::new().with_providers(providers);
+            let mut client_config = AgentClientConfig::new()
+                .with_providers(providers)
+                .with_hook_registry(tool_argument_redaction_hooks(secret_manager.clone()));
+
+            if let Some(title_model) = ctx_clone.get_title_model() {
+                client_config = client_config.with_title_model(title_model);
+            }
+
+            if let Some(context_limits) = ctx_clone.context_limits {
+                client_config = client_config.with_context_limits(context_limits);
+            }
+
+            if let Some(search_api_url) = ctx_clone.search_api_url.clone() {
+                client_config = client_config.with_search_api_url(search_api_url);
+            }
 
             if let Some(ref key) = api_key_for_client {
                 client_config = client_config.with_stakpak(
@@ -468,11 +525,17 @@ pub async fn run_interactive(
 
             if let Some(session_id_str) = session_id {
                 let (chat_messages, tool_calls, session_id_uuid, checkpoint_metadata) =
-                    resume_session_from_checkpoint(client.as_ref(), &session_id_str, &input_tx)
-                        .await?;
+                    resume_session_from_checkpoint(
+                        client.as_ref(),
+                        &session_id_str,
+                        &input_tx,
+                        checkpoint_markers,
+                    )
+                    .await?;
 
                 set_session_id(&mut current_session_id, session_id_uuid, &input_tx).await?;
                 current_metadata = checkpoint_metadata;
+                restore_session_state(&input_tx, current_metadata.as_ref()).await?;
                 should_refresh_skills_on_next_message = true;
                 tools_queue.extend(tool_calls.clone());
 
@@ -502,11 +565,13 @@ pub async fn run_interactive(
                 let (checkpoint_messages, checkpoint_metadata) =
                     get_checkpoint_messages(client.as_ref(), &checkpoint_id_str).await?;
                 current_metadata = checkpoint_metadata;
+                restore_session_state(&input_tx, current_metadata.as_ref()).await?;
 
                 let (chat_messages, tool_calls) = extract_checkpoint_messages_and_tool_calls(
                     &checkpoint_id_str,
                     &input_tx,
                     checkpoint_messages,
+                    checkpoint_markers,
                 )
                 .await?;
 
@@ -552,7 +617,7 @@ pub async fn run_interactive(
 
             while let Some(output_event) = output_rx.recv().await {
                 match output_event {
-                    OutputEvent::SwitchToModel(new_model) => {
+                    OutputEvent::SwitchToModel(new_model, restore) => {
                         // Transform model for Stakpak routing if using Stakpak API,
                         // but only for known cloud providers that don't have a direct
                         // API key configured. If the user has a direct provider key,
@@ -573,8 +638,20 @@ pub async fn run_interactive(
                             new_model.clone()
                         };
 
-                        // Save to recent models in config and update TUI state
-                        if let Ok(mut config_file) = AppConfig::load_config_file(&config_path)
+                        // Saved with the next checkpoint so resuming the session
+                        // switches back to this model
+                        record_session_model(
+                            &mut current_metadata,
+                            &crate::config::format_recent_model_id(
+                                &new_model.provider,
+                                &new_model.id,
+                            ),
+                        );
+
+                        // Save to recent models in config and update TUI state.
+                        // Restoring a resumed session's model isn't a new pick.
+                        if !restore
+                            && let Ok(mut config_file) = AppConfig::load_config_file(&config_path)
                             && let Some(profile) = config_file.profiles.get_mut(&profile_name)
                         {
                             // Store in normalized "provider/short_name" format
@@ -623,7 +700,11 @@ pub async fn run_interactive(
                             }
                         }
 
-                        let mut user_input = user_input.clone();
+                        // Typed messages are never rejected for undefined
+                        // placeholders; those stay literal
+                        let mut user_input =
+                            render_prompt_template(&user_input, &prompt_vars, false)
+                                .unwrap_or_else(|_| user_input.clone());
 
                         // Add user shell history to the user input
                         if let Some(tool_call_results) = &tool_calls_results
@@ -800,15 +881,15 @@ pub async fn run_interactive(
                             InputEvent::StartLoadingOperation(LoadingOperation::ToolExecution),
                         )
                         .await?;
-                        let result = if let Some(ref client) = mcp_client {
-                            run_tool_call(
+                        let result = if let Some(ref mcp) = mcp_client {
+                            run_tool_call_with_hooks(
                                 client.as_ref(),
+                                &model,
+                                mcp.as_ref(),
                                 &mcp_tools,
                                 &tool_call,
                                 Some(cancel_rx.resubscribe()),
                                 current_session_id,
-                                Some(model.id.clone()),
-                                Some(model.provider.clone()),
                             )
                             .await?
                         } else {
@@ -867,17 +948,22 @@ pub async fn run_interactive(
                                         content_parts.join("\n")
                                     };
 
-                                    messages.push(tool_result(
-                                        tool_call.clone().id,
-                                        result_content.clone(),
-                                    ));
+                                    // Tools that show output to the user get secrets
+                                    // restored for display only; the model keeps the
+                                    // redacted text.
+                                    let display_content = secret_manager.user_visible_tool_output(
+                                        &result_content,
+                                        tool_redaction.for_tool(&tool_call.function.name),
+                                    );
+                                    messages
+                                        .push(tool_result(tool_call.clone().id, result_content));
 
                                     send_input_event(
                                         &input_tx,
                                         InputEvent::ToolResult(
                                             stakpak_shared::models::integrations::openai::ToolCallResult {
                                                 call: tool_call.clone(),
-                                                result: result_content,
+                                                result: display_content,
                                                 status,
                                             },
                                         ),
@@ -908,6 +994,12 @@ pub async fn run_interactive(
                         }
                     }
                     OutputEvent::RejectTool(tool_call, should_stop) => {
+                        record_tool_audit(
+                            client.as_ref(),
+                            current_session_id,
+                            RecordToolAuditRequest::rejected(&tool_call),
+                        )
+                        .await;
                         messages.push(tool_result(
                             tool_call.id.clone(),
                             "TOOL_CALL_REJECTED".to_string(),
@@ -948,10 +1040,50 @@ pub async fn run_interactive(
                         }
                         continue;
                     }
+                    OutputEvent::RegenerateSessionTitle => {
+                        let Some(session_id) = current_session_id else {
+                            send_input_event(
+                                &input_tx,
+                                InputEvent::Error(
+                                    "No active session to retitle yet, send a message first"
+                                        .to_string(),
+                                ),
+                            )
+                            .await?;
+                            continue;
+                        };
+                        match client.regenerate_title(session_id).await {
+                            Ok(title) => {
+                                send_input_event(
+                                    &input_tx,
+                                    InputEvent::SetBannerMessage(
+                                        format!("Session title: {}", title),
+                                        BannerStyle::Success,
+                                    ),
+                                )
+                                .await?;
+                            }
+                            Err(e) => {
+                                send_input_event(
+                                    &input_tx,
+                                    InputEvent::Error(format!(
+                                        "Failed to regenerate session title: {}",
+                                        e
+                                    )),
+                                )
+                                .await?;
+                            }
+                        }
+                        continue;
+                    }
                     OutputEvent::NewSession => {
                         // Clear the current session and start fresh
+                        start_new_run();
                         current_session_id = None;
                         messages.clear();
+                        current_metadata = None;
+                        send_input_event(&input_tx, InputEvent::SessionApprovalsLoaded(Vec::new()))
+                            .await?;
                         total_session_usage = LLMTokenUsage {
                             prompt_tokens: 0,
                             completion_tokens: 0,
@@ -962,6 +1094,7 @@ pub async fn run_interactive(
                     }
 
                     OutputEvent::ResumeSession => {
+                        start_new_run();
                         let session_id = if let Some(session_id) = &current_session_id {
                             Some(session_id.to_string())
                         } else {
@@ -985,6 +1118,7 @@ pub async fn run_interactive(
                                 client.as_ref(),
                                 session_id,
                                 &input_tx,
+                                checkpoint_markers,
                             )
                             .await
                             {
@@ -1002,6 +1136,8 @@ pub async fn run_interactive(
                                     )
                                     .await?;
                                     current_metadata = checkpoint_metadata;
+                                    restore_session_state(&input_tx, current_metadata.as_ref())
+                                        .await?;
 
                                     // Mark that we need to refresh skills on the next user message
                                     should_refresh_skills_on_next_message = true;
@@ -1057,6 +1193,7 @@ pub async fn run_interactive(
                         continue;
                     }
                     OutputEvent::SwitchToSession(session_id) => {
+                        start_new_run();
                         send_input_event(
                             &input_tx,
                             InputEvent::StartLoadingOperation(LoadingOperation::CheckpointResume),
@@ -1066,6 +1203,7 @@ pub async fn run_interactive(
                             client.as_ref(),
                             &session_id,
                             &input_tx,
+                            checkpoint_markers,
                         )
                         .await
                         {
@@ -1079,6 +1217,7 @@ pub async fn run_interactive(
                                 set_session_id(&mut current_session_id, session_id_uuid, &input_tx)
                                     .await?;
                                 current_metadata = checkpoint_metadata;
+                                restore_session_state(&input_tx, current_metadata.as_ref()).await?;
 
                                 // Mark that we need to refresh skills on the next user message
                                 should_refresh_skills_on_next_message = true;
@@ -1295,6 +1434,17 @@ pub async fn run_interactive(
                         }
                         continue;
                     }
+                    OutputEvent::AllowToolCallForSession(approval) => {
+                        record_session_approval(&mut current_metadata, approval);
+                        continue;
+                    }
+                    OutputEvent::RetryToolCall(tool_call) => {
+                        // Goes through the approval flow like a call from the model;
+                        // AcceptTool then runs it and continues from its result
+                        messages.push(retried_tool_call_message(tool_call.clone()));
+                        send_tool_call(&input_tx, &tool_call).await?;
+                        continue;
+                    }
                     OutputEvent::PlanModeActivated(inline_prompt) => {
                         // Transition to plan mode
                         plan_mode_active = true;
@@ -1504,7 +1654,12 @@ pub async fn run_interactive(
                 };
 
                 match response_result {
-                    Ok(response) => {
+                    Ok(mut response) => {
+                        // The streamed message carries raw tool call arguments
+                        redact_tool_call_arguments(
+                            &mut response.choices[0].message,
+                            &secret_manager,
+                        );
                         messages.push(response.choices[0].message.clone());
 
                         if let Some(session_id) = response
@@ -1581,10 +1736,13 @@ pub async fn run_interactive(
                             refresh_billing_info(client.as_ref(), &input_tx).await;
                         }
 
+                        // Prefer the id from the response metadata; content markers
+                        // are only a fallback for older checkpoints.
                         if current_session_id.is_none()
-                            && let Some(checkpoint_id) =
+                            && let Some(checkpoint_uuid) = response.checkpoint_id().or_else(|| {
                                 extract_checkpoint_id_from_messages(&messages)
-                            && let Ok(checkpoint_uuid) = Uuid::parse_str(&checkpoint_id)
+                                    .and_then(|id| Uuid::parse_str(&id).ok())
+                            })
                             && let Ok(checkpoint) = client.get_checkpoint(checkpoint_uuid).await
                         {
                             set_session_id(
@@ -1615,6 +1773,8 @@ pub async fn run_interactive(
                                 continue;
                             }
                         }
+
+                        send_input_event(&input_tx, InputEvent::AssistantTurnComplete).await?;
                     }
                     Err(_) => {
                         continue;
@@ -1632,8 +1792,14 @@ pub async fn run_interactive(
         });
 
         // Wait for all tasks to finish
-        let (client_res, _, _) = tokio::try_join!(client_handle, tui_handle, mcp_progress_handle)
-            .map_err(|e| e.to_string())?;
+        let (client_res, frontend_res, _) =
+            tokio::try_join!(client_handle, tui_handle, mcp_progress_handle)
+                .map_err(|e| e.to_string())?;
+
+        // A headless failure (e.g. an uncovered approval) explains why the client stopped
+        if is_headless {
+            frontend_res?;
+        }
 
         let (
             final_messages,
@@ -1694,7 +1860,7 @@ pub async fn run_interactive(
             }
             config.allowed_tools = new_config.allowed_tools.clone();
             config.auto_approve = new_config.auto_approve.clone();
-            config.model = new_config.get_default_model(None);
+            config.model = new_config.try_get_default_model(None)?;
 
             // Update ctx
             ctx = new_config;
@@ -6,6 +6,23 @@ use std::collections::HashSet;
 /// Exit code indicating the agent has paused and needs input or approval to resume.
 pub const EXIT_CODE_PAUSED: i32 = 10;
 
+/// Exit code indicating the run hit its step limit before the agent finished.
+pub const EXIT_CODE_MAX_STEPS: i32 = 11;
+
+/// Exit code indicating the run was interrupted with Ctrl+C, as for SIGINT.
+pub const EXIT_CODE_CANCELLED: i32 = 130;
+
+/// Why an async run that neither paused nor failed stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsyncStopReason {
+    /// The agent decided it was done.
+    Completed,
+    /// The run reached `max_steps` while the agent was still working.
+    MaxStepsReached,
+    /// The user interrupted the run.
+    Cancelled,
+}
+
 /// The outcome of an async agent run.
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -16,6 +33,7 @@ pub enum AsyncOutcome {
         session_id: Option<String>,
         agent_message: Option<String>,
         steps: usize,
+        stop_reason: AsyncStopReason,
     },
     /// Agent paused and needs input or approval.
     Paused {
@@ -28,6 +46,22 @@ pub enum AsyncOutcome {
     Failed { error: String },
 }
 
+impl AsyncOutcome {
+    /// Process exit code reporting the outcome, so CI can tell a finished run
+    /// from one that ran out of steps.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AsyncOutcome::Completed { stop_reason, .. } => match stop_reason {
+                AsyncStopReason::Completed => 0,
+                AsyncStopReason::MaxStepsReached => EXIT_CODE_MAX_STEPS,
+                AsyncStopReason::Cancelled => EXIT_CODE_CANCELLED,
+            },
+            AsyncOutcome::Paused { .. } => EXIT_CODE_PAUSED,
+            AsyncOutcome::Failed { .. } => 1,
+        }
+    }
+}
+
 /// Resume input provided via CLI flags when resuming from a paused checkpoint.
 #[derive(Debug, Clone, Default)]
 pub struct ResumeInput {
@@ -119,3 +153,48 @@ pub fn build_resume_hint(checkpoint_id: &str, pause_reason: &PauseReason) -> Str
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completed(stop_reason: AsyncStopReason) -> AsyncOutcome {
+        AsyncOutcome::Completed {
+            checkpoint_id: None,
+            session_id: None,
+            agent_message: None,
+            steps: 3,
+            stop_reason,
+        }
+    }
+
+    #[test]
+    fn exit_codes_distinguish_stop_reasons() {
+        assert_eq!(completed(AsyncStopReason::Completed).exit_code(), 0);
+        assert_eq!(
+            completed(AsyncStopReason::MaxStepsReached).exit_code(),
+            EXIT_CODE_MAX_STEPS
+        );
+        assert_eq!(
+            completed(AsyncStopReason::Cancelled).exit_code(),
+            EXIT_CODE_CANCELLED
+        );
+        assert_eq!(
+            AsyncOutcome::Paused {
+                checkpoint_id: None,
+                session_id: None,
+                pause_reason: PauseReason::InputRequired,
+                agent_message: None,
+            }
+            .exit_code(),
+            EXIT_CODE_PAUSED
+        );
+        assert_eq!(
+            AsyncOutcome::Failed {
+                error: "boom".to_string()
+            }
+            .exit_code(),
+            1
+        );
+    }
+}
@@ -31,18 +31,18 @@ pub async fn validate_profile_switch(
     // 3. Create AgentClient and test API connection with retry logic
     let client: Box<dyn AgentProvider> = {
         // Use credential resolution with auth.toml fallback chain
-        let stakpak = new_config
-            .get_stakpak_api_key()
-            .map(|api_key| StakpakConfig {
-                api_key,
-                api_endpoint: new_config.api_endpoint.clone(),
-            });
+        let stakpak = new_config.get_stakpak_api_key().map(|api_key| {
+            StakpakConfig::new(api_key).with_endpoint(new_config.api_endpoint.clone())
+        });
 
         let client = AgentClient::new(AgentClientConfig {
             stakpak,
             providers: new_config.get_llm_provider_config(),
             store_path: None,
             hook_registry: None,
+            title_model: new_config.get_title_model(),
+            context_limits: new_config.context_limits.unwrap_or_default(),
+            search_api_url: new_config.search_api_url.clone(),
         })
         .await
         .map_err(|e| format!("Failed to create agent client: {}", e))?;
@@ -678,4 +678,57 @@ mod tests {
         assert_eq!(tool_calls[0].function.name, "my_function");
         assert_eq!(tool_calls[0].function.arguments, "{\"key\":\"value\"}");
     }
+
+    #[tokio::test]
+    async fn test_checkpoint_id_arrives_in_metadata_not_content() {
+        let (input_tx, mut input_rx) = tokio::sync::mpsc::channel(100);
+        let checkpoint_id = Uuid::new_v4();
+
+        // The chunk the agent client emits once the checkpoint is saved,
+        // sent through JSON as it is over the wire.
+        let metadata_chunk = ChatCompletionStreamResponse {
+            id: "test".to_string(),
+            object: "chat.completion.chunk".to_string(),
+            created: 0,
+            model: String::new(),
+            choices: vec![],
+            usage: None,
+            metadata: Some(serde_json::json!({
+                "session_id": Uuid::new_v4().to_string(),
+                "checkpoint_id": checkpoint_id.to_string(),
+            })),
+        };
+        let metadata_chunk: ChatCompletionStreamResponse =
+            serde_json::from_str(&serde_json::to_string(&metadata_chunk).unwrap()).unwrap();
+
+        let responses = vec![
+            Ok(create_content_response("Deployed ")),
+            Ok(create_content_response("the api.")),
+            Ok(metadata_chunk),
+        ];
+        let streamed = tokio::spawn(async move {
+            let mut streamed = String::new();
+            while let Some(event) = input_rx.recv().await {
+                if let InputEvent::StreamAssistantMessage(_, content) = event {
+                    streamed.push_str(&content);
+                }
+            }
+            streamed
+        });
+
+        let response = process_responses_stream(stream::iter(responses), &input_tx)
+            .await
+            .unwrap();
+        drop(input_tx);
+
+        let content = response.choices[0]
+            .message
+            .content
+            .as_ref()
+            .unwrap()
+            .to_string();
+        assert_eq!(content, "Deployed the api.");
+        assert_eq!(streamed.await.unwrap(), "Deployed the api.");
+        assert_eq!(response.checkpoint_id(), Some(checkpoint_id));
+    }
 }
@@ -2,8 +2,8 @@ use rmcp::model::{
     CallToolRequestParam, CallToolResult, CancelledNotification, CancelledNotificationParam,
     ServerResult,
 };
-use stakpak_api::AgentProvider;
-use stakpak_api::storage::ListSessionsQuery;
+use stakpak_api::storage::{ListSessionsQuery, RecordToolAuditRequest, ToolAuditStatus};
+use stakpak_api::{AgentProvider, Model};
 use stakpak_mcp_client::McpClient;
 use stakpak_shared::models::integrations::mcp::CallToolResultExt;
 use stakpak_shared::models::integrations::openai::ToolCall;
@@ -181,3 +181,120 @@ pub async fn run_tool_call(
 
     Ok(None)
 }
+
+/// Append a tool call to the session's audit log.
+///
+/// Backends without an audit log reject the write, which must not fail the
+/// tool call, so errors are only logged.
+pub async fn record_tool_audit(
+    agent: &dyn AgentProvider,
+    session_id: Option<Uuid>,
+    request: RecordToolAuditRequest,
+) {
+    let Some(session_id) = session_id else {
+        return;
+    };
+    if let Err(e) = agent.record_tool_audit(session_id, &request).await {
+        log::debug!(
+            "Failed to record tool audit for '{}': {}",
+            request.tool_name,
+            e
+        );
+    }
+}
+
+/// Run a tool call between the client's `BeforeToolExecution` and
+/// `AfterToolExecution` hooks, recording the outcome in the session's audit log.
+///
+/// A hook aborting the call turns it into an error result without running the tool.
+pub async fn run_tool_call_with_hooks(
+    agent: &dyn AgentProvider,
+    model: &Model,
+    mcp_client: &McpClient,
+    tools: &[rmcp::model::Tool],
+    tool_call: &ToolCall,
+    cancel_rx: Option<tokio::sync::broadcast::Receiver<()>>,
+    session_id: Option<Uuid>,
+) -> Result<Option<CallToolResult>, String> {
+    if let Err(reason) = agent
+        .before_tool_execution(model, tool_call, session_id)
+        .await
+    {
+        log::warn!(
+            "Tool call '{}' blocked by hook: {}",
+            tool_call.function.name,
+            reason
+        );
+        record_tool_audit(
+            agent,
+            session_id,
+            RecordToolAuditRequest::approved(tool_call, ToolAuditStatus::Blocked, &reason),
+        )
+        .await;
+        return Ok(Some(CallToolResult::error(vec![
+            rmcp::model::Content::text("TOOL_CALL_BLOCKED_BY_HOOK"),
+            rmcp::model::Content::text(reason),
+        ])));
+    }
+
+    let result = match run_tool_call(
+        mcp_client,
+        tools,
+        tool_call,
+        cancel_rx,
+        session_id,
+        Some(model.id.clone()),
+        Some(model.provider.clone()),
+    )
+    .await
+    {
+        Ok(result) => result,
+        Err(e) => {
+            record_tool_audit(
+                agent,
+                session_id,
+                RecordToolAuditRequest::approved(tool_call, ToolAuditStatus::Failed, &e),
+            )
+            .await;
+            return Err(e);
+        }
+    };
+
+    if let Some(result) = &result {
+        let output = result
+            .content
+            .iter()
+            .filter_map(|content| content.raw.as_text().map(|text| text.text.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if let Err(e) = agent
+            .after_tool_execution(model, tool_call, &output, session_id)
+            .await
+        {
+            log::warn!(
+                "After-tool hook failed for '{}': {}",
+                tool_call.function.name,
+                e
+            );
+        }
+        record_tool_audit(
+            agent,
+            session_id,
+            RecordToolAuditRequest::approved(tool_call, result.get_status().into(), &output),
+        )
+        .await;
+    } else {
+        record_tool_audit(
+            agent,
+            session_id,
+            RecordToolAuditRequest::approved(
+                tool_call,
+                ToolAuditStatus::Failed,
+                "Tool is not available",
+            ),
+        )
+        .await;
+    }
+
+    Ok(result)
+}
@@ -1,5 +1,6 @@
 //! Login command - authenticate with LLM providers
 
+use crate::config::secret_store::{default_secret_store, store_api_key};
 use crate::config::{ProfileConfig, ProviderType};
 use crate::onboarding::auth_flow::{AuthFlowConfig, run_provider_auth_flow};
 use crate::onboarding::config_templates::{
@@ -350,10 +351,15 @@ async fn handle_non_interactive_api_setup(
     // Determine profile config based on provider
     let mut profile_config = match provider_id {
         "stakpak" => {
-            // Stakpak API key -> Remote provider (key stored in config.toml)
+            // Stakpak API key -> Remote provider (key stored in config.toml,
+            // or in the OS keychain with the `keyring` feature)
             ProfileConfig {
                 provider: Some(ProviderType::Remote),
-                api_key: Some(api_key.clone()),
+                api_key: Some(store_api_key(
+                    api_key.clone(),
+                    profile_name,
+                    default_secret_store().as_deref(),
+                )?),
                 api_endpoint: validated_endpoint.clone(),
                 ..ProfileConfig::default()
             }
@@ -910,6 +910,7 @@ async fn start_autopilot(config: &mut AppConfig, options: StartOptions) -> Resul
                 gateway_config.channels.slack = Some(stakpak_gateway::config::SlackConfig {
                     bot_token,
                     app_token,
+                    signing_secret: std::env::var("SLACK_SIGNING_SECRET").ok(),
                     model: None,
                     auto_approve: None,
                     profile: Some(config.profile_name.clone()),
@@ -1339,6 +1340,8 @@ async fn start_foreground_runtime(
         default_model,
         resolved_tool_policy.clone(),
     )
+    .with_denied_tools(config.denied_tools.clone().unwrap_or_default())
+    .with_loop_detection(config.loop_detection.clone().unwrap_or_default())
     .with_base_system_prompt(Some(DEFAULT_SYSTEM_PROMPT.trim().to_string()))
     .with_project_dir(startup_project_dir)
     .with_skills(startup_remote_skills)
@@ -1637,6 +1640,7 @@ fn resolve_server_tool_policy(
     let mut policy = stakpak_server::ToolApprovalPolicy::Custom {
         rules: std::collections::HashMap::new(),
         default: stakpak_server::ToolApprovalAction::Ask,
+        argument_rules: std::collections::HashMap::new(),
     }
     .with_overrides(resolved_allowed_tools.into_iter().filter_map(|tool| {
         let trimmed = tool.trim().to_string();
@@ -4417,6 +4421,18 @@ mod tests {
             model: None,
             system_prompt: None,
             max_turns: None,
+            title_model: None,
+            context_limits: None,
+            loop_detection: None,
+            max_concurrent_requests: HashMap::new(),
+            search_api_url: None,
+            allow_unknown_models: false,
+            run_command_env: None,
+            max_file_size: None,
+            prompt_vars: HashMap::new(),
+            tool_output_redaction: HashMap::new(),
+            privacy_patterns: HashMap::new(),
+            denied_tools: None,
             anonymous_id: None,
             collect_telemetry: None,
             editor: None,
@@ -5535,6 +5551,7 @@ max_turns = 12
         gateway_cfg.channels.slack = Some(stakpak_gateway::config::SlackConfig {
             bot_token: "xoxb-token".to_string(),
             app_token: "xapp-token".to_string(),
+            signing_secret: None,
             model: None,
             auto_approve: None,
             profile: Some("ops".to_string()),
@@ -5551,6 +5568,7 @@ max_turns = 12
         gateway_cfg.channels.slack = Some(stakpak_gateway::config::SlackConfig {
             bot_token: "xoxb-token".to_string(),
             app_token: "xapp-token".to_string(),
+            signing_secret: None,
             model: None,
             auto_approve: None,
             profile: None,
@@ -5566,6 +5584,7 @@ max_turns = 12
         gateway_cfg.channels.slack = Some(stakpak_gateway::config::SlackConfig {
             bot_token: "xoxb-token".to_string(),
             app_token: "xapp-token".to_string(),
+            signing_secret: None,
             model: None,
             auto_approve: None,
             profile: Some("ops".to_string()),
@@ -1072,6 +1072,18 @@ mod tests {
             model: None,
             system_prompt: None,
             max_turns: None,
+            title_model: None,
+            context_limits: None,
+            loop_detection: None,
+            max_concurrent_requests: HashMap::new(),
+            search_api_url: None,
+            allow_unknown_models: false,
+            run_command_env: None,
+            max_file_size: None,
+            prompt_vars: HashMap::new(),
+            tool_output_redaction: HashMap::new(),
+            privacy_patterns: HashMap::new(),
+            denied_tools: None,
             anonymous_id: None,
             collect_telemetry: None,
             editor: None,
@@ -1365,6 +1377,7 @@ mod tests {
             volumes: vec![
                 "/tmp/stakpak-config.toml:/home/agent/.stakpak/config.toml:ro".to_string(),
             ],
+            runtime: Default::default(),
         });
 
         let env = MockProbeEnvironment::default().with_path_access(
@@ -1,8 +1,12 @@
 use std::collections::HashMap;
 
 use clap::Subcommand;
-use stakpak_mcp_server::ToolMode;
+use serde::Serialize;
+use stakpak_mcp_client::{ConnectionStage, test_connection};
+use stakpak_mcp_server::{EnabledToolsConfig, ToolMode};
+use stakpak_shared::cert_utils::create_client_config_from_pem;
 
+use crate::commands::agent::run::dry_run::DryRunPlan;
 use crate::config::AppConfig;
 
 use stakpak_mcp_config::{
@@ -47,6 +51,42 @@ pub enum McpCommands {
         #[arg(long = "privacy-mode", default_value_t = false)]
         privacy_mode: bool,
     },
+    /// List the tools the MCP server exposes in a tool mode
+    ListTools {
+        /// Tool mode to list (local, remote, combined)
+        #[arg(long, short = 'm', default_value_t = ToolMode::Combined)]
+        tool_mode: ToolMode,
+
+        /// Include Slack tools (experimental)
+        #[arg(long = "enable-slack-tools", default_value_t = false)]
+        enable_slack_tools: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check that a running MCP server accepts this client's mTLS setup
+    TestConnection {
+        /// MCP endpoint URL (e.g. https://127.0.0.1:8443/mcp)
+        #[arg(long)]
+        url: String,
+
+        /// CA certificate (PEM) that signed the server certificate
+        #[arg(long)]
+        ca: Option<String>,
+
+        /// Client certificate (PEM) to present for mTLS
+        #[arg(long = "client-cert", requires_all = ["ca", "client_key"])]
+        client_cert: Option<String>,
+
+        /// Private key (PEM) for the client certificate
+        #[arg(long = "client-key", requires = "client_cert")]
+        client_key: Option<String>,
+
+        /// Bearer token to send with every request
+        #[arg(long = "auth-token")]
+        auth_token: Option<String>,
+    },
     /// Add an MCP server to config
     Add {
         /// Server name (unique identifier)
@@ -161,6 +201,87 @@ impl McpCommands {
                 };
                 proxy::run_proxy(config_path, disable_secret_redaction, privacy_mode).await
             }
+            McpCommands::ListTools {
+                tool_mode,
+                enable_slack_tools,
+                json,
+            } => {
+                let tools = tool_listings(&tool_mode, enable_slack_tools);
+
+                if json {
+                    let output = serde_json::to_string_pretty(&tools)
+                        .map_err(|e| format!("Failed to serialize: {e}"))?;
+                    println!("{output}");
+                    return Ok(());
+                }
+
+                let name_header = "NAME";
+                let mutating_header = "MUTATING";
+                let description_header = "DESCRIPTION";
+                println!("{name_header:<28} {mutating_header:<9} {description_header}");
+                for tool in &tools {
+                    let summary = tool.description.lines().next().unwrap_or_default();
+                    let summary: String = summary.chars().take(80).collect();
+                    println!(
+                        "{:<28} {:<9} {}",
+                        tool.name,
+                        if tool.mutating { "yes" } else { "no" },
+                        summary,
+                    );
+                }
+
+                Ok(())
+            }
+            McpCommands::TestConnection {
+                url,
+                ca,
+                client_cert,
+                client_key,
+                auth_token,
+            } => {
+                let tls_config = match &ca {
+                    Some(ca) => {
+                        let read = |path: &str| {
+                            std::fs::read_to_string(path)
+                                .map_err(|e| format!("Failed to read {path}: {e}"))
+                        };
+                        let ca_pem = read(ca)?;
+                        let client_identity = match (&client_cert, &client_key) {
+                            (Some(cert), Some(key)) => Some((read(cert)?, read(key)?)),
+                            _ => None,
+                        };
+                        let tls_config = create_client_config_from_pem(
+                            &ca_pem,
+                            client_identity
+                                .as_ref()
+                                .map(|(cert, key)| (cert.as_str(), key.as_str())),
+                        )
+                        .map_err(|e| format!("Invalid TLS material: {e:#}"))?;
+                        Some(tls_config)
+                    }
+                    None => None,
+                };
+
+                match test_connection(&url, tls_config, auth_token.as_deref()).await {
+                    Ok(tools) => {
+                        for stage in ConnectionStage::ALL {
+                            println!("✓ {stage}");
+                        }
+                        println!("{} tools available at {url}", tools.len());
+                        Ok(())
+                    }
+                    Err(err) => {
+                        for stage in ConnectionStage::ALL {
+                            if stage == err.stage {
+                                println!("✗ {stage}: {}", err.message);
+                                break;
+                            }
+                            println!("✓ {stage}");
+                        }
+                        Err(err.to_string())
+                    }
+                }
+            }
             McpCommands::Add {
                 name,
                 command,
@@ -296,6 +417,32 @@ impl McpCommands {
     }
 }
 
+/// A tool as shown by `stakpak mcp list-tools`
+#[derive(Debug, Serialize)]
+struct ToolListing {
+    name: String,
+    description: String,
+    /// Whether the tool can change state; read-only tools also run during dry runs
+    mutating: bool,
+}
+
+/// The tools a server in `tool_mode` exposes, built from the tool routers
+/// without starting a server or touching the network
+fn tool_listings(tool_mode: &ToolMode, enable_slack_tools: bool) -> Vec<ToolListing> {
+    stakpak_mcp_server::list_tools(
+        tool_mode,
+        &EnabledToolsConfig::with_slack(enable_slack_tools),
+        true,
+    )
+    .into_iter()
+    .map(|tool| ToolListing {
+        mutating: !DryRunPlan::is_read_only(&tool.name),
+        name: tool.name.to_string(),
+        description: tool.description.map(|d| d.to_string()).unwrap_or_default(),
+    })
+    .collect()
+}
+
 /// Parse KEY=VALUE pairs from a vec of strings.
 fn parse_key_values(pairs: &[String]) -> Result<HashMap<String, String>, String> {
     let mut map = HashMap::new();
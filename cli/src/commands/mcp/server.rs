@@ -1,8 +1,10 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use stakpak_api::local::skills::default_skill_directories;
 use stakpak_mcp_server::{
-    EnabledToolsConfig, MCPServerConfig, SubagentConfig, ToolMode, start_server,
+    DEFAULT_MAX_FILE_SIZE, EnabledToolsConfig, MCPServerConfig, SubagentConfig, ToolMode,
+    drain::DEFAULT_SHUTDOWN_GRACE_PERIOD, start_server,
 };
 use stakpak_shared::cert_utils::{CertificateChain, MtlsIdentity};
 
@@ -17,6 +19,10 @@ use crate::{commands::get_client, config::AppConfig};
 /// private keys never leave their respective processes.
 const TRUSTED_CLIENT_CA_ENV: &str = "STAKPAK_MCP_CLIENT_CA";
 
+/// Environment variable overriding how many seconds in-flight tool calls may
+/// keep running after a shutdown signal.
+const SHUTDOWN_GRACE_SECS_ENV: &str = "STAKPAK_MCP_SHUTDOWN_GRACE_SECS";
+
 /// Start the MCP server (standalone HTTP/HTTPS server with tools)
 #[allow(clippy::too_many_arguments)]
 pub async fn run_server(
@@ -51,6 +57,14 @@ pub async fn run_server(
         network::find_available_bind_address_with_listener().await?
     };
 
+    let shutdown_grace_period = match std::env::var(SHUTDOWN_GRACE_SECS_ENV) {
+        Ok(secs) => Duration::from_secs(
+            secs.parse()
+                .map_err(|e| format!("Invalid {SHUTDOWN_GRACE_SECS_ENV} '{secs}': {e}"))?,
+        ),
+        Err(_) => DEFAULT_SHUTDOWN_GRACE_PERIOD,
+    };
+
     let (certificate_chain, server_tls_config) =
         if let Ok(trusted_client_ca_pem) = std::env::var(TRUSTED_CLIENT_CA_ENV) {
             // Sandbox mode: a parent process provided the client's CA cert.
@@ -115,8 +129,11 @@ pub async fn run_server(
                 config_path: Some(config.config_path.clone()),
                 model: config.subagent_model(),
             },
+            run_command_env: config.run_command_env.clone().unwrap_or_default(),
+            max_file_size: config.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE),
             server_tls_config,
             task_manager_handle: None,
+            shutdown_grace_period,
         },
         Some(listener),
         None,
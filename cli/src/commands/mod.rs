@@ -3,6 +3,7 @@ use std::process::Command;
 use std::sync::Arc;
 
 use crate::config::AppConfig;
+use crate::config::secret_store::{default_secret_store, store_api_key};
 use clap::{CommandFactory, Subcommand};
 use serde::{Deserialize, Serialize};
 use stakpak_api::{AgentClient, AgentClientConfig, AgentProvider, StakpakConfig};
@@ -204,7 +205,7 @@ pub enum Commands {
     #[command(subcommand)]
     Autopilot(AutopilotCommands),
 
-    /// List and inspect past sessions
+    /// List, inspect, export and import past sessions
     #[command(subcommand, alias = "session")]
     Sessions(SessionsCommands),
 
@@ -236,10 +237,9 @@ async fn build_agent_client(config: &AppConfig) -> Result<AgentClient, String> {
     // Refresh OAuth tokens in parallel to minimize startup delay
     let providers = config.get_llm_provider_config_async().await;
 
-    let stakpak = config.get_stakpak_api_key().map(|api_key| StakpakConfig {
-        api_key,
-        api_endpoint: config.api_endpoint.clone(),
-    });
+    let stakpak = config
+        .get_stakpak_api_key()
+        .map(|api_key| StakpakConfig::new(api_key).with_endpoint(config.api_endpoint.clone()));
 
     AgentClient::new(AgentClientConfig {
         stakpak,
@@ -294,8 +294,13 @@ impl Commands {
                 eprintln!("Please use: \x1b[1;34mstakpak auth login --provider stakpak\x1b[0m");
                 eprintln!();
 
+                // With the `keyring` feature the key goes to the OS keychain
                 let mut updated_config = config.clone();
-                updated_config.api_key = Some(api_key);
+                updated_config.api_key = Some(store_api_key(
+                    api_key,
+                    &config.profile_name,
+                    default_secret_store().as_deref(),
+                )?);
 
                 updated_config
                     .save()
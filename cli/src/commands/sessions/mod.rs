@@ -4,12 +4,18 @@
 //! explicit `--json` output. Uses `build_agent_client(&config)` so it works
 //! with whatever profile backend is configured (SQLite or Stakpak API) and
 //! does not depend on the autopilot server.
+//!
+//! `export` and `import` always operate on the local SQLite store, so local
+//! sessions can be backed up or moved between machines.
 
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use clap::Subcommand;
-use stakpak_api::{AgentClient, ListSessionsQuery, SessionStorage, StakpakConfig, StorageError};
+use stakpak_api::{
+    AgentClient, ListSessionsQuery, LocalStorage, SessionStorage, StakpakConfig, StorageError,
+};
 use uuid::Uuid;
 
 use crate::config::AppConfig;
@@ -71,6 +77,26 @@ pub enum SessionsCommands {
         #[arg(long)]
         json: bool,
     },
+
+    /// Export a local session and all its checkpoints to a portable JSON archive.
+    Export {
+        /// Full session UUID
+        id: String,
+
+        /// File to write the archive to (default: stdout)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Import a session archive into the local store as a new session.
+    Import {
+        /// Archive file to import, or `-` for stdin
+        path: PathBuf,
+
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 impl SessionsCommands {
@@ -96,15 +122,18 @@ impl SessionsCommands {
                 let limit = if limit == 0 { None } else { Some(limit) };
                 run_show(&config, &id, role.as_deref(), limit, offset, mode).await
             }
+            SessionsCommands::Export { id, output } => run_export(&id, output.as_deref()).await,
+            SessionsCommands::Import { path, json } => {
+                run_import(&path, OutputMode::from_flag(json)).await
+            }
         }
     }
 }
 
 async fn build_storage(config: &AppConfig) -> Result<Arc<dyn SessionStorage>, String> {
-    let stakpak = config.get_stakpak_api_key().map(|api_key| StakpakConfig {
-        api_key,
-        api_endpoint: config.api_endpoint.clone(),
-    });
+    let stakpak = config
+        .get_stakpak_api_key()
+        .map(|api_key| StakpakConfig::new(api_key).with_endpoint(config.api_endpoint.clone()));
     AgentClient::build_session_storage(stakpak, None, Some(config.profile_name.clone())).await
 }
 
@@ -226,6 +255,101 @@ async fn run_show(
     }
 }
 
+async fn open_local_store() -> Result<LocalStorage, String> {
+    LocalStorage::new(&AgentClient::default_store_path())
+        .await
+        .map_err(|e| format!("Failed to open local session store: {}", e))
+}
+
+/// Write a session archive to `output` (or stdout), returning a status line for stderr.
+pub(crate) async fn export_session_output(
+    storage: &LocalStorage,
+    session_id: Uuid,
+    output: Option<&Path>,
+) -> Result<String, StorageError> {
+    let archive = match output {
+        Some(path) => {
+            let file = std::fs::File::create(path).map_err(|e| {
+                StorageError::InvalidRequest(format!("cannot create '{}': {}", path.display(), e))
+            })?;
+            storage
+                .export_session(session_id, std::io::BufWriter::new(file))
+                .await?
+        }
+        None => {
+            let archive = storage
+                .export_session(session_id, std::io::stdout())
+                .await?;
+            println!();
+            archive
+        }
+    };
+
+    Ok(format!(
+        "Exported session {} ({} checkpoints){}",
+        session_id,
+        archive.checkpoints.len(),
+        output
+            .map(|path| format!(" to {}", path.display()))
+            .unwrap_or_default()
+    ))
+}
+
+/// Import a session archive from `path` (`-` for stdin) and render the new session id.
+pub(crate) async fn import_session_output(
+    storage: &LocalStorage,
+    path: &Path,
+    mode: OutputMode,
+) -> Result<String, StorageError> {
+    let session_id = if path == Path::new("-") {
+        storage.import_session(std::io::stdin()).await?
+    } else {
+        let file = std::fs::File::open(path).map_err(|e| {
+            StorageError::InvalidRequest(format!("cannot open '{}': {}", path.display(), e))
+        })?;
+        storage
+            .import_session(std::io::BufReader::new(file))
+            .await?
+    };
+
+    Ok(match mode {
+        OutputMode::Json => serde_json::json!({ "session_id": session_id }).to_string(),
+        OutputMode::Human => format!("Imported session as {}", session_id),
+    })
+}
+
+async fn run_export(id_str: &str, output: Option<&Path>) -> Result<(), String> {
+    let mode = OutputMode::Human;
+    let session_id = match Uuid::parse_str(id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            let msg = format!("invalid session id '{}': expected a full UUID", id_str);
+            emit_error(&msg, "invalid_argument", mode);
+            std::process::exit(2);
+        }
+    };
+
+    let storage = open_local_store().await?;
+    match export_session_output(&storage, session_id, output).await {
+        Ok(status) => {
+            eprintln!("{}", status);
+            Ok(())
+        }
+        Err(e) => exit_with_storage_error(e, mode),
+    }
+}
+
+async fn run_import(path: &Path, mode: OutputMode) -> Result<(), String> {
+    let storage = open_local_store().await?;
+    match import_session_output(&storage, path, mode).await {
+        Ok(rendered) => {
+            emit_stdout(&rendered);
+            Ok(())
+        }
+        Err(e) => exit_with_storage_error(e, mode),
+    }
+}
+
 fn emit_stdout(rendered: &str) {
     if rendered.ends_with('\n') {
         print!("{}", rendered);
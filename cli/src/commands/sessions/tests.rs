@@ -12,9 +12,9 @@ use stakpak_api::{
 use stakpak_shared::models::integrations::openai::{ChatMessage, MessageContent, Role};
 use uuid::Uuid;
 
-use super::classify_storage_error;
 use super::messages::{RoleFilter, filter_messages};
 use super::output::{self, OutputMode, ShowRenderOptions, render_error};
+use super::{classify_storage_error, export_session_output, import_session_output};
 
 async fn in_memory_storage() -> LocalStorage {
     LocalStorage::new(":memory:")
@@ -1086,3 +1086,48 @@ fn classify_storage_error_maps_every_variant() {
         ("internal_error", 1)
     );
 }
+
+#[tokio::test]
+async fn sessions_export_then_import_round_trips_through_archive_file() {
+    let source = in_memory_storage().await;
+    let created = source
+        .create_session(&CreateSessionRequest::new(
+            "Move me",
+            vec![
+                msg(Role::User, "what changed?"),
+                msg(Role::Assistant, "two files"),
+            ],
+        ))
+        .await
+        .expect("create session");
+
+    let dir = tempfile::tempdir().expect("temp dir");
+    let archive_path = dir.path().join("session.json");
+    let status = export_session_output(&source, created.session_id, Some(&archive_path))
+        .await
+        .expect("export");
+    assert!(status.contains("(1 checkpoints)"), "{status}");
+
+    let target = in_memory_storage().await;
+    let rendered = import_session_output(&target, &archive_path, OutputMode::Json)
+        .await
+        .expect("import");
+    let value: serde_json::Value = serde_json::from_str(&rendered).expect("json output");
+    let imported_id: Uuid = value["session_id"]
+        .as_str()
+        .and_then(|id| id.parse().ok())
+        .expect("session_id");
+
+    let session = target.get_session(imported_id).await.expect("imported");
+    assert_eq!(session.title, "Move me");
+    let messages = session
+        .active_checkpoint
+        .expect("checkpoint")
+        .state
+        .messages;
+    assert_eq!(messages.len(), 2);
+    assert_eq!(
+        messages[1].content.as_ref().map(|c| c.to_string()),
+        Some("two files".to_string())
+    );
+}
@@ -3,8 +3,8 @@ use crate::utils::plugins::{PluginConfig, get_plugin_path};
 use clap::Subcommand;
 // Re-export container constants so existing callers (autopilot.rs) don't need to change imports.
 use stakpak_shared::container::{
-    agent_knowledge_store_path, resolve_ak_store_for_sandbox, volume_container_part,
-    warden_ak_store_args,
+    ContainerRuntime, ContainerRuntimeSetting, agent_knowledge_store_path,
+    resolve_ak_store_for_sandbox, volume_container_part, warden_ak_store_args,
 };
 pub use stakpak_shared::container::{
     expand_volume_path, stakpak_agent_default_mounts, stakpak_agent_image,
@@ -31,9 +31,10 @@ pub enum WardenCommands {
         /// Enable TTY allocation for interactive use
         #[arg(short, long)]
         tty: bool,
-        /// Container runtime: docker or podman
+        /// Container runtime: docker, podman, or auto (defaults to the profile's
+        /// `warden.runtime`, then auto-detection)
         #[arg(short, long)]
-        runtime: Option<String>,
+        runtime: Option<ContainerRuntimeSetting>,
         /// Command and arguments to run inside the container
         #[arg(last = true)]
         command: Vec<String>,
@@ -79,11 +80,14 @@ impl WardenCommands {
                 // Image is positional first argument
                 cmd.arg(&image);
 
+                let runtime = resolve_container_runtime(&config, runtime);
+                push_runtime_args(&mut cmd, runtime);
+
                 for env_var in env {
                     cmd.args(["--env", &env_var]);
                 }
 
-                stakpak_shared::container::ensure_named_volumes_exist();
+                stakpak_shared::container::ensure_named_volumes_exist_for(runtime);
 
                 let ak_store_override = match resolve_ak_store_for_sandbox() {
                     Ok(opt) => opt,
@@ -103,13 +107,23 @@ impl WardenCommands {
                         continue;
                     }
                     let expanded_vol = expand_volume_path(&vol);
-                    cmd.args(["--volume", &expanded_vol]);
+                    cmd.args(["--volume", &runtime.translate_volume(&expanded_vol)]);
                 }
 
-                cmd.args(warden_ak_store_args(ak_store_override.as_deref()));
+                let ak_store_args = warden_ak_store_args(ak_store_override.as_deref());
+                for pair in ak_store_args.chunks(2) {
+                    if let [flag, value] = pair {
+                        let value = if flag == "--volume" {
+                            runtime.translate_volume(value)
+                        } else {
+                            value.clone()
+                        };
+                        cmd.args([flag.as_str(), value.as_str()]);
+                    }
+                }
 
                 for vol in volume {
-                    cmd.args(["--volume", &vol]);
+                    cmd.args(["--volume", &runtime.translate_volume(&vol)]);
                 }
 
                 if let Some(workdir) = workdir {
@@ -121,10 +135,6 @@ impl WardenCommands {
                     needs_tty = true;
                 }
 
-                if let Some(runtime) = runtime {
-                    cmd.args(["--runtime", &runtime]);
-                }
-
                 // Command comes after -- separator
                 if !command.is_empty() {
                     cmd.arg("--");
@@ -183,6 +193,24 @@ pub async fn get_warden_plugin_path() -> String {
     get_plugin_path(warden_config).await
 }
 
+/// Resolve the container runtime for a warden run: an explicit CLI choice wins,
+/// then the profile's `warden.runtime`, then `PATH` auto-detection.
+fn resolve_container_runtime(
+    config: &AppConfig,
+    cli_runtime: Option<ContainerRuntimeSetting>,
+) -> ContainerRuntime {
+    cli_runtime
+        .or_else(|| config.warden.as_ref().map(|w| w.runtime))
+        .unwrap_or_default()
+        .resolve()
+}
+
+/// Pass the selected runtime and any runtime-specific flags to `warden wrap`.
+fn push_runtime_args(cmd: &mut Command, runtime: ContainerRuntime) {
+    cmd.args(["--runtime", runtime.binary()]);
+    cmd.args(runtime.wrap_args());
+}
+
 /// Helper function to prepare volumes for warden container.
 ///
 /// Collects volumes from the profile config, then ensures every entry from
@@ -322,10 +350,13 @@ pub async fn run_default_warden(
     // Enable TTY by default for convenience command
     cmd.arg("--tty");
 
+    let runtime = resolve_container_runtime(&config, None);
+    push_runtime_args(&mut cmd, runtime);
+
     // Prepare and mount volumes
     for volume in prepare_volumes(&config, true) {
         let expanded_volume = expand_volume_path(&volume);
-        cmd.args(["--volume", &expanded_volume]);
+        cmd.args(["--volume", &runtime.translate_volume(&expanded_volume)]);
     }
 
     // Add extra environment variables
@@ -335,7 +366,7 @@ pub async fn run_default_warden(
 
     // Add extra volume mounts (these override/extend profile volumes)
     for volume in extra_volumes {
-        cmd.args(["--volume", &volume]);
+        cmd.args(["--volume", &runtime.translate_volume(&volume)]);
     }
 
     // Command comes after -- separator
@@ -370,10 +401,13 @@ async fn run_stakpak_in_warden_with_path(
         cmd.arg("--tty");
     }
 
+    let runtime = resolve_container_runtime(&config, None);
+    push_runtime_args(&mut cmd, runtime);
+
     // Prepare and mount volumes (don't check enabled flag for this function)
     for volume in prepare_volumes(&config, false) {
         let expanded_volume = expand_volume_path(&volume);
-        cmd.args(["--volume", &expanded_volume]);
+        cmd.args(["--volume", &runtime.translate_volume(&expanded_volume)]);
     }
 
     // Set environment variable to prevent infinite recursion
@@ -451,6 +485,18 @@ mod tests {
             model: None,
             system_prompt: None,
             max_turns: None,
+            title_model: None,
+            context_limits: None,
+            loop_detection: None,
+            max_concurrent_requests: HashMap::new(),
+            search_api_url: None,
+            allow_unknown_models: false,
+            run_command_env: None,
+            max_file_size: None,
+            prompt_vars: HashMap::new(),
+            tool_output_redaction: HashMap::new(),
+            privacy_patterns: HashMap::new(),
+            denied_tools: None,
             anonymous_id: None,
             collect_telemetry: None,
             editor: None,
@@ -470,6 +516,7 @@ mod tests {
         let config = test_config(Some(WardenConfig {
             enabled: true,
             volumes: vec!["/tmp:/tmp:ro".into()],
+            runtime: Default::default(),
         }));
         let vols = prepare_volumes(&config, false);
         assert!(has_aqua_cache(&vols), "aqua cache missing: {vols:?}");
@@ -480,6 +527,7 @@ mod tests {
         let config = test_config(Some(WardenConfig {
             enabled: false,
             volumes: vec!["/tmp:/tmp:ro".into()],
+            runtime: Default::default(),
         }));
         let vols = prepare_volumes(&config, false);
         assert!(has_aqua_cache(&vols), "aqua cache missing: {vols:?}");
@@ -501,6 +549,7 @@ mod tests {
         let config = test_config(Some(WardenConfig {
             enabled: true,
             volumes: vec!["./:/agent:ro".into()],
+            runtime: Default::default(),
         }));
         let vols = prepare_volumes(&config, true);
         assert!(has_aqua_cache(&vols), "aqua cache missing: {vols:?}");
@@ -513,6 +562,7 @@ mod tests {
         let config = test_config(Some(WardenConfig {
             enabled: false,
             volumes: vec!["./:/agent:ro".into()],
+            runtime: Default::default(),
         }));
         let vols = prepare_volumes(&config, true);
         assert!(has_aqua_cache(&vols), "aqua cache missing: {vols:?}");
@@ -527,6 +577,7 @@ mod tests {
         let config = test_config(Some(WardenConfig {
             enabled: true,
             volumes: WardenConfig::readonly_profile().volumes,
+            runtime: Default::default(),
         }));
         let vols = prepare_volumes(&config, false);
         assert!(has_aqua_cache(&vols), "aqua cache missing: {vols:?}");
@@ -540,6 +591,7 @@ mod tests {
         let config = test_config(Some(WardenConfig {
             enabled: true,
             volumes: vec![custom.clone()],
+            runtime: Default::default(),
         }));
         let vols = prepare_volumes(&config, false);
         let aqua_count = vols.iter().filter(|v| v.contains("aquaproj-aqua")).count();
@@ -582,6 +634,7 @@ mod tests {
         let config = test_config(Some(WardenConfig {
             enabled: true,
             volumes: vec!["~/.stakpak/config.toml:/home/agent/.stakpak/config.toml:ro".into()],
+            runtime: Default::default(),
         }));
         let vols = prepare_volumes(&config, false);
         assert!(
@@ -600,6 +653,7 @@ mod tests {
         let config = test_config(Some(WardenConfig {
             enabled: true,
             volumes: vec!["./:/agent:ro".into()],
+            runtime: Default::default(),
         }));
         let vols = prepare_volumes(&config, false);
         let agent_count = vols.iter().filter(|v| *v == "./:/agent:ro").count();
@@ -636,6 +690,7 @@ mod tests {
         let config = test_config(Some(WardenConfig {
             enabled: true,
             volumes: WardenConfig::readonly_profile().volumes,
+            runtime: Default::default(),
         }));
         let vols = prepare_volumes(&config, false);
         assert_eq!(
@@ -654,6 +709,7 @@ mod tests {
         let config = test_config(Some(WardenConfig {
             enabled: true,
             volumes: vec![custom.clone()],
+            runtime: Default::default(),
         }));
         let vols = prepare_volumes(&config, false);
         assert_eq!(
@@ -720,4 +776,115 @@ mod tests {
             "expected inner process to inherit skip-warden env, got: {log}"
         );
     }
+
+    // ── Container runtime selection ────────────────────────────────────
+
+    #[test]
+    fn explicit_cli_runtime_overrides_profile() {
+        let config = test_config(Some(WardenConfig {
+            enabled: true,
+            volumes: Vec::new(),
+            runtime: ContainerRuntimeSetting::Docker,
+        }));
+        assert_eq!(
+            resolve_container_runtime(&config, Some(ContainerRuntimeSetting::Podman)),
+            ContainerRuntime::Podman
+        );
+    }
+
+    #[test]
+    fn profile_runtime_used_when_cli_unset() {
+        let config = test_config(Some(WardenConfig {
+            enabled: true,
+            volumes: Vec::new(),
+            runtime: ContainerRuntimeSetting::Podman,
+        }));
+        assert_eq!(
+            resolve_container_runtime(&config, None),
+            ContainerRuntime::Podman
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn run_stakpak_in_warden_translates_flags_for_podman() {
+        let temp_dir = tempfile::TempDir::new().expect("temp dir");
+        let log_path = temp_dir.path().join("warden-args.log");
+        let fake_warden = temp_dir.path().join("warden");
+        let config = test_config(Some(WardenConfig {
+            enabled: true,
+            volumes: vec!["/tmp:/tmp:ro".into()],
+            runtime: ContainerRuntimeSetting::Podman,
+        }));
+
+        write_executable_script(
+            &fake_warden,
+            &format!(
+                "#!/bin/sh\nprintf '%s\\n' \"$*\" > \"{}\"\nexit 0\n",
+                log_path.display()
+            ),
+        );
+
+        run_stakpak_in_warden_with_path(
+            &fake_warden.to_string_lossy(),
+            config,
+            &["stakpak".to_string()],
+        )
+        .await
+        .expect("warden command succeeds");
+
+        let log = std::fs::read_to_string(&log_path).expect("read log");
+        assert!(
+            log.contains("--runtime podman --userns keep-id"),
+            "expected podman runtime flags, got: {log}"
+        );
+        assert!(
+            log.contains("--volume /tmp:/tmp:ro,z"),
+            "expected SELinux relabel on bind mount, got: {log}"
+        );
+        assert!(
+            log.contains("--volume stakpak-aqua-cache:/home/agent/.local/share/aquaproj-aqua "),
+            "named volume should not be relabeled, got: {log}"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn run_stakpak_in_warden_leaves_docker_flags_untouched() {
+        let temp_dir = tempfile::TempDir::new().expect("temp dir");
+        let log_path = temp_dir.path().join("warden-args.log");
+        let fake_warden = temp_dir.path().join("warden");
+        let config = test_config(Some(WardenConfig {
+            enabled: true,
+            volumes: vec!["/tmp:/tmp:ro".into()],
+            runtime: ContainerRuntimeSetting::Docker,
+        }));
+
+        write_executable_script(
+            &fake_warden,
+            &format!(
+                "#!/bin/sh\nprintf '%s\\n' \"$*\" > \"{}\"\nexit 0\n",
+                log_path.display()
+            ),
+        );
+
+        run_stakpak_in_warden_with_path(
+            &fake_warden.to_string_lossy(),
+            config,
+            &["stakpak".to_string()],
+        )
+        .await
+        .expect("warden command succeeds");
+
+        let log = std::fs::read_to_string(&log_path).expect("read log");
+        assert!(
+            log.contains("--runtime docker"),
+            "expected docker runtime flag, got: {log}"
+        );
+        assert!(!log.contains("--userns"), "unexpected userns flag: {log}");
+        assert!(
+            log.contains("--volume /tmp:/tmp:ro "),
+            "docker volume should be passed as-is, got: {log}"
+        );
+    }
 }
@@ -100,6 +100,8 @@ pub struct SpawnConfig {
     pub caller_context: Vec<CallerContextInput>,
     /// Tools this run can auto-approve. Empty means allow all tools.
     pub allowed_tools: HashSet<String>,
+    /// Tools this run always rejects, even when allowed.
+    pub denied_tools: HashSet<String>,
     /// Optional per-request server overrides resolved from profile/channel config.
     pub overrides: Option<RunOverrides>,
     /// Agent server connection.
@@ -152,16 +154,25 @@ pub async fn spawn_agent(config: SpawnConfig) -> Result<AgentResult, AgentError>
 fn build_tool_decisions(
     tool_calls: &[(String, String)],
     allowed_tools: &HashSet<String>,
+    denied_tools: &HashSet<String>,
 ) -> HashMap<String, ToolDecisionInput> {
     tool_calls
         .iter()
         .map(|(tool_call_id, tool_name)| {
             let normalized = stakpak_server::strip_tool_prefix(tool_name).to_string();
+            let is_denied = denied_tools.contains(&normalized) || denied_tools.contains(tool_name);
             let is_allowed = allowed_tools.is_empty()
                 || allowed_tools.contains(&normalized)
                 || allowed_tools.contains(tool_name);
 
-            let (action, content) = if is_allowed {
+            let (action, content) = if is_denied {
+                (
+                    ToolDecisionAction::Reject,
+                    Some(format!(
+                        "Blocked by profile denied_tools (tool: {normalized})"
+                    )),
+                )
+            } else if is_allowed {
                 (ToolDecisionAction::Accept, None)
             } else {
                 (
@@ -253,7 +264,8 @@ async fn run_server_session(
                 .iter()
                 .map(|tool_call| (tool_call.id.clone(), tool_call.name.clone()))
                 .collect();
-            let decisions = build_tool_decisions(&tool_calls, &config.allowed_tools);
+            let decisions =
+                build_tool_decisions(&tool_calls, &config.allowed_tools, &config.denied_tools);
             client
                 .resolve_tools(&session_id, &run_id, decisions)
                 .await?;
@@ -312,7 +324,7 @@ mod tests {
         allowed_tools.insert("view".to_string());
 
         let tool_calls = vec![("1".to_string(), "stakpak__view".to_string())];
-        let decisions = build_tool_decisions(&tool_calls, &allowed_tools);
+        let decisions = build_tool_decisions(&tool_calls, &allowed_tools, &HashSet::new());
 
         let decision = decisions.get("1");
         assert!(decision.is_some());
@@ -328,7 +340,7 @@ mod tests {
         allowed_tools.insert("view".to_string());
 
         let tool_calls = vec![("1".to_string(), "stakpak__run_command".to_string())];
-        let decisions = build_tool_decisions(&tool_calls, &allowed_tools);
+        let decisions = build_tool_decisions(&tool_calls, &allowed_tools, &HashSet::new());
 
         let decision = decisions.get("1");
         assert!(decision.is_some());
@@ -346,7 +358,7 @@ mod tests {
         let allowed_tools = HashSet::new();
 
         let tool_calls = vec![("1".to_string(), "stakpak__run_command".to_string())];
-        let decisions = build_tool_decisions(&tool_calls, &allowed_tools);
+        let decisions = build_tool_decisions(&tool_calls, &allowed_tools, &HashSet::new());
 
         let decision = decisions.get("1");
         assert!(decision.is_some());
@@ -356,6 +368,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_tool_decision_deny_wins_over_allow() {
+        let allowed_tools = HashSet::from(["run_command".to_string()]);
+        let denied_tools = HashSet::from(["run_command".to_string()]);
+
+        let tool_calls = vec![("1".to_string(), "stakpak__run_command".to_string())];
+        let decisions = build_tool_decisions(&tool_calls, &allowed_tools, &denied_tools);
+
+        let decision = decisions.get("1");
+        assert!(decision.is_some());
+        if let Some(decision) = decision {
+            assert!(matches!(decision.action, ToolDecisionAction::Reject));
+            assert_eq!(
+                decision.content.as_deref(),
+                Some("Blocked by profile denied_tools (tool: run_command)")
+            );
+        }
+    }
+
     #[test]
     fn test_agent_result_success() {
         let result = AgentResult {
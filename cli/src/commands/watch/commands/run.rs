@@ -733,7 +733,7 @@ async fn handle_schedule_event(
     print_event("agent", &schedule.name, "Spawning agent...");
 
     let profile_name = schedule.effective_profile(&config.defaults).to_string();
-    let (profile_overrides, profile_allowed_tools) =
+    let (profile_overrides, profile_allowed_tools, profile_denied_tools) =
         resolve_schedule_profile_overrides(&profile_name, server);
     let run_overrides = apply_schedule_run_overrides(profile_overrides, schedule);
 
@@ -750,6 +750,7 @@ async fn handle_schedule_event(
         caller_context,
         allowed_tools: profile_allowed_tools
             .unwrap_or_else(|| server.default_allowed_tools.clone()),
+        denied_tools: profile_denied_tools,
         overrides: run_overrides,
         server: server.clone(),
     };
@@ -872,15 +873,20 @@ async fn handle_schedule_event(
     Ok(())
 }
 
+/// Resolve a schedule profile's run overrides, allowed tools and denied tools.
 fn resolve_schedule_profile_overrides(
     profile_name: &str,
     server: &AgentServerConnection,
-) -> (Option<RunOverrides>, Option<HashSet<String>>) {
+) -> (
+    Option<RunOverrides>,
+    Option<HashSet<String>>,
+    HashSet<String>,
+) {
     let Some(resolved) = crate::config::profile_resolver::resolve_profile_run_overrides(
         profile_name,
         Some(server.config_path.as_str()),
     ) else {
-        return (None, None);
+        return (None, None, HashSet::new());
     };
 
     let normalized_model = resolved.model.and_then(|model| {
@@ -896,6 +902,10 @@ fn resolve_schedule_profile_overrides(
         .map(|tools| AutoApproveOverride::AllowList(normalize_tool_list(tools)));
 
     let normalized_allowed_tools = resolved.allowed_tools.map(normalize_allowed_tools);
+    let normalized_denied_tools = resolved
+        .denied_tools
+        .map(normalize_allowed_tools)
+        .unwrap_or_default();
 
     let overrides = RunOverrides {
         model: normalized_model,
@@ -910,7 +920,7 @@ fn resolve_schedule_profile_overrides(
         Some(overrides)
     };
 
-    (overrides, normalized_allowed_tools)
+    (overrides, normalized_allowed_tools, normalized_denied_tools)
 }
 
 fn apply_schedule_run_overrides(
@@ -2213,6 +2223,7 @@ auto_approve = ["view"]
 api_key = "prod-key"
 model = "anthropic/claude-sonnet-4-5"
 allowed_tools = ["stakpak__run_command", "  "]
+denied_tools = ["stakpak__create"]
 auto_approve = ["stakpak__view"]
 system_prompt = "production prompt"
 max_turns = 24
@@ -2229,7 +2240,8 @@ max_turns = 24
             config_path: config_path.to_string_lossy().to_string(),
         };
 
-        let (overrides, allowed_tools) = resolve_schedule_profile_overrides("production", &server);
+        let (overrides, allowed_tools, denied_tools) =
+            resolve_schedule_profile_overrides("production", &server);
 
         let overrides = overrides.expect("expected run overrides");
         assert_eq!(
@@ -2248,6 +2260,7 @@ max_turns = 24
 
         let allowed_tools = allowed_tools.expect("expected allowed tools override");
         assert!(allowed_tools.contains("run_command"));
+        assert_eq!(denied_tools, HashSet::from(["create".to_string()]));
     }
 
     #[test]
@@ -2276,7 +2289,8 @@ auto_approve = ["view"]
             config_path: config_path.to_string_lossy().to_string(),
         };
 
-        let (overrides, _allowed_tools) = resolve_schedule_profile_overrides("default", &server);
+        let (overrides, _allowed_tools, _denied_tools) =
+            resolve_schedule_profile_overrides("default", &server);
         let overrides = overrides.expect("expected default profile overrides");
         assert_eq!(overrides.model.as_deref(), Some("openai/default-model"));
     }
@@ -2310,7 +2324,8 @@ auto_approve = []
             config_path: config_path.to_string_lossy().to_string(),
         };
 
-        let (overrides, allowed_tools) = resolve_schedule_profile_overrides("production", &server);
+        let (overrides, allowed_tools, _denied_tools) =
+            resolve_schedule_profile_overrides("production", &server);
         let allowed_tools = allowed_tools.expect("expected explicit allow-all override");
         assert!(allowed_tools.is_empty());
 
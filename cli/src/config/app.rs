@@ -1,19 +1,26 @@
 //! Main application configuration.
 
 use config::ConfigError;
+use stakpak_api::ContextLimitsConfig;
+use stakpak_mcp_server::RunCommandEnvConfig;
+use stakpak_server::LoopDetectionConfig;
 use stakpak_shared::auth_manager::AuthManager;
 use stakpak_shared::models::auth::ProviderAuth;
 use stakpak_shared::models::integrations::anthropic::AnthropicConfig;
 use stakpak_shared::models::integrations::gemini::GeminiConfig;
 use stakpak_shared::models::llm::{LLMProviderConfig, ProviderConfig};
+use stakpak_shared::secret_manager::{ToolOutputRedaction, ToolRedactionPolicy};
+use stakpak_shared::secrets::PrivacyPattern;
 use std::collections::HashMap;
 use std::fs::{create_dir_all, write};
 use std::io;
 use std::path::{Path, PathBuf};
 
+use super::env_interpolation::{interpolate_profile, process_env};
 use super::file::ConfigFile;
 use super::profile::{ProfileConfig, SubagentConfig};
 use super::rulebook::RulebookConfig;
+use super::secret_store::{api_key_for_save, default_secret_store, resolve_api_key};
 use super::types::{OldAppConfig, ProviderType, Settings};
 use super::warden::WardenConfig;
 use super::{STAKPAK_API_ENDPOINT, STAKPAK_CONFIG_PATH};
@@ -39,6 +46,8 @@ pub struct AppConfig {
     pub config_path: String,
     /// Allowed tools (empty = all tools allowed)
     pub allowed_tools: Option<Vec<String>>,
+    /// Tools removed even when allow-listed (deny wins)
+    pub denied_tools: Option<Vec<String>>,
     /// Tools that auto-approve without asking
     pub auto_approve: Option<Vec<String>>,
     /// Rulebook filtering configuration
@@ -55,6 +64,28 @@ pub struct AppConfig {
     pub system_prompt: Option<String>,
     /// Optional max turn override for sessions using this profile.
     pub max_turns: Option<usize>,
+    /// Optional model for session title generation.
+    pub title_model: Option<String>,
+    /// Optional limits for the history kept before inference.
+    pub context_limits: Option<ContextLimitsConfig>,
+    /// Optional limits on repeated tool calls in autopilot runs.
+    pub loop_detection: Option<LoopDetectionConfig>,
+    /// Maximum in-flight inference requests per provider.
+    pub max_concurrent_requests: HashMap<String, usize>,
+    /// Optional search API for local docs search.
+    pub search_api_url: Option<String>,
+    /// Accept model ids missing from the catalog as custom models.
+    pub allow_unknown_models: bool,
+    /// Environment variables passed to `run_command` (safe defaults when unset).
+    pub run_command_env: Option<RunCommandEnvConfig>,
+    /// Largest file the file tools load into memory (server default when unset).
+    pub max_file_size: Option<u64>,
+    /// Variables substituted into `{{name}}` placeholders in prompts.
+    pub prompt_vars: HashMap<String, String>,
+    /// Output redaction overrides per tool name.
+    pub tool_output_redaction: HashMap<String, ToolOutputRedaction>,
+    /// Custom privacy-mode patterns as label -> regex.
+    pub privacy_patterns: HashMap<String, String>,
     /// Unique ID for anonymous telemetry
     pub anonymous_id: Option<String>,
     /// Whether to collect telemetry data
@@ -79,6 +110,14 @@ impl AppConfig {
         let mut config_file = Self::load_config_file(&config_path)?;
         let is_config_dirty = config_file.ensure_readonly();
         let profile = config_file.resolved_profile_config(profile_name)?;
+        let mut profile = interpolate_profile(profile, &process_env)
+            .map_err(|e| ConfigError::Message(format!("Profile '{}': {}", profile_name, e)))?;
+        profile.api_key = resolve_api_key(
+            profile.api_key,
+            profile_name,
+            default_secret_store().as_deref(),
+        )
+        .map_err(ConfigError::Message)?;
 
         if is_config_dirty {
             // fail without crashing, because it's not critical
@@ -121,7 +160,20 @@ impl AppConfig {
         // Load existing config or create new one
         let config_path = PathBuf::from(&self.config_path);
         let mut config_file = Self::load_config_file(&config_path).unwrap_or_default();
-        config_file.insert_app_config(self.clone());
+
+        // Keys kept in the OS keychain stay there; the file only holds the sentinel
+        let mut config = self.clone();
+        let stored_api_key = config_file
+            .profile_config(&self.profile_name)
+            .and_then(|profile| profile.api_key.clone());
+        config.api_key = api_key_for_save(
+            stored_api_key.as_deref(),
+            config.api_key,
+            &self.profile_name,
+            default_secret_store().as_deref(),
+        )?;
+
+        config_file.insert_app_config(config);
         config_file.set_app_config_settings(self.clone());
 
         if let Some(parent) = config_path.parent() {
@@ -161,6 +213,7 @@ impl AppConfig {
             profile_name: profile_name.to_string(),
             config_path: path.display().to_string(),
             allowed_tools: profile_config.allowed_tools,
+            denied_tools: profile_config.denied_tools,
             auto_approve: profile_config.auto_approve,
             rulebooks: profile_config.rulebooks,
             warden: profile_config.warden,
@@ -170,6 +223,17 @@ impl AppConfig {
             subagent: profile_config.subagent,
             system_prompt: profile_config.system_prompt,
             max_turns: profile_config.max_turns,
+            title_model: profile_config.title_model,
+            context_limits: profile_config.context_limits,
+            loop_detection: profile_config.loop_detection,
+            max_concurrent_requests: profile_config.max_concurrent_requests,
+            search_api_url: profile_config.search_api_url,
+            allow_unknown_models: profile_config.allow_unknown_models.unwrap_or(false),
+            run_command_env: profile_config.run_command_env,
+            max_file_size: profile_config.max_file_size,
+            prompt_vars: profile_config.prompt_vars,
+            tool_output_redaction: profile_config.tool_output_redaction,
+            privacy_patterns: profile_config.privacy_patterns,
             anonymous_id: settings.anonymous_id,
             collect_telemetry: settings.collect_telemetry,
             editor: settings.editor,
@@ -220,6 +284,7 @@ impl AppConfig {
         match std::fs::read_to_string(config_path.as_ref()) {
             Ok(content) => {
                 Self::validate_removed_openai_provider_fields(&content)?;
+                Self::validate_pooled_api_keys(&content)?;
 
                 let config_file = toml::from_str::<ConfigFile>(&content).or_else(|e| {
                     println!("Failed to parse config file in new format: {}", e);
@@ -277,6 +342,40 @@ impl AppConfig {
         Ok(())
     }
 
+    /// Reject `api_keys` on providers that can't balance across keys.
+    ///
+    /// Only the OpenAI and Anthropic providers pool keys; anywhere else the
+    /// field would be dropped while parsing and every key but `api_key`
+    /// silently ignored.
+    fn validate_pooled_api_keys(content: &str) -> Result<(), ConfigError> {
+        let value = match content.parse::<toml::Value>() {
+            Ok(value) => value,
+            Err(_) => return Ok(()),
+        };
+
+        let Some(profiles) = value.get("profiles").and_then(toml::Value::as_table) else {
+            return Ok(());
+        };
+
+        for (profile_name, profile) in profiles {
+            let Some(providers) = profile.get("providers").and_then(toml::Value::as_table) else {
+                continue;
+            };
+            for (provider_name, provider) in providers {
+                let provider_type = provider.get("type").and_then(toml::Value::as_str);
+                if provider.get("api_keys").is_some()
+                    && !matches!(provider_type, Some("openai" | "anthropic"))
+                {
+                    return Err(ConfigError::Message(format!(
+                        "profiles.{profile_name}.providers.{provider_name}.api_keys is only supported by the openai and anthropic providers; use api_key instead"
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Migrate legacy provider configs (openai, anthropic, gemini)
     /// to the new unified `providers` HashMap format.
     /// Also migrates auth.toml to config.toml, model fields, and ensures settings have default values.
@@ -648,6 +747,7 @@ impl AppConfig {
             api_key: None,
             api_endpoint,
             auth: Some(auth),
+            api_keys: Vec::new(),
         }
     }
 
@@ -769,6 +869,7 @@ impl AppConfig {
                     api_endpoint: anthropic.api_endpoint,
                     access_token: anthropic.access_token,
                     auth: None, // Auth is already resolved into api_key/access_token
+                    api_keys: Vec::new(),
                 },
             );
         }
@@ -812,6 +913,7 @@ impl AppConfig {
             self.get_anthropic_config_with_auth(),
             self.get_gemini_config_with_auth(),
         );
+        config.max_concurrent_requests = self.max_concurrent_requests.clone();
 
         config
     }
@@ -827,6 +929,7 @@ impl AppConfig {
             self.get_anthropic_config_with_auth_async().await,
             self.get_gemini_config_with_auth_async().await,
         );
+        config.max_concurrent_requests = self.max_concurrent_requests.clone();
 
         config
     }
@@ -901,6 +1004,25 @@ impl AppConfig {
         self.subagent.as_ref().and_then(|s| s.model.clone())
     }
 
+    /// Per-tool output redaction: the built-in defaults with the profile's overrides
+    pub fn tool_redaction_policy(&self) -> ToolRedactionPolicy {
+        ToolRedactionPolicy::default().with_overrides(&self.tool_output_redaction)
+    }
+
+    /// Registers the profile's custom privacy patterns with the redaction engine
+    pub fn register_privacy_patterns(&self) -> Result<(), String> {
+        let mut patterns = self
+            .privacy_patterns
+            .iter()
+            .map(|(label, regex)| PrivacyPattern {
+                label: label.clone(),
+                regex: regex.clone(),
+            })
+            .collect::<Vec<_>>();
+        patterns.sort_by(|a, b| a.label.cmp(&b.label));
+        stakpak_shared::secrets::register_privacy_patterns(&patterns)
+    }
+
     /// Get the default Model from config
     ///
     /// Uses the `model` field if set, otherwise falls back to a default Claude Opus model.
@@ -911,22 +1033,23 @@ impl AppConfig {
     /// prefix (e.g., "anthropic/claude-opus-4-5"), it searches within that
     /// provider first. Otherwise, it searches all providers.
     pub fn get_default_model(&self, cli_override: Option<&str>) -> stakpak_api::Model {
-        let has_stakpak_key = self.get_stakpak_api_key().is_some();
+        let model_str = self.requested_model(cli_override);
+        self.resolve_model(model_str, stakpak_api::find_model(model_str, false))
+    }
 
-        // Priority: cli_override > recent_models[0] > model > default
-        // The most recently used model takes precedence over the static config model,
-        // so re-opening stakpak continues with the last model you were using.
-        let most_recent = self.recent_models.first().map(|s| s.as_str());
-        let model_str = cli_override
-            .or(most_recent)
-            .or(self.model.as_deref())
-            .unwrap_or("claude-opus-4-6");
+    /// Turn a model id and its catalog entry, if any, into the model to run
+    fn resolve_model(
+        &self,
+        model_str: &str,
+        catalog_model: Option<stakpak_api::Model>,
+    ) -> stakpak_api::Model {
+        let has_stakpak_key = self.get_stakpak_api_key().is_some();
 
         // Extract explicit provider prefix if present (e.g., "amazon-bedrock/claude-sonnet-4-5")
         let explicit_provider = model_str.find('/').map(|idx| &model_str[..idx]);
 
-        // First, find the model without Stakpak transform to determine its native provider
-        let model = stakpak_api::find_model(model_str, false).unwrap_or_else(|| {
+        // The catalog entry, found without Stakpak transform, gives the native provider
+        let model = catalog_model.unwrap_or_else(|| {
             // Model not found in catalog - create a custom model
             // Extract provider from prefix if present
             let (provider, model_id) = if let Some(idx) = model_str.find('/') {
@@ -978,6 +1101,82 @@ impl AppConfig {
 
         model
     }
+
+    /// Like `get_default_model`, but an id missing from the model catalog is a
+    /// config error instead of silently becoming a custom model.
+    ///
+    /// Only ids that resolve against the catalog are checked: with a Stakpak
+    /// key any id can be routed through Stakpak, and ids prefixed with a
+    /// provider outside the catalog (amazon-bedrock native ids, a custom
+    /// OpenAI-compatible provider, ollama, stakpak, ...) are passed through.
+    /// `allow_unknown_models` restores the lenient behavior for every id.
+    /// Without a catalog cache nothing can be checked, so every id is accepted.
+    pub fn try_get_default_model(
+        &self,
+        cli_override: Option<&str>,
+    ) -> Result<stakpak_api::Model, String> {
+        let catalog = if self.checks_model_ids(cli_override) {
+            stakpak_api::load_model_catalog()
+        } else {
+            Vec::new()
+        };
+        self.try_get_default_model_in(&catalog, cli_override)
+    }
+
+    /// [`Self::try_get_default_model`] against an already loaded `catalog`.
+    ///
+    /// An empty catalog checks nothing, since every id would be unknown.
+    pub(crate) fn try_get_default_model_in(
+        &self,
+        catalog: &[stakpak_api::Model],
+        cli_override: Option<&str>,
+    ) -> Result<stakpak_api::Model, String> {
+        let model_str = self.requested_model(cli_override);
+        if self.checks_model_ids(cli_override) && !catalog.is_empty() {
+            let model = stakpak_api::try_find_model_in(catalog, model_str).map_err(|e| {
+                format!(
+                    "{}. Prefix a custom model with its provider (e.g. \"ollama/{}\") or set allow_unknown_models = true in profile '{}'",
+                    e,
+                    model_str.split_once('/').map_or(model_str, |(_, id)| id),
+                    self.profile_name
+                )
+            })?;
+            return Ok(self.resolve_model(model_str, Some(model)));
+        }
+
+        Ok(self.get_default_model(cli_override))
+    }
+
+    /// Whether the requested id is checked against the catalog, see
+    /// [`Self::try_get_default_model`].
+    fn checks_model_ids(&self, cli_override: Option<&str>) -> bool {
+        let catalog_providers = ["anthropic", "openai", "google", "gemini"];
+        let provider = self
+            .requested_model(cli_override)
+            .split_once('/')
+            .map(|(provider, _)| provider);
+        !self.allow_unknown_models
+            && self.get_stakpak_api_key().is_none()
+            && provider.is_none_or(|provider| catalog_providers.contains(&provider))
+    }
+
+    /// Model id to use. Priority: cli_override > recent_models[0] > model > default.
+    /// The most recently used model takes precedence over the static config model,
+    /// so re-opening stakpak continues with the last model you were using.
+    fn requested_model<'a>(&'a self, cli_override: Option<&'a str>) -> &'a str {
+        let most_recent = self.recent_models.first().map(|s| s.as_str());
+        cli_override
+            .or(most_recent)
+            .or(self.model.as_deref())
+            .unwrap_or("claude-opus-4-6")
+    }
+
+    /// Resolve the configured `title_model` the same way as the session model.
+    pub fn get_title_model(&self) -> Option<stakpak_api::Model> {
+        self.title_model
+            .as_deref()
+            .map(|model| self.get_default_model(Some(model)))
+    }
 }
 
 // Conversions
@@ -1000,6 +1199,7 @@ impl From<AppConfig> for ProfileConfig {
             api_endpoint: Some(config.api_endpoint),
             api_key: config.api_key,
             allowed_tools: config.allowed_tools,
+            denied_tools: config.denied_tools,
             auto_approve: config.auto_approve,
             rulebooks: config.rulebooks,
             warden: config.warden,
@@ -1010,6 +1210,17 @@ impl From<AppConfig> for ProfileConfig {
             recent_models: config.recent_models,
             system_prompt: config.system_prompt,
             max_turns: config.max_turns,
+            title_model: config.title_model,
+            context_limits: config.context_limits,
+            loop_detection: config.loop_detection,
+            max_concurrent_requests: config.max_concurrent_requests,
+            search_api_url: config.search_api_url,
+            allow_unknown_models: config.allow_unknown_models.then_some(true),
+            run_command_env: config.run_command_env,
+            max_file_size: config.max_file_size,
+            prompt_vars: config.prompt_vars,
+            tool_output_redaction: config.tool_output_redaction,
+            privacy_patterns: config.privacy_patterns,
             // Legacy fields - not used in new format
             openai: None,
             anthropic: None,
@@ -0,0 +1,190 @@
+//! `${VAR}` environment-variable references in profile credentials.
+//!
+//! Credential and endpoint fields (`api_key`, `api_endpoint`, `access_token`,
+//! provider auth keys, Bedrock region/profile) may reference the environment
+//! as `${VAR}` or `${VAR:-default}`, so secrets never have to be written to
+//! `config.toml`. Other fields, such as system prompts, are left untouched.
+//! `$${` renders a literal `${`.
+
+use super::profile::ProfileConfig;
+use serde_json::Value;
+
+/// Profile keys whose string values are interpolated, at any depth.
+const INTERPOLATED_KEYS: &[&str] = &[
+    "api_key",
+    "api_endpoint",
+    "access_token",
+    "key",
+    "region",
+    "profile_name",
+];
+
+/// Resolve `${VAR}` and `${VAR:-default}` references in `value`.
+pub(crate) fn interpolate_env(
+    value: &str,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut resolved = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some((before, after)) = rest.split_once("${") {
+        if let Some(before) = before.strip_suffix('$') {
+            resolved.push_str(before);
+            resolved.push_str("${");
+            rest = after;
+            continue;
+        }
+        resolved.push_str(before);
+
+        let (reference, tail) = after
+            .split_once('}')
+            .ok_or_else(|| format!("unterminated '${{' in '{}'", value))?;
+        rest = tail;
+
+        let (name, default) = match reference.split_once(":-") {
+            Some((name, default)) => (name.trim(), Some(default)),
+            None => (reference.trim(), None),
+        };
+        if !is_env_var_name(name) {
+            return Err(format!("invalid environment variable name '{}'", name));
+        }
+
+        match (lookup(name), default) {
+            // Like the shell, `:-` also covers variables that are set but empty
+            (Some(value), Some(default)) if value.is_empty() => resolved.push_str(default),
+            (Some(value), _) => resolved.push_str(&value),
+            (None, Some(default)) => resolved.push_str(default),
+            (None, None) => {
+                return Err(format!(
+                    "environment variable '{}' is not set (use ${{{}:-default}} for a fallback)",
+                    name, name
+                ));
+            }
+        }
+    }
+    resolved.push_str(rest);
+
+    Ok(resolved)
+}
+
+/// Resolve environment references in the credential fields of a profile.
+pub(crate) fn interpolate_profile(
+    profile: ProfileConfig,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<ProfileConfig, String> {
+    let mut value = serde_json::to_value(&profile).map_err(|e| e.to_string())?;
+    if !interpolate_value(&mut value, "", false, lookup)? {
+        return Ok(profile);
+    }
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+/// Put `${VAR}` references from `raw` back into `resolved` before it is saved.
+///
+/// A field keeps its reference only while the reference still resolves to the
+/// value being saved, so an explicitly changed value is written as-is.
+pub(crate) fn restore_env_references(
+    raw: &ProfileConfig,
+    resolved: ProfileConfig,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> ProfileConfig {
+    let (Ok(raw_value), Ok(mut value)) =
+        (serde_json::to_value(raw), serde_json::to_value(&resolved))
+    else {
+        return resolved;
+    };
+    if !restore_value(&raw_value, &mut value, false, lookup) {
+        return resolved;
+    }
+    serde_json::from_value(value).unwrap_or(resolved)
+}
+
+/// Environment lookup used outside of tests.
+pub(crate) fn process_env(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+fn interpolate_value(
+    value: &mut Value,
+    path: &str,
+    interpolated_key: bool,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<bool, String> {
+    match value {
+        Value::String(text) if interpolated_key && text.contains("${") => {
+            *text = interpolate_env(text, lookup).map_err(|e| format!("{}: {}", path, e))?;
+            Ok(true)
+        }
+        Value::Object(map) => {
+            let mut changed = false;
+            for (key, child) in map.iter_mut() {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                changed |= interpolate_value(
+                    child,
+                    &child_path,
+                    INTERPOLATED_KEYS.contains(&key.as_str()),
+                    lookup,
+                )?;
+            }
+            Ok(changed)
+        }
+        Value::Array(items) => {
+            let mut changed = false;
+            for (index, item) in items.iter_mut().enumerate() {
+                let item_path = format!("{}[{}]", path, index);
+                changed |= interpolate_value(item, &item_path, interpolated_key, lookup)?;
+            }
+            Ok(changed)
+        }
+        _ => Ok(false),
+    }
+}
+
+fn restore_value(
+    raw: &Value,
+    value: &mut Value,
+    interpolated_key: bool,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> bool {
+    match (raw, value) {
+        (Value::String(raw), Value::String(text)) if interpolated_key && raw.contains("${") => {
+            if interpolate_env(raw, lookup).is_ok_and(|resolved| resolved == *text) {
+                *text = raw.clone();
+                return true;
+            }
+            false
+        }
+        (Value::Object(raw), Value::Object(map)) => {
+            let mut changed = false;
+            for (key, child) in map.iter_mut() {
+                if let Some(raw_child) = raw.get(key) {
+                    changed |= restore_value(
+                        raw_child,
+                        child,
+                        INTERPOLATED_KEYS.contains(&key.as_str()),
+                        lookup,
+                    );
+                }
+            }
+            changed
+        }
+        (Value::Array(raw), Value::Array(items)) => {
+            let mut changed = false;
+            for (raw_item, item) in raw.iter().zip(items.iter_mut()) {
+                changed |= restore_value(raw_item, item, interpolated_key, lookup);
+            }
+            changed
+        }
+        _ => false,
+    }
+}
+
+fn is_env_var_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -7,6 +7,7 @@ use std::fs::{create_dir_all, write};
 use std::path::Path;
 
 use super::STAKPAK_API_ENDPOINT;
+use super::env_interpolation::{process_env, restore_env_references};
 use super::profile::ProfileConfig;
 use super::types::{OldAppConfig, Settings};
 
@@ -82,9 +83,16 @@ impl ConfigFile {
     }
 
     /// Insert or update a profile from an AppConfig.
+    ///
+    /// Credentials loaded from `${VAR}` references are written back as the
+    /// reference, not the resolved secret.
     pub(crate) fn insert_app_config(&mut self, config: super::AppConfig) {
-        self.profiles
-            .insert(config.profile_name.clone(), config.into());
+        let mut profile: ProfileConfig = config.clone().into();
+        if let Some(raw) = self.profile_config(&config.profile_name) {
+            let raw = raw.merge(self.profile_config("all"));
+            profile = restore_env_references(&raw, profile, &process_env);
+        }
+        self.profiles.insert(config.profile_name, profile);
     }
 
     /// Update settings from an AppConfig.
@@ -9,12 +9,14 @@
 //! - Models cache from models.dev
 
 mod app;
+mod env_interpolation;
 mod file;
 pub mod models_cache;
 pub(crate) mod openai_resolver;
 mod profile;
 pub(crate) mod profile_resolver;
 mod rulebook;
+pub(crate) mod secret_store;
 mod types;
 pub(crate) mod warden;
 
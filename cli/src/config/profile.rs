@@ -1,10 +1,15 @@
 //! Profile configuration for per-environment settings.
 
 use serde::{Deserialize, Serialize};
+use stakpak_api::ContextLimitsConfig;
+use stakpak_mcp_server::RunCommandEnvConfig;
+use stakpak_server::LoopDetectionConfig;
 use stakpak_shared::models::integrations::anthropic::AnthropicConfig;
 use stakpak_shared::models::integrations::gemini::GeminiConfig;
 use stakpak_shared::models::integrations::openai::OpenAIConfig;
 use stakpak_shared::models::llm::ProviderConfig;
+use stakpak_shared::secret_manager::ToolOutputRedaction;
+use stakpak_shared::secrets::PrivacyPattern;
 use std::collections::HashMap;
 
 use super::rulebook::RulebookConfig;
@@ -51,6 +56,9 @@ pub struct ProfileConfig {
     pub provider: Option<ProviderType>,
     /// Allowed tools (empty = all tools allowed)
     pub allowed_tools: Option<Vec<String>>,
+    /// Tools removed even when allow-listed (deny wins)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub denied_tools: Option<Vec<String>>,
     /// Tools that auto-approve without asking
     pub auto_approve: Option<Vec<String>>,
     /// Rulebook filtering configuration
@@ -99,6 +107,59 @@ pub struct ProfileConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_turns: Option<usize>,
 
+    /// Model used to generate session titles. Defaults to a cheap model
+    /// from the configured providers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_model: Option<String>,
+
+    /// How much conversation history is kept before inference.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_limits: Option<ContextLimitsConfig>,
+
+    /// When to stop autopilot runs that keep repeating the same tool calls;
+    /// `enabled = false` turns the check off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loop_detection: Option<LoopDetectionConfig>,
+
+    /// Maximum in-flight inference requests per provider (key = provider name).
+    /// Requests beyond the limit wait for a slot.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub max_concurrent_requests: HashMap<String, usize>,
+
+    /// Search API used for docs search without a Stakpak API key, e.g. a
+    /// self-hosted searchpak. Defaults to the bundled search service.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_api_url: Option<String>,
+
+    /// Accept model ids missing from the model catalog instead of failing,
+    /// treating them as custom models like older versions did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_unknown_models: Option<bool>,
+
+    /// Environment variables passed to `run_command`: `allow` extends the safe
+    /// defaults, `deny` wins and is redacted from output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_command_env: Option<RunCommandEnvConfig>,
+
+    /// Largest file, in bytes, the view, create and str_replace tools load
+    /// into memory. Larger files must be paged with view_range or grep.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_file_size: Option<u64>,
+
+    /// Variables substituted into `{{name}}` placeholders in prompts.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub prompt_vars: HashMap<String, String>,
+
+    /// Output redaction per tool: `redact` (default), `show_to_user` to redact
+    /// only what the model sees, or `fully_redact` to hide the whole output.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tool_output_redaction: HashMap<String, ToolOutputRedaction>,
+
+    /// Extra values redacted in privacy mode, as label -> regex. The label
+    /// appears in the placeholder, e.g. `[REDACTED_SECRET:employee-id:...]`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub privacy_patterns: HashMap<String, String>,
+
     // =========================================================================
     // Legacy model fields - kept for backward compatibility during migration
     // These are read but deprecated (will migrate to 'model' field)
@@ -140,6 +201,18 @@ impl ProfileConfig {
                 recent_models: default.recent_models.clone(),
                 system_prompt: default.system_prompt.clone(),
                 max_turns: default.max_turns,
+                title_model: default.title_model.clone(),
+                context_limits: default.context_limits,
+                loop_detection: default.loop_detection.clone(),
+                max_concurrent_requests: default.max_concurrent_requests.clone(),
+                search_api_url: default.search_api_url.clone(),
+                allow_unknown_models: default.allow_unknown_models,
+                max_file_size: default.max_file_size,
+                prompt_vars: default.prompt_vars.clone(),
+                tool_output_redaction: default.tool_output_redaction.clone(),
+                privacy_patterns: default.privacy_patterns.clone(),
+                // Denials only restrict, so they carry over
+                denied_tools: default.denied_tools.clone(),
                 // Enable warden for readonly sandboxed execution
                 warden: Some(WardenConfig::readonly_profile()),
                 // Don't copy allowed_tools/auto_approve - readonly has its own restrictions
@@ -191,6 +264,7 @@ impl ProfileConfig {
                     api_key: openai.api_key,
                     api_endpoint: Self::clean_api_endpoint(openai.api_endpoint),
                     auth: None,
+                    api_keys: Vec::new(),
                 });
                 migrated = true;
             }
@@ -206,6 +280,7 @@ impl ProfileConfig {
                         api_endpoint: Self::clean_api_endpoint(anthropic.api_endpoint),
                         access_token: anthropic.access_token,
                         auth: None,
+                        api_keys: Vec::new(),
                     },
                 );
                 migrated = true;
@@ -384,6 +459,10 @@ impl ProfileConfig {
                 .allowed_tools
                 .clone()
                 .or_else(|| other.and_then(|config| config.allowed_tools.clone())),
+            denied_tools: self
+                .denied_tools
+                .clone()
+                .or_else(|| other.and_then(|config| config.denied_tools.clone())),
             auto_approve: self
                 .auto_approve
                 .clone()
@@ -437,6 +516,59 @@ impl ProfileConfig {
             max_turns: self
                 .max_turns
                 .or_else(|| other.and_then(|config| config.max_turns)),
+            title_model: self
+                .title_model
+                .clone()
+                .or_else(|| other.and_then(|config| config.title_model.clone())),
+            context_limits: self
+                .context_limits
+                .or_else(|| other.and_then(|config| config.context_limits)),
+            loop_detection: self
+                .loop_detection
+                .clone()
+                .or_else(|| other.and_then(|config| config.loop_detection.clone())),
+            // Concurrency limits - other's as the base, self's win on conflicts
+            max_concurrent_requests: other
+                .map(|config| config.max_concurrent_requests.clone())
+                .unwrap_or_default()
+                .into_iter()
+                .chain(self.max_concurrent_requests.clone())
+                .collect(),
+            search_api_url: self
+                .search_api_url
+                .clone()
+                .or_else(|| other.and_then(|config| config.search_api_url.clone())),
+            allow_unknown_models: self
+                .allow_unknown_models
+                .or_else(|| other.and_then(|config| config.allow_unknown_models)),
+            run_command_env: self
+                .run_command_env
+                .clone()
+                .or_else(|| other.and_then(|config| config.run_command_env.clone())),
+            max_file_size: self
+                .max_file_size
+                .or_else(|| other.and_then(|config| config.max_file_size)),
+            // Prompt vars - other's as the base, self's win on conflicts
+            prompt_vars: other
+                .map(|config| config.prompt_vars.clone())
+                .unwrap_or_default()
+                .into_iter()
+                .chain(self.prompt_vars.clone())
+                .collect(),
+            // Tool output redaction - other's as the base, self's win on conflicts
+            tool_output_redaction: other
+                .map(|config| config.tool_output_redaction.clone())
+                .unwrap_or_default()
+                .into_iter()
+                .chain(self.tool_output_redaction.clone())
+                .collect(),
+            // Privacy patterns - other's as the base, self's win on conflicts
+            privacy_patterns: other
+                .map(|config| config.privacy_patterns.clone())
+                .unwrap_or_default()
+                .into_iter()
+                .chain(self.privacy_patterns.clone())
+                .collect(),
             // Legacy fields - kept for reading only, not merged
             eco_model: None,
             smart_model: None,
@@ -452,6 +584,42 @@ impl ProfileConfig {
             return Err(format!("max_turns must be 1-256, got {max_turns}"));
         }
 
+        if let Some(context_limits) = self.context_limits.as_ref() {
+            context_limits.validate()?;
+        }
+
+        if let Some(loop_detection) = self.loop_detection.as_ref() {
+            loop_detection.validate()?;
+        }
+
+        if let Some((provider, limit)) = self
+            .max_concurrent_requests
+            .iter()
+            .find(|(_, limit)| !(1..=64).contains(*limit))
+        {
+            return Err(format!(
+                "max_concurrent_requests for '{provider}' must be 1-64, got {limit}"
+            ));
+        }
+
+        if self.max_file_size == Some(0) {
+            return Err("max_file_size must be greater than 0".to_string());
+        }
+
+        for (label, regex) in &self.privacy_patterns {
+            PrivacyPattern {
+                label: label.clone(),
+                regex: regex.clone(),
+            }
+            .compile()?;
+        }
+
+        if let Some(url) = self.search_api_url.as_deref()
+            && !(url.starts_with("http://") || url.starts_with("https://"))
+        {
+            return Err(format!("search_api_url must be an http(s) URL, got {url}"));
+        }
+
         if let Some(system_prompt) = self.system_prompt.as_ref()
             && system_prompt.chars().count() > 32 * 1024
         {
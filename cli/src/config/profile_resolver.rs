@@ -9,6 +9,7 @@ pub(crate) struct ResolvedProfileOverrides {
     pub model: Option<String>,
     pub auto_approve: Option<Vec<String>>,
     pub allowed_tools: Option<Vec<String>>,
+    pub denied_tools: Option<Vec<String>>,
     pub system_prompt: Option<String>,
     pub max_turns: Option<usize>,
 }
@@ -22,12 +23,14 @@ pub(crate) fn resolve_profile_run_overrides(
     let model = normalize_optional_string(config.model);
     let auto_approve = normalize_tool_list(config.auto_approve);
     let allowed_tools = normalize_tool_list(config.allowed_tools);
+    let denied_tools = normalize_tool_list(config.denied_tools);
     let system_prompt = normalize_optional_string(config.system_prompt);
     let max_turns = config.max_turns;
 
     if model.is_none()
         && auto_approve.is_none()
         && allowed_tools.is_none()
+        && denied_tools.is_none()
         && system_prompt.is_none()
         && max_turns.is_none()
     {
@@ -38,6 +41,7 @@ pub(crate) fn resolve_profile_run_overrides(
         model,
         auto_approve,
         allowed_tools,
+        denied_tools,
         system_prompt,
         max_turns,
     })
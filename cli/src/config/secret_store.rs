@@ -0,0 +1,134 @@
+//! OS keychain storage for the Stakpak API key.
+//!
+//! With the `keyring` feature, `stakpak auth login` (and `stakpak login`) stores
+//! the key in the OS secret store under the `stakpak` service with the profile
+//! name as the account, and writes `api_key = "keyring"` to the profile instead
+//! of the key itself.
+//! Without the feature the key is kept in the config file as before.
+
+/// `api_key` value meaning "read the key from the OS keychain".
+pub const KEYRING_SENTINEL: &str = "keyring";
+
+/// Keychain service the API keys are stored under.
+pub const KEYRING_SERVICE: &str = "stakpak";
+
+/// A store for per-profile secrets.
+pub trait SecretStore {
+    fn get(&self, profile_name: &str) -> Result<Option<String>, String>;
+    fn set(&self, profile_name: &str, secret: &str) -> Result<(), String>;
+    fn delete(&self, profile_name: &str) -> Result<(), String>;
+}
+
+/// The OS keychain, via the `keyring` crate.
+#[cfg(feature = "keyring")]
+pub struct OsKeyring;
+
+#[cfg(feature = "keyring")]
+impl OsKeyring {
+    fn entry(profile_name: &str) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(KEYRING_SERVICE, profile_name)
+            .map_err(|e| format!("Failed to open keychain entry: {}", e))
+    }
+}
+
+#[cfg(feature = "keyring")]
+impl SecretStore for OsKeyring {
+    fn get(&self, profile_name: &str) -> Result<Option<String>, String> {
+        match Self::entry(profile_name)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Failed to read API key from keychain: {}", e)),
+        }
+    }
+
+    fn set(&self, profile_name: &str, secret: &str) -> Result<(), String> {
+        Self::entry(profile_name)?
+            .set_password(secret)
+            .map_err(|e| format!("Failed to store API key in keychain: {}", e))
+    }
+
+    fn delete(&self, profile_name: &str) -> Result<(), String> {
+        match Self::entry(profile_name)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to remove API key from keychain: {}", e)),
+        }
+    }
+}
+
+/// The secret store of this build, if it was built with one.
+pub fn default_secret_store() -> Option<Box<dyn SecretStore>> {
+    #[cfg(feature = "keyring")]
+    {
+        Some(Box::new(OsKeyring))
+    }
+    #[cfg(not(feature = "keyring"))]
+    {
+        None
+    }
+}
+
+/// Replace the `keyring` sentinel with the key stored for `profile_name`.
+pub(crate) fn resolve_api_key(
+    api_key: Option<String>,
+    profile_name: &str,
+    store: Option<&dyn SecretStore>,
+) -> Result<Option<String>, String> {
+    if api_key.as_deref() != Some(KEYRING_SENTINEL) {
+        return Ok(api_key);
+    }
+    let store = store.ok_or_else(|| {
+        "api_key = \"keyring\" needs a stakpak build with the `keyring` feature".to_string()
+    })?;
+    store.get(profile_name)?.map(Some).ok_or_else(|| {
+        format!(
+            "No API key in the OS keychain for profile '{}'. Run `stakpak auth login --provider stakpak` to store one",
+            profile_name
+        )
+    })
+}
+
+/// Store a new API key, returning the value to write to the config file.
+///
+/// Returns the sentinel when the key went to the keychain, or the key itself
+/// when this build has no secret store.
+pub(crate) fn store_api_key(
+    api_key: String,
+    profile_name: &str,
+    store: Option<&dyn SecretStore>,
+) -> Result<String, String> {
+    match store {
+        Some(store) => {
+            store.set(profile_name, &api_key)?;
+            Ok(KEYRING_SENTINEL.to_string())
+        }
+        None => Ok(api_key),
+    }
+}
+
+/// The `api_key` to write when saving a profile whose stored value is `stored`.
+///
+/// A profile that keeps its key in the keychain keeps the sentinel on disk; a
+/// changed key is written to the keychain and a cleared key is removed from it.
+pub(crate) fn api_key_for_save(
+    stored: Option<&str>,
+    api_key: Option<String>,
+    profile_name: &str,
+    store: Option<&dyn SecretStore>,
+) -> Result<Option<String>, String> {
+    let Some(store) = store.filter(|_| stored == Some(KEYRING_SENTINEL)) else {
+        return Ok(api_key);
+    };
+    match api_key {
+        Some(key) if key == KEYRING_SENTINEL => Ok(Some(key)),
+        Some(key) => {
+            if store.get(profile_name)?.as_deref() != Some(key.as_str()) {
+                store.set(profile_name, &key)?;
+            }
+            Ok(Some(KEYRING_SENTINEL.to_string()))
+        }
+        None => {
+            store.delete(profile_name)?;
+            Ok(None)
+        }
+    }
+}
@@ -1,9 +1,11 @@
 //! Tests for configuration module.
 
+use super::secret_store::SecretStore;
 use super::*;
 use chrono::Utc;
 use stakpak_api::models::RuleBookVisibility;
 use stakpak_shared::models::llm::ProviderConfig;
+use stakpak_shared::secret_manager::ToolOutputRedaction;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -46,6 +48,7 @@ fn sample_app_config(profile_name: &str) -> AppConfig {
         profile_name: profile_name.into(),
         config_path: "/tmp/stakpak/config.toml".into(),
         allowed_tools: Some(vec!["git".into(), "curl".into()]),
+        denied_tools: None,
         auto_approve: Some(vec!["git status".into()]),
         subagent: None,
         rulebooks: Some(RulebookConfig {
@@ -57,12 +60,24 @@ fn sample_app_config(profile_name: &str) -> AppConfig {
         warden: Some(WardenConfig {
             enabled: true,
             volumes: vec!["/tmp:/tmp:ro".into()],
+            runtime: Default::default(),
         }),
         provider: ProviderType::Remote,
         providers: HashMap::new(),
         model: None,
         system_prompt: None,
         max_turns: None,
+        title_model: None,
+        context_limits: None,
+        loop_detection: None,
+        max_concurrent_requests: HashMap::new(),
+        search_api_url: None,
+        allow_unknown_models: false,
+        run_command_env: None,
+        max_file_size: None,
+        prompt_vars: HashMap::new(),
+        tool_output_redaction: HashMap::new(),
+        privacy_patterns: HashMap::new(),
         anonymous_id: Some("test-user-id".into()),
         collect_telemetry: Some(true),
         editor: Some("nano".into()),
@@ -221,6 +236,7 @@ fn resolved_profile_config_merges_all_profile_defaults() {
             warden: Some(WardenConfig {
                 enabled: true,
                 volumes: vec!["/tmp:/tmp:ro".into()],
+                runtime: Default::default(),
             }),
             ..ProfileConfig::default()
         },
@@ -283,6 +299,85 @@ fn profile_merge_falls_back_to_base_for_system_prompt_and_max_turns() {
     assert_eq!(merged.max_turns, Some(24));
 }
 
+#[test]
+fn profile_merge_layers_prompt_vars_over_base() {
+    let base = ProfileConfig {
+        prompt_vars: HashMap::from([
+            ("repo".to_string(), "stakpak/agent".to_string()),
+            ("env".to_string(), "staging".to_string()),
+        ]),
+        ..ProfileConfig::default()
+    };
+    let override_profile = ProfileConfig {
+        prompt_vars: HashMap::from([("env".to_string(), "production".to_string())]),
+        ..ProfileConfig::default()
+    };
+
+    let merged = override_profile.merge(Some(&base));
+    assert_eq!(
+        merged.prompt_vars.get("repo").map(String::as_str),
+        Some("stakpak/agent")
+    );
+    assert_eq!(
+        merged.prompt_vars.get("env").map(String::as_str),
+        Some("production")
+    );
+}
+
+#[test]
+fn profile_tool_output_redaction_parses_and_layers_over_base() {
+    let base: ProfileConfig = toml::from_str(
+        r#"
+[tool_output_redaction]
+generate_ssh_key = "show_to_user"
+issue_token = "fully_redact"
+"#,
+    )
+    .expect("parse profile");
+    let override_profile = ProfileConfig {
+        tool_output_redaction: HashMap::from([(
+            "issue_token".to_string(),
+            ToolOutputRedaction::Redact,
+        )]),
+        ..ProfileConfig::default()
+    };
+
+    let merged = override_profile.merge(Some(&base));
+    assert_eq!(
+        merged.tool_output_redaction.get("generate_ssh_key"),
+        Some(&ToolOutputRedaction::ShowToUser)
+    );
+    assert_eq!(
+        merged.tool_output_redaction.get("issue_token"),
+        Some(&ToolOutputRedaction::Redact)
+    );
+}
+
+#[test]
+fn profile_privacy_patterns_parse_and_reject_invalid_regex() {
+    let profile: ProfileConfig = toml::from_str(
+        r#"
+[privacy_patterns]
+employee-id = 'EMP-\d{6}'
+"#,
+    )
+    .expect("parse profile");
+    assert_eq!(
+        profile
+            .privacy_patterns
+            .get("employee-id")
+            .map(String::as_str),
+        Some(r"EMP-\d{6}")
+    );
+    assert!(profile.validate().is_ok());
+
+    let invalid = ProfileConfig {
+        privacy_patterns: HashMap::from([("employee-id".to_string(), "EMP-(".to_string())]),
+        ..ProfileConfig::default()
+    };
+    assert!(invalid.validate().is_err());
+}
+
 #[test]
 fn profile_merge_inherits_subagent_model_from_base() {
     let base = ProfileConfig {
@@ -358,6 +453,102 @@ fn profile_validate_rejects_invalid_max_turns_and_prompt_size() {
     assert!(invalid_prompt.validate().is_err());
 }
 
+#[test]
+fn profile_max_file_size_parses_and_rejects_zero() {
+    let profile: ProfileConfig = toml::from_str("max_file_size = 1048576").expect("parse");
+    assert_eq!(profile.max_file_size, Some(1_048_576));
+    assert!(profile.validate().is_ok());
+
+    let zero = ProfileConfig {
+        max_file_size: Some(0),
+        ..ProfileConfig::default()
+    };
+    assert!(zero.validate().is_err());
+}
+
+#[test]
+fn profile_context_limits_parse_and_validate() {
+    let profile: ProfileConfig = toml::from_str(
+        r#"
+[context_limits]
+keep_last_n_assistant_messages = 10
+context_budget_threshold = 0.6
+"#,
+    )
+    .unwrap();
+    let limits = profile.context_limits.unwrap();
+    assert_eq!(limits.keep_last_n_assistant_messages, Some(10));
+    assert_eq!(limits.context_budget_threshold, Some(0.6));
+    assert!(profile.validate().is_ok());
+
+    let invalid_threshold = ProfileConfig {
+        context_limits: Some(stakpak_api::ContextLimitsConfig {
+            context_budget_threshold: Some(0.0),
+            ..Default::default()
+        }),
+        ..ProfileConfig::default()
+    };
+    assert!(invalid_threshold.validate().is_err());
+}
+
+#[test]
+fn profile_loop_detection_parses_and_validates() {
+    let profile: ProfileConfig = toml::from_str(
+        r#"
+[loop_detection]
+max_repeats = 5
+"#,
+    )
+    .unwrap();
+    let loop_detection = profile.loop_detection.clone().unwrap();
+    assert!(loop_detection.enabled);
+    assert_eq!(loop_detection.max_repeats, 5);
+    assert!(profile.validate().is_ok());
+
+    let disabled: ProfileConfig = toml::from_str(
+        r#"
+[loop_detection]
+enabled = false
+"#,
+    )
+    .unwrap();
+    assert!(!disabled.loop_detection.unwrap().enabled);
+
+    let zero_repeats: ProfileConfig = toml::from_str(
+        r#"
+[loop_detection]
+max_repeats = 0
+"#,
+    )
+    .unwrap();
+    assert!(zero_repeats.validate().is_err());
+}
+
+#[test]
+fn profile_max_concurrent_requests_reach_provider_config() {
+    let profile = ProfileConfig {
+        max_concurrent_requests: HashMap::from([("anthropic".to_string(), 4)]),
+        ..ProfileConfig::default()
+    };
+    assert!(profile.validate().is_ok());
+
+    let mut config = sample_app_config("default");
+    config.max_concurrent_requests = profile.max_concurrent_requests;
+    assert_eq!(
+        config
+            .get_llm_provider_config()
+            .max_concurrent_requests
+            .get("anthropic"),
+        Some(&4)
+    );
+
+    let zero_limit = ProfileConfig {
+        max_concurrent_requests: HashMap::from([("anthropic".to_string(), 0)]),
+        ..ProfileConfig::default()
+    };
+    assert!(zero_limit.validate().is_err());
+}
+
 #[test]
 fn profile_serde_round_trip_new_fields() {
     let profile = ProfileConfig {
@@ -538,6 +729,7 @@ fn save_writes_profile_and_settings() {
         profile_name: "dev".into(),
         config_path: path.to_string_lossy().into_owned(),
         allowed_tools: Some(vec!["git".into(), "curl".into()]),
+        denied_tools: None,
         auto_approve: Some(vec!["git status".into()]),
         subagent: None,
         rulebooks: Some(RulebookConfig {
@@ -549,12 +741,24 @@ fn save_writes_profile_and_settings() {
         warden: Some(WardenConfig {
             enabled: true,
             volumes: vec!["/tmp:/tmp:ro".into()],
+            runtime: Default::default(),
         }),
         provider: ProviderType::Remote,
         providers: HashMap::new(),
         model: None,
         system_prompt: None,
         max_turns: None,
+        title_model: None,
+        context_limits: None,
+        loop_detection: None,
+        max_concurrent_requests: HashMap::new(),
+        search_api_url: None,
+        allow_unknown_models: false,
+        run_command_env: None,
+        max_file_size: None,
+        prompt_vars: HashMap::new(),
+        tool_output_redaction: HashMap::new(),
+        privacy_patterns: HashMap::new(),
         anonymous_id: Some("test-user-id".into()),
         collect_telemetry: Some(true),
         editor: Some("nano".into()),
@@ -1785,3 +1989,362 @@ api_key = "sk-litellm"
     let litellm = profile.providers.get("litellm").unwrap();
     assert_eq!(litellm.api_endpoint(), Some("http://localhost:4000/v1"));
 }
+
+fn test_env(name: &str) -> Option<String> {
+    match name {
+        "OPENAI_KEY" => Some("sk-from-env".to_string()),
+        "EMPTY_VAR" => Some(String::new()),
+        _ => None,
+    }
+}
+
+#[test]
+fn interpolate_env_resolves_references_and_defaults() {
+    use super::env_interpolation::interpolate_env;
+
+    assert_eq!(
+        interpolate_env("${OPENAI_KEY}", &test_env).as_deref(),
+        Ok("sk-from-env")
+    );
+    assert_eq!(
+        interpolate_env("Bearer ${ OPENAI_KEY }!", &test_env).as_deref(),
+        Ok("Bearer sk-from-env!")
+    );
+    assert_eq!(
+        interpolate_env("${MISSING_VAR:-http://localhost:4000/v1}", &test_env).as_deref(),
+        Ok("http://localhost:4000/v1")
+    );
+    assert_eq!(
+        interpolate_env("${EMPTY_VAR:-fallback}", &test_env).as_deref(),
+        Ok("fallback")
+    );
+    assert_eq!(
+        interpolate_env("$${OPENAI_KEY}", &test_env).as_deref(),
+        Ok("${OPENAI_KEY}")
+    );
+}
+
+#[test]
+fn interpolate_env_errors_on_missing_variable() {
+    use super::env_interpolation::interpolate_env;
+
+    let err = interpolate_env("${MISSING_VAR}", &test_env).unwrap_err();
+    assert!(err.contains("'MISSING_VAR' is not set"), "{err}");
+    assert!(interpolate_env("${OPENAI_KEY", &test_env).is_err());
+    assert!(interpolate_env("${not a name}", &test_env).is_err());
+}
+
+#[test]
+fn interpolate_profile_only_touches_credential_fields() {
+    use super::env_interpolation::{interpolate_profile, restore_env_references};
+
+    let raw: ProfileConfig = toml::from_str(
+        r#"
+api_key = "${OPENAI_KEY}"
+system_prompt = "Keep ${HOME} literal"
+
+[providers.openai]
+type = "openai"
+api_key = "${OPENAI_KEY}"
+api_endpoint = "${OPENAI_ENDPOINT:-https://api.openai.com/v1}"
+"#,
+    )
+    .unwrap();
+
+    let resolved = interpolate_profile(raw.clone(), &test_env).unwrap();
+    assert_eq!(resolved.api_key.as_deref(), Some("sk-from-env"));
+    assert_eq!(
+        resolved.system_prompt.as_deref(),
+        Some("Keep ${HOME} literal")
+    );
+    let openai = resolved.providers.get("openai").unwrap();
+    assert_eq!(openai.api_key(), Some("sk-from-env"));
+    assert_eq!(openai.api_endpoint(), Some("https://api.openai.com/v1"));
+
+    // Saving writes the references back instead of the resolved secrets
+    let mut changed = resolved.clone();
+    changed.api_key = Some("sk-typed-in".to_string());
+    let restored = restore_env_references(&raw, changed, &test_env);
+    assert_eq!(restored.api_key.as_deref(), Some("sk-typed-in"));
+    assert_eq!(
+        restored.providers.get("openai").unwrap().api_key(),
+        Some("${OPENAI_KEY}")
+    );
+
+    let err = interpolate_profile(raw, &|_: &str| None).unwrap_err();
+    assert!(err.contains("api_key"), "{err}");
+}
+
+#[test]
+fn provider_api_keys_default_weight_and_resolve_env_references() {
+    use super::env_interpolation::interpolate_profile;
+
+    let raw: ProfileConfig = toml::from_str(
+        r#"
+[providers.anthropic]
+type = "anthropic"
+api_keys = [
+    { key = "${OPENAI_KEY}", weight = 3 },
+    { key = "sk-ant-second" },
+]
+"#,
+    )
+    .unwrap();
+
+    let resolved = interpolate_profile(raw, &test_env).unwrap();
+    let api_keys = resolved.providers.get("anthropic").unwrap().api_keys();
+    assert_eq!(api_keys.len(), 2);
+    assert_eq!(api_keys[0].key, "sk-from-env");
+    assert_eq!(api_keys[0].weight, 3);
+    assert_eq!(api_keys[1].key, "sk-ant-second");
+    assert_eq!(api_keys[1].weight, 1);
+}
+
+#[test]
+fn api_keys_on_a_provider_without_key_pooling_fail_to_load() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::write(
+        &config_path,
+        r#"
+[settings]
+
+[profiles.default.providers.gemini]
+type = "gemini"
+api_keys = [{ key = "gm-first" }, { key = "gm-second" }]
+"#,
+    )
+    .unwrap();
+
+    let err = AppConfig::load("default", Some(&config_path))
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(
+        err.contains("profiles.default.providers.gemini.api_keys is only supported"),
+        "{err}"
+    );
+}
+
+#[test]
+fn load_resolves_env_defaults_and_reports_missing_variables() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("config.toml");
+
+    std::fs::write(
+        &config_path,
+        r#"
+[profiles.default]
+api_key = "${STAKPAK_TEST_UNSET_KEY_0451:-sk-default}"
+
+[profiles.missing]
+api_key = "${STAKPAK_TEST_UNSET_KEY_0451}"
+
+[settings]
+"#,
+    )
+    .unwrap();
+
+    let app = AppConfig::load("default", Some(&config_path)).expect("load app config");
+    if std::env::var("STAKPAK_API_KEY").is_err() {
+        assert_eq!(app.api_key.as_deref(), Some("sk-default"));
+    }
+
+    app.save().unwrap();
+    let saved = std::fs::read_to_string(&config_path).unwrap();
+    assert!(saved.contains("${STAKPAK_TEST_UNSET_KEY_0451:-sk-default}"));
+
+    let err = AppConfig::load("missing", Some(&config_path)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Profile 'missing': api_key: environment variable 'STAKPAK_TEST_UNSET_KEY_0451' is not set"),
+        "{err}"
+    );
+}
+
+#[derive(Default)]
+struct MockKeyring {
+    secrets: std::sync::Mutex<HashMap<String, String>>,
+}
+
+impl SecretStore for MockKeyring {
+    fn get(&self, profile_name: &str) -> Result<Option<String>, String> {
+        Ok(self.secrets.lock().unwrap().get(profile_name).cloned())
+    }
+
+    fn set(&self, profile_name: &str, secret: &str) -> Result<(), String> {
+        self.secrets
+            .lock()
+            .unwrap()
+            .insert(profile_name.to_string(), secret.to_string());
+        Ok(())
+    }
+
+    fn delete(&self, profile_name: &str) -> Result<(), String> {
+        self.secrets.lock().unwrap().remove(profile_name);
+        Ok(())
+    }
+}
+
+#[test]
+fn keyring_stores_and_resolves_api_keys() {
+    use secret_store::{KEYRING_SENTINEL, resolve_api_key, store_api_key};
+
+    let keyring = MockKeyring::default();
+    let store: Option<&dyn SecretStore> = Some(&keyring);
+    let stored = store_api_key("stkpk_api_123".to_string(), "dev", store).unwrap();
+    assert_eq!(stored, KEYRING_SENTINEL);
+    assert_eq!(
+        keyring.get("dev").unwrap().as_deref(),
+        Some("stkpk_api_123")
+    );
+
+    assert_eq!(
+        resolve_api_key(Some(stored.clone()), "dev", store).unwrap(),
+        Some("stkpk_api_123".to_string())
+    );
+    let err = resolve_api_key(Some(stored), "prod", store).unwrap_err();
+    assert!(err.contains("profile 'prod'"), "{err}");
+
+    // Without a secret store the key stays in the config file
+    assert_eq!(
+        store_api_key("stkpk_api_123".to_string(), "dev", None).unwrap(),
+        "stkpk_api_123"
+    );
+}
+
+#[test]
+fn keyring_sentinel_is_only_special_with_a_store() {
+    use secret_store::{KEYRING_SENTINEL, api_key_for_save, resolve_api_key};
+
+    let keyring = MockKeyring::default();
+    let store: Option<&dyn SecretStore> = Some(&keyring);
+    assert_eq!(
+        resolve_api_key(Some("sk-plain".to_string()), "dev", store).unwrap(),
+        Some("sk-plain".to_string())
+    );
+    assert!(resolve_api_key(Some(KEYRING_SENTINEL.to_string()), "dev", None).is_err());
+
+    // A keychain-backed profile keeps the sentinel on disk and updates the keychain
+    let saved = api_key_for_save(
+        Some(KEYRING_SENTINEL),
+        Some("stkpk_api_new".to_string()),
+        "dev",
+        store,
+    )
+    .unwrap();
+    assert_eq!(saved.as_deref(), Some(KEYRING_SENTINEL));
+    assert_eq!(
+        keyring.get("dev").unwrap().as_deref(),
+        Some("stkpk_api_new")
+    );
+
+    let saved = api_key_for_save(Some(KEYRING_SENTINEL), None, "dev", store).unwrap();
+    assert_eq!(saved, None);
+    assert_eq!(keyring.get("dev").unwrap(), None);
+
+    // Plaintext profiles are saved as-is
+    let saved = api_key_for_save(Some("sk-old"), Some("sk-new".to_string()), "dev", store).unwrap();
+    assert_eq!(saved.as_deref(), Some("sk-new"));
+}
+
+#[test]
+fn try_get_default_model_accepts_catalog_ids() {
+    let config = sample_app_config("default");
+
+    let model = config
+        .try_get_default_model(Some("claude-sonnet-4-5"))
+        .unwrap();
+    // Routed through Stakpak as "anthropic/claude-sonnet-4-5" with the sample API key
+    assert!(model.id.ends_with("claude-sonnet-4-5"), "{}", model.id);
+}
+
+fn fixture_catalog() -> Vec<stakpak_api::Model> {
+    ["claude-sonnet-4-5", "claude-opus-4-6"]
+        .iter()
+        .map(|id| stakpak_api::Model::custom(id.to_string(), "anthropic"))
+        .collect()
+}
+
+#[test]
+fn try_get_default_model_rejects_typos_in_the_catalog() {
+    let config = AppConfig {
+        api_key: None,
+        ..sample_app_config("default")
+    };
+
+    let error = config
+        .try_get_default_model_in(&fixture_catalog(), Some("claude-4.5-sonet"))
+        .unwrap_err();
+    assert!(
+        error.contains("Unknown model 'claude-4.5-sonet', did you mean 'claude-sonnet-4-5'?"),
+        "{error}"
+    );
+    assert!(error.contains("allow_unknown_models"), "{error}");
+
+    let model = config
+        .try_get_default_model_in(&fixture_catalog(), Some("claude-sonnet-4-5"))
+        .unwrap();
+    assert_eq!(model.id, "claude-sonnet-4-5");
+
+    let lenient = AppConfig {
+        api_key: None,
+        allow_unknown_models: true,
+        ..sample_app_config("default")
+    };
+    let model = lenient
+        .try_get_default_model_in(&fixture_catalog(), Some("claude-4.5-sonet"))
+        .unwrap();
+    assert_eq!(model.id, "claude-4.5-sonet");
+}
+
+#[test]
+fn try_get_default_model_takes_any_id_without_a_catalog() {
+    let config = AppConfig {
+        api_key: None,
+        ..sample_app_config("default")
+    };
+
+    // Nothing to check against, so the id is taken as given
+    let model = config
+        .try_get_default_model_in(&[], Some("claude-4.5-sonet"))
+        .unwrap();
+    assert_eq!(model.id, "claude-4.5-sonet");
+}
+
+#[test]
+fn try_get_default_model_lets_stakpak_resolve_unknown_ids() {
+    // The sample config has a Stakpak key, which can route any model name
+    let config = sample_app_config("default");
+
+    let model = config
+        .try_get_default_model(Some("claude-4.5-sonet"))
+        .unwrap();
+    assert_eq!(model.provider, "stakpak");
+    assert_eq!(model.id, "claude-4.5-sonet");
+}
+
+#[test]
+fn try_get_default_model_passes_bedrock_native_ids_through() {
+    let config = AppConfig {
+        api_key: None,
+        ..sample_app_config("default")
+    };
+
+    let model = config
+        .try_get_default_model(Some("amazon-bedrock/us.anthropic.claude-sonnet-4-5-v1:0"))
+        .unwrap();
+    assert_eq!(model.provider, "amazon-bedrock");
+    assert_eq!(model.id, "us.anthropic.claude-sonnet-4-5-v1:0");
+}
+
+#[test]
+fn try_get_default_model_passes_custom_provider_ids_through() {
+    let config = sample_app_config("default");
+
+    let model = config
+        .try_get_default_model(Some("litellm/my-finetune"))
+        .unwrap();
+    assert_eq!(model.provider, "litellm");
+    assert_eq!(model.id, "my-finetune");
+}
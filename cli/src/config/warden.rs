@@ -3,6 +3,7 @@
 use serde::{Deserialize, Serialize};
 
 // Re-export from the shared crate — single source of truth for container layout.
+pub use stakpak_shared::container::ContainerRuntimeSetting;
 pub use stakpak_shared::container::stakpak_agent_default_mounts;
 
 /// Configuration for the Warden runtime security system.
@@ -13,6 +14,9 @@ pub struct WardenConfig {
     /// Volume mounts for the warden container
     #[serde(default)]
     pub volumes: Vec<String>,
+    /// Container runtime warden drives: "docker", "podman", or "auto" (default)
+    #[serde(default, skip_serializing_if = "ContainerRuntimeSetting::is_auto")]
+    pub runtime: ContainerRuntimeSetting,
 }
 
 impl WardenConfig {
@@ -21,6 +25,7 @@ impl WardenConfig {
         WardenConfig {
             enabled: true,
             volumes: stakpak_agent_default_mounts(),
+            runtime: ContainerRuntimeSetting::default(),
         }
     }
 }
@@ -26,6 +26,8 @@ use stakpak_api::local::skills::{default_skill_directories, discover_skills};
 use stakpak_api::models::Skill;
 use stakpak_api::{AgentClient, AgentClientConfig, AgentProvider};
 use stakpak_mcp_server::EnabledToolsConfig;
+use stakpak_shared::paths::stakpak_home_dir;
+use stakpak_shared::run_id::run_id;
 use std::{
     env,
     ffi::OsString,
@@ -46,8 +48,8 @@ use commands::{
     agent::{
         self,
         run::{
-            AsyncOutcome, OutputFormat, ResumeInput, RunAsyncConfig, RunInteractiveConfig,
-            pause::EXIT_CODE_PAUSED,
+            AsyncOutcome, HeadlessConfig, OutputFormat, ResumeInput, RunAsyncConfig,
+            RunInteractiveConfig, pause::EXIT_CODE_MAX_STEPS,
         },
     },
 };
@@ -59,6 +61,7 @@ use utils::apps_md::discover_apps_md;
 use utils::check_update::check_update;
 use utils::gitignore;
 use utils::local_context::analyze_local_context;
+use utils::prompt_template::{merge_prompt_vars, parse_prompt_var, render_prompt_template};
 
 use crate::onboarding::{OnboardingMode, run_onboarding};
 
@@ -74,7 +77,12 @@ fn config_has_any_auth_flags(has_stakpak_key: bool, has_provider_keys: bool) ->
 }
 
 fn should_spawn_auto_update(cli: &Cli, skip_warden: bool) -> bool {
-    cli.command.is_none() && !cli.r#async && !cli.print && !skip_warden
+    cli.command.is_none()
+        && !cli.r#async
+        && !cli.print
+        && !cli.dry_run
+        && !cli.headless
+        && !skip_warden
 }
 
 fn background_auto_update_args(cli: &Cli) -> Vec<OsString> {
@@ -121,6 +129,10 @@ struct Cli {
     #[arg(short = 'a', long = "async", default_value_t = false)]
     r#async: bool,
 
+    /// Run interactive mode without a TTY, resolving approvals from the profile's auto_approve list
+    #[arg(long = "headless", default_value_t = false, conflicts_with_all = ["print", "async", "dry_run"])]
+    headless: bool,
+
     /// Maximum number of steps the agent can take (default: 50 for --async, 1 for --print/--approve)
     #[arg(short = 'm', long = "max-steps")]
     max_steps: Option<usize>,
@@ -157,6 +169,11 @@ struct Cli {
     #[arg(long = "privacy-mode", default_value_t = false)]
     privacy_mode: bool,
 
+    /// Append <checkpoint_id> markers to resumed assistant messages, for tools
+    /// that still read the checkpoint id from message content
+    #[arg(long = "checkpoint-markers", default_value_t = false)]
+    checkpoint_markers: bool,
+
     /// Enable study mode to use the agent as a study assistant
     #[arg(long = "study-mode", default_value_t = false)]
     study_mode: bool,
@@ -177,6 +194,10 @@ struct Cli {
     #[arg(long = "plan-new", default_value_t = false)]
     plan_new: bool,
 
+    /// Run without executing mutating tools; print the tool calls the agent would make (implies --async)
+    #[arg(long = "dry-run", default_value_t = false)]
+    dry_run: bool,
+
     /// Allow indexing of large projects (more than 500 supported files)
     #[arg(long = "index-big-project", default_value_t = false)]
     index_big_project: bool,
@@ -233,6 +254,10 @@ struct Cli {
     #[arg(short = 't', long = "tool", action = clap::ArgAction::Append)]
     allowed_tools: Option<Vec<String>>,
 
+    /// Remove the specified tool from the agent's context, even if allowed by --tool
+    #[arg(long = "deny-tool", action = clap::ArgAction::Append)]
+    denied_tools: Option<Vec<String>>,
+
     /// Read system prompt from file
     #[arg(long = "system-prompt-file")]
     system_prompt_file: Option<String>,
@@ -241,6 +266,14 @@ struct Cli {
     #[arg(long = "prompt-file")]
     prompt_file: Option<String>,
 
+    /// Set a prompt variable substituted into `{{key}}` placeholders (repeatable)
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_prompt_var, action = clap::ArgAction::Append)]
+    prompt_vars: Vec<(String, String)>,
+
+    /// Fail when a prompt references an undefined `{{variable}}`
+    #[arg(long = "strict-vars", default_value_t = false)]
+    strict_vars: bool,
+
     /// Configuration profile to use (can also be set with STAKPAK_PROFILE env var)
     #[arg(long = "profile")]
     profile: Option<String>,
@@ -286,14 +319,22 @@ async fn main() {
         }
     }
 
-    if cli.debug {
+    // --debug also logs which rule produced each redaction; STAKPAK_REDACTION_DEBUG
+    // logs only that
+    if cli.debug || stakpak_shared::secrets::redaction_debug_enabled() {
+        let mut default_filter = "error,stakpak_shared=debug".to_string();
+        if cli.debug {
+            default_filter.push_str(&format!(",{}=debug", env!("CARGO_CRATE_NAME")));
+        }
         tracing_subscriber::registry()
             .with(
                 tracing_subscriber::EnvFilter::try_from_default_env()
-                    .unwrap_or_else(|_| format!("error,{}=debug", env!("CARGO_CRATE_NAME")).into()),
+                    .unwrap_or_else(|_| default_filter.into()),
             )
             .with(tracing_subscriber::fmt::layer())
             .init();
+        tracing::debug!(run_id = %run_id(), "Starting run");
+        stakpak_shared::secrets::set_redaction_debug(true);
     }
 
     // Determine which profile to use: CLI arg > STAKPAK_PROFILE env var > "default"
@@ -313,6 +354,21 @@ async fn main() {
 
     match config_result {
         Ok(mut config) => {
+            // Custom privacy patterns apply to every redaction in this process
+            if let Err(e) = config.register_privacy_patterns() {
+                eprintln!("Failed to load privacy patterns: {}", e);
+                std::process::exit(1);
+            }
+
+            // --deny-tool adds to the profile's denied_tools, which every mode
+            // (agent, ACP, autopilot) enforces from the config
+            if let Some(denied) = &cli.denied_tools {
+                config
+                    .denied_tools
+                    .get_or_insert_with(Vec::new)
+                    .extend(denied.iter().cloned());
+            }
+
             // Check if warden is enabled in profile and we're not already inside warden
             let should_use_warden = config.warden.as_ref().map(|w| w.enabled).unwrap_or(false)
                 && std::env::var("STAKPAK_SKIP_WARDEN").is_err()
@@ -350,7 +406,7 @@ async fn main() {
                 // Initialize theme detection early, before any color code runs (e.g. onboarding).
                 // This ensures --theme flag takes effect for CLI colors too.
                 // In async mode, skip terminal detection (no TTY) — default to Dark.
-                let theme_override = if cli.r#async || cli.print {
+                let theme_override = if cli.r#async || cli.print || cli.dry_run || cli.headless {
                     Some(stakpak_shared::terminal_theme::Theme::Dark)
                 } else {
                     match cli.theme.to_lowercase().as_str() {
@@ -404,6 +460,18 @@ async fn main() {
                 // Create unified AgentClient - automatically routes through Stakpak when API key is present
                 let mut client_config = AgentClientConfig::new().with_providers(providers);
 
+                if let Some(title_model) = config.get_title_model() {
+                    client_config = client_config.with_title_model(title_model);
+                }
+
+                if let Some(context_limits) = config.context_limits {
+                    client_config = client_config.with_context_limits(context_limits);
+                }
+
+                if let Some(search_api_url) = config.search_api_url.clone() {
+                    client_config = client_config.with_search_api_url(search_api_url);
+                }
+
                 if let Some(api_key) = config.get_stakpak_api_key() {
                     client_config = client_config.with_stakpak(
                         stakpak_api::StakpakConfig::new(api_key)
@@ -545,8 +613,21 @@ async fn main() {
                     cli.prompt.unwrap_or_default()
                 };
 
+                // Substitute {{var}} placeholders; --var flags override profile prompt_vars
+                let prompt_vars = merge_prompt_vars(&config.prompt_vars, &cli.prompt_vars);
+                let render = |template: &str| {
+                    render_prompt_template(template, &prompt_vars, cli.strict_vars).unwrap_or_else(
+                        |e| {
+                            eprintln!("{}", e);
+                            std::process::exit(1);
+                        },
+                    )
+                };
+                let system_prompt = system_prompt.map(|system_prompt| render(&system_prompt));
+                let prompt = render(&prompt);
+
                 // When using --prompt-file, force async mode only
-                let use_async_mode = cli.r#async || cli.print;
+                let use_async_mode = cli.r#async || cli.print || cli.dry_run;
 
                 // Determine max_steps: 1 for single-step mode (--print/--approve), user setting or default for --async
                 let max_steps = if cli.print {
@@ -560,7 +641,12 @@ async fn main() {
 
                 let allowed_tools = cli.allowed_tools.or_else(|| config.allowed_tools.clone());
                 let auto_approve = config.auto_approve.clone();
-                let default_model = config.get_default_model(cli.model.as_deref());
+                let default_model = config
+                    .try_get_default_model(cli.model.as_deref())
+                    .unwrap_or_else(|e| {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    });
                 let checkpoint_id = cli.checkpoint_id.clone();
                 let session_id = cli.session_id.clone();
 
@@ -593,6 +679,7 @@ async fn main() {
                                 plan_new: cli.plan_new,
                                 pause_on_approval: cli.pause_on_approval,
                                 show_session_stats: cli.show_session_stats,
+                                dry_run: cli.dry_run,
                                 resume_input: if cli.approve.is_some()
                                     || cli.reject.is_some()
                                     || cli.approve_all
@@ -623,12 +710,17 @@ async fn main() {
 
                         // Handle AsyncOutcome → exit code
                         match async_result {
-                            Ok(AsyncOutcome::Paused { .. }) => {
-                                cache_task.abort();
-                                std::process::exit(EXIT_CODE_PAUSED);
-                            }
-                            Ok(AsyncOutcome::Completed { .. }) => Ok(()),
                             Ok(AsyncOutcome::Failed { error }) => Err(error),
+                            Ok(outcome) => match outcome.exit_code() {
+                                0 => Ok(()),
+                                // --print is single-step by design, so its step
+                                // limit is the expected way to stop
+                                EXIT_CODE_MAX_STEPS if cli.print => Ok(()),
+                                code => {
+                                    cache_task.abort();
+                                    std::process::exit(code);
+                                }
+                            },
                             Err(e) => Err(e),
                         }
                     }
@@ -641,6 +733,9 @@ async fn main() {
                             "dark" => Some(stakpak_tui::services::detect_term::Theme::Dark),
                             _ => None, // "auto" or anything else = auto-detect
                         };
+                        let headless = cli
+                            .headless
+                            .then(|| HeadlessConfig::new(vec![prompt], auto_approve.as_ref()));
 
                         agent::run::run_interactive(
                             config,
@@ -664,6 +759,14 @@ async fn main() {
                                 model: default_model,
                                 send_init_prompt_on_start,
                                 theme,
+                                headless,
+                                prompt_var_overrides: cli.prompt_vars,
+                                transcript_log: cli.debug.then(|| {
+                                    stakpak_tui::services::transcript_log::new_session_path(
+                                        &stakpak_home_dir().join("logs"),
+                                    )
+                                }),
+                                checkpoint_markers: cli.checkpoint_markers,
                             },
                         )
                         .await
@@ -675,6 +778,10 @@ async fn main() {
 
                 if let Err(e) = result {
                     eprintln!("Ops! something went wrong: {}", e);
+                    eprintln!(
+                        "Run ID: {} (include it when reporting this issue)",
+                        run_id()
+                    );
                     std::process::exit(1);
                 }
             } else if let Some(command) = cli.command {
@@ -777,6 +884,23 @@ mod tests {
         assert!(should_spawn_auto_update(&cli, false));
     }
 
+    #[test]
+    fn auto_update_gate_is_false_for_headless_startup() {
+        let cli = Cli::try_parse_from(["stakpak", "--headless", "hello"]).expect("parse cli");
+        assert!(!should_spawn_auto_update(&cli, false));
+    }
+
+    #[test]
+    fn auto_update_gate_is_false_for_dry_run() {
+        let cli = Cli::try_parse_from(["stakpak", "--dry-run", "hello"]).expect("parse cli");
+        assert!(!should_spawn_auto_update(&cli, false));
+    }
+
+    #[test]
+    fn headless_conflicts_with_dry_run() {
+        assert!(Cli::try_parse_from(["stakpak", "--headless", "--dry-run", "hello"]).is_err());
+    }
+
     #[cfg(unix)]
     #[test]
     fn agent_dockerfile_precreates_persistent_storage_mount_parents() {
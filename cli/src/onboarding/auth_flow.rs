@@ -1,3 +1,4 @@
+use crate::config::secret_store::{default_secret_store, resolve_api_key};
 use crate::config::{AppConfig, ProfileConfig, ProviderType};
 use crate::onboarding::byom::configure_byom;
 use crate::onboarding::config_templates::{
@@ -103,8 +104,16 @@ pub fn apply_auth_flow_result(config: &mut AppConfig, result: &AuthFlowResult) {
     config.provider = result.profile.provider.unwrap_or(ProviderType::Local);
     config.providers = result.profile.providers.clone();
     config.model = result.profile.model.clone();
+    // A profile keeping its key in the OS keychain only stores the sentinel
     if let Some(api_key) = &result.profile.api_key {
-        config.api_key = Some(api_key.clone());
+        match resolve_api_key(
+            Some(api_key.clone()),
+            &config.profile_name,
+            default_secret_store().as_deref(),
+        ) {
+            Ok(api_key) => config.api_key = api_key,
+            Err(error) => styled_output::render_warning(&error),
+        }
     }
     config.anonymous_id = result.telemetry.anonymous_id.clone();
     config.collect_telemetry = result.telemetry.collect_telemetry;
@@ -73,6 +73,7 @@ pub fn generate_openai_profile() -> ProfileConfig {
             api_key: None,
             api_endpoint: None,
             auth: None,
+            api_keys: Vec::new(),
         },
     );
     profile
@@ -127,6 +128,7 @@ pub fn generate_anthropic_profile() -> ProfileConfig {
             api_endpoint: None,
             access_token: None,
             auth: None,
+            api_keys: Vec::new(),
         },
     );
     profile
@@ -256,6 +258,7 @@ pub fn generate_multi_provider_profile(
                         api_key: Some(setup.api_key),
                         api_endpoint: None,
                         auth: None,
+                        api_keys: Vec::new(),
                     },
                 );
             }
@@ -277,6 +280,7 @@ pub fn generate_multi_provider_profile(
                         api_endpoint: None,
                         access_token: None,
                         auth: None,
+                        api_keys: Vec::new(),
                     },
                 );
             }
@@ -204,12 +204,30 @@ async fn handle_stakpak_api_for_new_profile(config: &AppConfig, profile_name: &s
     // Now save the new profile with the API key and endpoint
     let config_path = get_config_path_string(config);
 
+    // Keep the key in the OS keychain when this build has one
+    use crate::config::secret_store::{default_secret_store, store_api_key};
+    let api_key = match temp_config
+        .api_key
+        .clone()
+        .map(|key| store_api_key(key, profile_name, default_secret_store().as_deref()))
+        .transpose()
+    {
+        Ok(api_key) => api_key,
+        Err(e) => {
+            crate::onboarding::styled_output::render_error(&format!(
+                "Failed to store API key: {}",
+                e
+            ));
+            std::process::exit(1);
+        }
+    };
+
     // Create profile config with Remote provider, new API key, and same endpoint
     use crate::config::ProfileConfig;
     use crate::config::ProviderType;
     let new_profile = ProfileConfig {
         provider: Some(ProviderType::Remote),
-        api_key: temp_config.api_key.clone(),
+        api_key,
         api_endpoint: Some(config.api_endpoint.clone()), // Copy endpoint from default
         ..ProfileConfig::default()
     };
@@ -8,4 +8,5 @@ pub mod gitignore;
 pub mod local_context;
 pub mod network;
 pub mod plugins;
+pub mod prompt_template;
 pub mod server_context;
@@ -0,0 +1,174 @@
+//! `{{name}}` variable substitution for system and user prompts.
+//!
+//! Variables come from `[profiles.<name>.prompt_vars]` and `--var key=value`
+//! flags, and apply to every user message of a session, not just the first.
+//! Placeholders without a matching variable are left as-is unless strict mode
+//! is on, and `\{{` renders a literal `{{`. Braces around anything that is not
+//! a plain variable name (e.g. `${{ secrets.TOKEN }}`) are never treated as
+//! placeholders.
+
+use std::collections::HashMap;
+
+/// Substitute `{{name}}` placeholders in `template` with values from `vars`.
+///
+/// With `strict`, any placeholder without a value is an error listing every
+/// undefined name.
+pub fn render_prompt_template(
+    template: &str,
+    vars: &HashMap<String, String>,
+    strict: bool,
+) -> Result<String, String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut missing: Vec<String> = Vec::new();
+    let mut rest = template;
+
+    while let Some((before, after)) = rest.split_once("{{") {
+        if let Some(before) = before.strip_suffix('\\') {
+            rendered.push_str(before);
+            rendered.push_str("{{");
+            rest = after;
+            continue;
+        }
+        rendered.push_str(before);
+
+        let Some((inner, tail)) = after.split_once("}}") else {
+            rendered.push_str("{{");
+            rest = after;
+            break;
+        };
+        if inner.contains("{{") {
+            // Unclosed braces; a later placeholder may still be valid
+            rendered.push_str("{{");
+            rest = after;
+            continue;
+        }
+        rest = tail;
+
+        let name = inner.trim();
+        if is_prompt_var_name(name) {
+            if let Some(value) = vars.get(name) {
+                rendered.push_str(value);
+                continue;
+            }
+            if strict && !missing.iter().any(|missing| missing == name) {
+                missing.push(name.to_string());
+            }
+        }
+        rendered.push_str("{{");
+        rendered.push_str(inner);
+        rendered.push_str("}}");
+    }
+    rendered.push_str(rest);
+
+    if !missing.is_empty() {
+        return Err(format!(
+            "Undefined prompt variable(s): {}. Pass them with --var key=value or set them in [profiles.<name>.prompt_vars]",
+            missing.join(", ")
+        ));
+    }
+
+    Ok(rendered)
+}
+
+/// A profile's `prompt_vars` overlaid with `--var` flags, which win.
+pub fn merge_prompt_vars(
+    profile_vars: &HashMap<String, String>,
+    overrides: &[(String, String)],
+) -> HashMap<String, String> {
+    let mut vars = profile_vars.clone();
+    vars.extend(overrides.iter().cloned());
+    vars
+}
+
+/// Parse a `--var key=value` flag.
+pub fn parse_prompt_var(raw: &str) -> Result<(String, String), String> {
+    let (key, value) = raw
+        .split_once('=')
+        .ok_or_else(|| format!("expected key=value, got '{}'", raw))?;
+    let key = key.trim();
+    if !is_prompt_var_name(key) {
+        return Err(format!(
+            "invalid variable name '{}': use letters, digits, '_' or '-'",
+            key
+        ));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+fn is_prompt_var_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn substitutes_known_variables() {
+        let rendered = render_prompt_template(
+            "Deploy {{repo}} to {{ environment }}, then check {{repo}} again.",
+            &vars(&[("repo", "stakpak/agent"), ("environment", "staging")]),
+            false,
+        );
+        assert_eq!(
+            rendered.as_deref(),
+            Ok("Deploy stakpak/agent to staging, then check stakpak/agent again.")
+        );
+    }
+
+    #[test]
+    fn unknown_variables_are_literal_unless_strict() {
+        let template = "Deploy {{repo}} to {{env}} and {{region}}";
+        let vars = vars(&[("repo", "api")]);
+
+        assert_eq!(
+            render_prompt_template(template, &vars, false).as_deref(),
+            Ok("Deploy api to {{env}} and {{region}}")
+        );
+
+        let err = render_prompt_template(template, &vars, true).unwrap_err();
+        assert!(err.starts_with("Undefined prompt variable(s): env, region."));
+    }
+
+    #[test]
+    fn escaped_and_non_variable_braces_are_kept() {
+        let rendered = render_prompt_template(
+            r"Use \{{repo}} literally, keep ${{ secrets.TOKEN }} and {{ unclosed, but {{repo}}",
+            &vars(&[("repo", "api")]),
+            true,
+        );
+        assert_eq!(
+            rendered.as_deref(),
+            Ok("Use {{repo}} literally, keep ${{ secrets.TOKEN }} and {{ unclosed, but api")
+        );
+    }
+
+    #[test]
+    fn flags_override_profile_vars() {
+        let merged = merge_prompt_vars(
+            &vars(&[("repo", "api"), ("env", "staging")]),
+            &[("env".to_string(), "prod".to_string())],
+        );
+        assert_eq!(merged, vars(&[("repo", "api"), ("env", "prod")]));
+    }
+
+    #[test]
+    fn parses_var_flags() {
+        assert_eq!(
+            parse_prompt_var("env=prod=eu"),
+            Ok(("env".to_string(), "prod=eu".to_string()))
+        );
+        assert!(parse_prompt_var("env").is_err());
+        assert!(parse_prompt_var("my var=x").is_err());
+    }
+}
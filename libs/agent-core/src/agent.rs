@@ -1,11 +1,13 @@
 use crate::{
     approval::ApprovalStateMachine,
-    compaction::CompactionEngine,
+    budget_context::BudgetAwareContextReducer,
+    compaction::{CompactionEngine, CompactionResult},
     context::ContextReducer,
     error::AgentError,
     hooks::AgentHook,
     retry::exponential_backoff_ms,
-    tools::{ToolExecutionResult, ToolExecutor},
+    schema::validate_tool_arguments,
+    tools::{ToolErrorKind, ToolExecutionResult, ToolExecutor},
     types::{
         AgentCommand, AgentConfig, AgentEvent, AgentLoopResult, AgentRunContext, ProposedToolCall,
         StopReason, ToolDecision, TurnFinishReason,
@@ -20,8 +22,33 @@ use tokio_util::sync::CancellationToken;
 #[derive(Default)]
 struct RuntimeQueues {
     steering: VecDeque<String>,
+    injected: VecDeque<Message>,
     follow_up: VecDeque<String>,
     pending_tool_decisions: HashMap<String, ToolDecision>,
+    paused: bool,
+}
+
+/// Counts how many turns in a row proposed the same tool calls.
+#[derive(Default)]
+struct RepeatedToolCalls {
+    last: Vec<(String, serde_json::Value)>,
+    repeats: usize,
+}
+
+impl RepeatedToolCalls {
+    fn observe(&mut self, tool_calls: &[ProposedToolCall]) -> usize {
+        let signature: Vec<(String, serde_json::Value)> = tool_calls
+            .iter()
+            .map(|tool_call| (tool_call.name.clone(), tool_call.arguments.clone()))
+            .collect();
+        if signature == self.last {
+            self.repeats += 1;
+        } else {
+            self.last = signature;
+            self.repeats = 1;
+        }
+        self.repeats
+    }
 }
 
 enum ToolCycleOutcome {
@@ -62,6 +89,7 @@ pub async fn run_agent(
     let mut queues = RuntimeQueues::default();
     let mut total_usage = stakai::Usage::default();
     let mut total_turns = 0usize;
+    let mut repeated_tool_calls = RepeatedToolCalls::default();
 
     'run_loop: loop {
         drain_runtime_commands_nonblocking(
@@ -93,11 +121,29 @@ pub async fn run_agent(
             });
         }
 
+        if queues.paused {
+            wait_while_paused(
+                &run,
+                hooks,
+                &event_tx,
+                &mut command_rx,
+                &cancel,
+                &mut queues,
+                &mut current_model,
+                &messages,
+                total_turns,
+            )
+            .await?;
+            // Commands that arrived while paused may include a cancel.
+            continue;
+        }
+
         while let Some(steering) = queues.steering.pop_front() {
             if !steering.is_empty() {
                 messages.push(Message::new(Role::User, steering));
             }
         }
+        messages.extend(queues.injected.drain(..));
 
         if total_turns >= config.max_turns {
             emit(
@@ -132,6 +178,27 @@ pub async fn run_agent(
         )
         .await;
 
+        if config.compaction.enabled
+            && let Some(trigger_tokens) = config.compaction.trigger_tokens_for(&current_model)
+        {
+            let estimated_tokens = BudgetAwareContextReducer::estimate_tokens(&messages)
+                + BudgetAwareContextReducer::estimate_tool_overhead(&config.tools);
+            if estimated_tokens >= trigger_tokens {
+                messages = compact_messages(
+                    &run,
+                    messages,
+                    &current_model,
+                    format!(
+                        "estimated context of {estimated_tokens} tokens reached the compaction trigger of {trigger_tokens}"
+                    ),
+                    compactor,
+                    hooks,
+                    &event_tx,
+                )
+                .await?;
+            }
+        }
+
         let mut attempt = 0usize;
         let response = loop {
             if cancel.is_cancelled() {
@@ -158,12 +225,29 @@ pub async fn run_agent(
 
             let reduced_messages = context_reducer.reduce(
                 messages.clone(),
-                &current_model,
+                &config.compaction.sized_model(&current_model),
                 config.max_output_tokens,
                 &config.tools,
                 context_metadata,
             );
 
+            // The reducer trims a copy of the conversation for every request,
+            // so hooks hear about every request that is sent trimmed.
+            let tokens_before = BudgetAwareContextReducer::estimate_tokens(&messages);
+            let tokens_after = BudgetAwareContextReducer::estimate_tokens(&reduced_messages);
+            if tokens_after < tokens_before {
+                let result = CompactionResult {
+                    messages: reduced_messages.clone(),
+                    tokens_before: tokens_before as usize,
+                    tokens_after: tokens_after as usize,
+                    truncated: true,
+                };
+                for hook in hooks {
+                    hook.on_compaction(&run, &messages, &reduced_messages, &result)
+                        .await?;
+                }
+            }
+
             for hook in hooks {
                 hook.before_inference(&run, &reduced_messages, &current_model)
                     .await?;
@@ -191,34 +275,24 @@ pub async fn run_agent(
                     attempt += 1;
 
                     if config.compaction.enabled && is_context_overflow_error(&reason) {
-                        emit(
-                            &event_tx,
-                            AgentEvent::CompactionStarted {
-                                run_id: run.run_id,
-                                reason: reason.clone(),
-                            },
-                        )
-                        .await;
-
-                        let compacted = compactor.compact(messages.clone(), &current_model).await?;
-                        messages = compacted.messages;
-
-                        emit(
+                        messages = compact_messages(
+                            &run,
+                            messages,
+                            &current_model,
+                            reason,
+                            compactor,
+                            hooks,
                             &event_tx,
-                            AgentEvent::CompactionCompleted {
-                                run_id: run.run_id,
-                                tokens_before: compacted.tokens_before,
-                                tokens_after: compacted.tokens_after,
-                                truncated: compacted.truncated,
-                            },
                         )
-                        .await;
+                        .await?;
 
                         total_turns = total_turns.saturating_sub(1);
                         continue 'run_loop;
                     }
 
-                    if attempt < config.retry.max_attempts {
+                    if attempt < config.retry.max_attempts && !run.retry_budget.try_spend() {
+                        emit_retry_budget_exhausted(&run, &event_tx, reason.clone()).await;
+                    } else if attempt < config.retry.max_attempts {
                         let delay_ms = exponential_backoff_ms(&config.retry, attempt);
                         emit(
                             &event_tx,
@@ -364,6 +438,43 @@ pub async fn run_agent(
             )
             .await;
 
+            let repeats = repeated_tool_calls.observe(&proposed_tool_calls);
+            let loop_detection = &config.loop_detection;
+            if loop_detection.enabled && repeats > loop_detection.max_repeats {
+                append_skipped_due_to_loop(&run, &event_tx, &mut messages, &proposed_tool_calls)
+                    .await;
+
+                emit(
+                    &event_tx,
+                    AgentEvent::TurnCompleted {
+                        run_id: run.run_id,
+                        turn: total_turns,
+                        finish_reason: TurnFinishReason::ToolCalls,
+                    },
+                )
+                .await;
+
+                emit(
+                    &event_tx,
+                    AgentEvent::RunCompleted {
+                        run_id: run.run_id,
+                        total_turns,
+                        total_usage: total_usage.clone(),
+                        stop_reason: StopReason::LoopDetected,
+                    },
+                )
+                .await;
+
+                return Ok(AgentLoopResult {
+                    run_id: run.run_id,
+                    total_turns,
+                    total_usage,
+                    stop_reason: StopReason::LoopDetected,
+                    messages,
+                    metadata: context_metadata.clone(),
+                });
+            }
+
             let tool_outcome = run_tool_cycle(
                 &run,
                 config,
@@ -412,6 +523,21 @@ pub async fn run_agent(
                     });
                 }
                 ToolCycleOutcome::Completed => {
+                    if loop_detection.enabled && repeats == loop_detection.max_repeats {
+                        // A user turn rather than a system message: some
+                        // providers hoist system messages out of the
+                        // conversation, which would lose where the note goes.
+                        messages.push(Message::new(
+                            Role::User,
+                            format!(
+                                "System note: you have made the same tool calls with identical \
+                                 arguments {repeats} turns in a row and they keep returning the \
+                                 same results. Do not repeat them again; try a different approach \
+                                 or explain what is blocking you."
+                            ),
+                        ));
+                    }
+
                     emit(
                         &event_tx,
                         AgentEvent::TurnCompleted {
@@ -439,6 +565,18 @@ pub async fn run_agent(
         )
         .await;
 
+        // Messages injected while the model was answering get a turn of
+        // their own instead of being dropped with the finished run.
+        drain_runtime_commands_nonblocking(
+            &mut command_rx,
+            &mut queues,
+            &mut current_model,
+            &cancel,
+        );
+        if !queues.injected.is_empty() {
+            continue;
+        }
+
         if let Some(follow_up) = queues.follow_up.pop_front()
             && !follow_up.is_empty()
         {
@@ -470,6 +608,48 @@ pub async fn run_agent(
     }
 }
 
+/// Run the compaction engine over `messages`, reporting it through events and
+/// hooks, and return the compacted conversation.
+async fn compact_messages(
+    run: &AgentRunContext,
+    messages: Vec<Message>,
+    model: &stakai::Model,
+    reason: String,
+    compactor: &dyn CompactionEngine,
+    hooks: &[Box<dyn AgentHook>],
+    event_tx: &mpsc::Sender<AgentEvent>,
+) -> Result<Vec<Message>, AgentError> {
+    emit(
+        event_tx,
+        AgentEvent::CompactionStarted {
+            run_id: run.run_id,
+            reason,
+        },
+    )
+    .await;
+
+    let before = messages.clone();
+    let compacted = compactor.compact(messages, model).await?;
+
+    for hook in hooks {
+        hook.on_compaction(run, &before, &compacted.messages, &compacted)
+            .await?;
+    }
+
+    emit(
+        event_tx,
+        AgentEvent::CompactionCompleted {
+            run_id: run.run_id,
+            tokens_before: compacted.tokens_before,
+            tokens_after: compacted.tokens_after,
+            truncated: compacted.truncated,
+        },
+    )
+    .await;
+
+    Ok(compacted.messages)
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn run_tool_cycle(
     run: &AgentRunContext,
@@ -553,10 +733,24 @@ async fn run_tool_cycle(
                             .await?;
                     }
 
-                    match tools
-                        .execute_tool_call(run, &resolved.tool_call, cancel)
-                        .await?
-                    {
+                    // Malformed arguments never reach the executor
+                    let execution =
+                        match invalid_arguments_result(&config.tools, &resolved.tool_call) {
+                            Some(execution) => execution,
+                            None => {
+                                execute_with_retry(
+                                    run,
+                                    config,
+                                    tools,
+                                    &resolved.tool_call,
+                                    event_tx,
+                                    cancel,
+                                )
+                                .await?
+                            }
+                        };
+
+                    match execution {
                         ToolExecutionResult::Cancelled => {
                             append_tool_result_message(
                                 messages,
@@ -588,12 +782,16 @@ async fn run_tool_cycle(
 
                             return Ok(ToolCycleOutcome::Cancelled);
                         }
-                        ToolExecutionResult::Completed { result, is_error } => {
-                            append_tool_result_message(
-                                messages,
-                                &tool_call_id,
-                                json!(result.clone()),
-                            );
+                        ToolExecutionResult::Completed {
+                            result,
+                            is_error,
+                            error_kind,
+                        } => {
+                            let content = match error_kind {
+                                Some(kind) => kind.annotate(&result),
+                                None => result.clone(),
+                            };
+                            append_tool_result_message(messages, &tool_call_id, json!(content));
                             completed_tool_ids.insert(tool_call_id.clone());
 
                             emit(
@@ -697,9 +895,18 @@ async fn run_tool_cycle(
             AgentCommand::FollowUp(text) => {
                 queues.follow_up.push_back(text);
             }
+            AgentCommand::InjectMessage(message) => {
+                queues.injected.push_back(message);
+            }
             AgentCommand::SwitchModel(model) => {
                 *current_model = model;
             }
+            AgentCommand::Pause => {
+                queues.paused = true;
+            }
+            AgentCommand::Resume => {
+                queues.paused = false;
+            }
             AgentCommand::Cancel => {
                 cancel.cancel();
             }
@@ -707,6 +914,85 @@ async fn run_tool_cycle(
     }
 }
 
+/// Run a tool call, retrying transient failures with the same backoff as inference.
+///
+/// `Transient` means the call was never dispatched, so a retry cannot repeat
+/// the side effects of an already approved call.
+async fn execute_with_retry(
+    run: &AgentRunContext,
+    config: &AgentConfig,
+    tools: &dyn ToolExecutor,
+    tool_call: &ProposedToolCall,
+    event_tx: &mpsc::Sender<AgentEvent>,
+    cancel: &CancellationToken,
+) -> Result<ToolExecutionResult, AgentError> {
+    let mut attempt = 1;
+    loop {
+        let execution = tools.execute_tool_call(run, tool_call, cancel).await?;
+
+        let retryable = matches!(
+            &execution,
+            ToolExecutionResult::Completed {
+                error_kind: Some(kind),
+                ..
+            } if kind.is_retryable()
+        );
+        if !retryable || attempt >= config.retry.max_attempts {
+            return Ok(execution);
+        }
+        if !run.retry_budget.try_spend() {
+            emit_retry_budget_exhausted(
+                run,
+                event_tx,
+                format!("tool {} failed with a retryable error", tool_call.name),
+            )
+            .await;
+            return Ok(execution);
+        }
+
+        let delay_ms = exponential_backoff_ms(&config.retry, attempt);
+        tokio::select! {
+            _ = cancel.cancelled() => return Ok(execution),
+            _ = tokio::time::sleep(std::time::Duration::from_millis(delay_ms)) => {}
+        }
+        attempt += 1;
+    }
+}
+
+/// Check a tool call against its declared input schema, returning the error
+/// result to report instead of running the tool when the arguments are invalid.
+fn invalid_arguments_result(
+    tools: &[stakai::Tool],
+    tool_call: &ProposedToolCall,
+) -> Option<ToolExecutionResult> {
+    let tool = tools
+        .iter()
+        .find(|tool| tool.function.name == tool_call.name)?;
+
+    // Absent arguments are sent to MCP servers as an empty object
+    let empty = json!({});
+    let arguments = if tool_call.arguments.is_null() {
+        &empty
+    } else {
+        &tool_call.arguments
+    };
+
+    let violations = validate_tool_arguments(&tool.function.parameters, arguments);
+    if violations.is_empty() {
+        return None;
+    }
+
+    Some(ToolExecutionResult::Completed {
+        result: format!(
+            "Invalid arguments for tool '{}', so it was not run:\n- {}",
+            tool_call.name,
+            violations.join("\n- ")
+        ),
+        is_error: true,
+        error_kind: Some(ToolErrorKind::InvalidArgs),
+    })
+}
+
 fn append_tool_result_message(
     messages: &mut Vec<Message>,
     tool_call_id: &str,
@@ -780,6 +1066,30 @@ async fn append_skipped_due_to_steering(
     }
 }
 
+async fn append_skipped_due_to_loop(
+    run: &AgentRunContext,
+    event_tx: &mpsc::Sender<AgentEvent>,
+    messages: &mut Vec<Message>,
+    proposed_tool_calls: &[ProposedToolCall],
+) {
+    for tool_call in proposed_tool_calls {
+        let reason = "Skipped: identical tool call repeated too many times".to_string();
+
+        append_tool_result_message(messages, &tool_call.id, json!({"skipped": reason.clone()}));
+
+        emit(
+            event_tx,
+            AgentEvent::ToolRejected {
+                run_id: run.run_id,
+                tool_call_id: tool_call.id.clone(),
+                tool_name: tool_call.name.clone(),
+                reason,
+            },
+        )
+        .await;
+    }
+}
+
 fn drain_runtime_commands_nonblocking(
     command_rx: &mut mpsc::Receiver<AgentCommand>,
     queues: &mut RuntimeQueues,
@@ -787,30 +1097,95 @@ fn drain_runtime_commands_nonblocking(
     cancel: &CancellationToken,
 ) {
     while let Ok(command) = command_rx.try_recv() {
-        match command {
-            AgentCommand::ResolveTool {
-                tool_call_id,
-                decision,
-            } => {
+        apply_runtime_command(command, queues, current_model, cancel);
+    }
+}
+
+fn apply_runtime_command(
+    command: AgentCommand,
+    queues: &mut RuntimeQueues,
+    current_model: &mut stakai::Model,
+    cancel: &CancellationToken,
+) {
+    match command {
+        AgentCommand::ResolveTool {
+            tool_call_id,
+            decision,
+        } => {
+            queues.pending_tool_decisions.insert(tool_call_id, decision);
+        }
+        AgentCommand::ResolveTools { decisions } => {
+            for (tool_call_id, decision) in decisions {
                 queues.pending_tool_decisions.insert(tool_call_id, decision);
             }
-            AgentCommand::ResolveTools { decisions } => {
-                for (tool_call_id, decision) in decisions {
-                    queues.pending_tool_decisions.insert(tool_call_id, decision);
-                }
-            }
-            AgentCommand::Steering(text) => {
-                queues.steering.push_back(text);
-            }
-            AgentCommand::FollowUp(text) => {
-                queues.follow_up.push_back(text);
-            }
-            AgentCommand::SwitchModel(model) => {
-                *current_model = model;
-            }
-            AgentCommand::Cancel => cancel.cancel(),
         }
+        AgentCommand::Steering(text) => {
+            queues.steering.push_back(text);
+        }
+        AgentCommand::FollowUp(text) => {
+            queues.follow_up.push_back(text);
+        }
+        AgentCommand::InjectMessage(message) => {
+            queues.injected.push_back(message);
+        }
+        AgentCommand::SwitchModel(model) => {
+            *current_model = model;
+        }
+        AgentCommand::Pause => queues.paused = true,
+        AgentCommand::Resume => queues.paused = false,
+        AgentCommand::Cancel => cancel.cancel(),
+    }
+}
+
+/// Hold the run between steps until it is resumed or cancelled. Other
+/// commands received meanwhile are queued as usual.
+#[allow(clippy::too_many_arguments)]
+async fn wait_while_paused(
+    run: &AgentRunContext,
+    hooks: &[Box<dyn AgentHook>],
+    event_tx: &mpsc::Sender<AgentEvent>,
+    command_rx: &mut mpsc::Receiver<AgentCommand>,
+    cancel: &CancellationToken,
+    queues: &mut RuntimeQueues,
+    current_model: &mut stakai::Model,
+    messages: &[Message],
+    turn: usize,
+) -> Result<(), AgentError> {
+    emit(
+        event_tx,
+        AgentEvent::RunPaused {
+            run_id: run.run_id,
+            turn,
+        },
+    )
+    .await;
+    for hook in hooks {
+        hook.on_pause(run, messages).await?;
+    }
+
+    while queues.paused && !cancel.is_cancelled() {
+        let command = tokio::select! {
+            command = command_rx.recv() => command,
+            _ = cancel.cancelled() => break,
+        };
+        match command {
+            Some(command) => apply_runtime_command(command, queues, current_model, cancel),
+            // Nobody is left to resume the run, so don't hold it forever.
+            None => queues.paused = false,
+        }
+    }
+
+    if !cancel.is_cancelled() {
+        emit(
+            event_tx,
+            AgentEvent::RunResumed {
+                run_id: run.run_id,
+                turn,
+            },
+        )
+        .await;
     }
+    Ok(())
 }
 
 fn map_finish_reason(reason: &stakai::FinishReason) -> TurnFinishReason {
@@ -854,6 +1229,22 @@ fn is_context_overflow_error(error: &str) -> bool {
             || error.contains("limit"))
 }
 
+async fn emit_retry_budget_exhausted(
+    run: &AgentRunContext,
+    event_tx: &mpsc::Sender<AgentEvent>,
+    reason: String,
+) {
+    emit(
+        event_tx,
+        AgentEvent::RetryBudgetExhausted {
+            run_id: run.run_id,
+            retries: run.retry_budget.used(),
+            reason,
+        },
+    )
+    .await;
+}
+
 async fn emit(event_tx: &mpsc::Sender<AgentEvent>, event: AgentEvent) {
     let _ = event_tx.send(event).await;
 }
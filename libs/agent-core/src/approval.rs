@@ -261,6 +261,7 @@ mod tests {
         let policy = ToolApprovalPolicy::Custom {
             rules,
             default: ToolApprovalAction::Ask,
+            argument_rules: HashMap::new(),
         };
 
         let mut machine = ApprovalStateMachine::new(calls, &policy);
@@ -316,4 +317,35 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn argument_predicate_approves_matching_args_and_gates_the_rest() {
+        let calls = vec![
+            ProposedToolCall {
+                id: "tc_1".to_string(),
+                name: "stakpak__run_command".to_string(),
+                arguments: json!({"command": "kubectl get pods"}),
+                metadata: None,
+            },
+            ProposedToolCall {
+                id: "tc_2".to_string(),
+                name: "stakpak__run_command".to_string(),
+                arguments: json!({"command": "kubectl delete pod web"}),
+                metadata: None,
+            },
+        ];
+        let policy = ToolApprovalPolicy::from_allowlist(&[
+            "run_command[command=re:^kubectl get]".to_string()
+        ]);
+
+        let mut machine = ApprovalStateMachine::new(calls, &policy);
+
+        let first = machine.next_ready();
+        assert_eq!(
+            first.map(|resolved| (resolved.tool_call.id, resolved.decision)),
+            Some(("tc_1".to_string(), ToolDecision::Accept))
+        );
+        assert!(machine.next_ready().is_none());
+        assert_eq!(machine.pending_tool_call_ids(), vec!["tc_2".to_string()]);
+    }
 }
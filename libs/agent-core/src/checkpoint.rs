@@ -102,7 +102,7 @@ fn migrate_legacy_checkpoint(value: &serde_json::Value) -> Option<CheckpointEnve
 mod tests {
     use super::*;
     use serde_json::json;
-    use stakai::{Message, Role};
+    use stakai::{ContentPart, Message, MessageContent, Role};
 
     #[test]
     fn roundtrip_v1_envelope() {
@@ -132,6 +132,41 @@ mod tests {
         assert_eq!(first_message_text, Some("hello".to_string()));
     }
 
+    #[test]
+    fn roundtrip_keeps_mixed_content_parts() {
+        let envelope = CheckpointEnvelopeV1::new(
+            None,
+            vec![Message::new(
+                Role::User,
+                MessageContent::Parts(vec![
+                    ContentPart::text("what is wrong with this dashboard?"),
+                    ContentPart::image("data:image/png;base64,iVBORw0KGgo="),
+                ]),
+            )],
+            json!({}),
+        );
+
+        let payload = match serialize_checkpoint(&envelope) {
+            Ok(payload) => payload,
+            Err(error) => panic!("serialization should succeed, got: {error}"),
+        };
+        let parsed = match deserialize_checkpoint(&payload) {
+            Ok(parsed) => parsed,
+            Err(error) => panic!("deserialization should succeed, got: {error}"),
+        };
+
+        let Some(MessageContent::Parts(parts)) = parsed.messages.first().map(|m| &m.content) else {
+            panic!("expected multi-part content, got {:?}", parsed.messages);
+        };
+        assert_eq!(parts.len(), 2);
+        assert!(
+            matches!(&parts[0], ContentPart::Text { text, .. } if text == "what is wrong with this dashboard?")
+        );
+        assert!(
+            matches!(&parts[1], ContentPart::Image { url, .. } if url == "data:image/png;base64,iVBORw0KGgo=")
+        );
+    }
+
     #[test]
     fn migrates_legacy_messages_array() {
         let payload = json!([
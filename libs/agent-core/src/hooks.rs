@@ -1,4 +1,7 @@
-use crate::{error::AgentError, types::AgentRunContext, types::ProposedToolCall};
+use crate::{
+    compaction::CompactionResult, error::AgentError, types::AgentRunContext,
+    types::ProposedToolCall,
+};
 use async_trait::async_trait;
 use stakai::{Message, Model};
 
@@ -40,6 +43,32 @@ pub trait AgentHook: Send + Sync {
         Ok(())
     }
 
+    /// Called after the compaction engine rewrites the conversation, with the
+    /// messages it was given and the ones that replaced them.
+    ///
+    /// Also called before every request the context reducer trims. The
+    /// reducer works on a copy, so the same trim is reported on each request
+    /// for as long as the conversation stays over budget.
+    async fn on_compaction(
+        &self,
+        _run: &AgentRunContext,
+        _before: &[Message],
+        _after: &[Message],
+        _result: &CompactionResult,
+    ) -> Result<(), AgentError> {
+        Ok(())
+    }
+
+    /// Called once the run has paused between steps, with the conversation
+    /// it will continue from when resumed.
+    async fn on_pause(
+        &self,
+        _run: &AgentRunContext,
+        _messages: &[Message],
+    ) -> Result<(), AgentError> {
+        Ok(())
+    }
+
     async fn on_error(
         &self,
         _run: &AgentRunContext,
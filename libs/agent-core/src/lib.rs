@@ -7,6 +7,7 @@ pub mod context;
 pub mod error;
 pub mod hooks;
 pub mod retry;
+pub mod schema;
 pub mod stream;
 pub mod tools;
 pub mod types;
@@ -30,12 +31,15 @@ pub use retry::{
     RetryDelay, RetryDelaySource, exponential_backoff_ms, parse_retry_delay_from_headers,
     resolve_retry_delay_ms,
 };
+pub use schema::validate_tool_arguments;
 pub use stream::{
     IndexedStreamEvent, OrderedContentPart, StreamAssemblyError, assemble_ordered_content,
 };
-pub use tools::{ToolExecutionResult, ToolExecutor};
+pub use tools::{ToolErrorKind, ToolExecutionResult, ToolExecutor};
 pub use types::{
-    AgentCommand, AgentConfig, AgentEvent, AgentLoopResult, AgentRunContext, CompactionConfig,
-    ContextConfig, ProposedToolCall, RetryConfig, SAFE_AUTOPILOT_TOOLS, StopReason, TokenUsage,
-    ToolApprovalAction, ToolApprovalPolicy, ToolDecision, TurnFinishReason, strip_tool_prefix,
+    AgentCommand, AgentConfig, AgentEvent, AgentLoopResult, AgentRunContext, ArgumentPredicate,
+    CompactionConfig, ContextConfig, DEFAULT_RUN_RETRY_BUDGET, LoopDetectionConfig,
+    MAX_COMPACTION_TRIGGER_RATIO, MIN_COMPACTION_TRIGGER_RATIO, ProposedToolCall, RetryBudget,
+    RetryConfig, SAFE_AUTOPILOT_TOOLS, StopReason, TokenUsage, ToolApprovalAction,
+    ToolApprovalPolicy, ToolDecision, TurnFinishReason, strip_tool_prefix,
 };
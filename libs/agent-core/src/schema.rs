@@ -0,0 +1,259 @@
+//! Lightweight JSON Schema checks for tool arguments.
+//!
+//! Covers the subset of JSON Schema that tool input schemas (schemars / rmcp)
+//! actually use: `type`, `required`, `properties`, `additionalProperties`,
+//! `items`, `enum`, `anyOf`/`oneOf` and numeric bounds. Anything else is
+//! accepted, so an unusual schema never blocks a valid call.
+
+use serde_json::{Map, Value};
+
+/// Check `arguments` against a tool's input schema.
+///
+/// Returns one human-readable message per violation; empty when the arguments are valid.
+pub fn validate_tool_arguments(schema: &Value, arguments: &Value) -> Vec<String> {
+    let mut violations = Vec::new();
+    validate_value(schema, arguments, "arguments", &mut violations);
+    violations
+}
+
+fn validate_value(schema: &Value, value: &Value, path: &str, violations: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(expected) = schema.get("type")
+        && !type_matches(expected, value)
+    {
+        violations.push(format!(
+            "{}: expected {}, got {}",
+            path,
+            describe_type(expected),
+            json_type_name(value)
+        ));
+        return;
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+        violations.push(format!(
+            "{}: {} is not one of [{}]",
+            path,
+            value,
+            allowed.join(", ")
+        ));
+    }
+
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(variants) = schema.get(keyword).and_then(Value::as_array)
+            && !variants.is_empty()
+            && !variants
+                .iter()
+                .any(|variant| validate_tool_arguments(variant, value).is_empty())
+        {
+            violations.push(format!("{}: does not match any allowed shape", path));
+        }
+    }
+
+    match value {
+        Value::Object(object) => validate_object(schema, object, path, violations),
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_value(
+                        item_schema,
+                        item,
+                        &format!("{}[{}]", path, index),
+                        violations,
+                    );
+                }
+            }
+        }
+        Value::Number(number) => {
+            if let Some(number) = number.as_f64() {
+                if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64)
+                    && number < minimum
+                {
+                    violations.push(format!("{}: {} is less than {}", path, number, minimum));
+                }
+                if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64)
+                    && number > maximum
+                {
+                    violations.push(format!("{}: {} is greater than {}", path, number, maximum));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn validate_object(
+    schema: &Map<String, Value>,
+    object: &Map<String, Value>,
+    path: &str,
+    violations: &mut Vec<String>,
+) {
+    let properties = schema.get("properties").and_then(Value::as_object);
+
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for field in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(field) {
+                violations.push(format!("{}: missing required field `{}`", path, field));
+            }
+        }
+    }
+
+    for (key, value) in object {
+        let field_path = format!("{}.{}", path, key);
+        match properties.and_then(|properties| properties.get(key)) {
+            Some(property_schema) => {
+                validate_value(property_schema, value, &field_path, violations)
+            }
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    violations.push(format!("{}: unknown field `{}`", path, key));
+                }
+                Some(additional @ Value::Object(_)) => {
+                    validate_value(additional, value, &field_path, violations);
+                }
+                _ => {}
+            },
+        }
+    }
+}
+
+fn type_matches(expected: &Value, value: &Value) -> bool {
+    match expected {
+        Value::String(name) => single_type_matches(name, value),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|name| single_type_matches(name, value)),
+        _ => true,
+    }
+}
+
+fn single_type_matches(name: &str, value: &Value) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64()
+                || value.is_u64()
+                || value.as_f64().is_some_and(|number| number.fract() == 0.0)
+        }
+        // Unknown type names are not ours to reject
+        _ => true,
+    }
+}
+
+fn describe_type(expected: &Value) -> String {
+    match expected {
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        Value::String(name) => name.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run_command_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "command": { "type": "string" },
+                "timeout": { "type": ["integer", "null"], "format": "uint64", "minimum": 0 },
+                "view_range": { "type": ["array", "null"], "items": { "type": "integer" } },
+                "mode": { "type": "string", "enum": ["fast", "safe"] }
+            },
+            "required": ["command"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn valid_arguments_pass() {
+        let violations = validate_tool_arguments(
+            &run_command_schema(),
+            &json!({ "command": "ls", "timeout": null, "view_range": [1, 20], "mode": "safe" }),
+        );
+        assert!(violations.is_empty(), "{violations:?}");
+    }
+
+    #[test]
+    fn missing_required_field_is_reported() {
+        let violations = validate_tool_arguments(&run_command_schema(), &json!({ "timeout": 5 }));
+        assert_eq!(
+            violations,
+            vec!["arguments: missing required field `command`".to_string()]
+        );
+    }
+
+    #[test]
+    fn wrong_types_are_reported_per_field() {
+        let mut violations = validate_tool_arguments(
+            &run_command_schema(),
+            &json!({ "command": ["ls"], "timeout": "soon", "view_range": [1, "x"], "extra": true }),
+        );
+        // Field order depends on serde_json's map ordering
+        violations.sort();
+        assert_eq!(
+            violations,
+            vec![
+                "arguments.command: expected string, got array".to_string(),
+                "arguments.timeout: expected integer or null, got string".to_string(),
+                "arguments.view_range[1]: expected integer, got string".to_string(),
+                "arguments: unknown field `extra`".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn non_object_arguments_and_enum_and_bounds() {
+        assert_eq!(
+            validate_tool_arguments(&run_command_schema(), &json!("ls")),
+            vec!["arguments: expected object, got string".to_string()]
+        );
+        let mut violations = validate_tool_arguments(
+            &run_command_schema(),
+            &json!({ "command": "ls", "mode": "yolo", "timeout": -1 }),
+        );
+        violations.sort();
+        assert_eq!(
+            violations,
+            vec![
+                "arguments.mode: \"yolo\" is not one of [\"fast\", \"safe\"]".to_string(),
+                "arguments.timeout: -1 is less than 0".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn permissive_schemas_accept_anything() {
+        assert!(validate_tool_arguments(&json!({}), &json!({ "anything": 1 })).is_empty());
+        assert!(validate_tool_arguments(&Value::Null, &json!([1, 2])).is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 use serde_json::Value;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use thiserror::Error;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -31,6 +31,23 @@ pub enum IndexedStreamEvent {
     },
 }
 
+impl IndexedStreamEvent {
+    fn tool_call_id(&self) -> Option<(&str, usize)> {
+        match self {
+            IndexedStreamEvent::ToolCallStart {
+                content_index, id, ..
+            }
+            | IndexedStreamEvent::ToolCallArgumentsDelta {
+                content_index, id, ..
+            }
+            | IndexedStreamEvent::ToolCallEnd {
+                content_index, id, ..
+            } => Some((id, *content_index)),
+            IndexedStreamEvent::TextDelta { .. } | IndexedStreamEvent::ThinkingDelta { .. } => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum OrderedContentPart {
     Text(String),
@@ -40,6 +57,10 @@ pub enum OrderedContentPart {
         name: String,
         arguments: Value,
         metadata: Option<Value>,
+        /// The stream ended mid-call (e.g. at the output token limit) and
+        /// `arguments` were repaired from the truncated JSON, so fields may be
+        /// missing or cut short.
+        incomplete: bool,
     },
 }
 
@@ -51,6 +72,21 @@ pub enum StreamAssemblyError {
     #[error("tool call id mismatch at content index {content_index}")]
     ToolCallIdMismatch { content_index: usize },
 
+    #[error("tool call name mismatch at content index {content_index}")]
+    ToolCallNameMismatch { content_index: usize },
+
+    #[error(
+        "tool call {tool_call_id} streamed at content indices {first_index} and {second_index}"
+    )]
+    DuplicateToolCallId {
+        tool_call_id: String,
+        first_index: usize,
+        second_index: usize,
+    },
+
+    #[error("tool call {tool_call_id} never received a name")]
+    MissingToolCallName { tool_call_id: String },
+
     #[error("invalid tool call arguments for {tool_call_id}: {source}")]
     InvalidToolCallArguments {
         tool_call_id: String,
@@ -78,6 +114,32 @@ impl PartialEq for StreamAssemblyError {
                     content_index: right,
                 },
             ) => left == right,
+            (
+                StreamAssemblyError::ToolCallNameMismatch {
+                    content_index: left,
+                },
+                StreamAssemblyError::ToolCallNameMismatch {
+                    content_index: right,
+                },
+            ) => left == right,
+            (
+                StreamAssemblyError::DuplicateToolCallId {
+                    tool_call_id: left_id,
+                    first_index: left_first,
+                    second_index: left_second,
+                },
+                StreamAssemblyError::DuplicateToolCallId {
+                    tool_call_id: right_id,
+                    first_index: right_first,
+                    second_index: right_second,
+                },
+            ) => left_id == right_id && left_first == right_first && left_second == right_second,
+            (
+                StreamAssemblyError::MissingToolCallName { tool_call_id: left },
+                StreamAssemblyError::MissingToolCallName {
+                    tool_call_id: right,
+                },
+            ) => left == right,
             (
                 StreamAssemblyError::InvalidToolCallArguments {
                     tool_call_id: left, ..
@@ -119,18 +181,49 @@ impl ToolCallSlot {
         }
     }
 
+    /// Fill in the name from a later event, rejecting one that contradicts
+    /// the name already seen.
+    fn merge_name(
+        &mut self,
+        name: String,
+        content_index: usize,
+    ) -> Result<(), StreamAssemblyError> {
+        if self.name.is_empty() {
+            self.name = name;
+        } else if !name.is_empty() && self.name != name {
+            return Err(StreamAssemblyError::ToolCallNameMismatch { content_index });
+        }
+        Ok(())
+    }
+
     fn into_part(self) -> Result<OrderedContentPart, StreamAssemblyError> {
+        if self.name.is_empty() {
+            return Err(StreamAssemblyError::MissingToolCallName {
+                tool_call_id: self.id,
+            });
+        }
+
+        let mut incomplete = false;
         let arguments = if let Some(arguments) = self.final_arguments {
             arguments
         } else if self.arguments_buffer.trim().is_empty() {
             Value::Object(Default::default())
         } else {
-            serde_json::from_str(&self.arguments_buffer).map_err(|source| {
-                StreamAssemblyError::InvalidToolCallArguments {
-                    tool_call_id: self.id.clone(),
-                    source,
-                }
-            })?
+            match serde_json::from_str(&self.arguments_buffer) {
+                Ok(arguments) => arguments,
+                Err(source) => match repair_truncated_json(&self.arguments_buffer) {
+                    Some(arguments) => {
+                        incomplete = true;
+                        arguments
+                    }
+                    None => {
+                        return Err(StreamAssemblyError::InvalidToolCallArguments {
+                            tool_call_id: self.id,
+                            source,
+                        });
+                    }
+                },
+            }
         };
 
         Ok(OrderedContentPart::ToolCall {
@@ -138,16 +231,108 @@ impl ToolCallSlot {
             name: self.name,
             arguments,
             metadata: self.metadata,
+            incomplete,
         })
     }
 }
 
+/// Best-effort completion of tool arguments cut off mid-stream. Only text
+/// that is a valid prefix of a JSON document is repaired; anything else is
+/// malformed rather than truncated and is left to fail.
+///
+/// The whole text is tried first with an open string and open containers
+/// closed, keeping a cut-off string value. If that does not parse (the cut
+/// fell inside a key, a literal or an escape), the text is trimmed back to
+/// the last point where a value was complete.
+fn repair_truncated_json(text: &str) -> Option<Value> {
+    match serde_json::from_str::<Value>(text) {
+        Err(error) if error.is_eof() => {}
+        _ => return None,
+    }
+
+    let closing = |open: &[char]| open.iter().rev().collect::<String>();
+    let mut open = Vec::new();
+    // Prefix lengths that end right after a complete value or an opening
+    // bracket, with the brackets that close them.
+    let mut cut_points: Vec<(usize, String)> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for (position, ch) in text.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                in_string = false;
+                cut_points.push((position + 1, closing(&open)));
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_string = true,
+            '{' => {
+                open.push('}');
+                cut_points.push((position + 1, closing(&open)));
+            }
+            '[' => {
+                open.push(']');
+                cut_points.push((position + 1, closing(&open)));
+            }
+            '}' | ']' => {
+                open.pop();
+                cut_points.push((position + 1, closing(&open)));
+            }
+            ',' => cut_points.push((position, closing(&open))),
+            _ => {}
+        }
+    }
+
+    let mut whole = text.to_string();
+    if in_string {
+        if escaped {
+            whole.pop();
+        }
+        whole.push('"');
+    }
+    whole.push_str(&closing(&open));
+
+    std::iter::once(whole)
+        .chain(cut_points.into_iter().rev().filter_map(|(len, closers)| {
+            text.get(..len).map(|prefix| format!("{prefix}{closers}"))
+        }))
+        .find_map(|candidate| serde_json::from_str(&candidate).ok())
+}
+
+/// Rebuild a response's content parts from indexed stream events.
+///
+/// Parts come out in `content_index` order, which is the order the provider
+/// emitted them in, however the events for different indices interleaved or
+/// arrived. Deltas for the same index are concatenated in arrival order.
+/// Indices need not be contiguous.
 pub fn assemble_ordered_content(
     events: impl IntoIterator<Item = IndexedStreamEvent>,
 ) -> Result<Vec<OrderedContentPart>, StreamAssemblyError> {
     let mut slots: BTreeMap<usize, ContentSlot> = BTreeMap::new();
+    let mut tool_call_indices: HashMap<String, usize> = HashMap::new();
 
     for event in events {
+        if let Some((id, content_index)) = event.tool_call_id() {
+            match tool_call_indices.get(id) {
+                Some(&first_index) if first_index != content_index => {
+                    return Err(StreamAssemblyError::DuplicateToolCallId {
+                        tool_call_id: id.to_string(),
+                        first_index: first_index.min(content_index),
+                        second_index: first_index.max(content_index),
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    tool_call_indices.insert(id.to_string(), content_index);
+                }
+            }
+        }
+
         match event {
             IndexedStreamEvent::TextDelta {
                 content_index,
@@ -182,9 +367,7 @@ pub fn assemble_ordered_content(
                     if slot.id != id {
                         return Err(StreamAssemblyError::ToolCallIdMismatch { content_index });
                     }
-                    if slot.name.is_empty() {
-                        slot.name = name;
-                    }
+                    slot.merge_name(name, content_index)?;
                 }
                 Some(_) => {
                     return Err(StreamAssemblyError::ContentTypeMismatch { content_index });
@@ -227,9 +410,7 @@ pub fn assemble_ordered_content(
                     if slot.id != id {
                         return Err(StreamAssemblyError::ToolCallIdMismatch { content_index });
                     }
-                    if slot.name.is_empty() {
-                        slot.name = name;
-                    }
+                    slot.merge_name(name, content_index)?;
                     slot.final_arguments = Some(arguments);
                     slot.metadata = metadata;
                 }
@@ -320,6 +501,7 @@ mod tests {
                     name: "stakpak__run_command".to_string(),
                     arguments: json!({"cmd":"kubectl get pods"}),
                     metadata: None,
+                    incomplete: false,
                 },
                 OrderedContentPart::Thinking("observing cluster state".to_string()),
             ])
@@ -343,6 +525,7 @@ mod tests {
                 name: "stakpak__view".to_string(),
                 arguments: json!({"path":"README.md"}),
                 metadata: Some(json!({"provider":"gemini"})),
+                incomplete: false,
             }])
         );
     }
@@ -388,4 +571,297 @@ mod tests {
                 if tool_call_id == "tc_1"
         ));
     }
+
+    /// Events of a reasoning model that thinks, answers, calls a tool, then
+    /// thinks again, each part split into several deltas.
+    fn interleaved_reasoning_events() -> Vec<IndexedStreamEvent> {
+        vec![
+            IndexedStreamEvent::ThinkingDelta {
+                content_index: 0,
+                delta: "the pods ".to_string(),
+            },
+            IndexedStreamEvent::ThinkingDelta {
+                content_index: 0,
+                delta: "look unhealthy".to_string(),
+            },
+            IndexedStreamEvent::TextDelta {
+                content_index: 1,
+                delta: "Let me ".to_string(),
+            },
+            IndexedStreamEvent::TextDelta {
+                content_index: 1,
+                delta: "check.".to_string(),
+            },
+            IndexedStreamEvent::ToolCallStart {
+                content_index: 2,
+                id: "tc_1".to_string(),
+                name: "stakpak__run_command".to_string(),
+            },
+            IndexedStreamEvent::ToolCallArgumentsDelta {
+                content_index: 2,
+                id: "tc_1".to_string(),
+                delta: "{\"command\":".to_string(),
+            },
+            IndexedStreamEvent::ToolCallArgumentsDelta {
+                content_index: 2,
+                id: "tc_1".to_string(),
+                delta: "\"kubectl get pods\"}".to_string(),
+            },
+            IndexedStreamEvent::ThinkingDelta {
+                content_index: 3,
+                delta: "then read ".to_string(),
+            },
+            IndexedStreamEvent::ThinkingDelta {
+                content_index: 3,
+                delta: "the events".to_string(),
+            },
+        ]
+    }
+
+    fn event_index(event: &IndexedStreamEvent) -> usize {
+        match event {
+            IndexedStreamEvent::TextDelta { content_index, .. }
+            | IndexedStreamEvent::ThinkingDelta { content_index, .. }
+            | IndexedStreamEvent::ToolCallStart { content_index, .. }
+            | IndexedStreamEvent::ToolCallArgumentsDelta { content_index, .. }
+            | IndexedStreamEvent::ToolCallEnd { content_index, .. } => *content_index,
+        }
+    }
+
+    /// Interleave the events of different indices pseudo-randomly while
+    /// keeping each index's own events in order.
+    fn shuffle_across_indices(events: &[IndexedStreamEvent], seed: u64) -> Vec<IndexedStreamEvent> {
+        let mut queues: BTreeMap<usize, std::collections::VecDeque<IndexedStreamEvent>> =
+            BTreeMap::new();
+        for event in events {
+            queues
+                .entry(event_index(event))
+                .or_default()
+                .push_back(event.clone());
+        }
+
+        let mut state = seed;
+        let mut shuffled = Vec::with_capacity(events.len());
+        while !queues.is_empty() {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let pick = (state >> 33) as usize % queues.len();
+            let index = *queues.keys().nth(pick).unwrap();
+            let queue = queues.get_mut(&index).unwrap();
+            shuffled.push(queue.pop_front().unwrap());
+            if queue.is_empty() {
+                queues.remove(&index);
+            }
+        }
+        shuffled
+    }
+
+    #[test]
+    fn reconstructs_reasoning_interleaving_from_shuffled_events() {
+        let events = interleaved_reasoning_events();
+        let expected = vec![
+            OrderedContentPart::Thinking("the pods look unhealthy".to_string()),
+            OrderedContentPart::Text("Let me check.".to_string()),
+            OrderedContentPart::ToolCall {
+                id: "tc_1".to_string(),
+                name: "stakpak__run_command".to_string(),
+                arguments: json!({"command":"kubectl get pods"}),
+                metadata: None,
+                incomplete: false,
+            },
+            OrderedContentPart::Thinking("then read the events".to_string()),
+        ];
+        assert_eq!(
+            assemble_ordered_content(events.clone()),
+            Ok(expected.clone())
+        );
+
+        for seed in 0..64 {
+            let shuffled = shuffle_across_indices(&events, seed);
+            assert_eq!(
+                assemble_ordered_content(shuffled.clone()),
+                Ok(expected.clone()),
+                "seed {seed}: {shuffled:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_emitted_order_with_non_monotonic_sparse_indices() {
+        let parts = assemble_ordered_content(vec![
+            IndexedStreamEvent::TextDelta {
+                content_index: 7,
+                delta: "done".to_string(),
+            },
+            IndexedStreamEvent::ToolCallEnd {
+                content_index: 4,
+                id: "tc_1".to_string(),
+                name: "stakpak__view".to_string(),
+                arguments: json!({"path":"README.md"}),
+                metadata: None,
+            },
+            IndexedStreamEvent::ThinkingDelta {
+                content_index: 1,
+                delta: "reading first".to_string(),
+            },
+            IndexedStreamEvent::ToolCallStart {
+                content_index: 4,
+                id: "tc_1".to_string(),
+                name: "stakpak__view".to_string(),
+            },
+        ]);
+
+        assert_eq!(
+            parts,
+            Ok(vec![
+                OrderedContentPart::Thinking("reading first".to_string()),
+                OrderedContentPart::ToolCall {
+                    id: "tc_1".to_string(),
+                    name: "stakpak__view".to_string(),
+                    arguments: json!({"path":"README.md"}),
+                    metadata: None,
+                    incomplete: false,
+                },
+                OrderedContentPart::Text("done".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn errors_on_tool_call_id_at_two_indices() {
+        let result = assemble_ordered_content(vec![
+            IndexedStreamEvent::ToolCallStart {
+                content_index: 3,
+                id: "tc_1".to_string(),
+                name: "stakpak__view".to_string(),
+            },
+            IndexedStreamEvent::ToolCallArgumentsDelta {
+                content_index: 1,
+                id: "tc_1".to_string(),
+                delta: "{}".to_string(),
+            },
+        ]);
+
+        assert_eq!(
+            result,
+            Err(StreamAssemblyError::DuplicateToolCallId {
+                tool_call_id: "tc_1".to_string(),
+                first_index: 1,
+                second_index: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn errors_on_conflicting_tool_call_names() {
+        let result = assemble_ordered_content(vec![
+            IndexedStreamEvent::ToolCallStart {
+                content_index: 0,
+                id: "tc_1".to_string(),
+                name: "stakpak__view".to_string(),
+            },
+            IndexedStreamEvent::ToolCallEnd {
+                content_index: 0,
+                id: "tc_1".to_string(),
+                name: "stakpak__remove".to_string(),
+                arguments: json!({}),
+                metadata: None,
+            },
+        ]);
+
+        assert_eq!(
+            result,
+            Err(StreamAssemblyError::ToolCallNameMismatch { content_index: 0 })
+        );
+    }
+
+    #[test]
+    fn errors_on_tool_call_without_name() {
+        let result = assemble_ordered_content(vec![IndexedStreamEvent::ToolCallArgumentsDelta {
+            content_index: 0,
+            id: "tc_1".to_string(),
+            delta: "{}".to_string(),
+        }]);
+
+        assert_eq!(
+            result,
+            Err(StreamAssemblyError::MissingToolCallName {
+                tool_call_id: "tc_1".to_string(),
+            })
+        );
+    }
+
+    fn truncated_call(arguments: &str) -> Result<Vec<OrderedContentPart>, StreamAssemblyError> {
+        assemble_ordered_content(vec![
+            IndexedStreamEvent::ToolCallStart {
+                content_index: 0,
+                id: "tc_1".to_string(),
+                name: "stakpak__create".to_string(),
+            },
+            IndexedStreamEvent::ToolCallArgumentsDelta {
+                content_index: 0,
+                id: "tc_1".to_string(),
+                delta: arguments.to_string(),
+            },
+        ])
+    }
+
+    fn incomplete_call(arguments: Value) -> Result<Vec<OrderedContentPart>, StreamAssemblyError> {
+        Ok(vec![OrderedContentPart::ToolCall {
+            id: "tc_1".to_string(),
+            name: "stakpak__create".to_string(),
+            arguments,
+            metadata: None,
+            incomplete: true,
+        }])
+    }
+
+    #[test]
+    fn repairs_arguments_truncated_inside_a_string() {
+        assert_eq!(
+            truncated_call(r#"{"path":"main.rs","file_text":"fn main() {\n    println!(\"hi"#),
+            incomplete_call(json!({
+                "path": "main.rs",
+                "file_text": "fn main() {\n    println!(\"hi",
+            }))
+        );
+        assert_eq!(
+            truncated_call(r#"{"path":"a.txt","file_text":"line\"#),
+            incomplete_call(json!({"path": "a.txt", "file_text": "line"}))
+        );
+    }
+
+    #[test]
+    fn repairs_arguments_truncated_between_values() {
+        assert_eq!(
+            truncated_call(r#"{"path":"a.txt","tags":["x","y""#),
+            incomplete_call(json!({"path": "a.txt", "tags": ["x", "y"]}))
+        );
+        assert_eq!(
+            truncated_call(r#"{"path":"a.txt","#),
+            incomplete_call(json!({"path": "a.txt"}))
+        );
+        assert_eq!(
+            truncated_call(r#"{"path":"a.txt","overwrite":tr"#),
+            incomplete_call(json!({"path": "a.txt"}))
+        );
+        assert_eq!(
+            truncated_call(r#"{"path":"a.txt","file_te"#),
+            incomplete_call(json!({"path": "a.txt"}))
+        );
+        assert_eq!(truncated_call(r#"{"pa"#), incomplete_call(json!({})));
+    }
+
+    #[test]
+    fn complete_arguments_are_not_marked_incomplete() {
+        let parts = truncated_call(r#"{"path":"a.txt"}"#).unwrap();
+        assert!(matches!(
+            parts.as_slice(),
+            [OrderedContentPart::ToolCall {
+                incomplete: false,
+                ..
+            }]
+        ));
+    }
 }
@@ -1,13 +1,103 @@
 use crate::{error::AgentError, types::AgentRunContext, types::ProposedToolCall};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ToolExecutionResult {
-    Completed { result: String, is_error: bool },
+    Completed {
+        result: String,
+        is_error: bool,
+        /// Why the tool failed, when the executor can tell.
+        error_kind: Option<ToolErrorKind>,
+    },
     Cancelled,
 }
 
+/// Coarse classification of a failed tool call, so the model (and the agent
+/// loop) can tell a failure worth retrying from one that needs different input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolErrorKind {
+    /// The call failed before it was dispatched to the tool (e.g. a transport
+    /// error sending the request), so re-sending it cannot repeat side effects.
+    /// Only executors set this; [`ToolErrorKind::classify`] never returns it.
+    Transient,
+    NotFound,
+    PermissionDenied,
+    InvalidArgs,
+    Timeout,
+}
+
+impl ToolErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ToolErrorKind::Transient => "transient",
+            ToolErrorKind::NotFound => "not_found",
+            ToolErrorKind::PermissionDenied => "permission_denied",
+            ToolErrorKind::InvalidArgs => "invalid_args",
+            ToolErrorKind::Timeout => "timeout",
+        }
+    }
+
+    /// Only transient failures are retried automatically by the agent loop.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ToolErrorKind::Transient)
+    }
+
+    /// Best-effort classification of a free-form tool error message.
+    ///
+    /// The message comes from a tool that already ran, so network-looking
+    /// failures are not reported as `Transient`: retrying them would re-run
+    /// a call that may have had side effects.
+    pub fn classify(message: &str) -> Option<Self> {
+        let message = message.to_lowercase();
+        let contains_any = |needles: &[&str]| needles.iter().any(|n| message.contains(n));
+
+        if contains_any(&["timed out", "timeout", "deadline exceeded"]) {
+            Some(ToolErrorKind::Timeout)
+        } else if contains_any(&[
+            "permission denied",
+            "access denied",
+            "forbidden",
+            "unauthorized",
+            "operation not permitted",
+        ]) {
+            Some(ToolErrorKind::PermissionDenied)
+        } else if contains_any(&[
+            "invalid argument",
+            "invalid params",
+            "invalid parameter",
+            "missing field",
+            "unknown field",
+            "invalid type",
+            "missing required",
+        ]) {
+            Some(ToolErrorKind::InvalidArgs)
+        } else if contains_any(&["not found", "no such file", "does not exist"]) {
+            Some(ToolErrorKind::NotFound)
+        } else {
+            None
+        }
+    }
+
+    /// Prefix a tool result with a machine-readable error tag.
+    pub fn annotate(&self, result: &str) -> String {
+        format!(
+            "<tool_error kind=\"{}\" retryable=\"{}\" />\n{}",
+            self.as_str(),
+            self.is_retryable(),
+            result
+        )
+    }
+}
+
+impl std::fmt::Display for ToolErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[async_trait]
 pub trait ToolExecutor: Send + Sync {
     async fn execute_tool_call(
@@ -17,3 +107,60 @@ pub trait ToolExecutor: Send + Sync {
         cancel: &CancellationToken,
     ) -> Result<ToolExecutionResult, AgentError>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_representative_tool_failures() {
+        let cases = [
+            // The tool already ran; never classified as retryable
+            ("MCP tool execution error: connection reset by peer", None),
+            ("HTTP 503 Service Unavailable, try again later", None),
+            (
+                "cat: /etc/missing.conf: No such file or directory",
+                Some(ToolErrorKind::NotFound),
+            ),
+            (
+                "open /var/log/secure: permission denied",
+                Some(ToolErrorKind::PermissionDenied),
+            ),
+            (
+                "Invalid params: missing field `command`",
+                Some(ToolErrorKind::InvalidArgs),
+            ),
+            (
+                "Command timed out after 120 seconds",
+                Some(ToolErrorKind::Timeout),
+            ),
+            ("exit status 1", None),
+        ];
+
+        for (message, expected) in cases {
+            assert_eq!(ToolErrorKind::classify(message), expected, "{message}");
+        }
+    }
+
+    #[test]
+    fn only_transient_errors_are_retryable() {
+        assert!(ToolErrorKind::Transient.is_retryable());
+        assert!(!ToolErrorKind::Timeout.is_retryable());
+        assert!(!ToolErrorKind::InvalidArgs.is_retryable());
+        assert!(!ToolErrorKind::NotFound.is_retryable());
+        assert!(!ToolErrorKind::PermissionDenied.is_retryable());
+    }
+
+    #[test]
+    fn annotate_prefixes_structured_tag() {
+        let annotated = ToolErrorKind::NotFound.annotate("no such file");
+        assert_eq!(
+            annotated,
+            "<tool_error kind=\"not_found\" retryable=\"false\" />\nno such file"
+        );
+        assert_eq!(
+            serde_json::to_value(ToolErrorKind::PermissionDenied).ok(),
+            Some(serde_json::json!("permission_denied"))
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use uuid::Uuid;
 
 pub type TokenUsage = stakai::Usage;
@@ -9,8 +11,78 @@ pub type TokenUsage = stakai::Usage;
 pub struct AgentRunContext {
     pub run_id: Uuid,
     pub session_id: Uuid,
+    pub retry_budget: RetryBudget,
 }
 
+impl AgentRunContext {
+    /// A run context with no cap on retries across the run.
+    pub fn new(run_id: Uuid, session_id: Uuid) -> Self {
+        Self {
+            run_id,
+            session_id,
+            retry_budget: RetryBudget::unlimited(),
+        }
+    }
+
+    pub fn with_retry_budget(mut self, max_retries: usize) -> Self {
+        self.retry_budget = RetryBudget::new(max_retries);
+        self
+    }
+}
+
+/// Retries a server run may spend in total before failing fast.
+pub const DEFAULT_RUN_RETRY_BUDGET: usize = 20;
+
+/// Retries left for a whole run. `RetryConfig` bounds the retries of a single
+/// request; this caps the sum across every inference request and tool call
+/// in the run. Clones of a run context share one budget.
+#[derive(Debug, Clone, Default)]
+pub struct RetryBudget {
+    max_retries: Option<usize>,
+    used: Arc<AtomicUsize>,
+}
+
+impl RetryBudget {
+    pub fn new(max_retries: usize) -> Self {
+        Self {
+            max_retries: Some(max_retries),
+            used: Arc::default(),
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Take one retry from the budget, or return `false` once it is spent.
+    pub fn try_spend(&self) -> bool {
+        self.used
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| {
+                match self.max_retries {
+                    Some(max_retries) if used >= max_retries => None,
+                    _ => Some(used + 1),
+                }
+            })
+            .is_ok()
+    }
+
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::SeqCst)
+    }
+
+    pub fn max_retries(&self) -> Option<usize> {
+        self.max_retries
+    }
+}
+
+impl PartialEq for RetryBudget {
+    fn eq(&self, other: &Self) -> bool {
+        self.max_retries == other.max_retries && self.used() == other.used()
+    }
+}
+
+impl Eq for RetryBudget {}
+
 #[derive(Debug, Clone)]
 pub struct AgentConfig {
     pub model: stakai::Model,
@@ -21,6 +93,7 @@ pub struct AgentConfig {
     pub tool_approval: ToolApprovalPolicy,
     pub retry: RetryConfig,
     pub compaction: CompactionConfig,
+    pub loop_detection: LoopDetectionConfig,
     pub tools: Vec<stakai::Tool>,
 }
 
@@ -56,14 +129,127 @@ impl Default for RetryConfig {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Guards against a model stuck proposing the same tool calls. A turn is a
+/// repeat when its tool calls have the same names and arguments as the turn
+/// before. After `max_repeats` identical turns in a row the model is told to
+/// change course; one more repeat stops the run with
+/// `StopReason::LoopDetected`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoopDetectionConfig {
+    pub enabled: bool,
+    pub max_repeats: usize,
+}
+
+impl LoopDetectionConfig {
+    /// Check that `max_repeats` leaves room for at least one repeat.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=100).contains(&self.max_repeats) {
+            return Err(format!(
+                "loop_detection.max_repeats must be 1-100, got {}",
+                self.max_repeats
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for LoopDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_repeats: 3,
+        }
+    }
+}
+
+/// Bounds a compaction `trigger_ratio` is clamped to, so a typo cannot make
+/// every turn compact or let the context overflow before compacting.
+pub const MIN_COMPACTION_TRIGGER_RATIO: f64 = 0.1;
+pub const MAX_COMPACTION_TRIGGER_RATIO: f64 = 0.95;
+
+#[derive(Clone)]
 pub struct CompactionConfig {
     pub enabled: bool,
+    /// Compact before a turn once the estimated context reaches this many
+    /// tokens. Without a trigger, compaction only runs when the provider
+    /// reports a context overflow.
+    pub trigger_tokens: Option<u64>,
+    /// Compact once the context reaches this fraction of the model's context
+    /// window. Overrides `trigger_tokens` when the window is known.
+    pub trigger_ratio: Option<f64>,
+    /// models.dev data used to size the context budget. Models it does not
+    /// list keep the limits they were created with.
+    pub model_registry: Option<Arc<HashMap<String, stakai::ProviderInfo>>>,
+}
+
+impl CompactionConfig {
+    pub fn with_model_registry(mut self, registry: HashMap<String, stakai::ProviderInfo>) -> Self {
+        self.model_registry = Some(Arc::new(registry));
+        self
+    }
+
+    pub fn with_trigger_tokens(mut self, tokens: u64) -> Self {
+        self.trigger_tokens = Some(tokens);
+        self
+    }
+
+    pub fn with_trigger_ratio(mut self, ratio: f64) -> Self {
+        self.trigger_ratio = Some(ratio);
+        self
+    }
+
+    /// Estimated context size, in tokens, at which to compact before a turn
+    /// with `model`.
+    pub fn trigger_tokens_for(&self, model: &stakai::Model) -> Option<u64> {
+        let context_window = self.sized_model(model).limit.context;
+        match self.trigger_ratio {
+            Some(ratio) if !ratio.is_nan() && context_window > 0 => {
+                let ratio = ratio.clamp(MIN_COMPACTION_TRIGGER_RATIO, MAX_COMPACTION_TRIGGER_RATIO);
+                Some((context_window as f64 * ratio) as u64)
+            }
+            _ => self.trigger_tokens,
+        }
+    }
+
+    /// `model` with the context window and output limit the registry lists
+    /// for it, used to size the context budget before each turn.
+    pub fn sized_model(&self, model: &stakai::Model) -> stakai::Model {
+        let mut sized = model.clone();
+        if let Some(registry) = &self.model_registry {
+            if let Some(context_window) = model.context_window(registry) {
+                sized.limit.context = context_window as u64;
+            }
+            if let Some(max_output_tokens) = model.max_output_tokens(registry) {
+                sized.limit.output = max_output_tokens as u64;
+            }
+        }
+        sized
+    }
 }
 
 impl Default for CompactionConfig {
     fn default() -> Self {
-        Self { enabled: true }
+        Self {
+            enabled: true,
+            trigger_tokens: None,
+            trigger_ratio: None,
+            model_registry: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for CompactionConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompactionConfig")
+            .field("enabled", &self.enabled)
+            .field("trigger_tokens", &self.trigger_tokens)
+            .field("trigger_ratio", &self.trigger_ratio)
+            .field(
+                "model_registry_providers",
+                &self.model_registry.as_ref().map(|registry| registry.len()),
+            )
+            .finish()
     }
 }
 
@@ -74,9 +260,55 @@ pub enum ToolApprovalPolicy {
     Custom {
         rules: HashMap<String, ToolApprovalAction>,
         default: ToolApprovalAction,
+        /// Per-tool argument predicates. A tool listed here is approved only
+        /// when one of its predicates matches; otherwise it falls back to Ask.
+        argument_rules: HashMap<String, Vec<ArgumentPredicate>>,
     },
 }
 
+/// Matches a single string argument of a tool call against a pattern.
+///
+/// Patterns use the same syntax as shell approval scopes: `re:<regex>` for a
+/// regex, `*`/`?`/`[` for a glob, anything else for exact equality.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArgumentPredicate {
+    pub field: String,
+    pub pattern: String,
+}
+
+impl ArgumentPredicate {
+    pub fn new(field: impl Into<String>, pattern: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            pattern: pattern.into(),
+        }
+    }
+
+    /// Parse an allowlist entry of the form `tool[field=pattern]`.
+    ///
+    /// Returns the stripped tool name and the predicate, or `None` if the
+    /// entry is a plain tool name or malformed.
+    pub fn parse_allowlist_entry(entry: &str) -> Option<(String, Self)> {
+        let (tool, rest) = entry.trim().split_once('[')?;
+        let (field, pattern) = rest.strip_suffix(']')?.split_once('=')?;
+        let tool = strip_tool_prefix(tool.trim());
+        let field = field.trim();
+        if tool.is_empty() || field.is_empty() || pattern.is_empty() {
+            return None;
+        }
+        Some((tool.to_string(), Self::new(field, pattern)))
+    }
+
+    pub fn matches(&self, arguments: &Value) -> bool {
+        arguments
+            .get(&self.field)
+            .and_then(|value| value.as_str())
+            .is_some_and(|value| {
+                stakpak_shell_tool_approvals::matches_pattern(&self.pattern, value)
+            })
+    }
+}
+
 const SHELL_TOOLS: &[&str] = &[
     "run_command",
     "run_command_task",
@@ -89,6 +321,9 @@ const BASE_SHELL_TOOL: &str = "run_command";
 /// Read-only tools that are safe to auto-approve by default.
 const DEFAULT_AUTO_APPROVE_TOOLS: &[&str] = &[
     "view",
+    "search_files",
+    "git_status",
+    "git_diff",
     "generate_password",
     "search_docs",
     "search_memory",
@@ -106,6 +341,7 @@ pub const SAFE_AUTOPILOT_TOOLS: &[&str] = DEFAULT_AUTO_APPROVE_TOOLS;
 const DEFAULT_ASK_TOOLS: &[&str] = &[
     "create",
     "str_replace",
+    "apply_patch",
     "generate_code",
     "run_command",
     "run_command_task",
@@ -132,26 +368,61 @@ impl ToolApprovalPolicy {
         Self::Custom {
             rules,
             default: ToolApprovalAction::Ask,
+            argument_rules: HashMap::new(),
         }
     }
 
     /// Build an unattended policy from an explicit allowlist.
     ///
-    /// Listed tools are approved. Everything else is denied.
+    /// Listed tools are approved. Everything else is denied. Entries of the
+    /// form `tool[field=pattern]` approve `tool` only when `field` matches
+    /// `pattern`; calls with other arguments ask instead.
     pub fn from_allowlist(tools: &[String]) -> Self {
-        let mut rules = HashMap::new();
+        let mut policy = Self::Custom {
+            rules: HashMap::new(),
+            default: ToolApprovalAction::Deny,
+            argument_rules: HashMap::new(),
+        };
 
         for name in tools {
+            if name.contains('[') {
+                // Malformed predicates are dropped so the tool stays gated
+                if let Some((tool, predicate)) = ArgumentPredicate::parse_allowlist_entry(name) {
+                    policy = policy.with_argument_rule(tool, predicate);
+                }
+                continue;
+            }
+
             let normalized = strip_tool_prefix(name.trim());
             if normalized.is_empty() {
                 continue;
             }
-            rules.insert(normalized.to_string(), ToolApprovalAction::Approve);
+            policy = policy.with_overrides([(normalized.to_string(), ToolApprovalAction::Approve)]);
         }
 
-        Self::Custom {
-            rules,
-            default: ToolApprovalAction::Deny,
+        policy
+    }
+
+    /// Gate a tool on an argument predicate.
+    /// Only meaningful for `Custom` — returns `self` unchanged for `None`/`All`.
+    pub fn with_argument_rule(self, tool: impl Into<String>, predicate: ArgumentPredicate) -> Self {
+        match self {
+            Self::Custom {
+                rules,
+                default,
+                mut argument_rules,
+            } => {
+                argument_rules
+                    .entry(tool.into())
+                    .or_default()
+                    .push(predicate);
+                Self::Custom {
+                    rules,
+                    default,
+                    argument_rules,
+                }
+            }
+            other => other,
         }
     }
 
@@ -162,11 +433,19 @@ impl ToolApprovalPolicy {
         overrides: impl IntoIterator<Item = (String, ToolApprovalAction)>,
     ) -> Self {
         match self {
-            Self::Custom { mut rules, default } => {
+            Self::Custom {
+                mut rules,
+                default,
+                argument_rules,
+            } => {
                 for (name, action) in overrides {
                     rules.insert(name, action);
                 }
-                Self::Custom { rules, default }
+                Self::Custom {
+                    rules,
+                    default,
+                    argument_rules,
+                }
             }
             other => other,
         }
@@ -175,9 +454,9 @@ impl ToolApprovalPolicy {
     /// Determine the approval action for a tool call.
     ///
     /// `tool_arguments` is `Some` when the raw JSON arguments are available
-    /// (used for hierarchical shell command resolution on `run_command`).
-    /// Pass `None` for tools that have no arguments or when shell inspection
-    /// is not needed.
+    /// (used for hierarchical shell command resolution on `run_command` and
+    /// for argument predicates). Pass `None` for tools that have no arguments
+    /// or when argument inspection is not needed.
     pub fn action_for(
         &self,
         tool_name: &str,
@@ -188,42 +467,88 @@ impl ToolApprovalPolicy {
         match self {
             Self::None => ToolApprovalAction::Ask,
             Self::All => ToolApprovalAction::Approve,
-            Self::Custom { rules, default } => {
-                if SHELL_TOOLS.contains(&stripped)
-                    && let Some(args) = tool_arguments
-                    && let Some(command_str) = args.get("command").and_then(|v| v.as_str())
+            Self::Custom {
+                rules,
+                default,
+                argument_rules,
+            } => {
+                let Some(predicates) = argument_rules.get(stripped) else {
+                    return rule_action(stripped, tool_arguments, rules, *default);
+                };
+
+                // Explicit deny rules still win over a matching predicate,
+                // but the policy default must not mask the predicate result.
+                if rule_action(stripped, tool_arguments, rules, ToolApprovalAction::Ask)
+                    == ToolApprovalAction::Deny
                 {
-                    let fallback_scopes = if SHELL_TOOLS
-                        .iter()
-                        .any(|&c| c == stripped && c != BASE_SHELL_TOOL)
-                    {
-                        vec![BASE_SHELL_TOOL]
-                    } else {
-                        Vec::new()
-                    };
-
-                    match stakpak_shell_tool_approvals::resolve_hierarchical_policy(
-                        command_str,
-                        stripped,
-                        &fallback_scopes,
-                        rules,
-                        *default,
-                    ) {
-                        Ok(Some(action)) => return action,
-                        Ok(None) => {}
-                        Err(_) => {
-                            return conservative_shell_parse_fallback(stripped, rules, *default)
-                                .max(ToolApprovalAction::Ask);
-                        }
-                    }
+                    return ToolApprovalAction::Deny;
                 }
 
-                rules.get(stripped).copied().unwrap_or(*default)
+                // A matching prefix must not wave through chained shell commands
+                let matched = tool_arguments.is_some_and(|args| {
+                    predicates.iter().any(|predicate| predicate.matches(args))
+                        && (!SHELL_TOOLS.contains(&stripped) || is_single_shell_command(args))
+                });
+                if matched {
+                    ToolApprovalAction::Approve
+                } else {
+                    ToolApprovalAction::Ask
+                }
             }
         }
     }
 }
 
+fn rule_action(
+    stripped: &str,
+    tool_arguments: Option<&Value>,
+    rules: &HashMap<String, ToolApprovalAction>,
+    default: ToolApprovalAction,
+) -> ToolApprovalAction {
+    if SHELL_TOOLS.contains(&stripped)
+        && let Some(args) = tool_arguments
+        && let Some(command_str) = args.get("command").and_then(|v| v.as_str())
+    {
+        let fallback_scopes = if SHELL_TOOLS
+            .iter()
+            .any(|&c| c == stripped && c != BASE_SHELL_TOOL)
+        {
+            vec![BASE_SHELL_TOOL]
+        } else {
+            Vec::new()
+        };
+
+        match stakpak_shell_tool_approvals::resolve_hierarchical_policy(
+            command_str,
+            stripped,
+            &fallback_scopes,
+            rules,
+            default,
+        ) {
+            Ok(Some(action)) => return action,
+            Ok(None) => {}
+            Err(_) => {
+                return conservative_shell_parse_fallback(stripped, rules, default)
+                    .max(ToolApprovalAction::Ask);
+            }
+        }
+    }
+
+    rules.get(stripped).copied().unwrap_or(default)
+}
+
+fn is_single_shell_command(arguments: &Value) -> bool {
+    arguments
+        .get("command")
+        .and_then(|v| v.as_str())
+        .is_some_and(|command| {
+            matches!(
+                stakpak_shell_tool_approvals::parse_with_status(command),
+                Ok(commands) if commands.len() == 1
+            )
+        })
+}
+
 fn conservative_shell_parse_fallback(
     tool_scope: &str,
     rules: &HashMap<String, ToolApprovalAction>,
@@ -291,7 +616,7 @@ impl Ord for ToolApprovalAction {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum AgentCommand {
     ResolveTool {
         tool_call_id: String,
@@ -302,7 +627,15 @@ pub enum AgentCommand {
     },
     Steering(String),
     FollowUp(String),
+    /// Append a message to the conversation at the next turn boundary, once
+    /// any tool calls in flight have their results. Unlike `Steering`, it
+    /// does not skip the remaining tool calls of the current turn.
+    InjectMessage(stakai::Message),
     SwitchModel(stakai::Model),
+    /// Let the current step finish, then hold the run before its next
+    /// inference until `Resume` or `Cancel` arrives.
+    Pause,
+    Resume,
     Cancel,
 }
 
@@ -330,6 +663,7 @@ pub enum StopReason {
     Completed,
     Cancelled,
     MaxTurns,
+    LoopDetected,
     Error,
 }
 
@@ -367,6 +701,16 @@ pub enum AgentEvent {
         error: String,
         retryable: bool,
     },
+    /// The run is holding after `turn` and will not call the model again
+    /// until it is resumed.
+    RunPaused {
+        run_id: Uuid,
+        turn: usize,
+    },
+    RunResumed {
+        run_id: Uuid,
+        turn: usize,
+    },
 
     TextDelta {
         run_id: Uuid,
@@ -419,6 +763,13 @@ pub enum AgentEvent {
         delay_ms: u64,
         reason: String,
     },
+    /// A retry was skipped because the run's retry budget is spent; the
+    /// failure that would have been retried is surfaced instead.
+    RetryBudgetExhausted {
+        run_id: Uuid,
+        retries: usize,
+        reason: String,
+    },
     CompactionStarted {
         run_id: Uuid,
         reason: String,
@@ -619,6 +970,7 @@ mod tests {
         let policy = ToolApprovalPolicy::Custom {
             rules,
             default: ToolApprovalAction::Ask,
+            argument_rules: HashMap::new(),
         };
         assert_eq!(
             policy.action_for(
@@ -640,6 +992,7 @@ mod tests {
         let policy = ToolApprovalPolicy::Custom {
             rules,
             default: ToolApprovalAction::Ask,
+            argument_rules: HashMap::new(),
         };
         assert_eq!(
             policy.action_for(
@@ -661,6 +1014,7 @@ mod tests {
         let policy = ToolApprovalPolicy::Custom {
             rules,
             default: ToolApprovalAction::Ask,
+            argument_rules: HashMap::new(),
         };
         // "git log" → Approve, "git push" → Deny; max = Deny
         assert_eq!(
@@ -679,6 +1033,7 @@ mod tests {
         let policy = ToolApprovalPolicy::Custom {
             rules,
             default: ToolApprovalAction::Ask,
+            argument_rules: HashMap::new(),
         };
         // "rm" not in rules → default (Ask)
         assert_eq!(
@@ -701,6 +1056,7 @@ mod tests {
         let policy = ToolApprovalPolicy::Custom {
             rules,
             default: ToolApprovalAction::Ask,
+            argument_rules: HashMap::new(),
         };
         // non-prod URL → Approve
         assert_eq!(
@@ -727,6 +1083,7 @@ mod tests {
         let policy = ToolApprovalPolicy::Custom {
             rules,
             default: ToolApprovalAction::Approve,
+            argument_rules: HashMap::new(),
         };
         // The outer command is "sh", but the inner script contains "rm"
         // stakpak_shell_tool_approvals recursively extracts inner commands from "sh -c '...'"
@@ -749,6 +1106,7 @@ mod tests {
         let policy = ToolApprovalPolicy::Custom {
             rules,
             default: ToolApprovalAction::Ask,
+            argument_rules: HashMap::new(),
         };
 
         assert_eq!(
@@ -768,6 +1126,7 @@ mod tests {
         let policy = ToolApprovalPolicy::Custom {
             rules,
             default: ToolApprovalAction::Ask,
+            argument_rules: HashMap::new(),
         };
 
         assert_eq!(
@@ -786,6 +1145,7 @@ mod tests {
         let policy = ToolApprovalPolicy::Custom {
             rules,
             default: ToolApprovalAction::Ask,
+            argument_rules: HashMap::new(),
         };
 
         assert_eq!(
@@ -804,6 +1164,7 @@ mod tests {
         let policy = ToolApprovalPolicy::Custom {
             rules,
             default: ToolApprovalAction::Approve,
+            argument_rules: HashMap::new(),
         };
 
         let mut command = "echo deeply nested".to_string();
@@ -819,4 +1180,222 @@ mod tests {
             ToolApprovalAction::Deny
         );
     }
+
+    #[test]
+    fn parse_allowlist_entry_extracts_tool_and_predicate() {
+        assert_eq!(
+            ArgumentPredicate::parse_allowlist_entry(
+                "stakpak__run_command[command=re:^kubectl get]"
+            ),
+            Some((
+                "run_command".to_string(),
+                ArgumentPredicate::new("command", "re:^kubectl get")
+            ))
+        );
+        assert_eq!(
+            ArgumentPredicate::parse_allowlist_entry("run_command"),
+            None
+        );
+        assert_eq!(
+            ArgumentPredicate::parse_allowlist_entry("run_command[command]"),
+            None
+        );
+    }
+
+    #[test]
+    fn from_allowlist_argument_predicate_gates_on_args() {
+        let tools = vec![
+            "view".to_string(),
+            "run_command[command=re:^kubectl get ]".to_string(),
+            "create[path=/tmp/*]".to_string(),
+        ];
+        let policy = ToolApprovalPolicy::from_allowlist(&tools);
+
+        assert_eq!(
+            policy.action_for(
+                "run_command",
+                Some(&serde_json::json!({"command": "kubectl get pods -A"}))
+            ),
+            ToolApprovalAction::Approve
+        );
+        assert_eq!(
+            policy.action_for(
+                "run_command",
+                Some(&serde_json::json!({"command": "kubectl delete pod web"}))
+            ),
+            ToolApprovalAction::Ask
+        );
+        assert_eq!(
+            policy.action_for("create", Some(&serde_json::json!({"path": "/tmp/out.txt"}))),
+            ToolApprovalAction::Approve
+        );
+        assert_eq!(
+            policy.action_for("create", Some(&serde_json::json!({"path": "/etc/hosts"}))),
+            ToolApprovalAction::Ask
+        );
+        // Unlisted tools keep the allowlist's deny default
+        assert_eq!(policy.action_for("remove", None), ToolApprovalAction::Deny);
+    }
+
+    #[test]
+    fn argument_predicate_does_not_approve_chained_shell_commands() {
+        let policy = ToolApprovalPolicy::with_defaults().with_argument_rule(
+            "run_command",
+            ArgumentPredicate::new("command", "re:^kubectl get"),
+        );
+
+        assert_eq!(
+            policy.action_for(
+                "run_command",
+                Some(&serde_json::json!({"command": "kubectl get pods && rm -rf /"}))
+            ),
+            ToolApprovalAction::Ask
+        );
+    }
+
+    #[test]
+    fn explicit_deny_rule_wins_over_matching_argument_predicate() {
+        let policy = ToolApprovalPolicy::with_defaults()
+            .with_overrides([("run_command::kubectl".to_string(), ToolApprovalAction::Deny)])
+            .with_argument_rule(
+                "run_command",
+                ArgumentPredicate::new("command", "re:^kubectl get"),
+            );
+
+        assert_eq!(
+            policy.action_for(
+                "run_command",
+                Some(&serde_json::json!({"command": "kubectl get pods"}))
+            ),
+            ToolApprovalAction::Deny
+        );
+    }
+
+    #[test]
+    fn compaction_sizes_models_from_registry() {
+        let registry = stakai::parse_models_dev(
+            r#"{
+                "anthropic": {
+                    "name": "Anthropic",
+                    "models": {
+                        "claude-sonnet-4": {
+                            "id": "claude-sonnet-4",
+                            "name": "Claude Sonnet 4",
+                            "tool_call": true,
+                            "limit": { "context": 200000, "output": 64000 }
+                        }
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+        let compaction = CompactionConfig::default().with_model_registry(registry);
+
+        let known = compaction.sized_model(&stakai::Model::custom("claude-sonnet-4", "anthropic"));
+        assert_eq!(known.limit.context, 200_000);
+        assert_eq!(known.limit.output, 64_000);
+
+        let unknown = stakai::Model::custom("llama3", "ollama");
+        assert_eq!(compaction.sized_model(&unknown).limit, unknown.limit);
+    }
+
+    fn model_with_context(context: u64) -> stakai::Model {
+        let mut model = stakai::Model::custom("test-model", "test");
+        model.limit.context = context;
+        model
+    }
+
+    #[test]
+    fn compaction_trigger_scales_with_context_window() {
+        let compaction = CompactionConfig::default().with_trigger_ratio(0.8);
+
+        assert_eq!(
+            compaction.trigger_tokens_for(&model_with_context(200_000)),
+            Some(160_000)
+        );
+        assert_eq!(
+            compaction.trigger_tokens_for(&model_with_context(1_000_000)),
+            Some(800_000)
+        );
+        assert_eq!(
+            compaction.trigger_tokens_for(&model_with_context(8_192)),
+            Some(6_553)
+        );
+    }
+
+    #[test]
+    fn compaction_trigger_ratio_overrides_absolute_tokens() {
+        let compaction = CompactionConfig::default()
+            .with_trigger_tokens(50_000)
+            .with_trigger_ratio(0.5);
+        assert_eq!(
+            compaction.trigger_tokens_for(&model_with_context(128_000)),
+            Some(64_000)
+        );
+
+        // The ratio needs a known window; otherwise the absolute value applies.
+        assert_eq!(
+            compaction.trigger_tokens_for(&model_with_context(0)),
+            Some(50_000)
+        );
+        assert_eq!(
+            CompactionConfig::default().trigger_tokens_for(&model_with_context(128_000)),
+            None
+        );
+    }
+
+    #[test]
+    fn compaction_trigger_ratio_is_clamped() {
+        let model = model_with_context(100_000);
+
+        let too_high = CompactionConfig::default().with_trigger_ratio(1.5);
+        assert_eq!(too_high.trigger_tokens_for(&model), Some(95_000));
+
+        let too_low = CompactionConfig::default().with_trigger_ratio(0.0);
+        assert_eq!(too_low.trigger_tokens_for(&model), Some(10_000));
+
+        let not_a_number = CompactionConfig::default()
+            .with_trigger_tokens(70_000)
+            .with_trigger_ratio(f64::NAN);
+        assert_eq!(not_a_number.trigger_tokens_for(&model), Some(70_000));
+    }
+
+    #[test]
+    fn loop_detection_config_fills_unset_fields_with_defaults() {
+        let config: LoopDetectionConfig =
+            serde_json::from_value(serde_json::json!({"max_repeats": 5})).unwrap();
+        assert_eq!(
+            config,
+            LoopDetectionConfig {
+                enabled: true,
+                max_repeats: 5,
+            }
+        );
+        assert!(config.validate().is_ok());
+
+        let disabled: LoopDetectionConfig =
+            serde_json::from_value(serde_json::json!({"enabled": false})).unwrap();
+        assert!(!disabled.enabled);
+        assert_eq!(disabled.max_repeats, 3);
+
+        let never_repeats = LoopDetectionConfig {
+            max_repeats: 0,
+            ..LoopDetectionConfig::default()
+        };
+        assert!(never_repeats.validate().is_err());
+    }
+
+    #[test]
+    fn retry_budget_is_shared_across_clones() {
+        let run = AgentRunContext::new(Uuid::new_v4(), Uuid::new_v4()).with_retry_budget(2);
+        let clone = run.clone();
+
+        assert!(run.retry_budget.try_spend());
+        assert!(clone.retry_budget.try_spend());
+        assert!(!run.retry_budget.try_spend());
+        assert_eq!(clone.retry_budget.used(), 2);
+
+        let unlimited = RetryBudget::unlimited();
+        assert!((0..100).all(|_| unlimited.try_spend()));
+    }
 }
@@ -0,0 +1,163 @@
+//! Fixtures shared by the agent loop integration tests.
+
+#![allow(dead_code)]
+
+use async_trait::async_trait;
+use serde_json::json;
+use stakai::provider::Provider;
+use stakai::{
+    FinishReason, GenerateRequest, GenerateResponse, GenerateStream, Headers, Inference, Message,
+    Model, ModelLimit, ResponseContent, ToolCall, Usage,
+};
+use stakpak_agent_core::{
+    AgentConfig, AgentError, AgentRunContext, CompactionConfig, LoopDetectionConfig,
+    ProposedToolCall, RetryConfig, ToolApprovalPolicy, ToolExecutionResult, ToolExecutor,
+};
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+type Respond = dyn Fn(usize) -> stakai::Result<GenerateResponse> + Send + Sync;
+
+/// Answers each request with `respond(step)`, where `step` counts requests
+/// from 1, recording the messages of every request.
+pub struct ScriptedProvider {
+    requests: Arc<Mutex<Vec<Vec<Message>>>>,
+    respond: Box<Respond>,
+}
+
+impl ScriptedProvider {
+    pub fn new(
+        respond: impl Fn(usize) -> stakai::Result<GenerateResponse> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            requests: Arc::new(Mutex::new(Vec::new())),
+            respond: Box::new(respond),
+        }
+    }
+
+    /// The messages of every request so far, shared with the provider.
+    pub fn requests(&self) -> Arc<Mutex<Vec<Vec<Message>>>> {
+        self.requests.clone()
+    }
+}
+
+#[async_trait]
+impl Provider for ScriptedProvider {
+    fn provider_id(&self) -> &str {
+        "test"
+    }
+
+    fn build_headers(&self, _custom_headers: Option<&Headers>) -> Headers {
+        Headers::new()
+    }
+
+    async fn generate(&self, request: GenerateRequest) -> stakai::Result<GenerateResponse> {
+        let step = {
+            let mut requests = self.requests.lock().unwrap();
+            requests.push(request.messages);
+            requests.len()
+        };
+        (self.respond)(step)
+    }
+
+    async fn stream(&self, _request: GenerateRequest) -> stakai::Result<GenerateStream> {
+        Err(stakai::Error::ProviderError(
+            "streaming is not supported".to_string(),
+        ))
+    }
+}
+
+pub fn inference(provider: ScriptedProvider) -> Inference {
+    Inference::builder()
+        .register_provider("test", provider)
+        .build()
+        .unwrap()
+}
+
+/// A final answer.
+pub fn text_response(text: &str) -> GenerateResponse {
+    response(
+        ResponseContent::Text {
+            text: text.to_string(),
+        },
+        FinishReason::stop(),
+    )
+}
+
+/// A request to view the README, with a tool call id unique to `step`.
+pub fn view_readme_response(step: usize) -> GenerateResponse {
+    response(
+        ResponseContent::ToolCall(ToolCall {
+            id: format!("tc_{step}"),
+            name: "view".to_string(),
+            arguments: json!({"path": "README.md"}),
+            metadata: None,
+        }),
+        FinishReason::tool_calls(),
+    )
+}
+
+fn response(content: ResponseContent, finish_reason: FinishReason) -> GenerateResponse {
+    GenerateResponse {
+        content: vec![content],
+        usage: Usage::default(),
+        finish_reason,
+        metadata: None,
+        warnings: None,
+        model: None,
+    }
+}
+
+pub fn test_model() -> Model {
+    Model::new(
+        "test-model",
+        "Test Model",
+        "test",
+        false,
+        None,
+        ModelLimit::new(100_000, 1_000),
+    )
+}
+
+/// Approves every tool and otherwise uses the defaults.
+pub fn test_config(max_turns: usize) -> AgentConfig {
+    AgentConfig {
+        model: test_model(),
+        system_prompt: String::new(),
+        max_turns,
+        max_output_tokens: 1_000,
+        provider_options: None,
+        tool_approval: ToolApprovalPolicy::All,
+        retry: RetryConfig::default(),
+        compaction: CompactionConfig::default(),
+        loop_detection: LoopDetectionConfig::default(),
+        tools: Vec::new(),
+    }
+}
+
+/// What the README tool returns.
+pub fn readme_result() -> ToolExecutionResult {
+    ToolExecutionResult::Completed {
+        result: "# README".to_string(),
+        is_error: false,
+        error_kind: None,
+    }
+}
+
+/// Fails any tool call; for runs that should never reach a tool.
+pub struct NoTools;
+
+#[async_trait]
+impl ToolExecutor for NoTools {
+    async fn execute_tool_call(
+        &self,
+        _run: &AgentRunContext,
+        tool_call: &ProposedToolCall,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolExecutionResult, AgentError> {
+        Err(AgentError::ToolExecution(format!(
+            "unexpected tool call {}",
+            tool_call.name
+        )))
+    }
+}
@@ -0,0 +1,198 @@
+mod common;
+
+use async_trait::async_trait;
+use common::{
+    NoTools, ScriptedProvider, inference, readme_result, test_config, text_response,
+    view_readme_response,
+};
+use stakai::{Message, Model, ModelLimit, Role};
+use stakpak_agent_core::{
+    AgentConfig, AgentError, AgentHook, AgentRunContext, BudgetAwareContextReducer,
+    CompactionConfig, CompactionEngine, CompactionResult, PassthroughCompactionEngine,
+    ProposedToolCall, ToolExecutionResult, ToolExecutor, run_agent,
+};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Keeps only the newest message.
+struct KeepLastCompaction;
+
+#[async_trait]
+impl CompactionEngine for KeepLastCompaction {
+    async fn compact(
+        &self,
+        messages: Vec<Message>,
+        _model: &Model,
+    ) -> Result<CompactionResult, AgentError> {
+        let tokens_before = messages.len();
+        let messages: Vec<Message> = messages.into_iter().last().into_iter().collect();
+        Ok(CompactionResult {
+            tokens_after: messages.len(),
+            messages,
+            tokens_before,
+            truncated: true,
+        })
+    }
+}
+
+/// Answers every tool call with the README.
+struct ReadmeTool;
+
+#[async_trait]
+impl ToolExecutor for ReadmeTool {
+    async fn execute_tool_call(
+        &self,
+        _run: &AgentRunContext,
+        _tool_call: &ProposedToolCall,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolExecutionResult, AgentError> {
+        Ok(readme_result())
+    }
+}
+
+struct RecordedCompaction {
+    before: Vec<String>,
+    after: Vec<String>,
+    tokens_before: usize,
+    tokens_after: usize,
+}
+
+struct RecordingHook {
+    compactions: Arc<Mutex<Vec<RecordedCompaction>>>,
+}
+
+fn texts(messages: &[Message]) -> Vec<String> {
+    messages
+        .iter()
+        .map(|message| message.text().unwrap_or_default())
+        .collect()
+}
+
+#[async_trait]
+impl AgentHook for RecordingHook {
+    async fn on_compaction(
+        &self,
+        _run: &AgentRunContext,
+        before: &[Message],
+        after: &[Message],
+        result: &CompactionResult,
+    ) -> Result<(), AgentError> {
+        self.compactions.lock().unwrap().push(RecordedCompaction {
+            before: texts(before),
+            after: texts(after),
+            tokens_before: result.tokens_before,
+            tokens_after: result.tokens_after,
+        });
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn compaction_hook_observes_messages_before_and_after() {
+    let inference = inference(ScriptedProvider::new(|_| Ok(text_response("done"))));
+    let config = AgentConfig {
+        // Any context at all reaches the trigger, so the first turn compacts.
+        compaction: CompactionConfig::default().with_trigger_tokens(1),
+        ..test_config(4)
+    };
+
+    let compactions = Arc::new(Mutex::new(Vec::new()));
+    let hooks: Vec<Box<dyn AgentHook>> = vec![Box::new(RecordingHook {
+        compactions: compactions.clone(),
+    })];
+    let (event_tx, _event_rx) = mpsc::channel(64);
+    let (_command_tx, command_rx) = mpsc::channel(1);
+
+    run_agent(
+        AgentRunContext::new(Uuid::new_v4(), Uuid::new_v4()),
+        &inference,
+        &config,
+        vec![
+            Message::new(Role::User, "deploy the api"),
+            Message::new(Role::Assistant, "deployed"),
+        ],
+        &mut serde_json::json!({}),
+        Message::new(Role::User, "now check the logs"),
+        &NoTools,
+        &hooks,
+        event_tx,
+        command_rx,
+        CancellationToken::new(),
+        &KeepLastCompaction,
+        &BudgetAwareContextReducer::new(5, 0.8),
+    )
+    .await
+    .unwrap();
+
+    let compactions = compactions.lock().unwrap();
+    let first = compactions.first().unwrap();
+    assert_eq!(
+        first.before,
+        vec!["deploy the api", "deployed", "now check the logs"]
+    );
+    assert_eq!(first.after, vec!["now check the logs"]);
+    assert_eq!((first.tokens_before, first.tokens_after), (3, 1));
+}
+
+#[tokio::test]
+async fn compaction_hook_observes_every_context_reducer_trim() {
+    let inference = inference(ScriptedProvider::new(|step| match step {
+        1 => Ok(view_readme_response(step)),
+        _ => Ok(text_response("done")),
+    }));
+    let config = AgentConfig {
+        // Leaves 1,000 tokens of context, which the old answer alone exceeds.
+        model: Model::new(
+            "test-model",
+            "Test Model",
+            "test",
+            false,
+            None,
+            ModelLimit::new(2_000, 1_000),
+        ),
+        ..test_config(4)
+    };
+
+    let compactions = Arc::new(Mutex::new(Vec::new()));
+    let hooks: Vec<Box<dyn AgentHook>> = vec![Box::new(RecordingHook {
+        compactions: compactions.clone(),
+    })];
+    let (event_tx, _event_rx) = mpsc::channel(64);
+    let (_command_tx, command_rx) = mpsc::channel(1);
+    let old_answer = "deployed ".repeat(1_000);
+
+    run_agent(
+        AgentRunContext::new(Uuid::new_v4(), Uuid::new_v4()),
+        &inference,
+        &config,
+        vec![
+            Message::new(Role::User, "deploy the api"),
+            Message::new(Role::Assistant, old_answer.clone()),
+            Message::new(Role::User, "and the worker"),
+            Message::new(Role::Assistant, "deployed the worker"),
+        ],
+        &mut serde_json::json!({}),
+        Message::new(Role::User, "now check the logs"),
+        &ReadmeTool,
+        &hooks,
+        event_tx,
+        command_rx,
+        CancellationToken::new(),
+        &PassthroughCompactionEngine,
+        &BudgetAwareContextReducer::new(1, 0.8),
+    )
+    .await
+    .unwrap();
+
+    // Both requests are sent with the old answer trimmed, and both are reported
+    let compactions = compactions.lock().unwrap();
+    assert_eq!(compactions.len(), 2);
+    for trimmed in compactions.iter() {
+        assert_eq!(trimmed.before[1], old_answer);
+        assert_ne!(trimmed.after[1], old_answer);
+        assert!(trimmed.tokens_after < trimmed.tokens_before);
+    }
+    assert_eq!(compactions[0].after.last().unwrap(), "now check the logs");
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use async_trait::async_trait;
+use common::{
+    ScriptedProvider, inference, readme_result, test_config, text_response, view_readme_response,
+};
+use stakai::{Message, Role};
+use stakpak_agent_core::{
+    AgentCommand, AgentError, AgentRunContext, BudgetAwareContextReducer,
+    PassthroughCompactionEngine, ProposedToolCall, ToolExecutionResult, ToolExecutor, run_agent,
+};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Injects a message while its tool call is still running.
+struct InjectingTool {
+    command_tx: mpsc::Sender<AgentCommand>,
+}
+
+#[async_trait]
+impl ToolExecutor for InjectingTool {
+    async fn execute_tool_call(
+        &self,
+        _run: &AgentRunContext,
+        _tool_call: &ProposedToolCall,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolExecutionResult, AgentError> {
+        self.command_tx
+            .send(AgentCommand::InjectMessage(Message::new(
+                Role::User,
+                "also check the changelog",
+            )))
+            .await
+            .unwrap();
+        Ok(readme_result())
+    }
+}
+
+#[tokio::test]
+async fn injected_message_reaches_the_next_request_after_tool_results() {
+    // Asks for one tool call, then answers with text.
+    let provider = ScriptedProvider::new(|step| {
+        Ok(if step == 1 {
+            view_readme_response(step)
+        } else {
+            text_response("all done")
+        })
+    });
+    let requests = provider.requests();
+    let inference = inference(provider);
+    let config = test_config(5);
+    let (event_tx, _event_rx) = mpsc::channel(256);
+    let (command_tx, command_rx) = mpsc::channel(8);
+
+    let result = run_agent(
+        AgentRunContext::new(Uuid::new_v4(), Uuid::new_v4()),
+        &inference,
+        &config,
+        Vec::new(),
+        &mut serde_json::json!({}),
+        Message::new(Role::User, "read the readme"),
+        &InjectingTool { command_tx },
+        &[],
+        event_tx,
+        command_rx,
+        CancellationToken::new(),
+        &PassthroughCompactionEngine,
+        &BudgetAwareContextReducer::new(5, 0.8),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.total_turns, 2);
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(
+        !requests[0]
+            .iter()
+            .any(|message| message.text().as_deref() == Some("also check the changelog"))
+    );
+
+    // The tool turn stays intact: the injected message follows its result.
+    let step_two: Vec<(Role, Option<String>)> = requests[1]
+        .iter()
+        .filter(|message| message.role != Role::System)
+        .map(|message| (message.role, message.text()))
+        .collect();
+    let roles: Vec<Role> = step_two.iter().map(|(role, _)| *role).collect();
+    assert_eq!(
+        roles,
+        vec![Role::User, Role::Assistant, Role::Tool, Role::User]
+    );
+    assert_eq!(
+        step_two.last().and_then(|(_, text)| text.as_deref()),
+        Some("also check the changelog")
+    );
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use async_trait::async_trait;
+use common::{ScriptedProvider, inference, readme_result, test_config, view_readme_response};
+use stakai::{Message, Role};
+use stakpak_agent_core::{
+    AgentConfig, AgentError, AgentRunContext, BudgetAwareContextReducer, LoopDetectionConfig,
+    PassthroughCompactionEngine, ProposedToolCall, StopReason, ToolExecutionResult, ToolExecutor,
+    run_agent,
+};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Proposes the same tool call every turn.
+fn stuck_provider() -> ScriptedProvider {
+    ScriptedProvider::new(|step| Ok(view_readme_response(step)))
+}
+
+struct CountingTool {
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ToolExecutor for CountingTool {
+    async fn execute_tool_call(
+        &self,
+        _run: &AgentRunContext,
+        _tool_call: &ProposedToolCall,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolExecutionResult, AgentError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(readme_result())
+    }
+}
+
+#[tokio::test]
+async fn repeating_the_same_tool_call_stops_the_run() {
+    let provider = stuck_provider();
+    let requests = provider.requests();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let inference = inference(provider);
+    let config = AgentConfig {
+        loop_detection: LoopDetectionConfig {
+            enabled: true,
+            max_repeats: 2,
+        },
+        ..test_config(10)
+    };
+    let (event_tx, _event_rx) = mpsc::channel(256);
+    let (_command_tx, command_rx) = mpsc::channel(1);
+
+    let result = run_agent(
+        AgentRunContext::new(Uuid::new_v4(), Uuid::new_v4()),
+        &inference,
+        &config,
+        Vec::new(),
+        &mut serde_json::json!({}),
+        Message::new(Role::User, "read the readme"),
+        &CountingTool {
+            calls: calls.clone(),
+        },
+        &[],
+        event_tx,
+        command_rx,
+        CancellationToken::new(),
+        &PassthroughCompactionEngine,
+        &BudgetAwareContextReducer::new(5, 0.8),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.stop_reason, StopReason::LoopDetected);
+    assert_eq!(result.total_turns, 3);
+    // The third identical call is answered without running the tool.
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(
+        result.messages.last().map(|message| message.role),
+        Some(Role::Tool)
+    );
+
+    // The model was warned before its final repeat.
+    let requests = requests.lock().unwrap();
+    let warned = |messages: &[Message]| {
+        messages.iter().any(|message| {
+            message.role == Role::User
+                && message
+                    .text()
+                    .is_some_and(|text| text.starts_with("System note:"))
+        })
+    };
+    assert_eq!(requests.len(), 3);
+    assert!(!warned(&requests[1]));
+    assert!(warned(&requests[2]));
+}
+
+#[tokio::test]
+async fn disabled_loop_detection_runs_until_max_turns() {
+    let inference = inference(stuck_provider());
+    let config = AgentConfig {
+        loop_detection: LoopDetectionConfig {
+            enabled: false,
+            max_repeats: 1,
+        },
+        ..test_config(4)
+    };
+    let (event_tx, _event_rx) = mpsc::channel(256);
+    let (_command_tx, command_rx) = mpsc::channel(1);
+
+    let result = run_agent(
+        AgentRunContext::new(Uuid::new_v4(), Uuid::new_v4()),
+        &inference,
+        &config,
+        Vec::new(),
+        &mut serde_json::json!({}),
+        Message::new(Role::User, "read the readme"),
+        &CountingTool {
+            calls: Arc::new(AtomicUsize::new(0)),
+        },
+        &[],
+        event_tx,
+        command_rx,
+        CancellationToken::new(),
+        &PassthroughCompactionEngine,
+        &BudgetAwareContextReducer::new(5, 0.8),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.stop_reason, StopReason::MaxTurns);
+    assert_eq!(result.total_turns, 4);
+}
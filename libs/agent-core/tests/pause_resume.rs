@@ -0,0 +1,137 @@
+mod common;
+
+use async_trait::async_trait;
+use common::{ScriptedProvider, inference, readme_result, test_config, view_readme_response};
+use serde_json::json;
+use stakai::{Message, Role};
+use stakpak_agent_core::{
+    AgentCommand, AgentError, AgentEvent, AgentHook, AgentRunContext, BudgetAwareContextReducer,
+    PassthroughCompactionEngine, ProposedToolCall, StopReason, ToolExecutionResult, ToolExecutor,
+    run_agent,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Pauses the run from inside its first tool call.
+struct PausingTool {
+    command_tx: mpsc::Sender<AgentCommand>,
+    paused: AtomicBool,
+}
+
+#[async_trait]
+impl ToolExecutor for PausingTool {
+    async fn execute_tool_call(
+        &self,
+        _run: &AgentRunContext,
+        _tool_call: &ProposedToolCall,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolExecutionResult, AgentError> {
+        if !self.paused.swap(true, Ordering::SeqCst) {
+            self.command_tx.send(AgentCommand::Pause).await.unwrap();
+        }
+        Ok(readme_result())
+    }
+}
+
+struct CheckpointHook {
+    checkpoints: Arc<Mutex<Vec<Vec<Message>>>>,
+}
+
+#[async_trait]
+impl AgentHook for CheckpointHook {
+    async fn on_pause(
+        &self,
+        _run: &AgentRunContext,
+        messages: &[Message],
+    ) -> Result<(), AgentError> {
+        self.checkpoints.lock().unwrap().push(messages.to_vec());
+        Ok(())
+    }
+}
+
+fn roles(messages: &[Message]) -> Vec<Role> {
+    messages.iter().map(|message| message.role).collect()
+}
+
+#[tokio::test]
+async fn paused_run_holds_until_resumed() {
+    // Asks for a tool call every turn.
+    let provider = ScriptedProvider::new(|step| Ok(view_readme_response(step)));
+    let requests = provider.requests();
+    let checkpoints = Arc::new(Mutex::new(Vec::new()));
+    let inference = inference(provider);
+    let config = test_config(2);
+    let hooks: Vec<Box<dyn AgentHook>> = vec![Box::new(CheckpointHook {
+        checkpoints: checkpoints.clone(),
+    })];
+    let (event_tx, mut event_rx) = mpsc::channel(256);
+    let (command_tx, command_rx) = mpsc::channel(8);
+    let tool = PausingTool {
+        command_tx: command_tx.clone(),
+        paused: AtomicBool::new(false),
+    };
+
+    let mut metadata = json!({});
+    let context_reducer = BudgetAwareContextReducer::new(5, 0.8);
+    let run = run_agent(
+        AgentRunContext::new(Uuid::new_v4(), Uuid::new_v4()),
+        &inference,
+        &config,
+        Vec::new(),
+        &mut metadata,
+        Message::new(Role::User, "read the readme"),
+        &tool,
+        &hooks,
+        event_tx,
+        command_rx,
+        CancellationToken::new(),
+        &PassthroughCompactionEngine,
+        &context_reducer,
+    );
+
+    let operator = async {
+        let mut events = Vec::new();
+        while let Some(event) = event_rx.recv().await {
+            let paused = matches!(event, AgentEvent::RunPaused { turn: 1, .. });
+            events.push(event);
+            if paused {
+                break;
+            }
+        }
+
+        // The first step finished, but no further inference happens.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        command_tx.send(AgentCommand::Resume).await.unwrap();
+        while let Some(event) = event_rx.recv().await {
+            events.push(event);
+        }
+        events
+    };
+
+    let (result, events) = tokio::join!(run, operator);
+    let result = result.unwrap();
+    assert_eq!(result.stop_reason, StopReason::MaxTurns);
+    assert_eq!(result.total_turns, 2);
+    assert!(
+        events
+            .iter()
+            .any(|event| matches!(event, AgentEvent::RunResumed { turn: 1, .. }))
+    );
+
+    // Resuming picks up from the checkpoint taken at the pause.
+    let checkpoints = checkpoints.lock().unwrap();
+    assert_eq!(checkpoints.len(), 1);
+    assert_eq!(
+        roles(&checkpoints[0]),
+        vec![Role::User, Role::Assistant, Role::Tool]
+    );
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(roles(&requests[1]), roles(&checkpoints[0]));
+}
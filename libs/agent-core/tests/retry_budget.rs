@@ -0,0 +1,71 @@
+mod common;
+
+use common::{NoTools, ScriptedProvider, inference, test_config};
+use stakai::{Message, Role};
+use stakpak_agent_core::{
+    AgentConfig, AgentError, AgentEvent, AgentRunContext, BudgetAwareContextReducer,
+    PassthroughCompactionEngine, RetryConfig, run_agent,
+};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn repeated_rate_limits_exhaust_the_run_retry_budget() {
+    // Answers every request with a rate limit error.
+    let provider = ScriptedProvider::new(|_| {
+        Err(stakai::Error::RateLimitExceeded(
+            "too many requests".to_string(),
+        ))
+    });
+    let requests = provider.requests();
+    let inference = inference(provider);
+    let config = AgentConfig {
+        // Each request alone would be retried far more than the run allows.
+        retry: RetryConfig {
+            max_attempts: 10,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 1,
+            multiplier: 1.0,
+        },
+        ..test_config(4)
+    };
+    let run = AgentRunContext::new(Uuid::new_v4(), Uuid::new_v4()).with_retry_budget(2);
+    let (event_tx, mut event_rx) = mpsc::channel(64);
+    let (_command_tx, command_rx) = mpsc::channel(1);
+
+    let result = run_agent(
+        run.clone(),
+        &inference,
+        &config,
+        Vec::new(),
+        &mut serde_json::json!({}),
+        Message::new(Role::User, "deploy the api"),
+        &NoTools,
+        &[],
+        event_tx,
+        command_rx,
+        CancellationToken::new(),
+        &PassthroughCompactionEngine,
+        &BudgetAwareContextReducer::new(5, 0.8),
+    )
+    .await;
+
+    let error = result.unwrap_err();
+    assert!(matches!(error, AgentError::Inference(_)), "{error}");
+    assert!(error.to_string().contains("Rate limit"), "{error}");
+    assert_eq!(requests.lock().unwrap().len(), 3);
+    assert_eq!(run.retry_budget.used(), 2);
+
+    let mut retries = 0;
+    let mut exhausted = Vec::new();
+    while let Ok(event) = event_rx.try_recv() {
+        match event {
+            AgentEvent::RetryAttempt { .. } => retries += 1,
+            AgentEvent::RetryBudgetExhausted { retries, .. } => exhausted.push(retries),
+            _ => {}
+        }
+    }
+    assert_eq!(retries, 2);
+    assert_eq!(exhausted, vec![2]);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use async_trait::async_trait;
+use common::{
+    ScriptedProvider, inference, readme_result, test_config, text_response, view_readme_response,
+};
+use stakai::{Message, Role};
+use stakpak_agent_core::{
+    AgentError, AgentLoopResult, AgentRunContext, BudgetAwareContextReducer,
+    PassthroughCompactionEngine, ProposedToolCall, StopReason, ToolExecutionResult, ToolExecutor,
+    run_agent,
+};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+struct ViewTool;
+
+#[async_trait]
+impl ToolExecutor for ViewTool {
+    async fn execute_tool_call(
+        &self,
+        _run: &AgentRunContext,
+        _tool_call: &ProposedToolCall,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolExecutionResult, AgentError> {
+        Ok(readme_result())
+    }
+}
+
+async fn run(keep_working: bool, max_turns: usize, cancel: CancellationToken) -> AgentLoopResult {
+    // Replies with text, or asks for another tool call every turn when
+    // `keep_working` is set.
+    let inference = inference(ScriptedProvider::new(move |step| {
+        Ok(if keep_working {
+            view_readme_response(step)
+        } else {
+            text_response("all done")
+        })
+    }));
+    let config = test_config(max_turns);
+    let (event_tx, _event_rx) = mpsc::channel(256);
+    let (_command_tx, command_rx) = mpsc::channel(1);
+
+    run_agent(
+        AgentRunContext::new(Uuid::new_v4(), Uuid::new_v4()),
+        &inference,
+        &config,
+        Vec::new(),
+        &mut serde_json::json!({}),
+        Message::new(Role::User, "read the readme"),
+        &ViewTool,
+        &[],
+        event_tx,
+        command_rx,
+        cancel,
+        &PassthroughCompactionEngine,
+        &BudgetAwareContextReducer::new(5, 0.8),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn model_finishing_on_its_own_is_completed() {
+    let result = run(false, 5, CancellationToken::new()).await;
+    assert_eq!(result.stop_reason, StopReason::Completed);
+    assert_eq!(result.total_turns, 1);
+}
+
+#[tokio::test]
+async fn running_out_of_turns_is_max_turns() {
+    let result = run(true, 3, CancellationToken::new()).await;
+    assert_eq!(result.stop_reason, StopReason::MaxTurns);
+    assert_eq!(result.total_turns, 3);
+}
+
+#[tokio::test]
+async fn cancelled_run_is_cancelled() {
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    let result = run(true, 3, cancel).await;
+    assert_eq!(result.stop_reason, StopReason::Cancelled);
+}
@@ -1,13 +1,15 @@
 //! Inference client builder
 
 use super::{ClientConfig, Inference, InferenceConfig};
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::provider::Provider;
 use crate::providers::{
     anthropic::AnthropicProvider, gemini::GeminiProvider, openai::OpenAIProvider,
-    openrouter::OpenRouterProvider, stakpak::StakpakProvider,
+    openai_compatible::OpenAICompatibleProvider, openrouter::OpenRouterProvider,
+    stakpak::StakpakProvider,
 };
 use crate::registry::ProviderRegistry;
+use crate::types::Model;
 
 #[cfg(feature = "bedrock")]
 use crate::providers::bedrock::BedrockProvider;
@@ -17,6 +19,9 @@ use crate::providers::bedrock::BedrockProvider;
 pub struct ClientBuilder {
     registry: Option<ProviderRegistry>,
     config: ClientConfig,
+    fallback_models: Vec<Model>,
+    /// First provider that failed to build, reported by [`ClientBuilder::build`]
+    error: Option<Error>,
 }
 
 impl ClientBuilder {
@@ -43,40 +48,43 @@ impl ClientBuilder {
     /// ```
     pub fn with_inference_config(mut self, inference_config: InferenceConfig) -> Self {
         let mut registry = self.registry.take().unwrap_or_default();
+        let http_options = inference_config.http_client_options();
 
         // Register OpenAI if configured
-        if let Some(config) = inference_config.openai_config
-            && let Ok(provider) = OpenAIProvider::new(config)
-        {
-            registry = registry.register("openai", provider);
+        if let Some(config) = inference_config.openai_config {
+            let provider = OpenAIProvider::with_http_options(config, &http_options);
+            registry = self.register_configured(registry, "openai", provider);
         }
 
         // Register Anthropic if configured
-        if let Some(config) = inference_config.anthropic_config
-            && let Ok(provider) = AnthropicProvider::new(config)
-        {
-            registry = registry.register("anthropic", provider);
+        if let Some(config) = inference_config.anthropic_config {
+            let provider = AnthropicProvider::with_http_options(config, &http_options);
+            registry = self.register_configured(registry, "anthropic", provider);
         }
 
         // Register Gemini if configured
-        if let Some(config) = inference_config.gemini_config
-            && let Ok(provider) = GeminiProvider::new(config)
-        {
-            registry = registry.register("google", provider);
+        if let Some(config) = inference_config.gemini_config {
+            let provider = GeminiProvider::with_http_options(config, &http_options);
+            registry = self.register_configured(registry, "google", provider);
         }
 
         // Register Stakpak if configured
-        if let Some(config) = inference_config.stakpak_config
-            && let Ok(provider) = StakpakProvider::new(config)
-        {
-            registry = registry.register("stakpak", provider);
+        if let Some(config) = inference_config.stakpak_config {
+            let provider = StakpakProvider::with_http_options(config, &http_options);
+            registry = self.register_configured(registry, "stakpak", provider);
         }
 
         // Register OpenRouter if configured
-        if let Some(config) = inference_config.openrouter_config
-            && let Ok(provider) = OpenRouterProvider::new(config)
-        {
-            registry = registry.register("openrouter", provider);
+        if let Some(config) = inference_config.openrouter_config {
+            let provider = OpenRouterProvider::with_http_options(config, &http_options);
+            registry = self.register_configured(registry, "openrouter", provider);
+        }
+
+        // Register OpenAI-compatible backends under their own provider IDs
+        for config in inference_config.openai_compatible_configs {
+            let provider_id = config.provider_id.clone();
+            let provider = OpenAICompatibleProvider::with_http_options(config, &http_options);
+            registry = self.register_configured(registry, provider_id, provider);
         }
 
         // Register Bedrock if configured
@@ -88,9 +96,33 @@ impl ClientBuilder {
 
         self.registry = Some(registry);
         self.config = inference_config.client_config;
+        self.fallback_models = inference_config.fallback_models;
         self
     }
 
+    /// Register a provider built from the inference config. Providers
+    /// configured without an API key are skipped; any other failure, such as
+    /// an unreadable CA bundle or an invalid proxy, is kept so that
+    /// [`ClientBuilder::build`] reports it instead of dropping the provider.
+    fn register_configured<P: Provider + 'static>(
+        &mut self,
+        registry: ProviderRegistry,
+        id: impl Into<String>,
+        provider: Result<P>,
+    ) -> ProviderRegistry {
+        match provider {
+            Ok(provider) => registry.register(id, provider),
+            Err(Error::MissingApiKey(_)) => registry,
+            Err(error) => {
+                let id = id.into();
+                self.error.get_or_insert(Error::ConfigError(format!(
+                    "Failed to configure provider {id}: {error}"
+                )));
+                registry
+            }
+        }
+    }
+
     /// Use a custom provider registry
     pub fn with_registry(mut self, registry: ProviderRegistry) -> Self {
         self.registry = Some(registry);
@@ -114,6 +146,12 @@ impl ClientBuilder {
         self
     }
 
+    /// Set the models to fail over to when the requested model fails
+    pub fn with_fallback_models(mut self, models: Vec<Model>) -> Self {
+        self.fallback_models = models;
+        self
+    }
+
     /// Set default temperature
     pub fn with_temperature(mut self, temperature: f32) -> Self {
         self.config.default_temperature = Some(temperature);
@@ -127,10 +165,17 @@ impl ClientBuilder {
     }
 
     /// Build the inference client
+    ///
+    /// Fails if a provider from [`ClientBuilder::with_inference_config`]
+    /// could not be constructed.
     pub fn build(self) -> Result<Inference> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
         Ok(Inference {
             registry: self.registry.unwrap_or_default(),
             config: self.config,
+            fallback_models: self.fallback_models,
         })
     }
 }
@@ -1,10 +1,18 @@
 //! Client configuration
 
 use crate::providers::{
-    anthropic::AnthropicConfig, gemini::GeminiConfig, openai::OpenAIConfig,
-    openrouter::OpenRouterConfig, stakpak::StakpakProviderConfig,
+    anthropic::AnthropicConfig,
+    gemini::GeminiConfig,
+    openai::OpenAIConfig,
+    openai_compatible::OpenAICompatibleConfig,
+    openrouter::OpenRouterConfig,
+    stakpak::StakpakProviderConfig,
+    tls::{HttpClientOptions, ProxyConfig},
 };
 
+use crate::types::Model;
+use std::path::PathBuf;
+
 #[cfg(feature = "bedrock")]
 use crate::providers::bedrock::BedrockConfig;
 
@@ -68,9 +76,13 @@ pub struct InferenceConfig {
     pub(crate) gemini_config: Option<GeminiConfig>,
     pub(crate) stakpak_config: Option<StakpakProviderConfig>,
     pub(crate) openrouter_config: Option<OpenRouterConfig>,
+    pub(crate) openai_compatible_configs: Vec<OpenAICompatibleConfig>,
     #[cfg(feature = "bedrock")]
     pub(crate) bedrock_config: Option<BedrockConfig>,
     pub(crate) client_config: ClientConfig,
+    pub(crate) proxy: Option<ProxyConfig>,
+    pub(crate) extra_ca_certs: Vec<PathBuf>,
+    pub(crate) fallback_models: Vec<Model>,
 }
 
 impl InferenceConfig {
@@ -216,6 +228,71 @@ impl InferenceConfig {
         self
     }
 
+    /// Route all provider requests through an explicit HTTP(S) proxy
+    ///
+    /// When unset, the standard `HTTPS_PROXY` / `HTTP_PROXY` / `NO_PROXY`
+    /// environment variables are honored instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use stakai::{InferenceConfig, ProxyConfig};
+    /// let config = InferenceConfig::new()
+    ///     .anthropic("sk-ant-...", None)
+    ///     .proxy(ProxyConfig::new("http://proxy.corp.example:3128").with_basic_auth("user", "pass"));
+    /// ```
+    pub fn proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Trust an additional PEM CA bundle for provider TLS connections
+    ///
+    /// Useful behind TLS-inspecting proxies whose CA is not in the OS store.
+    /// Bundles listed in `STAKAI_EXTRA_CA_CERTS` are trusted as well.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use stakai::InferenceConfig;
+    /// let config = InferenceConfig::new()
+    ///     .anthropic("sk-ant-...", None)
+    ///     .extra_ca_cert("/etc/ssl/corp-root-ca.pem");
+    /// ```
+    pub fn extra_ca_cert(mut self, path: impl Into<PathBuf>) -> Self {
+        self.extra_ca_certs.push(path.into());
+        self
+    }
+
+    /// Models to fail over to, in order, when the requested model fails
+    ///
+    /// Only retryable errors (rate limits, 5xx responses, timeouts and
+    /// connection failures) trigger a failover; the model that served the
+    /// response is reported in `GenerateResponse::model`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use stakai::{InferenceConfig, Model};
+    /// let config = InferenceConfig::new()
+    ///     .anthropic("sk-ant-...", None)
+    ///     .openai("sk-...", None)
+    ///     .fallback_models(vec![Model::custom("gpt-4.1", "openai")]);
+    /// ```
+    pub fn fallback_models(mut self, models: Vec<Model>) -> Self {
+        self.fallback_models = models;
+        self
+    }
+
+    /// Transport options applied to every provider's HTTP client
+    pub(crate) fn http_client_options(&self) -> HttpClientOptions {
+        HttpClientOptions {
+            proxy: self.proxy.clone(),
+            extra_ca_certs: self.extra_ca_certs.clone(),
+            read_timeout: None,
+        }
+    }
+
     /// Configure Stakpak provider with API key and optional base URL
     ///
     /// Stakpak provides unified access to multiple LLM providers through
@@ -299,6 +376,27 @@ impl InferenceConfig {
         self
     }
 
+    /// Add an OpenAI-compatible provider (vLLM, LiteLLM, Together, Groq, ...)
+    ///
+    /// The provider is registered under `config.provider_id`, so several
+    /// compatible backends can be configured side by side.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use stakai::{InferenceConfig, providers::openai_compatible::OpenAICompatibleConfig};
+    /// let groq = OpenAICompatibleConfig::new("groq", "https://api.groq.com/openai/v1")
+    ///     .with_api_key("gsk-...")
+    ///     .with_stream_options_support(false);
+    ///
+    /// let config = InferenceConfig::new()
+    ///     .openai_compatible(groq);
+    /// ```
+    pub fn openai_compatible(mut self, config: OpenAICompatibleConfig) -> Self {
+        self.openai_compatible_configs.push(config);
+        self
+    }
+
     /// Configure AWS Bedrock provider with a region
     ///
     /// Uses the AWS credential chain for authentication (env vars, shared credentials,
@@ -6,9 +6,11 @@ mod config;
 pub use builder::ClientBuilder;
 pub use config::{ClientConfig, InferenceConfig};
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::registry::ProviderRegistry;
-use crate::types::{GenerateRequest, GenerateResponse, GenerateStream};
+use crate::types::{GenerateRequest, GenerateResponse, GenerateStream, Model};
+use futures::StreamExt;
+use std::time::Duration;
 
 #[cfg(feature = "tracing")]
 use tracing::Instrument;
@@ -22,6 +24,7 @@ pub struct Inference {
     registry: ProviderRegistry,
     #[allow(dead_code)]
     config: ClientConfig,
+    fallback_models: Vec<Model>,
 }
 
 impl Inference {
@@ -198,10 +201,31 @@ impl Inference {
         self.generate_internal(request).await
     }
 
-    /// Internal generate implementation
+    /// Internal generate implementation, failing over to the fallback models
     async fn generate_internal(&self, request: &GenerateRequest) -> Result<GenerateResponse> {
-        let provider = self.registry.get_provider(&request.model.provider)?;
-        provider.generate(request.clone()).await
+        let mut candidates = self.candidate_models(&request.model).peekable();
+        loop {
+            let Some(model) = candidates.next() else {
+                return Err(Error::ConfigError("No model to generate with".to_string()));
+            };
+            let mut attempt = request.clone();
+            attempt.model = model.clone();
+
+            let result = match self.registry.get_provider(&model.provider) {
+                Ok(provider) => provider.generate(attempt).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(mut response) => {
+                    response.model = Some(model.clone());
+                    return Ok(response);
+                }
+                Err(e) if e.is_retryable() && candidates.peek().is_some() => {
+                    log_failover(model, &e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 
     /// Generate a streaming response
@@ -312,10 +336,118 @@ impl Inference {
         self.stream_internal(request).await
     }
 
-    /// Internal stream implementation
+    /// Internal stream implementation, failing over to the fallback models
+    ///
+    /// Only failures to open the stream fail over; errors after the first
+    /// event are returned from the stream itself.
     async fn stream_internal(&self, request: &GenerateRequest) -> Result<GenerateStream> {
-        let provider = self.registry.get_provider(&request.model.provider)?;
-        provider.stream(request.clone()).await
+        let mut candidates = self.candidate_models(&request.model).peekable();
+        loop {
+            let Some(model) = candidates.next() else {
+                return Err(Error::ConfigError("No model to stream with".to_string()));
+            };
+            let mut attempt = request.clone();
+            attempt.model = model.clone();
+
+            let result = match self.registry.get_provider(&model.provider) {
+                Ok(provider) => provider.stream(attempt).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Err(e) if e.is_retryable() && candidates.peek().is_some() => {
+                    log_failover(model, &e);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Generate responses for many independent requests
+    ///
+    /// At most `concurrency` requests are in flight at once (0 is treated as
+    /// 1). Results are returned in the order of `requests`, and a failed
+    /// request does not affect the rest of the batch.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use stakai::{Inference, GenerateRequest, Message, Model, Role};
+    /// # async fn example() {
+    /// let client = Inference::new();
+    /// let requests: Vec<_> = ["Hi", "Hello"]
+    ///     .iter()
+    ///     .map(|prompt| {
+    ///         GenerateRequest::new(
+    ///             Model::custom("gpt-4", "openai"),
+    ///             vec![Message::new(Role::User, *prompt)],
+    ///         )
+    ///     })
+    ///     .collect();
+    /// for result in client.generate_batch(&requests, 8).await {
+    ///     match result {
+    ///         Ok(response) => println!("{}", response.text()),
+    ///         Err(error) => eprintln!("request failed: {error}"),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn generate_batch(
+        &self,
+        requests: &[GenerateRequest],
+        concurrency: usize,
+    ) -> Vec<Result<GenerateResponse>> {
+        self.run_batch(requests, concurrency, None).await
+    }
+
+    /// Like [`generate_batch`](Self::generate_batch), but fails any request
+    /// that takes longer than `timeout` with [`Error::NetworkError`]
+    pub async fn generate_batch_with_timeout(
+        &self,
+        requests: &[GenerateRequest],
+        concurrency: usize,
+        timeout: Duration,
+    ) -> Vec<Result<GenerateResponse>> {
+        self.run_batch(requests, concurrency, Some(timeout)).await
+    }
+
+    async fn run_batch(
+        &self,
+        requests: &[GenerateRequest],
+        concurrency: usize,
+        timeout: Option<Duration>,
+    ) -> Vec<Result<GenerateResponse>> {
+        let mut results: Vec<(usize, Result<GenerateResponse>)> =
+            futures::stream::iter(requests.iter().enumerate())
+                .map(|(index, request)| async move {
+                    let result = match timeout {
+                        Some(timeout) => tokio::time::timeout(timeout, self.generate(request))
+                            .await
+                            .unwrap_or_else(|_| {
+                                Err(Error::NetworkError(format!(
+                                    "Request timed out after {:?}",
+                                    timeout
+                                )))
+                            }),
+                        None => self.generate(request).await,
+                    };
+                    (index, result)
+                })
+                .buffer_unordered(concurrency.max(1))
+                .collect()
+                .await;
+
+        // Requests finish out of order; restore the input order
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// The requested model followed by the fallback models, without repeats
+    fn candidate_models<'a>(&'a self, requested: &'a Model) -> impl Iterator<Item = &'a Model> {
+        std::iter::once(requested).chain(
+            self.fallback_models.iter().filter(move |model| {
+                model.id != requested.id || model.provider != requested.provider
+            }),
+        )
     }
 
     /// Get the provider registry
@@ -324,6 +456,18 @@ impl Inference {
     }
 }
 
+fn log_failover(model: &Model, error: &Error) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(
+        provider = %model.provider,
+        model = %model.id,
+        error = %error,
+        "model failed with a retryable error, trying the next fallback model"
+    );
+    #[cfg(not(feature = "tracing"))]
+    let _ = (model, error);
+}
+
 impl Default for Inference {
     fn default() -> Self {
         Self::new()
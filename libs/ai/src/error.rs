@@ -52,6 +52,10 @@ pub enum Error {
     #[error("Configuration error: {0}")]
     ConfigError(String),
 
+    /// Request failed validation before being sent
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
     /// Network error (fetch failed, timeout, etc.)
     #[error("Network error: {0}")]
     NetworkError(String),
@@ -72,11 +76,64 @@ impl Error {
         Self::InvalidResponse(msg.into())
     }
 
+    /// Create a new invalid request error
+    pub fn invalid_request(msg: impl Into<String>) -> Self {
+        Self::InvalidRequest(msg.into())
+    }
+
     /// Create a new stream error
     pub fn stream_error(msg: impl Into<String>) -> Self {
         Self::StreamError(msg.into())
     }
 
+    /// Whether the request may succeed if sent again or to another model.
+    ///
+    /// True for rate limits, timeouts, connection failures and 5xx responses.
+    /// Providers report HTTP failures as `"... error <status>: <body>"`; the
+    /// status is read from that position only, never from the body.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimitExceeded(_) | Self::NetworkError(_) => true,
+            Self::HttpError(e) => {
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.status()
+                        .is_some_and(|status| is_retryable_status(status.as_u16()))
+            }
+            Self::ProviderError(message) | Self::StreamError(message) => {
+                if let Some(status) = first_status_code(message) {
+                    return is_retryable_status(status);
+                }
+                let message = message.to_lowercase();
+                [
+                    "rate limit",
+                    "overloaded",
+                    "timed out",
+                    "timeout",
+                    "service unavailable",
+                    "internal server error",
+                ]
+                .iter()
+                .any(|needle| message.contains(needle))
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether this error is the provider rejecting the request for exceeding
+    /// a rate limit (HTTP 429), as opposed to any other failure.
+    pub fn is_rate_limited(&self) -> bool {
+        match self {
+            Self::RateLimitExceeded(_) => true,
+            Self::HttpError(e) => e.status().is_some_and(|status| status.as_u16() == 429),
+            Self::ProviderError(message) | Self::StreamError(message) => {
+                first_status_code(message) == Some(429)
+                    || message.to_lowercase().contains("rate limit")
+            }
+            _ => false,
+        }
+    }
+
     /// Try to parse raw stream data as a provider error response.
     ///
     /// When an upstream provider (Claude, OpenAI, Gemini, etc.) is down or
@@ -106,3 +163,44 @@ impl Error {
         Self::StreamError(format!("{}: {}", fallback_label, preview))
     }
 }
+
+fn is_retryable_status(status: u16) -> bool {
+    status == 408 || status == 429 || (500..=599).contains(&status)
+}
+
+/// Where providers put the HTTP status in their error messages, e.g.
+/// `"Anthropic API error 503 Service Unavailable: ..."` or `"(HTTP 401 ...)"`.
+const STATUS_PREFIXES: [&str; 4] = ["error ", "http ", "status: ", "status "];
+
+/// The HTTP status code (`100`-`599`) a provider reported in an error message.
+///
+/// Only a code right after one of [`STATUS_PREFIXES`] counts, so numbers in
+/// the response body (token counts, ids, sizes) are never mistaken for it.
+fn first_status_code(message: &str) -> Option<u16> {
+    let message = message.to_ascii_lowercase();
+    STATUS_PREFIXES
+        .iter()
+        .flat_map(|prefix| {
+            message
+                .match_indices(prefix)
+                .map(move |(index, _)| index + prefix.len())
+        })
+        .filter_map(|start| {
+            let status = message.get(start..).and_then(leading_status_code)?;
+            Some((start, status))
+        })
+        .min_by_key(|(start, _)| *start)
+        .map(|(_, status)| status)
+}
+
+/// A three-digit status code at the very start of `text`.
+fn leading_status_code(text: &str) -> Option<u16> {
+    let digits: String = text.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if digits.len() != 3 {
+        return None;
+    }
+    digits
+        .parse::<u16>()
+        .ok()
+        .filter(|status| (100..=599).contains(status))
+}
@@ -38,6 +38,7 @@ pub mod error;
 pub mod provider;
 pub mod providers;
 pub mod registry;
+pub mod tokenizer;
 pub mod types;
 
 #[cfg(feature = "tracing")]
@@ -46,14 +47,17 @@ pub mod tracing;
 // Re-export commonly used types
 pub use client::{Inference, InferenceConfig};
 pub use error::{Error, Result};
+pub use providers::ProxyConfig;
 pub use registry::{
     ProviderRegistry,
     models_dev::{
         DEFAULT_CACHE_PATH, MODELS_DEV_URL, ProviderInfo, fetch_models_dev,
         filter_configured_providers, get_available_models, load_available_models,
-        load_models_for_provider, load_models_for_provider_from_path, parse_models_dev,
+        load_models_dev_cache, load_models_for_provider, load_models_for_provider_from_path,
+        parse_models_dev,
     },
 };
+pub use tokenizer::{TokenCount, TokenCountAccuracy};
 pub use types::{
     // Cache control types
     AnthropicCacheConfig,
@@ -64,6 +68,7 @@ pub use types::{
     AnthropicOptions,
     // Options types
     AnthropicToolOptions,
+    AutoCachePlan,
     CacheContext,
     CacheControl,
     CacheControlValidator,
@@ -78,6 +83,7 @@ pub use types::{
     FinishReasonKind,
     GenerateOptions,
     GenerateRequest,
+    GenerateRequestBuilder,
     GenerateResponse,
     GenerateStream,
     GoogleOptions,
@@ -8,9 +8,9 @@ use super::types::{
 };
 use crate::error::{Error, Result};
 use crate::types::{
-    CacheContext, CacheControlValidator, CacheWarning, CacheWarningType, ContentPart, FinishReason,
-    FinishReasonKind, GenerateRequest, GenerateResponse, InputTokenDetails, Message,
-    OutputTokenDetails, ResponseContent, Role, Usage,
+    AutoCachePlan, CacheContext, CacheControlValidator, CacheStrategy, CacheWarning,
+    CacheWarningType, ContentPart, FinishReason, FinishReasonKind, GenerateRequest,
+    GenerateResponse, InputTokenDetails, Message, OutputTokenDetails, ResponseContent, Role, Usage,
 };
 use serde_json::json;
 use std::collections::{HashMap, HashSet};
@@ -58,7 +58,14 @@ pub fn to_anthropic_request(
         .clone()
         .unwrap_or_else(|| config.default_cache_strategy.clone());
 
-    let cache_config = cache_strategy.to_anthropic_config();
+    // Auto analyzes the request and records any breakpoint it had to skip
+    let (cache_config, cache_decisions) = match &cache_strategy {
+        CacheStrategy::Auto => {
+            let plan = AutoCachePlan::for_request(req);
+            (Some(plan.config), plan.decisions)
+        }
+        other => (other.to_anthropic_config(), Vec::new()),
+    };
 
     // Check if we have tools (for cache budget calculation)
     let has_tools = req.options.tools.as_ref().is_some_and(|t| !t.is_empty());
@@ -130,7 +137,8 @@ pub fn to_anthropic_request(
     });
 
     let has_cache_control = validator.breakpoint_count() > 0;
-    let mut warnings = validator.take_warnings();
+    let mut warnings = cache_decisions;
+    warnings.extend(validator.take_warnings());
 
     // top_k is already None at the struct level; only cover temperature/top_p on input.
     let (temperature, top_p) = if is_opus_47 {
@@ -545,10 +553,9 @@ fn sanitize_message_sequence(messages: &mut Vec<AnthropicMessage>) {
     // the optional final assistant message"
     remove_empty_content_messages(messages);
 
-    // Step 5: Re-merge consecutive same-role messages that may have been
-    // introduced by insertions/removals in steps 1-4.
-    let re_merged = merge_consecutive_messages(std::mem::take(messages));
-    *messages = re_merged;
+    // Step 5: Restore strict user/assistant alternation that may have been
+    // broken by insertions/removals in steps 1-4.
+    ensure_role_alternation(messages);
 
     // Step 6: Ensure the first message is role="user".
     if messages.first().is_some_and(|m| m.role != "user") {
@@ -1035,6 +1042,28 @@ fn merge_consecutive_messages(messages: Vec<AnthropicMessage>) -> Vec<AnthropicM
     result
 }
 
+/// Guarantee strict user/assistant alternation.
+///
+/// Same-role runs are merged into a single message. Within merged user
+/// messages, `tool_result` blocks are moved ahead of any other content
+/// (stable order otherwise): Anthropic requires tool results to come first
+/// in the user turn that answers a `tool_use`, and a run like
+/// `[user: "wait", user: tool_result]` — common after context reduction
+/// drops the assistant turn in between — would otherwise merge text-first.
+fn ensure_role_alternation(messages: &mut Vec<AnthropicMessage>) {
+    *messages = merge_consecutive_messages(std::mem::take(messages));
+
+    for msg in messages.iter_mut().filter(|m| m.role == "user") {
+        if let AnthropicMessageContent::Blocks(blocks) = &mut msg.content {
+            let (mut ordered, rest): (Vec<_>, Vec<_>) = std::mem::take(blocks)
+                .into_iter()
+                .partition(|b| matches!(b, AnthropicContent::ToolResult { .. }));
+            ordered.extend(rest);
+            *blocks = ordered;
+        }
+    }
+}
+
 /// Convert AnthropicMessageContent to a Vec<AnthropicContent> blocks.
 fn content_to_blocks(content: AnthropicMessageContent) -> Vec<AnthropicContent> {
     match content {
@@ -1266,7 +1295,8 @@ pub fn from_anthropic_response_with_warnings(
         })
         .collect();
 
-    if content.is_empty() {
+    // A refusal may legitimately come back without any content
+    if content.is_empty() && resp.stop_reason.as_deref() != Some("refusal") {
         return Err(Error::invalid_response("No content in response"));
     }
 
@@ -1332,6 +1362,7 @@ pub fn from_anthropic_response_with_warnings(
             "model": resp.model,
         })),
         warnings: response_warnings,
+        model: None,
     })
 }
 
@@ -1342,6 +1373,8 @@ fn parse_stop_reason(reason: &Option<String>) -> FinishReason {
         Some("max_tokens") => FinishReason::with_raw(FinishReasonKind::Length, "max_tokens"),
         Some("stop_sequence") => FinishReason::with_raw(FinishReasonKind::Stop, "stop_sequence"),
         Some("tool_use") => FinishReason::with_raw(FinishReasonKind::ToolCalls, "tool_use"),
+        // Claude declined to continue for safety reasons
+        Some("refusal") => FinishReason::with_raw(FinishReasonKind::ContentFilter, "refusal"),
         Some(raw) => FinishReason::with_raw(FinishReasonKind::Other, raw),
         None => FinishReason::other(),
     }
@@ -1361,6 +1394,27 @@ mod tests {
         assert_eq!(infer_max_tokens("claude-3-opus"), 4096);
     }
 
+    #[test]
+    fn test_refusal_maps_to_content_filter() {
+        let resp: AnthropicResponse = serde_json::from_value(serde_json::json!({
+            "id": "msg_refusal",
+            "type": "message",
+            "role": "assistant",
+            "content": [],
+            "model": "claude-sonnet-4-5",
+            "stop_reason": "refusal",
+            "usage": { "input_tokens": 12, "output_tokens": 0 }
+        }))
+        .unwrap();
+
+        let result = from_anthropic_response_with_warnings(resp, vec![]).unwrap();
+        assert_eq!(
+            result.finish_reason.unified,
+            FinishReasonKind::ContentFilter
+        );
+        assert_eq!(result.finish_reason.raw.as_deref(), Some("refusal"));
+    }
+
     #[test]
     fn test_parse_image_source() {
         let data_url = "data:image/png;base64,iVBORw0KGgoAAAANS";
@@ -2123,6 +2177,138 @@ mod tests {
         );
     }
 
+    // --- role alternation tests ---
+
+    /// Assert the structural rules Anthropic enforces on the messages array
+    fn assert_valid_anthropic_sequence(messages: &[AnthropicMessage]) {
+        assert_eq!(messages.first().map(|m| m.role.as_str()), Some("user"));
+        for pair in messages.windows(2) {
+            assert_ne!(
+                pair[0].role, pair[1].role,
+                "roles must alternate: {messages:?}"
+            );
+        }
+        for (i, msg) in messages.iter().enumerate() {
+            let tool_use_ids = extract_tool_use_ids(msg);
+            if tool_use_ids.is_empty() {
+                continue;
+            }
+            let next = &messages[i + 1];
+            let AnthropicMessageContent::Blocks(blocks) = &next.content else {
+                panic!("tool_use must be answered by a blocks message: {next:?}");
+            };
+            let leading_results: Vec<&str> = blocks
+                .iter()
+                .map_while(|b| match b {
+                    AnthropicContent::ToolResult { tool_use_id, .. } => Some(tool_use_id.as_str()),
+                    _ => None,
+                })
+                .collect();
+            for id in &tool_use_ids {
+                assert!(
+                    leading_results.contains(&id.as_str()),
+                    "tool_result for {id} must lead the next user message: {blocks:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_alternation_merges_consecutive_user_messages() {
+        let mut messages = vec![user_msg("first"), user_msg("second"), assistant_msg("ok")];
+        messages.push(user_msg("third"));
+
+        sanitize_message_sequence(&mut messages);
+
+        assert_valid_anthropic_sequence(&messages);
+        assert_eq!(messages.len(), 3);
+    }
+
+    #[test]
+    fn test_alternation_keeps_tool_results_first_after_merge() {
+        // A user interjection landed between the tool call and its result.
+        // Mirror the conversion pipeline: merge first, then sanitize.
+        let messages = vec![
+            user_msg("deploy it"),
+            assistant_blocks_msg(vec![tool_use_block("t1", "run_command")]),
+            user_msg("actually, wait"),
+            user_blocks_msg(vec![tool_result_block("t1", "deployed")]),
+        ];
+        let mut messages = merge_consecutive_messages(messages);
+
+        sanitize_message_sequence(&mut messages);
+
+        assert_valid_anthropic_sequence(&messages);
+        match &messages[2].content {
+            AnthropicMessageContent::Blocks(blocks) => {
+                assert!(matches!(
+                    &blocks[0],
+                    AnthropicContent::ToolResult { content: Some(AnthropicMessageContent::String(c)), .. }
+                        if c == "deployed"
+                ));
+                assert!(
+                    matches!(&blocks[1], AnthropicContent::Text { text, .. } if text == "actually, wait")
+                );
+            }
+            other => panic!("expected blocks, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_alternation_after_empty_assistant_removed() {
+        let mut messages = vec![
+            user_msg("hello"),
+            assistant_msg(""),
+            user_msg("are you there?"),
+            assistant_msg("yes"),
+            assistant_msg("still here"),
+        ];
+
+        sanitize_message_sequence(&mut messages);
+
+        assert_valid_anthropic_sequence(&messages);
+        // Substantive trailing assistant is kept as prefill, merged into one turn
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].role, "assistant");
+    }
+
+    #[test]
+    fn test_request_with_malformed_history_is_valid() {
+        use crate::types::{Message as UnifiedMessage, Model, Role as UnifiedRole};
+
+        let tool_call = UnifiedMessage::new(
+            UnifiedRole::Assistant,
+            vec![ContentPart::tool_call(
+                "t1",
+                "run_command",
+                json!({"cmd": "ls"}),
+            )],
+        );
+        let tool_result = UnifiedMessage::new(
+            UnifiedRole::Tool,
+            vec![ContentPart::tool_result("t1", json!("file.txt"))],
+        );
+        let req = GenerateRequest::new(
+            Model::custom("claude-sonnet-4-5", "anthropic"),
+            vec![
+                UnifiedMessage::new(UnifiedRole::User, "list files"),
+                UnifiedMessage::new(UnifiedRole::User, "in the current dir"),
+                tool_call,
+                UnifiedMessage::new(UnifiedRole::User, "hurry"),
+                tool_result,
+                UnifiedMessage::new(UnifiedRole::Assistant, "Found file.txt"),
+                UnifiedMessage::new(UnifiedRole::Assistant, "Anything else?"),
+                UnifiedMessage::new(UnifiedRole::User, "no"),
+                UnifiedMessage::new(UnifiedRole::User, "thanks"),
+            ],
+        );
+
+        let result = to_anthropic_request(&req, &anthropic_config(), false).unwrap();
+
+        assert_valid_anthropic_sequence(&result.request.messages);
+        assert_eq!(result.request.messages.len(), 5);
+    }
+
     // --- sanitize_message_sequence tests ---
 
     #[test]
@@ -2863,6 +3049,65 @@ mod tests {
         crate::providers::anthropic::types::AnthropicConfig::new("key")
     }
 
+    #[test]
+    fn test_auto_cache_places_breakpoints_for_system_tools_and_history() {
+        use crate::types::{CacheWarningType, Message, Model, Role, Tool};
+
+        let filler = "lorem ipsum dolor sit amet ".repeat(400);
+        let mut req = crate::types::GenerateRequest::new(
+            Model::custom("claude-sonnet-4-5", "anthropic"),
+            vec![
+                Message::new(Role::System, format!("You are a DevOps agent. {filler}")),
+                Message::new(Role::User, "List the pods"),
+                Message::new(Role::Assistant, "There are 3 pods running."),
+                Message::new(Role::User, "Restart the failing one"),
+            ],
+        );
+        req.options = req.options.add_tool(
+            Tool::function("run_command", format!("Run a shell command. {filler}"))
+                .parameters(json!({"type": "object", "properties": {}})),
+        );
+
+        let result = to_anthropic_request(&req, &anthropic_config(), false).unwrap();
+        let body = serde_json::to_value(&result.request).unwrap();
+
+        assert!(body["tools"][0].get("cache_control").is_some());
+        assert!(body["system"][0].get("cache_control").is_some());
+        let messages = body["messages"].as_array().unwrap();
+        let cached: Vec<bool> = messages
+            .iter()
+            .map(|m| {
+                m["content"]
+                    .as_array()
+                    .is_some_and(|blocks| blocks.iter().any(|b| b.get("cache_control").is_some()))
+            })
+            .collect();
+        assert_eq!(cached, vec![false, true, true]);
+
+        let decisions: Vec<_> = result
+            .warnings
+            .iter()
+            .filter(|w| w.warning_type == CacheWarningType::AutoPlacement)
+            .collect();
+        assert!(decisions.is_empty());
+    }
+
+    #[test]
+    fn test_auto_cache_skips_breakpoints_below_minimum_size() {
+        let result = to_anthropic_request(
+            &request_for("claude-sonnet-4-5"),
+            &anthropic_config(),
+            false,
+        )
+        .unwrap();
+
+        assert!(!result.has_cache_control);
+        assert!(result.warnings.iter().any(|w| {
+            w.message
+                .starts_with("Skipped cache breakpoint after last user turn")
+        }));
+    }
+
     #[test]
     fn test_anthropic_request_rewrites_invalid_tool_use_ids() {
         let invalid_id = "kimi.tool/use:1";
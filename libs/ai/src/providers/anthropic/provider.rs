@@ -2,10 +2,10 @@
 
 use super::convert::{from_anthropic_response_with_warnings, to_anthropic_request};
 use super::stream::create_stream;
-use super::types::{AnthropicConfig, AnthropicResponse};
+use super::types::{AnthropicAuth, AnthropicConfig, AnthropicResponse};
 use crate::error::{Error, Result};
 use crate::provider::Provider;
-use crate::providers::tls::create_platform_tls_client;
+use crate::providers::tls::{HttpClientOptions, create_platform_tls_client_with};
 use crate::types::{GenerateRequest, GenerateResponse, GenerateStream, Headers, Model};
 use async_trait::async_trait;
 use reqwest::Client;
@@ -26,11 +26,19 @@ impl AnthropicProvider {
 
     /// Create a new Anthropic provider
     pub fn new(config: AnthropicConfig) -> Result<Self> {
-        if config.auth.is_empty() {
+        Self::with_http_options(config, &HttpClientOptions::default())
+    }
+
+    /// Create a new Anthropic provider using the given HTTP transport options
+    pub fn with_http_options(
+        config: AnthropicConfig,
+        http_options: &HttpClientOptions,
+    ) -> Result<Self> {
+        if config.auth.is_empty() && config.api_key_pool.is_none() {
             return Err(Error::MissingApiKey("anthropic".to_string()));
         }
 
-        let client = create_platform_tls_client()?;
+        let client = create_platform_tls_client_with(http_options)?;
         Ok(Self { config, client })
     }
 
@@ -55,7 +63,7 @@ impl Provider for AnthropicProvider {
     }
 
     fn build_headers(&self, custom_headers: Option<&Headers>) -> Headers {
-        self.build_headers_with_cache(custom_headers, false)
+        self.build_headers_with_cache(custom_headers, false, None)
     }
 
     async fn list_models(&self) -> Result<Vec<Model>> {
@@ -69,12 +77,48 @@ impl Provider for AnthropicProvider {
     }
 
     async fn generate(&self, request: GenerateRequest) -> Result<GenerateResponse> {
+        let pooled_key = self.next_pooled_key();
+        let result = self.send_generate(request, pooled_key.as_deref()).await;
+        if let (Some(pool), Some(key)) = (&self.config.api_key_pool, &pooled_key) {
+            pool.observe(key, &result);
+        }
+        result
+    }
+
+    async fn stream(&self, request: GenerateRequest) -> Result<GenerateStream> {
+        let pooled_key = self.next_pooled_key();
+        let result = self.send_stream(request, pooled_key.as_deref()).await;
+        match (&self.config.api_key_pool, pooled_key) {
+            (Some(pool), Some(key)) => {
+                pool.observe(&key, &result);
+                result.map(|stream| pool.watch_stream(key, stream))
+            }
+            _ => result,
+        }
+    }
+}
+
+impl AnthropicProvider {
+    /// The pooled API key for the next request, if a key pool is configured
+    fn next_pooled_key(&self) -> Option<String> {
+        if !matches!(self.config.auth, AnthropicAuth::ApiKey(_)) {
+            return None;
+        }
+        self.config.api_key_pool.as_ref()?.next_key()
+    }
+
+    async fn send_generate(
+        &self,
+        request: GenerateRequest,
+        api_key: Option<&str>,
+    ) -> Result<GenerateResponse> {
         let url = format!("{}messages", self.config.base_url);
         let conversion_result = to_anthropic_request(&request, &self.config, false)?;
 
         let headers = self.build_headers_with_cache(
             request.options.headers.as_ref(),
             conversion_result.has_cache_control,
+            api_key,
         );
 
         let response = self
@@ -98,13 +142,18 @@ impl Provider for AnthropicProvider {
         from_anthropic_response_with_warnings(anthropic_resp, conversion_result.warnings)
     }
 
-    async fn stream(&self, request: GenerateRequest) -> Result<GenerateStream> {
+    async fn send_stream(
+        &self,
+        request: GenerateRequest,
+        api_key: Option<&str>,
+    ) -> Result<GenerateStream> {
         let url = format!("{}messages", self.config.base_url);
         let conversion_result = to_anthropic_request(&request, &self.config, true)?;
 
         let headers = self.build_headers_with_cache(
             request.options.headers.as_ref(),
             conversion_result.has_cache_control,
+            api_key,
         );
 
         let req_builder = self
@@ -120,20 +169,27 @@ impl Provider for AnthropicProvider {
         // Warnings would need to be communicated via the stream events
         create_stream(event_source).await
     }
-}
 
-impl AnthropicProvider {
     /// Build headers with optional cache control beta feature
+    ///
+    /// `api_key` replaces the configured API key, for requests that use a key
+    /// from the pool.
     fn build_headers_with_cache(
         &self,
         custom_headers: Option<&Headers>,
         has_cache_control: bool,
+        api_key: Option<&str>,
     ) -> Headers {
         let mut headers = Headers::new();
 
         // Apply authentication (works for both API key and OAuth)
-        let (auth_header, auth_value) = self.config.auth.to_header();
-        headers.insert(auth_header, auth_value);
+        match api_key {
+            Some(key) => headers.insert("x-api-key", key),
+            None => {
+                let (auth_header, auth_value) = self.config.auth.to_header();
+                headers.insert(auth_header, auth_value);
+            }
+        }
 
         headers.insert("anthropic-version", &self.config.anthropic_version);
         headers.insert("Content-Type", "application/json");
@@ -32,6 +32,7 @@ pub async fn create_stream(mut event_source: EventSource) -> Result<GenerateStre
         let mut accumulated_usage = Usage::default();
         // Track content blocks by index - stores both ID and accumulated input
         let mut content_blocks: std::collections::HashMap<u32, ContentBlock> = std::collections::HashMap::new();
+        let mut stop_reason: Option<String> = None;
 
         while let Some(event) = event_source.next().await {
             match event {
@@ -47,7 +48,7 @@ pub async fn create_stream(mut event_source: EventSource) -> Result<GenerateStre
 
                     match serde_json::from_str::<AnthropicStreamEvent>(&message.data) {
                         Ok(event) => {
-                            for stream_event in process_anthropic_event(event, &mut accumulated_usage, &mut content_blocks) {
+                            for stream_event in process_anthropic_event(event, &mut accumulated_usage, &mut content_blocks, &mut stop_reason) {
                                 yield Ok(stream_event);
                             }
                         }
@@ -121,6 +122,7 @@ fn process_anthropic_event(
     event: AnthropicStreamEvent,
     accumulated_usage: &mut Usage,
     content_blocks: &mut std::collections::HashMap<u32, ContentBlock>,
+    stop_reason: &mut Option<String>,
 ) -> Vec<StreamEvent> {
     match event.type_.as_str() {
         "message_start" => {
@@ -227,7 +229,10 @@ fn process_anthropic_event(
             Vec::new()
         }
         "message_delta" => {
-            // Message delta - could have usage updates
+            // Message delta - carries the stop reason and usage updates
+            if let Some(reason) = event.delta.and_then(|delta| delta.stop_reason) {
+                *stop_reason = Some(reason);
+            }
             if let Some(usage) = event.usage {
                 accumulated_usage.completion_tokens = usage.output_tokens;
                 accumulated_usage.total_tokens =
@@ -237,9 +242,14 @@ fn process_anthropic_event(
         }
         "message_stop" => {
             // Message finished - emit final usage
+            let finish_reason = if stop_reason.as_deref() == Some("refusal") {
+                FinishReason::with_raw(FinishReasonKind::ContentFilter, "refusal")
+            } else {
+                FinishReason::with_raw(FinishReasonKind::Stop, "message_stop")
+            };
             vec![StreamEvent::finish(
                 accumulated_usage.clone(),
-                FinishReason::with_raw(FinishReasonKind::Stop, "message_stop"),
+                finish_reason,
             )]
         }
         "error" => {
@@ -264,6 +274,7 @@ mod tests {
     fn test_process_text_delta() {
         let mut usage = Usage::default();
         let mut content_blocks = std::collections::HashMap::new();
+        let mut stop_reason = None;
 
         // First start a text block
         let start_event = AnthropicStreamEvent {
@@ -278,7 +289,12 @@ mod tests {
             usage: None,
             error: None,
         };
-        process_anthropic_event(start_event, &mut usage, &mut content_blocks);
+        process_anthropic_event(
+            start_event,
+            &mut usage,
+            &mut content_blocks,
+            &mut stop_reason,
+        );
 
         let event = AnthropicStreamEvent {
             type_: "content_block_delta".to_string(),
@@ -291,14 +307,15 @@ mod tests {
                 thinking: None,
                 _signature: None,
                 partial_json: None,
-                _stop_reason: None,
+                stop_reason: None,
                 _stop_sequence: None,
             }),
             usage: None,
             error: None,
         };
 
-        let results = process_anthropic_event(event, &mut usage, &mut content_blocks);
+        let results =
+            process_anthropic_event(event, &mut usage, &mut content_blocks, &mut stop_reason);
         assert_eq!(results.len(), 1);
 
         if let StreamEvent::TextDelta { delta, .. } = &results[0] {
@@ -312,6 +329,7 @@ mod tests {
     fn test_tool_call_complete_flow() {
         let mut usage = Usage::default();
         let mut content_blocks = std::collections::HashMap::new();
+        let mut stop_reason = None;
 
         // 1. content_block_start for tool use
         let start_event = AnthropicStreamEvent {
@@ -329,7 +347,12 @@ mod tests {
             error: None,
         };
 
-        let results = process_anthropic_event(start_event, &mut usage, &mut content_blocks);
+        let results = process_anthropic_event(
+            start_event,
+            &mut usage,
+            &mut content_blocks,
+            &mut stop_reason,
+        );
         assert_eq!(results.len(), 1);
         if let StreamEvent::ToolCallStart { id, name } = &results[0] {
             assert_eq!(id, "toolu_01ABC123");
@@ -350,14 +373,19 @@ mod tests {
                 thinking: None,
                 _signature: None,
                 partial_json: Some(r#"{"location":"#.to_string()),
-                _stop_reason: None,
+                stop_reason: None,
                 _stop_sequence: None,
             }),
             usage: None,
             error: None,
         };
 
-        let results = process_anthropic_event(delta_event1, &mut usage, &mut content_blocks);
+        let results = process_anthropic_event(
+            delta_event1,
+            &mut usage,
+            &mut content_blocks,
+            &mut stop_reason,
+        );
         assert_eq!(results.len(), 1);
         if let StreamEvent::ToolCallDelta { id, delta } = &results[0] {
             assert_eq!(id, "toolu_01ABC123");
@@ -378,14 +406,19 @@ mod tests {
                 thinking: None,
                 _signature: None,
                 partial_json: Some(r#""San Francisco"}"#.to_string()),
-                _stop_reason: None,
+                stop_reason: None,
                 _stop_sequence: None,
             }),
             usage: None,
             error: None,
         };
 
-        let results = process_anthropic_event(delta_event2, &mut usage, &mut content_blocks);
+        let results = process_anthropic_event(
+            delta_event2,
+            &mut usage,
+            &mut content_blocks,
+            &mut stop_reason,
+        );
         assert_eq!(results.len(), 1);
 
         // 4. content_block_stop - should emit ToolCallEnd with complete JSON
@@ -399,7 +432,12 @@ mod tests {
             error: None,
         };
 
-        let results = process_anthropic_event(stop_event, &mut usage, &mut content_blocks);
+        let results = process_anthropic_event(
+            stop_event,
+            &mut usage,
+            &mut content_blocks,
+            &mut stop_reason,
+        );
         assert_eq!(results.len(), 1);
         if let StreamEvent::ToolCallEnd {
             id,
@@ -420,6 +458,7 @@ mod tests {
     fn test_multiple_tool_calls_in_same_message() {
         let mut usage = Usage::default();
         let mut content_blocks = std::collections::HashMap::new();
+        let mut stop_reason = None;
 
         // First tool call at index 0
         let event1 = AnthropicStreamEvent {
@@ -436,7 +475,7 @@ mod tests {
             usage: None,
             error: None,
         };
-        process_anthropic_event(event1, &mut usage, &mut content_blocks);
+        process_anthropic_event(event1, &mut usage, &mut content_blocks, &mut stop_reason);
 
         // Second tool call at index 1
         let event2 = AnthropicStreamEvent {
@@ -453,7 +492,7 @@ mod tests {
             usage: None,
             error: None,
         };
-        process_anthropic_event(event2, &mut usage, &mut content_blocks);
+        process_anthropic_event(event2, &mut usage, &mut content_blocks, &mut stop_reason);
 
         // Delta for first tool call
         let delta1 = AnthropicStreamEvent {
@@ -467,14 +506,15 @@ mod tests {
                 thinking: None,
                 _signature: None,
                 partial_json: Some(r#"{"city":"NYC"}"#.to_string()),
-                _stop_reason: None,
+                stop_reason: None,
                 _stop_sequence: None,
             }),
             usage: None,
             error: None,
         };
 
-        let results = process_anthropic_event(delta1, &mut usage, &mut content_blocks);
+        let results =
+            process_anthropic_event(delta1, &mut usage, &mut content_blocks, &mut stop_reason);
         assert_eq!(results.len(), 1);
         if let StreamEvent::ToolCallDelta { id, .. } = &results[0] {
             assert_eq!(id, "toolu_first");
@@ -494,14 +534,15 @@ mod tests {
                 thinking: None,
                 _signature: None,
                 partial_json: Some(r#"{"timezone":"EST"}"#.to_string()),
-                _stop_reason: None,
+                stop_reason: None,
                 _stop_sequence: None,
             }),
             usage: None,
             error: None,
         };
 
-        let results = process_anthropic_event(delta2, &mut usage, &mut content_blocks);
+        let results =
+            process_anthropic_event(delta2, &mut usage, &mut content_blocks, &mut stop_reason);
         assert_eq!(results.len(), 1);
         if let StreamEvent::ToolCallDelta { id, .. } = &results[0] {
             assert_eq!(id, "toolu_second");
@@ -520,7 +561,8 @@ mod tests {
             error: None,
         };
 
-        let results = process_anthropic_event(stop1, &mut usage, &mut content_blocks);
+        let results =
+            process_anthropic_event(stop1, &mut usage, &mut content_blocks, &mut stop_reason);
         assert_eq!(results.len(), 1);
         if let StreamEvent::ToolCallEnd {
             id,
@@ -547,7 +589,8 @@ mod tests {
             error: None,
         };
 
-        let results = process_anthropic_event(stop2, &mut usage, &mut content_blocks);
+        let results =
+            process_anthropic_event(stop2, &mut usage, &mut content_blocks, &mut stop_reason);
         assert_eq!(results.len(), 1);
         if let StreamEvent::ToolCallEnd {
             id,
@@ -568,6 +611,7 @@ mod tests {
     fn test_thinking_delta() {
         let mut usage = Usage::default();
         let mut content_blocks = std::collections::HashMap::new();
+        let mut stop_reason = None;
 
         let event = AnthropicStreamEvent {
             type_: "content_block_delta".to_string(),
@@ -580,14 +624,15 @@ mod tests {
                 thinking: Some("Let me think about this...".to_string()),
                 _signature: None,
                 partial_json: None,
-                _stop_reason: None,
+                stop_reason: None,
                 _stop_sequence: None,
             }),
             usage: None,
             error: None,
         };
 
-        let results = process_anthropic_event(event, &mut usage, &mut content_blocks);
+        let results =
+            process_anthropic_event(event, &mut usage, &mut content_blocks, &mut stop_reason);
         assert_eq!(results.len(), 1);
 
         if let StreamEvent::ReasoningDelta { delta, .. } = &results[0] {
@@ -601,6 +646,7 @@ mod tests {
     fn test_message_stop_emits_finish() {
         let mut usage = Usage::new(10, 20);
         let mut content_blocks = std::collections::HashMap::new();
+        let mut stop_reason = None;
 
         let event = AnthropicStreamEvent {
             type_: "message_stop".to_string(),
@@ -612,7 +658,8 @@ mod tests {
             error: None,
         };
 
-        let results = process_anthropic_event(event, &mut usage, &mut content_blocks);
+        let results =
+            process_anthropic_event(event, &mut usage, &mut content_blocks, &mut stop_reason);
         assert_eq!(results.len(), 1);
 
         if let StreamEvent::Finish { usage: u, reason } = &results[0] {
@@ -628,10 +675,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_refusal_stop_reason_finishes_as_content_filter() {
+        let mut usage = Usage::default();
+        let mut content_blocks = std::collections::HashMap::new();
+        let mut stop_reason = None;
+
+        for data in [
+            r#"{"type":"message_delta","delta":{"stop_reason":"refusal","stop_sequence":null}}"#,
+            r#"{"type":"message_stop"}"#,
+        ] {
+            let event: AnthropicStreamEvent = serde_json::from_str(data).unwrap();
+            let results =
+                process_anthropic_event(event, &mut usage, &mut content_blocks, &mut stop_reason);
+            if let Some(StreamEvent::Finish { reason, .. }) = results.first() {
+                assert_eq!(
+                    reason.unified,
+                    crate::types::FinishReasonKind::ContentFilter
+                );
+                assert_eq!(reason.raw.as_deref(), Some("refusal"));
+                return;
+            }
+        }
+        panic!("Expected Finish event");
+    }
+
     #[test]
     fn test_error_event() {
         let mut usage = Usage::default();
         let mut content_blocks = std::collections::HashMap::new();
+        let mut stop_reason = None;
 
         let event = AnthropicStreamEvent {
             type_: "error".to_string(),
@@ -645,7 +718,8 @@ mod tests {
             }),
         };
 
-        let results = process_anthropic_event(event, &mut usage, &mut content_blocks);
+        let results =
+            process_anthropic_event(event, &mut usage, &mut content_blocks, &mut stop_reason);
         assert_eq!(results.len(), 1);
 
         if let StreamEvent::Error { message } = &results[0] {
@@ -659,6 +733,7 @@ mod tests {
     fn test_tool_call_with_empty_input() {
         let mut usage = Usage::default();
         let mut content_blocks = std::collections::HashMap::new();
+        let mut stop_reason = None;
 
         // Start tool call
         let start_event = AnthropicStreamEvent {
@@ -675,7 +750,12 @@ mod tests {
             usage: None,
             error: None,
         };
-        process_anthropic_event(start_event, &mut usage, &mut content_blocks);
+        process_anthropic_event(
+            start_event,
+            &mut usage,
+            &mut content_blocks,
+            &mut stop_reason,
+        );
 
         // Stop immediately without any deltas
         let stop_event = AnthropicStreamEvent {
@@ -688,7 +768,12 @@ mod tests {
             error: None,
         };
 
-        let results = process_anthropic_event(stop_event, &mut usage, &mut content_blocks);
+        let results = process_anthropic_event(
+            stop_event,
+            &mut usage,
+            &mut content_blocks,
+            &mut stop_reason,
+        );
         assert_eq!(results.len(), 1);
 
         if let StreamEvent::ToolCallEnd {
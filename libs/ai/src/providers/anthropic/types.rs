@@ -1,7 +1,9 @@
 //! Anthropic-specific types
 
+use crate::providers::key_pool::{ApiKeyPool, WeightedApiKey};
 use crate::types::{CacheControl, CacheStrategy};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 /// Authentication type for Anthropic
 #[derive(Debug, Clone)]
@@ -63,6 +65,8 @@ pub struct AnthropicConfig {
     /// - Last system message
     /// - Last 2 non-system messages
     pub default_cache_strategy: CacheStrategy,
+    /// Extra API keys to balance requests across (overrides the `auth` key)
+    pub api_key_pool: Option<Arc<ApiKeyPool>>,
 }
 
 /// Beta header for OAuth authentication
@@ -89,6 +93,7 @@ impl AnthropicConfig {
             anthropic_version: "2023-06-01".to_string(),
             beta_features: vec![],
             default_cache_strategy: CacheStrategy::Auto,
+            api_key_pool: None,
         }
     }
 
@@ -100,6 +105,7 @@ impl AnthropicConfig {
             anthropic_version: "2023-06-01".to_string(),
             beta_features: vec![OAUTH_BETA_HEADER.to_string()],
             default_cache_strategy: CacheStrategy::Auto,
+            api_key_pool: None,
         }
     }
 
@@ -116,6 +122,7 @@ impl AnthropicConfig {
             anthropic_version: "2023-06-01".to_string(),
             beta_features,
             default_cache_strategy: CacheStrategy::Auto,
+            api_key_pool: None,
         }
     }
 
@@ -148,6 +155,17 @@ impl AnthropicConfig {
         self
     }
 
+    /// Balance requests across several weighted API keys
+    ///
+    /// Each request uses one key from the pool, chosen by weight; a key that
+    /// is rate limited (HTTP 429) is skipped for a cooldown period. Only
+    /// applies to API key authentication.
+    pub fn with_api_keys(mut self, keys: Vec<WeightedApiKey>) -> Self {
+        let pool = ApiKeyPool::new(keys);
+        self.api_key_pool = (!pool.is_empty()).then(|| Arc::new(pool));
+        self
+    }
+
     /// Set API version
     pub fn with_version(mut self, version: impl Into<String>) -> Self {
         self.anthropic_version = version.into();
@@ -194,6 +212,7 @@ impl Default for AnthropicConfig {
             anthropic_version: "2023-06-01".to_string(),
             beta_features: vec![],
             default_cache_strategy: CacheStrategy::Auto,
+            api_key_pool: None,
         }
     }
 }
@@ -462,7 +481,7 @@ pub struct AnthropicDelta {
     pub thinking: Option<String>,
     pub _signature: Option<String>,
     pub partial_json: Option<String>,
-    pub _stop_reason: Option<String>,
+    pub stop_reason: Option<String>,
     pub _stop_sequence: Option<String>,
 }
 
@@ -1,6 +1,7 @@
 //! AWS Bedrock provider module
 //!
-//! Provides access to Anthropic Claude models through AWS Bedrock's InvokeModel API.
+//! Provides access to Anthropic Claude, Meta Llama and Amazon Titan Text models
+//! through AWS Bedrock's InvokeModel API.
 //! Authentication is handled entirely by the AWS credential chain — no API keys needed.
 //!
 //! # Features
@@ -9,11 +10,13 @@
 //!   prompt caching, and extended thinking
 //! - Uses `aws-sdk-bedrockruntime` for SigV4 auth and EventStream transport
 //! - Reuses the Anthropic conversion layer — no duplicated message/tool logic
+//! - Llama and Titan models (text only, no tool calling) are selected by model ID
 
 mod convert;
 pub mod models;
 mod provider;
 mod stream;
+mod text_models;
 mod types;
 
 pub use provider::BedrockProvider;
@@ -6,7 +6,7 @@
 //! - Cross-region: `us.anthropic.claude-sonnet-4-5-20250929-v1:0`
 //!
 //! This module accepts both Anthropic-style and Bedrock-style model IDs, mapping when needed.
-//! - If the ID already looks like a Bedrock ID (contains `anthropic.`), pass through
+//! - If the ID already looks like a Bedrock ID (contains `anthropic.`, or is a `meta.`/`amazon.titan-text` ID), pass through
 //! - If the ID has a region prefix (`us.`, `eu.`, `global.`), pass through
 //! - Otherwise, map from Anthropic-style to Bedrock format
 
@@ -123,7 +123,7 @@ static ANTHROPIC_TO_BEDROCK: LazyLock<HashMap<&'static str, &'static str>> = Laz
 /// Resolve a model ID to a Bedrock-compatible model ID
 ///
 /// Accepts both Anthropic-style and Bedrock-style model IDs:
-/// 1. If the ID already contains `anthropic.` → passthrough (already a Bedrock ID)
+/// 1. If the ID already contains `anthropic.` or is a Llama/Titan ID → passthrough
 /// 2. If the ID has a region prefix (`us.`, `eu.`, `ap.`, `global.`) → passthrough
 /// 3. If the ID is in the known mapping table → use the mapped value
 /// 4. Otherwise → best-effort: `anthropic.{id}-v1:0`
@@ -158,8 +158,10 @@ static ANTHROPIC_TO_BEDROCK: LazyLock<HashMap<&'static str, &'static str>> = Laz
 /// );
 /// ```
 pub fn resolve_bedrock_model_id(model_id: &str) -> String {
-    // 1. Already a Bedrock ID (contains "anthropic." anywhere)
-    if model_id.contains("anthropic.") {
+    // 1. Already a Bedrock ID (contains "anthropic." anywhere, or a Llama/Titan ID)
+    if model_id.contains("anthropic.")
+        || bedrock_model_family(model_id) != BedrockModelFamily::Anthropic
+    {
         return model_id.to_string();
     }
 
@@ -179,6 +181,50 @@ pub fn resolve_bedrock_model_id(model_id: &str) -> String {
     format!("us.anthropic.{}-v1:0", model_id)
 }
 
+/// Model families served through InvokeModel, each with its own body format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BedrockModelFamily {
+    /// Anthropic Claude (Messages API body)
+    Anthropic,
+    /// Meta Llama (`prompt` / `generation` body)
+    MetaLlama,
+    /// Amazon Titan Text (`inputText` / `results` body)
+    AmazonTitan,
+}
+
+/// Detect the model family from a (possibly region-prefixed) Bedrock model ID
+///
+/// Anything that isn't recognisably Llama or Titan is treated as Anthropic,
+/// matching the fallback in [`resolve_bedrock_model_id`].
+///
+/// ```
+/// use stakai::providers::bedrock::models::{bedrock_model_family, BedrockModelFamily};
+///
+/// assert_eq!(
+///     bedrock_model_family("us.meta.llama3-3-70b-instruct-v1:0"),
+///     BedrockModelFamily::MetaLlama
+/// );
+/// assert_eq!(
+///     bedrock_model_family("amazon.titan-text-express-v1"),
+///     BedrockModelFamily::AmazonTitan
+/// );
+/// assert_eq!(bedrock_model_family("claude-sonnet-4-5"), BedrockModelFamily::Anthropic);
+/// ```
+pub fn bedrock_model_family(model_id: &str) -> BedrockModelFamily {
+    let base = ["us.", "eu.", "ap.", "global."]
+        .iter()
+        .find_map(|p| model_id.strip_prefix(p))
+        .unwrap_or(model_id);
+
+    if base.starts_with("meta.") {
+        BedrockModelFamily::MetaLlama
+    } else if base.starts_with("amazon.titan-text") || base.starts_with("amazon.titan-tg1") {
+        BedrockModelFamily::AmazonTitan
+    } else {
+        BedrockModelFamily::Anthropic
+    }
+}
+
 /// Check if a model ID has a region prefix (cross-region inference)
 fn has_region_prefix(model_id: &str) -> bool {
     // AWS region prefixes used in cross-region inference profile IDs
@@ -287,6 +333,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_model_family_detection() {
+        assert_eq!(
+            bedrock_model_family("meta.llama3-8b-instruct-v1:0"),
+            BedrockModelFamily::MetaLlama
+        );
+        assert_eq!(
+            bedrock_model_family("eu.meta.llama3-2-3b-instruct-v1:0"),
+            BedrockModelFamily::MetaLlama
+        );
+        assert_eq!(
+            bedrock_model_family("amazon.titan-text-premier-v1:0"),
+            BedrockModelFamily::AmazonTitan
+        );
+        assert_eq!(
+            bedrock_model_family("us.anthropic.claude-sonnet-4-5-20250929-v1:0"),
+            BedrockModelFamily::Anthropic
+        );
+        // Titan embeddings aren't text-generation models
+        assert_eq!(
+            bedrock_model_family("amazon.titan-embed-text-v2:0"),
+            BedrockModelFamily::Anthropic
+        );
+    }
+
+    #[test]
+    fn test_llama_and_titan_ids_pass_through() {
+        assert_eq!(
+            resolve_bedrock_model_id("meta.llama3-70b-instruct-v1:0"),
+            "meta.llama3-70b-instruct-v1:0"
+        );
+        assert_eq!(
+            resolve_bedrock_model_id("amazon.titan-text-express-v1"),
+            "amazon.titan-text-express-v1"
+        );
+    }
+
     #[test]
     fn test_unknown_model_fallback() {
         assert_eq!(
@@ -5,7 +5,9 @@
 //! the `convert` module.
 
 use super::convert::to_bedrock_body;
-use super::stream::create_stream;
+use super::models::{BedrockModelFamily, bedrock_model_family, resolve_bedrock_model_id};
+use super::stream::{create_stream, create_text_model_stream};
+use super::text_models::{from_text_model_response, to_text_model_body};
 use super::types::BedrockConfig;
 use crate::error::{Error, Result};
 use crate::provider::Provider;
@@ -191,6 +193,26 @@ impl Provider for BedrockProvider {
     }
 
     async fn generate(&self, request: GenerateRequest) -> Result<GenerateResponse> {
+        let family = bedrock_model_family(&request.model.id);
+        if family != BedrockModelFamily::Anthropic {
+            let body = to_text_model_body(&request, family)?;
+            let body_bytes = serde_json::to_vec(&body)
+                .map_err(|e| Error::invalid_response(format!("Failed to serialize body: {}", e)))?;
+
+            let client = self.client().await?;
+            let response = client
+                .invoke_model()
+                .model_id(resolve_bedrock_model_id(&request.model.id))
+                .content_type("application/json")
+                .accept("application/json")
+                .body(Blob::new(body_bytes))
+                .send()
+                .await
+                .map_err(map_invoke_model_error)?;
+
+            return from_text_model_response(response.body().as_ref(), family);
+        }
+
         let conversion_result = to_bedrock_body(&request, &self.anthropic_config)?;
 
         let body_bytes = serde_json::to_vec(&conversion_result.body)
@@ -218,6 +240,25 @@ impl Provider for BedrockProvider {
     }
 
     async fn stream(&self, request: GenerateRequest) -> Result<GenerateStream> {
+        let family = bedrock_model_family(&request.model.id);
+        if family != BedrockModelFamily::Anthropic {
+            let body = to_text_model_body(&request, family)?;
+            let body_bytes = serde_json::to_vec(&body)
+                .map_err(|e| Error::invalid_response(format!("Failed to serialize body: {}", e)))?;
+
+            let client = self.client().await?;
+            let response = client
+                .invoke_model_with_response_stream()
+                .model_id(resolve_bedrock_model_id(&request.model.id))
+                .content_type("application/json")
+                .body(Blob::new(body_bytes))
+                .send()
+                .await
+                .map_err(map_invoke_stream_error)?;
+
+            return create_text_model_stream(response.body).await;
+        }
+
         let conversion_result = to_bedrock_body(&request, &self.anthropic_config)?;
 
         let body_bytes = serde_json::to_vec(&conversion_result.body)
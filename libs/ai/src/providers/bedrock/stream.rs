@@ -9,6 +9,7 @@
 //! We reuse the Anthropic stream event processing logic since the JSON payloads
 //! are identical.
 
+use super::text_models::{parse_text_model_chunk, process_text_model_chunk};
 use crate::error::{Error, Result};
 use crate::providers::anthropic::types::AnthropicStreamEvent;
 use crate::types::{
@@ -88,6 +89,54 @@ pub async fn create_stream(
     Ok(GenerateStream::new(Box::pin(stream)))
 }
 
+/// Create a GenerateStream for Llama/Titan models from a Bedrock EventStream receiver
+pub async fn create_text_model_stream(
+    receiver: EventReceiver<
+        ResponseStream,
+        aws_sdk_bedrockruntime::types::error::ResponseStreamError,
+    >,
+) -> Result<GenerateStream> {
+    let stream = async_stream::stream! {
+        let mut accumulated_usage = Usage::default();
+        let mut receiver = receiver;
+
+        loop {
+            match receiver.recv().await {
+                Ok(Some(ResponseStream::Chunk(payload_part))) => {
+                    let Some(bytes) = payload_part.bytes.as_ref().filter(|b| !b.as_ref().is_empty())
+                    else {
+                        continue;
+                    };
+                    match parse_text_model_chunk(bytes.as_ref()) {
+                        Ok(chunk) => {
+                            for stream_event in
+                                process_text_model_chunk(chunk, &mut accumulated_usage)
+                            {
+                                yield Ok(stream_event);
+                            }
+                        }
+                        Err(e) => {
+                            yield Err(e);
+                            break;
+                        }
+                    }
+                }
+                Ok(Some(_)) => {
+                    // Unknown event variant — skip for forward compatibility
+                    continue;
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    yield Err(Error::stream_error(format!("Bedrock stream error: {:?}", e)));
+                    break;
+                }
+            }
+        }
+    };
+
+    Ok(GenerateStream::new(Box::pin(stream)))
+}
+
 /// Parse a Bedrock PayloadPart into an Anthropic stream event
 fn parse_payload_part(part: &PayloadPart) -> Result<Option<AnthropicStreamEvent>> {
     let Some(ref bytes) = part.bytes else {
@@ -0,0 +1,412 @@
+//! Non-Anthropic text models on Bedrock (Meta Llama, Amazon Titan Text)
+//!
+//! These families use their own InvokeModel body and response shapes rather
+//! than the Anthropic Messages format:
+//!
+//! - **Llama**: `{"prompt", "max_gen_len", "temperature", "top_p"}` with the
+//!   Llama 3 chat template rendered into `prompt`; responses and stream chunks
+//!   carry `generation`, `prompt_token_count`, `generation_token_count`, `stop_reason`.
+//! - **Titan**: `{"inputText", "textGenerationConfig": {...}}`; responses carry
+//!   `results[].outputText`, stream chunks carry a top-level `outputText`.
+//!
+//! Neither family supports tool calling through InvokeModel, so requests with
+//! tools are rejected up front instead of silently dropping them.
+
+use super::models::BedrockModelFamily;
+use crate::error::{Error, Result};
+use crate::types::{
+    FinishReason, FinishReasonKind, GenerateRequest, GenerateResponse, ResponseContent, Role,
+    StreamEvent, Usage,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+/// Default generation cap when the request doesn't set `max_tokens`
+const DEFAULT_MAX_TOKENS: u32 = 2048;
+
+/// Build the InvokeModel body for a Llama or Titan model
+pub fn to_text_model_body(
+    req: &GenerateRequest,
+    family: BedrockModelFamily,
+) -> Result<serde_json::Value> {
+    if req.options.tools.as_ref().is_some_and(|t| !t.is_empty()) {
+        return Err(Error::InvalidModel(format!(
+            "Bedrock model '{}' does not support tool calling",
+            req.model.id
+        )));
+    }
+
+    let max_tokens = req.options.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS);
+
+    match family {
+        BedrockModelFamily::MetaLlama => {
+            let mut body = json!({
+                "prompt": render_llama3_prompt(req),
+                "max_gen_len": max_tokens,
+            });
+            if let Some(temperature) = req.options.temperature {
+                body["temperature"] = json!(temperature);
+            }
+            if let Some(top_p) = req.options.top_p {
+                body["top_p"] = json!(top_p);
+            }
+            Ok(body)
+        }
+        BedrockModelFamily::AmazonTitan => {
+            let mut config = json!({ "maxTokenCount": max_tokens });
+            if let Some(temperature) = req.options.temperature {
+                config["temperature"] = json!(temperature);
+            }
+            if let Some(top_p) = req.options.top_p {
+                config["topP"] = json!(top_p);
+            }
+            if let Some(stop) = &req.options.stop_sequences {
+                config["stopSequences"] = json!(stop);
+            }
+            Ok(json!({
+                "inputText": render_titan_prompt(req),
+                "textGenerationConfig": config,
+            }))
+        }
+        BedrockModelFamily::Anthropic => Err(Error::invalid_response(
+            "Anthropic models use the Messages body, not the text-model body",
+        )),
+    }
+}
+
+/// Render messages with the Llama 3 chat template, ending on an open assistant turn
+fn render_llama3_prompt(req: &GenerateRequest) -> String {
+    let mut prompt = String::from("<|begin_of_text|>");
+    for message in &req.messages {
+        let role = match message.role {
+            Role::System => "system",
+            Role::User | Role::Tool => "user",
+            Role::Assistant => "assistant",
+        };
+        let text = message.text().unwrap_or_default();
+        prompt.push_str(&format!(
+            "<|start_header_id|>{role}<|end_header_id|>\n\n{text}<|eot_id|>"
+        ));
+    }
+    prompt.push_str("<|start_header_id|>assistant<|end_header_id|>\n\n");
+    prompt
+}
+
+/// Render messages as a Titan `User:`/`Bot:` transcript ending on an open bot turn
+fn render_titan_prompt(req: &GenerateRequest) -> String {
+    let mut lines = Vec::new();
+    for message in &req.messages {
+        let text = message.text().unwrap_or_default();
+        match message.role {
+            Role::System => lines.push(text),
+            Role::User | Role::Tool => lines.push(format!("User: {text}")),
+            Role::Assistant => lines.push(format!("Bot: {text}")),
+        }
+    }
+    lines.push("Bot:".to_string());
+    lines.join("\n")
+}
+
+#[derive(Debug, Deserialize)]
+struct LlamaResponse {
+    #[serde(default)]
+    generation: String,
+    #[serde(default)]
+    prompt_token_count: Option<u32>,
+    #[serde(default)]
+    generation_token_count: Option<u32>,
+    #[serde(default)]
+    stop_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TitanResponse {
+    #[serde(default)]
+    input_text_token_count: Option<u32>,
+    #[serde(default)]
+    results: Vec<TitanResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TitanResult {
+    #[serde(default)]
+    token_count: Option<u32>,
+    #[serde(default)]
+    output_text: String,
+    #[serde(default)]
+    completion_reason: Option<String>,
+}
+
+/// Streaming chunk for Llama and Titan; fields not used by a family stay `None`
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct TextModelChunk {
+    // Llama
+    #[serde(default)]
+    generation: Option<String>,
+    #[serde(default)]
+    prompt_token_count: Option<u32>,
+    #[serde(default)]
+    generation_token_count: Option<u32>,
+    #[serde(default)]
+    stop_reason: Option<String>,
+    // Titan
+    #[serde(default, rename = "outputText")]
+    output_text: Option<String>,
+    #[serde(default, rename = "inputTextTokenCount")]
+    input_text_token_count: Option<u32>,
+    #[serde(default, rename = "totalOutputTextTokenCount")]
+    total_output_text_token_count: Option<u32>,
+    #[serde(default, rename = "completionReason")]
+    completion_reason: Option<String>,
+}
+
+/// Map a Llama `stop_reason` or Titan `completionReason` to the unified kind
+fn map_finish_reason(raw: &str) -> FinishReason {
+    let unified = match raw.to_ascii_lowercase().as_str() {
+        "stop" | "finish" | "stop_criteria_met" => FinishReasonKind::Stop,
+        "length" => FinishReasonKind::Length,
+        "content_filtered" => FinishReasonKind::ContentFilter,
+        _ => FinishReasonKind::Other,
+    };
+    FinishReason::with_raw(unified, raw)
+}
+
+/// Parse a non-streaming Llama/Titan InvokeModel response
+pub fn from_text_model_response(
+    bytes: &[u8],
+    family: BedrockModelFamily,
+) -> Result<GenerateResponse> {
+    let parse_err = |e: serde_json::Error| {
+        Error::invalid_response(format!("Failed to parse Bedrock response: {}", e))
+    };
+
+    let (text, usage, finish_reason) = match family {
+        BedrockModelFamily::MetaLlama => {
+            let resp: LlamaResponse = serde_json::from_slice(bytes).map_err(parse_err)?;
+            let usage = Usage::new(
+                resp.prompt_token_count.unwrap_or(0),
+                resp.generation_token_count.unwrap_or(0),
+            );
+            let reason = resp
+                .stop_reason
+                .as_deref()
+                .map(map_finish_reason)
+                .unwrap_or_else(FinishReason::stop);
+            (resp.generation, usage, reason)
+        }
+        BedrockModelFamily::AmazonTitan => {
+            let resp: TitanResponse = serde_json::from_slice(bytes).map_err(parse_err)?;
+            let result = resp
+                .results
+                .into_iter()
+                .next()
+                .ok_or_else(|| Error::invalid_response("No results in Titan response"))?;
+            let usage = Usage::new(
+                resp.input_text_token_count.unwrap_or(0),
+                result.token_count.unwrap_or(0),
+            );
+            let reason = result
+                .completion_reason
+                .as_deref()
+                .map(map_finish_reason)
+                .unwrap_or_else(FinishReason::stop);
+            (result.output_text, usage, reason)
+        }
+        BedrockModelFamily::Anthropic => {
+            return Err(Error::invalid_response(
+                "Anthropic responses are parsed by the Anthropic converter",
+            ));
+        }
+    };
+
+    Ok(GenerateResponse {
+        content: vec![ResponseContent::Text { text }],
+        usage,
+        finish_reason,
+        metadata: None,
+        warnings: None,
+        model: None,
+    })
+}
+
+/// Parse one Llama/Titan stream chunk payload
+pub(super) fn parse_text_model_chunk(bytes: &[u8]) -> Result<TextModelChunk> {
+    serde_json::from_slice(bytes).map_err(|_| {
+        Error::from_unparseable_chunk(
+            &String::from_utf8_lossy(bytes),
+            "Failed to parse Bedrock event",
+        )
+    })
+}
+
+/// Turn a Llama/Titan stream chunk into unified stream events
+///
+/// Token counts arrive incrementally (Llama reports the prompt count on the
+/// first chunk only), so they're accumulated until the chunk carrying a
+/// stop reason, which emits the `Finish` event.
+pub(super) fn process_text_model_chunk(
+    chunk: TextModelChunk,
+    accumulated_usage: &mut Usage,
+) -> Vec<StreamEvent> {
+    let mut events = Vec::new();
+
+    if let Some(prompt_tokens) = chunk.prompt_token_count.or(chunk.input_text_token_count) {
+        accumulated_usage.prompt_tokens = prompt_tokens;
+    }
+    if let Some(tokens) = chunk.generation_token_count {
+        accumulated_usage.completion_tokens += tokens;
+    }
+    if let Some(total) = chunk.total_output_text_token_count {
+        accumulated_usage.completion_tokens = total;
+    }
+
+    if let Some(text) = chunk.generation.or(chunk.output_text)
+        && !text.is_empty()
+    {
+        events.push(StreamEvent::text_delta("", text));
+    }
+
+    if let Some(reason) = chunk.stop_reason.or(chunk.completion_reason) {
+        accumulated_usage.total_tokens =
+            accumulated_usage.prompt_tokens + accumulated_usage.completion_tokens;
+        events.push(StreamEvent::finish(
+            accumulated_usage.clone(),
+            map_finish_reason(&reason),
+        ));
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{GenerateOptions, Message, Model, Tool};
+
+    fn request(model: &str) -> GenerateRequest {
+        let mut req = GenerateRequest::new(
+            Model::custom(model, "bedrock"),
+            vec![
+                Message::new(Role::System, "Be brief."),
+                Message::new(Role::User, "Hi"),
+            ],
+        );
+        req.options = GenerateOptions::new().max_tokens(64).temperature(0.2);
+        req
+    }
+
+    fn collect_stream(fixture: &[&str]) -> (String, Vec<StreamEvent>) {
+        let mut usage = Usage::default();
+        let mut text = String::new();
+        let mut events = Vec::new();
+        for raw in fixture {
+            let chunk = parse_text_model_chunk(raw.as_bytes()).unwrap();
+            for event in process_text_model_chunk(chunk, &mut usage) {
+                if let StreamEvent::TextDelta { delta, .. } = &event {
+                    text.push_str(delta);
+                }
+                events.push(event);
+            }
+        }
+        (text, events)
+    }
+
+    #[test]
+    fn test_llama_body_uses_chat_template() {
+        let body = to_text_model_body(
+            &request("meta.llama3-70b-instruct-v1:0"),
+            BedrockModelFamily::MetaLlama,
+        )
+        .unwrap();
+
+        assert_eq!(body["max_gen_len"], 64);
+        let prompt = body["prompt"].as_str().unwrap();
+        assert!(prompt.starts_with("<|begin_of_text|><|start_header_id|>system"));
+        assert!(prompt.contains("user<|end_header_id|>\n\nHi<|eot_id|>"));
+        assert!(prompt.ends_with("<|start_header_id|>assistant<|end_header_id|>\n\n"));
+    }
+
+    #[test]
+    fn test_titan_body_uses_generation_config() {
+        let body = to_text_model_body(
+            &request("amazon.titan-text-express-v1"),
+            BedrockModelFamily::AmazonTitan,
+        )
+        .unwrap();
+
+        assert_eq!(body["inputText"], "Be brief.\nUser: Hi\nBot:");
+        assert_eq!(body["textGenerationConfig"]["maxTokenCount"], 64);
+        assert!(body["textGenerationConfig"]["temperature"].is_number());
+    }
+
+    #[test]
+    fn test_tools_are_rejected_for_text_models() {
+        let mut req = request("meta.llama3-8b-instruct-v1:0");
+        req.options = req
+            .options
+            .add_tool(Tool::function("lookup", "Look up a value"));
+        assert!(to_text_model_body(&req, BedrockModelFamily::MetaLlama).is_err());
+    }
+
+    #[test]
+    fn test_llama_response_parsing() {
+        let fixture = br#"{"generation":"Hello there!","prompt_token_count":21,"generation_token_count":4,"stop_reason":"stop"}"#;
+        let resp = from_text_model_response(fixture, BedrockModelFamily::MetaLlama).unwrap();
+        assert_eq!(resp.text(), "Hello there!");
+        assert_eq!(resp.usage.prompt_tokens, 21);
+        assert_eq!(resp.usage.completion_tokens, 4);
+        assert_eq!(resp.finish_reason.unified, FinishReasonKind::Stop);
+    }
+
+    #[test]
+    fn test_titan_response_parsing() {
+        let fixture = br#"{"inputTextTokenCount":9,"results":[{"tokenCount":3,"outputText":" Hi!","completionReason":"LENGTH"}]}"#;
+        let resp = from_text_model_response(fixture, BedrockModelFamily::AmazonTitan).unwrap();
+        assert_eq!(resp.text(), " Hi!");
+        assert_eq!(resp.usage.total_tokens, 12);
+        assert_eq!(resp.finish_reason.unified, FinishReasonKind::Length);
+    }
+
+    #[test]
+    fn test_llama_stream_fixture() {
+        // Recorded from invoke-model-with-response-stream (payload bytes only)
+        let fixture = [
+            r#"{"generation":"Hello","prompt_token_count":21,"generation_token_count":1,"stop_reason":null}"#,
+            r#"{"generation":" there","prompt_token_count":null,"generation_token_count":1,"stop_reason":null}"#,
+            r#"{"generation":"!","prompt_token_count":null,"generation_token_count":1,"stop_reason":"stop","amazon-bedrock-invocationMetrics":{"inputTokenCount":21,"outputTokenCount":3,"invocationLatency":312,"firstByteLatency":120}}"#,
+        ];
+        let (text, events) = collect_stream(&fixture);
+
+        assert_eq!(text, "Hello there!");
+        match events.last() {
+            Some(StreamEvent::Finish { usage, reason }) => {
+                assert_eq!(usage.prompt_tokens, 21);
+                assert_eq!(usage.completion_tokens, 3);
+                assert_eq!(usage.total_tokens, 24);
+                assert_eq!(reason.unified, FinishReasonKind::Stop);
+            }
+            other => panic!("expected finish event, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_titan_stream_fixture() {
+        let fixture = [
+            r#"{"outputText":" Hi","index":0,"totalOutputTextTokenCount":1,"completionReason":null,"inputTextTokenCount":9}"#,
+            r#"{"outputText":" there.","index":0,"totalOutputTextTokenCount":3,"completionReason":"FINISH","inputTextTokenCount":null,"amazon-bedrock-invocationMetrics":{"inputTokenCount":9,"outputTokenCount":3}}"#,
+        ];
+        let (text, events) = collect_stream(&fixture);
+
+        assert_eq!(text, " Hi there.");
+        match events.last() {
+            Some(StreamEvent::Finish { usage, reason }) => {
+                assert_eq!(usage.prompt_tokens, 9);
+                assert_eq!(usage.completion_tokens, 3);
+                assert_eq!(reason.raw.as_deref(), Some("FINISH"));
+            }
+            other => panic!("expected finish event, got {other:?}"),
+        }
+    }
+}
@@ -33,7 +33,7 @@ use crate::provider::Provider;
 use crate::providers::openai::convert::{from_openai_response, to_openai_request};
 use crate::providers::openai::stream::create_completions_stream;
 use crate::providers::openai::types::ChatCompletionResponse;
-use crate::providers::tls::create_platform_tls_client;
+use crate::providers::tls::{HttpClientOptions, create_platform_tls_client_with};
 use crate::types::{GenerateRequest, GenerateResponse, GenerateStream, Headers, Model};
 use async_trait::async_trait;
 use reqwest::Client;
@@ -79,10 +79,18 @@ pub struct CopilotProvider {
 impl CopilotProvider {
     /// Create a new Copilot provider from a config.
     pub fn new(config: CopilotConfig) -> Result<Self> {
+        Self::with_http_options(config, &HttpClientOptions::default())
+    }
+
+    /// Create a new Copilot provider using the given HTTP transport options
+    pub fn with_http_options(
+        config: CopilotConfig,
+        http_options: &HttpClientOptions,
+    ) -> Result<Self> {
         if config.github_token.is_empty() {
             return Err(Error::MissingApiKey("github-copilot".to_string()));
         }
-        let client = create_platform_tls_client()?;
+        let client = create_platform_tls_client_with(http_options)?;
         Ok(Self {
             config,
             client,
@@ -1,9 +1,9 @@
 //! Conversion between unified types and Gemini types
 
 use super::types::{
-    GeminiContent, GeminiFunctionCall, GeminiFunctionDeclaration, GeminiFunctionResponse,
-    GeminiGenerationConfig, GeminiInlineData, GeminiPart, GeminiRequest, GeminiResponse,
-    GeminiSystemInstruction, GeminiThinkingConfig, GeminiTool,
+    GeminiCandidate, GeminiContent, GeminiFunctionCall, GeminiFunctionDeclaration,
+    GeminiFunctionResponse, GeminiGenerationConfig, GeminiInlineData, GeminiPart, GeminiRequest,
+    GeminiResponse, GeminiSystemInstruction, GeminiThinkingConfig, GeminiTool,
 };
 use crate::error::{Error, Result};
 use crate::types::{
@@ -368,8 +368,10 @@ pub fn from_gemini_response(resp: GeminiResponse) -> Result<GenerateResponse> {
         FinishReason::with_raw(FinishReasonKind::ToolCalls, "TOOL_CALLS")
     } else {
         candidate
-            .and_then(|c| c.finish_reason.as_deref())
-            .map(parse_finish_reason)
+            .and_then(|c| {
+                let reason = parse_finish_reason(c.finish_reason.as_deref()?);
+                Some(reason.with_category(safety_category(c)))
+            })
             .unwrap_or_else(FinishReason::other)
     };
 
@@ -382,6 +384,7 @@ pub fn from_gemini_response(resp: GeminiResponse) -> Result<GenerateResponse> {
             "response_id": resp.response_id,
         })),
         warnings: None, // Gemini doesn't have SDK-level cache validation warnings
+        model: None,
     })
 }
 
@@ -390,13 +393,31 @@ pub(super) fn parse_finish_reason(reason: &str) -> FinishReason {
     match reason {
         "STOP" => FinishReason::with_raw(FinishReasonKind::Stop, "STOP"),
         "MAX_TOKENS" => FinishReason::with_raw(FinishReasonKind::Length, "MAX_TOKENS"),
-        "SAFETY" => FinishReason::with_raw(FinishReasonKind::ContentFilter, "SAFETY"),
-        "RECITATION" => FinishReason::with_raw(FinishReasonKind::ContentFilter, "RECITATION"),
+        "SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" | "IMAGE_SAFETY" => {
+            FinishReason::with_raw(FinishReasonKind::ContentFilter, reason)
+        }
         "OTHER" => FinishReason::with_raw(FinishReasonKind::Other, "OTHER"),
         raw => FinishReason::with_raw(FinishReasonKind::Other, raw),
     }
 }
 
+/// The harm categories that blocked a candidate, as a comma-separated list
+pub(super) fn safety_category(candidate: &GeminiCandidate) -> Option<String> {
+    let categories: Vec<String> = candidate
+        .safety_ratings
+        .as_deref()?
+        .iter()
+        .filter(|rating| rating.blocked == Some(true))
+        .filter_map(|rating| {
+            serde_json::to_value(rating.category)
+                .ok()?
+                .as_str()
+                .map(str::to_string)
+        })
+        .collect();
+    (!categories.is_empty()).then(|| categories.join(","))
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::types::GeminiCandidate;
@@ -569,6 +590,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_from_gemini_response_safety_stop_reports_blocked_category() {
+        let resp: GeminiResponse = serde_json::from_value(serde_json::json!({
+            "candidates": [{
+                "finishReason": "SAFETY",
+                "safetyRatings": [
+                    { "category": "HARM_CATEGORY_HARASSMENT", "probability": "NEGLIGIBLE" },
+                    {
+                        "category": "HARM_CATEGORY_DANGEROUS_CONTENT",
+                        "probability": "HIGH",
+                        "blocked": true
+                    }
+                ]
+            }]
+        }))
+        .unwrap();
+
+        let result = from_gemini_response(resp).unwrap();
+        assert_eq!(
+            result.finish_reason.unified,
+            FinishReasonKind::ContentFilter
+        );
+        assert_eq!(result.finish_reason.raw.as_deref(), Some("SAFETY"));
+        assert_eq!(
+            result.finish_reason.category.as_deref(),
+            Some("HARM_CATEGORY_DANGEROUS_CONTENT")
+        );
+
+        assert_eq!(
+            parse_finish_reason("PROHIBITED_CONTENT").unified,
+            FinishReasonKind::ContentFilter
+        );
+    }
+
     #[test]
     fn test_convert_messages_system_instruction() {
         let messages = vec![
@@ -5,7 +5,7 @@ use super::stream::create_stream;
 use super::types::{GeminiConfig, GeminiResponse};
 use crate::error::{Error, Result};
 use crate::provider::Provider;
-use crate::providers::tls::create_platform_tls_client;
+use crate::providers::tls::{HttpClientOptions, create_platform_tls_client_with};
 use crate::types::{GenerateRequest, GenerateResponse, GenerateStream, Headers, Model};
 use async_trait::async_trait;
 use reqwest::Client;
@@ -22,11 +22,19 @@ impl GeminiProvider {
 
     /// Create a new Gemini provider
     pub fn new(config: GeminiConfig) -> Result<Self> {
+        Self::with_http_options(config, &HttpClientOptions::default())
+    }
+
+    /// Create a new Gemini provider using the given HTTP transport options
+    pub fn with_http_options(
+        config: GeminiConfig,
+        http_options: &HttpClientOptions,
+    ) -> Result<Self> {
         if config.api_key.is_empty() {
             return Err(Error::MissingApiKey("gemini".to_string()));
         }
 
-        let client = create_platform_tls_client()?;
+        let client = create_platform_tls_client_with(http_options)?;
         Ok(Self { config, client })
     }
 
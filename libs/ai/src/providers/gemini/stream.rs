@@ -1,10 +1,10 @@
 //! Gemini streaming support
 
+use super::convert::{parse_finish_reason, safety_category};
 use super::types::GeminiResponse;
 use crate::error::{Error, Result};
 use crate::types::{
-    FinishReason, FinishReasonKind, GenerateStream, InputTokenDetails, OutputTokenDetails,
-    StreamEvent, Usage,
+    FinishReason, GenerateStream, InputTokenDetails, OutputTokenDetails, StreamEvent, Usage,
 };
 use futures::stream::StreamExt;
 use reqwest::Response;
@@ -266,12 +266,7 @@ fn process_gemini_response(
     }
 
     if let Some(finish_reason) = &candidate.finish_reason {
-        let reason = match finish_reason.as_str() {
-            "STOP" => FinishReason::with_raw(FinishReasonKind::Stop, "STOP"),
-            "MAX_TOKENS" => FinishReason::with_raw(FinishReasonKind::Length, "MAX_TOKENS"),
-            "SAFETY" => FinishReason::with_raw(FinishReasonKind::ContentFilter, "SAFETY"),
-            raw => FinishReason::with_raw(FinishReasonKind::Other, raw),
-        };
+        let reason = parse_finish_reason(finish_reason).with_category(safety_category(candidate));
         events.push(StreamEvent::finish(accumulated_usage.clone(), reason));
     }
 
@@ -284,6 +279,7 @@ mod tests {
     use crate::providers::gemini::types::{
         GeminiCandidate, GeminiContent, GeminiFunctionCall, GeminiPart,
     };
+    use crate::types::FinishReasonKind;
 
     #[test]
     fn test_process_sse_line_accepts_data_prefix_with_or_without_space() {
@@ -0,0 +1,287 @@
+//! Weighted load balancing across several API keys of one provider
+//!
+//! Requests are spread over the keys with smooth weighted round-robin: a key
+//! with weight 3 serves three requests for every one served by a key with
+//! weight 1, interleaved rather than in bursts. A key whose request is
+//! rejected with HTTP 429 is skipped until its cooldown ends.
+//!
+//! Only the Anthropic and OpenAI providers take a pool, through
+//! `with_api_keys` on their configs; other providers use a single key.
+
+use crate::error::Result;
+use crate::types::GenerateStream;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// An API key and its share of the requests
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeightedApiKey {
+    /// The API key
+    pub key: String,
+    /// Relative share of requests (default: 1). Keys with weight 0 are unused.
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+impl WeightedApiKey {
+    /// Create a weighted API key
+    pub fn new(key: impl Into<String>, weight: u32) -> Self {
+        Self {
+            key: key.into(),
+            weight,
+        }
+    }
+}
+
+impl fmt::Debug for WeightedApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeightedApiKey")
+            .field("key", &"[REDACTED]")
+            .field("weight", &self.weight)
+            .finish()
+    }
+}
+
+struct KeySlot {
+    key: String,
+    weight: i64,
+    current_weight: i64,
+    cooldown_until: Option<Instant>,
+}
+
+impl KeySlot {
+    fn is_cooling_down(&self, now: Instant) -> bool {
+        self.cooldown_until.is_some_and(|until| now < until)
+    }
+}
+
+/// A set of API keys that requests are balanced across
+pub struct ApiKeyPool {
+    slots: Mutex<Vec<KeySlot>>,
+    cooldown: Duration,
+}
+
+impl ApiKeyPool {
+    /// How long a rate-limited key is skipped by default
+    pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(60);
+
+    /// Create a pool from weighted keys, ignoring empty keys and zero weights
+    pub fn new(keys: Vec<WeightedApiKey>) -> Self {
+        let slots = keys
+            .into_iter()
+            .filter(|key| !key.key.is_empty() && key.weight > 0)
+            .map(|key| KeySlot {
+                key: key.key,
+                weight: i64::from(key.weight),
+                current_weight: 0,
+                cooldown_until: None,
+            })
+            .collect();
+
+        Self {
+            slots: Mutex::new(slots),
+            cooldown: Self::DEFAULT_COOLDOWN,
+        }
+    }
+
+    /// Set how long a rate-limited key is skipped
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Number of usable keys in the pool
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether the pool has no usable keys
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Pick the key for the next request
+    ///
+    /// When every key is cooling down, the one whose cooldown ends first is
+    /// returned rather than failing the request outright.
+    pub fn next_key(&self) -> Option<String> {
+        self.next_key_at(Instant::now())
+    }
+
+    /// Skip `key` until its cooldown ends
+    pub fn report_rate_limited(&self, key: &str) {
+        self.report_rate_limited_at(key, Instant::now());
+    }
+
+    /// Put `key` on cooldown if `result` is a rate-limit error
+    pub(crate) fn observe<T>(&self, key: &str, result: &Result<T>) {
+        if let Err(error) = result
+            && error.is_rate_limited()
+        {
+            self.report_rate_limited(key);
+        }
+    }
+
+    /// Wrap `stream` so a rate-limit error it yields puts `key` on cooldown
+    pub(crate) fn watch_stream(
+        self: &Arc<Self>,
+        key: String,
+        stream: GenerateStream,
+    ) -> GenerateStream {
+        let pool = Arc::clone(self);
+        GenerateStream::new(Box::pin(stream.inspect(move |event| {
+            if let Err(error) = event
+                && error.is_rate_limited()
+            {
+                pool.report_rate_limited(&key);
+            }
+        })))
+    }
+
+    fn next_key_at(&self, now: Instant) -> Option<String> {
+        let mut slots = self.lock();
+
+        if slots.iter().all(|slot| slot.is_cooling_down(now)) {
+            return slots
+                .iter()
+                .min_by_key(|slot| slot.cooldown_until)
+                .map(|slot| slot.key.clone());
+        }
+
+        let mut total_weight = 0;
+        let mut selected: Option<usize> = None;
+        for index in 0..slots.len() {
+            if slots[index].is_cooling_down(now) {
+                continue;
+            }
+            slots[index].current_weight += slots[index].weight;
+            total_weight += slots[index].weight;
+            if selected.is_none_or(|best| slots[index].current_weight > slots[best].current_weight)
+            {
+                selected = Some(index);
+            }
+        }
+
+        let selected = selected?;
+        slots[selected].current_weight -= total_weight;
+        Some(slots[selected].key.clone())
+    }
+
+    fn report_rate_limited_at(&self, key: &str, now: Instant) {
+        let mut slots = self.lock();
+        if let Some(slot) = slots.iter_mut().find(|slot| slot.key == key) {
+            slot.cooldown_until = Some(now + self.cooldown);
+            slot.current_weight = 0;
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<KeySlot>> {
+        self.slots
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl fmt::Debug for ApiKeyPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKeyPool")
+            .field("keys", &self.len())
+            .field("cooldown", &self.cooldown)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use std::collections::HashMap;
+
+    fn pool() -> ApiKeyPool {
+        ApiKeyPool::new(vec![
+            WeightedApiKey::new("key-a", 3),
+            WeightedApiKey::new("key-b", 1),
+            WeightedApiKey::new("key-c", 0),
+            WeightedApiKey::new("", 5),
+        ])
+    }
+
+    fn count_picks(pool: &ApiKeyPool, now: Instant, picks: usize) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for _ in 0..picks {
+            let key = pool.next_key_at(now).unwrap();
+            *counts.entry(key).or_default() += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn distribution_follows_weights() {
+        let pool = pool();
+        assert_eq!(pool.len(), 2);
+
+        let counts = count_picks(&pool, Instant::now(), 400);
+        assert_eq!(counts.get("key-a"), Some(&300));
+        assert_eq!(counts.get("key-b"), Some(&100));
+        assert!(!counts.contains_key("key-c"));
+
+        // Smooth round-robin interleaves keys instead of sending bursts
+        let now = Instant::now();
+        let order: Vec<String> = (0..4).map(|_| pool.next_key_at(now).unwrap()).collect();
+        assert_eq!(order.iter().filter(|key| *key == "key-b").count(), 1);
+    }
+
+    #[test]
+    fn rate_limited_key_is_skipped_until_cooldown_ends() {
+        let pool = pool().with_cooldown(Duration::from_secs(30));
+        let start = Instant::now();
+
+        pool.report_rate_limited_at("key-a", start);
+        let during = count_picks(&pool, start + Duration::from_secs(10), 20);
+        assert_eq!(during.get("key-b"), Some(&20));
+
+        let after = count_picks(&pool, start + Duration::from_secs(31), 40);
+        assert_eq!(after.get("key-a"), Some(&30));
+        assert_eq!(after.get("key-b"), Some(&10));
+    }
+
+    #[test]
+    fn all_keys_cooling_down_falls_back_to_earliest_recovery() {
+        let pool = pool();
+        let start = Instant::now();
+        pool.report_rate_limited_at("key-b", start);
+        pool.report_rate_limited_at("key-a", start + Duration::from_secs(5));
+
+        assert_eq!(
+            pool.next_key_at(start + Duration::from_secs(6)).as_deref(),
+            Some("key-b")
+        );
+    }
+
+    #[test]
+    fn only_rate_limit_errors_start_a_cooldown() {
+        let pool = pool();
+        let server_error: Result<()> = Err(Error::provider_error(
+            "Anthropic API error 500 Internal Server Error: oops",
+        ));
+        pool.observe("key-a", &server_error);
+        assert!(pool.lock().iter().all(|slot| slot.cooldown_until.is_none()));
+
+        let rate_limited: Result<()> = Err(Error::provider_error(
+            "Anthropic API error 429 Too Many Requests: slow down",
+        ));
+        pool.observe("key-a", &rate_limited);
+        let now = Instant::now();
+        assert!(
+            (0..5).all(|_| pool.next_key_at(now).as_deref() == Some("key-b")),
+            "rate-limited key should be skipped"
+        );
+    }
+}
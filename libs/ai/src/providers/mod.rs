@@ -5,7 +5,9 @@ pub mod anthropic;
 pub mod bedrock;
 pub mod copilot;
 pub mod gemini;
+pub mod key_pool;
 pub mod openai;
+pub mod openai_compatible;
 pub mod openrouter;
 pub mod stakpak;
 pub(crate) mod tls;
@@ -16,6 +18,9 @@ pub use anthropic::AnthropicProvider;
 pub use bedrock::BedrockProvider;
 pub use copilot::{CopilotConfig, CopilotProvider};
 pub use gemini::GeminiProvider;
+pub use key_pool::{ApiKeyPool, WeightedApiKey};
 pub use openai::OpenAIProvider;
+pub use openai_compatible::{OpenAICompatibleConfig, OpenAICompatibleProvider};
 pub use openrouter::{OpenRouterConfig, OpenRouterProvider};
 pub use stakpak::StakpakProvider;
+pub use tls::{HttpClientOptions, ProxyConfig};
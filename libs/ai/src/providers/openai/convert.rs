@@ -88,6 +88,14 @@ pub fn to_openai_request(req: &GenerateRequest, stream: bool) -> ChatCompletionR
         None
     };
 
+    let response_format = match req.provider_options.as_ref() {
+        Some(ProviderOptions::OpenAI(opts)) => match &opts.api_config {
+            Some(OpenAIApiConfig::Completions(config)) => config.response_format.clone(),
+            _ => None,
+        },
+        _ => None,
+    };
+
     ChatCompletionRequest {
         model: req.model.id.clone(),
         messages,
@@ -99,6 +107,7 @@ pub fn to_openai_request(req: &GenerateRequest, stream: bool) -> ChatCompletionR
         stream_options,
         tools,
         tool_choice,
+        response_format,
     }
 }
 
@@ -236,7 +245,9 @@ pub fn from_openai_response(resp: ChatCompletionResponse) -> Result<GenerateResp
 
     let content = parse_message_content(&choice.message)?;
 
-    let finish_reason = parse_openai_finish_reason(choice.finish_reason.as_deref());
+    let finish_reason = parse_openai_finish_reason(choice.finish_reason.as_deref()).with_category(
+        content_filter_category(choice.content_filter_results.as_ref()),
+    );
 
     // OpenAI: prompt_tokens_details.cached_tokens -> cacheRead (OpenAI doesn't report cacheWrite)
     let prompt_tokens = resp.usage.prompt_tokens;
@@ -285,6 +296,7 @@ pub fn from_openai_response(resp: ChatCompletionResponse) -> Result<GenerateResp
             "object": resp.object,
         })),
         warnings: None, // OpenAI caching is automatic, no SDK-level validation warnings
+        model: None,
     })
 }
 
@@ -305,6 +317,20 @@ fn parse_openai_finish_reason(reason: Option<&str>) -> FinishReason {
     }
 }
 
+/// The categories a content filter flagged, as a comma-separated list
+///
+/// Azure OpenAI reports `content_filter_results` as
+/// `{"hate": {"filtered": true, "severity": "high"}, ...}`.
+pub(super) fn content_filter_category(results: Option<&serde_json::Value>) -> Option<String> {
+    let categories: Vec<&str> = results?
+        .as_object()?
+        .iter()
+        .filter(|(_, result)| result.get("filtered").and_then(|f| f.as_bool()) == Some(true))
+        .map(|(category, _)| category.as_str())
+        .collect();
+    (!categories.is_empty()).then(|| categories.join(","))
+}
+
 /// Parse message content from OpenAI format
 fn parse_message_content(msg: &ChatMessage) -> Result<Vec<ResponseContent>> {
     let mut content = Vec::new();
@@ -639,7 +665,16 @@ pub fn from_responses_response(resp: ResponsesResponse) -> Result<GenerateRespon
 
     let finish_reason = match resp.status.as_str() {
         "completed" => FinishReason::with_raw(FinishReasonKind::Stop, "completed"),
-        "incomplete" => FinishReason::with_raw(FinishReasonKind::Length, "incomplete"),
+        "incomplete" => match resp
+            .incomplete_details
+            .as_ref()
+            .and_then(|details| details.reason.as_deref())
+        {
+            Some("content_filter") => {
+                FinishReason::with_raw(FinishReasonKind::ContentFilter, "content_filter")
+            }
+            _ => FinishReason::with_raw(FinishReasonKind::Length, "incomplete"),
+        },
         "failed" => FinishReason::with_raw(FinishReasonKind::Other, "failed"),
         raw => FinishReason::with_raw(FinishReasonKind::Other, raw),
     };
@@ -688,6 +723,7 @@ pub fn from_responses_response(resp: ResponsesResponse) -> Result<GenerateRespon
             "status": resp.status,
         })),
         warnings: None,
+        model: None,
     })
 }
 
@@ -997,6 +1033,7 @@ mod tests {
                 output_tokens_details: None,
             },
             status: "completed".to_string(),
+            incomplete_details: None,
         };
 
         let result = from_responses_response(resp).unwrap();
@@ -1025,6 +1062,7 @@ mod tests {
             }],
             usage: ResponsesUsage::default(),
             status: "completed".to_string(),
+            incomplete_details: None,
         };
 
         let result = from_responses_response(resp).unwrap();
@@ -1049,12 +1087,63 @@ mod tests {
             output: vec![],
             usage: ResponsesUsage::default(),
             status: "incomplete".to_string(),
+            incomplete_details: None,
         };
 
         let result = from_responses_response(resp).unwrap();
         assert_eq!(result.finish_reason.unified, FinishReasonKind::Length);
     }
 
+    #[test]
+    fn test_from_responses_response_incomplete_content_filter() {
+        let resp: ResponsesResponse = serde_json::from_value(json!({
+            "id": "resp_filtered",
+            "object": "response",
+            "created_at": 1234567890,
+            "model": "gpt-4o",
+            "output": [],
+            "usage": { "input_tokens": 10, "output_tokens": 0, "total_tokens": 10 },
+            "status": "incomplete",
+            "incomplete_details": { "reason": "content_filter" }
+        }))
+        .unwrap();
+
+        let result = from_responses_response(resp).unwrap();
+        assert_eq!(
+            result.finish_reason.unified,
+            FinishReasonKind::ContentFilter
+        );
+        assert_eq!(result.finish_reason.raw.as_deref(), Some("content_filter"));
+    }
+
+    #[test]
+    fn test_from_openai_response_content_filter_with_category() {
+        let resp: ChatCompletionResponse = serde_json::from_value(json!({
+            "id": "chatcmpl-filtered",
+            "object": "chat.completion",
+            "created": 1234567890,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": null },
+                "finish_reason": "content_filter",
+                "content_filter_results": {
+                    "self_harm": { "filtered": true, "severity": "medium" },
+                    "sexual": { "filtered": false, "severity": "safe" }
+                }
+            }],
+            "usage": { "prompt_tokens": 10, "completion_tokens": 0, "total_tokens": 10 }
+        }))
+        .unwrap();
+
+        let result = from_openai_response(resp).unwrap();
+        assert_eq!(
+            result.finish_reason.unified,
+            FinishReasonKind::ContentFilter
+        );
+        assert_eq!(result.finish_reason.category.as_deref(), Some("self_harm"));
+    }
+
     // =========================================================================
     // Input Format Tests
     // =========================================================================
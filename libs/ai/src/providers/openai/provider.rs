@@ -10,7 +10,7 @@ use super::stream::{
 use super::types::{ChatCompletionResponse, OpenAIConfig, ResponsesResponse};
 use crate::error::{Error, Result};
 use crate::provider::Provider;
-use crate::providers::tls::create_platform_tls_client;
+use crate::providers::tls::{HttpClientOptions, create_platform_tls_client_with};
 use crate::types::{
     GenerateRequest, GenerateResponse, GenerateStream, Headers, Model, OpenAIApiConfig,
     ProviderOptions,
@@ -339,15 +339,23 @@ impl OpenAIProvider {
     ///
     /// Note: API key validation is skipped when a custom base URL is configured,
     /// as OpenAI-compatible providers like Ollama may not require authentication.
-    pub fn new(mut config: OpenAIConfig) -> Result<Self> {
+    pub fn new(config: OpenAIConfig) -> Result<Self> {
+        Self::with_http_options(config, &HttpClientOptions::default())
+    }
+
+    /// Create a new OpenAI provider using the given HTTP transport options
+    pub fn with_http_options(
+        mut config: OpenAIConfig,
+        http_options: &HttpClientOptions,
+    ) -> Result<Self> {
         let is_default_url = config.base_url == Self::OFFICIAL_OPENAI_BASE_URL;
-        if config.api_key.is_empty() && is_default_url {
+        if config.api_key.is_empty() && config.api_key_pool.is_none() && is_default_url {
             return Err(Error::MissingApiKey("openai".to_string()));
         }
 
         config.base_url = config.base_url.trim_end_matches('/').to_string();
         let backend = Self::resolve_backend(&config)?;
-        let client = create_platform_tls_client()?;
+        let client = create_platform_tls_client_with(http_options)?;
         Ok(Self {
             config,
             client,
@@ -445,8 +453,8 @@ impl OpenAIProvider {
         }
     }
 
-    fn build_stream_headers(&self, request: &GenerateRequest) -> Headers {
-        let headers = self.build_headers(request.options.headers.as_ref());
+    fn build_stream_headers(&self, request: &GenerateRequest, api_key: Option<&str>) -> Headers {
+        let headers = self.build_headers_with_key(request.options.headers.as_ref(), api_key);
         match &self.backend {
             OpenAIBackend::Codex(backend) => {
                 backend.stream_transport.build_headers(headers, request)
@@ -459,18 +467,25 @@ impl OpenAIProvider {
     pub fn from_env() -> Result<Self> {
         Self::new(OpenAIConfig::default())
     }
-}
 
-#[async_trait]
-impl Provider for OpenAIProvider {
-    fn provider_id(&self) -> &str {
-        "openai"
+    /// The pooled API key for the next request, if a key pool is configured
+    fn next_pooled_key(&self) -> Option<String> {
+        if matches!(self.backend, OpenAIBackend::Codex(_)) {
+            return None;
+        }
+        self.config.api_key_pool.as_ref()?.next_key()
     }
 
-    fn build_headers(&self, custom_headers: Option<&Headers>) -> Headers {
+    /// Build headers, with `api_key` replacing the configured API key
+    fn build_headers_with_key(
+        &self,
+        custom_headers: Option<&Headers>,
+        api_key: Option<&str>,
+    ) -> Headers {
         let mut headers = Headers::new();
 
-        headers.insert("Authorization", format!("Bearer {}", self.config.api_key));
+        let api_key = api_key.unwrap_or(&self.config.api_key);
+        headers.insert("Authorization", format!("Bearer {}", api_key));
         headers.insert("Content-Type", "application/json");
 
         if let Some(org) = &self.config.organization {
@@ -486,8 +501,12 @@ impl Provider for OpenAIProvider {
         headers
     }
 
-    async fn generate(&self, request: GenerateRequest) -> Result<GenerateResponse> {
-        let headers = self.build_headers(request.options.headers.as_ref());
+    async fn send_generate(
+        &self,
+        request: GenerateRequest,
+        api_key: Option<&str>,
+    ) -> Result<GenerateResponse> {
+        let headers = self.build_headers_with_key(request.options.headers.as_ref(), api_key);
 
         if matches!(self.effective_api_mode(&request), ApiMode::Responses) {
             let url = format!("{}/responses", self.backend.base_url());
@@ -538,12 +557,16 @@ impl Provider for OpenAIProvider {
         }
     }
 
-    async fn stream(&self, request: GenerateRequest) -> Result<GenerateStream> {
+    async fn send_stream(
+        &self,
+        request: GenerateRequest,
+        api_key: Option<&str>,
+    ) -> Result<GenerateStream> {
         let api_mode = self.effective_api_mode(&request);
         let headers = if matches!(api_mode, ApiMode::Responses) {
-            self.build_stream_headers(&request)
+            self.build_stream_headers(&request, api_key)
         } else {
-            self.build_headers(request.options.headers.as_ref())
+            self.build_headers_with_key(request.options.headers.as_ref(), api_key)
         };
 
         if matches!(api_mode, ApiMode::Responses) {
@@ -593,6 +616,38 @@ impl Provider for OpenAIProvider {
             create_completions_stream(event_source).await
         }
     }
+}
+
+#[async_trait]
+impl Provider for OpenAIProvider {
+    fn provider_id(&self) -> &str {
+        "openai"
+    }
+
+    fn build_headers(&self, custom_headers: Option<&Headers>) -> Headers {
+        self.build_headers_with_key(custom_headers, None)
+    }
+
+    async fn generate(&self, request: GenerateRequest) -> Result<GenerateResponse> {
+        let pooled_key = self.next_pooled_key();
+        let result = self.send_generate(request, pooled_key.as_deref()).await;
+        if let (Some(pool), Some(key)) = (&self.config.api_key_pool, &pooled_key) {
+            pool.observe(key, &result);
+        }
+        result
+    }
+
+    async fn stream(&self, request: GenerateRequest) -> Result<GenerateStream> {
+        let pooled_key = self.next_pooled_key();
+        let result = self.send_stream(request, pooled_key.as_deref()).await;
+        match (&self.config.api_key_pool, pooled_key) {
+            (Some(pool), Some(key)) => {
+                pool.observe(&key, &result);
+                result.map(|stream| pool.watch_stream(key, stream))
+            }
+            _ => result,
+        }
+    }
 
     async fn list_models(&self) -> Result<Vec<Model>> {
         let headers = self.build_headers(None);
@@ -783,7 +838,7 @@ mod tests {
             ..Default::default()
         }));
 
-        let headers = provider.build_stream_headers(&req);
+        let headers = provider.build_stream_headers(&req, None);
 
         assert_eq!(
             headers.get("Accept"),
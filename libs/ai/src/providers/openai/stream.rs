@@ -7,6 +7,7 @@
 //! - Subsequent chunks for the same tool call have id: None and use index to identify
 //! - Accumulate tool call input and emit ToolCallEnd when finish_reason is "tool_calls"
 
+use super::convert::content_filter_category;
 use super::types::ChatCompletionChunk;
 use crate::error::{Error, Result};
 use crate::types::{FinishReason, FinishReasonKind, GenerateStream, StreamEvent, Usage};
@@ -200,6 +201,9 @@ fn parse_chunk(
             "length" => FinishReason::with_raw(FinishReasonKind::Length, "length"),
             "content_filter" => {
                 FinishReason::with_raw(FinishReasonKind::ContentFilter, "content_filter")
+                    .with_category(content_filter_category(
+                        choice.content_filter_results.as_ref(),
+                    ))
             }
             "tool_calls" => FinishReason::with_raw(FinishReasonKind::ToolCalls, "tool_calls"),
             raw => FinishReason::with_raw(FinishReasonKind::Other, raw),
@@ -693,7 +697,7 @@ fn parse_responses_event(
             }
         }
 
-        "response.completed" => {
+        "response.completed" | "response.incomplete" => {
             let response = &event["response"];
 
             // Parse usage
@@ -710,7 +714,12 @@ fn parse_responses_event(
             let status = response["status"].as_str().unwrap_or("completed");
             let mut finish_reason = match status {
                 "completed" => FinishReason::with_raw(FinishReasonKind::Stop, "stop"),
-                "incomplete" => FinishReason::with_raw(FinishReasonKind::Length, "length"),
+                "incomplete" => match response["incomplete_details"]["reason"].as_str() {
+                    Some("content_filter") => {
+                        FinishReason::with_raw(FinishReasonKind::ContentFilter, "content_filter")
+                    }
+                    _ => FinishReason::with_raw(FinishReasonKind::Length, "length"),
+                },
                 "failed" | "cancelled" => FinishReason::with_raw(FinishReasonKind::Other, "error"),
                 "in_progress" | "queued" => FinishReason::with_raw(FinishReasonKind::Stop, "stop"),
                 _ => FinishReason::with_raw(FinishReasonKind::Stop, "stop"),
@@ -784,6 +793,7 @@ mod tests {
                     tool_calls,
                 },
                 finish_reason: finish_reason.map(|s| s.to_string()),
+                content_filter_results: None,
             }],
             usage,
         };
@@ -1035,6 +1045,61 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_finish_content_filter_reports_flagged_categories() {
+        let mut usage = None;
+        let mut tool_calls = std::collections::HashMap::new();
+
+        let chunk = serde_json::json!({
+            "id": "chatcmpl-filtered",
+            "object": "chat.completion.chunk",
+            "created": 0,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "delta": {},
+                "finish_reason": "content_filter",
+                "content_filter_results": {
+                    "hate": { "filtered": false, "severity": "safe" },
+                    "violence": { "filtered": true, "severity": "high" }
+                }
+            }]
+        })
+        .to_string();
+
+        let events = parse_chunk(&chunk, &mut usage, &mut tool_calls).unwrap();
+        if let StreamEvent::Finish { reason, .. } = &events[0] {
+            assert_eq!(reason.unified, FinishReasonKind::ContentFilter);
+            assert_eq!(reason.category.as_deref(), Some("violence"));
+        } else {
+            panic!("Expected Finish event");
+        }
+    }
+
+    #[test]
+    fn test_responses_incomplete_content_filter_finishes_as_content_filter() {
+        let mut state = ResponsesStreamState::default();
+        let mut started = true;
+        let data = serde_json::json!({
+            "type": "response.incomplete",
+            "response": {
+                "status": "incomplete",
+                "incomplete_details": { "reason": "content_filter" },
+                "usage": { "input_tokens": 8, "output_tokens": 2 }
+            }
+        })
+        .to_string();
+
+        let events =
+            parse_responses_event("response.incomplete", &data, &mut state, &mut started).unwrap();
+        if let Some(StreamEvent::Finish { reason, .. }) = events.last() {
+            assert_eq!(reason.unified, FinishReasonKind::ContentFilter);
+            assert_eq!(reason.raw.as_deref(), Some("content_filter"));
+        } else {
+            panic!("Expected Finish event");
+        }
+    }
+
     #[tokio::test]
     async fn test_create_responses_stream_from_response_without_content_type() {
         let mut server = mockito::Server::new_async().await;
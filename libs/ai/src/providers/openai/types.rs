@@ -1,7 +1,9 @@
 //! OpenAI-specific types
 
+use crate::providers::key_pool::{ApiKeyPool, WeightedApiKey};
 use crate::types::{Headers, OpenAIOptions};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 /// Configuration for OpenAI provider
 #[derive(Debug, Clone)]
@@ -16,6 +18,8 @@ pub struct OpenAIConfig {
     pub custom_headers: Headers,
     /// Default OpenAI-specific options applied when a request does not specify any.
     pub default_openai_options: Option<OpenAIOptions>,
+    /// Extra API keys to balance requests across (overrides `api_key`)
+    pub api_key_pool: Option<Arc<ApiKeyPool>>,
 }
 
 impl OpenAIConfig {
@@ -27,6 +31,7 @@ impl OpenAIConfig {
             organization: None,
             custom_headers: Headers::new(),
             default_openai_options: None,
+            api_key_pool: None,
         }
     }
 
@@ -42,6 +47,16 @@ impl OpenAIConfig {
         self
     }
 
+    /// Balance requests across several weighted API keys
+    ///
+    /// Each request uses one key from the pool, chosen by weight; a key that
+    /// is rate limited (HTTP 429) is skipped for a cooldown period.
+    pub fn with_api_keys(mut self, keys: Vec<WeightedApiKey>) -> Self {
+        let pool = ApiKeyPool::new(keys);
+        self.api_key_pool = (!pool.is_empty()).then(|| Arc::new(pool));
+        self
+    }
+
     /// Add a provider-level custom header.
     pub fn with_custom_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.custom_headers.insert(key, value);
@@ -88,6 +103,8 @@ pub struct ChatCompletionRequest {
     pub tools: Option<Vec<serde_json::Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_choice: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<serde_json::Value>,
 }
 
 /// Options for streaming responses
@@ -149,6 +166,9 @@ pub struct ChatChoice {
     pub index: u32,
     pub message: ChatMessage,
     pub finish_reason: Option<String>,
+    /// Per-category filter verdicts (Azure OpenAI)
+    #[serde(default)]
+    pub content_filter_results: Option<serde_json::Value>,
 }
 
 /// OpenAI prompt token details
@@ -214,6 +234,9 @@ pub struct ChunkChoice {
     pub index: u32,
     pub delta: ChatDelta,
     pub finish_reason: Option<String>,
+    /// Per-category filter verdicts (Azure OpenAI)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_filter_results: Option<serde_json::Value>,
 }
 
 /// OpenAI delta content
@@ -328,6 +351,16 @@ pub struct ResponsesResponse {
     pub usage: ResponsesUsage,
     #[serde(default)]
     pub status: String,
+    /// Why the response is `incomplete` (e.g. `max_output_tokens`, `content_filter`)
+    #[serde(default)]
+    pub incomplete_details: Option<ResponsesIncompleteDetails>,
+}
+
+/// Details of an incomplete Responses API response
+#[derive(Debug, Deserialize)]
+pub struct ResponsesIncompleteDetails {
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 /// Output item in Responses API response
@@ -0,0 +1,7 @@
+//! Generic OpenAI-compatible provider (vLLM, LiteLLM, Together, Groq, ...)
+
+mod provider;
+pub mod types;
+
+pub use provider::OpenAICompatibleProvider;
+pub use types::OpenAICompatibleConfig;
@@ -0,0 +1,250 @@
+//! OpenAI-compatible provider implementation
+
+use super::types::OpenAICompatibleConfig;
+use crate::error::{Error, Result};
+use crate::provider::Provider;
+use crate::providers::openai::convert::{from_openai_response, to_openai_request};
+use crate::providers::openai::stream::create_completions_stream;
+use crate::providers::openai::types::{ChatCompletionRequest, ChatCompletionResponse};
+use crate::providers::tls::{HttpClientOptions, create_platform_tls_client_with};
+use crate::types::{GenerateRequest, GenerateResponse, GenerateStream, Headers};
+use async_trait::async_trait;
+use reqwest::Client;
+use reqwest_eventsource::EventSource;
+
+/// Provider for any backend speaking the OpenAI Chat Completions API
+pub struct OpenAICompatibleProvider {
+    config: OpenAICompatibleConfig,
+    client: Client,
+}
+
+impl OpenAICompatibleProvider {
+    /// Create a new OpenAI-compatible provider
+    pub fn new(config: OpenAICompatibleConfig) -> Result<Self> {
+        Self::with_http_options(config, &HttpClientOptions::default())
+    }
+
+    /// Create a new OpenAI-compatible provider using the given HTTP transport options
+    pub fn with_http_options(
+        mut config: OpenAICompatibleConfig,
+        http_options: &HttpClientOptions,
+    ) -> Result<Self> {
+        if config.base_url.is_empty() {
+            return Err(Error::ConfigError(format!(
+                "OpenAI-compatible provider '{}' requires a base URL",
+                config.provider_id
+            )));
+        }
+        config.base_url = config.base_url.trim_end_matches('/').to_string();
+        let client = create_platform_tls_client_with(http_options)?;
+        Ok(Self { config, client })
+    }
+
+    /// Build the Chat Completions body, dropping fields the backend rejects
+    fn build_request(&self, request: &GenerateRequest, stream: bool) -> ChatCompletionRequest {
+        let mut openai_req = to_openai_request(request, stream);
+        if !self.config.supports_stream_options {
+            openai_req.stream_options = None;
+        }
+        if !self.config.supports_response_format {
+            openai_req.response_format = None;
+        }
+        openai_req
+    }
+}
+
+#[async_trait]
+impl Provider for OpenAICompatibleProvider {
+    fn provider_id(&self) -> &str {
+        &self.config.provider_id
+    }
+
+    fn build_headers(&self, custom_headers: Option<&Headers>) -> Headers {
+        let mut headers = Headers::new();
+
+        if let Some(api_key) = self.config.api_key.as_ref().filter(|k| !k.is_empty()) {
+            headers.insert("Authorization", format!("Bearer {}", api_key));
+        }
+        headers.insert("Content-Type", "application/json");
+        headers.merge_with(&self.config.default_headers);
+
+        if let Some(custom) = custom_headers {
+            headers.merge_with(custom);
+        }
+
+        headers
+    }
+
+    async fn generate(&self, request: GenerateRequest) -> Result<GenerateResponse> {
+        let url = format!("{}/chat/completions", self.config.base_url);
+        let headers = self.build_headers(request.options.headers.as_ref());
+
+        let openai_req = self.build_request(&request, false);
+
+        let response = self
+            .client
+            .post(&url)
+            .headers(headers.to_reqwest_headers())
+            .json(&openai_req)
+            .send()
+            .await
+            .map_err(|e| {
+                Error::provider_error(format!("{} request failed: {}", self.config.provider_id, e))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(Error::provider_error(format!(
+                "{} returned error {}: {}",
+                self.config.provider_id, status, text
+            )));
+        }
+
+        let openai_resp: ChatCompletionResponse = response.json().await?;
+        from_openai_response(openai_resp)
+    }
+
+    async fn stream(&self, request: GenerateRequest) -> Result<GenerateStream> {
+        let url = format!("{}/chat/completions", self.config.base_url);
+        let headers = self.build_headers(request.options.headers.as_ref());
+
+        let openai_req = self.build_request(&request, true);
+
+        let request_builder = self
+            .client
+            .post(&url)
+            .headers(headers.to_reqwest_headers())
+            .json(&openai_req);
+
+        let event_source = EventSource::new(request_builder)
+            .map_err(|e| Error::provider_error(format!("Failed to create event source: {}", e)))?;
+
+        create_completions_stream(event_source).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        CompletionsConfig, Message, Model, OpenAIApiConfig, OpenAIOptions, ProviderOptions, Role,
+    };
+    use futures::StreamExt;
+
+    const COMPLETION_BODY: &str = r#"{"id":"chatcmpl-1","object":"chat.completion","created":0,"model":"llama-3","choices":[{"index":0,"message":{"role":"assistant","content":"hello"},"finish_reason":"stop"}],"usage":{"prompt_tokens":3,"completion_tokens":1,"total_tokens":4}}"#;
+
+    fn json_mode_request() -> GenerateRequest {
+        let mut request = GenerateRequest::new(
+            Model::custom("llama-3", "vllm"),
+            vec![Message::new(Role::User, "Hi")],
+        );
+        request.provider_options = Some(ProviderOptions::OpenAI(OpenAIOptions {
+            api_config: Some(OpenAIApiConfig::Completions(CompletionsConfig {
+                response_format: Some(serde_json::json!({"type": "json_object"})),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }));
+        request
+    }
+
+    #[tokio::test]
+    async fn test_generate_uses_custom_base_path_and_headers() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/serving/openai/v2/chat/completions")
+            .match_header("authorization", "Bearer test-key")
+            .match_header("x-tenant", "acme")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "model": "llama-3",
+                "response_format": {"type": "json_object"}
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(COMPLETION_BODY)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let provider = OpenAICompatibleProvider::new(
+            OpenAICompatibleConfig::new("vllm", format!("{}/serving/openai/v2/", server.url()))
+                .with_api_key("test-key")
+                .with_default_header("X-Tenant", "acme"),
+        )
+        .unwrap();
+
+        let response = provider.generate(json_mode_request()).await.unwrap();
+
+        assert_eq!(response.text(), "hello");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_generate_strips_unsupported_response_format() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/chat/completions")
+            .match_request(|request| {
+                let body = request.utf8_lossy_body().unwrap_or_default();
+                !body.contains("response_format")
+            })
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(COMPLETION_BODY)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let provider = OpenAICompatibleProvider::new(
+            OpenAICompatibleConfig::new("groq", format!("{}/v1", server.url()))
+                .with_response_format_support(false),
+        )
+        .unwrap();
+
+        provider.generate(json_mode_request()).await.unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_stream_strips_unsupported_stream_options() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/chat/completions")
+            .match_header("authorization", mockito::Matcher::Missing)
+            .match_request(|request| {
+                let body = request.utf8_lossy_body().unwrap_or_default();
+                body.contains("\"stream\":true") && !body.contains("stream_options")
+            })
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(
+                "data: {\"id\":\"c1\",\"object\":\"chat.completion.chunk\",\"created\":0,\"model\":\"llama-3\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"hi\"},\"finish_reason\":\"stop\"}]}\n\ndata: [DONE]\n\n",
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let provider = OpenAICompatibleProvider::new(
+            OpenAICompatibleConfig::new("litellm", format!("{}/v1", server.url()))
+                .with_stream_options_support(false),
+        )
+        .unwrap();
+
+        let request = GenerateRequest::new(
+            Model::custom("llama-3", "litellm"),
+            vec![Message::new(Role::User, "Hi")],
+        );
+        let mut stream = provider.stream(request).await.unwrap();
+        while let Some(event) = stream.next().await {
+            event.unwrap();
+        }
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_empty_base_url_is_rejected() {
+        let result = OpenAICompatibleProvider::new(OpenAICompatibleConfig::new("vllm", ""));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,56 @@
+use crate::types::Headers;
+
+/// Configuration for an OpenAI-compatible Chat Completions endpoint
+#[derive(Debug, Clone)]
+pub struct OpenAICompatibleConfig {
+    /// Provider ID used for registry routing (e.g., "groq", "vllm")
+    pub provider_id: String,
+    /// Base URL including any path prefix (e.g., `http://localhost:8000/v1`)
+    pub base_url: String,
+    /// API key sent as a Bearer token; omitted when `None`
+    pub api_key: Option<String>,
+    /// Headers applied to every request
+    pub default_headers: Headers,
+    /// Whether the backend accepts `stream_options` (default: true)
+    pub supports_stream_options: bool,
+    /// Whether the backend accepts `response_format` (default: true)
+    pub supports_response_format: bool,
+}
+
+impl OpenAICompatibleConfig {
+    /// Create a new config for the given provider ID and base URL
+    pub fn new(provider_id: impl Into<String>, base_url: impl Into<String>) -> Self {
+        Self {
+            provider_id: provider_id.into(),
+            base_url: base_url.into(),
+            api_key: None,
+            default_headers: Headers::new(),
+            supports_stream_options: true,
+            supports_response_format: true,
+        }
+    }
+
+    /// Set the API key
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Add a default header applied to every request
+    pub fn with_default_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.default_headers.insert(key, value);
+        self
+    }
+
+    /// Set whether the backend accepts `stream_options`
+    pub fn with_stream_options_support(mut self, supported: bool) -> Self {
+        self.supports_stream_options = supported;
+        self
+    }
+
+    /// Set whether the backend accepts `response_format`
+    pub fn with_response_format_support(mut self, supported: bool) -> Self {
+        self.supports_response_format = supported;
+        self
+    }
+}
@@ -4,7 +4,7 @@ use crate::provider::Provider;
 use crate::providers::openai::convert::{from_openai_response, to_openai_request};
 use crate::providers::openai::stream::create_completions_stream;
 use crate::providers::openai::types::ChatCompletionResponse;
-use crate::providers::tls::create_platform_tls_client;
+use crate::providers::tls::{HttpClientOptions, create_platform_tls_client_with};
 use crate::types::{GenerateRequest, GenerateResponse, GenerateStream, Headers, Model};
 use async_trait::async_trait;
 use reqwest::Client;
@@ -17,10 +17,18 @@ pub struct OpenRouterProvider {
 
 impl OpenRouterProvider {
     pub fn new(config: OpenRouterConfig) -> Result<Self> {
+        Self::with_http_options(config, &HttpClientOptions::default())
+    }
+
+    /// Create a new OpenRouter provider using the given HTTP transport options
+    pub fn with_http_options(
+        config: OpenRouterConfig,
+        http_options: &HttpClientOptions,
+    ) -> Result<Self> {
         if config.api_key.is_empty() {
             return Err(Error::MissingApiKey("openrouter".to_string()));
         }
-        let client = create_platform_tls_client()?;
+        let client = create_platform_tls_client_with(http_options)?;
         Ok(Self { config, client })
     }
 }
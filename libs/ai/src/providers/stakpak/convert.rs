@@ -62,6 +62,7 @@ pub fn to_stakpak_request(req: &GenerateRequest, stream: bool) -> ChatCompletion
         stream_options,
         tools,
         tool_choice,
+        response_format: None,
     }
 }
 
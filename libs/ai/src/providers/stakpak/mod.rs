@@ -8,5 +8,5 @@ mod provider;
 mod stream;
 mod types;
 
-pub use provider::StakpakProvider;
+pub use provider::{EXCEEDED_API_LIMIT, StakpakProvider, exceeded_api_limit_message};
 pub use types::StakpakProviderConfig;
@@ -5,7 +5,7 @@ use super::stream::create_stream;
 use super::types::{StakpakModelsResponse, StakpakProviderConfig, StakpakResponse};
 use crate::error::{Error, Result};
 use crate::provider::Provider;
-use crate::providers::tls::create_platform_tls_client;
+use crate::providers::tls::{HttpClientOptions, create_platform_tls_client_with};
 use crate::types::{
     FinishReason, FinishReasonKind, GenerateRequest, GenerateResponse, GenerateStream, Headers,
     InputTokenDetails, Model, OutputTokenDetails, ResponseContent, ToolCall, Usage,
@@ -30,11 +30,19 @@ pub struct StakpakProvider {
 impl StakpakProvider {
     /// Create a new Stakpak provider
     pub fn new(config: StakpakProviderConfig) -> Result<Self> {
+        Self::with_http_options(config, &HttpClientOptions::default())
+    }
+
+    /// Create a new Stakpak provider using the given HTTP transport options
+    pub fn with_http_options(
+        config: StakpakProviderConfig,
+        http_options: &HttpClientOptions,
+    ) -> Result<Self> {
         if config.api_key.is_empty() {
             return Err(Error::MissingApiKey("stakpak".to_string()));
         }
 
-        let client = create_platform_tls_client()?;
+        let client = create_platform_tls_client_with(http_options)?;
         Ok(Self {
             config,
             client,
@@ -108,6 +116,12 @@ impl Provider for StakpakProvider {
     }
 
     async fn generate(&self, request: GenerateRequest) -> Result<GenerateResponse> {
+        if self.config.stream_generate {
+            // Long completions keep the connection busy instead of idling
+            // until the whole body is ready
+            return self.stream(request).await?.collect_response().await;
+        }
+
         let url = format!("{}/v1/chat/completions", self.config.base_url);
 
         let openai_req = to_stakpak_request(&request, false);
@@ -236,6 +250,7 @@ fn from_stakpak_response(resp: StakpakResponse) -> Result<GenerateResponse> {
             "object": resp.object,
         })),
         warnings: None,
+        model: None,
     })
 }
 
@@ -269,6 +284,17 @@ fn parse_stakpak_message(msg: &super::types::StakpakMessage) -> Result<Vec<Respo
     Ok(content)
 }
 
+/// Error key the Stakpak API returns when the account is out of credits
+pub const EXCEEDED_API_LIMIT: &str = "EXCEEDED_API_LIMIT";
+
+/// An `EXCEEDED_API_LIMIT` error message with the billing top-up link
+pub fn exceeded_api_limit_message(message: &str) -> String {
+    format!(
+        "{}. You can top up your billing at https://stakpak.dev/settings/billing",
+        message
+    )
+}
+
 /// Parse Stakpak API error and return user-friendly message
 pub(crate) fn parse_stakpak_error(error_text: &str, status_code: u16) -> String {
     // Try to parse as JSON error
@@ -278,6 +304,10 @@ pub(crate) fn parse_stakpak_error(error_text: &str, status_code: u16) -> String
         let message = error.get("message").and_then(|m| m.as_str()).unwrap_or("");
         let error_type = error.get("type").and_then(|t| t.as_str()).unwrap_or("");
 
+        if error.get("key").and_then(|k| k.as_str()) == Some(EXCEEDED_API_LIMIT) {
+            return exceeded_api_limit_message(message);
+        }
+
         // Check for insufficient credits
         if message.contains("Exceeded credits") || message.contains("balance is") {
             return format!(
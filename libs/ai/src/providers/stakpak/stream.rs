@@ -124,11 +124,20 @@ pub async fn create_stream(event_source: EventSource) -> Result<GenerateStream>
                     )));
                     break;
                 }
-                Err(reqwest_eventsource::Error::InvalidContentType(content_type, _)) => {
-                    yield Err(Error::stream_error(format!(
-                        "Invalid content type from server: {:?} (expected text/event-stream)",
-                        content_type
-                    )));
+                Err(reqwest_eventsource::Error::InvalidContentType(content_type, response)) => {
+                    // Errors can come back as a plain JSON body instead of an event stream
+                    let status = response.status();
+                    let body = response.text().await.unwrap_or_default();
+                    if serde_json::from_str::<serde_json::Value>(&body)
+                        .is_ok_and(|json| json.get("error").is_some())
+                    {
+                        yield Err(Error::provider_error(parse_stakpak_error(&body, status.as_u16())));
+                    } else {
+                        yield Err(Error::stream_error(format!(
+                            "Invalid content type from server: {:?} (expected text/event-stream)",
+                            content_type
+                        )));
+                    }
                     break;
                 }
                 Err(e) => {
@@ -14,6 +14,9 @@ pub struct StakpakProviderConfig {
     pub base_url: String,
     /// User-Agent header (e.g., "Stakpak/1.0.0")
     pub user_agent: Option<String>,
+    /// Serve `generate` from the streaming endpoint, so the HTTP read timeout
+    /// applies between chunks instead of to the whole buffered body
+    pub stream_generate: bool,
 }
 
 impl StakpakProviderConfig {
@@ -23,6 +26,7 @@ impl StakpakProviderConfig {
             api_key: api_key.into(),
             base_url: "https://apiv2.stakpak.dev".to_string(),
             user_agent: None,
+            stream_generate: false,
         }
     }
 
@@ -37,6 +41,12 @@ impl StakpakProviderConfig {
         self.user_agent = Some(user_agent.into());
         self
     }
+
+    /// Serve `generate` from the streaming endpoint
+    pub fn with_stream_generate(mut self, stream_generate: bool) -> Self {
+        self.stream_generate = stream_generate;
+        self
+    }
 }
 
 impl Default for StakpakProviderConfig {
@@ -45,6 +55,7 @@ impl Default for StakpakProviderConfig {
             api_key: std::env::var("STAKPAK_API_KEY").unwrap_or_else(|_| String::new()),
             base_url: "https://apiv2.stakpak.dev".to_string(),
             user_agent: None,
+            stream_generate: false,
         }
     }
 }
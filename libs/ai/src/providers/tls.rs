@@ -4,10 +4,112 @@
 //! that validate server certificates against the OS-provided CA certificate
 //! store. This is important for enterprise environments with custom CA certs
 //! (e.g., corporate proxies, private PKI).
+//!
+//! Additional PEM CA bundles can be trusted on top of the OS store via
+//! [`HttpClientOptions::extra_ca_certs`] or the `STAKAI_EXTRA_CA_CERTS`
+//! environment variable, for TLS-inspecting proxies whose CA is not installed
+//! system-wide.
 
 use crate::error::{Error, Result};
 use reqwest::Client;
-use rustls_platform_verifier::BuilderVerifierExt;
+use rustls::pki_types::{CertificateDer, pem::PemObject};
+use rustls_platform_verifier::{BuilderVerifierExt, Verifier};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Environment variable listing extra PEM CA bundles, separated like `PATH`.
+pub const EXTRA_CA_CERTS_ENV: &str = "STAKAI_EXTRA_CA_CERTS";
+
+/// Idle time allowed between reads of a response.
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Explicit HTTP(S) proxy for provider requests.
+///
+/// When no proxy is configured, provider clients fall back to the standard
+/// `HTTP_PROXY` / `HTTPS_PROXY` / `ALL_PROXY` / `NO_PROXY` environment variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// Proxy URL (e.g., `http://proxy.corp.example:3128`)
+    pub url: String,
+    /// Optional basic-auth username
+    pub username: Option<String>,
+    /// Optional basic-auth password
+    pub password: Option<String>,
+}
+
+impl ProxyConfig {
+    /// Create a proxy configuration without authentication
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            username: None,
+            password: None,
+        }
+    }
+
+    /// Set basic-auth credentials for the proxy
+    pub fn with_basic_auth(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.username = Some(username.into());
+        self.password = Some(password.into());
+        self
+    }
+
+    fn to_reqwest_proxy(&self) -> Result<reqwest::Proxy> {
+        let mut proxy = reqwest::Proxy::all(&self.url)
+            .map_err(|e| Error::ConfigError(format!("Invalid proxy URL '{}': {}", self.url, e)))?;
+        if let Some(username) = &self.username {
+            proxy = proxy.basic_auth(username, self.password.as_deref().unwrap_or_default());
+        }
+        // Hosts listed in NO_PROXY still bypass an explicitly configured proxy.
+        Ok(proxy.no_proxy(reqwest::NoProxy::from_env()))
+    }
+}
+
+/// Transport options shared by every provider's HTTP client.
+#[derive(Debug, Clone, Default)]
+pub struct HttpClientOptions {
+    /// Explicit proxy; `None` defers to the proxy environment variables
+    pub proxy: Option<ProxyConfig>,
+    /// PEM CA bundles trusted in addition to the OS certificate store
+    pub extra_ca_certs: Vec<PathBuf>,
+    /// Longest wait for the next chunk of a response; defaults to 300s
+    pub read_timeout: Option<Duration>,
+}
+
+/// Collect the extra CA bundle paths from options and `STAKAI_EXTRA_CA_CERTS`.
+fn extra_ca_cert_paths(options: &HttpClientOptions) -> Vec<PathBuf> {
+    let mut paths = options.extra_ca_certs.clone();
+    if let Some(env_paths) = std::env::var_os(EXTRA_CA_CERTS_ENV) {
+        paths.extend(std::env::split_paths(&env_paths).filter(|p| !p.as_os_str().is_empty()));
+    }
+    paths
+}
+
+/// Load every certificate from a PEM bundle.
+fn load_pem_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|iter| iter.collect::<std::result::Result<Vec<_>, _>>())
+        .map_err(|e| {
+            Error::ConfigError(format!(
+                "Failed to load CA certificates from {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+    if certs.is_empty() {
+        return Err(Error::ConfigError(format!(
+            "No CA certificates found in {}",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
 
 /// Create an HTTP client configured with platform-verified TLS.
 ///
@@ -15,22 +117,49 @@ use rustls_platform_verifier::BuilderVerifierExt;
 /// `rustls-platform-verifier`, ensuring proper certificate validation
 /// when calling provider APIs.
 pub fn create_platform_tls_client() -> Result<Client> {
-    let arc_crypto_provider = std::sync::Arc::new(rustls::crypto::ring::default_provider());
-    let tls_config = rustls::ClientConfig::builder_with_provider(arc_crypto_provider)
+    create_platform_tls_client_with(&HttpClientOptions::default())
+}
+
+/// Same as [`create_platform_tls_client`], applying the given transport options.
+pub fn create_platform_tls_client_with(options: &HttpClientOptions) -> Result<Client> {
+    let arc_crypto_provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = rustls::ClientConfig::builder_with_provider(arc_crypto_provider.clone())
         .with_safe_default_protocol_versions()
-        .map_err(|e| Error::provider_error(format!("Failed to build TLS config: {}", e)))?
-        .with_platform_verifier()
-        .with_no_client_auth();
+        .map_err(|e| Error::provider_error(format!("Failed to build TLS config: {}", e)))?;
 
-    Client::builder()
+    let extra_ca_paths = extra_ca_cert_paths(options);
+    let tls_config = if extra_ca_paths.is_empty() {
+        builder.with_platform_verifier().with_no_client_auth()
+    } else {
+        let mut extra_roots = Vec::new();
+        for path in &extra_ca_paths {
+            extra_roots.extend(load_pem_certs(path)?);
+        }
+        let verifier = Verifier::new_with_extra_roots(extra_roots)
+            .map_err(|e| Error::provider_error(format!("Failed to build TLS verifier: {}", e)))?
+            .with_provider(arc_crypto_provider);
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(verifier))
+            .with_no_client_auth()
+    };
+
+    let mut builder = Client::builder()
         .use_preconfigured_tls(tls_config)
         // Use read_timeout instead of timeout: read_timeout only fires when no
         // data arrives for the given duration (idle timeout), while timeout caps
         // the *entire* request lifecycle. SSE streams can legitimately run for
         // many minutes during long tool calls, so a total timeout causes
         // spurious "Transport error: TimedOut" failures.
-        .read_timeout(std::time::Duration::from_secs(300))
-        .connect_timeout(std::time::Duration::from_secs(30))
+        .read_timeout(options.read_timeout.unwrap_or(DEFAULT_READ_TIMEOUT))
+        .connect_timeout(std::time::Duration::from_secs(30));
+
+    // An explicit proxy replaces reqwest's environment-derived proxies.
+    if let Some(proxy) = &options.proxy {
+        builder = builder.proxy(proxy.to_reqwest_proxy()?);
+    }
+
+    builder
         .build()
         .map_err(|e| Error::provider_error(format!("Failed to create TLS HTTP client: {}", e)))
 }
@@ -64,6 +193,158 @@ mod tests {
         assert!(client3.is_ok());
     }
 
+    #[test]
+    fn test_invalid_proxy_url_is_rejected() {
+        let options = HttpClientOptions {
+            proxy: Some(ProxyConfig::new("not a url")),
+            ..Default::default()
+        };
+        assert!(create_platform_tls_client_with(&options).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_requests_traverse_configured_proxy() {
+        let mut proxy = mockito::Server::new_async().await;
+        let mock = proxy
+            .mock("GET", "/ping")
+            .match_header("host", "provider.stakai-proxy-test.invalid")
+            .with_status(200)
+            .with_body("pong")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let options = HttpClientOptions {
+            proxy: Some(ProxyConfig::new(proxy.url())),
+            ..Default::default()
+        };
+        let client = create_platform_tls_client_with(&options).unwrap();
+        let response = client
+            .get("http://provider.stakai-proxy-test.invalid/ping")
+            .send()
+            .await
+            .expect("request should be routed through the proxy");
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), "pong");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_proxy_basic_auth_is_sent() {
+        let mut proxy = mockito::Server::new_async().await;
+        // "user:secret" base64-encoded
+        let mock = proxy
+            .mock("GET", "/ping")
+            .match_header("proxy-authorization", "Basic dXNlcjpzZWNyZXQ=")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let options = HttpClientOptions {
+            proxy: Some(ProxyConfig::new(proxy.url()).with_basic_auth("user", "secret")),
+            ..Default::default()
+        };
+        let client = create_platform_tls_client_with(&options).unwrap();
+        let response = client
+            .get("http://provider.stakai-proxy-test.invalid/ping")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_missing_ca_bundle_is_rejected() {
+        let options = HttpClientOptions {
+            extra_ca_certs: vec![PathBuf::from("/nonexistent/stakai-ca.pem")],
+            ..Default::default()
+        };
+        let err = create_platform_tls_client_with(&options).unwrap_err();
+        assert!(err.to_string().contains("stakai-ca.pem"), "{err}");
+    }
+
+    /// Spawn a one-shot HTTPS server on localhost with a freshly generated
+    /// self-signed certificate. Returns the port and the certificate as PEM.
+    async fn spawn_self_signed_server() -> (u16, String) {
+        use rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert_pem = cert.serialize_pem().unwrap();
+        let cert_der = CertificateDer::from(cert.serialize_der().unwrap());
+        let key_der =
+            PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(cert.serialize_private_key_der()));
+
+        let server_config = rustls::ServerConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert_der], key_der)
+        .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(server_config));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let Ok(mut tls) = acceptor.accept(stream).await else {
+                    continue;
+                };
+                let mut buf = [0u8; 4096];
+                let _ = tls.read(&mut buf).await;
+                let _ = tls
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok",
+                    )
+                    .await;
+                let _ = tls.shutdown().await;
+            }
+        });
+
+        (port, cert_pem)
+    }
+
+    #[tokio::test]
+    async fn test_self_signed_server_rejected_without_extra_ca() {
+        let (port, _) = spawn_self_signed_server().await;
+        let client = create_platform_tls_client().unwrap();
+        let response = client
+            .get(format!("https://localhost:{port}/"))
+            .send()
+            .await;
+        assert!(
+            response.is_err(),
+            "self-signed certificate should not be trusted by default"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_self_signed_server_accepted_with_extra_ca() {
+        let (port, cert_pem) = spawn_self_signed_server().await;
+        let dir = tempfile::tempdir().unwrap();
+        let ca_path = dir.path().join("ca.pem");
+        std::fs::write(&ca_path, cert_pem).unwrap();
+
+        let options = HttpClientOptions {
+            extra_ca_certs: vec![ca_path],
+            ..Default::default()
+        };
+        let client = create_platform_tls_client_with(&options).unwrap();
+        let response = client
+            .get(format!("https://localhost:{port}/"))
+            .send()
+            .await
+            .expect("request should succeed once the CA is trusted");
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), "ok");
+    }
+
     #[tokio::test]
     #[cfg(feature = "network-tests")]
     async fn test_tls_client_validates_valid_certificate() {
@@ -168,6 +168,17 @@ pub fn load_models_for_provider_from_path(provider_id: &str, path: &Path) -> Res
         .unwrap_or_default())
 }
 
+/// Load all cached models.dev providers from the default cache location
+///
+/// Uses `~/.stakpak/cache/models.json` as the cache file.
+pub fn load_models_dev_cache() -> Result<HashMap<String, ProviderInfo>> {
+    let cache_path = dirs::home_dir()
+        .unwrap_or_default()
+        .join(DEFAULT_CACHE_PATH);
+
+    load_cache_file(&cache_path)
+}
+
 /// Load all models from providers that have authentication configured
 pub fn load_available_models() -> Result<Vec<Model>> {
     let cache_path = dirs::home_dir()
@@ -0,0 +1,325 @@
+//! Prompt token counting
+//!
+//! Counts how many input tokens a set of messages and tools will consume
+//! before a request is sent, so callers can compact context proactively
+//! instead of waiting for a context-window error.
+//!
+//! - **OpenAI models**: encoded with the matching tiktoken encoding
+//!   (`o200k_base` or `cl100k_base`) plus OpenAI's per-message framing.
+//!   Text-only counts are reported as [`TokenCountAccuracy::Exact`].
+//! - **Everything else**: a bytes-per-token heuristic tuned per provider,
+//!   reported as [`TokenCountAccuracy::Approximate`].
+//!
+//! Images and tool definitions are always approximate, since providers
+//! inject their own hidden formatting for both.
+
+use crate::error::Result;
+use crate::types::{ContentPart, ImageDetail, Message, MessageContent, Model, Role, Tool};
+use std::sync::LazyLock;
+use tiktoken_rs::CoreBPE;
+
+/// How trustworthy a [`TokenCount`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenCountAccuracy {
+    /// Counted with the model's own tokenizer
+    Exact,
+    /// Estimated from content size; may drift from the provider's count
+    Approximate,
+}
+
+/// Result of [`Model::count_tokens`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenCount {
+    /// Number of prompt tokens
+    pub tokens: usize,
+    /// Whether `tokens` is exact or an estimate
+    pub accuracy: TokenCountAccuracy,
+}
+
+impl TokenCount {
+    /// Check whether the count came from the model's own tokenizer
+    pub fn is_exact(&self) -> bool {
+        self.accuracy == TokenCountAccuracy::Exact
+    }
+}
+
+/// Tokens OpenAI adds around every message (`<|start|>{role}\n ... <|end|>`)
+const TOKENS_PER_MESSAGE: usize = 3;
+/// Tokens OpenAI adds when a message carries a `name`
+const TOKENS_PER_NAME: usize = 1;
+/// Tokens OpenAI adds to prime the assistant reply
+const REPLY_PRIMING_TOKENS: usize = 3;
+/// Tokens per tool definition for the surrounding function-schema wrapper
+const TOKENS_PER_TOOL: usize = 8;
+/// Low-detail image cost on OpenAI vision models
+const LOW_DETAIL_IMAGE_TOKENS: usize = 85;
+/// Conservative per-image estimate when the resolution is unknown
+const DEFAULT_IMAGE_TOKENS: usize = 1600;
+
+static O200K_BASE: LazyLock<Option<CoreBPE>> = LazyLock::new(|| tiktoken_rs::o200k_base().ok());
+static CL100K_BASE: LazyLock<Option<CoreBPE>> = LazyLock::new(|| tiktoken_rs::cl100k_base().ok());
+
+/// Pick the tiktoken encoding for an OpenAI model ID, if it is one
+///
+/// Accepts routed IDs such as `openai/gpt-4o` by looking at the last segment.
+fn openai_encoding(model_id: &str) -> Option<&'static CoreBPE> {
+    let id = model_id.rsplit('/').next().unwrap_or(model_id);
+
+    let o200k = [
+        "gpt-4o",
+        "gpt-4.1",
+        "gpt-4.5",
+        "gpt-5",
+        "chatgpt-4o",
+        "o1",
+        "o3",
+        "o4",
+    ];
+    let cl100k = ["gpt-4", "gpt-3.5", "text-embedding"];
+
+    if o200k.iter().any(|p| id.starts_with(p)) {
+        O200K_BASE.as_ref()
+    } else if cl100k.iter().any(|p| id.starts_with(p)) {
+        CL100K_BASE.as_ref()
+    } else {
+        None
+    }
+}
+
+/// Average UTF-8 bytes per token for providers without a public tokenizer
+///
+/// Deliberately on the low side so estimates err towards overcounting.
+fn bytes_per_token(provider: &str) -> f64 {
+    match provider {
+        "google" | "gemini" => 4.0,
+        "openai" => 3.8,
+        _ => 3.5,
+    }
+}
+
+fn role_str(role: &Role) -> &'static str {
+    match role {
+        Role::System => "system",
+        Role::User => "user",
+        Role::Assistant => "assistant",
+        Role::Tool => "tool",
+    }
+}
+
+/// Counts text either with a real encoder or by the bytes heuristic
+struct Counter {
+    encoder: Option<&'static CoreBPE>,
+    bytes_per_token: f64,
+    exact: bool,
+}
+
+impl Counter {
+    fn text(&self, text: &str) -> usize {
+        match self.encoder {
+            Some(bpe) => bpe.encode_with_special_tokens(text).len(),
+            None => (text.len() as f64 / self.bytes_per_token).ceil() as usize,
+        }
+    }
+
+    fn part(&mut self, part: &ContentPart) -> usize {
+        match part {
+            ContentPart::Text { text, .. } => self.text(text),
+            ContentPart::Image { detail, .. } => {
+                self.exact = false;
+                match detail {
+                    Some(ImageDetail::Low) => LOW_DETAIL_IMAGE_TOKENS,
+                    _ => DEFAULT_IMAGE_TOKENS,
+                }
+            }
+            ContentPart::ToolCall {
+                name, arguments, ..
+            } => self.text(name) + self.text(&arguments.to_string()),
+            ContentPart::ToolResult { content, .. } => match content {
+                serde_json::Value::String(s) => self.text(s),
+                other => self.text(&other.to_string()),
+            },
+        }
+    }
+
+    fn message(&mut self, message: &Message) -> usize {
+        let content = match &message.content {
+            MessageContent::Text(text) => self.text(text),
+            MessageContent::Parts(parts) => parts.iter().map(|p| self.part(p)).sum(),
+        };
+        let name = message
+            .name
+            .as_deref()
+            .map(|n| self.text(n) + TOKENS_PER_NAME)
+            .unwrap_or(0);
+
+        TOKENS_PER_MESSAGE + self.text(role_str(&message.role)) + content + name
+    }
+
+    fn tool(&mut self, tool: &Tool) -> Result<usize> {
+        self.exact = false;
+        let schema = serde_json::to_string(&tool.function.parameters)?;
+        Ok(self.text(&tool.function.name)
+            + self.text(&tool.function.description)
+            + self.text(&schema)
+            + TOKENS_PER_TOOL)
+    }
+}
+
+/// Count prompt tokens for `messages` and `tools` as sent to `model`
+pub(crate) fn count_tokens(
+    model: &Model,
+    messages: &[Message],
+    tools: &[Tool],
+) -> Result<TokenCount> {
+    let encoder = openai_encoding(&model.id);
+    let mut counter = Counter {
+        encoder,
+        bytes_per_token: bytes_per_token(&model.provider),
+        exact: encoder.is_some() && model.provider == "openai",
+    };
+
+    let mut tokens = REPLY_PRIMING_TOKENS;
+    for message in messages {
+        tokens += counter.message(message);
+    }
+    for tool in tools {
+        tokens += counter.tool(tool)?;
+    }
+
+    Ok(TokenCount {
+        tokens,
+        accuracy: if counter.exact {
+            TokenCountAccuracy::Exact
+        } else {
+            TokenCountAccuracy::Approximate
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ~110 words of plain English prose
+    const PROSE_FIXTURE: &str = "Infrastructure as code lets teams describe servers, networks, \
+        and permissions in version-controlled files instead of clicking through consoles. \
+        When a change is reviewed like any other pull request, mistakes are caught before \
+        they reach production, and every environment can be rebuilt from scratch. The hard \
+        part is rarely the syntax: it is deciding how to split state between teams, how to \
+        roll out changes gradually, and how to detect drift when someone edits a resource by \
+        hand. Good tooling surfaces a plan, explains what will be destroyed, and refuses to \
+        continue when credentials look wrong. Great tooling also remembers why a resource \
+        exists, so the next engineer does not delete it during a late-night cleanup.";
+
+    fn user(text: &str) -> Message {
+        Message::new(Role::User, text)
+    }
+
+    #[test]
+    fn test_openai_text_count_is_exact() {
+        let model = Model::custom("gpt-4o", "openai");
+        let count = model.count_tokens(&[user("hello world")], &[]).unwrap();
+
+        // 3 (message framing) + 1 ("user") + 2 ("hello", " world") + 3 (reply priming)
+        assert_eq!(count.tokens, 9);
+        assert!(count.is_exact());
+    }
+
+    #[test]
+    fn test_cl100k_model_uses_cl100k_encoding() {
+        let model = Model::custom("gpt-4-turbo", "openai");
+        let count = model.count_tokens(&[user("hello world")], &[]).unwrap();
+        assert_eq!(count.tokens, 9);
+    }
+
+    #[test]
+    fn test_name_adds_tokens() {
+        let model = Model::custom("gpt-4o", "openai");
+        let mut named = user("hello world");
+        named.name = Some("alice".to_string());
+
+        let plain = model.count_tokens(&[user("hello world")], &[]).unwrap();
+        let with_name = model.count_tokens(&[named], &[]).unwrap();
+        assert_eq!(with_name.tokens, plain.tokens + 2);
+    }
+
+    #[test]
+    fn test_routed_openai_model_is_approximate() {
+        let model = Model::custom("openai/gpt-4o", "openrouter");
+        let count = model.count_tokens(&[user("hello world")], &[]).unwrap();
+
+        assert_eq!(count.tokens, 9);
+        assert_eq!(count.accuracy, TokenCountAccuracy::Approximate);
+    }
+
+    #[test]
+    fn test_tools_and_images_make_count_approximate() {
+        let model = Model::custom("gpt-4o", "openai");
+        let tool = Tool::function("search", "Search documents")
+            .parameters(serde_json::json!({"type": "object", "properties": {}}));
+        let with_tool = model.count_tokens(&[user("hi")], &[tool]).unwrap();
+        assert!(!with_tool.is_exact());
+
+        let image = Message::new(
+            Role::User,
+            vec![
+                ContentPart::text("what is this?"),
+                ContentPart::image("https://x/y.png"),
+            ],
+        );
+        let with_image = model.count_tokens(&[image], &[]).unwrap();
+        assert!(!with_image.is_exact());
+        assert!(with_image.tokens > DEFAULT_IMAGE_TOKENS);
+    }
+
+    #[test]
+    fn test_exact_count_matches_fixtures() {
+        let model = Model::custom("gpt-4-turbo", "openai");
+        let framing = TOKENS_PER_MESSAGE + 1 + REPLY_PRIMING_TOKENS;
+
+        // Reference counts from OpenAI's tokenizer for cl100k_base
+        for (text, expected) in [("hello world", 2), ("tiktoken is great!", 6)] {
+            let count = model.count_tokens(&[user(text)], &[]).unwrap();
+            assert_eq!(count.tokens, expected + framing, "fixture {text:?}");
+        }
+    }
+
+    #[test]
+    fn test_heuristic_estimate_is_within_tolerance() {
+        // OpenAI's tokenizer puts the prose fixture at 139 tokens with framing;
+        // every estimate must overcount it, by no more than the divisor allows
+        for (model_id, provider, range) in [
+            ("claude-sonnet-4-5", "anthropic", 200..=230),
+            ("llama-3.1-70b", "openai", 185..=215),
+            ("gemini-2.5-pro", "google", 175..=200),
+        ] {
+            let estimate = Model::custom(model_id, provider)
+                .count_tokens(&[user(PROSE_FIXTURE)], &[])
+                .unwrap();
+
+            assert_eq!(estimate.accuracy, TokenCountAccuracy::Approximate);
+            assert!(
+                range.contains(&estimate.tokens),
+                "{provider}: {} not in {range:?}",
+                estimate.tokens
+            );
+        }
+    }
+
+    #[test]
+    fn test_heuristic_estimate_of_short_inputs() {
+        let model = Model::custom("claude-sonnet-4-5", "anthropic");
+        // 3 (message framing) + 2 ("user") + 3 (reply priming) around the content
+        for (text, range) in [
+            ("hello world", 12..=12),
+            ("fn main() { println!(\"{}\", 1 + 2); }", 18..=20),
+        ] {
+            let count = model.count_tokens(&[user(text)], &[]).unwrap();
+            assert!(
+                range.contains(&count.tokens),
+                "{text:?}: {} not in {range:?}",
+                count.tokens
+            );
+        }
+    }
+}
@@ -146,6 +146,9 @@ pub enum CacheWarningType {
 
     /// Cache control is not supported by the target provider
     UnsupportedProvider,
+
+    /// Informational: a breakpoint skipped by `CacheStrategy::Auto`
+    AutoPlacement,
 }
 
 impl std::fmt::Display for CacheWarningType {
@@ -154,6 +157,7 @@ impl std::fmt::Display for CacheWarningType {
             Self::UnsupportedContext => write!(f, "unsupported_context"),
             Self::BreakpointLimitExceeded => write!(f, "breakpoint_limit_exceeded"),
             Self::UnsupportedProvider => write!(f, "unsupported_provider"),
+            Self::AutoPlacement => write!(f, "auto_placement"),
         }
     }
 }
@@ -213,6 +217,17 @@ impl CacheWarning {
             ),
         )
     }
+
+    /// Record a breakpoint skipped because its prefix is too small to cache
+    pub fn auto_skipped(location: &str, prefix_tokens: usize, min_tokens: usize) -> Self {
+        Self::new(
+            CacheWarningType::AutoPlacement,
+            format!(
+                "Skipped cache breakpoint after {}: ~{} tokens is below the {}-token minimum.",
+                location, prefix_tokens, min_tokens
+            ),
+        )
+    }
 }
 
 impl std::fmt::Display for CacheWarning {
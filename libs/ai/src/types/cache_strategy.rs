@@ -9,10 +9,16 @@
 //!
 //! # Default Strategy (Anthropic)
 //!
-//! The default `CacheStrategy::Auto` applies:
+//! The default `CacheStrategy::Auto` analyzes each request with
+//! [`AutoCachePlan::for_request`] and applies:
 //! 1. Cache on **last tool** (caches all tools as a group)
 //! 2. Cache on **last system message** block
-//! 3. Cache on **last 2 non-system messages** (with remaining budget)
+//! 3. Cache on **last 2 non-system messages** (the last user turn and the one before it)
+//!
+//! A breakpoint is only placed when the prefix it closes reaches the model's
+//! minimum cacheable size; smaller prefixes would be rejected by the provider
+//! and only waste the breakpoint budget. Each skipped breakpoint is recorded
+//! as a [`CacheWarning`] so callers can see why it wasn't placed.
 //!
 //! This maximizes cache hit rates while staying within the 4 breakpoint limit.
 //!
@@ -33,6 +39,7 @@
 //!     .with_cache_strategy(CacheStrategy::anthropic(false, true, 3));
 //! ```
 
+use super::{CacheWarning, GenerateRequest, Message, Role};
 use serde::{Deserialize, Serialize};
 
 /// Caching strategy configuration
@@ -214,6 +221,102 @@ impl CacheStrategy {
     }
 }
 
+/// Number of tail messages `Auto` caches: the last user turn and the turn before it
+const AUTO_TAIL_MESSAGE_COUNT: usize = 2;
+
+/// Minimum prompt prefix (in tokens) Anthropic will cache for a model
+///
+/// See: https://docs.claude.com/en/docs/build-with-claude/prompt-caching#cache-limitations
+pub fn min_cacheable_tokens(model_id: &str) -> usize {
+    let id = model_id.to_lowercase();
+    if id.contains("haiku-4-5") || id.contains("opus-4-5") {
+        4096
+    } else if id.contains("haiku") {
+        2048
+    } else {
+        1024
+    }
+}
+
+/// Breakpoint placement chosen by [`CacheStrategy::Auto`] for a single request
+///
+/// # Example
+///
+/// ```rust
+/// use stakai::{AutoCachePlan, GenerateRequest, Message, Model, Role};
+///
+/// let req = GenerateRequest::new(
+///     Model::custom("claude-sonnet-4-5", "anthropic"),
+///     vec![Message::new(Role::User, "Hi")],
+/// );
+///
+/// // Far below the minimum cacheable size: nothing is placed, and the plan says why
+/// let plan = AutoCachePlan::for_request(&req);
+/// assert_eq!(plan.config.tail_message_count, 0);
+/// assert!(!plan.decisions.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct AutoCachePlan {
+    /// Breakpoints to place
+    pub config: AnthropicCacheConfig,
+    /// One entry per breakpoint skipped because its prefix is too small;
+    /// empty when every candidate was placed as the default config would
+    pub decisions: Vec<CacheWarning>,
+}
+
+impl AutoCachePlan {
+    /// Analyze a request and pick the breakpoints worth placing
+    ///
+    /// Breakpoints cache the whole prefix before them (tools → system →
+    /// messages), so each candidate is judged by the cumulative size of
+    /// everything up to and including it.
+    pub fn for_request(req: &GenerateRequest) -> Self {
+        let min_tokens = min_cacheable_tokens(&req.model.id);
+        let tools = req.options.tools.as_deref().unwrap_or_default();
+        let system: Vec<Message> = req
+            .messages
+            .iter()
+            .filter(|m| m.role == Role::System)
+            .cloned()
+            .collect();
+
+        let count = |messages: &[Message], tools| {
+            req.model
+                .count_tokens(messages, tools)
+                .map(|c| c.tokens)
+                .unwrap_or(0)
+        };
+
+        let mut decisions = Vec::new();
+        let mut decide = |label: &str, prefix_tokens: usize| {
+            let place = prefix_tokens >= min_tokens;
+            if !place {
+                decisions.push(CacheWarning::auto_skipped(label, prefix_tokens, min_tokens));
+            }
+            place
+        };
+
+        let cache_tools = !tools.is_empty() && decide("tools block", count(&[], tools));
+        let cache_system = !system.is_empty() && decide("system prompt", count(&system, tools));
+        let has_history = req.messages.iter().any(|m| m.role != Role::System);
+        let tail_message_count =
+            if has_history && decide("last user turn", count(&req.messages, tools)) {
+                AUTO_TAIL_MESSAGE_COUNT
+            } else {
+                0
+            };
+
+        Self {
+            config: AnthropicCacheConfig {
+                cache_tools,
+                cache_system,
+                tail_message_count,
+            },
+            decisions,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -299,6 +402,70 @@ mod tests {
         assert_eq!(deserialized, CacheStrategy::None);
     }
 
+    fn typical_request(filler_words: usize) -> GenerateRequest {
+        use crate::types::{Model, Tool};
+
+        let filler = "lorem ipsum dolor sit amet ".repeat(filler_words / 5);
+        let mut req = GenerateRequest::new(
+            Model::custom("claude-sonnet-4-5", "anthropic"),
+            vec![
+                Message::new(Role::System, format!("You are a DevOps agent. {filler}")),
+                Message::new(Role::User, "List the pods"),
+                Message::new(Role::Assistant, "There are 3 pods running."),
+                Message::new(Role::User, "Restart the failing one"),
+            ],
+        );
+        req.options = req.options.add_tool(
+            Tool::function("run_command", format!("Run a shell command. {filler}"))
+                .parameters(serde_json::json!({"type": "object", "properties": {}})),
+        );
+        req
+    }
+
+    #[test]
+    fn test_auto_plan_places_all_breakpoints_on_large_request() {
+        let plan = AutoCachePlan::for_request(&typical_request(2000));
+
+        assert!(plan.config.cache_tools);
+        assert!(plan.config.cache_system);
+        assert_eq!(plan.config.tail_message_count, 2);
+        assert!(plan.decisions.is_empty());
+    }
+
+    #[test]
+    fn test_auto_plan_skips_prefixes_below_minimum() {
+        // ~600 words of tools alone is below 1024 tokens, but tools + system is above
+        let plan = AutoCachePlan::for_request(&typical_request(600));
+
+        assert!(!plan.config.cache_tools);
+        assert!(plan.config.cache_system);
+        assert_eq!(plan.config.tail_message_count, 2);
+        assert_eq!(plan.decisions.len(), 1);
+        assert!(plan.decisions[0].message.starts_with("Skipped"));
+    }
+
+    #[test]
+    fn test_auto_plan_places_nothing_on_tiny_request() {
+        let plan = AutoCachePlan::for_request(&typical_request(0));
+
+        assert!(!plan.config.cache_tools);
+        assert!(!plan.config.cache_system);
+        assert_eq!(plan.config.tail_message_count, 0);
+        assert_eq!(plan.decisions.len(), 3);
+        assert!(
+            plan.decisions
+                .iter()
+                .all(|d| d.warning_type == crate::types::CacheWarningType::AutoPlacement)
+        );
+    }
+
+    #[test]
+    fn test_min_cacheable_tokens_by_model() {
+        assert_eq!(min_cacheable_tokens("claude-sonnet-4-5-20250929"), 1024);
+        assert_eq!(min_cacheable_tokens("claude-3-5-haiku-20241022"), 2048);
+        assert_eq!(min_cacheable_tokens("claude-haiku-4-5"), 4096);
+    }
+
     #[test]
     fn test_anthropic_cache_config_default() {
         let config = AnthropicCacheConfig::default();
@@ -13,7 +13,9 @@ mod stream;
 
 // Cache control types
 pub use cache::{CacheControl, CacheWarning, CacheWarningType, PromptCacheRetention};
-pub use cache_strategy::{AnthropicCacheConfig, CacheStrategy};
+pub use cache_strategy::{
+    AnthropicCacheConfig, AutoCachePlan, CacheStrategy, min_cacheable_tokens,
+};
 pub use cache_validator::{CacheContext, CacheControlValidator};
 
 // Headers
@@ -32,9 +34,9 @@ pub use options::{
 
 // Request types
 pub use request::{
-    AnthropicOptions, CompletionsConfig, GenerateRequest, GoogleOptions, OpenAIApiConfig,
-    OpenAIOptions, ProviderOptions, ReasoningEffort, ReasoningSummary, ResponsesConfig,
-    SystemMessageMode, ThinkingOptions,
+    AnthropicOptions, CompletionsConfig, GenerateRequest, GenerateRequestBuilder, GoogleOptions,
+    OpenAIApiConfig, OpenAIOptions, ProviderOptions, ReasoningEffort, ReasoningSummary,
+    ResponsesConfig, SystemMessageMode, ThinkingOptions,
 };
 
 // Response types
@@ -3,7 +3,12 @@
 //! This module provides a single `Model` struct that replaces provider-specific
 //! model enums (AnthropicModel, OpenAIModel, GeminiModel) and related types.
 
+use super::{Message, Tool};
+use crate::error::Result;
+use crate::registry::models_dev::ProviderInfo;
+use crate::tokenizer::TokenCount;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Unified model representation across all providers
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -80,6 +85,55 @@ impl Model {
     pub fn provider_name(&self) -> &str {
         &self.provider
     }
+
+    /// Context window size listed for this model in models.dev data
+    ///
+    /// Returns `None` when the registry does not list the model or its limit,
+    /// so callers can tell a known window apart from the default in [`ModelLimit`].
+    pub fn context_window(&self, registry: &HashMap<String, ProviderInfo>) -> Option<usize> {
+        self.registry_limit(registry, |limit| limit.context)
+    }
+
+    /// Maximum output tokens listed for this model in models.dev data
+    ///
+    /// Returns `None` when the registry does not list the model or its limit.
+    pub fn max_output_tokens(&self, registry: &HashMap<String, ProviderInfo>) -> Option<usize> {
+        self.registry_limit(registry, |limit| limit.output)
+    }
+
+    fn registry_limit(
+        &self,
+        registry: &HashMap<String, ProviderInfo>,
+        field: impl Fn(&ModelLimit) -> u64,
+    ) -> Option<usize> {
+        let listed = registry.get(&self.provider)?.models.get(&self.id)?;
+        // models.dev omits unknown limits, which parse as 0
+        let tokens = field(&listed.limit);
+        if tokens == 0 {
+            return None;
+        }
+        usize::try_from(tokens).ok()
+    }
+
+    /// Count the prompt tokens `messages` and `tools` would use with this model
+    ///
+    /// OpenAI models are counted with their tiktoken encoding; other providers
+    /// get a conservative estimate. Check [`TokenCount::accuracy`] to tell them apart.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use stakai::{Message, Model, Role};
+    ///
+    /// let model = Model::custom("gpt-4o", "openai");
+    /// let count = model
+    ///     .count_tokens(&[Message::new(Role::User, "hello world")], &[])
+    ///     .unwrap();
+    /// assert!(count.is_exact());
+    /// ```
+    pub fn count_tokens(&self, messages: &[Message], tools: &[Tool]) -> Result<TokenCount> {
+        crate::tokenizer::count_tokens(self, messages, tools)
+    }
 }
 
 impl std::fmt::Display for Model {
@@ -210,6 +264,56 @@ mod tests {
         assert!(!model.has_pricing());
     }
 
+    fn registry() -> HashMap<String, ProviderInfo> {
+        crate::registry::models_dev::parse_models_dev(
+            r#"{
+                "anthropic": {
+                    "name": "Anthropic",
+                    "models": {
+                        "claude-sonnet-4": {
+                            "id": "claude-sonnet-4",
+                            "name": "Claude Sonnet 4",
+                            "tool_call": true,
+                            "limit": { "context": 200000, "output": 64000 }
+                        },
+                        "claude-unlisted-limits": {
+                            "id": "claude-unlisted-limits",
+                            "name": "Claude Unlisted Limits",
+                            "tool_call": true
+                        }
+                    }
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_limits_from_registry() {
+        let registry = registry();
+        let model = Model::custom("claude-sonnet-4", "anthropic");
+
+        assert_eq!(model.context_window(&registry), Some(200_000));
+        assert_eq!(model.max_output_tokens(&registry), Some(64_000));
+    }
+
+    #[test]
+    fn test_limits_unknown_to_registry() {
+        let registry = registry();
+
+        let unknown = Model::custom("llama3", "ollama");
+        assert_eq!(unknown.context_window(&registry), None);
+        assert_eq!(unknown.max_output_tokens(&registry), None);
+
+        // Same id under another provider is a different model
+        let other_provider = Model::custom("claude-sonnet-4", "openrouter");
+        assert_eq!(other_provider.context_window(&registry), None);
+
+        let unlisted = Model::custom("claude-unlisted-limits", "anthropic");
+        assert_eq!(unlisted.context_window(&registry), None);
+        assert_eq!(unlisted.max_output_tokens(&registry), None);
+    }
+
     #[test]
     fn test_cost_calculation() {
         let cost = ModelCost::new(3.0, 15.0);
@@ -2,7 +2,8 @@
 
 use super::cache::PromptCacheRetention;
 use super::model::Model;
-use super::{GenerateOptions, Message};
+use super::{GenerateOptions, Message, Tool, ToolChoice};
+use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -117,6 +118,11 @@ pub struct CompletionsConfig {
     /// Cache retention policy for prompt caching.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prompt_cache_retention: Option<PromptCacheRetention>,
+
+    /// Structured output format, passed through verbatim as `response_format`
+    /// (e.g., `{"type": "json_object"}`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<serde_json::Value>,
 }
 
 /// Configuration for OpenAI Responses API
@@ -263,6 +269,26 @@ impl GenerateRequest {
         }
     }
 
+    /// Start building a request for `model` with chainable setters
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use stakai::{GenerateOptions, GenerateRequest, Message, Model, Role, Tool, ToolChoice};
+    ///
+    /// let request = GenerateRequest::builder(Model::custom("gpt-4o", "openai"))
+    ///     .message(Message::new(Role::System, "You are a helpful assistant."))
+    ///     .message(Message::new(Role::User, "What's the weather in Paris?"))
+    ///     .tool(Tool::function("get_weather", "Get the current weather"))
+    ///     .tool_choice(ToolChoice::Required { name: "get_weather".into() })
+    ///     .options(GenerateOptions::new().max_tokens(512))
+    ///     .build()?;
+    /// # Ok::<(), stakai::Error>(())
+    /// ```
+    pub fn builder(model: Model) -> GenerateRequestBuilder {
+        GenerateRequestBuilder::new(model)
+    }
+
     /// Set provider options
     pub fn with_provider_options(mut self, options: ProviderOptions) -> Self {
         self.provider_options = Some(options);
@@ -278,3 +304,121 @@ impl GenerateRequest {
         self
     }
 }
+
+/// Fluent builder for [`GenerateRequest`], created with [`GenerateRequest::builder`]
+///
+/// Tools and tool choice set through [`tool`](Self::tool) and
+/// [`tool_choice`](Self::tool_choice) are merged into the options at build
+/// time, so they can be called before or after [`options`](Self::options).
+#[derive(Debug, Clone)]
+pub struct GenerateRequestBuilder {
+    model: Model,
+    messages: Vec<Message>,
+    options: GenerateOptions,
+    tools: Vec<Tool>,
+    tool_choice: Option<ToolChoice>,
+    provider_options: Option<ProviderOptions>,
+    telemetry_metadata: Option<HashMap<String, String>>,
+}
+
+impl GenerateRequestBuilder {
+    /// Create a builder for `model` with no messages
+    pub fn new(model: Model) -> Self {
+        Self {
+            model,
+            messages: Vec::new(),
+            options: GenerateOptions::default(),
+            tools: Vec::new(),
+            tool_choice: None,
+            provider_options: None,
+            telemetry_metadata: None,
+        }
+    }
+
+    /// Append a message
+    pub fn message(mut self, message: Message) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    /// Append several messages
+    pub fn messages(mut self, messages: impl IntoIterator<Item = Message>) -> Self {
+        self.messages.extend(messages);
+        self
+    }
+
+    /// Add a tool definition
+    pub fn tool(mut self, tool: Tool) -> Self {
+        self.tools.push(tool);
+        self
+    }
+
+    /// Set the tool choice
+    pub fn tool_choice(mut self, choice: ToolChoice) -> Self {
+        self.tool_choice = Some(choice);
+        self
+    }
+
+    /// Set generation options (temperature, max_tokens, etc.)
+    pub fn options(mut self, options: GenerateOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set provider-specific options
+    pub fn provider_options(mut self, options: ProviderOptions) -> Self {
+        self.provider_options = Some(options);
+        self
+    }
+
+    /// Set telemetry metadata
+    pub fn telemetry_metadata(mut self, metadata: HashMap<String, String>) -> Self {
+        self.telemetry_metadata = Some(metadata);
+        self
+    }
+
+    /// Validate and build the request
+    ///
+    /// Fails with [`Error::InvalidRequest`] when there are no messages, or
+    /// when `ToolChoice::Required` names a tool that isn't defined.
+    pub fn build(self) -> Result<GenerateRequest> {
+        if self.messages.is_empty() {
+            return Err(Error::invalid_request(
+                "request must contain at least one message",
+            ));
+        }
+
+        let mut options = self.options;
+        if !self.tools.is_empty() {
+            options
+                .tools
+                .get_or_insert_with(Vec::new)
+                .extend(self.tools);
+        }
+        if let Some(choice) = self.tool_choice {
+            options.tool_choice = Some(choice);
+        }
+
+        if let Some(ToolChoice::Required { name }) = &options.tool_choice {
+            let defined = options
+                .tools
+                .iter()
+                .flatten()
+                .any(|tool| &tool.function.name == name);
+            if !defined {
+                return Err(Error::invalid_request(format!(
+                    "tool_choice requires tool '{}', but no tool with that name is defined",
+                    name
+                )));
+            }
+        }
+
+        Ok(GenerateRequest {
+            model: self.model,
+            messages: self.messages,
+            options,
+            provider_options: self.provider_options,
+            telemetry_metadata: self.telemetry_metadata,
+        })
+    }
+}
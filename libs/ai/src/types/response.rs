@@ -1,6 +1,7 @@
 //! Response types from AI providers
 
 use super::cache::CacheWarning;
+use super::model::Model;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -19,6 +20,10 @@ pub struct GenerateResponse {
     /// Warnings generated during request processing (e.g., cache validation)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub warnings: Option<Vec<ResponseWarning>>,
+    /// The model that actually served the response, set by `Inference`.
+    /// Differs from the requested model when a fallback model was used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<Model>,
 }
 
 /// Warning generated during request processing
@@ -265,12 +270,20 @@ pub struct FinishReason {
     /// Raw finish reason from the provider
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw: Option<String>,
+    /// Provider category for a content filter stop (e.g. `hate`,
+    /// `HARM_CATEGORY_DANGEROUS_CONTENT`), when the provider reports one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
 }
 
 impl FinishReason {
     /// Create a new finish reason with only unified value
     pub fn new(unified: FinishReasonKind) -> Self {
-        Self { unified, raw: None }
+        Self {
+            unified,
+            raw: None,
+            category: None,
+        }
     }
 
     /// Create a new finish reason with both unified and raw values
@@ -278,9 +291,16 @@ impl FinishReason {
         Self {
             unified,
             raw: Some(raw.into()),
+            category: None,
         }
     }
 
+    /// Attach the provider's content filter category
+    pub fn with_category(mut self, category: Option<String>) -> Self {
+        self.category = category;
+        self
+    }
+
     /// Convenience constructors
     pub fn stop() -> Self {
         Self::new(FinishReasonKind::Stop)
@@ -1,8 +1,8 @@
 //! Streaming types for AI generation
 
-use super::{FinishReason, Usage};
-use crate::error::Result;
-use futures::Stream;
+use super::{FinishReason, GenerateResponse, ResponseContent, ToolCall, Usage};
+use crate::error::{Error, Result};
+use futures::{Stream, StreamExt};
 use pin_project::pin_project;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -72,6 +72,64 @@ impl GenerateStream {
     }
 }
 
+impl GenerateStream {
+    /// Drain the stream into a single response, as `generate` would return it.
+    ///
+    /// Reasoning and text deltas are joined into one block each, and tool
+    /// calls are taken from their end events. A stream that stops without a
+    /// finish event is an error.
+    pub async fn collect_response(mut self) -> Result<GenerateResponse> {
+        let mut reasoning = String::new();
+        let mut text = String::new();
+        let mut tool_calls: Vec<ToolCall> = Vec::new();
+        let mut finish = None;
+
+        while let Some(event) = self.next().await {
+            match event? {
+                StreamEvent::TextDelta { delta, .. } => text.push_str(&delta),
+                StreamEvent::ReasoningDelta { delta, .. } => reasoning.push_str(&delta),
+                StreamEvent::ToolCallEnd {
+                    id,
+                    name,
+                    arguments,
+                    metadata,
+                } => tool_calls.push(ToolCall {
+                    id,
+                    name,
+                    arguments,
+                    metadata,
+                }),
+                StreamEvent::Finish { usage, reason } => finish = Some((usage, reason)),
+                StreamEvent::Error { message } => return Err(Error::stream_error(message)),
+                StreamEvent::Start { .. }
+                | StreamEvent::ToolCallStart { .. }
+                | StreamEvent::ToolCallDelta { .. } => {}
+            }
+        }
+
+        let (usage, finish_reason) = finish
+            .ok_or_else(|| Error::stream_error("Stream ended before the response finished"))?;
+
+        let mut content = Vec::new();
+        if !reasoning.is_empty() {
+            content.push(ResponseContent::Reasoning { reasoning });
+        }
+        if !text.is_empty() {
+            content.push(ResponseContent::Text { text });
+        }
+        content.extend(tool_calls.into_iter().map(ResponseContent::ToolCall));
+
+        Ok(GenerateResponse {
+            content,
+            usage,
+            finish_reason,
+            metadata: None,
+            warnings: None,
+            model: None,
+        })
+    }
+}
+
 impl Stream for GenerateStream {
     type Item = Result<StreamEvent>;
 
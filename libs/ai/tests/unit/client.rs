@@ -138,3 +138,338 @@ fn test_provider_registry_manual_registration() {
     assert_eq!(registry.list_providers().len(), 1);
     assert!(registry.has_provider("openai"));
 }
+
+mod failover {
+    use async_trait::async_trait;
+    use stakai::provider::Provider;
+    use stakai::{
+        Error, FinishReason, GenerateRequest, GenerateResponse, GenerateStream, Headers, Inference,
+        InferenceConfig, Message, Model, ResponseContent, Role, Usage,
+    };
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A provider that fails every call with a fixed error, or answers with its own id
+    struct MockProvider {
+        id: &'static str,
+        error: Option<fn() -> Error>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl MockProvider {
+        fn new(id: &'static str, error: Option<fn() -> Error>) -> (Self, Arc<AtomicUsize>) {
+            let calls = Arc::new(AtomicUsize::new(0));
+            (
+                Self {
+                    id,
+                    error,
+                    calls: calls.clone(),
+                },
+                calls,
+            )
+        }
+    }
+
+    #[async_trait]
+    impl Provider for MockProvider {
+        fn provider_id(&self) -> &str {
+            self.id
+        }
+
+        fn build_headers(&self, _custom_headers: Option<&Headers>) -> Headers {
+            Headers::new()
+        }
+
+        async fn generate(&self, request: GenerateRequest) -> stakai::Result<GenerateResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if let Some(error) = self.error {
+                return Err(error());
+            }
+            Ok(GenerateResponse {
+                content: vec![ResponseContent::Text {
+                    text: format!("{} answered", request.model.id),
+                }],
+                usage: Usage::new(1, 1),
+                finish_reason: FinishReason::stop(),
+                metadata: None,
+                warnings: None,
+                model: None,
+            })
+        }
+
+        async fn stream(&self, _request: GenerateRequest) -> stakai::Result<GenerateStream> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err(self
+                .error
+                .map(|error| error())
+                .unwrap_or_else(|| Error::Other("streaming not mocked".to_string())))
+        }
+    }
+
+    fn rate_limited() -> Error {
+        Error::provider_error(
+            "Anthropic API error 429 Too Many Requests: {\"type\":\"rate_limit_error\"}",
+        )
+    }
+
+    fn bad_request() -> Error {
+        Error::provider_error("Anthropic API error 400 Bad Request: prompt is too long")
+    }
+
+    fn request() -> GenerateRequest {
+        GenerateRequest::new(
+            Model::custom("claude-sonnet-4-5", "primary"),
+            vec![Message::new(Role::User, "Hello")],
+        )
+    }
+
+    fn client(primary_error: fn() -> Error) -> (Inference, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let (primary, primary_calls) = MockProvider::new("primary", Some(primary_error));
+        let (fallback, fallback_calls) = MockProvider::new("fallback", None);
+        let client = Inference::builder()
+            .register_provider("primary", primary)
+            .register_provider("fallback", fallback)
+            .with_fallback_models(vec![Model::custom("gpt-4.1", "fallback")])
+            .build()
+            .unwrap();
+        (client, primary_calls, fallback_calls)
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_primary_fails_over_to_fallback() {
+        let (client, primary_calls, fallback_calls) = client(rate_limited);
+
+        let response = client.generate(&request()).await.unwrap();
+
+        assert_eq!(response.text(), "gpt-4.1 answered");
+        let served_by = response.model.expect("serving model is recorded");
+        assert_eq!(served_by.id, "gpt-4.1");
+        assert_eq!(served_by.provider, "fallback");
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_error_does_not_fail_over() {
+        let (client, _, fallback_calls) = client(bad_request);
+
+        let error = client.generate(&request()).await.unwrap_err();
+
+        assert!(error.to_string().contains("400 Bad Request"), "{error}");
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_primary_model_is_recorded_without_failover() {
+        let (fallback, _) = MockProvider::new("fallback", None);
+        let client = Inference::builder()
+            .with_inference_config(
+                InferenceConfig::new().fallback_models(vec![Model::custom("gpt-4.1", "fallback")]),
+            )
+            .register_provider("fallback", fallback)
+            .build()
+            .unwrap();
+
+        let response = client
+            .generate(&GenerateRequest::new(
+                Model::custom("gpt-4.1-mini", "fallback"),
+                vec![Message::new(Role::User, "Hello")],
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            response.model.map(|model| model.id).as_deref(),
+            Some("gpt-4.1-mini")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_fails_over_when_opening_the_stream_fails() {
+        let (client, primary_calls, fallback_calls) = client(rate_limited);
+
+        // The fallback mock cannot stream, so the failover is visible in its error
+        let error = client.stream(&request()).await.err().unwrap();
+
+        assert!(
+            error.to_string().contains("streaming not mocked"),
+            "{error}"
+        );
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(rate_limited().is_retryable());
+        assert!(
+            Error::provider_error("Gemini API error 503 Service Unavailable: overloaded")
+                .is_retryable()
+        );
+        assert!(Error::RateLimitExceeded("Bedrock throttling".to_string()).is_retryable());
+        assert!(Error::provider_error("Bedrock model timeout: slow").is_retryable());
+        assert!(!bad_request().is_retryable());
+        assert!(!Error::MissingApiKey("openai".to_string()).is_retryable());
+    }
+
+    #[test]
+    fn test_status_is_read_from_the_status_position_only() {
+        assert!(
+            !Error::provider_error("Anthropic API error 400 Bad Request: prompt is 529 tokens")
+                .is_retryable()
+        );
+        assert!(
+            Error::provider_error("Failed to obtain Copilot API token (HTTP 502 Bad Gateway): ")
+                .is_retryable()
+        );
+        assert!(!Error::provider_error("Context window of 500 tokens exceeded").is_retryable());
+        assert!(!Error::provider_error("Tool call 429 was rejected").is_rate_limited());
+        assert!(Error::provider_error("Stakpak API error 429: slow down").is_rate_limited());
+    }
+}
+
+mod batch {
+    use async_trait::async_trait;
+    use stakai::provider::Provider;
+    use stakai::{
+        Error, FinishReason, GenerateRequest, GenerateResponse, GenerateStream, Headers, Inference,
+        Message, Model, ResponseContent, Role, Usage,
+    };
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct Concurrency {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    /// Counts a call as in flight until dropped, including when it times out
+    struct InFlight<'a>(&'a Concurrency);
+
+    impl<'a> InFlight<'a> {
+        fn start(concurrency: &'a Concurrency) -> Self {
+            let running = concurrency.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            concurrency
+                .max_in_flight
+                .fetch_max(running, Ordering::SeqCst);
+            Self(concurrency)
+        }
+    }
+
+    impl Drop for InFlight<'_> {
+        fn drop(&mut self) {
+            self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Answers with the model id after the delay in milliseconds given as
+    /// the prompt
+    struct SlowProvider {
+        concurrency: Arc<Concurrency>,
+    }
+
+    #[async_trait]
+    impl Provider for SlowProvider {
+        fn provider_id(&self) -> &str {
+            "slow"
+        }
+
+        fn build_headers(&self, _custom_headers: Option<&Headers>) -> Headers {
+            Headers::new()
+        }
+
+        async fn generate(&self, request: GenerateRequest) -> stakai::Result<GenerateResponse> {
+            let _in_flight = InFlight::start(&self.concurrency);
+            let delay_ms: u64 = request.messages[0]
+                .text()
+                .unwrap_or_default()
+                .parse()
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+
+            if request.model.id == "broken" {
+                return Err(Error::provider_error("Mock API error 400 Bad Request"));
+            }
+            Ok(GenerateResponse {
+                content: vec![ResponseContent::Text {
+                    text: request.model.id.clone(),
+                }],
+                usage: Usage::new(1, 1),
+                finish_reason: FinishReason::stop(),
+                metadata: None,
+                warnings: None,
+                model: None,
+            })
+        }
+
+        async fn stream(&self, _request: GenerateRequest) -> stakai::Result<GenerateStream> {
+            Err(Error::Other("streaming not mocked".to_string()))
+        }
+    }
+
+    fn client() -> (Inference, Arc<Concurrency>) {
+        let concurrency = Arc::new(Concurrency::default());
+        let provider = SlowProvider {
+            concurrency: concurrency.clone(),
+        };
+        let client = Inference::builder()
+            .register_provider("slow", provider)
+            .build()
+            .unwrap();
+        (client, concurrency)
+    }
+
+    fn request(model: &str, delay_ms: u64) -> GenerateRequest {
+        GenerateRequest::new(
+            Model::custom(model, "slow"),
+            vec![Message::new(Role::User, delay_ms.to_string())],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_batch_preserves_input_order_with_bounded_concurrency() {
+        let (client, provider) = client();
+        // Later requests finish first
+        let requests: Vec<_> = (0..12)
+            .map(|i| request(&format!("req-{i}"), 60 - i * 5))
+            .collect();
+
+        let results = client.generate_batch(&requests, 3).await;
+
+        let answers: Vec<String> = results.into_iter().map(|r| r.unwrap().text()).collect();
+        let expected: Vec<String> = (0..12).map(|i| format!("req-{i}")).collect();
+        assert_eq!(answers, expected);
+        assert_eq!(provider.max_in_flight.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_batch_reports_failures_and_timeouts_per_request() {
+        let (client, provider) = client();
+        let requests = vec![
+            request("ok-1", 1),
+            request("broken", 1),
+            request("too-slow", 5_000),
+            request("ok-2", 1),
+        ];
+
+        let results = client
+            .generate_batch_with_timeout(&requests, 0, Duration::from_millis(200))
+            .await;
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap().text(), "ok-1");
+        assert!(
+            results[1]
+                .as_ref()
+                .unwrap_err()
+                .to_string()
+                .contains("400 Bad Request")
+        );
+        assert!(matches!(results[2], Err(Error::NetworkError(_))));
+        assert_eq!(results[3].as_ref().unwrap().text(), "ok-2");
+        // A concurrency of 0 still makes progress, one request at a time
+        assert_eq!(provider.max_in_flight.load(Ordering::SeqCst), 1);
+    }
+}
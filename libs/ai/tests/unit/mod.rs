@@ -2,5 +2,6 @@
 
 mod client;
 mod provider;
+mod stakpak;
 mod tls;
 mod types;
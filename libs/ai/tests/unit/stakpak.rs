@@ -0,0 +1,155 @@
+//! Unit tests for the Stakpak provider's streamed `generate`
+//!
+//! A slow mock server only produces a complete body after longer than the
+//! client's read timeout, but sends streamed chunks well within it.
+
+use mockito::Matcher;
+use stakai::provider::Provider;
+use stakai::providers::HttpClientOptions;
+use stakai::providers::stakpak::{StakpakProvider, StakpakProviderConfig};
+use stakai::{FinishReasonKind, GenerateRequest, Message, Model, ResponseContent, Role};
+use std::time::Duration;
+
+const READ_TIMEOUT: Duration = Duration::from_millis(500);
+const CHUNK_DELAY: Duration = Duration::from_millis(200);
+
+fn provider(base_url: String, stream_generate: bool) -> StakpakProvider {
+    let config = StakpakProviderConfig::new("test-key")
+        .with_base_url(base_url)
+        .with_stream_generate(stream_generate);
+    let options = HttpClientOptions {
+        read_timeout: Some(READ_TIMEOUT),
+        ..Default::default()
+    };
+    StakpakProvider::with_http_options(config, &options).unwrap()
+}
+
+fn request() -> GenerateRequest {
+    GenerateRequest::new(
+        Model::custom("anthropic/claude-sonnet-4-5", "stakpak"),
+        vec![Message::new(Role::User, "Write a long story")],
+    )
+}
+
+/// Mocks both endpoints: the streaming one sends a chunk every `CHUNK_DELAY`,
+/// the buffered one is silent for longer than `READ_TIMEOUT` before answering.
+async fn slow_server() -> mockito::ServerGuard {
+    let mut server = mockito::Server::new_async().await;
+
+    server
+        .mock("POST", "/v1/chat/completions")
+        .match_body(Matcher::PartialJson(serde_json::json!({"stream": true})))
+        .with_status(200)
+        .with_header("content-type", "text/event-stream")
+        .with_chunked_body(|w| {
+            let chunks = [
+                r#"{"id":"c1","choices":[{"delta":{"role":"assistant"},"finish_reason":null}]}"#,
+                r#"{"id":"c1","choices":[{"delta":{"content":"Once upon "},"finish_reason":null}]}"#,
+                r#"{"id":"c1","choices":[{"delta":{"content":"a time"},"finish_reason":null}]}"#,
+                r#"{"id":"c1","choices":[{"delta":{},"finish_reason":"stop"}],"usage":{"prompt_tokens":5,"completion_tokens":4,"total_tokens":9}}"#,
+            ];
+            for chunk in chunks {
+                std::thread::sleep(CHUNK_DELAY);
+                write!(w, "data: {}\n\n", chunk)?;
+                w.flush()?;
+            }
+            write!(w, "data: [DONE]\n\n")
+        })
+        .create_async()
+        .await;
+
+    server
+        .mock("POST", "/v1/chat/completions")
+        .match_body(Matcher::PartialJson(serde_json::json!({"stream": false})))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_chunked_body(|w| {
+            std::thread::sleep(CHUNK_DELAY * 4);
+            w.write_all(
+                br#"{"id":"c1","object":"chat.completion","created":0,"model":"claude","choices":[{"index":0,"message":{"role":"assistant","content":"Once upon a time"},"finish_reason":"stop"}],"usage":{"prompt_tokens":5,"completion_tokens":4,"total_tokens":9}}"#,
+            )
+        })
+        .create_async()
+        .await;
+
+    server
+}
+
+#[tokio::test]
+async fn test_buffered_generate_times_out_on_a_slow_body() {
+    let server = slow_server().await;
+
+    let result = provider(server.url(), false).generate(request()).await;
+
+    assert!(result.is_err(), "expected a read timeout, got {result:?}");
+}
+
+#[tokio::test]
+async fn test_streamed_generate_reads_a_slow_response_chunk_by_chunk() {
+    let server = slow_server().await;
+
+    let response = provider(server.url(), true)
+        .generate(request())
+        .await
+        .unwrap();
+
+    assert_eq!(response.text(), "Once upon a time");
+    assert!(matches!(
+        response.content.as_slice(),
+        [ResponseContent::Text { .. }]
+    ));
+    assert_eq!(response.finish_reason.unified, FinishReasonKind::Stop);
+    assert_eq!(response.usage.total_tokens, 9);
+}
+
+const EXCEEDED_API_LIMIT_BODY: &str =
+    r#"{"error":{"key":"EXCEEDED_API_LIMIT","message":"Exceeded API limit"}}"#;
+
+async fn first_stream_error(server: &mockito::ServerGuard) -> String {
+    use futures::StreamExt;
+
+    let error = match provider(server.url(), false).stream(request()).await {
+        Ok(mut stream) => stream.next().await.unwrap().unwrap_err(),
+        Err(error) => error,
+    };
+    error.to_string()
+}
+
+#[tokio::test]
+async fn test_stream_error_status_links_to_billing_on_exceeded_api_limit() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(402)
+        .with_header("content-type", "application/json")
+        .with_body(EXCEEDED_API_LIMIT_BODY)
+        .create_async()
+        .await;
+
+    let error = first_stream_error(&server).await;
+
+    assert!(error.contains("Exceeded API limit"), "{error}");
+    assert!(
+        error.contains("https://stakpak.dev/settings/billing"),
+        "{error}"
+    );
+}
+
+#[tokio::test]
+async fn test_stream_json_error_body_links_to_billing_on_exceeded_api_limit() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(EXCEEDED_API_LIMIT_BODY)
+        .create_async()
+        .await;
+
+    let error = first_stream_error(&server).await;
+
+    assert!(
+        error.contains("https://stakpak.dev/settings/billing"),
+        "{error}"
+    );
+}
@@ -227,3 +227,64 @@ fn test_inference_builder_creates_providers_with_tls() {
 
     assert_eq!(client.registry().list_providers().len(), 4);
 }
+
+#[test]
+fn test_inference_builder_reports_unreadable_extra_ca_bundle() {
+    let result = Inference::builder()
+        .with_inference_config(
+            InferenceConfig::new()
+                .anthropic("test-key", None)
+                .extra_ca_cert("/nonexistent/stakai-test-ca.pem"),
+        )
+        .build();
+
+    let error = result.err().map(|e| e.to_string()).unwrap_or_default();
+    assert!(error.contains("anthropic"), "unexpected error: {error}");
+    assert!(
+        error.contains("stakai-test-ca.pem"),
+        "unexpected error: {error}"
+    );
+}
+
+// --- Proxy routing through InferenceConfig ---
+
+#[tokio::test]
+async fn test_inference_config_proxy_applies_to_providers() {
+    use stakai::{GenerateRequest, Message, Model, ProxyConfig, Role};
+
+    let mut proxy = mockito::Server::new_async().await;
+    let mock = proxy
+        .mock("POST", "/v1/chat/completions")
+        .match_header("host", "llm.stakai-proxy-test.invalid")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"id":"chatcmpl-1","object":"chat.completion","created":0,"model":"test-model","choices":[{"index":0,"message":{"role":"assistant","content":"hi"},"finish_reason":"stop"}],"usage":{"prompt_tokens":1,"completion_tokens":1,"total_tokens":2}}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = Inference::with_config(
+        InferenceConfig::new()
+            .openai(
+                "test-key",
+                Some("http://llm.stakai-proxy-test.invalid/v1".to_string()),
+            )
+            .proxy(ProxyConfig::new(proxy.url())),
+    )
+    .unwrap();
+
+    let request = GenerateRequest::new(
+        Model::custom("test-model", "openai"),
+        vec![Message::new(Role::User, "Hello")],
+    );
+    let response = client.generate(&request).await;
+
+    assert!(
+        response.is_ok(),
+        "request should reach the provider via the proxy: {:?}",
+        response.err()
+    );
+    mock.assert_async().await;
+}
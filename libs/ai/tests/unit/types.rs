@@ -117,6 +117,72 @@ fn test_generate_request_simple() {
     assert_eq!(request.messages[0].role, Role::User);
 }
 
+#[test]
+fn test_generate_request_builder() {
+    let request = GenerateRequest::builder(Model::custom("gpt-4", "openai"))
+        .message(Message::new(Role::System, "Be concise."))
+        .message(Message::new(Role::User, "Weather in Paris?"))
+        .tool(Tool::function("get_weather", "Get the current weather"))
+        .tool_choice(ToolChoice::Required {
+            name: "get_weather".to_string(),
+        })
+        .options(GenerateOptions::new().temperature(0.2).max_tokens(256))
+        .provider_options(ProviderOptions::OpenAI(OpenAIOptions::default()))
+        .build()
+        .unwrap();
+
+    assert_eq!(request.messages.len(), 2);
+    assert_eq!(request.options.temperature, Some(0.2));
+    assert_eq!(request.options.max_tokens, Some(256));
+    // Tools added before `.options()` survive the options replacement
+    assert_eq!(request.options.tools.as_ref().map(Vec::len), Some(1));
+    assert!(matches!(
+        request.options.tool_choice,
+        Some(ToolChoice::Required { ref name }) if name == "get_weather"
+    ));
+    assert!(matches!(
+        request.provider_options,
+        Some(ProviderOptions::OpenAI(_))
+    ));
+}
+
+#[test]
+fn test_generate_request_builder_requires_messages() {
+    let err = GenerateRequest::builder(Model::custom("gpt-4", "openai"))
+        .build()
+        .unwrap_err();
+    assert!(matches!(err, stakai::Error::InvalidRequest(_)));
+}
+
+#[test]
+fn test_generate_request_builder_rejects_unknown_required_tool() {
+    let err = GenerateRequest::builder(Model::custom("gpt-4", "openai"))
+        .message(Message::new(Role::User, "Hi"))
+        .tool(Tool::function("search", "Search documents"))
+        .tool_choice(ToolChoice::Required {
+            name: "get_weather".to_string(),
+        })
+        .build()
+        .unwrap_err();
+
+    match err {
+        stakai::Error::InvalidRequest(msg) => assert!(msg.contains("get_weather")),
+        other => panic!("expected InvalidRequest, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_generate_request_builder_accepts_tool_from_options() {
+    let request = GenerateRequest::builder(Model::custom("gpt-4", "openai"))
+        .message(Message::new(Role::User, "Hi"))
+        .options(GenerateOptions::new().add_tool(Tool::function("search", "Search documents")))
+        .tool_choice(ToolChoice::Required {
+            name: "search".to_string(),
+        })
+        .build();
+    assert!(request.is_ok());
+}
+
 #[test]
 fn test_generate_options() {
     let options = GenerateOptions::new()
@@ -181,6 +247,7 @@ fn test_response_text_extraction() {
         finish_reason: FinishReason::stop(),
         metadata: None,
         warnings: None,
+        model: None,
     };
 
     assert_eq!(response.text(), "Hello World");
@@ -7,15 +7,22 @@
 //! - Integrates with hooks for lifecycle events
 
 mod provider;
+#[cfg(test)]
+mod tests;
 
 use crate::local::hooks::task_board_context::{TaskBoardContextHook, TaskBoardContextHookOptions};
 use crate::local::storage::LocalStorage;
 use crate::models::AgentState;
 use crate::stakpak::storage::StakpakStorage;
-use crate::stakpak::{StakpakApiClient, StakpakApiConfig};
+use crate::stakpak::{RequestTimeouts, StakpakApiClient, StakpakApiConfig};
 use crate::storage::SessionStorage;
 
+use serde::{Deserialize, Serialize};
+use stakai::Model;
 use stakpak_shared::hooks::{HookRegistry, LifecycleEvent};
+use stakpak_shared::models::integrations::search_service::{
+    BundledSearchBackend, SearchBackend, SearchClient,
+};
 use stakpak_shared::models::llm::{LLMProviderConfig, ProviderConfig};
 use stakpak_shared::models::stakai_adapter::StakAIClient;
 use std::sync::Arc;
@@ -34,6 +41,8 @@ pub struct StakpakConfig {
     pub api_key: String,
     /// Stakpak API endpoint (default: https://apiv2.stakpak.dev)
     pub api_endpoint: String,
+    /// Base request timeout and per-operation overrides
+    pub timeouts: RequestTimeouts,
 }
 
 impl StakpakConfig {
@@ -41,6 +50,7 @@ impl StakpakConfig {
         Self {
             api_key: api_key.into(),
             api_endpoint: DEFAULT_STAKPAK_ENDPOINT.to_string(),
+            timeouts: RequestTimeouts::default(),
         }
     }
 
@@ -48,6 +58,62 @@ impl StakpakConfig {
         self.api_endpoint = endpoint.into();
         self
     }
+
+    pub fn with_timeouts(mut self, timeouts: RequestTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+}
+
+/// Limits for how much conversation history is kept before inference.
+///
+/// Unset fields fall back to the defaults below.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContextLimitsConfig {
+    /// Recent assistant messages kept untrimmed when trimming triggers (default: 5)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_last_n_assistant_messages: Option<usize>,
+    /// Fraction of the context window at which trimming triggers (default: 0.8)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_budget_threshold: Option<f32>,
+}
+
+impl ContextLimitsConfig {
+    const DEFAULT_KEEP_LAST_N_ASSISTANT_MESSAGES: usize = 5;
+    const DEFAULT_CONTEXT_BUDGET_THRESHOLD: f32 = 0.8;
+
+    /// Check that the limits leave the agent a usable context.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(keep_last_n) = self.keep_last_n_assistant_messages
+            && !(1..=1000).contains(&keep_last_n)
+        {
+            return Err(format!(
+                "keep_last_n_assistant_messages must be 1-1000, got {keep_last_n}"
+            ));
+        }
+        if let Some(threshold) = self.context_budget_threshold
+            && !(0.1..=1.0).contains(&threshold)
+        {
+            return Err(format!(
+                "context_budget_threshold must be between 0.1 and 1.0, got {threshold}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Options for the context hook, with defaults filled in.
+    pub fn hook_options(&self) -> TaskBoardContextHookOptions {
+        TaskBoardContextHookOptions {
+            keep_last_n_assistant_messages: Some(
+                self.keep_last_n_assistant_messages
+                    .unwrap_or(Self::DEFAULT_KEEP_LAST_N_ASSISTANT_MESSAGES),
+            ),
+            context_budget_threshold: Some(
+                self.context_budget_threshold
+                    .unwrap_or(Self::DEFAULT_CONTEXT_BUDGET_THRESHOLD),
+            ),
+        }
+    }
 }
 
 /// Configuration for creating an AgentClient
@@ -61,6 +127,12 @@ pub struct AgentClientConfig {
     pub store_path: Option<String>,
     /// Hook registry for lifecycle events
     pub hook_registry: Option<HookRegistry<AgentState>>,
+    /// Model for session title generation (default: a cheap model from the configured providers)
+    pub title_model: Option<Model>,
+    /// Limits for the history kept before inference
+    pub context_limits: ContextLimitsConfig,
+    /// Search API used for local docs search (default: the bundled searchpak service)
+    pub search_api_url: Option<String>,
 }
 
 impl AgentClientConfig {
@@ -94,6 +166,24 @@ impl AgentClientConfig {
         self.hook_registry = Some(registry);
         self
     }
+
+    /// Set the model used to generate session titles
+    pub fn with_title_model(mut self, model: Model) -> Self {
+        self.title_model = Some(model);
+        self
+    }
+
+    /// Set the limits for the history kept before inference
+    pub fn with_context_limits(mut self, limits: ContextLimitsConfig) -> Self {
+        self.context_limits = limits;
+        self
+    }
+
+    /// Set the search API used for local docs search
+    pub fn with_search_api_url(mut self, url: impl Into<String>) -> Self {
+        self.search_api_url = Some(url.into());
+        self
+    }
 }
 
 // =============================================================================
@@ -120,6 +210,10 @@ pub struct AgentClient {
     pub(crate) hook_registry: Arc<HookRegistry<AgentState>>,
     /// Stakpak configuration (for reference)
     pub(crate) stakpak: Option<StakpakConfig>,
+    /// Model for session title generation, overriding the cheap-model lookup
+    pub(crate) title_model: Option<Model>,
+    /// Web search backend for local docs search (used without Stakpak API)
+    pub(crate) search_backend: Arc<dyn SearchBackend>,
 }
 
 impl AgentClient {
@@ -145,11 +239,7 @@ impl AgentClient {
             .map_err(|e| format!("Failed to create Stakpak storage: {}", e))?;
             Ok(Arc::new(storage))
         } else {
-            let store_path = store_path.unwrap_or_else(|| {
-                std::env::var("HOME")
-                    .map(|h| format!("{}/{}", h, DEFAULT_STORE_PATH))
-                    .unwrap_or_else(|_| DEFAULT_STORE_PATH.to_string())
-            });
+            let store_path = store_path.unwrap_or_else(Self::default_store_path);
             let storage = LocalStorage::new(&store_path)
                 .await
                 .map_err(|e| format!("Failed to create local storage: {}", e))?;
@@ -157,8 +247,17 @@ impl AgentClient {
         }
     }
 
+    /// Path of the local SQLite session store (`~/.stakpak/data/local.db`).
+    pub fn default_store_path() -> String {
+        std::env::var("HOME")
+            .map(|h| format!("{}/{}", h, DEFAULT_STORE_PATH))
+            .unwrap_or_else(|_| DEFAULT_STORE_PATH.to_string())
+    }
+
     /// Create a new AgentClient
     pub async fn new(config: AgentClientConfig) -> Result<Self, String> {
+        config.context_limits.validate()?;
+
         // 1. Build LLMProviderConfig with Stakpak if configured (only if api_key is not empty)
         let mut providers = config.providers.clone();
         if let Some(stakpak) = &config.stakpak
@@ -182,10 +281,11 @@ impl AgentClient {
         let stakpak_api = if let Some(stakpak) = &config.stakpak {
             if !stakpak.api_key.is_empty() {
                 Some(
-                    StakpakApiClient::new(&StakpakApiConfig {
-                        api_key: stakpak.api_key.clone(),
-                        api_endpoint: stakpak.api_endpoint.clone(),
-                    })
+                    StakpakApiClient::new(
+                        &StakpakApiConfig::new(stakpak.api_key.clone())
+                            .with_endpoint(stakpak.api_endpoint.clone())
+                            .with_timeouts(stakpak.timeouts.clone()),
+                    )
                     .map_err(|e| format!("Failed to create Stakpak API client: {}", e))?,
                 )
             } else {
@@ -204,11 +304,10 @@ impl AgentClient {
                     .map_err(|e| format!("Failed to create Stakpak storage: {}", e))?,
             )
         } else {
-            let store_path = config.store_path.clone().unwrap_or_else(|| {
-                std::env::var("HOME")
-                    .map(|h| format!("{}/{}", h, DEFAULT_STORE_PATH))
-                    .unwrap_or_else(|_| DEFAULT_STORE_PATH.to_string())
-            });
+            let store_path = config
+                .store_path
+                .clone()
+                .unwrap_or_else(Self::default_store_path);
             Arc::new(
                 LocalStorage::new(&store_path)
                     .await
@@ -220,19 +319,26 @@ impl AgentClient {
         let mut hook_registry = config.hook_registry.unwrap_or_default();
         hook_registry.register(
             LifecycleEvent::BeforeInference,
-            Box::new(TaskBoardContextHook::new(TaskBoardContextHookOptions {
-                keep_last_n_assistant_messages: Some(5), // Keep the last 5 assistant messages in context
-                context_budget_threshold: Some(0.8),     // defaults to 0.8 (80%)
-            })),
+            Box::new(TaskBoardContextHook::new(
+                config.context_limits.hook_options(),
+            )),
         );
         let hook_registry = Arc::new(hook_registry);
 
+        // 7. Pick the web search backend for local docs search
+        let search_backend: Arc<dyn SearchBackend> = match config.search_api_url {
+            Some(url) => Arc::new(SearchClient::new(url)),
+            None => Arc::new(BundledSearchBackend),
+        };
+
         Ok(Self {
             stakai,
             stakpak_api,
             session_storage,
             hook_registry,
             stakpak: config.stakpak,
+            title_model: config.title_model,
+            search_backend,
         })
     }
 
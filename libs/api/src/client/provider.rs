@@ -18,13 +18,19 @@ use reqwest::header::HeaderMap;
 use rmcp::model::Content;
 use stakai::Model;
 use stakpak_shared::hooks::{HookContext, LifecycleEvent};
+use stakpak_shared::models::error::{AgentError, BadRequestErrorMessage};
 use stakpak_shared::models::integrations::openai::{
     ChatCompletionChoice, ChatCompletionResponse, ChatCompletionStreamChoice,
-    ChatCompletionStreamResponse, ChatMessage, FinishReason, MessageContent, Role, Tool,
+    ChatCompletionStreamResponse, ChatMessage, FinishReason, MessageContent, Role, Tool, ToolCall,
+};
+use stakpak_shared::models::integrations::search_service::{
+    AnalysisResult, MAX_SCRAPED_CONTENT_CHARS, STRICT_JSON_REPAIR_PROMPT, ScrapedContent,
+    ValidationResult, parse_llm_reply,
 };
 use stakpak_shared::models::llm::{
     GenerationDelta, LLMInput, LLMMessage, LLMMessageContent, LLMStreamInput,
 };
+use stakpak_shared::run_id::{RUN_ID_HEADER, run_id};
 use std::pin::Pin;
 use tokio::sync::mpsc;
 use uuid::Uuid;
@@ -442,6 +448,43 @@ impl AgentProvider for AgentClient {
         }
     }
 
+    // =========================================================================
+    // Tool Hooks
+    // =========================================================================
+
+    async fn before_tool_execution(
+        &self,
+        model: &Model,
+        tool_call: &ToolCall,
+        session_id: Option<Uuid>,
+    ) -> Result<(), String> {
+        self.run_tool_hooks(
+            &LifecycleEvent::BeforeToolExecution,
+            model,
+            tool_call,
+            None,
+            session_id,
+        )
+        .await
+    }
+
+    async fn after_tool_execution(
+        &self,
+        model: &Model,
+        tool_call: &ToolCall,
+        result: &str,
+        session_id: Option<Uuid>,
+    ) -> Result<(), String> {
+        self.run_tool_hooks(
+            &LifecycleEvent::AfterToolExecution,
+            model,
+            tool_call,
+            Some(result.to_string()),
+            session_id,
+        )
+        .await
+    }
+
     // =========================================================================
     // Search Docs
     // =========================================================================
@@ -455,20 +498,59 @@ impl AgentProvider for AgentClient {
             })
             .await
         } else {
-            // Fallback to local search service
-            use stakpak_shared::models::integrations::search_service::*;
+            // Fallback to the configured local search backend. With a model
+            // available, the query is rewritten before searching and the
+            // pages found are screened after; either step is skipped when
+            // the model's answer is unusable.
+            let model = self.select_title_model();
+            let mut query = input.keywords.clone();
+            if let Some(model) = &model {
+                match self.analyze_search_query(model, &query).await {
+                    Ok(analysis) if !analysis.reformulated_query.trim().is_empty() => {
+                        query = analysis.reformulated_query;
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::debug!(error = %e, "Search query analysis failed"),
+                }
+            }
 
-            let config = SearchServicesOrchestrator::start()
+            let search_results = self
+                .search_backend
+                .search_and_scrape(query.clone(), None)
                 .await
                 .map_err(|e| e.to_string())?;
 
-            let api_url = format!("http://localhost:{}", config.api_port);
-            let search_client = SearchClient::new(api_url);
-
-            let search_results = search_client
-                .search_and_scrape(input.keywords.clone(), None)
-                .await
-                .map_err(|e| e.to_string())?;
+            // Binary pages are dropped, HTML is flattened and long pages are capped
+            let mut search_results: Vec<ScrapedContent> = search_results
+                .into_iter()
+                .filter_map(|result| result.into_clean_text(MAX_SCRAPED_CONTENT_CHARS))
+                .collect();
+
+            if let Some(model) = &model
+                && !search_results.is_empty()
+            {
+                match self
+                    .validate_search_docs(model, &query, &search_results)
+                    .await
+                {
+                    Ok(validation) => {
+                        let valid: Vec<ScrapedContent> = search_results
+                            .iter()
+                            .filter(|result| {
+                                validation
+                                    .valid_docs
+                                    .iter()
+                                    .any(|doc| doc.url == result.url)
+                            })
+                            .cloned()
+                            .collect();
+                        if !valid.is_empty() {
+                            search_results = valid;
+                        }
+                    }
+                    Err(e) => tracing::debug!(error = %e, "Search result validation failed"),
+                }
+            }
 
             if search_results.is_empty() {
                 return Ok(vec![Content::text("No results found".to_string())]);
@@ -477,8 +559,16 @@ impl AgentProvider for AgentClient {
             Ok(search_results
                 .into_iter()
                 .map(|result| {
-                    let content = result.content.unwrap_or_default();
-                    Content::text(format!("URL: {}\nContent: {}", result.url, content))
+                    let truncated = result.is_truncated();
+                    let mut text = format!(
+                        "URL: {}\nContent: {}",
+                        result.url,
+                        result.content.unwrap_or_default()
+                    );
+                    if truncated {
+                        text.push_str("\n[Content truncated]");
+                    }
+                    Content::text(text)
                 })
                 .collect())
         }
@@ -561,6 +651,36 @@ impl AgentProvider for AgentClient {
         }
     }
 
+    // =========================================================================
+    // Sessions
+    // =========================================================================
+
+    async fn regenerate_title(&self, session_id: Uuid) -> Result<String, String> {
+        let checkpoint = self
+            .session_storage
+            .get_active_checkpoint(session_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let messages = checkpoint.state.messages;
+
+        if messages.is_empty() {
+            return Err(format!("Session {} has no messages to title", session_id));
+        }
+
+        let title = match self.generate_session_title(&messages).await {
+            Ok(title) if !title.trim().is_empty() => title.trim().to_string(),
+            _ => Self::fallback_session_title(&messages),
+        };
+
+        let request = StorageUpdateSessionRequest::new().with_title(title.clone());
+        self.session_storage
+            .update_session(session_id, &request)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(title)
+    }
+
     // =========================================================================
     // Models
     // =========================================================================
@@ -680,6 +800,40 @@ impl crate::storage::SessionStorage for super::AgentClient {
     ) -> Result<crate::storage::SessionStats, crate::storage::StorageError> {
         self.session_storage.get_session_stats(session_id).await
     }
+
+    async fn get_recovery_options(
+        &self,
+        session_id: Uuid,
+    ) -> Result<crate::storage::RecoveryOptions, crate::storage::StorageError> {
+        self.session_storage.get_recovery_options(session_id).await
+    }
+
+    async fn submit_recovery_action(
+        &self,
+        session_id: Uuid,
+        request: &crate::storage::RecoveryActionRequest,
+    ) -> Result<crate::storage::Checkpoint, crate::storage::StorageError> {
+        self.session_storage
+            .submit_recovery_action(session_id, request)
+            .await
+    }
+
+    async fn record_tool_audit(
+        &self,
+        session_id: Uuid,
+        request: &crate::storage::RecordToolAuditRequest,
+    ) -> Result<crate::storage::ToolAuditEntry, crate::storage::StorageError> {
+        self.session_storage
+            .record_tool_audit(session_id, request)
+            .await
+    }
+
+    async fn get_tool_audit(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Vec<crate::storage::ToolAuditEntry>, crate::storage::StorageError> {
+        self.session_storage.get_tool_audit(session_id).await
+    }
 }
 
 // =============================================================================
@@ -687,6 +841,11 @@ impl crate::storage::SessionStorage for super::AgentClient {
 // =============================================================================
 
 const TITLE_GENERATOR_PROMPT: &str = include_str!("../prompts/session_title_generator.v1.txt");
+const SEARCH_QUERY_ANALYSIS_PROMPT: &str = include_str!("../prompts/search_query_analysis.v1.txt");
+const SEARCH_DOCS_VALIDATION_PROMPT: &str =
+    include_str!("../prompts/search_docs_validation.v1.txt");
+/// Characters of each page shown to the model when validating search results
+const SEARCH_VALIDATION_EXCERPT_CHARS: usize = 1_000;
 
 impl AgentClient {
     /// Initialize or resume a session based on context
@@ -831,7 +990,28 @@ impl AgentClient {
         })
     }
 
+    /// Run the hooks registered for a tool lifecycle event around one tool call
+    async fn run_tool_hooks(
+        &self,
+        event: &LifecycleEvent,
+        model: &Model,
+        tool_call: &ToolCall,
+        result: Option<String>,
+        session_id: Option<Uuid>,
+    ) -> Result<(), String> {
+        let mut state = AgentState::new(model.clone(), Vec::new(), None, None);
+        state.set_tool_execution(tool_call.clone(), result);
+        let mut ctx = HookContext::new(session_id, state);
+
+        self.hook_registry
+            .execute_hooks(&mut ctx, event)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok()
+    }
+
     /// Run agent completion (inference)
+    #[tracing::instrument(skip_all, fields(run_id = %run_id(), session_id = ?ctx.session_id))]
     pub(crate) async fn run_agent_completion(
         &self,
         ctx: &mut HookContext<AgentState>,
@@ -853,11 +1033,12 @@ impl AgentClient {
             );
         };
 
-        // Inject session_id header if available
+        // Inject the run id, and the session_id if available, for request correlation
+        let headers = input
+            .headers
+            .get_or_insert_with(std::collections::HashMap::new);
+        headers.insert(RUN_ID_HEADER.to_string(), run_id());
         if let Some(session_id) = ctx.session_id {
-            let headers = input
-                .headers
-                .get_or_insert_with(std::collections::HashMap::new);
             headers.insert("X-Session-Id".to_string(), session_id.to_string());
         }
 
@@ -917,9 +1098,13 @@ impl AgentClient {
         Ok(ChatMessage::from(llm_output))
     }
 
-    /// Generate a title for a new session
-    async fn generate_session_title(&self, messages: &[ChatMessage]) -> Result<String, String> {
-        // Pick a cheap model from the user's configured providers
+    /// The model used for session titles: the configured title model, or
+    /// else a cheap model from the user's configured providers.
+    pub(crate) fn select_title_model(&self) -> Option<Model> {
+        if let Some(model) = &self.title_model {
+            return Some(model.clone());
+        }
+
         let use_stakpak = self.stakpak.is_some();
         let providers = self.stakai.registry().list_providers();
         let cheap_models: &[(&str, &str)] = &[
@@ -929,15 +1114,130 @@ impl AgentClient {
             ("openai", "gpt-4.1-mini"),
             ("google", "gemini-2.5-flash"),
         ];
-        let model = cheap_models
+        cheap_models.iter().find_map(|(provider, model_id)| {
+            if providers.contains(&provider.to_string()) {
+                crate::find_model(model_id, use_stakpak)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Ask `model` which documentation answers `keywords` and for a better
+    /// search query.
+    async fn analyze_search_query(
+        &self,
+        model: &Model,
+        keywords: &str,
+    ) -> Result<AnalysisResult, String> {
+        self.complete_json(
+            model,
+            SEARCH_QUERY_ANALYSIS_PROMPT,
+            format!("Search keywords: {keywords}"),
+            AnalysisResult::from_llm_output,
+        )
+        .await
+    }
+
+    /// Ask `model` which of the scraped pages help answer `query`.
+    async fn validate_search_docs(
+        &self,
+        model: &Model,
+        query: &str,
+        docs: &[ScrapedContent],
+    ) -> Result<ValidationResult, String> {
+        let pages = docs
             .iter()
-            .find_map(|(provider, model_id)| {
-                if providers.contains(&provider.to_string()) {
-                    crate::find_model(model_id, use_stakpak)
-                } else {
-                    None
-                }
+            .map(|doc| {
+                let excerpt: String = doc
+                    .content
+                    .as_deref()
+                    .unwrap_or_default()
+                    .chars()
+                    .take(SEARCH_VALIDATION_EXCERPT_CHARS)
+                    .collect();
+                format!("URL: {}\nExcerpt: {excerpt}", doc.url)
             })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        self.complete_json(
+            model,
+            SEARCH_DOCS_VALIDATION_PROMPT,
+            format!("Search query: {query}\n\nPages:\n{pages}"),
+            ValidationResult::from_llm_output,
+        )
+        .await
+    }
+
+    /// One model turn whose reply is parsed with `parse`. A reply that does
+    /// not parse gets one follow-up asking for strict JSON.
+    async fn complete_json<T>(
+        &self,
+        model: &Model,
+        system_prompt: &str,
+        user_prompt: String,
+        parse: fn(&str) -> Result<T, AgentError>,
+    ) -> Result<T, String> {
+        let mut messages = vec![
+            LLMMessage {
+                role: Role::System.to_string(),
+                content: LLMMessageContent::String(system_prompt.to_string()),
+            },
+            LLMMessage {
+                role: Role::User.to_string(),
+                content: LLMMessageContent::String(user_prompt),
+            },
+        ];
+        let reply = self.complete_text(model, messages.clone()).await?;
+
+        parse_llm_reply(reply, parse, |reply, error| {
+            messages.push(LLMMessage {
+                role: Role::Assistant.to_string(),
+                content: LLMMessageContent::String(reply),
+            });
+            messages.push(LLMMessage {
+                role: Role::User.to_string(),
+                content: LLMMessageContent::String(format!("{STRICT_JSON_REPAIR_PROMPT}{error}")),
+            });
+            async move {
+                self.complete_text(model, messages)
+                    .await
+                    .map_err(|e| AgentError::BadRequest(BadRequestErrorMessage::ApiError(e)))
+            }
+        })
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// The text of a single non-streaming completion.
+    async fn complete_text(
+        &self,
+        model: &Model,
+        messages: Vec<LLMMessage>,
+    ) -> Result<String, String> {
+        let input = LLMInput {
+            model: model.clone(),
+            messages,
+            max_tokens: 1_000,
+            tools: None,
+            provider_options: None,
+            headers: Some(std::collections::HashMap::from([(
+                RUN_ID_HEADER.to_string(),
+                run_id(),
+            )])),
+        };
+        let response = self.stakai.chat(input).await.map_err(|e| e.to_string())?;
+        response
+            .choices
+            .first()
+            .map(|choice| choice.message.content.to_string())
+            .ok_or_else(|| "Model returned no choices".to_string())
+    }
+
+    /// Generate a title for a new session
+    async fn generate_session_title(&self, messages: &[ChatMessage]) -> Result<String, String> {
+        let model = self
+            .select_title_model()
             .ok_or_else(|| "No model available for title generation".to_string())?;
 
         let llm_messages = vec![
@@ -967,7 +1267,10 @@ impl AgentClient {
             max_tokens: 100,
             tools: None,
             provider_options: None,
-            headers: None,
+            headers: Some(std::collections::HashMap::from([(
+                RUN_ID_HEADER.to_string(),
+                run_id(),
+            )])),
         };
 
         let response = self.stakai.chat(input).await.map_err(|e| e.to_string())?;
@@ -0,0 +1,296 @@
+use super::{AgentClient, AgentClientConfig, ContextLimitsConfig};
+use crate::AgentProvider;
+use crate::local::context_managers::task_board_context_manager::TaskBoardContextManagerOptions;
+use crate::local::hooks::task_board_context::TaskBoardContextHook;
+use crate::models::{AgentState, SearchDocsRequest};
+use crate::storage::{CreateCheckpointRequest, CreateSessionRequest, SessionStorage};
+use stakpak_shared::hooks::{
+    Hook, HookAction, HookContext, HookError, HookRegistry, LifecycleEvent,
+};
+use stakpak_shared::models::error::AgentError;
+use stakpak_shared::models::integrations::openai::{
+    ChatMessage, FunctionCall, MessageContent, Role, ToolCall,
+};
+use stakpak_shared::models::integrations::search_service::{ScrapedContent, SearchBackend};
+
+fn user_msg(text: &str) -> ChatMessage {
+    ChatMessage {
+        role: Role::User,
+        content: Some(MessageContent::String(text.to_string())),
+        ..Default::default()
+    }
+}
+
+/// A client with no LLM providers, so titles come from the deterministic fallback.
+async fn local_client() -> AgentClient {
+    AgentClient::new(AgentClientConfig::new().with_store_path(":memory:"))
+        .await
+        .expect("Failed to create client")
+}
+
+#[tokio::test]
+async fn regenerate_title_follows_current_messages() {
+    let client = local_client().await;
+    let storage = client.session_storage();
+
+    let created = storage
+        .create_session(&CreateSessionRequest::new(
+            "New Session",
+            vec![user_msg("deploy the staging cluster with terraform")],
+        ))
+        .await
+        .unwrap();
+    let session_id = created.session_id;
+
+    let first = client.regenerate_title(session_id).await.unwrap();
+    assert_eq!(first, "deploy the staging cluster with");
+    assert_eq!(storage.get_session(session_id).await.unwrap().title, first);
+
+    storage
+        .create_checkpoint(
+            session_id,
+            &CreateCheckpointRequest::new(vec![user_msg(
+                "rotate the production database password",
+            )])
+            .with_parent(created.checkpoint.id),
+        )
+        .await
+        .unwrap();
+
+    let second = client.regenerate_title(session_id).await.unwrap();
+    assert_ne!(second, first);
+    assert_eq!(second, "rotate the production database password");
+    assert_eq!(storage.get_session(session_id).await.unwrap().title, second);
+}
+
+#[tokio::test]
+async fn regenerate_title_rejects_unknown_session() {
+    let client = local_client().await;
+    assert!(client.regenerate_title(uuid::Uuid::new_v4()).await.is_err());
+}
+
+#[tokio::test]
+async fn configured_title_model_is_used_for_titles() {
+    // Without a title model or any providers there is nothing to title with.
+    assert!(local_client().await.select_title_model().is_none());
+
+    let client = AgentClient::new(
+        AgentClientConfig::new()
+            .with_store_path(":memory:")
+            .with_title_model(stakai::Model::custom("llama3", "ollama")),
+    )
+    .await
+    .expect("Failed to create client");
+
+    let model = client.select_title_model().unwrap();
+    assert_eq!(model.id, "llama3");
+    assert_eq!(model.provider, "ollama");
+}
+
+#[test]
+fn configured_context_limits_reach_the_context_manager() {
+    let options = |limits: ContextLimitsConfig| {
+        TaskBoardContextHook::new(limits.hook_options())
+            .context_manager
+            .options()
+    };
+
+    assert_eq!(
+        options(ContextLimitsConfig::default()),
+        TaskBoardContextManagerOptions {
+            keep_last_n_assistant_messages: 5,
+            context_budget_threshold: 0.8,
+        }
+    );
+    assert_eq!(
+        options(ContextLimitsConfig {
+            keep_last_n_assistant_messages: Some(12),
+            context_budget_threshold: Some(0.6),
+        }),
+        TaskBoardContextManagerOptions {
+            keep_last_n_assistant_messages: 12,
+            context_budget_threshold: 0.6,
+        }
+    );
+}
+
+#[tokio::test]
+async fn invalid_context_limits_are_rejected() {
+    for limits in [
+        ContextLimitsConfig {
+            keep_last_n_assistant_messages: Some(0),
+            ..Default::default()
+        },
+        ContextLimitsConfig {
+            context_budget_threshold: Some(1.5),
+            ..Default::default()
+        },
+        ContextLimitsConfig {
+            context_budget_threshold: Some(f32::NAN),
+            ..Default::default()
+        },
+    ] {
+        let result = AgentClient::new(
+            AgentClientConfig::new()
+                .with_store_path(":memory:")
+                .with_context_limits(limits),
+        )
+        .await;
+        assert!(result.is_err(), "{limits:?} should be rejected");
+    }
+}
+
+/// Records every tool execution it sees, aborting calls to `blocked_tool`.
+struct ToolRecorderHook {
+    seen: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+#[async_trait::async_trait]
+impl Hook<AgentState> for ToolRecorderHook {
+    fn name(&self) -> &str {
+        "tool_recorder"
+    }
+
+    async fn execute(
+        &self,
+        ctx: &mut HookContext<AgentState>,
+        event: &LifecycleEvent,
+    ) -> Result<HookAction, HookError> {
+        let Some(execution) = &ctx.state.tool_execution else {
+            return Ok(HookAction::Continue);
+        };
+        self.seen.lock().unwrap().push(format!(
+            "{event} {} {} {:?}",
+            execution.tool_call.function.name,
+            execution.tool_call.function.arguments,
+            execution.result,
+        ));
+        if execution.tool_call.function.name == "blocked_tool" {
+            return Ok(HookAction::Abort {
+                name: None,
+                reason: "not allowed".to_string(),
+            });
+        }
+        Ok(HookAction::Continue)
+    }
+}
+
+fn tool_call(name: &str, arguments: &str) -> ToolCall {
+    ToolCall {
+        id: format!("call_{name}"),
+        r#type: "function".to_string(),
+        function: FunctionCall {
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+        },
+        metadata: None,
+    }
+}
+
+#[tokio::test]
+async fn tool_execution_hooks_see_tool_calls_and_results() {
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut registry = HookRegistry::default();
+    for event in [
+        LifecycleEvent::BeforeToolExecution,
+        LifecycleEvent::AfterToolExecution,
+    ] {
+        registry.register(event, Box::new(ToolRecorderHook { seen: seen.clone() }));
+    }
+    let client = AgentClient::new(
+        AgentClientConfig::new()
+            .with_store_path(":memory:")
+            .with_hook_registry(registry),
+    )
+    .await
+    .expect("Failed to create client");
+    let model = stakai::Model::custom("llama3", "ollama");
+    let session_id = Some(uuid::Uuid::new_v4());
+
+    let view = tool_call("view", r#"{"path":"README.md"}"#);
+    client
+        .before_tool_execution(&model, &view, session_id)
+        .await
+        .unwrap();
+    client
+        .after_tool_execution(&model, &view, "# README", session_id)
+        .await
+        .unwrap();
+
+    let blocked = tool_call("blocked_tool", "{}");
+    let error = client
+        .before_tool_execution(&model, &blocked, session_id)
+        .await
+        .unwrap_err();
+    assert!(error.contains("not allowed"), "{error}");
+
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![
+            r#"BeforeToolExecution view {"path":"README.md"} None"#,
+            r##"AfterToolExecution view {"path":"README.md"} Some("# README")"##,
+            "BeforeToolExecution blocked_tool {} None",
+        ]
+    );
+}
+
+/// Returns canned results, recording each query it receives.
+struct CannedSearchBackend {
+    queries: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    results: Vec<ScrapedContent>,
+}
+
+#[async_trait::async_trait]
+impl SearchBackend for CannedSearchBackend {
+    async fn search_and_scrape(
+        &self,
+        query: String,
+        _whitelist: Option<Vec<String>>,
+    ) -> Result<Vec<ScrapedContent>, AgentError> {
+        self.queries.lock().unwrap().push(query);
+        Ok(self.results.clone())
+    }
+}
+
+#[tokio::test]
+async fn local_docs_search_uses_the_configured_backend() {
+    let queries = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut client = local_client().await;
+    client.search_backend = std::sync::Arc::new(CannedSearchBackend {
+        queries: queries.clone(),
+        results: vec![ScrapedContent {
+            url: "https://developer.hashicorp.com/terraform".to_string(),
+            title: Some("Terraform".to_string()),
+            content: Some("Terraform is an infrastructure as code tool.".to_string()),
+            metadata: None,
+            error: None,
+        }],
+    });
+
+    let request = SearchDocsRequest {
+        keywords: "terraform state".to_string(),
+        exclude_keywords: None,
+        limit: None,
+    };
+    let results = client.search_docs(&request).await.unwrap();
+
+    assert_eq!(*queries.lock().unwrap(), vec!["terraform state"]);
+    assert_eq!(results.len(), 1);
+    assert_eq!(
+        results[0].raw.as_text().map(|text| text.text.as_str()),
+        Some(
+            "URL: https://developer.hashicorp.com/terraform\nContent: Terraform is an infrastructure as code tool."
+        )
+    );
+
+    // An empty result set is reported rather than returned as no content.
+    client.search_backend = std::sync::Arc::new(CannedSearchBackend {
+        queries,
+        results: Vec::new(),
+    });
+    let results = client.search_docs(&request).await.unwrap();
+    assert_eq!(
+        results[0].raw.as_text().map(|text| text.text.as_str()),
+        Some("No results found")
+    );
+}
@@ -4,7 +4,7 @@ use models::*;
 use reqwest::header::HeaderMap;
 use rmcp::model::Content;
 use stakpak_shared::models::integrations::openai::{
-    ChatCompletionResponse, ChatCompletionStreamResponse, ChatMessage, Tool,
+    ChatCompletionResponse, ChatCompletionStreamResponse, ChatMessage, Tool, ToolCall,
 };
 use uuid::Uuid;
 
@@ -16,7 +16,11 @@ pub mod stakpak;
 pub mod storage;
 
 // Re-export unified AgentClient as the primary client
-pub use client::{AgentClient, AgentClientConfig, DEFAULT_STAKPAK_ENDPOINT, StakpakConfig};
+pub use client::{
+    AgentClient, AgentClientConfig, ContextLimitsConfig, DEFAULT_STAKPAK_ENDPOINT, StakpakConfig,
+};
+
+pub use stakpak::{ApiOperation, RequestTimeouts};
 
 // Re-export Model types from stakai
 pub use stakai::{Model, ModelCost, ModelLimit};
@@ -26,11 +30,16 @@ pub use storage::{
     BackendInfo, BackendKind, BoxedSessionStorage, Checkpoint, CheckpointState, CheckpointSummary,
     CreateCheckpointRequest, CreateSessionRequest as StorageCreateSessionRequest,
     CreateSessionResult, ListCheckpointsQuery, ListCheckpointsResult, ListSessionsQuery,
-    ListSessionsResult, LocalStorage, Session, SessionStats, SessionStatus, SessionStorage,
-    SessionSummary, SessionVisibility, StakpakStorage, StorageError,
+    ListSessionsResult, LocalStorage, RecordToolAuditRequest, RecoveryActionKind,
+    RecoveryActionRequest, RecoveryOption, RecoveryOptions, Session, SessionStats, SessionStatus,
+    SessionStorage, SessionSummary, SessionVisibility, StakpakStorage, StorageError,
+    ToolApprovalDecision, ToolAuditEntry, ToolAuditStatus,
     UpdateSessionRequest as StorageUpdateSessionRequest,
 };
 
+/// Providers in the models.dev catalog cache, in lookup order
+const CATALOG_PROVIDERS: &[&str] = &["anthropic", "openai", "google"];
+
 /// Find a model by ID string
 ///
 /// Parses the model string and searches the model cache:
@@ -39,15 +48,13 @@ pub use storage::{
 ///
 /// When `use_stakpak` is true, the model is transformed for Stakpak API routing.
 pub fn find_model(model_str: &str, use_stakpak: bool) -> Option<Model> {
-    const PROVIDERS: &[&str] = &["anthropic", "openai", "google"];
-
     let (provider_hint, model_id) = parse_model_string(model_str);
 
     // Search with provider hint first, then fall back to searching all
     let model = provider_hint
         .and_then(|p| find_in_provider(p, model_id))
         .or_else(|| {
-            PROVIDERS
+            CATALOG_PROVIDERS
                 .iter()
                 .find_map(|&p| find_in_provider(p, model_id))
         })?;
@@ -59,6 +66,108 @@ pub fn find_model(model_str: &str, use_stakpak: bool) -> Option<Model> {
     })
 }
 
+/// A model id that is not in the catalog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownModelError {
+    pub model: String,
+    /// Closest catalog id, when one is close enough to be a likely typo
+    pub suggestion: Option<String>,
+}
+
+impl std::fmt::Display for UnknownModelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown model '{}'", self.model)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean '{}'?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnknownModelError {}
+
+/// Load every model in the catalog cache, empty while the cache is missing
+///
+/// Callers checking several ids should load it once and pass it to
+/// [`try_find_model_in`], since each load reads the cache file from disk.
+pub fn load_model_catalog() -> Vec<Model> {
+    CATALOG_PROVIDERS
+        .iter()
+        .filter_map(|provider| stakai::load_models_for_provider(provider).ok())
+        .flatten()
+        .collect()
+}
+
+/// Find a model by ID string in a loaded catalog, failing instead of falling
+/// back to a custom model
+///
+/// Same lookup as [`find_model`]; on a miss, the error suggests the closest
+/// catalog id so typos like "claude-4.5-sonet" are caught early. Every id is
+/// unknown in an empty catalog, so check [`load_model_catalog`] found one.
+pub fn try_find_model_in(catalog: &[Model], model_str: &str) -> Result<Model, UnknownModelError> {
+    let (provider_hint, model_id) = parse_model_string(model_str);
+    let in_provider = |provider: &str| {
+        let models: Vec<Model> = catalog
+            .iter()
+            .filter(|model| model.provider == provider)
+            .cloned()
+            .collect();
+        match_model(&models, model_id)
+    };
+
+    provider_hint
+        .and_then(in_provider)
+        .or_else(|| CATALOG_PROVIDERS.iter().find_map(|&p| in_provider(p)))
+        .ok_or_else(|| unknown_model(model_str, catalog))
+}
+
+/// Build the error for a missing id, suggesting the closest catalog id.
+/// Ids are compared by their sorted name parts so reordered parts
+/// ("4.5-sonnet" vs "sonnet-4-5") count as close.
+fn unknown_model(model_str: &str, catalog: &[Model]) -> UnknownModelError {
+    const MAX_DISTANCE: usize = 3;
+
+    let normalize = |id: &str| {
+        let mut parts: Vec<String> = id
+            .split(['-', '.', '_', ':'])
+            .filter(|part| !part.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        parts.sort();
+        parts.join("-")
+    };
+    let wanted = normalize(parse_model_string(model_str).1);
+
+    let suggestion = catalog
+        .iter()
+        .map(|model| (edit_distance(&wanted, &normalize(&model.id)), &model.id))
+        .filter(|(distance, _)| *distance <= MAX_DISTANCE)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, id)| id.to_string());
+
+    UnknownModelError {
+        model: model_str.to_string(),
+        suggestion,
+    }
+}
+
+/// Levenshtein distance between two strings, by chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            let insertion = current[j] + 1;
+            let deletion = previous[j + 1] + 1;
+            current.push(substitution.min(insertion).min(deletion));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 /// Parse "provider/model_id" or plain "model_id"
 #[allow(clippy::string_slice)] // idx from find('/') on same string, '/' is ASCII
 fn parse_model_string(s: &str) -> (Option<&str>, &str) {
@@ -79,7 +188,11 @@ fn parse_model_string(s: &str) -> (Option<&str>, &str) {
 /// Find a model by ID within a specific provider
 fn find_in_provider(provider_id: &str, model_id: &str) -> Option<Model> {
     let models = stakai::load_models_for_provider(provider_id).ok()?;
+    match_model(&models, model_id)
+}
 
+/// Match a model ID against one provider's models
+fn match_model(models: &[Model], model_id: &str) -> Option<Model> {
     // Try exact match first
     if let Some(model) = models.iter().find(|m| m.id == model_id) {
         return Some(model.clone());
@@ -90,7 +203,7 @@ fn find_in_provider(provider_id: &str, model_id: &str) -> Option<Model> {
     let mut best_match: Option<&Model> = None;
     let mut best_len = 0;
 
-    for model in &models {
+    for model in models {
         if model_id.starts_with(&model.id) && model.id.len() > best_len {
             best_match = Some(model);
             best_len = model.id.len();
@@ -171,6 +284,25 @@ pub trait AgentProvider: SessionStorage + Send + Sync {
     >;
     async fn cancel_stream(&self, request_id: String) -> Result<(), String>;
 
+    // Tool hooks
+    /// Run the `BeforeToolExecution` hooks for a tool call.
+    ///
+    /// Returns an error when a hook aborts, in which case the tool must not run.
+    async fn before_tool_execution(
+        &self,
+        model: &Model,
+        tool_call: &ToolCall,
+        session_id: Option<Uuid>,
+    ) -> Result<(), String>;
+    /// Run the `AfterToolExecution` hooks with the tool's text output.
+    async fn after_tool_execution(
+        &self,
+        model: &Model,
+        tool_call: &ToolCall,
+        result: &str,
+        session_id: Option<Uuid>,
+    ) -> Result<(), String>;
+
     // Search Docs
     async fn search_docs(&self, input: &SearchDocsRequest) -> Result<Vec<Content>, String>;
 
@@ -192,6 +324,66 @@ pub trait AgentProvider: SessionStorage + Send + Sync {
         input: &SlackSendMessageRequest,
     ) -> Result<Vec<Content>, String>;
 
+    // Sessions
+    /// Re-run the title generator over a session's current messages and store the result.
+    ///
+    /// Falls back to a title built from the first user message when no model is
+    /// available for title generation. Returns the new title.
+    async fn regenerate_title(&self, session_id: Uuid) -> Result<String, String>;
+
     // Models
     async fn list_models(&self) -> Vec<Model>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog() -> Vec<Model> {
+        ["claude-sonnet-4-5", "claude-opus-4-6", "gpt-4.1"]
+            .iter()
+            .map(|id| Model::custom(id.to_string(), "anthropic"))
+            .collect()
+    }
+
+    #[test]
+    fn unknown_model_suggests_the_closest_catalog_id() {
+        let error = unknown_model("claude-4.5-sonet", &catalog());
+        assert_eq!(error.suggestion.as_deref(), Some("claude-sonnet-4-5"));
+        assert_eq!(
+            error.to_string(),
+            "Unknown model 'claude-4.5-sonet', did you mean 'claude-sonnet-4-5'?"
+        );
+
+        let prefixed = unknown_model("anthropic/claude-opus-4-7", &catalog());
+        assert_eq!(prefixed.suggestion.as_deref(), Some("claude-opus-4-6"));
+    }
+
+    #[test]
+    fn unknown_model_far_from_the_catalog_has_no_suggestion() {
+        let error = unknown_model("llama-3-70b-instruct", &catalog());
+        assert_eq!(error.suggestion, None);
+        assert_eq!(error.to_string(), "Unknown model 'llama-3-70b-instruct'");
+    }
+
+    #[test]
+    fn try_find_model_in_searches_the_loaded_catalog() {
+        let catalog = catalog();
+
+        let bare = try_find_model_in(&catalog, "claude-sonnet-4-5").unwrap();
+        assert_eq!(bare.id, "claude-sonnet-4-5");
+        let prefixed = try_find_model_in(&catalog, "anthropic/gpt-4.1-2025-04-14").unwrap();
+        assert_eq!(prefixed.id, "gpt-4.1");
+
+        let error = try_find_model_in(&catalog, "anthropic/claude-sonet-4-5").unwrap_err();
+        assert_eq!(error.suggestion.as_deref(), Some("claude-sonnet-4-5"));
+        assert!(try_find_model_in(&[], "claude-sonnet-4-5").is_err());
+    }
+
+    #[test]
+    fn edit_distance_counts_single_char_edits() {
+        assert_eq!(edit_distance("sonnet", "sonnet"), 0);
+        assert_eq!(edit_distance("sonet", "sonnet"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
@@ -0,0 +1,259 @@
+//! Portable session archives for the local store.
+//!
+//! An archive is a JSON document holding one session and every checkpoint in
+//! it, so local sessions can be backed up or moved to another machine.
+//! Importing assigns fresh ids to the session and its checkpoints, so an
+//! archive can be imported into the database it came from without collisions.
+
+use crate::local::storage::{LocalStorage, parse_datetime};
+use crate::storage::{
+    Checkpoint, CheckpointState, SessionStatus, SessionStorage, SessionVisibility, StorageError,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::str::FromStr;
+use uuid::Uuid;
+
+pub const SESSION_ARCHIVE_FORMAT: &str = "stakpak.session-archive";
+pub const SESSION_ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionArchive {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub session: ArchivedSession,
+    /// Every checkpoint in the session, parents before their children.
+    pub checkpoints: Vec<ArchivedCheckpoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedSession {
+    pub id: Uuid,
+    pub title: String,
+    pub visibility: SessionVisibility,
+    pub status: SessionStatus,
+    pub cwd: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedCheckpoint {
+    pub id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub state: CheckpointState,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Checkpoint> for ArchivedCheckpoint {
+    fn from(checkpoint: Checkpoint) -> Self {
+        Self {
+            id: checkpoint.id,
+            parent_id: checkpoint.parent_id,
+            state: checkpoint.state,
+            created_at: checkpoint.created_at,
+            updated_at: checkpoint.updated_at,
+        }
+    }
+}
+
+impl LocalStorage {
+    /// Serialize a session and all of its checkpoints as a JSON archive.
+    pub async fn export_session<W: Write>(
+        &self,
+        session_id: Uuid,
+        writer: W,
+    ) -> Result<SessionArchive, StorageError> {
+        let session = self.get_session(session_id).await?;
+        let checkpoints = self.all_checkpoints(session_id).await?;
+
+        let archive = SessionArchive {
+            format: SESSION_ARCHIVE_FORMAT.to_string(),
+            version: SESSION_ARCHIVE_VERSION,
+            exported_at: Utc::now(),
+            session: ArchivedSession {
+                id: session.id,
+                title: session.title,
+                visibility: session.visibility,
+                status: session.status,
+                cwd: session.cwd,
+                created_at: session.created_at,
+                updated_at: session.updated_at,
+            },
+            checkpoints: checkpoints.into_iter().map(Into::into).collect(),
+        };
+
+        serde_json::to_writer_pretty(writer, &archive)
+            .map_err(|e| StorageError::Internal(format!("Failed to write archive: {}", e)))?;
+        Ok(archive)
+    }
+
+    /// Insert a session archive as a new session, returning the new session id.
+    ///
+    /// The session and checkpoint ids are re-keyed; titles, timestamps and the
+    /// checkpoint parent chain are preserved.
+    pub async fn import_session<R: Read>(&self, reader: R) -> Result<Uuid, StorageError> {
+        let archive: SessionArchive = serde_json::from_reader(reader)
+            .map_err(|e| StorageError::InvalidRequest(format!("Invalid session archive: {}", e)))?;
+
+        if archive.format != SESSION_ARCHIVE_FORMAT {
+            return Err(StorageError::InvalidRequest(format!(
+                "Unsupported archive format '{}'",
+                archive.format
+            )));
+        }
+        if archive.version > SESSION_ARCHIVE_VERSION {
+            return Err(StorageError::InvalidRequest(format!(
+                "Archive version {} is newer than supported version {}",
+                archive.version, SESSION_ARCHIVE_VERSION
+            )));
+        }
+
+        let session_id = Uuid::new_v4();
+        let id_map: HashMap<Uuid, Uuid> = archive
+            .checkpoints
+            .iter()
+            .map(|checkpoint| (checkpoint.id, Uuid::new_v4()))
+            .collect();
+
+        // Parents must be inserted before their children
+        let checkpoints = parents_first(
+            archive.checkpoints,
+            |checkpoint| checkpoint.id,
+            |checkpoint| checkpoint.parent_id,
+        );
+
+        let internal = |e: libsql::Error| StorageError::Internal(e.to_string());
+        let conn = self.connection().await?;
+        let tx = conn.transaction().await.map_err(internal)?;
+
+        let session = &archive.session;
+        tx.execute(
+            "INSERT INTO sessions (id, title, visibility, status, cwd, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            (
+                session_id.to_string(),
+                session.title.as_str(),
+                session.visibility.to_string(),
+                session.status.to_string(),
+                session.cwd.as_deref(),
+                session.created_at.to_rfc3339(),
+                session.updated_at.to_rfc3339(),
+            ),
+        )
+        .await
+        .map_err(internal)?;
+
+        for checkpoint in &checkpoints {
+            let state_json = serde_json::to_string(&checkpoint.state)
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+            let new_id = id_map
+                .get(&checkpoint.id)
+                .copied()
+                .unwrap_or_else(Uuid::new_v4);
+            let parent_id = checkpoint
+                .parent_id
+                .and_then(|parent_id| id_map.get(&parent_id))
+                .map(Uuid::to_string);
+
+            tx.execute(
+                "INSERT INTO checkpoints (id, session_id, parent_id, state, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+                (
+                    new_id.to_string(),
+                    session_id.to_string(),
+                    parent_id,
+                    state_json,
+                    checkpoint.created_at.to_rfc3339(),
+                    checkpoint.updated_at.to_rfc3339(),
+                ),
+            )
+            .await
+            .map_err(internal)?;
+        }
+
+        tx.commit().await.map_err(internal)?;
+        Ok(session_id)
+    }
+
+    /// Every checkpoint of a session with its state, parents before their children.
+    async fn all_checkpoints(&self, session_id: Uuid) -> Result<Vec<Checkpoint>, StorageError> {
+        let internal = |e: libsql::Error| StorageError::Internal(e.to_string());
+        let conn = self.connection().await?;
+        let mut rows = conn
+            .query(
+                "SELECT id, parent_id, state, created_at, updated_at FROM checkpoints
+                 WHERE session_id = ? ORDER BY created_at ASC",
+                [session_id.to_string()],
+            )
+            .await
+            .map_err(internal)?;
+
+        let mut checkpoints = Vec::new();
+        while let Some(row) = rows.next().await.map_err(internal)? {
+            let id: String = row.get(0).map_err(internal)?;
+            let parent_id: Option<String> = row.get(1).map_err(internal)?;
+            // Checkpoints written before the state column existed have no state
+            let state: Option<String> = row.get(2).map_err(internal)?;
+            let created_at: String = row.get(3).map_err(internal)?;
+            let updated_at: String = row.get(4).map_err(internal)?;
+
+            let parse_id =
+                |id: &str| Uuid::from_str(id).map_err(|e| StorageError::Internal(e.to_string()));
+            let state = match state {
+                Some(state) => serde_json::from_str(&state).map_err(|e| {
+                    StorageError::Internal(format!("Invalid state in checkpoint {}: {}", id, e))
+                })?,
+                None => CheckpointState::default(),
+            };
+
+            checkpoints.push(Checkpoint {
+                id: parse_id(&id)?,
+                session_id,
+                parent_id: parent_id.as_deref().map(parse_id).transpose()?,
+                state,
+                created_at: parse_datetime(&created_at)?,
+                updated_at: parse_datetime(&updated_at)?,
+            });
+        }
+
+        Ok(parents_first(
+            checkpoints,
+            |checkpoint| checkpoint.id,
+            |checkpoint| checkpoint.parent_id,
+        ))
+    }
+}
+
+/// Reorder checkpoints so every parent comes before its children.
+///
+/// Creation timestamps can tie or go backwards (clock changes, imports), so
+/// they are only used to keep the original order among checkpoints that are
+/// ready to be placed. Checkpoints in a parent cycle keep their order at the end.
+fn parents_first<T>(
+    items: Vec<T>,
+    id: impl Fn(&T) -> Uuid,
+    parent_id: impl Fn(&T) -> Option<Uuid>,
+) -> Vec<T> {
+    let ids: HashSet<Uuid> = items.iter().map(&id).collect();
+    let mut placed = HashSet::new();
+    let mut ordered = Vec::with_capacity(items.len());
+    let mut pending = items;
+
+    while !pending.is_empty() {
+        let (ready, rest): (Vec<T>, Vec<T>) = pending.into_iter().partition(|item| {
+            parent_id(item).is_none_or(|parent| !ids.contains(&parent) || placed.contains(&parent))
+        });
+        if ready.is_empty() {
+            ordered.extend(rest);
+            break;
+        }
+        placed.extend(ready.iter().map(&id));
+        ordered.extend(ready);
+        pending = rest;
+    }
+
+    ordered
+}
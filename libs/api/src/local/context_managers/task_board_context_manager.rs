@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 
+use stakai::Model;
 use stakpak_shared::models::{
     integrations::openai::{ChatMessage, MessageContent},
     llm::{LLMMessage, LLMMessageContent, LLMMessageTypedContent, LLMTool},
+    stakai_adapter::{to_stakai_message, to_stakai_tool},
 };
 
 pub struct TaskBoardContextManager {
@@ -104,27 +106,29 @@ impl TaskBoardContextManager {
     /// - 2000 tokens per image (vs previous 1000)
     /// - 5% safety buffer on the final total
     pub fn estimate_tokens(messages: &[LLMMessage]) -> u64 {
-        let raw_estimate: u64 = messages
-            .iter()
-            .map(|msg| {
-                let content_tokens = match &msg.content {
-                    LLMMessageContent::String(s) => Self::bytes_to_tokens(s.len()),
-                    LLMMessageContent::List(parts) => {
-                        let part_tokens: u64 =
-                            parts.iter().map(Self::estimate_content_part_tokens).sum();
-                        // Per-part structural overhead: each content block in a List
-                        // has type discriminator and wrapper tokens (~3 tokens each)
-                        let part_overhead = parts.len() as u64 * 3;
-                        part_tokens + part_overhead
-                    }
-                };
-                // Per-message overhead: role tag, content block wrapper, formatting.
-                // Anthropic's actual overhead is ~7-10 tokens; use 8 as conservative middle.
-                content_tokens + 8
-            })
-            .sum();
+        let raw_estimate: u64 = messages.iter().map(Self::estimate_message_tokens).sum();
+        Self::with_safety_buffer(raw_estimate)
+    }
+
+    /// Estimate for a single message, before the safety buffer.
+    fn estimate_message_tokens(msg: &LLMMessage) -> u64 {
+        let content_tokens = match &msg.content {
+            LLMMessageContent::String(s) => Self::bytes_to_tokens(s.len()),
+            LLMMessageContent::List(parts) => {
+                let part_tokens: u64 = parts.iter().map(Self::estimate_content_part_tokens).sum();
+                // Per-part structural overhead: each content block in a List
+                // has type discriminator and wrapper tokens (~3 tokens each)
+                let part_overhead = parts.len() as u64 * 3;
+                part_tokens + part_overhead
+            }
+        };
+        // Per-message overhead: role tag, content block wrapper, formatting.
+        // Anthropic's actual overhead is ~7-10 tokens; use 8 as conservative middle.
+        content_tokens + 8
+    }
 
-        // 5% safety buffer to catch remaining estimation drift
+    /// 5% safety buffer to catch remaining estimation drift
+    fn with_safety_buffer(raw_estimate: u64) -> u64 {
         (raw_estimate as f64 * 1.05).ceil() as u64
     }
 
@@ -177,6 +181,46 @@ impl TaskBoardContextManager {
             .unwrap_or(0)
     }
 
+    /// Count prompt tokens for `messages` plus `tools`.
+    ///
+    /// When `model` has an exact tokenizer (see [`Model::count_tokens`]) its
+    /// count is used as-is; otherwise this falls back to the conservative
+    /// [`Self::estimate_tokens`] + [`Self::estimate_tool_overhead`] heuristic,
+    /// which is tuned to overcount.
+    pub fn count_tokens(
+        model: Option<&Model>,
+        messages: &[LLMMessage],
+        tools: Option<&[LLMTool]>,
+    ) -> u64 {
+        if let Some(model) = model {
+            let stakai_messages: Vec<_> = messages.iter().map(to_stakai_message).collect();
+            let stakai_tools: Vec<_> = tools
+                .unwrap_or_default()
+                .iter()
+                .map(to_stakai_tool)
+                .collect();
+            if let Ok(count) = model.count_tokens(&stakai_messages, &stakai_tools)
+                && count.is_exact()
+            {
+                return count.tokens as u64;
+            }
+        }
+        Self::estimate_tokens(messages) + Self::estimate_tool_overhead(tools)
+    }
+
+    /// Budget-aware context reduction without a model-specific tokenizer.
+    ///
+    /// See [`Self::reduce_context_with_budget_for_model`].
+    pub fn reduce_context_with_budget(
+        &self,
+        messages: Vec<ChatMessage>,
+        context_window: u64,
+        metadata: Option<serde_json::Value>,
+        tools: Option<&[LLMTool]>,
+    ) -> (Vec<LLMMessage>, Option<serde_json::Value>) {
+        self.reduce_context_with_budget_for_model(messages, context_window, metadata, tools, None)
+    }
+
     /// Budget-aware context reduction that preserves Anthropic prompt caching.
     ///
     /// **Trimming semantics:**
@@ -195,12 +239,16 @@ impl TaskBoardContextManager {
     ///   `context_window × threshold` again — it never moves backward.
     /// - When under threshold and no previous trimming exists, messages are
     ///   returned as-is with no metadata changes.
-    pub fn reduce_context_with_budget(
+    ///
+    /// Token usage is measured with [`Self::count_tokens`], so passing the
+    /// active `model` gives exact counts where a tokenizer is available.
+    pub fn reduce_context_with_budget_for_model(
         &self,
         messages: Vec<ChatMessage>,
         context_window: u64,
         metadata: Option<serde_json::Value>,
         tools: Option<&[LLMTool]>,
+        model: Option<&Model>,
     ) -> (Vec<LLMMessage>, Option<serde_json::Value>) {
         // Standard processing: clean, convert, merge, dedup
         let llm_messages: Vec<_> = messages
@@ -215,7 +263,7 @@ impl TaskBoardContextManager {
         let llm_messages = merge_consecutive_same_role(llm_messages);
         let mut llm_messages = dedup_tool_results(llm_messages);
 
-        let tool_overhead = Self::estimate_tool_overhead(tools);
+        let mut tally = TokenTally::new(model, &llm_messages, tools);
         let threshold = (context_window as f32 * self.context_budget_threshold) as u64;
 
         // Read previous trimming state from metadata
@@ -226,9 +274,7 @@ impl TaskBoardContextManager {
             .unwrap_or(0) as usize;
 
         // Fast path: under threshold and no previous trimming → return as-is
-        if prev_trimmed_up_to == 0
-            && Self::estimate_tokens(&llm_messages) + tool_overhead <= threshold
-        {
+        if prev_trimmed_up_to == 0 && tally.total() <= threshold {
             return (llm_messages, metadata);
         }
 
@@ -267,18 +313,19 @@ impl TaskBoardContextManager {
         // token count (what the API actually sees). This keeps the prefix
         // stable for prompt caching.
         let prev_clamped = prev_trimmed_up_to.min(len);
-        for msg in &mut llm_messages[..prev_clamped] {
+        for (index, msg) in llm_messages[..prev_clamped].iter_mut().enumerate() {
             if msg.role == "system" || msg.role == "user" {
                 continue;
             }
             Self::trim_message(msg);
+            tally.update(index, msg);
         }
 
         // Re-estimate tokens after applying previous trimming. This reflects
         // the actual token count the API will see, so we only advance the
         // trim boundary when the *effective* content exceeds the threshold —
         // not just because the raw (untrimmed) history grew.
-        let effective_estimated_tokens = Self::estimate_tokens(&llm_messages) + tool_overhead;
+        let effective_estimated_tokens = tally.total();
 
         // Decide whether to advance the trim index or keep the old one.
         // The index only advances when the effective tokens (after re-applying
@@ -291,14 +338,16 @@ impl TaskBoardContextManager {
             // Step 1: Apply keep_last_n trimming if it provides a boundary.
             let mut candidate = if keep_n_trim_end > 0 {
                 // Trim up to the keep_last_n boundary first
-                for msg in llm_messages
+                for (index, msg) in llm_messages
                     .iter_mut()
+                    .enumerate()
                     .take(keep_n_trim_end.min(len))
                     .skip(prev_clamped)
                 {
                     let role = msg.role.as_str();
                     if role == "assistant" || role == "tool" {
                         Self::trim_message(msg);
+                        tally.update(index, msg);
                     }
                 }
                 keep_n_trim_end
@@ -311,18 +360,17 @@ impl TaskBoardContextManager {
             // keep_last_n is best-effort. This handles the case where the
             // last N assistant messages themselves exceed the budget (e.g.,
             // long tool results, large file contents).
-            let current_tokens = Self::estimate_tokens(&llm_messages) + tool_overhead;
-            if current_tokens > threshold {
+            if tally.total() > threshold {
                 let mut scan_idx = candidate;
                 while scan_idx < len {
                     let role = llm_messages[scan_idx].role.as_str();
                     if role == "assistant" || role == "tool" {
                         Self::trim_message(&mut llm_messages[scan_idx]);
+                        tally.update(scan_idx, &llm_messages[scan_idx]);
                         candidate = scan_idx + 1;
 
                         // Check if we're under budget now
-                        let current_tokens = Self::estimate_tokens(&llm_messages) + tool_overhead;
-                        if current_tokens <= threshold {
+                        if tally.total() <= threshold {
                             break;
                         }
                     }
@@ -363,6 +411,94 @@ impl TaskBoardContextManager {
     }
 }
 
+/// Running token count for a message list, kept per message so trimming one
+/// message updates the total without recounting the whole history.
+///
+/// Matches [`TaskBoardContextManager::count_tokens`]: exact counts when the
+/// model's tokenizer covers every message and the tools, the conservative
+/// estimate otherwise.
+struct TokenTally<'a> {
+    /// Model counting exactly, or `None` when estimating
+    exact_model: Option<&'a Model>,
+    per_message: Vec<u64>,
+    messages_total: u64,
+    /// Tokens that do not depend on the messages (tools, reply priming)
+    fixed: u64,
+}
+
+impl<'a> TokenTally<'a> {
+    fn new(model: Option<&'a Model>, messages: &[LLMMessage], tools: Option<&[LLMTool]>) -> Self {
+        if let Some(model) = model
+            && let Some(tally) = Self::exact(model, messages, tools)
+        {
+            return tally;
+        }
+        let per_message: Vec<u64> = messages
+            .iter()
+            .map(TaskBoardContextManager::estimate_message_tokens)
+            .collect();
+        Self {
+            exact_model: None,
+            messages_total: per_message.iter().sum(),
+            per_message,
+            fixed: TaskBoardContextManager::estimate_tool_overhead(tools),
+        }
+    }
+
+    /// Per-message exact counts, or `None` if anything has to be estimated.
+    fn exact(model: &'a Model, messages: &[LLMMessage], tools: Option<&[LLMTool]>) -> Option<Self> {
+        let stakai_tools: Vec<_> = tools
+            .unwrap_or_default()
+            .iter()
+            .map(to_stakai_tool)
+            .collect();
+        let fixed = model
+            .count_tokens(&[], &stakai_tools)
+            .ok()
+            .filter(|count| count.is_exact())?
+            .tokens as u64;
+        let per_message = messages
+            .iter()
+            .map(|message| Self::exact_message_tokens(model, message))
+            .collect::<Option<Vec<u64>>>()?;
+        Some(Self {
+            exact_model: Some(model),
+            messages_total: per_message.iter().sum(),
+            per_message,
+            fixed,
+        })
+    }
+
+    fn exact_message_tokens(model: &Model, message: &LLMMessage) -> Option<u64> {
+        let empty = model.count_tokens(&[], &[]).ok()?.tokens;
+        let count = model
+            .count_tokens(&[to_stakai_message(message)], &[])
+            .ok()
+            .filter(|count| count.is_exact())?;
+        Some(count.tokens.saturating_sub(empty) as u64)
+    }
+
+    fn total(&self) -> u64 {
+        match self.exact_model {
+            Some(_) => self.fixed + self.messages_total,
+            None => TaskBoardContextManager::with_safety_buffer(self.messages_total) + self.fixed,
+        }
+    }
+
+    /// Recount the message at `index` after it changed.
+    fn update(&mut self, index: usize, message: &LLMMessage) {
+        let tokens = match self.exact_model {
+            // Trimming only replaces text, so an exact count stays exact
+            Some(model) => Self::exact_message_tokens(model, message).unwrap_or_default(),
+            None => TaskBoardContextManager::estimate_message_tokens(message),
+        };
+        if let Some(slot) = self.per_message.get_mut(index) {
+            self.messages_total = self.messages_total - *slot + tokens;
+            *slot = tokens;
+        }
+    }
+}
+
 /// Merge consecutive LLMMessages that share the same role into a single message.
 ///
 /// When the assistant returns N tool_calls, the chat history contains N separate
@@ -745,6 +881,72 @@ mod tests {
         assert_eq!(tokens, expected);
     }
 
+    #[test]
+    fn test_count_tokens_uses_exact_tokenizer_when_available() {
+        let messages = vec![LLMMessage {
+            role: "user".to_string(),
+            content: LLMMessageContent::String("hello world".to_string()),
+        }];
+        let model = Model::custom("gpt-4o", "openai");
+
+        let exact = model
+            .count_tokens(&[to_stakai_message(&messages[0])], &[])
+            .unwrap();
+        assert!(exact.is_exact());
+        assert_eq!(
+            TaskBoardContextManager::count_tokens(Some(&model), &messages, None),
+            exact.tokens as u64
+        );
+    }
+
+    #[test]
+    fn test_count_tokens_falls_back_to_conservative_estimate() {
+        let messages = vec![LLMMessage {
+            role: "user".to_string(),
+            content: LLMMessageContent::String("hello world".to_string()),
+        }];
+        let model = Model::custom("claude-sonnet-4-5", "anthropic");
+
+        assert_eq!(
+            TaskBoardContextManager::count_tokens(Some(&model), &messages, None),
+            TaskBoardContextManager::estimate_tokens(&messages)
+        );
+        assert_eq!(
+            TaskBoardContextManager::count_tokens(None, &messages, None),
+            TaskBoardContextManager::estimate_tokens(&messages)
+        );
+    }
+
+    #[test]
+    fn test_token_tally_tracks_count_tokens_through_trimming() {
+        let original: Vec<LLMMessage> = ["system", "user", "assistant", "tool", "assistant"]
+            .iter()
+            .enumerate()
+            .map(|(i, role)| LLMMessage {
+                role: role.to_string(),
+                content: LLMMessageContent::String(format!("message {i} ").repeat(40)),
+            })
+            .collect();
+        let exact_model = Model::custom("gpt-4o", "openai");
+
+        for model in [None, Some(&exact_model)] {
+            let mut messages = original.clone();
+            let mut tally = TokenTally::new(model, &messages, None);
+            assert_eq!(
+                tally.total(),
+                TaskBoardContextManager::count_tokens(model, &messages, None)
+            );
+            for index in [2, 3] {
+                TaskBoardContextManager::trim_message(&mut messages[index]);
+                tally.update(index, &messages[index]);
+                assert_eq!(
+                    tally.total(),
+                    TaskBoardContextManager::count_tokens(model, &messages, None)
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_estimate_tokens_multiple_messages() {
         let messages = vec![
@@ -2873,6 +3075,7 @@ mod tests {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct TaskBoardContextManagerOptions {
     /// How many recent **assistant** messages to keep untrimmed when context
     /// trimming is triggered. The trim boundary is placed just before the
@@ -2891,4 +3094,12 @@ impl TaskBoardContextManager {
             context_budget_threshold: options.context_budget_threshold,
         }
     }
+
+    /// The limits this manager trims with.
+    pub fn options(&self) -> TaskBoardContextManagerOptions {
+        TaskBoardContextManagerOptions {
+            keep_last_n_assistant_messages: self.keep_last_n_assistant_messages,
+            context_budget_threshold: self.context_budget_threshold,
+        }
+    }
 }
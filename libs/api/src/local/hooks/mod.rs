@@ -3,3 +3,4 @@
 pub mod file_scratchpad_context;
 pub mod inline_scratchpad_context;
 pub mod task_board_context;
+pub mod tool_argument_redaction;
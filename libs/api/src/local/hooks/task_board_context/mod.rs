@@ -49,10 +49,14 @@ define_hook!(
         // only the space actually available for chat messages.
         // - System prompt: added after trimming (line 67+), not in message list
         // - max_output_tokens: reserved for the model's response
-        let system_prompt_tokens = TaskBoardContextManager::estimate_tokens(&[LLMMessage {
-            role: Role::System.to_string(),
-            content: LLMMessageContent::String(SYSTEM_PROMPT.to_string()),
-        }]);
+        let system_prompt_tokens = TaskBoardContextManager::count_tokens(
+            Some(&model),
+            &[LLMMessage {
+                role: Role::System.to_string(),
+                content: LLMMessageContent::String(SYSTEM_PROMPT.to_string()),
+            }],
+            None,
+        );
         let context_window = model
             .limit
             .context
@@ -66,13 +70,16 @@ define_hook!(
 
         // Use budget-aware trimming with metadata from checkpoint.
         // Tool definitions are passed in so the context manager can account
-        // for their token overhead internally.
-        let (reduced_messages, updated_metadata) = self.context_manager.reduce_context_with_budget(
-            ctx.state.messages.clone(),
-            context_window,
-            ctx.state.metadata.clone(),
-            llm_tools.as_deref(),
-        );
+        // for their token overhead internally, and the active model lets it
+        // use an exact tokenizer when one is available.
+        let (reduced_messages, updated_metadata) =
+            self.context_manager.reduce_context_with_budget_for_model(
+                ctx.state.messages.clone(),
+                context_window,
+                ctx.state.metadata.clone(),
+                llm_tools.as_deref(),
+                Some(&model),
+            );
 
         // Write updated metadata back to state for checkpoint persistence
         ctx.state.metadata = updated_metadata;
@@ -0,0 +1,43 @@
+use stakpak_shared::define_hook;
+use stakpak_shared::hooks::{Hook, HookAction, HookContext, HookError, LifecycleEvent};
+use stakpak_shared::models::llm::{LLMMessageContent, LLMMessageTypedContent};
+use stakpak_shared::secret_manager::SecretManager;
+
+use crate::models::AgentState;
+
+/// Redacts secrets in the arguments of new tool calls before the message is
+/// returned or checkpointed. The redactions go into the session map, so the
+/// MCP proxy restores the real values when the tool runs.
+pub struct ToolArgumentRedactionHook {
+    pub secret_manager: SecretManager,
+}
+
+impl ToolArgumentRedactionHook {
+    pub fn new(secret_manager: SecretManager) -> Self {
+        Self { secret_manager }
+    }
+}
+
+define_hook!(
+    ToolArgumentRedactionHook,
+    "tool_argument_redaction",
+    async |&self, ctx: &mut HookContext<AgentState>, event: &LifecycleEvent| {
+        if *event != LifecycleEvent::AfterInference {
+            return Ok(HookAction::Continue);
+        }
+
+        let Some(llm_output) = ctx.state.llm_output.as_mut() else {
+            return Ok(HookAction::Continue);
+        };
+
+        if let LLMMessageContent::List(items) = &mut llm_output.new_message.content {
+            for item in items.iter_mut() {
+                if let LLMMessageTypedContent::ToolCall { args, .. } = item {
+                    self.secret_manager.redact_and_store_json(args);
+                }
+            }
+        }
+
+        Ok(HookAction::Continue)
+    }
+);
@@ -11,6 +11,7 @@ use std::pin::Pin;
 
 mod v001_initial_schema;
 mod v002_nullable_columns;
+mod v003_tool_audit;
 
 /// Async migration function type
 pub type MigrationFn =
@@ -33,6 +34,7 @@ pub fn all_migrations() -> Vec<Migration> {
     vec![
         v001_initial_schema::migration(),
         v002_nullable_columns::migration(),
+        v003_tool_audit::migration(),
     ]
 }
 
@@ -0,0 +1,63 @@
+//! v003: Add the tool audit log
+//!
+//! - tool_audit: one row per tool call with its redacted arguments, approval
+//!   decision and outcome
+
+use super::Migration;
+use libsql::Connection;
+use std::future::Future;
+use std::pin::Pin;
+
+pub fn migration() -> Migration {
+    Migration {
+        version: 3,
+        description: "Add tool_audit table",
+        apply,
+        rollback,
+    }
+}
+
+fn apply(conn: &Connection) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + '_>> {
+    Box::pin(async move {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tool_audit (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                tool_call_id TEXT NOT NULL,
+                tool_name TEXT NOT NULL,
+                arguments TEXT NOT NULL,
+                decision TEXT NOT NULL,
+                status TEXT NOT NULL,
+                summary TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY(session_id) REFERENCES sessions(id)
+            )",
+            (),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tool_audit_session_id ON tool_audit(session_id)",
+            (),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        Ok(())
+    })
+}
+
+fn rollback(conn: &Connection) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + '_>> {
+    Box::pin(async move {
+        conn.execute("DROP INDEX IF EXISTS idx_tool_audit_session_id", ())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        conn.execute("DROP TABLE IF EXISTS tool_audit", ())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    })
+}
@@ -5,6 +5,7 @@
 //! - Lifecycle hooks for context management
 
 // Sub-modules
+pub mod archive;
 pub(crate) mod context_managers;
 pub mod hooks;
 pub mod migrations;
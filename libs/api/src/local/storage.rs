@@ -5,12 +5,15 @@
 use crate::storage::{
     BackendInfo, Checkpoint, CheckpointState, CheckpointSummary, CreateCheckpointRequest,
     CreateSessionRequest, CreateSessionResult, ListCheckpointsQuery, ListCheckpointsResult,
-    ListSessionsQuery, ListSessionsResult, Session, SessionStatus, SessionStorage, SessionSummary,
-    SessionVisibility, StorageError, UpdateSessionRequest,
+    ListSessionsQuery, ListSessionsResult, RecordToolAuditRequest, RecoveryActionKind,
+    RecoveryActionRequest, RecoveryOption, RecoveryOptions, Session, SessionStatus, SessionStorage,
+    SessionSummary, SessionVisibility, StorageError, ToolApprovalDecision, ToolAuditEntry,
+    ToolAuditStatus, UpdateSessionRequest,
 };
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use libsql::{Connection, Database};
+use stakpak_shared::models::integrations::openai::{ChatMessage, MessageContent, Role};
 use std::path::Path;
 use std::str::FromStr;
 use tempfile::TempDir;
@@ -23,6 +26,8 @@ use uuid::Uuid;
 pub struct LocalStorage {
     db: Database,
     backend_info: BackendInfo,
+    /// Opened with `open_readonly`; writes are rejected.
+    read_only: bool,
     /// Owns temporary backing storage for in-memory mode and cleans it on drop.
     _temp_dir: Option<TempDir>,
 }
@@ -48,6 +53,14 @@ impl LocalStorage {
             })?;
         }
 
+        // Another process may be initializing the same file; only one runs
+        // pragmas and migrations at a time.
+        let init_lock = stakpak_shared::sqlite::acquire_schema_init_lock(&resolved_path)
+            .await
+            .map_err(|e| {
+                StorageError::Connection(format!("Failed to lock database for setup: {}", e))
+            })?;
+
         let db = libsql::Builder::new_local(&resolved_path)
             .build()
             .await
@@ -56,14 +69,59 @@ impl LocalStorage {
         let storage = Self {
             db,
             backend_info: BackendInfo::local(db_path.to_string()),
+            read_only: false,
             _temp_dir: temp_dir,
         };
         storage.configure_database_pragmas().await?;
         storage.init_schema().await?;
+        drop(init_lock);
 
         Ok(storage)
     }
 
+    /// Open an existing database read-only.
+    ///
+    /// Safe to use from another process while a writer is running, e.g. to
+    /// inspect session history during a long async run. Each query sees a
+    /// consistent WAL snapshot; migrations are left to the writer and every
+    /// write is rejected.
+    pub async fn open_readonly(db_path: &str) -> Result<Self, StorageError> {
+        let path = Path::new(db_path);
+        if !path.is_file() {
+            return Err(StorageError::NotFound(format!(
+                "Database {} does not exist",
+                db_path
+            )));
+        }
+
+        let db = libsql::Builder::new_local(path)
+            .flags(libsql::OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .build()
+            .await
+            .map_err(|e| StorageError::Connection(format!("Failed to open database: {}", e)))?;
+
+        Ok(Self {
+            db,
+            backend_info: BackendInfo::local(db_path.to_string()),
+            read_only: true,
+            _temp_dir: None,
+        })
+    }
+
+    /// Whether this handle was opened with `open_readonly`
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    fn ensure_writable(&self) -> Result<(), StorageError> {
+        if self.read_only {
+            return Err(StorageError::InvalidRequest(
+                "Storage was opened read-only".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     /// Create from an existing database + connection pair.
     ///
     /// The provided connection is intentionally ignored; a fresh connection is
@@ -75,6 +133,7 @@ impl LocalStorage {
         let storage = Self {
             db,
             backend_info: BackendInfo::local(":memory:"),
+            read_only: false,
             _temp_dir: None,
         };
         storage.configure_database_pragmas().await?;
@@ -128,6 +187,11 @@ impl LocalStorage {
         stakpak_shared::sqlite::apply_connection_pragmas(&conn)
             .await
             .map_err(|e| StorageError::Connection(e.to_string()))?;
+        if self.read_only {
+            conn.query("PRAGMA query_only = ON", ())
+                .await
+                .map_err(|e| StorageError::Connection(e.to_string()))?;
+        }
         Ok(conn)
     }
 
@@ -345,6 +409,7 @@ impl SessionStorage for LocalStorage {
         &self,
         request: &CreateSessionRequest,
     ) -> Result<CreateSessionResult, StorageError> {
+        self.ensure_writable()?;
         let now = Utc::now();
         let session_id = Uuid::new_v4();
         let checkpoint_id = Uuid::new_v4();
@@ -403,6 +468,7 @@ impl SessionStorage for LocalStorage {
         session_id: Uuid,
         request: &UpdateSessionRequest,
     ) -> Result<Session, StorageError> {
+        self.ensure_writable()?;
         let now = Utc::now();
 
         {
@@ -435,6 +501,7 @@ impl SessionStorage for LocalStorage {
     }
 
     async fn delete_session(&self, session_id: Uuid) -> Result<(), StorageError> {
+        self.ensure_writable()?;
         // Mark as deleted instead of actually deleting
         let now = Utc::now();
         let conn = self.connection().await?;
@@ -561,6 +628,7 @@ impl SessionStorage for LocalStorage {
         session_id: Uuid,
         request: &CreateCheckpointRequest,
     ) -> Result<Checkpoint, StorageError> {
+        self.ensure_writable()?;
         let now = Utc::now();
         let checkpoint_id = Uuid::new_v4();
 
@@ -600,6 +668,214 @@ impl SessionStorage for LocalStorage {
             updated_at: now,
         })
     }
+
+    async fn get_recovery_options(
+        &self,
+        session_id: Uuid,
+    ) -> Result<RecoveryOptions, StorageError> {
+        let conn = self.connection().await?;
+        let latest = Self::get_latest_checkpoint_for_session_inner(&conn, session_id).await?;
+        recovery_options(&latest)
+    }
+
+    async fn submit_recovery_action(
+        &self,
+        session_id: Uuid,
+        request: &RecoveryActionRequest,
+    ) -> Result<Checkpoint, StorageError> {
+        let failed = {
+            let conn = self.connection().await?;
+            Self::get_latest_checkpoint_for_session_inner(&conn, session_id).await?
+        };
+        let options = recovery_options(&failed)?;
+        let option = options
+            .options
+            .iter()
+            .find(|option| option.kind == request.kind)
+            .ok_or_else(|| {
+                StorageError::InvalidRequest(format!(
+                    "{:?} is not available for checkpoint {}",
+                    request.kind, failed.id
+                ))
+            })?;
+
+        let state = match request.kind {
+            RecoveryActionKind::Rollback => self.get_checkpoint(option.checkpoint_id).await?.state,
+            RecoveryActionKind::Retry => CheckpointState {
+                messages: up_to_last_user_message(&failed.state.messages),
+                metadata: failed.state.metadata.clone(),
+            },
+            RecoveryActionKind::EditAndContinue => {
+                let message = request.message.as_deref().ok_or_else(|| {
+                    StorageError::InvalidRequest(
+                        "EditAndContinue needs a replacement message".to_string(),
+                    )
+                })?;
+                let mut messages = up_to_last_user_message(&failed.state.messages);
+                if let Some(last) = messages.last_mut() {
+                    last.content = Some(MessageContent::String(message.to_string()));
+                }
+                CheckpointState {
+                    messages,
+                    metadata: failed.state.metadata.clone(),
+                }
+            }
+        };
+
+        // Recovery branches off the failed checkpoint so the failure stays in history
+        self.create_checkpoint(
+            session_id,
+            &CreateCheckpointRequest {
+                state,
+                parent_id: Some(failed.id),
+            },
+        )
+        .await
+    }
+
+    async fn record_tool_audit(
+        &self,
+        session_id: Uuid,
+        request: &RecordToolAuditRequest,
+    ) -> Result<ToolAuditEntry, StorageError> {
+        self.ensure_writable()?;
+        let now = Utc::now();
+        let entry_id = Uuid::new_v4();
+
+        let conn = self.connection().await?;
+        conn.execute(
+            "INSERT INTO tool_audit (id, session_id, tool_call_id, tool_name, arguments, decision, status, summary, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            (
+                entry_id.to_string(),
+                session_id.to_string(),
+                request.tool_call_id.as_str(),
+                request.tool_name.as_str(),
+                request.arguments.as_str(),
+                request.decision.to_string(),
+                request.status.to_string(),
+                request.summary.as_deref(),
+                now.to_rfc3339(),
+            ),
+        )
+        .await
+        .map_err(|e| StorageError::Internal(e.to_string()))?;
+
+        Ok(ToolAuditEntry {
+            id: entry_id,
+            session_id,
+            tool_call_id: request.tool_call_id.clone(),
+            tool_name: request.tool_name.clone(),
+            arguments: request.arguments.clone(),
+            decision: request.decision,
+            status: request.status,
+            summary: request.summary.clone(),
+            created_at: now,
+        })
+    }
+
+    async fn get_tool_audit(&self, session_id: Uuid) -> Result<Vec<ToolAuditEntry>, StorageError> {
+        let conn = self.connection().await?;
+        let mut rows = conn
+            .query(
+                "SELECT id, tool_call_id, tool_name, arguments, decision, status, summary, created_at FROM tool_audit
+                 WHERE session_id = ? ORDER BY created_at ASC, rowid ASC",
+                [session_id.to_string()],
+            )
+            .await
+            .map_err(|e| StorageError::Internal(e.to_string()))?;
+
+        let mut entries = Vec::new();
+        while let Ok(Some(row)) = rows.next().await {
+            let id: String = row
+                .get(0)
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+            let tool_call_id: String = row
+                .get(1)
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+            let tool_name: String = row
+                .get(2)
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+            let arguments: String = row
+                .get(3)
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+            let decision: String = row
+                .get(4)
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+            let status: String = row
+                .get(5)
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+            let summary: Option<String> = row.get(6).ok();
+            let created_at: String = row
+                .get(7)
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+
+            entries.push(ToolAuditEntry {
+                id: Uuid::from_str(&id).map_err(|e| StorageError::Internal(e.to_string()))?,
+                session_id,
+                tool_call_id,
+                tool_name,
+                arguments,
+                decision: parse_tool_decision(&decision),
+                status: parse_tool_audit_status(&status),
+                summary,
+                created_at: parse_datetime(&created_at)?,
+            });
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Derive the recovery actions for a checkpoint, failing if it did not fail
+fn recovery_options(checkpoint: &Checkpoint) -> Result<RecoveryOptions, StorageError> {
+    let reason = checkpoint.state.failure_reason().ok_or_else(|| {
+        StorageError::NotFound(format!(
+            "Latest checkpoint {} of session {} has not failed",
+            checkpoint.id, checkpoint.session_id
+        ))
+    })?;
+
+    let mut options = Vec::new();
+    if checkpoint
+        .state
+        .messages
+        .iter()
+        .any(|message| message.role == Role::User)
+    {
+        options.push(RecoveryOption {
+            kind: RecoveryActionKind::Retry,
+            description: "Run the last user message again".to_string(),
+            checkpoint_id: checkpoint.id,
+        });
+        options.push(RecoveryOption {
+            kind: RecoveryActionKind::EditAndContinue,
+            description: "Edit the last user message and continue from it".to_string(),
+            checkpoint_id: checkpoint.id,
+        });
+    }
+    if let Some(parent_id) = checkpoint.parent_id {
+        options.push(RecoveryOption {
+            kind: RecoveryActionKind::Rollback,
+            description: "Return to the checkpoint before the failure".to_string(),
+            checkpoint_id: parent_id,
+        });
+    }
+
+    Ok(RecoveryOptions {
+        session_id: checkpoint.session_id,
+        failed_checkpoint_id: checkpoint.id,
+        reason,
+        options,
+    })
+}
+
+/// Messages up to and including the last user message
+fn up_to_last_user_message(messages: &[ChatMessage]) -> Vec<ChatMessage> {
+    let end = messages
+        .iter()
+        .rposition(|message| message.role == Role::User)
+        .map_or(0, |index| index + 1);
+    messages.iter().take(end).cloned().collect()
 }
 
 // Helper functions
@@ -617,7 +893,24 @@ fn parse_status(s: &str) -> SessionStatus {
     }
 }
 
-fn parse_datetime(s: &str) -> Result<DateTime<Utc>, StorageError> {
+fn parse_tool_decision(s: &str) -> ToolApprovalDecision {
+    match s {
+        "rejected" => ToolApprovalDecision::Rejected,
+        _ => ToolApprovalDecision::Approved,
+    }
+}
+
+fn parse_tool_audit_status(s: &str) -> ToolAuditStatus {
+    match s {
+        "succeeded" => ToolAuditStatus::Succeeded,
+        "cancelled" => ToolAuditStatus::Cancelled,
+        "blocked" => ToolAuditStatus::Blocked,
+        "not_run" => ToolAuditStatus::NotRun,
+        _ => ToolAuditStatus::Failed,
+    }
+}
+
+pub(crate) fn parse_datetime(s: &str) -> Result<DateTime<Utc>, StorageError> {
     DateTime::parse_from_rfc3339(s)
         .map(|dt| dt.with_timezone(&Utc))
         .map_err(|e| StorageError::Internal(format!("Failed to parse datetime: {}", e)))
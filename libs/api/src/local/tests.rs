@@ -1000,6 +1000,154 @@ mod local_storage_tests {
         assert!(stats.tools_usage.is_empty());
     }
 
+    // =========================================================================
+    // Session export / import
+    // =========================================================================
+
+    #[tokio::test]
+    async fn test_export_import_round_trip_preserves_messages() {
+        let source = create_test_storage().await;
+        let req = CreateSessionRequest::new("Exported", vec![user_msg("deploy the app")])
+            .with_cwd("/srv/app");
+        let session = source.create_session(&req).await.unwrap();
+        let cp2 = source
+            .create_checkpoint(
+                session.session_id,
+                &CreateCheckpointRequest::new(vec![
+                    user_msg("deploy the app"),
+                    assistant_msg("Deployed ✅ to \"prod\""),
+                ])
+                .with_parent(session.checkpoint.id),
+            )
+            .await
+            .unwrap();
+
+        let mut archive = Vec::new();
+        let exported = source
+            .export_session(session.session_id, &mut archive)
+            .await
+            .unwrap();
+        assert_eq!(exported.checkpoints.len(), 2);
+
+        let target = create_test_storage().await;
+        let imported_id = target.import_session(archive.as_slice()).await.unwrap();
+        assert_ne!(imported_id, session.session_id);
+
+        let imported = target.get_session(imported_id).await.unwrap();
+        assert_eq!(imported.title, "Exported");
+        assert_eq!(imported.cwd, Some("/srv/app".to_string()));
+
+        let active = imported.active_checkpoint.unwrap();
+        assert_ne!(active.id, cp2.id);
+        let contents: Vec<String> = active
+            .state
+            .messages
+            .iter()
+            .map(|m| m.content.as_ref().unwrap().to_string())
+            .collect();
+        assert_eq!(contents, vec!["deploy the app", "Deployed ✅ to \"prod\""]);
+
+        // The parent chain is re-keyed onto the imported checkpoints
+        let parent = target
+            .get_checkpoint(active.parent_id.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(parent.session_id, imported_id);
+        assert!(parent.parent_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_export_orders_parents_before_children_when_timestamps_disagree() {
+        let source = create_test_storage().await;
+        let session = source
+            .create_session(&session_request("Skewed", vec![user_msg("hi")]))
+            .await
+            .unwrap();
+        let child = source
+            .create_checkpoint(
+                session.session_id,
+                &CreateCheckpointRequest::new(vec![user_msg("hi"), assistant_msg("hello")])
+                    .with_parent(session.checkpoint.id),
+            )
+            .await
+            .unwrap();
+
+        // The child claims to be older than its parent (e.g. after a clock change)
+        let conn = source.connection().await.expect("connection");
+        conn.execute(
+            "UPDATE checkpoints SET created_at = ? WHERE id = ?",
+            ("2000-01-01T00:00:00+00:00", child.id.to_string()),
+        )
+        .await
+        .unwrap();
+
+        let mut archive = Vec::new();
+        let exported = source
+            .export_session(session.session_id, &mut archive)
+            .await
+            .unwrap();
+        let ids: Vec<Uuid> = exported.checkpoints.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![session.checkpoint.id, child.id]);
+
+        let target = create_test_storage().await;
+        let imported_id = target.import_session(archive.as_slice()).await.unwrap();
+        let imported = target.get_session(imported_id).await.unwrap();
+        assert!(imported.active_checkpoint.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_export_fails_on_corrupt_checkpoint_state() {
+        let storage = create_test_storage().await;
+        let session = storage
+            .create_session(&session_request("Corrupt", vec![user_msg("hi")]))
+            .await
+            .unwrap();
+
+        let conn = storage.connection().await.expect("connection");
+        conn.execute(
+            "UPDATE checkpoints SET state = ? WHERE id = ?",
+            ("{not json", session.checkpoint.id.to_string()),
+        )
+        .await
+        .unwrap();
+
+        let result = storage.export_session(session.session_id, Vec::new()).await;
+        assert!(matches!(result, Err(StorageError::Internal(_))));
+    }
+
+    #[tokio::test]
+    async fn test_import_into_source_database_does_not_collide() {
+        let storage = create_test_storage().await;
+        let session = storage
+            .create_session(&session_request("Twice", vec![user_msg("hi")]))
+            .await
+            .unwrap();
+
+        let mut archive = Vec::new();
+        storage
+            .export_session(session.session_id, &mut archive)
+            .await
+            .unwrap();
+        let first = storage.import_session(archive.as_slice()).await.unwrap();
+        let second = storage.import_session(archive.as_slice()).await.unwrap();
+
+        assert_ne!(first, second);
+        let listed = storage
+            .list_sessions(&ListSessionsQuery::new())
+            .await
+            .unwrap();
+        assert_eq!(listed.sessions.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_unknown_archive_format() {
+        let storage = create_test_storage().await;
+        let result = storage
+            .import_session(br#"{"format":"other","version":1}"#.as_slice())
+            .await;
+        assert!(matches!(result, Err(StorageError::InvalidRequest(_))));
+    }
+
     // =========================================================================
     // Migration tests
     // =========================================================================
@@ -1015,10 +1163,10 @@ mod local_storage_tests {
         let version = crate::local::migrations::current_version(&conn)
             .await
             .unwrap();
-        assert_eq!(version, 2, "All migrations should be applied");
+        assert_eq!(version, 3, "All migrations should be applied");
 
         let status = crate::local::migrations::status(&conn).await.unwrap();
-        assert_eq!(status.applied, vec![1, 2]);
+        assert_eq!(status.applied, vec![1, 2, 3]);
         assert!(status.pending.is_empty());
     }
 
@@ -1030,7 +1178,18 @@ mod local_storage_tests {
             .await
             .expect("failed to open test connection");
 
-        // Should be at version 2
+        // Should be at version 3
+        let version = crate::local::migrations::current_version(&conn)
+            .await
+            .unwrap();
+        assert_eq!(version, 3);
+
+        // Rollback to version 2
+        let rolled_back = crate::local::migrations::rollback_last(&conn)
+            .await
+            .unwrap();
+        assert_eq!(rolled_back, Some(3));
+
         let version = crate::local::migrations::current_version(&conn)
             .await
             .unwrap();
@@ -1060,7 +1219,7 @@ mod local_storage_tests {
 
         // Re-apply all
         let applied = crate::local::migrations::apply_all(&conn).await.unwrap();
-        assert_eq!(applied, vec![1, 2]);
+        assert_eq!(applied, vec![1, 2, 3]);
     }
 
     // =========================================================================
@@ -1763,11 +1922,11 @@ mod local_storage_tests {
             .await
             .expect("failed to open test connection");
 
-        // Rollback to version 1 (keeps 1, removes 2)
+        // Rollback to version 1 (keeps 1, removes 3 and 2)
         let rolled_back = crate::local::migrations::rollback_to(&conn, 1)
             .await
             .unwrap();
-        assert_eq!(rolled_back, vec![2]);
+        assert_eq!(rolled_back, vec![3, 2]);
 
         let version = crate::local::migrations::current_version(&conn)
             .await
@@ -1941,4 +2100,451 @@ mod local_storage_tests {
             result.err()
         );
     }
+
+    /// Two stores opened on the same file model two `stakpak` processes
+    /// sharing `~/.stakpak/data/local.db`: setup is serialized by the schema
+    /// init lock and concurrent writes wait on WAL + busy_timeout.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_two_stores_on_same_file_write_concurrently() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let db_path = temp_dir.path().join("shared.db");
+        let db_path = db_path.to_string_lossy().into_owned();
+
+        let (store_a, store_b) = tokio::join!(
+            crate::local::storage::LocalStorage::new(&db_path),
+            crate::local::storage::LocalStorage::new(&db_path),
+        );
+        let store_a = std::sync::Arc::new(store_a.expect("first store should open"));
+        let store_b = std::sync::Arc::new(store_b.expect("second store should open"));
+
+        let n: usize = 10;
+        let barrier = std::sync::Arc::new(tokio::sync::Barrier::new(2 * n));
+        let mut handles = Vec::new();
+        for i in 0..2 * n {
+            let storage = if i % 2 == 0 {
+                std::sync::Arc::clone(&store_a)
+            } else {
+                std::sync::Arc::clone(&store_b)
+            };
+            let barrier = std::sync::Arc::clone(&barrier);
+            handles.push(tokio::spawn(async move {
+                barrier.wait().await;
+                storage
+                    .create_session(&session_request(
+                        &format!("shared-session-{}", i),
+                        vec![user_msg("hi")],
+                    ))
+                    .await
+            }));
+        }
+
+        for handle in handles {
+            let result = handle.await.expect("task panicked");
+            assert!(
+                result.is_ok(),
+                "concurrent write failed: {:?}",
+                result.err()
+            );
+        }
+
+        let journal_mode = stakpak_shared::sqlite::read_journal_mode(
+            &store_b.connection().await.expect("connection"),
+        )
+        .await
+        .expect("read journal mode");
+        assert_eq!(journal_mode.to_lowercase(), "wal");
+
+        let listed = store_a
+            .list_sessions(&ListSessionsQuery::new())
+            .await
+            .expect("list sessions");
+        assert_eq!(listed.sessions.len(), 2 * n);
+    }
+
+    // =========================================================================
+    // Read-only handles
+    // =========================================================================
+
+    /// A read-only handle on the file a writer is using sees every committed
+    /// checkpoint whole and in order, and cannot write.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_readonly_handle_reads_while_writer_writes() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let db_path = temp_dir.path().join("local.db");
+        let db_path = db_path.to_string_lossy().into_owned();
+
+        let writer = crate::local::storage::LocalStorage::new(&db_path)
+            .await
+            .expect("writer should open");
+        let created = writer
+            .create_session(&session_request("Long run", vec![user_msg("step 0")]))
+            .await
+            .unwrap();
+        let session_id = created.session_id;
+
+        let reader = crate::local::storage::LocalStorage::open_readonly(&db_path)
+            .await
+            .expect("read-only handle should open");
+        assert!(reader.is_read_only());
+
+        let steps = 20;
+        let write = async {
+            let mut messages = vec![user_msg("step 0")];
+            let mut parent = created.checkpoint.id;
+            for step in 1..=steps {
+                messages.push(assistant_msg(&format!("step {step}")));
+                let checkpoint = writer
+                    .create_checkpoint(
+                        session_id,
+                        &CreateCheckpointRequest::new(messages.clone()).with_parent(parent),
+                    )
+                    .await
+                    .unwrap();
+                parent = checkpoint.id;
+                tokio::task::yield_now().await;
+            }
+        };
+        let read = async {
+            let mut last_seen = 0;
+            while last_seen < steps {
+                let active = reader.get_active_checkpoint(session_id).await.unwrap();
+                let seen = active.state.messages.len() - 1;
+                assert!(seen >= last_seen, "went back from {last_seen} to {seen}");
+                let last_text = active.state.messages.last().unwrap().content.as_ref();
+                assert_eq!(last_text.unwrap().to_string(), format!("step {seen}"));
+                last_seen = seen;
+                tokio::task::yield_now().await;
+            }
+        };
+        tokio::join!(write, read);
+
+        let listed = reader
+            .list_checkpoints(session_id, &ListCheckpointsQuery::new())
+            .await
+            .unwrap();
+        assert_eq!(listed.checkpoints.len(), steps + 1);
+
+        let err = reader
+            .create_session(&session_request("Nope", vec![user_msg("hi")]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StorageError::InvalidRequest(_)), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_readonly_handle_requires_an_existing_database() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let db_path = temp_dir.path().join("missing.db");
+
+        let result =
+            crate::local::storage::LocalStorage::open_readonly(&db_path.to_string_lossy()).await;
+        assert!(matches!(result, Err(StorageError::NotFound(_))));
+    }
+
+    // =========================================================================
+    // Recovery
+    // =========================================================================
+
+    /// Helper: a session whose second checkpoint failed mid tool call
+    async fn session_with_failed_checkpoint(
+        storage: &crate::local::storage::LocalStorage,
+    ) -> (Uuid, Checkpoint, Checkpoint) {
+        use stakpak_shared::models::integrations::openai::{FunctionCall, ToolCall};
+
+        let created = storage
+            .create_session(&session_request(
+                "Recovery",
+                vec![user_msg("hello"), assistant_msg("hi")],
+            ))
+            .await
+            .unwrap();
+        let good = created.checkpoint;
+
+        let interrupted = ChatMessage {
+            role: Role::Assistant,
+            tool_calls: Some(vec![ToolCall {
+                id: "tc_1".to_string(),
+                r#type: "function".to_string(),
+                function: FunctionCall {
+                    name: "run_command".to_string(),
+                    arguments: "{\"command\":\"terraform apply\"}".to_string(),
+                },
+                metadata: None,
+            }]),
+            ..Default::default()
+        };
+        let failed = storage
+            .create_checkpoint(
+                created.session_id,
+                &CreateCheckpointRequest::new(vec![
+                    user_msg("hello"),
+                    assistant_msg("hi"),
+                    user_msg("deploy it"),
+                    interrupted,
+                ])
+                .with_parent(good.id),
+            )
+            .await
+            .unwrap();
+
+        (created.session_id, good, failed)
+    }
+
+    #[tokio::test]
+    async fn test_recovery_options_for_failed_checkpoint() {
+        let storage = create_test_storage().await;
+        let (session_id, good, failed) = session_with_failed_checkpoint(&storage).await;
+
+        let options = storage.get_recovery_options(session_id).await.unwrap();
+
+        assert_eq!(options.session_id, session_id);
+        assert_eq!(options.failed_checkpoint_id, failed.id);
+        assert!(
+            options.reason.contains("1 tool call(s)"),
+            "{}",
+            options.reason
+        );
+        let kinds: Vec<(RecoveryActionKind, Uuid)> = options
+            .options
+            .iter()
+            .map(|option| (option.kind, option.checkpoint_id))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (RecoveryActionKind::Retry, failed.id),
+                (RecoveryActionKind::EditAndContinue, failed.id),
+                (RecoveryActionKind::Rollback, good.id),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_recovery_options_require_a_failure() {
+        let storage = create_test_storage().await;
+        let created = storage
+            .create_session(&session_request(
+                "Healthy",
+                vec![user_msg("hello"), assistant_msg("hi")],
+            ))
+            .await
+            .unwrap();
+
+        let err = storage
+            .get_recovery_options(created.session_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StorageError::NotFound(_)), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_rollback_restores_the_checkpoint_before_the_failure() {
+        let storage = create_test_storage().await;
+        let (session_id, good, failed) = session_with_failed_checkpoint(&storage).await;
+
+        let recovered = storage
+            .submit_recovery_action(
+                session_id,
+                &RecoveryActionRequest::new(RecoveryActionKind::Rollback),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(recovered.parent_id, Some(failed.id));
+        assert_eq!(recovered.state.messages, good.state.messages);
+        let active = storage.get_active_checkpoint(session_id).await.unwrap();
+        assert_eq!(active.id, recovered.id);
+        assert!(active.state.failure_reason().is_none());
+
+        // The failure stays in history
+        let listed = storage
+            .list_checkpoints(session_id, &ListCheckpointsQuery::new())
+            .await
+            .unwrap();
+        assert_eq!(listed.checkpoints.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_edit_and_continue_replaces_the_last_user_message() {
+        let storage = create_test_storage().await;
+        let (session_id, _, _) = session_with_failed_checkpoint(&storage).await;
+
+        let missing_message = storage
+            .submit_recovery_action(
+                session_id,
+                &RecoveryActionRequest::new(RecoveryActionKind::EditAndContinue),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(missing_message, StorageError::InvalidRequest(_)));
+
+        let recovered = storage
+            .submit_recovery_action(
+                session_id,
+                &RecoveryActionRequest::new(RecoveryActionKind::EditAndContinue)
+                    .with_message("deploy it to staging"),
+            )
+            .await
+            .unwrap();
+
+        let texts: Vec<String> = recovered
+            .state
+            .messages
+            .iter()
+            .map(|message| message.content.as_ref().unwrap().to_string())
+            .collect();
+        assert_eq!(texts, vec!["hello", "hi", "deploy it to staging"]);
+    }
+
+    // =========================================================================
+    // Tool audit
+    // =========================================================================
+
+    /// Helper: a tool call with the given name and JSON arguments
+    fn audited_call(
+        id: &str,
+        name: &str,
+        arguments: &str,
+    ) -> stakpak_shared::models::integrations::openai::ToolCall {
+        use stakpak_shared::models::integrations::openai::{FunctionCall, ToolCall};
+
+        ToolCall {
+            id: id.to_string(),
+            r#type: "function".to_string(),
+            function: FunctionCall {
+                name: name.to_string(),
+                arguments: arguments.to_string(),
+            },
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_audit_records_executed_and_rejected_calls() {
+        let storage = create_test_storage().await;
+        let session = storage
+            .create_session(&session_request("Audit", vec![user_msg("clean up")]))
+            .await
+            .unwrap();
+        let other = storage
+            .create_session(&session_request("Other", vec![user_msg("hi")]))
+            .await
+            .unwrap();
+
+        let view = audited_call("tc_1", "view", r#"{"path":"README.md"}"#);
+        let delete = audited_call("tc_2", "run_command", r#"{"command":"rm -rf build"}"#);
+        storage
+            .record_tool_audit(
+                session.session_id,
+                &RecordToolAuditRequest::approved(&view, ToolAuditStatus::Succeeded, "# README"),
+            )
+            .await
+            .unwrap();
+        storage
+            .record_tool_audit(
+                session.session_id,
+                &RecordToolAuditRequest::rejected(&delete),
+            )
+            .await
+            .unwrap();
+
+        let audit = storage.get_tool_audit(session.session_id).await.unwrap();
+        assert_eq!(audit.len(), 2);
+
+        assert_eq!(audit[0].session_id, session.session_id);
+        assert_eq!(audit[0].tool_call_id, "tc_1");
+        assert_eq!(audit[0].tool_name, "view");
+        assert_eq!(audit[0].arguments, r#"{"path":"README.md"}"#);
+        assert_eq!(audit[0].decision, ToolApprovalDecision::Approved);
+        assert_eq!(audit[0].status, ToolAuditStatus::Succeeded);
+        assert_eq!(audit[0].summary.as_deref(), Some("# README"));
+
+        assert_eq!(audit[1].tool_call_id, "tc_2");
+        assert_eq!(audit[1].tool_name, "run_command");
+        assert_eq!(audit[1].decision, ToolApprovalDecision::Rejected);
+        assert_eq!(audit[1].status, ToolAuditStatus::NotRun);
+        assert_eq!(audit[1].summary, None);
+        assert!(audit[0].created_at <= audit[1].created_at);
+
+        assert!(
+            storage
+                .get_tool_audit(other.session_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tool_audit_redacts_arguments_and_output() {
+        let storage = create_test_storage().await;
+        let session = storage
+            .create_session(&session_request("Audit", vec![user_msg("deploy")]))
+            .await
+            .unwrap();
+
+        let api_key = ["abc123def456", "ghi789jkl012", "mno345pqr678"].concat();
+        let call = audited_call(
+            "tc_1",
+            "run_command",
+            &format!(r#"{{"command":"export API_KEY={api_key} && ./deploy.sh"}}"#),
+        );
+        let output = format!("API_KEY={api_key}\n{}", "x".repeat(1_000));
+        storage
+            .record_tool_audit(
+                session.session_id,
+                &RecordToolAuditRequest::approved(&call, ToolAuditStatus::Failed, &output),
+            )
+            .await
+            .unwrap();
+
+        let audit = storage.get_tool_audit(session.session_id).await.unwrap();
+        assert_eq!(audit.len(), 1);
+        assert!(
+            !audit[0].arguments.contains(&api_key),
+            "{}",
+            audit[0].arguments
+        );
+        assert!(
+            audit[0].arguments.contains("[REDACTED_"),
+            "{}",
+            audit[0].arguments
+        );
+
+        let summary = audit[0].summary.as_deref().unwrap();
+        assert!(!summary.contains(&api_key), "{summary}");
+        assert!(summary.ends_with("..."), "{summary}");
+        assert_eq!(audit[0].status, ToolAuditStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_readonly_handle_rejects_tool_audit_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("audit.db");
+        let db_path = db_path.to_string_lossy();
+        let writer = crate::local::storage::LocalStorage::new(&db_path)
+            .await
+            .unwrap();
+        let session = writer
+            .create_session(&session_request("Audit", vec![user_msg("hi")]))
+            .await
+            .unwrap();
+
+        let reader = crate::local::storage::LocalStorage::open_readonly(&db_path)
+            .await
+            .unwrap();
+        let call = audited_call("tc_1", "view", "{}");
+        let result = reader
+            .record_tool_audit(session.session_id, &RecordToolAuditRequest::rejected(&call))
+            .await;
+
+        assert!(matches!(result, Err(StorageError::InvalidRequest(_))));
+        assert!(
+            reader
+                .get_tool_audit(session.session_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
 }
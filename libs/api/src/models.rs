@@ -3,8 +3,12 @@ use rmcp::model::Content;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use stakai::Model;
+use stakpak_shared::local_store::LocalStore;
 use stakpak_shared::models::{
-    integrations::openai::{ChatMessage, FunctionCall, MessageContent, Role, Tool, ToolCall},
+    indexing::IndexingStatus,
+    integrations::openai::{
+        ChatMessage, ContentPart, FunctionCall, ImageUrl, MessageContent, Role, Tool, ToolCall,
+    },
     llm::{LLMInput, LLMMessage, LLMMessageContent, LLMMessageTypedContent, LLMTokenUsage},
 };
 use std::collections::HashMap;
@@ -260,12 +264,118 @@ pub struct BuildCodeIndexOutput {
     pub warnings: Vec<IndexError>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Current on-disk format of [`CodeIndex`]; bump when the layout changes.
+pub const CODE_INDEX_VERSION: u32 = 1;
+
+/// Session store file holding the serialized [`CodeIndex`].
+pub const CODE_INDEX_FILE: &str = "code_index.json";
+/// Session store file holding the last [`IndexingStatus`].
+pub const INDEXING_STATUS_FILE: &str = "indexing_status.json";
+
+#[derive(Debug, Clone)]
 pub struct CodeIndex {
+    /// Format version the index was stored with
+    pub version: u32,
     pub last_updated: DateTime<Utc>,
     pub index: BuildCodeIndexOutput,
 }
 
+/// On-disk layout of a [`CodeIndex`]. `index` is kept as the exact bytes
+/// that were written so the checksum covers what is on disk, not a
+/// re-serialization of it.
+#[derive(Serialize, Deserialize)]
+struct StoredCodeIndex<'a> {
+    /// Indexes written before versioning deserialize as 0
+    #[serde(default)]
+    version: u32,
+    /// Hex SHA-256 of `index`, used to detect partial or corrupt writes
+    #[serde(default)]
+    checksum: String,
+    last_updated: DateTime<Utc>,
+    #[serde(borrow)]
+    index: &'a serde_json::value::RawValue,
+}
+
+impl CodeIndex {
+    /// Wrap a freshly built index, stamping the current version.
+    pub fn new(index: BuildCodeIndexOutput) -> Self {
+        Self {
+            version: CODE_INDEX_VERSION,
+            last_updated: Utc::now(),
+            index,
+        }
+    }
+
+    /// Serialize for storage, with a checksum over the stored index bytes.
+    pub fn to_stored_json(&self) -> Result<String, String> {
+        let index = serde_json::value::to_raw_value(&self.index)
+            .map_err(|e| format!("Failed to serialize code index: {}", e))?;
+        serde_json::to_string_pretty(&StoredCodeIndex {
+            version: self.version,
+            checksum: Self::checksum_of(index.get()),
+            last_updated: self.last_updated,
+            index: &index,
+        })
+        .map_err(|e| format!("Failed to serialize code index: {}", e))
+    }
+
+    /// Parse a stored index and check its version and checksum.
+    pub fn parse_verified(index_str: &str) -> Result<Self, String> {
+        let stored: StoredCodeIndex<'_> =
+            serde_json::from_str(index_str).map_err(|e| format!("Code index is corrupt: {}", e))?;
+        if stored.version != CODE_INDEX_VERSION {
+            return Err(format!(
+                "Code index version {} does not match expected version {}",
+                stored.version, CODE_INDEX_VERSION
+            ));
+        }
+        if stored.checksum != Self::checksum_of(stored.index.get()) {
+            return Err("Code index checksum mismatch, the index is corrupt".to_string());
+        }
+        let index = serde_json::from_str(stored.index.get())
+            .map_err(|e| format!("Code index is corrupt: {}", e))?;
+        Ok(Self {
+            version: stored.version,
+            last_updated: stored.last_updated,
+            index,
+        })
+    }
+
+    /// Load the index `local_code_search` should use from the session store.
+    ///
+    /// Never builds or rebuilds an index: when none is stored, or the stored
+    /// one fails verification, the error carries the reason from the stored
+    /// [`IndexingStatus`].
+    pub fn load_stored_for_search() -> Result<Self, String> {
+        let stored = LocalStore::read_session_data(CODE_INDEX_FILE).unwrap_or_default();
+        let status = LocalStore::read_session_data(INDEXING_STATUS_FILE)
+            .ok()
+            .and_then(|status| serde_json::from_str::<IndexingStatus>(&status).ok());
+        Self::load_for_search(&stored, status.as_ref())
+    }
+
+    /// Load a stored index for `local_code_search`.
+    ///
+    /// `stored` is the content of the index file and `status` the last
+    /// recorded indexing status. When no usable index exists the error says
+    /// why, so the tool can report it instead of searching bad data.
+    pub fn load_for_search(stored: &str, status: Option<&IndexingStatus>) -> Result<Self, String> {
+        if stored.trim().is_empty() {
+            let reason = status
+                .filter(|status| !status.indexed)
+                .map(|status| status.reason.as_str())
+                .unwrap_or("code indexing is disabled");
+            return Err(format!("code index unavailable: {}", reason));
+        }
+        Self::parse_verified(stored).map_err(|e| format!("code index unavailable: {}", e))
+    }
+
+    fn checksum_of(index_json: &str) -> String {
+        use sha2::{Digest, Sha256};
+        format!("{:x}", Sha256::digest(index_json.as_bytes()))
+    }
+}
+
 /// Unified skill type representing knowledge from any source.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Skill {
@@ -567,6 +677,17 @@ pub struct AgentState {
     /// Metadata for checkpoint persistence (context trimming state, etc.)
     /// Loaded from checkpoint on session resume and saved back after inference
     pub metadata: Option<Value>,
+
+    /// The tool call being run, set for tool execution hooks
+    pub tool_execution: Option<ToolExecution>,
+}
+
+/// A tool call passed to `BeforeToolExecution`/`AfterToolExecution` hooks.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolExecution {
+    pub tool_call: ToolCall,
+    /// Text output of the tool, only set after it has run
+    pub result: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize)]
@@ -578,17 +699,47 @@ pub struct LLMOutput {
 impl From<&LLMOutput> for ChatMessage {
     fn from(value: &LLMOutput) -> Self {
         let message_content = match &value.new_message.content {
-            LLMMessageContent::String(s) => s.clone(),
-            LLMMessageContent::List(l) => l
-                .iter()
-                .map(|c| match c {
-                    LLMMessageTypedContent::Text { text } => text.clone(),
-                    LLMMessageTypedContent::ToolCall { .. } => String::new(),
-                    LLMMessageTypedContent::ToolResult { content, .. } => content.clone(),
-                    LLMMessageTypedContent::Image { .. } => String::new(),
-                })
-                .collect::<Vec<_>>()
-                .join("\n"),
+            LLMMessageContent::String(s) => MessageContent::String(s.clone()),
+            // Joining into a single string would drop the images, so keep the parts.
+            LLMMessageContent::List(l)
+                if l.iter()
+                    .any(|c| matches!(c, LLMMessageTypedContent::Image { .. })) =>
+            {
+                MessageContent::Array(
+                    l.iter()
+                        .filter_map(|c| match c {
+                            LLMMessageTypedContent::Text { text }
+                            | LLMMessageTypedContent::ToolResult { content: text, .. } => {
+                                Some(ContentPart {
+                                    r#type: "text".to_string(),
+                                    text: Some(text.clone()),
+                                    image_url: None,
+                                })
+                            }
+                            LLMMessageTypedContent::Image { source } => Some(ContentPart {
+                                r#type: "image_url".to_string(),
+                                text: None,
+                                image_url: Some(ImageUrl {
+                                    url: source.to_url(),
+                                    detail: None,
+                                }),
+                            }),
+                            LLMMessageTypedContent::ToolCall { .. } => None,
+                        })
+                        .collect(),
+                )
+            }
+            LLMMessageContent::List(l) => MessageContent::String(
+                l.iter()
+                    .map(|c| match c {
+                        LLMMessageTypedContent::Text { text } => text.clone(),
+                        LLMMessageTypedContent::ToolCall { .. } => String::new(),
+                        LLMMessageTypedContent::ToolResult { content, .. } => content.clone(),
+                        LLMMessageTypedContent::Image { .. } => String::new(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
         };
         let tool_calls = if let LLMMessageContent::List(items) = &value.new_message.content {
             let calls: Vec<ToolCall> = items
@@ -622,7 +773,7 @@ impl From<&LLMOutput> for ChatMessage {
         };
         ChatMessage {
             role: Role::Assistant,
-            content: Some(MessageContent::String(message_content)),
+            content: Some(message_content),
             name: None,
             tool_calls,
             tool_call_id: None,
@@ -646,6 +797,7 @@ impl AgentState {
             metadata,
             llm_input: None,
             llm_output: None,
+            tool_execution: None,
         }
     }
 
@@ -675,4 +827,132 @@ impl AgentState {
     pub fn append_new_message(&mut self, new_message: ChatMessage) {
         self.messages.push(new_message);
     }
+
+    pub fn set_tool_execution(&mut self, tool_call: ToolCall, result: Option<String>) {
+        self.tool_execution = Some(ToolExecution { tool_call, result });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_index() -> CodeIndex {
+        CodeIndex::new(BuildCodeIndexOutput {
+            blocks: Vec::new(),
+            errors: vec![IndexError {
+                uri: "file:///repo/main.tf".to_string(),
+                message: "unterminated block".to_string(),
+                details: None,
+            }],
+            warnings: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn code_index_round_trips_with_valid_checksum() {
+        let stored = sample_index().to_stored_json().unwrap();
+        let loaded = CodeIndex::parse_verified(&stored).unwrap();
+        assert_eq!(loaded.version, CODE_INDEX_VERSION);
+        assert_eq!(loaded.index.errors.len(), 1);
+    }
+
+    #[test]
+    fn code_index_checksum_covers_the_stored_bytes() {
+        let stored = sample_index().to_stored_json().unwrap();
+
+        // Same data, different bytes: the checksum is over what was written
+        let reformatted = stored.replace(r#""details":null"#, r#""details": null"#);
+        assert_ne!(reformatted, stored);
+        let err = CodeIndex::parse_verified(&reformatted).unwrap_err();
+        assert!(err.contains("checksum mismatch"), "{err}");
+    }
+
+    #[test]
+    fn tampered_or_truncated_code_index_is_rejected() {
+        let stored = sample_index().to_stored_json().unwrap();
+
+        let tampered = stored.replace("unterminated block", "garbage");
+        let err = CodeIndex::parse_verified(&tampered).unwrap_err();
+        assert!(err.contains("checksum mismatch"), "{err}");
+
+        let truncated = stored.chars().take(stored.len() / 2).collect::<String>();
+        let err = CodeIndex::parse_verified(&truncated).unwrap_err();
+        assert!(err.starts_with("Code index is corrupt"), "{err}");
+    }
+
+    #[test]
+    fn mixed_text_and_image_output_keeps_its_parts_through_storage() {
+        use stakpak_shared::models::llm::LLMMessageImageSource;
+
+        let output = LLMOutput {
+            new_message: LLMMessage {
+                role: "assistant".to_string(),
+                content: LLMMessageContent::List(vec![
+                    LLMMessageTypedContent::Text {
+                        text: "here is the diagram".to_string(),
+                    },
+                    LLMMessageTypedContent::Image {
+                        source: LLMMessageImageSource {
+                            r#type: "base64".to_string(),
+                            media_type: "image/png".to_string(),
+                            data: "iVBORw0KGgo=".to_string(),
+                        },
+                    },
+                ]),
+            },
+            usage: LLMTokenUsage::default(),
+        };
+
+        let message = ChatMessage::from(&output);
+        let stored = serde_json::to_string(&message).unwrap();
+        let loaded: ChatMessage = serde_json::from_str(&stored).unwrap();
+
+        let Some(MessageContent::Array(parts)) = loaded.content else {
+            panic!("expected multi-part content, got {:?}", loaded.content);
+        };
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].text.as_deref(), Some("here is the diagram"));
+        assert_eq!(
+            parts[1].image_url.as_ref().map(|image| image.url.as_str()),
+            Some("data:image/png;base64,iVBORw0KGgo=")
+        );
+    }
+
+    #[test]
+    fn unversioned_code_index_is_rejected() {
+        let legacy = serde_json::json!({
+            "last_updated": Utc::now(),
+            "index": { "blocks": [], "errors": [], "warnings": [] }
+        });
+        let err = CodeIndex::parse_verified(&legacy.to_string()).unwrap_err();
+        assert!(err.contains("version 0"), "{err}");
+    }
+
+    #[test]
+    fn code_search_explains_why_no_index_is_available() {
+        let err = CodeIndex::load_for_search("", None).unwrap_err();
+        assert_eq!(err, "code index unavailable: code indexing is disabled");
+
+        let status = IndexingStatus {
+            indexed: false,
+            reason: "Directory contains 500 supported files".to_string(),
+            file_count: 500,
+            timestamp: Utc::now(),
+        };
+        let err = CodeIndex::load_for_search("", Some(&status)).unwrap_err();
+        assert!(
+            err.ends_with("Directory contains 500 supported files"),
+            "{err}"
+        );
+
+        let err = CodeIndex::load_for_search("{\"version\": 1", Some(&status)).unwrap_err();
+        assert!(
+            err.starts_with("code index unavailable: Code index is corrupt"),
+            "{err}"
+        );
+
+        let stored = sample_index().to_stored_json().unwrap();
+        assert!(CodeIndex::load_for_search(&stored, Some(&status)).is_ok());
+    }
 }
@@ -3,22 +3,27 @@
 //! Provides access to Stakpak's non-inference APIs.
 
 use super::{
-    CheckpointState, CreateCheckpointRequest, CreateCheckpointResponse, CreateSessionRequest,
-    CreateSessionResponse, GetCheckpointResponse, GetSessionResponse, ListCheckpointsQuery,
-    ListCheckpointsResponse, ListSessionsQuery, ListSessionsResponse, SessionVisibility,
-    StakpakApiConfig, UpdateSessionRequest, UpdateSessionResponse, knowledge::AccountCacheState,
-    models::*,
+    ApiOperation, CheckpointState, CreateCheckpointRequest, CreateCheckpointResponse,
+    CreateSessionRequest, CreateSessionResponse, GetCheckpointResponse, GetSessionResponse,
+    ListCheckpointsQuery, ListCheckpointsResponse, ListSessionsQuery, ListSessionsResponse,
+    RequestTimeouts, SessionVisibility, StakpakApiConfig, UpdateSessionRequest,
+    UpdateSessionResponse, knowledge::AccountCacheState, models::*,
 };
 use crate::models::{
     CreateRuleBookInput, CreateRuleBookResponse, GetMyAccountResponse, ListRuleBook,
     ListRulebooksResponse, RuleBook,
 };
-use reqwest::{Response, header};
+use reqwest::{Method, RequestBuilder, Response, StatusCode, header};
 use rmcp::model::Content;
 use serde::de::DeserializeOwned;
 use serde_json::{Value, json};
+use stakai::providers::stakpak::{EXCEEDED_API_LIMIT, exceeded_api_limit_message};
+use stakpak_agent_core::{RetryConfig, resolve_retry_delay_ms};
 use stakpak_shared::models::billing::BillingResponse;
+use stakpak_shared::run_id::run_id_headers;
+use stakpak_shared::secrets::redact_secrets;
 use stakpak_shared::tls_client::{TlsClientConfig, create_tls_client};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use uuid::Uuid;
@@ -29,6 +34,8 @@ pub struct StakpakApiClient {
     pub(super) client: reqwest::Client,
     pub(super) base_url: String,
     pub(super) account_name: Arc<Mutex<AccountCacheState>>,
+    pub(super) retry: RetryConfig,
+    pub(super) timeouts: RequestTimeouts,
 }
 
 /// API error response format
@@ -50,7 +57,7 @@ impl StakpakApiClient {
             return Err("Stakpak API key is required".to_string());
         }
 
-        let mut headers = header::HeaderMap::new();
+        let mut headers = run_id_headers();
         headers.insert(
             header::AUTHORIZATION,
             header::HeaderValue::from_str(&format!("Bearer {}", config.api_key))
@@ -65,13 +72,15 @@ impl StakpakApiClient {
         let client = create_tls_client(
             TlsClientConfig::default()
                 .with_headers(headers)
-                .with_timeout(std::time::Duration::from_secs(300)),
+                .with_timeout(config.timeouts.base),
         )?;
 
         Ok(Self {
             client,
             base_url: config.api_endpoint.clone(),
             account_name: Arc::new(Mutex::new(AccountCacheState::Unknown)),
+            retry: config.retry.clone(),
+            timeouts: config.timeouts.clone(),
         })
     }
 
@@ -86,12 +95,8 @@ impl StakpakApiClient {
     ) -> Result<CreateSessionResponse, String> {
         let url = format!("{}/v1/sessions", self.base_url);
         let response = self
-            .client
-            .post(&url)
-            .json(req)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Sessions, self.client.post(&url).json(req))
+            .await?;
         self.handle_response(response).await
     }
 
@@ -103,12 +108,8 @@ impl StakpakApiClient {
     ) -> Result<CreateCheckpointResponse, String> {
         let url = format!("{}/v1/sessions/{}/checkpoints", self.base_url, session_id);
         let response = self
-            .client
-            .post(&url)
-            .json(req)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Sessions, self.client.post(&url).json(req))
+            .await?;
         self.handle_response(response).await
     }
 
@@ -119,12 +120,8 @@ impl StakpakApiClient {
     ) -> Result<ListSessionsResponse, String> {
         let url = format!("{}/v1/sessions", self.base_url);
         let response = self
-            .client
-            .get(&url)
-            .query(query)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Sessions, self.client.get(&url).query(query))
+            .await?;
         self.handle_response(response).await
     }
 
@@ -132,11 +129,8 @@ impl StakpakApiClient {
     pub async fn get_session(&self, id: Uuid) -> Result<GetSessionResponse, String> {
         let url = format!("{}/v1/sessions/{}", self.base_url, id);
         let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Sessions, self.client.get(&url))
+            .await?;
         self.handle_response(response).await
     }
 
@@ -148,12 +142,8 @@ impl StakpakApiClient {
     ) -> Result<UpdateSessionResponse, String> {
         let url = format!("{}/v1/sessions/{}", self.base_url, id);
         let response = self
-            .client
-            .patch(&url)
-            .json(req)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Sessions, self.client.patch(&url).json(req))
+            .await?;
         self.handle_response(response).await
     }
 
@@ -161,11 +151,8 @@ impl StakpakApiClient {
     pub async fn delete_session(&self, id: Uuid) -> Result<(), String> {
         let url = format!("{}/v1/sessions/{}", self.base_url, id);
         let response = self
-            .client
-            .delete(&url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Sessions, self.client.delete(&url))
+            .await?;
         self.handle_response_no_body(response).await
     }
 
@@ -177,12 +164,8 @@ impl StakpakApiClient {
     ) -> Result<ListCheckpointsResponse, String> {
         let url = format!("{}/v1/sessions/{}/checkpoints", self.base_url, session_id);
         let response = self
-            .client
-            .get(&url)
-            .query(query)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Sessions, self.client.get(&url).query(query))
+            .await?;
         self.handle_response(response).await
     }
 
@@ -190,11 +173,8 @@ impl StakpakApiClient {
     pub async fn get_checkpoint(&self, id: Uuid) -> Result<GetCheckpointResponse, String> {
         let url = format!("{}/v1/sessions/checkpoints/{}", self.base_url, id);
         let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Sessions, self.client.get(&url))
+            .await?;
         self.handle_response(response).await
     }
 
@@ -206,11 +186,8 @@ impl StakpakApiClient {
     pub async fn cancel_request(&self, request_id: &str) -> Result<(), String> {
         let url = format!("{}/v1/chat/requests/{}/cancel", self.base_url, request_id);
         let response = self
-            .client
-            .post(&url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Cancel, self.client.post(&url))
+            .await?;
         self.handle_response_no_body(response).await
     }
 
@@ -222,11 +199,8 @@ impl StakpakApiClient {
     pub async fn get_account(&self) -> Result<GetMyAccountResponse, String> {
         let url = format!("{}/v1/account", self.base_url);
         let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Account, self.client.get(&url))
+            .await?;
         self.handle_response(response).await
     }
 
@@ -234,11 +208,8 @@ impl StakpakApiClient {
     pub async fn get_billing(&self, username: &str) -> Result<BillingResponse, String> {
         let url = format!("{}/v2/{}/billing", self.base_url, username);
         let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Billing, self.client.get(&url))
+            .await?;
         self.handle_response(response).await
     }
 
@@ -250,19 +221,11 @@ impl StakpakApiClient {
     pub async fn list_rulebooks(&self) -> Result<Vec<ListRuleBook>, String> {
         let url = format!("{}/v1/rules", self.base_url);
         let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Rulebooks, self.client.get(&url))
+            .await?;
 
-        let response = self.handle_response_error(response).await?;
-        let value: Value = response.json().await.map_err(|e| e.to_string())?;
-
-        match serde_json::from_value::<ListRulebooksResponse>(value) {
-            Ok(response) => Ok(response.results),
-            Err(e) => Err(format!("Failed to deserialize rulebooks response: {}", e)),
-        }
+        let response: ListRulebooksResponse = self.handle_response(response).await?;
+        Ok(response.results)
     }
 
     /// Get a rulebook by URI
@@ -270,11 +233,8 @@ impl StakpakApiClient {
         let encoded_uri = urlencoding::encode(uri);
         let url = format!("{}/v1/rules/{}", self.base_url, encoded_uri);
         let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Rulebooks, self.client.get(&url))
+            .await?;
         self.handle_response(response).await
     }
 
@@ -285,12 +245,8 @@ impl StakpakApiClient {
     ) -> Result<CreateRuleBookResponse, String> {
         let url = format!("{}/v1/rules", self.base_url);
         let response = self
-            .client
-            .post(&url)
-            .json(input)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Rulebooks, self.client.post(&url).json(input))
+            .await?;
         self.handle_response(response).await
     }
 
@@ -299,11 +255,8 @@ impl StakpakApiClient {
         let encoded_uri = urlencoding::encode(uri);
         let url = format!("{}/v1/rules/{}", self.base_url, encoded_uri);
         let response = self
-            .client
-            .delete(&url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Rulebooks, self.client.delete(&url))
+            .await?;
         self.handle_response_no_body(response).await
     }
 
@@ -336,11 +289,8 @@ impl StakpakApiClient {
             self.base_url, checkpoint_id
         );
         let response = self
-            .client
-            .post(&url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Tools, self.client.post(&url))
+            .await?;
         self.handle_response_no_body(response).await
     }
 
@@ -395,12 +345,8 @@ impl StakpakApiClient {
         });
 
         let response = self
-            .client
-            .post(&url)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+            .send(ApiOperation::Tools, self.client.post(&url).json(&body))
+            .await?;
 
         let resp: Value = self.handle_response(response).await?;
 
@@ -408,8 +354,8 @@ impl StakpakApiClient {
         if let Some(result) = resp.get("result")
             && let Some(content) = result.get("content")
         {
-            let content: Vec<Content> =
-                serde_json::from_value(content.clone()).map_err(|e| e.to_string())?;
+            let content: Vec<Content> = serde_json::from_value(content.clone())
+                .map_err(|e| format!("Failed to decode MCP tool content: {}", e))?;
             return Ok(content);
         }
 
@@ -425,6 +371,72 @@ impl StakpakApiClient {
         Err("Invalid MCP response format".to_string())
     }
 
+    /// Send a request with the timeout for `operation`, retrying idempotent
+    /// methods on network errors, 429 and 5xx.
+    ///
+    /// `Retry-After` headers are honored but capped at the configured maximum
+    /// backoff. Non-idempotent requests (POST, PATCH) are sent exactly once.
+    async fn send(
+        &self,
+        operation: ApiOperation,
+        request: RequestBuilder,
+    ) -> Result<Response, String> {
+        let request = request
+            .timeout(self.timeouts.for_operation(operation))
+            .build()
+            .map_err(|e| e.to_string())?;
+        let max_attempts = if is_idempotent(request.method()) {
+            self.retry.max_attempts.max(1)
+        } else {
+            1
+        };
+
+        let mut attempt = 1;
+        loop {
+            let retry_request = if attempt < max_attempts {
+                request.try_clone()
+            } else {
+                None
+            };
+            let current = match retry_request {
+                Some(retry_request) => retry_request,
+                None => {
+                    return self
+                        .client
+                        .execute(request)
+                        .await
+                        .map_err(|e| e.to_string());
+                }
+            };
+
+            let headers = match self.client.execute(current).await {
+                Ok(response) if !is_retryable_status(response.status()) => return Ok(response),
+                Ok(response) => response
+                    .headers()
+                    .iter()
+                    .filter_map(|(name, value)| {
+                        Some((name.to_string(), value.to_str().ok()?.to_string()))
+                    })
+                    .collect(),
+                Err(e) if e.is_connect() || e.is_timeout() || e.is_request() => HashMap::new(),
+                Err(e) => return Err(e.to_string()),
+            };
+
+            let delay = resolve_retry_delay_ms(&headers, &self.retry, attempt, chrono::Utc::now());
+            let delay_ms = delay.delay_ms.min(self.retry.max_backoff_ms);
+            tracing::debug!(
+                "Retrying {} {} in {}ms (attempt {}/{})",
+                request.method(),
+                request.url(),
+                delay_ms,
+                attempt + 1,
+                max_attempts
+            );
+            tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+            attempt += 1;
+        }
+    }
+
     /// Handle response and parse JSON
     async fn handle_response<T: DeserializeOwned>(&self, response: Response) -> Result<T, String> {
         let response = self.handle_response_error(response).await?;
@@ -436,14 +448,7 @@ impl StakpakApiClient {
                 url, status, e
             )
         })?;
-        serde_json::from_str(&body).map_err(|e| {
-            // Truncate body to avoid flooding the error message
-            let truncated_body: String = body.chars().take(500).collect();
-            format!(
-                "Failed to decode response from {} (status {}): {} | body: {}",
-                url, status, e, truncated_body
-            )
-        })
+        serde_json::from_str(&body).map_err(|e| decode_error(&url, status, &e, &body))
     }
 
     /// Handle response without body
@@ -464,11 +469,8 @@ impl StakpakApiClient {
         // Try to parse as API error
         if let Ok(api_error) = serde_json::from_str::<ApiError>(&error_body) {
             // Special handling for API limit exceeded
-            if api_error.error.key == "EXCEEDED_API_LIMIT" {
-                return Err(format!(
-                    "{}. You can top up your billing at https://stakpak.dev/settings/billing",
-                    api_error.error.message
-                ));
+            if api_error.error.key == EXCEEDED_API_LIMIT {
+                return Err(exceeded_api_limit_message(&api_error.error.message));
             }
             return Err(api_error.error.message);
         }
@@ -477,6 +479,43 @@ impl StakpakApiClient {
     }
 }
 
+/// Longest body snippet included in a decode error
+const DECODE_ERROR_BODY_CHARS: usize = 500;
+
+/// Describe a response body that failed to deserialize.
+///
+/// Includes the serde error (which names the offending field and position)
+/// and a truncated snippet of the body with secrets redacted, so the error is
+/// actionable when captured in logs or CI output.
+pub(super) fn decode_error(
+    url: &str,
+    status: StatusCode,
+    error: &serde_json::Error,
+    body: &str,
+) -> String {
+    let redacted = redact_secrets(body, None, &HashMap::new(), false).redacted_string;
+    let mut snippet: String = redacted.chars().take(DECODE_ERROR_BODY_CHARS).collect();
+    if redacted.chars().nth(DECODE_ERROR_BODY_CHARS).is_some() {
+        snippet.push_str("...");
+    }
+    format!(
+        "Failed to decode response from {} (status {}): {} | body: {}",
+        url, status, error, snippet
+    )
+}
+
+/// Methods that are safe to resend without duplicating side effects
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
 // =============================================================================
 // Builder helpers for creating sessions and checkpoints
 // =============================================================================
@@ -520,3 +559,208 @@ impl CreateCheckpointRequest {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{
+        Json, Router,
+        http::{HeaderMap, StatusCode},
+        response::IntoResponse,
+        routing::get,
+    };
+    use serde_json::json;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// Serves `/v1/rules`, failing the first `failures` requests with a 503
+    async fn flaky_server(
+        failures: usize,
+        retry_after: Option<&'static str>,
+    ) -> (String, Arc<AtomicUsize>, tokio::task::JoinHandle<()>) {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let handler = {
+            let hits = hits.clone();
+            move || {
+                let hits = hits.clone();
+                async move {
+                    if hits.fetch_add(1, Ordering::SeqCst) < failures {
+                        let mut headers = HeaderMap::new();
+                        if let Some(retry_after) = retry_after {
+                            headers.insert("retry-after", retry_after.parse().unwrap());
+                        }
+                        (StatusCode::SERVICE_UNAVAILABLE, headers, "unavailable").into_response()
+                    } else {
+                        Json(json!({ "results": [] })).into_response()
+                    }
+                }
+            }
+        };
+        let app = Router::new().route("/v1/rules", get(handler.clone()).post(handler));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (format!("http://{addr}"), hits, server)
+    }
+
+    fn client(endpoint: &str, initial_backoff_ms: u64) -> StakpakApiClient {
+        StakpakApiClient::new(
+            &StakpakApiConfig::new("test-key")
+                .with_endpoint(endpoint)
+                .with_retry(RetryConfig {
+                    max_attempts: 3,
+                    initial_backoff_ms,
+                    max_backoff_ms: 60_000,
+                    multiplier: 2.0,
+                }),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn get_retries_transient_server_errors() {
+        let (endpoint, hits, server) = flaky_server(2, None).await;
+
+        let rulebooks = client(&endpoint, 1).list_rulebooks().await.unwrap();
+
+        assert!(rulebooks.is_empty());
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn get_gives_up_after_max_attempts() {
+        let (endpoint, hits, server) = flaky_server(usize::MAX, None).await;
+
+        let error = client(&endpoint, 1).list_rulebooks().await.unwrap_err();
+
+        assert!(error.contains("503"), "{error}");
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn get_honors_retry_after_header() {
+        let (endpoint, hits, server) = flaky_server(2, Some("0")).await;
+
+        // The exponential fallback would wait a minute; Retry-After says retry now
+        let rulebooks = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            client(&endpoint, 60_000).list_rulebooks(),
+        )
+        .await
+        .expect("Retry-After should override the backoff")
+        .unwrap();
+
+        assert!(rulebooks.is_empty());
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn post_is_not_retried() {
+        let (endpoint, hits, server) = flaky_server(2, None).await;
+
+        let result = client(&endpoint, 1)
+            .create_rulebook(&CreateRuleBookInput {
+                uri: "stakpak://test/rule.md".to_string(),
+                description: "test".to_string(),
+                content: "test".to_string(),
+                tags: vec![],
+                visibility: None,
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn account_check_respects_short_timeout_override() {
+        let app = Router::new()
+            .route(
+                "/v1/account",
+                get(|| async {
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    Json(json!({}))
+                }),
+            )
+            .route(
+                "/v1/rules",
+                get(|| async { Json(json!({ "results": [] })) }),
+            );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        let client =
+            StakpakApiClient::new(
+                &StakpakApiConfig::new("test-key")
+                    .with_endpoint(format!("http://{addr}"))
+                    .with_retry(RetryConfig {
+                        max_attempts: 1,
+                        ..RetryConfig::default()
+                    })
+                    .with_timeouts(RequestTimeouts::default().with_override(
+                        ApiOperation::Account,
+                        std::time::Duration::from_millis(100),
+                    )),
+            )
+            .unwrap();
+
+        let started = std::time::Instant::now();
+        let result = client.get_account().await;
+
+        assert!(result.is_err());
+        assert!(started.elapsed() < std::time::Duration::from_secs(2));
+        // Other operations keep the base timeout
+        assert!(client.list_rulebooks().await.unwrap().is_empty());
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn malformed_response_reports_serde_error_and_body() {
+        let app = Router::new().route(
+            "/v1/rules",
+            get(|| async { Json(json!({ "results": [{ "uri": 42 }] })) }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let error = client(&format!("http://{addr}"), 1)
+            .list_rulebooks()
+            .await
+            .unwrap_err();
+
+        assert!(error.contains("invalid type: integer `42`"), "{error}");
+        assert!(error.contains("/v1/rules"), "{error}");
+        assert!(
+            error.contains(r#"body: {"results":[{"uri":42}]}"#),
+            "{error}"
+        );
+        server.abort();
+    }
+
+    #[test]
+    fn decode_error_redacts_and_truncates_body() {
+        let api_key = ["abc123def456", "ghi789jkl012", "mno345pqr678"].concat();
+        let body = format!("export API_KEY={api_key}\n{}", "x".repeat(1_000));
+        let error = serde_json::from_str::<Value>(&body).unwrap_err();
+
+        let message = decode_error("http://localhost/v1/account", StatusCode::OK, &error, &body);
+
+        assert!(!message.contains(&api_key), "{message}");
+        assert!(message.contains("[REDACTED_"), "{message}");
+        assert!(message.ends_with("..."), "{message}");
+    }
+}
@@ -7,7 +7,7 @@
 
 mod cache;
 
-use super::client::{ApiError, StakpakApiClient};
+use super::client::{ApiError, StakpakApiClient, decode_error};
 use super::models::*;
 use crate::models::GetMyAccountResponse;
 use reqwest::{Response, StatusCode, header};
@@ -431,14 +431,8 @@ impl StakpakApiClient {
                     url, status, e
                 ),
             })?;
-        serde_json::from_str(&body).map_err(|e| {
-            let truncated_body: String = body.chars().take(500).collect();
-            KnowledgeApiError::Transport {
-                message: format!(
-                    "Failed to decode response from {} (status {}): {} | body: {}",
-                    url, status, e, truncated_body
-                ),
-            }
+        serde_json::from_str(&body).map_err(|e| KnowledgeApiError::Transport {
+            message: decode_error(&url, status, &e, &body),
         })
     }
 
@@ -14,6 +14,9 @@ pub mod storage;
 pub use client::StakpakApiClient;
 pub use knowledge::KnowledgeApiError;
 pub use models::*;
+use stakpak_agent_core::RetryConfig;
+use std::collections::HashMap;
+use std::time::Duration;
 
 /// Configuration for StakpakApiClient
 #[derive(Clone, Debug)]
@@ -22,6 +25,10 @@ pub struct StakpakApiConfig {
     pub api_key: String,
     /// API endpoint URL (default: https://apiv2.stakpak.dev)
     pub api_endpoint: String,
+    /// Retry policy for idempotent requests (GET, PUT, DELETE)
+    pub retry: RetryConfig,
+    /// Base request timeout and per-operation overrides
+    pub timeouts: RequestTimeouts,
 }
 
 impl StakpakApiConfig {
@@ -30,6 +37,8 @@ impl StakpakApiConfig {
         Self {
             api_key: api_key.into(),
             api_endpoint: "https://apiv2.stakpak.dev".to_string(),
+            retry: default_retry_config(),
+            timeouts: RequestTimeouts::default(),
         }
     }
 
@@ -38,6 +47,82 @@ impl StakpakApiConfig {
         self.api_endpoint = endpoint.into();
         self
     }
+
+    /// Set the retry policy for idempotent requests
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Set the request timeouts
+    pub fn with_timeouts(mut self, timeouts: RequestTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+}
+
+/// Short backoff so transient failures don't stall interactive CLI commands
+fn default_retry_config() -> RetryConfig {
+    RetryConfig {
+        max_attempts: 3,
+        initial_backoff_ms: 500,
+        max_backoff_ms: 5_000,
+        multiplier: 2.0,
+    }
+}
+
+/// Groups of StakpakApiClient calls that can override the base timeout
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ApiOperation {
+    Sessions,
+    Account,
+    Billing,
+    Rulebooks,
+    Tools,
+    Cancel,
+}
+
+/// Request timeouts for StakpakApiClient
+///
+/// The account check defaults to a short timeout so startup fails fast when
+/// the API is unreachable; everything else uses the base timeout.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestTimeouts {
+    /// Timeout for operations without an override
+    pub base: Duration,
+    /// Per-operation overrides of `base`
+    pub overrides: HashMap<ApiOperation, Duration>,
+}
+
+impl RequestTimeouts {
+    pub const DEFAULT_BASE: Duration = Duration::from_secs(300);
+    pub const DEFAULT_ACCOUNT: Duration = Duration::from_secs(15);
+
+    /// Set the base timeout
+    pub fn with_base(mut self, timeout: Duration) -> Self {
+        self.base = timeout;
+        self
+    }
+
+    /// Override the timeout for one operation
+    pub fn with_override(mut self, operation: ApiOperation, timeout: Duration) -> Self {
+        self.overrides.insert(operation, timeout);
+        self
+    }
+
+    /// Timeout that applies to `operation`
+    pub fn for_operation(&self, operation: ApiOperation) -> Duration {
+        self.overrides.get(&operation).copied().unwrap_or(self.base)
+    }
+}
+
+impl Default for RequestTimeouts {
+    fn default() -> Self {
+        Self {
+            base: Self::DEFAULT_BASE,
+            overrides: HashMap::from([(ApiOperation::Account, Self::DEFAULT_ACCOUNT)]),
+        }
+    }
 }
 
 impl Default for StakpakApiConfig {
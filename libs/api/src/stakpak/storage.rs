@@ -30,11 +30,9 @@ impl StakpakStorage {
         api_endpoint: &str,
         profile: Option<String>,
     ) -> Result<Self, StorageError> {
-        let client = StakpakApiClient::new(&StakpakApiConfig {
-            api_key: api_key.to_string(),
-            api_endpoint: api_endpoint.to_string(),
-        })
-        .map_err(StorageError::Connection)?;
+        let client =
+            StakpakApiClient::new(&StakpakApiConfig::new(api_key).with_endpoint(api_endpoint))
+                .map_err(StorageError::Connection)?;
 
         Ok(Self {
             client,
@@ -6,7 +6,11 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use stakpak_shared::models::integrations::openai::ChatMessage;
+use stakpak_shared::models::integrations::openai::{
+    ChatMessage, Role, ToolCall, ToolCallResultStatus,
+};
+use stakpak_shared::secrets::redact_secrets;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 // Re-export implementations
@@ -92,6 +96,53 @@ pub trait SessionStorage: Send + Sync {
     async fn get_session_stats(&self, _session_id: Uuid) -> Result<SessionStats, StorageError> {
         Ok(SessionStats::default())
     }
+
+    // =========================================================================
+    // Recovery Operations (optional)
+    // =========================================================================
+
+    /// Get the ways to recover from a failed active checkpoint
+    async fn get_recovery_options(
+        &self,
+        _session_id: Uuid,
+    ) -> Result<RecoveryOptions, StorageError> {
+        Err(StorageError::InvalidRequest(
+            "Recovery is not supported by this storage backend".to_string(),
+        ))
+    }
+
+    /// Apply a recovery action, returning the new active checkpoint
+    async fn submit_recovery_action(
+        &self,
+        _session_id: Uuid,
+        _request: &RecoveryActionRequest,
+    ) -> Result<Checkpoint, StorageError> {
+        Err(StorageError::InvalidRequest(
+            "Recovery is not supported by this storage backend".to_string(),
+        ))
+    }
+
+    // =========================================================================
+    // Tool Audit Operations (optional)
+    // =========================================================================
+
+    /// Append an entry to a session's tool audit log
+    async fn record_tool_audit(
+        &self,
+        _session_id: Uuid,
+        _request: &RecordToolAuditRequest,
+    ) -> Result<ToolAuditEntry, StorageError> {
+        Err(StorageError::InvalidRequest(
+            "Tool audit is not supported by this storage backend".to_string(),
+        ))
+    }
+
+    /// Get a session's tool audit log, oldest entry first
+    async fn get_tool_audit(&self, _session_id: Uuid) -> Result<Vec<ToolAuditEntry>, StorageError> {
+        Err(StorageError::InvalidRequest(
+            "Tool audit is not supported by this storage backend".to_string(),
+        ))
+    }
 }
 
 /// Box wrapper for dynamic dispatch
@@ -291,6 +342,229 @@ pub struct CheckpointState {
     pub metadata: Option<serde_json::Value>,
 }
 
+impl CheckpointState {
+    /// Why the run behind this state failed, if it did: the model stopped
+    /// with an error, or tool calls were left without results
+    pub fn failure_reason(&self) -> Option<String> {
+        let last_assistant = self
+            .messages
+            .iter()
+            .rposition(|message| message.role == Role::Assistant)?;
+        let assistant = self.messages.get(last_assistant)?;
+
+        if assistant.finish_reason.as_deref() == Some("error") {
+            return Some("The model stopped with an error".to_string());
+        }
+
+        let answered = |id: &str| {
+            self.messages
+                .iter()
+                .skip(last_assistant + 1)
+                .any(|message| message.tool_call_id.as_deref() == Some(id))
+        };
+        let unanswered = assistant
+            .tool_calls
+            .iter()
+            .flatten()
+            .filter(|tool_call| !answered(&tool_call.id))
+            .count();
+        (unanswered > 0).then(|| format!("{} tool call(s) were left without results", unanswered))
+    }
+}
+
+// =============================================================================
+// Recovery Types
+// =============================================================================
+
+/// How to recover from a failed checkpoint
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryActionKind {
+    /// Drop the failed turn and run the last user message again
+    Retry,
+    /// Return to the state of the checkpoint before the failure
+    Rollback,
+    /// Replace the last user message and run from there
+    EditAndContinue,
+}
+
+/// One recovery action available for a failed checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecoveryOption {
+    pub kind: RecoveryActionKind,
+    pub description: String,
+    /// Checkpoint whose state the action starts from
+    pub checkpoint_id: Uuid,
+}
+
+/// Recovery actions available for a session's failed active checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecoveryOptions {
+    pub session_id: Uuid,
+    pub failed_checkpoint_id: Uuid,
+    pub reason: String,
+    pub options: Vec<RecoveryOption>,
+}
+
+/// Request to apply a recovery action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryActionRequest {
+    pub kind: RecoveryActionKind,
+    /// Replacement user message for `EditAndContinue`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl RecoveryActionRequest {
+    pub fn new(kind: RecoveryActionKind) -> Self {
+        Self {
+            kind,
+            message: None,
+        }
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+// =============================================================================
+// Tool Audit Types
+// =============================================================================
+
+/// Longest tool output kept in an audit entry's summary, in characters
+pub const TOOL_AUDIT_SUMMARY_CHARS: usize = 500;
+
+/// Whether a tool call was allowed to run
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolApprovalDecision {
+    /// Approved by the user or an auto-approve policy
+    Approved,
+    /// Rejected by the user
+    Rejected,
+}
+
+impl std::fmt::Display for ToolApprovalDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ToolApprovalDecision::Approved => write!(f, "approved"),
+            ToolApprovalDecision::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+/// How an audited tool call ended
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolAuditStatus {
+    Succeeded,
+    Failed,
+    Cancelled,
+    /// Stopped by a `BeforeToolExecution` hook
+    Blocked,
+    /// Never ran because it was rejected
+    NotRun,
+}
+
+impl std::fmt::Display for ToolAuditStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ToolAuditStatus::Succeeded => write!(f, "succeeded"),
+            ToolAuditStatus::Failed => write!(f, "failed"),
+            ToolAuditStatus::Cancelled => write!(f, "cancelled"),
+            ToolAuditStatus::Blocked => write!(f, "blocked"),
+            ToolAuditStatus::NotRun => write!(f, "not_run"),
+        }
+    }
+}
+
+impl From<ToolCallResultStatus> for ToolAuditStatus {
+    fn from(status: ToolCallResultStatus) -> Self {
+        match status {
+            ToolCallResultStatus::Success => ToolAuditStatus::Succeeded,
+            ToolCallResultStatus::Error => ToolAuditStatus::Failed,
+            ToolCallResultStatus::Cancelled => ToolAuditStatus::Cancelled,
+        }
+    }
+}
+
+/// One tool call in a session's audit log
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolAuditEntry {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub tool_call_id: String,
+    pub tool_name: String,
+    /// Tool arguments with secrets redacted
+    pub arguments: String,
+    pub decision: ToolApprovalDecision,
+    pub status: ToolAuditStatus,
+    /// Start of the tool output or error, with secrets redacted
+    pub summary: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request to append a tool call to a session's audit log
+///
+/// The constructors redact secrets from the arguments and output, so raw
+/// values never reach the store.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordToolAuditRequest {
+    pub tool_call_id: String,
+    pub tool_name: String,
+    pub arguments: String,
+    pub decision: ToolApprovalDecision,
+    pub status: ToolAuditStatus,
+    pub summary: Option<String>,
+}
+
+impl RecordToolAuditRequest {
+    /// An approved tool call that ended with `status` and produced `output`
+    pub fn approved(tool_call: &ToolCall, status: ToolAuditStatus, output: &str) -> Self {
+        Self {
+            tool_call_id: tool_call.id.clone(),
+            tool_name: tool_call.function.name.clone(),
+            arguments: redact(&tool_call.function.arguments),
+            decision: ToolApprovalDecision::Approved,
+            status,
+            summary: audit_summary(output),
+        }
+    }
+
+    /// A tool call the user rejected
+    pub fn rejected(tool_call: &ToolCall) -> Self {
+        Self {
+            tool_call_id: tool_call.id.clone(),
+            tool_name: tool_call.function.name.clone(),
+            arguments: redact(&tool_call.function.arguments),
+            decision: ToolApprovalDecision::Rejected,
+            status: ToolAuditStatus::NotRun,
+            summary: None,
+        }
+    }
+}
+
+fn redact(text: &str) -> String {
+    redact_secrets(text, None, &HashMap::new(), false).redacted_string
+}
+
+/// Output truncated to [`TOOL_AUDIT_SUMMARY_CHARS`] and then redacted, so
+/// large outputs are never scanned in full. `None` when empty
+fn audit_summary(output: &str) -> Option<String> {
+    let output = output.trim();
+    if output.is_empty() {
+        return None;
+    }
+    let truncated: String = output.chars().take(TOOL_AUDIT_SUMMARY_CHARS).collect();
+    let mut summary = redact(&truncated);
+    if output.chars().nth(TOOL_AUDIT_SUMMARY_CHARS).is_some() {
+        summary.push_str("...");
+    }
+    Some(summary)
+}
+
 // =============================================================================
 // Request Types
 // =============================================================================
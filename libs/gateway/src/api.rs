@@ -7,13 +7,14 @@ use axum::{
     response::IntoResponse,
     routing::{get, post},
 };
+use bytes::Bytes;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use tokio::sync::{RwLock, mpsc};
 use tracing::warn;
 
 use crate::{
-    channels::Channel,
+    channels::{Channel, WebhookRequest},
     client::StakpakClient,
     dispatcher::Dispatcher,
     router::{RouterConfig, resolve_routing_key},
@@ -179,11 +180,23 @@ pub fn router(state: Arc<GatewayApiState>) -> Router {
         )
         .route(
             "/sessions/{session_id}",
-            get(move |headers: HeaderMap, Path(session_id): Path<String>| {
+            get({
                 let state = state.clone();
-                async move { session_status_handler(state, headers, session_id).await }
+                move |headers: HeaderMap, Path(session_id): Path<String>| {
+                    let state = state.clone();
+                    async move { session_status_handler(state, headers, session_id).await }
+                }
             }),
         )
+        .route(
+            "/webhooks/{channel}",
+            post(
+                move |headers: HeaderMap, Path(channel): Path<String>, body: Bytes| {
+                    let state = state.clone();
+                    async move { webhook_handler(state, channel, headers, body).await }
+                },
+            ),
+        )
 }
 
 fn require_auth(state: &GatewayApiState, headers: &HeaderMap) -> Option<axum::response::Response> {
@@ -210,6 +223,56 @@ fn require_auth(state: &GatewayApiState, headers: &HeaderMap) -> Option<axum::re
     )
 }
 
+/// Events a platform pushes over HTTP. Not behind the bearer token: each
+/// channel authenticates its platform's requests itself.
+async fn webhook_handler(
+    state: Arc<GatewayApiState>,
+    channel_name: String,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(channel) = state.channels.get(&channel_name) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiError {
+                error: "channel_not_found".to_string(),
+                message: format!("Channel '{}' is not connected", channel_name),
+            }),
+        )
+            .into_response();
+    };
+
+    let inbound_tx = state.inbound_tx.read().await.clone();
+    let Some(inbound_tx) = inbound_tx else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiError {
+                error: "gateway_not_ready".to_string(),
+                message: "Gateway runtime is not ready to receive events".to_string(),
+            }),
+        )
+            .into_response();
+    };
+
+    match channel
+        .handle_webhook(WebhookRequest { headers, body }, &inbound_tx)
+        .await
+    {
+        Ok(response) => (response.status, response.body).into_response(),
+        Err(error) => {
+            warn!(channel = %channel_name, error = %error, "webhook request failed");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError {
+                    error: "webhook_rejected".to_string(),
+                    message: error.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
 async fn send_handler(
     state: Arc<GatewayApiState>,
     headers: HeaderMap,
@@ -875,8 +938,9 @@ mod tests {
     use super::{
         CallerContextInput, GatewayApiState, GatewaySendRequest, InteractiveOptions,
         build_interactive_prompt, extract_check_output, render_title, send_handler,
-        validate_interactive_options,
+        validate_interactive_options, webhook_handler,
     };
+    use crate::channels::slack::SlackChannel;
     use crate::channels::{Channel, ChannelTestResult};
     use crate::client::StakpakClient;
     use crate::config::ApprovalMode;
@@ -887,8 +951,10 @@ mod tests {
     use crate::types::{ChannelId, InboundMessage, OutboundReply};
     use anyhow::Result;
     use async_trait::async_trait;
-    use axum::http::{HeaderMap, StatusCode};
+    use axum::http::{HeaderMap, HeaderValue, StatusCode};
     use axum::response::IntoResponse;
+    use bytes::Bytes;
+    use chrono::Utc;
     use std::collections::HashMap;
     use std::sync::Arc;
     use std::sync::atomic::{AtomicUsize, Ordering};
@@ -942,14 +1008,7 @@ mod tests {
         }
     }
 
-    #[tokio::test]
-    async fn interactive_request_without_auth_is_rejected_before_delivery() {
-        let send_count = Arc::new(AtomicUsize::new(0));
-        let channel_impl = Arc::new(MockChannel::new("slack", send_count.clone()));
-
-        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
-        channels.insert("slack".to_string(), channel_impl);
-
+    async fn test_state(channels: HashMap<String, Arc<dyn Channel>>) -> Arc<GatewayApiState> {
         let store = Arc::new(
             GatewayStore::open_in_memory()
                 .await
@@ -969,7 +1028,7 @@ mod tests {
             "{channel}:{chat_type}:{chat_id}".to_string(),
         ));
 
-        let state = Arc::new(GatewayApiState {
+        Arc::new(GatewayApiState {
             channels,
             store,
             started_at: Instant::now(),
@@ -980,7 +1039,17 @@ mod tests {
             router_config: RouterConfig::default(),
             title_template: "{channel}:{chat_type}:{chat_id}".to_string(),
             inbound_tx: Arc::new(RwLock::new(None)),
-        });
+        })
+    }
+
+    #[tokio::test]
+    async fn interactive_request_without_auth_is_rejected_before_delivery() {
+        let send_count = Arc::new(AtomicUsize::new(0));
+        let channel_impl = Arc::new(MockChannel::new("slack", send_count.clone()));
+
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("slack".to_string(), channel_impl);
+        let state = test_state(channels).await;
 
         let request = GatewaySendRequest {
             channel: "slack".to_string(),
@@ -1005,6 +1074,32 @@ mod tests {
         assert_eq!(send_count.load(Ordering::SeqCst), 0);
     }
 
+    #[tokio::test]
+    async fn webhook_with_forged_slack_signature_is_unauthorized() {
+        let slack = SlackChannel::new("xoxb-token".to_string(), String::new())
+            .with_signing_secret(Some("secret".to_string()));
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("slack".to_string(), Arc::new(slack));
+        let state = test_state(channels).await;
+        let (inbound_tx, mut inbound_rx) = mpsc::channel(1);
+        *state.inbound_tx.write().await = Some(inbound_tx);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-slack-request-timestamp",
+            HeaderValue::from_str(&Utc::now().timestamp().to_string()).unwrap(),
+        );
+        headers.insert("x-slack-signature", HeaderValue::from_static("v0=forged"));
+        let body = Bytes::from_static(br#"{"type":"url_verification","challenge":"c-123"}"#);
+
+        let response = webhook_handler(state, "slack".to_string(), headers, body)
+            .await
+            .into_response();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(inbound_rx.try_recv().is_err());
+    }
+
     #[test]
     fn extract_check_output_reads_context_field() {
         let context = serde_json::json!({"check_output": "disk at 91%"});
@@ -0,0 +1,505 @@
+use std::fmt;
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use serde::{Deserialize, Serialize};
+use stakai::{ContentPart, Message, MessageContent, Role};
+use tracing::warn;
+
+use crate::types::MediaAttachment;
+
+/// Metadata key adapters use to pass skipped attachments to the dispatcher.
+pub const SKIPPED_ATTACHMENTS_KEY: &str = "skipped_attachments";
+
+pub const DEFAULT_MAX_ATTACHMENT_BYTES: u64 = 10 * 1024 * 1024;
+
+pub fn default_allowed_attachment_types() -> Vec<String> {
+    ["image/*", "text/*", "application/pdf", "application/json"]
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Limits on the files users attach to inbound messages.
+///
+/// Allowed types are MIME types, optionally with a `*` subtype (`image/*`);
+/// `*/*` accepts every type and an empty list accepts none.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttachmentPolicy {
+    pub max_bytes: u64,
+    pub allowed_types: Vec<String>,
+}
+
+impl Default for AttachmentPolicy {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_ATTACHMENT_BYTES,
+            allowed_types: default_allowed_attachment_types(),
+        }
+    }
+}
+
+/// A file attached to a platform message, known by its metadata until it is
+/// downloaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteAttachment {
+    pub url: String,
+    pub filename: Option<String>,
+    pub mime_type: String,
+    /// Size reported by the platform, checked before downloading
+    pub size: Option<u64>,
+}
+
+/// Why an attachment was dropped from an inbound message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum AttachmentRejection {
+    TooLarge {
+        name: String,
+        size: u64,
+        max_bytes: u64,
+    },
+    TypeNotAllowed {
+        name: String,
+        mime_type: String,
+    },
+    DownloadFailed {
+        name: String,
+    },
+}
+
+impl fmt::Display for AttachmentRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLarge {
+                name,
+                size,
+                max_bytes,
+            } => write!(
+                f,
+                "{name} is {}, over the {} limit",
+                format_size(*size),
+                format_size(*max_bytes)
+            ),
+            Self::TypeNotAllowed { name, mime_type } => {
+                write!(
+                    f,
+                    "{name} has a file type that is not accepted ({mime_type})"
+                )
+            }
+            Self::DownloadFailed { name } => write!(f, "{name} could not be downloaded"),
+        }
+    }
+}
+
+impl AttachmentPolicy {
+    pub fn check(&self, attachment: &MediaAttachment) -> Result<(), AttachmentRejection> {
+        self.check_file(
+            attachment.filename.as_deref(),
+            &attachment.mime_type,
+            Some(attachment.data.len() as u64),
+        )
+    }
+
+    /// Check a file by the metadata the platform reports, before fetching it.
+    pub fn check_remote(&self, remote: &RemoteAttachment) -> Result<(), AttachmentRejection> {
+        self.check_file(remote.filename.as_deref(), &remote.mime_type, remote.size)
+    }
+
+    fn check_file(
+        &self,
+        filename: Option<&str>,
+        mime_type: &str,
+        size: Option<u64>,
+    ) -> Result<(), AttachmentRejection> {
+        let name = attachment_name(filename);
+
+        if !self.allows_type(mime_type) {
+            return Err(AttachmentRejection::TypeNotAllowed {
+                name,
+                mime_type: mime_type.to_string(),
+            });
+        }
+
+        if let Some(size) = size
+            && size > self.max_bytes
+        {
+            return Err(AttachmentRejection::TooLarge {
+                name,
+                size,
+                max_bytes: self.max_bytes,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Split remote files into the ones worth downloading and the rejected.
+    pub fn screen(
+        &self,
+        remotes: Vec<RemoteAttachment>,
+    ) -> (Vec<RemoteAttachment>, Vec<AttachmentRejection>) {
+        let mut rejections = Vec::new();
+        let accepted = remotes
+            .into_iter()
+            .filter(|remote| match self.check_remote(remote) {
+                Ok(()) => true,
+                Err(rejection) => {
+                    rejections.push(rejection);
+                    false
+                }
+            })
+            .collect();
+        (accepted, rejections)
+    }
+
+    /// Download the remote files the policy accepts.
+    ///
+    /// Files rejected by their reported metadata are never requested, and a
+    /// download that grows past `max_bytes` is abandoned there, so a platform
+    /// under-reporting a size cannot make the gateway buffer a huge file.
+    pub async fn download(
+        &self,
+        remotes: Vec<RemoteAttachment>,
+        request: impl Fn(&str) -> reqwest::RequestBuilder,
+    ) -> (Vec<MediaAttachment>, Vec<AttachmentRejection>) {
+        let (accepted, mut rejections) = self.screen(remotes);
+        let mut media = Vec::with_capacity(accepted.len());
+
+        for remote in accepted {
+            match self.download_one(&remote, &request).await {
+                Ok(data) => media.push(MediaAttachment {
+                    mime_type: remote.mime_type,
+                    data,
+                    filename: remote.filename,
+                }),
+                Err(rejection) => rejections.push(rejection),
+            }
+        }
+
+        (media, rejections)
+    }
+
+    async fn download_one(
+        &self,
+        remote: &RemoteAttachment,
+        request: &impl Fn(&str) -> reqwest::RequestBuilder,
+    ) -> Result<Vec<u8>, AttachmentRejection> {
+        let name = attachment_name(remote.filename.as_deref());
+        let download_failed = |error: &dyn fmt::Display| {
+            warn!(attachment = %name, error = %error, "attachment download failed");
+            AttachmentRejection::DownloadFailed { name: name.clone() }
+        };
+        let too_large = |size: u64| AttachmentRejection::TooLarge {
+            name: name.clone(),
+            size,
+            max_bytes: self.max_bytes,
+        };
+
+        let mut response = request(&remote.url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|error| download_failed(&error))?;
+
+        if let Some(size) = response.content_length()
+            && size > self.max_bytes
+        {
+            return Err(too_large(size));
+        }
+
+        let mut data = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|error| download_failed(&error))?
+        {
+            data.extend_from_slice(&chunk);
+            if data.len() as u64 > self.max_bytes {
+                return Err(too_large(data.len() as u64));
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Drop the attachments the policy does not accept, returning why each
+    /// one was dropped.
+    pub fn filter(&self, media: &mut Vec<MediaAttachment>) -> Vec<AttachmentRejection> {
+        let mut rejections = Vec::new();
+        media.retain(|attachment| match self.check(attachment) {
+            Ok(()) => true,
+            Err(rejection) => {
+                rejections.push(rejection);
+                false
+            }
+        });
+        rejections
+    }
+
+    fn allows_type(&self, mime_type: &str) -> bool {
+        let essence = mime_essence(mime_type);
+        let Some((kind, _)) = essence.split_once('/') else {
+            return false;
+        };
+
+        self.allowed_types.iter().any(|allowed| {
+            let allowed = allowed.trim().to_ascii_lowercase();
+            match allowed.split_once('/') {
+                Some(("*", "*")) => true,
+                Some((allowed_kind, "*")) => allowed_kind == kind,
+                _ => allowed == essence,
+            }
+        })
+    }
+}
+
+/// Record skipped attachments on an inbound message's metadata.
+pub fn record_skipped(metadata: &mut serde_json::Value, rejections: &[AttachmentRejection]) {
+    if rejections.is_empty() {
+        return;
+    }
+    if let (Some(object), Ok(value)) = (metadata.as_object_mut(), serde_json::to_value(rejections))
+    {
+        object.insert(SKIPPED_ATTACHMENTS_KEY.to_string(), value);
+    }
+}
+
+/// Take the attachments an adapter skipped off an inbound message's metadata.
+pub fn take_skipped(metadata: &mut serde_json::Value) -> Vec<AttachmentRejection> {
+    metadata
+        .as_object_mut()
+        .and_then(|object| object.remove(SKIPPED_ATTACHMENTS_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// The user message a run starts from: the text, with images as image parts
+/// and text files inlined. Other accepted files are listed by name.
+pub fn user_message(text: &str, media: &[MediaAttachment]) -> Message {
+    if media.is_empty() {
+        return Message::new(Role::User, text.to_string());
+    }
+
+    let mut text = text.to_string();
+    let mut images = Vec::new();
+    for attachment in media {
+        let name = attachment_name(attachment.filename.as_deref());
+        let essence = mime_essence(&attachment.mime_type);
+        if essence.starts_with("image/") {
+            images.push(ContentPart::image(format!(
+                "data:{essence};base64,{}",
+                BASE64_STANDARD.encode(&attachment.data)
+            )));
+        } else if let Ok(content) = std::str::from_utf8(&attachment.data)
+            && (essence.starts_with("text/") || essence == "application/json")
+        {
+            text.push_str(&format!("\n\nAttached file {name}:\n```\n{content}\n```"));
+        } else {
+            text.push_str(&format!(
+                "\n\nAttached file {name} ({essence}) cannot be shown inline."
+            ));
+        }
+    }
+
+    let mut parts = vec![ContentPart::text(text)];
+    parts.extend(images);
+    Message::new(Role::User, MessageContent::Parts(parts))
+}
+
+fn mime_essence(mime_type: &str) -> String {
+    // Ignore parameters such as `; charset=utf-8`.
+    mime_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+fn attachment_name(filename: Option<&str>) -> String {
+    filename.unwrap_or("attachment").to_string()
+}
+
+/// The channel message telling the user which attachments were skipped.
+pub fn render_rejection_notice(rejections: &[AttachmentRejection]) -> String {
+    let mut notice = String::from("📎 Some attachments were skipped:");
+    for rejection in rejections {
+        notice.push_str(&format!("\n• {rejection}"));
+    }
+    notice
+}
+
+fn format_size(bytes: u64) -> String {
+    const MIB: f64 = 1024.0 * 1024.0;
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / MIB)
+    } else {
+        format!("{} KB", bytes.div_ceil(1024))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AttachmentPolicy, AttachmentRejection, RemoteAttachment, record_skipped,
+        render_rejection_notice, take_skipped, user_message,
+    };
+    use crate::types::MediaAttachment;
+    use stakai::{ContentPart, MessageContent};
+
+    fn attachment(name: &str, mime_type: &str, size: usize) -> MediaAttachment {
+        MediaAttachment {
+            mime_type: mime_type.to_string(),
+            data: vec![0; size],
+            filename: Some(name.to_string()),
+        }
+    }
+
+    #[test]
+    fn rejects_oversized_attachment() {
+        let policy = AttachmentPolicy {
+            max_bytes: 1024 * 1024,
+            ..AttachmentPolicy::default()
+        };
+
+        let rejection = policy
+            .check(&attachment("screenshot.png", "image/png", 3 * 1024 * 1024))
+            .unwrap_err();
+        assert!(matches!(rejection, AttachmentRejection::TooLarge { .. }));
+        assert_eq!(
+            rejection.to_string(),
+            "screenshot.png is 3.0 MB, over the 1.0 MB limit"
+        );
+        assert!(
+            policy
+                .check(&attachment("small.png", "image/png", 1024))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn rejects_disallowed_type() {
+        let policy = AttachmentPolicy::default();
+
+        let rejection = policy
+            .check(&attachment("payload.exe", "application/x-msdownload", 10))
+            .unwrap_err();
+        assert_eq!(
+            rejection,
+            AttachmentRejection::TypeNotAllowed {
+                name: "payload.exe".to_string(),
+                mime_type: "application/x-msdownload".to_string(),
+            }
+        );
+        assert!(
+            policy
+                .check(&attachment("notes.txt", "text/plain; charset=utf-8", 10))
+                .is_ok()
+        );
+        assert!(
+            policy
+                .check(&attachment("report.pdf", "Application/PDF", 10))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn wildcard_and_empty_allowlists() {
+        let any = AttachmentPolicy {
+            allowed_types: vec!["*/*".to_string()],
+            ..AttachmentPolicy::default()
+        };
+        assert!(
+            any.check(&attachment("a.zip", "application/zip", 10))
+                .is_ok()
+        );
+
+        let none = AttachmentPolicy {
+            allowed_types: Vec::new(),
+            ..AttachmentPolicy::default()
+        };
+        assert!(none.check(&attachment("a.png", "image/png", 10)).is_err());
+    }
+
+    #[test]
+    fn filter_keeps_accepted_attachments_and_explains_the_rest() {
+        let policy = AttachmentPolicy::default();
+        let mut media = vec![
+            attachment("diagram.png", "image/png", 10),
+            attachment("archive.zip", "application/zip", 10),
+        ];
+
+        let rejections = policy.filter(&mut media);
+        assert_eq!(media.len(), 1);
+        assert_eq!(media[0].filename.as_deref(), Some("diagram.png"));
+        assert_eq!(
+            render_rejection_notice(&rejections),
+            "📎 Some attachments were skipped:\n• archive.zip has a file type that is not accepted (application/zip)"
+        );
+    }
+
+    #[test]
+    fn remote_files_are_checked_by_reported_size() {
+        let policy = AttachmentPolicy {
+            max_bytes: 1024,
+            ..AttachmentPolicy::default()
+        };
+        let remote = |size| RemoteAttachment {
+            url: "https://files.example.com/a.png".to_string(),
+            filename: Some("a.png".to_string()),
+            mime_type: "image/png".to_string(),
+            size,
+        };
+
+        let (accepted, rejections) = policy.screen(vec![remote(Some(4096)), remote(None)]);
+        assert_eq!(accepted, vec![remote(None)]);
+        assert!(matches!(
+            rejections.as_slice(),
+            [AttachmentRejection::TooLarge { size: 4096, .. }]
+        ));
+    }
+
+    #[test]
+    fn skipped_attachments_round_trip_through_metadata() {
+        let rejections = vec![AttachmentRejection::DownloadFailed {
+            name: "a.png".to_string(),
+        }];
+        let mut metadata = serde_json::json!({"channel": "C1"});
+
+        record_skipped(&mut metadata, &rejections);
+        assert_eq!(take_skipped(&mut metadata), rejections);
+        assert_eq!(metadata, serde_json::json!({"channel": "C1"}));
+    }
+
+    #[test]
+    fn user_message_carries_images_and_inlines_text_files() {
+        let message = user_message(
+            "what broke?",
+            &[
+                MediaAttachment {
+                    mime_type: "image/png".to_string(),
+                    data: vec![1, 2, 3],
+                    filename: Some("graph.png".to_string()),
+                },
+                MediaAttachment {
+                    mime_type: "text/plain; charset=utf-8".to_string(),
+                    data: b"disk full".to_vec(),
+                    filename: Some("log.txt".to_string()),
+                },
+            ],
+        );
+
+        let MessageContent::Parts(parts) = message.content else {
+            panic!("expected content parts");
+        };
+        assert!(matches!(
+            &parts[0],
+            ContentPart::Text { text, .. }
+                if text.starts_with("what broke?") && text.contains("log.txt:\n```\ndisk full")
+        ));
+        assert!(matches!(
+            &parts[1],
+            ContentPart::Image { url, .. } if url == "data:image/png;base64,AQID"
+        ));
+    }
+}
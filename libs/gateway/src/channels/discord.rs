@@ -12,6 +12,7 @@ use tokio_util::sync::CancellationToken;
 use tracing::{error, warn};
 
 use crate::{
+    attachments::{AttachmentPolicy, RemoteAttachment, record_skipped},
     channels::{ApprovalButton, ButtonStyle, Channel, ChannelTestResult, parse_approval_callback},
     chunking::chunk_text,
     types::{ChannelId, ChatType, InboundMessage, OutboundReply, PeerId},
@@ -46,6 +47,7 @@ pub struct DiscordChannel {
     http: reqwest::Client,
     bot_user_id: Mutex<Option<String>>,
     channel_cache: Mutex<HashMap<String, DiscordChannelMeta>>,
+    attachment_policy: AttachmentPolicy,
 }
 
 #[derive(Debug, Clone)]
@@ -62,9 +64,17 @@ impl DiscordChannel {
             http: reqwest::Client::new(),
             bot_user_id: Mutex::new(None),
             channel_cache: Mutex::new(HashMap::new()),
+            attachment_policy: AttachmentPolicy::default(),
         }
     }
 
+    /// Limits on the files users attach; larger or disallowed files are never
+    /// downloaded.
+    pub fn with_attachment_policy(mut self, attachment_policy: AttachmentPolicy) -> Self {
+        self.attachment_policy = attachment_policy;
+        self
+    }
+
     fn auth_header(&self) -> String {
         format!("Bot {}", self.token)
     }
@@ -263,6 +273,31 @@ impl DiscordChannel {
         }
     }
 
+    async fn trigger_typing(&self, channel_id: &str) -> Result<()> {
+        loop {
+            let response = self
+                .http
+                .post(format!(
+                    "https://discord.com/api/v10/channels/{channel_id}/typing"
+                ))
+                .header("Authorization", self.auth_header())
+                .send()
+                .await
+                .context("discord typing request failed")?;
+
+            let Some(response) = Self::response_or_retry_after_rate_limit(response).await else {
+                continue;
+            };
+
+            if !response.status().is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(anyhow!("discord typing failed: {body}"));
+            }
+
+            return Ok(());
+        }
+    }
+
     async fn acknowledge_interaction(
         &self,
         interaction_id: &str,
@@ -548,6 +583,45 @@ impl Channel for DiscordChannel {
                                             continue;
                                         }
 
+                                        if event == "MESSAGE_DELETE" {
+                                            let deleted: MessageDeleteEvent = match serde_json::from_value(payload.d.unwrap_or_default()) {
+                                                Ok(value) => value,
+                                                Err(error) => {
+                                                    warn!(error = %error, "discord MESSAGE_DELETE decode failed");
+                                                    continue;
+                                                }
+                                            };
+
+                                            // Deletions carry no author; the dispatcher matches
+                                            // the message id against the runs it started.
+                                            let inbound = InboundMessage {
+                                                channel: self.id.clone(),
+                                                peer_id: PeerId("unknown".to_string()),
+                                                chat_type: match &deleted.guild_id {
+                                                    None => ChatType::Direct,
+                                                    Some(_) => ChatType::Group {
+                                                        id: deleted.channel_id.clone(),
+                                                    },
+                                                },
+                                                text: String::new(),
+                                                media: Vec::new(),
+                                                metadata: serde_json::json!({
+                                                    "type": "cancel",
+                                                    "reason": "message_deleted",
+                                                    "channel_id": deleted.channel_id,
+                                                    "guild_id": deleted.guild_id,
+                                                    "message_id": deleted.id,
+                                                }),
+                                                timestamp: Utc::now(),
+                                            };
+
+                                            if inbound_tx.send(inbound).await.is_err() {
+                                                return Ok(());
+                                            }
+
+                                            continue;
+                                        }
+
                                         if event != "MESSAGE_CREATE" {
                                             continue;
                                         }
@@ -575,7 +649,8 @@ impl Channel for DiscordChannel {
                                         }
 
                                         if message_event.kind != DISCORD_MESSAGE_TYPE_DEFAULT
-                                            || message_event.content.trim().is_empty()
+                                            || (message_event.content.trim().is_empty()
+                                                && message_event.attachments.is_empty())
                                         {
                                             continue;
                                         }
@@ -604,12 +679,25 @@ impl Channel for DiscordChannel {
                                             .map(|value| value.with_timezone(&Utc))
                                             .unwrap_or_else(|_| Utc::now());
 
-                                        let inbound = InboundMessage {
+                                        // Attachment URLs are signed CDN links; no bot token needed
+                                        let (media, skipped) = self
+                                            .attachment_policy
+                                            .download(
+                                                message_event
+                                                    .attachments
+                                                    .into_iter()
+                                                    .map(DiscordAttachment::into_remote)
+                                                    .collect(),
+                                                |url| self.http.get(url),
+                                            )
+                                            .await;
+
+                                        let mut inbound = InboundMessage {
                                             channel: self.id.clone(),
                                             peer_id: PeerId(message_event.author.id),
                                             chat_type,
                                             text: message_event.content,
-                                            media: Vec::new(),
+                                            media,
                                             metadata: serde_json::json!({
                                                 "channel_id": message_event.channel_id,
                                                 "guild_id": message_event.guild_id,
@@ -617,6 +705,7 @@ impl Channel for DiscordChannel {
                                             }),
                                             timestamp,
                                         };
+                                        record_skipped(&mut inbound.metadata, &skipped);
 
                                         if inbound_tx.send(inbound).await.is_err() {
                                             return Ok(());
@@ -713,6 +802,16 @@ impl Channel for DiscordChannel {
         Ok(format!("{channel_id}:{message_id}"))
     }
 
+    async fn send_editable(&self, reply: OutboundReply) -> Result<String> {
+        let channel_id = Self::extract_target(&reply)?;
+        let reply_to = Self::parse_message_reply_id(&reply.metadata);
+        let message_id = self
+            .post_message(&channel_id, &reply.text, reply_to.as_deref(), None)
+            .await?;
+
+        Ok(format!("{channel_id}:{message_id}"))
+    }
+
     async fn edit_message(&self, message_id: &str, new_text: &str) -> Result<()> {
         let Some((channel_id, msg_id)) = parse_discord_message_id(message_id) else {
             return Ok(());
@@ -722,6 +821,11 @@ impl Channel for DiscordChannel {
             .await
     }
 
+    async fn send_typing(&self, reply: OutboundReply) -> Result<()> {
+        let channel_id = Self::extract_target(&reply)?;
+        self.trigger_typing(&channel_id).await
+    }
+
     async fn test(&self) -> Result<ChannelTestResult> {
         let user = self.current_user().await?;
 
@@ -760,6 +864,38 @@ struct MessageCreateEvent {
     timestamp: String,
     #[serde(rename = "type")]
     kind: u8,
+    #[serde(default)]
+    attachments: Vec<DiscordAttachment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscordAttachment {
+    filename: String,
+    #[serde(default)]
+    content_type: Option<String>,
+    size: u64,
+    url: String,
+}
+
+impl DiscordAttachment {
+    fn into_remote(self) -> RemoteAttachment {
+        RemoteAttachment {
+            url: self.url,
+            filename: Some(self.filename),
+            mime_type: self
+                .content_type
+                .unwrap_or_else(|| "application/octet-stream".to_string()),
+            size: Some(self.size),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageDeleteEvent {
+    id: String,
+    channel_id: String,
+    #[serde(default)]
+    guild_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -902,7 +1038,8 @@ fn parse_discord_message_id(message_id: &str) -> Option<(&str, &str)> {
 #[cfg(test)]
 mod tests {
     use super::{
-        DISCORD_OP_DISPATCH, GatewayPayload, MessageCreateEvent, parse_discord_message_id,
+        DISCORD_OP_DISPATCH, GatewayPayload, MessageCreateEvent, MessageDeleteEvent,
+        parse_discord_message_id,
     };
 
     #[test]
@@ -942,6 +1079,20 @@ mod tests {
         assert_eq!(event.kind, 0);
     }
 
+    #[test]
+    fn message_delete_deserializes_without_guild() {
+        let raw = r#"{"id":"m1","channel_id":"c1"}"#;
+
+        let event: MessageDeleteEvent = match serde_json::from_str(raw) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to parse event: {error}"),
+        };
+
+        assert_eq!(event.id, "m1");
+        assert_eq!(event.channel_id, "c1");
+        assert_eq!(event.guild_id, None);
+    }
+
     #[test]
     fn parse_discord_message_id_splits_on_last_colon() {
         assert_eq!(parse_discord_message_id("123:456"), Some(("123", "456")));
@@ -4,6 +4,8 @@ pub mod telegram;
 
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
+use axum::http::{HeaderMap, StatusCode};
+use bytes::Bytes;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
@@ -35,6 +37,29 @@ pub struct ChannelTestResult {
     pub details: String,
 }
 
+/// An HTTP request a platform delivered to the gateway's webhook endpoint.
+#[derive(Debug, Clone)]
+pub struct WebhookRequest {
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+/// The response returned to the platform for a webhook request.
+#[derive(Debug, Clone)]
+pub struct WebhookResponse {
+    pub status: StatusCode,
+    pub body: String,
+}
+
+impl WebhookResponse {
+    pub fn new(status: StatusCode, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+        }
+    }
+}
+
 #[async_trait]
 pub trait Channel: Send + Sync + 'static {
     fn id(&self) -> &ChannelId;
@@ -65,6 +90,15 @@ pub trait Channel: Send + Sync + 'static {
         ))
     }
 
+    /// Post a single message that can later be updated with `edit_message`,
+    /// returning its message id.
+    async fn send_editable(&self, _reply: OutboundReply) -> Result<String> {
+        Err(anyhow!(
+            "channel '{}' does not support editing messages",
+            self.display_name()
+        ))
+    }
+
     async fn edit_message(&self, _message_id: &str, _new_text: &str) -> Result<()> {
         Err(anyhow!(
             "channel '{}' does not support editing messages",
@@ -72,6 +106,28 @@ pub trait Channel: Send + Sync + 'static {
         ))
     }
 
+    /// Show a typing/activity indicator at the reply target. The indicator
+    /// expires on its own, so callers repeat this while work is in progress.
+    async fn send_typing(&self, _reply: OutboundReply) -> Result<()> {
+        Err(anyhow!(
+            "channel '{}' does not support typing indicators",
+            self.display_name()
+        ))
+    }
+
+    /// Handle an event the platform pushed over HTTP instead of the channel's
+    /// own connection, forwarding resulting messages to `inbound_tx`.
+    async fn handle_webhook(
+        &self,
+        _request: WebhookRequest,
+        _inbound_tx: &mpsc::Sender<InboundMessage>,
+    ) -> Result<WebhookResponse> {
+        Err(anyhow!(
+            "channel '{}' does not accept webhooks",
+            self.display_name()
+        ))
+    }
+
     async fn test(&self) -> Result<ChannelTestResult>;
 }
 
@@ -185,6 +241,28 @@ mod tests {
         assert!(error.contains("DefaultOnly"));
     }
 
+    #[tokio::test]
+    async fn channel_default_send_editable_returns_error() {
+        let channel = DefaultBehaviorChannel::new();
+        let result = channel.send_editable(outbound_reply()).await;
+        let error = match result {
+            Ok(_) => String::new(),
+            Err(error) => error.to_string(),
+        };
+        assert!(error.contains("does not support editing messages"));
+    }
+
+    #[tokio::test]
+    async fn channel_default_send_typing_returns_error() {
+        let channel = DefaultBehaviorChannel::new();
+        let result = channel.send_typing(outbound_reply()).await;
+        let error = match result {
+            Ok(_) => String::new(),
+            Err(error) => error.to_string(),
+        };
+        assert!(error.contains("does not support typing indicators"));
+    }
+
     #[tokio::test]
     async fn channel_default_edit_message_returns_error() {
         let channel = DefaultBehaviorChannel::new();
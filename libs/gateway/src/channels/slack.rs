@@ -3,6 +3,7 @@ use std::sync::Mutex;
 
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
+use axum::http::StatusCode;
 use chrono::{DateTime, Utc};
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
@@ -12,24 +13,30 @@ use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
 use crate::{
+    attachments::{AttachmentPolicy, AttachmentRejection, RemoteAttachment, record_skipped},
     channels::{
-        ApprovalButton, ButtonStyle, Channel, ChannelTestResult, DeliveryReceipt,
-        parse_approval_callback,
+        ApprovalButton, ButtonStyle, Channel, ChannelTestResult, DeliveryReceipt, WebhookRequest,
+        WebhookResponse, parse_approval_callback,
     },
     slack_blocks::markdown_to_slack_messages,
-    types::{ChannelId, ChatType, InboundMessage, OutboundReply, PeerId},
+    slack_signature::verify_slack_signature,
+    types::{ChannelId, ChatType, InboundMessage, MediaAttachment, OutboundReply, PeerId},
 };
 
 const RECEIVED_REACTION: &str = "eyes";
+/// Dedup key scope for event ids, kept apart from `(channel, ts)` keys.
+const EVENT_ID_DEDUP_SCOPE: &str = "event_id";
 
 pub struct SlackChannel {
     id: ChannelId,
     bot_token: String,
     app_token: String,
+    signing_secret: Option<String>,
     http: reqwest::Client,
     bot_user_id: Mutex<Option<String>>,
     dedup: Mutex<DedupBuffer>,
     active_threads: Mutex<HashSet<(String, String)>>,
+    attachment_policy: AttachmentPolicy,
 }
 
 impl SlackChannel {
@@ -38,13 +45,33 @@ impl SlackChannel {
             id: "slack".into(),
             bot_token,
             app_token,
+            signing_secret: None,
             http: reqwest::Client::new(),
             bot_user_id: Mutex::new(None),
             dedup: Mutex::new(DedupBuffer::new(2048)),
             active_threads: Mutex::new(HashSet::new()),
+            attachment_policy: AttachmentPolicy::default(),
         }
     }
 
+    /// Limits on the files users share; larger or disallowed files are never
+    /// downloaded.
+    pub fn with_attachment_policy(mut self, attachment_policy: AttachmentPolicy) -> Self {
+        self.attachment_policy = attachment_policy;
+        self
+    }
+
+    /// Accept Events API requests over HTTP, verified with the app's signing
+    /// secret. Without an app token the channel relies on them entirely.
+    pub fn with_signing_secret(mut self, signing_secret: Option<String>) -> Self {
+        self.signing_secret = signing_secret.filter(|secret| !secret.trim().is_empty());
+        self
+    }
+
+    fn socket_mode_enabled(&self) -> bool {
+        !self.app_token.trim().is_empty()
+    }
+
     async fn auth_test(&self) -> Result<AuthTestResponse> {
         let response = self
             .http
@@ -238,6 +265,40 @@ impl SlackChannel {
         Ok((channel, channel_type, thread_ts))
     }
 
+    /// Set the "is working…" status of an assistant thread. Slack has no
+    /// typing indicator for bots outside of assistant threads, which need the
+    /// `assistant:write` scope.
+    async fn set_assistant_status(&self, channel: &str, thread_ts: &str) -> Result<()> {
+        let payload = serde_json::json!({
+            "channel_id": channel,
+            "thread_ts": thread_ts,
+            "status": "is working…",
+        });
+
+        let response = self
+            .http
+            .post("https://slack.com/api/assistant.threads.setStatus")
+            .bearer_auth(&self.bot_token)
+            .json(&payload)
+            .send()
+            .await
+            .context("slack assistant.threads.setStatus request failed")?;
+
+        let payload: SlackApiResponse = response
+            .json()
+            .await
+            .context("slack assistant.threads.setStatus decode failed")?;
+
+        if payload.ok {
+            return Ok(());
+        }
+
+        Err(anyhow!(
+            "slack assistant.threads.setStatus failed: {}",
+            payload.error.unwrap_or_else(|| "unknown error".to_string())
+        ))
+    }
+
     async fn add_reaction(&self, channel: &str, ts: &str, name: &str) -> Result<()> {
         let payload = ReactionsAdd {
             channel: channel.to_string(),
@@ -329,126 +390,12 @@ impl SlackChannel {
                     .await
                     .context("slack ack send failed")?;
 
-                if payload.retry_attempt.unwrap_or(0) > 0 {
-                    return Ok(HandleAction::Continue);
-                }
-
                 let Some(event_payload_raw) = payload.payload else {
                     return Ok(HandleAction::Continue);
                 };
 
-                let event_payload: EventPayload = serde_json::from_value(event_payload_raw)
-                    .context("slack event payload decode failed")?;
-
-                if event_payload.event_type != "event_callback" {
-                    return Ok(HandleAction::Continue);
-                }
-
-                let event = event_payload.event;
-                if event.event_type != "message" {
-                    return Ok(HandleAction::Continue);
-                }
-
-                if event.subtype.is_some() || event.bot_id.is_some() {
-                    return Ok(HandleAction::Continue);
-                }
-
-                let Some(user) = event.user else {
-                    return Ok(HandleAction::Continue);
-                };
-
-                let own_bot_id = self
-                    .bot_user_id
-                    .lock()
-                    .ok()
-                    .and_then(|guard| guard.clone())
-                    .unwrap_or_default();
-                if !own_bot_id.is_empty() && user == own_bot_id {
-                    return Ok(HandleAction::Continue);
-                }
-
-                let Some(channel) = event.channel else {
-                    return Ok(HandleAction::Continue);
-                };
-                let Some(raw_text) = event.text else {
-                    return Ok(HandleAction::Continue);
-                };
-                if raw_text.trim().is_empty() {
-                    return Ok(HandleAction::Continue);
-                }
-
-                let ts = event.ts.clone().unwrap_or_else(|| format_ts(Utc::now()));
-                if self.is_duplicate(&channel, &ts) {
-                    return Ok(HandleAction::Continue);
-                }
-
-                let channel_type = event.channel_type.unwrap_or_else(|| "channel".to_string());
-                let is_dm = channel_type == "im";
-                let mentioned = is_bot_mentioned(&raw_text, &own_bot_id);
-
-                let mut effective_thread_ts = event.thread_ts.clone();
-
-                if !is_dm {
-                    match event.thread_ts.as_deref() {
-                        Some(thread_ts) => {
-                            if mentioned {
-                                self.activate_thread(&channel, thread_ts);
-                            } else if !self.is_thread_active(&channel, thread_ts) {
-                                return Ok(HandleAction::Continue);
-                            }
-                            effective_thread_ts = Some(thread_ts.to_string());
-                        }
-                        None => {
-                            // Top-level channel/group message: only respond when bot is mentioned.
-                            if !mentioned {
-                                return Ok(HandleAction::Continue);
-                            }
-                            // Force thread session semantics by anchoring to the top-level message ts.
-                            self.activate_thread(&channel, &ts);
-                            effective_thread_ts = Some(ts.clone());
-                        }
-                    }
-                }
-
-                let cleaned_text = if is_dm {
-                    raw_text.trim().to_string()
-                } else {
-                    strip_bot_mention(&raw_text, &own_bot_id).trim().to_string()
-                };
-
-                if cleaned_text.is_empty() {
-                    return Ok(HandleAction::Continue);
-                }
-
-                let chat_type =
-                    map_chat_type(&channel, &channel_type, effective_thread_ts.as_deref());
-
-                if let Err(error) = self.add_reaction(&channel, &ts, RECEIVED_REACTION).await {
-                    warn!(error = %error, channel = %channel, ts = %ts, "failed to add slack receipt reaction");
-                }
-
-                let inbound = InboundMessage {
-                    channel: self.id.clone(),
-                    peer_id: PeerId(user.clone()),
-                    chat_type,
-                    text: cleaned_text,
-                    media: Vec::new(),
-                    metadata: serde_json::json!({
-                        "channel": channel,
-                        "ts": ts,
-                        "thread_ts": effective_thread_ts,
-                        "channel_type": channel_type,
-                        "mentioned": mentioned,
-                        "user_id": user,
-                    }),
-                    timestamp: parse_slack_ts_to_datetime(event.ts.as_deref()),
-                };
-
-                if inbound_tx.send(inbound).await.is_err() {
-                    return Ok(HandleAction::Stop);
-                }
-
-                Ok(HandleAction::Continue)
+                self.handle_event_payload(event_payload_raw, inbound_tx)
+                    .await
             }
             "interactive" => {
                 let Some(envelope_id) = payload.envelope_id else {
@@ -526,6 +473,197 @@ impl SlackChannel {
         }
     }
 
+    /// Turn an Events API payload into inbound messages. Shared by Socket
+    /// Mode and the HTTP webhook.
+    async fn handle_event_payload(
+        &self,
+        event_payload_raw: serde_json::Value,
+        inbound_tx: &mpsc::Sender<InboundMessage>,
+    ) -> Result<HandleAction> {
+        let event_payload: EventPayload = serde_json::from_value(event_payload_raw)
+            .context("slack event payload decode failed")?;
+
+        if event_payload.event_type != "event_callback" {
+            return Ok(HandleAction::Continue);
+        }
+
+        // Slack redelivers an event it saw no ack for; it keeps its event_id
+        if let Some(event_id) = event_payload.event_id.as_deref()
+            && self.is_duplicate(EVENT_ID_DEDUP_SCOPE, event_id)
+        {
+            return Ok(HandleAction::Continue);
+        }
+
+        let event = event_payload.event;
+        if event.event_type != "message" {
+            return Ok(HandleAction::Continue);
+        }
+
+        if let Some((reason, ts)) = withdrawn_message(&event) {
+            let Some(channel) = event.channel.clone() else {
+                return Ok(HandleAction::Continue);
+            };
+            let user = event
+                .previous_message
+                .as_ref()
+                .and_then(|message| message.user.clone())
+                .unwrap_or_else(|| "unknown".to_string());
+            let channel_type = event.channel_type.as_deref().unwrap_or("channel");
+
+            let inbound = InboundMessage {
+                channel: self.id.clone(),
+                peer_id: PeerId(user),
+                chat_type: map_chat_type(&channel, channel_type, None),
+                text: String::new(),
+                media: Vec::new(),
+                metadata: serde_json::json!({
+                    "type": "cancel",
+                    "reason": reason,
+                    "channel": channel,
+                    "ts": ts,
+                }),
+                timestamp: Utc::now(),
+            };
+
+            if inbound_tx.send(inbound).await.is_err() {
+                return Ok(HandleAction::Stop);
+            }
+
+            return Ok(HandleAction::Continue);
+        }
+
+        // `file_share` is a user message with files attached
+        let is_file_share = event.subtype.as_deref() == Some("file_share");
+        if (event.subtype.is_some() && !is_file_share) || event.bot_id.is_some() {
+            return Ok(HandleAction::Continue);
+        }
+
+        let Some(user) = event.user else {
+            return Ok(HandleAction::Continue);
+        };
+
+        let own_bot_id = self
+            .bot_user_id
+            .lock()
+            .ok()
+            .and_then(|guard| guard.clone())
+            .unwrap_or_default();
+        if !own_bot_id.is_empty() && user == own_bot_id {
+            return Ok(HandleAction::Continue);
+        }
+
+        let Some(channel) = event.channel else {
+            return Ok(HandleAction::Continue);
+        };
+        let files = event.files;
+        let raw_text = event.text.unwrap_or_default();
+        if raw_text.trim().is_empty() && files.is_empty() {
+            return Ok(HandleAction::Continue);
+        }
+
+        let ts = event.ts.clone().unwrap_or_else(|| format_ts(Utc::now()));
+        if self.is_duplicate(&channel, &ts) {
+            return Ok(HandleAction::Continue);
+        }
+
+        let channel_type = event.channel_type.unwrap_or_else(|| "channel".to_string());
+        let is_dm = channel_type == "im";
+        let mentioned = is_bot_mentioned(&raw_text, &own_bot_id);
+
+        let mut effective_thread_ts = event.thread_ts.clone();
+
+        if !is_dm {
+            match event.thread_ts.as_deref() {
+                Some(thread_ts) => {
+                    if mentioned {
+                        self.activate_thread(&channel, thread_ts);
+                    } else if !self.is_thread_active(&channel, thread_ts) {
+                        return Ok(HandleAction::Continue);
+                    }
+                    effective_thread_ts = Some(thread_ts.to_string());
+                }
+                None => {
+                    // Top-level channel/group message: only respond when bot is mentioned.
+                    if !mentioned {
+                        return Ok(HandleAction::Continue);
+                    }
+                    // Force thread session semantics by anchoring to the top-level message ts.
+                    self.activate_thread(&channel, &ts);
+                    effective_thread_ts = Some(ts.clone());
+                }
+            }
+        }
+
+        let cleaned_text = if is_dm {
+            raw_text.trim().to_string()
+        } else {
+            strip_bot_mention(&raw_text, &own_bot_id).trim().to_string()
+        };
+
+        if cleaned_text.is_empty() && files.is_empty() {
+            return Ok(HandleAction::Continue);
+        }
+
+        let chat_type = map_chat_type(&channel, &channel_type, effective_thread_ts.as_deref());
+
+        if let Err(error) = self.add_reaction(&channel, &ts, RECEIVED_REACTION).await {
+            warn!(error = %error, channel = %channel, ts = %ts, "failed to add slack receipt reaction");
+        }
+
+        let (media, skipped) = self.download_files(files).await;
+
+        let mut inbound = InboundMessage {
+            channel: self.id.clone(),
+            peer_id: PeerId(user.clone()),
+            chat_type,
+            text: cleaned_text,
+            media,
+            metadata: serde_json::json!({
+                "channel": channel,
+                "ts": ts,
+                "thread_ts": effective_thread_ts,
+                "channel_type": channel_type,
+                "mentioned": mentioned,
+                "user_id": user,
+            }),
+            timestamp: parse_slack_ts_to_datetime(event.ts.as_deref()),
+        };
+        record_skipped(&mut inbound.metadata, &skipped);
+
+        if inbound_tx.send(inbound).await.is_err() {
+            return Ok(HandleAction::Stop);
+        }
+
+        Ok(HandleAction::Continue)
+    }
+
+    /// Download the files shared with a message. Private file URLs need the
+    /// bot token.
+    async fn download_files(
+        &self,
+        files: Vec<SlackFile>,
+    ) -> (Vec<MediaAttachment>, Vec<AttachmentRejection>) {
+        let remotes = files
+            .into_iter()
+            .filter_map(|file| {
+                Some(RemoteAttachment {
+                    url: file.url_private_download.or(file.url_private)?,
+                    filename: file.name,
+                    mime_type: file
+                        .mimetype
+                        .unwrap_or_else(|| "application/octet-stream".to_string()),
+                    size: file.size,
+                })
+            })
+            .collect();
+
+        self.attachment_policy
+            .download(remotes, |url| {
+                self.http.get(url).bearer_auth(&self.bot_token)
+            })
+            .await
+    }
+
     fn is_duplicate(&self, channel: &str, ts: &str) -> bool {
         match self.dedup.lock() {
             Ok(mut guard) => guard.is_duplicate(channel.to_string(), ts.to_string()),
@@ -567,6 +705,12 @@ impl Channel for SlackChannel {
             *guard = auth.user_id.clone();
         }
 
+        if !self.socket_mode_enabled() {
+            info!("slack socket mode disabled; receiving events over HTTP");
+            cancel.cancelled().await;
+            return Ok(());
+        }
+
         let mut reconnect_backoff_secs = 1_u64;
 
         loop {
@@ -744,6 +888,14 @@ impl Channel for SlackChannel {
         Ok(format!("{channel}:{ts}"))
     }
 
+    async fn send_editable(&self, reply: OutboundReply) -> Result<String> {
+        let (channel, _channel_type, thread_ts) = Self::extract_target(&reply)?;
+        let ts = self
+            .post_message(&channel, &reply.text, None, None, thread_ts.as_deref())
+            .await?;
+        Ok(format!("{channel}:{ts}"))
+    }
+
     async fn edit_message(&self, message_id: &str, new_text: &str) -> Result<()> {
         let Some((channel, ts)) = parse_slack_message_id(message_id) else {
             return Ok(());
@@ -752,9 +904,64 @@ impl Channel for SlackChannel {
         self.update_message(channel, ts, new_text, Vec::new()).await
     }
 
+    async fn send_typing(&self, reply: OutboundReply) -> Result<()> {
+        let (channel, _channel_type, thread_ts) = Self::extract_target(&reply)?;
+        let Some(thread_ts) = thread_ts else {
+            return Err(anyhow!("slack typing status needs a thread"));
+        };
+        self.set_assistant_status(&channel, &thread_ts).await
+    }
+
+    async fn handle_webhook(
+        &self,
+        request: WebhookRequest,
+        inbound_tx: &mpsc::Sender<InboundMessage>,
+    ) -> Result<WebhookResponse> {
+        let Some(signing_secret) = self.signing_secret.as_deref() else {
+            return Err(anyhow!("slack webhooks require a signing_secret"));
+        };
+
+        if let Err(error) = verify_slack_signature(
+            signing_secret,
+            &request.headers,
+            &request.body,
+            Utc::now().timestamp(),
+        ) {
+            warn!(error = %error, "rejected slack webhook request");
+            return Ok(WebhookResponse::new(
+                StatusCode::UNAUTHORIZED,
+                "invalid slack signature",
+            ));
+        }
+
+        let Ok(envelope) = serde_json::from_slice::<serde_json::Value>(&request.body) else {
+            return Ok(WebhookResponse::new(
+                StatusCode::BAD_REQUEST,
+                "expected a JSON Events API payload",
+            ));
+        };
+
+        match envelope.get("type").and_then(|value| value.as_str()) {
+            Some("url_verification") => {
+                let challenge = envelope
+                    .get("challenge")
+                    .and_then(|value| value.as_str())
+                    .unwrap_or_default();
+                Ok(WebhookResponse::new(StatusCode::OK, challenge))
+            }
+            Some("event_callback") => {
+                self.handle_event_payload(envelope, inbound_tx).await?;
+                Ok(WebhookResponse::new(StatusCode::OK, ""))
+            }
+            _ => Ok(WebhookResponse::new(StatusCode::OK, "")),
+        }
+    }
+
     async fn test(&self) -> Result<ChannelTestResult> {
         let auth = self.auth_test().await?;
-        let _ = self.open_socket_url().await?;
+        if self.socket_mode_enabled() {
+            let _ = self.open_socket_url().await?;
+        }
 
         let identity = auth
             .user
@@ -786,8 +993,6 @@ struct SocketPayload {
     #[serde(default)]
     payload: Option<serde_json::Value>,
     #[serde(default)]
-    retry_attempt: Option<u32>,
-    #[serde(default)]
     reason: Option<String>,
 }
 
@@ -795,6 +1000,8 @@ struct SocketPayload {
 struct EventPayload {
     #[serde(rename = "type")]
     event_type: String,
+    #[serde(default)]
+    event_id: Option<String>,
     event: SlackEvent,
 }
 
@@ -818,6 +1025,40 @@ struct SlackEvent {
     subtype: Option<String>,
     #[serde(default)]
     bot_id: Option<String>,
+    #[serde(default)]
+    deleted_ts: Option<String>,
+    #[serde(default)]
+    message: Option<SlackChangedMessage>,
+    #[serde(default)]
+    previous_message: Option<SlackChangedMessage>,
+    #[serde(default)]
+    files: Vec<SlackFile>,
+}
+
+/// A file shared with a message (`file_share` subtype).
+#[derive(Debug, Deserialize)]
+struct SlackFile {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    mimetype: Option<String>,
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    url_private: Option<String>,
+    #[serde(default)]
+    url_private_download: Option<String>,
+}
+
+/// The message carried by `message_changed` and `message_deleted` events.
+#[derive(Debug, Deserialize)]
+struct SlackChangedMessage {
+    #[serde(default)]
+    ts: Option<String>,
+    #[serde(default)]
+    user: Option<String>,
+    #[serde(default)]
+    text: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1058,6 +1299,28 @@ fn build_approval_blocks(text: &str, buttons: &[ApprovalButton]) -> Vec<serde_js
     blocks
 }
 
+/// The reason and `ts` of a user message that was deleted or had its text
+/// edited. Other `message_changed` events, such as link unfurls, keep the
+/// text and are ignored.
+fn withdrawn_message(event: &SlackEvent) -> Option<(&'static str, String)> {
+    match event.subtype.as_deref()? {
+        "message_deleted" => event
+            .deleted_ts
+            .clone()
+            .or_else(|| event.previous_message.as_ref()?.ts.clone())
+            .map(|ts| ("message_deleted", ts)),
+        "message_changed" => {
+            let message = event.message.as_ref()?;
+            let previous = event.previous_message.as_ref()?;
+            if message.text == previous.text {
+                return None;
+            }
+            message.ts.clone().map(|ts| ("message_edited", ts))
+        }
+        _ => None,
+    }
+}
+
 fn parse_slack_message_id(message_id: &str) -> Option<(&str, &str)> {
     message_id.rsplit_once(':')
 }
@@ -1087,10 +1350,194 @@ fn collapse_whitespace(text: &str) -> String {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::http::{HeaderMap, HeaderValue, StatusCode};
+    use axum::routing::get;
+    use bytes::Bytes;
+    use chrono::Utc;
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+    use tokio::sync::mpsc;
+
     use super::{
-        DedupBuffer, is_bot_mentioned, map_chat_type, parse_slack_message_id, strip_bot_mention,
+        DedupBuffer, SlackChannel, SlackEvent, is_bot_mentioned, map_chat_type,
+        parse_slack_message_id, strip_bot_mention, withdrawn_message,
+    };
+    use crate::{
+        attachments::{AttachmentPolicy, AttachmentRejection, take_skipped},
+        channels::{Channel, WebhookRequest},
+        types::ChatType,
     };
-    use crate::types::ChatType;
+
+    fn signed_request(secret: &str, timestamp: i64, body: &str) -> WebhookRequest {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("v0:{timestamp}:{body}").as_bytes());
+        let signature: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-slack-request-timestamp",
+            HeaderValue::from_str(&timestamp.to_string()).unwrap(),
+        );
+        headers.insert(
+            "x-slack-signature",
+            HeaderValue::from_str(&format!("v0={signature}")).unwrap(),
+        );
+        WebhookRequest {
+            headers,
+            body: Bytes::from(body.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn webhook_answers_verified_requests_and_rejects_forged_ones() {
+        let channel = SlackChannel::new("xoxb-token".to_string(), String::new())
+            .with_signing_secret(Some("secret".to_string()));
+        let (inbound_tx, _inbound_rx) = mpsc::channel(1);
+        let body = r#"{"type":"url_verification","challenge":"c-123"}"#;
+        let now = Utc::now().timestamp();
+
+        let response = channel
+            .handle_webhook(signed_request("secret", now, body), &inbound_tx)
+            .await
+            .unwrap();
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.body, "c-123");
+
+        let mut tampered = signed_request("secret", now, body);
+        tampered.body = Bytes::from(body.replace("c-123", "c-456"));
+        let response = channel.handle_webhook(tampered, &inbound_tx).await.unwrap();
+        assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+
+        let stale = signed_request("secret", now - 60 * 10, body);
+        let response = channel.handle_webhook(stale, &inbound_tx).await.unwrap();
+        assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn webhook_delivers_retried_events_once_per_event_id() {
+        let channel = SlackChannel::new("xoxb-token".to_string(), String::new())
+            .with_signing_secret(Some("secret".to_string()));
+        let (inbound_tx, mut inbound_rx) = mpsc::channel(4);
+        let body = serde_json::json!({
+            "type": "event_callback",
+            "event_id": "Ev123",
+            "event": {
+                "type": "message",
+                "subtype": "message_deleted",
+                "channel": "C123",
+                "deleted_ts": "1700000000.1",
+                "previous_message": {"ts": "1700000000.1", "user": "U1", "text": "deploy"}
+            }
+        })
+        .to_string();
+
+        // The first delivery attempt never reached us, so the retry is new
+        let mut retry = signed_request("secret", Utc::now().timestamp(), &body);
+        retry
+            .headers
+            .insert("x-slack-retry-num", HeaderValue::from_static("1"));
+        let response = channel.handle_webhook(retry, &inbound_tx).await.unwrap();
+        assert_eq!(response.status, StatusCode::OK);
+        let inbound = inbound_rx.try_recv().unwrap();
+        assert_eq!(inbound.metadata["type"], "cancel");
+
+        let replay = signed_request("secret", Utc::now().timestamp(), &body);
+        let response = channel.handle_webhook(replay, &inbound_tx).await.unwrap();
+        assert_eq!(response.status, StatusCode::OK);
+        assert!(inbound_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn shared_files_are_screened_before_download() {
+        let oversized_hits = Arc::new(AtomicUsize::new(0));
+        let files = axum::Router::new()
+            .route("/notes.txt", get(|| async { "deploy notes" }))
+            .route(
+                "/dump.bin",
+                get({
+                    let oversized_hits = oversized_hits.clone();
+                    move || async move {
+                        oversized_hits.fetch_add(1, Ordering::SeqCst);
+                        "never requested"
+                    }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, files).await });
+
+        let channel = SlackChannel::new("xoxb-token".to_string(), String::new())
+            .with_signing_secret(Some("secret".to_string()))
+            .with_attachment_policy(AttachmentPolicy {
+                max_bytes: 1024,
+                allowed_types: vec!["*/*".to_string()],
+            });
+        let (inbound_tx, mut inbound_rx) = mpsc::channel(1);
+        let body = serde_json::json!({
+            "type": "event_callback",
+            "event_id": "Ev-files",
+            "event": {
+                "type": "message",
+                "subtype": "file_share",
+                "channel": "D123",
+                "channel_type": "im",
+                "user": "U1",
+                "text": "",
+                "ts": "1700000000.3",
+                "files": [
+                    {
+                        "name": "notes.txt",
+                        "mimetype": "text/plain",
+                        "size": 12,
+                        "url_private_download": format!("{base}/notes.txt")
+                    },
+                    {
+                        "name": "dump.bin",
+                        "mimetype": "application/octet-stream",
+                        "size": 50_000_000,
+                        "url_private_download": format!("{base}/dump.bin")
+                    }
+                ]
+            }
+        })
+        .to_string();
+
+        let request = signed_request("secret", Utc::now().timestamp(), &body);
+        channel.handle_webhook(request, &inbound_tx).await.unwrap();
+
+        let mut inbound = inbound_rx.try_recv().unwrap();
+        assert_eq!(inbound.media.len(), 1);
+        assert_eq!(inbound.media[0].data, b"deploy notes");
+        assert_eq!(oversized_hits.load(Ordering::SeqCst), 0);
+        let skipped = take_skipped(&mut inbound.metadata);
+        assert!(matches!(
+            skipped.as_slice(),
+            [AttachmentRejection::TooLarge { name, .. }] if name == "dump.bin"
+        ));
+    }
+
+    #[tokio::test]
+    async fn webhook_requires_signing_secret() {
+        let channel = SlackChannel::new("xoxb-token".to_string(), "xapp-token".to_string());
+        let (inbound_tx, _inbound_rx) = mpsc::channel(1);
+        let body = r#"{"type":"url_verification","challenge":"c-123"}"#;
+
+        let result = channel
+            .handle_webhook(
+                signed_request("secret", Utc::now().timestamp(), body),
+                &inbound_tx,
+            )
+            .await;
+        assert!(result.is_err());
+    }
 
     #[test]
     fn map_chat_type_im_to_direct() {
@@ -1150,4 +1597,56 @@ mod tests {
             Some(("team:C123", "1700000000.123456"))
         );
     }
+
+    fn slack_event(raw: serde_json::Value) -> SlackEvent {
+        match serde_json::from_value(raw) {
+            Ok(event) => event,
+            Err(error) => panic!("failed to parse slack event: {error}"),
+        }
+    }
+
+    #[test]
+    fn withdrawn_message_maps_deletes_and_text_edits() {
+        let deleted = slack_event(serde_json::json!({
+            "type": "message",
+            "subtype": "message_deleted",
+            "channel": "C123",
+            "deleted_ts": "1700000000.1",
+            "previous_message": {"ts": "1700000000.1", "user": "U1", "text": "deploy"}
+        }));
+        assert_eq!(
+            withdrawn_message(&deleted),
+            Some(("message_deleted", "1700000000.1".to_string()))
+        );
+
+        let edited = slack_event(serde_json::json!({
+            "type": "message",
+            "subtype": "message_changed",
+            "channel": "C123",
+            "message": {"ts": "1700000000.1", "user": "U1", "text": "deploy staging"},
+            "previous_message": {"ts": "1700000000.1", "user": "U1", "text": "deploy"}
+        }));
+        assert_eq!(
+            withdrawn_message(&edited),
+            Some(("message_edited", "1700000000.1".to_string()))
+        );
+
+        let unfurled = slack_event(serde_json::json!({
+            "type": "message",
+            "subtype": "message_changed",
+            "channel": "C123",
+            "message": {"ts": "1700000000.1", "user": "U1", "text": "see https://example.com"},
+            "previous_message": {"ts": "1700000000.1", "user": "U1", "text": "see https://example.com"}
+        }));
+        assert_eq!(withdrawn_message(&unfurled), None);
+
+        let plain = slack_event(serde_json::json!({
+            "type": "message",
+            "channel": "C123",
+            "user": "U1",
+            "text": "hello",
+            "ts": "1700000000.2"
+        }));
+        assert_eq!(withdrawn_message(&plain), None);
+    }
 }
@@ -9,11 +9,12 @@ use tokio_util::sync::CancellationToken;
 use tracing::{error, warn};
 
 use crate::{
+    attachments::{AttachmentPolicy, AttachmentRejection, RemoteAttachment, record_skipped},
     channels::{
         ApprovalButton, Channel, ChannelTestResult, DeliveryReceipt, parse_approval_callback,
     },
     chunking::chunk_text,
-    types::{ChannelId, ChatType, InboundMessage, OutboundReply, PeerId},
+    types::{ChannelId, ChatType, InboundMessage, MediaAttachment, OutboundReply, PeerId},
 };
 
 const TELEGRAM_TEXT_LIMIT: usize = 4096;
@@ -23,6 +24,7 @@ pub struct TelegramChannel {
     token: String,
     client: reqwest::Client,
     bot_user_id: Mutex<Option<i64>>,
+    attachment_policy: AttachmentPolicy,
 }
 
 impl TelegramChannel {
@@ -32,9 +34,17 @@ impl TelegramChannel {
             token,
             client: reqwest::Client::new(),
             bot_user_id: Mutex::new(None),
+            attachment_policy: AttachmentPolicy::default(),
         }
     }
 
+    /// Limits on the files users send; larger or disallowed files are never
+    /// downloaded.
+    pub fn with_attachment_policy(mut self, attachment_policy: AttachmentPolicy) -> Self {
+        self.attachment_policy = attachment_policy;
+        self
+    }
+
     fn api_url(&self, method: &str) -> String {
         format!("https://api.telegram.org/bot{}/{}", self.token, method)
     }
@@ -67,11 +77,83 @@ impl TelegramChannel {
         }
     }
 
+    /// The download URL of a file the bot received.
+    async fn get_file_url(&self, file_id: &str) -> Result<String> {
+        let response = self
+            .client
+            .post(self.api_url("getFile"))
+            .json(&serde_json::json!({ "file_id": file_id }))
+            .send()
+            .await
+            .context("telegram getFile request failed")?;
+
+        let payload: TgResponse<TgFile> = response
+            .json()
+            .await
+            .context("telegram getFile decode failed")?;
+
+        if !payload.ok {
+            return Err(anyhow!(
+                "telegram getFile error {}: {}",
+                payload.error_code.unwrap_or_default(),
+                payload
+                    .description
+                    .unwrap_or_else(|| "unknown error".to_string())
+            ));
+        }
+
+        let file_path = payload
+            .result
+            .and_then(|file| file.file_path)
+            .ok_or_else(|| anyhow!("telegram getFile missing file_path"))?;
+        Ok(format!(
+            "https://api.telegram.org/file/bot{}/{}",
+            self.token, file_path
+        ))
+    }
+
+    /// Download a message's photo and document. Files are checked against the
+    /// policy by the size Telegram reports before `getFile` is called.
+    async fn download_files(
+        &self,
+        files: Vec<RemoteAttachment>,
+    ) -> (Vec<MediaAttachment>, Vec<AttachmentRejection>) {
+        let (accepted, mut skipped) = self.attachment_policy.screen(files);
+
+        let mut resolved = Vec::with_capacity(accepted.len());
+        for mut remote in accepted {
+            // `url` holds the file_id until getFile resolves it
+            match self.get_file_url(&remote.url).await {
+                Ok(url) => {
+                    remote.url = url;
+                    resolved.push(remote);
+                }
+                Err(error) => {
+                    warn!(error = %error, "failed to resolve telegram file");
+                    skipped.push(AttachmentRejection::DownloadFailed {
+                        name: remote.filename.unwrap_or_else(|| "attachment".to_string()),
+                    });
+                }
+            }
+        }
+
+        let (media, failed) = self
+            .attachment_policy
+            .download(resolved, |url| self.client.get(url))
+            .await;
+        skipped.extend(failed);
+        (media, skipped)
+    }
+
     async fn get_updates(&self, offset: Option<i64>) -> Result<Vec<TgUpdate>> {
         let payload = GetUpdatesParams {
             offset,
             timeout: 30,
-            allowed_updates: vec!["message".to_string(), "callback_query".to_string()],
+            allowed_updates: vec![
+                "message".to_string(),
+                "edited_message".to_string(),
+                "callback_query".to_string(),
+            ],
         };
 
         let response = self
@@ -203,6 +285,41 @@ impl TelegramChannel {
         ))
     }
 
+    async fn send_chat_action(&self, chat_id: i64, thread_id: Option<i64>) -> Result<()> {
+        let mut payload = serde_json::json!({
+            "chat_id": chat_id,
+            "action": "typing",
+        });
+        if let Some(thread_id) = thread_id {
+            payload["message_thread_id"] = serde_json::json!(thread_id);
+        }
+
+        let response = self
+            .client
+            .post(self.api_url("sendChatAction"))
+            .json(&payload)
+            .send()
+            .await
+            .context("telegram sendChatAction request failed")?;
+
+        let payload: TgResponse<serde_json::Value> = response
+            .json()
+            .await
+            .context("telegram sendChatAction decode failed")?;
+
+        if payload.ok {
+            return Ok(());
+        }
+
+        Err(anyhow!(
+            "telegram sendChatAction error {}: {}",
+            payload.error_code.unwrap_or_default(),
+            payload
+                .description
+                .unwrap_or_else(|| "unknown error".to_string())
+        ))
+    }
+
     async fn edit_message_text(&self, chat_id: i64, message_id: i64, text: &str) -> Result<()> {
         let payload = EditMessageTextParams {
             chat_id,
@@ -252,17 +369,23 @@ impl TelegramChannel {
         Ok((chat_id, thread_id))
     }
 
-    fn map_message_inbound(&self, message: TgMessage) -> Option<InboundMessage> {
+    async fn map_message_inbound(&self, message: TgMessage) -> Option<InboundMessage> {
+        let files = message.remote_files();
         let TgMessage {
             message_id,
             from,
             chat,
             text,
+            caption,
             date,
             message_thread_id,
+            ..
         } = message;
 
-        let text = text?;
+        let text = text.or(caption).unwrap_or_default();
+        if text.trim().is_empty() && files.is_empty() {
+            return None;
+        }
         let from = from?;
 
         if from.is_bot {
@@ -282,13 +405,14 @@ impl TelegramChannel {
 
         let timestamp = DateTime::from_timestamp(date, 0).unwrap_or_else(Utc::now);
         let chat_type = chat_type_from_chat(&chat, message_thread_id);
+        let (media, skipped) = self.download_files(files).await;
 
-        Some(InboundMessage {
+        let mut inbound = InboundMessage {
             channel: self.id.clone(),
             peer_id: PeerId(from.id.to_string()),
             chat_type,
             text,
-            media: Vec::new(),
+            media,
             metadata: serde_json::json!({
                 "chat_id": chat.id,
                 "message_id": message_id,
@@ -298,6 +422,31 @@ impl TelegramChannel {
                 "display_name": from.first_name,
             }),
             timestamp,
+        };
+        record_skipped(&mut inbound.metadata, &skipped);
+        Some(inbound)
+    }
+
+    fn map_edited_inbound(&self, message: TgMessage) -> Option<InboundMessage> {
+        let from = message.from.as_ref()?;
+        if from.is_bot {
+            return None;
+        }
+
+        Some(InboundMessage {
+            channel: self.id.clone(),
+            peer_id: PeerId(from.id.to_string()),
+            chat_type: chat_type_from_tg_message(&message),
+            text: String::new(),
+            media: Vec::new(),
+            metadata: serde_json::json!({
+                "type": "cancel",
+                "reason": "message_edited",
+                "chat_id": message.chat.id,
+                "message_id": message.message_id,
+                "thread_id": message.message_thread_id,
+            }),
+            timestamp: Utc::now(),
         })
     }
 
@@ -382,10 +531,23 @@ impl Channel for TelegramChannel {
                         let TgUpdate {
                             update_id,
                             message,
+                            edited_message,
                             callback_query,
                         } = update;
                         offset = Some(update_id + 1);
 
+                        // The Bot API does not report deletions, so an edit is
+                        // what withdraws the message that started a run.
+                        if let Some(edited_message) = edited_message {
+                            if let Some(inbound) = self.map_edited_inbound(edited_message)
+                                && inbound_tx.send(inbound).await.is_err()
+                            {
+                                return Ok(());
+                            }
+
+                            continue;
+                        }
+
                         if let Some(callback_query) = callback_query {
                             if let Err(error) = self.answer_callback_query(&callback_query.id).await {
                                 warn!(error = %error, "failed to answer telegram callback query");
@@ -401,7 +563,7 @@ impl Channel for TelegramChannel {
                         }
 
                         if let Some(message) = message
-                            && let Some(inbound) = self.map_message_inbound(message)
+                            && let Some(inbound) = self.map_message_inbound(message).await
                             && inbound_tx.send(inbound).await.is_err()
                         {
                             return Ok(());
@@ -461,6 +623,15 @@ impl Channel for TelegramChannel {
         Ok(format!("{chat_id}:{}", message.message_id))
     }
 
+    async fn send_editable(&self, reply: OutboundReply) -> Result<String> {
+        let (chat_id, thread_id) = Self::extract_target(&reply)?;
+        let message = self
+            .send_message(chat_id, thread_id, &reply.text, None)
+            .await?;
+
+        Ok(format!("{chat_id}:{}", message.message_id))
+    }
+
     async fn edit_message(&self, message_id: &str, new_text: &str) -> Result<()> {
         let Some((chat_id, msg_id)) = parse_telegram_message_id(message_id) else {
             return Ok(());
@@ -469,6 +640,11 @@ impl Channel for TelegramChannel {
         self.edit_message_text(chat_id, msg_id, new_text).await
     }
 
+    async fn send_typing(&self, reply: OutboundReply) -> Result<()> {
+        let (chat_id, thread_id) = Self::extract_target(&reply)?;
+        self.send_chat_action(chat_id, thread_id).await
+    }
+
     async fn test(&self) -> Result<ChannelTestResult> {
         let me = self.get_me().await?;
 
@@ -591,6 +767,8 @@ struct TgUpdate {
     #[serde(default)]
     message: Option<TgMessage>,
     #[serde(default)]
+    edited_message: Option<TgMessage>,
+    #[serde(default)]
     callback_query: Option<TgCallbackQuery>,
 }
 
@@ -600,8 +778,62 @@ struct TgMessage {
     from: Option<TgUser>,
     chat: TgChat,
     text: Option<String>,
+    #[serde(default)]
+    caption: Option<String>,
     date: i64,
     message_thread_id: Option<i64>,
+    /// Sizes of one photo, smallest first
+    #[serde(default)]
+    photo: Vec<TgPhotoSize>,
+    #[serde(default)]
+    document: Option<TgDocument>,
+}
+
+impl TgMessage {
+    /// The photo (largest size) and document attached to the message, with
+    /// the file_id in place of the URL.
+    fn remote_files(&self) -> Vec<RemoteAttachment> {
+        let photo = self.photo.last().map(|photo| RemoteAttachment {
+            url: photo.file_id.clone(),
+            filename: Some("photo.jpg".to_string()),
+            mime_type: "image/jpeg".to_string(),
+            size: photo.file_size,
+        });
+        let document = self.document.as_ref().map(|document| RemoteAttachment {
+            url: document.file_id.clone(),
+            filename: document.file_name.clone(),
+            mime_type: document
+                .mime_type
+                .clone()
+                .unwrap_or_else(|| "application/octet-stream".to_string()),
+            size: document.file_size,
+        });
+        photo.into_iter().chain(document).collect()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TgPhotoSize {
+    file_id: String,
+    #[serde(default)]
+    file_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TgDocument {
+    file_id: String,
+    #[serde(default)]
+    file_name: Option<String>,
+    #[serde(default)]
+    mime_type: Option<String>,
+    #[serde(default)]
+    file_size: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TgFile {
+    #[serde(default)]
+    file_path: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
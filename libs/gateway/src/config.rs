@@ -5,7 +5,17 @@ use serde::{Deserialize, Serialize};
 use stakpak_shared::utils::normalize_optional_string;
 use thiserror::Error;
 
-use crate::router::{Binding, BindingMatch, DmScope, PeerMatch, PeerMatchKind, RouterConfig};
+use crate::attachments::{
+    AttachmentPolicy, DEFAULT_MAX_ATTACHMENT_BYTES, default_allowed_attachment_types,
+};
+use crate::router::{
+    Binding, BindingMatch, BindingOverrides, DmScope, PeerMatch, PeerMatchKind, RouterConfig,
+};
+use crate::types::ReplyMode;
+
+// Telegram shows a typing indicator for 5 seconds and Discord for 10, so it is
+// refreshed a little before the shorter one expires.
+const DEFAULT_TYPING_INTERVAL_SECS: u64 = 4;
 
 #[derive(Debug, Clone, Default)]
 pub struct GatewayCliFlags {
@@ -41,6 +51,13 @@ pub struct GatewaySettings {
     pub delivery_context_ttl_hours: u64,
     pub approval_mode: ApprovalMode,
     pub approval_allowlist: Vec<String>,
+    /// Seconds between typing indicators while a run is in progress (0 disables them)
+    pub typing_interval_secs: u64,
+    pub reply_mode: ReplyMode,
+    /// Largest attachment accepted on an inbound message, in bytes
+    pub max_attachment_bytes: u64,
+    /// MIME types accepted as attachments (`image/*` wildcards allowed)
+    pub allowed_attachment_types: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
@@ -78,6 +95,10 @@ impl Default for GatewaySettings {
             delivery_context_ttl_hours: 4,
             approval_mode: ApprovalMode::AllowAll,
             approval_allowlist: Vec::new(),
+            typing_interval_secs: DEFAULT_TYPING_INTERVAL_SECS,
+            reply_mode: ReplyMode::Chunked,
+            max_attachment_bytes: DEFAULT_MAX_ATTACHMENT_BYTES,
+            allowed_attachment_types: default_allowed_attachment_types(),
         }
     }
 }
@@ -124,6 +145,12 @@ pub struct BindingConfig {
     pub direct: Option<String>,
     #[serde(default)]
     pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_steps: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_mode: Option<ApprovalMode>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -169,7 +196,14 @@ pub struct DiscordConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SlackConfig {
     pub bot_token: String,
+    /// Socket Mode app token. May be empty when `signing_secret` is set and
+    /// events arrive over HTTP instead.
+    #[serde(default)]
     pub app_token: String,
+    /// Signing secret used to verify Events API requests sent to the gateway's
+    /// `/webhooks/slack` endpoint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_secret: Option<String>,
     #[serde(default)]
     pub model: Option<String>,
     #[serde(default)]
@@ -294,6 +328,36 @@ impl GatewayConfig {
                         .collect(),
                 ),
             );
+            gateway.insert(
+                "typing_interval_secs".to_string(),
+                toml::Value::Integer(
+                    i64::try_from(self.gateway.typing_interval_secs)
+                        .map_err(|_| anyhow!("typing_interval_secs exceeds i64 range"))?,
+                ),
+            );
+            gateway.insert(
+                "reply_mode".to_string(),
+                toml::Value::try_from(self.gateway.reply_mode)
+                    .map_err(|error| anyhow!("failed to serialize reply_mode: {error}"))?,
+            );
+            gateway.insert(
+                "max_attachment_bytes".to_string(),
+                toml::Value::Integer(
+                    i64::try_from(self.gateway.max_attachment_bytes)
+                        .map_err(|_| anyhow!("max_attachment_bytes exceeds i64 range"))?,
+                ),
+            );
+            gateway.insert(
+                "allowed_attachment_types".to_string(),
+                toml::Value::Array(
+                    self.gateway
+                        .allowed_attachment_types
+                        .iter()
+                        .cloned()
+                        .map(toml::Value::String)
+                        .collect(),
+                ),
+            );
         }
 
         {
@@ -351,7 +415,9 @@ impl GatewayConfig {
             if slack.bot_token.trim().is_empty() {
                 return Err(GatewayConfigValidationError::EmptySlackBotToken);
             }
-            if slack.app_token.trim().is_empty() {
+            if slack.app_token.trim().is_empty()
+                && normalize_optional_string(slack.signing_secret.clone()).is_none()
+            {
                 return Err(GatewayConfigValidationError::EmptySlackAppToken);
             }
         }
@@ -405,6 +471,13 @@ impl GatewayConfig {
         warnings
     }
 
+    pub fn attachment_policy(&self) -> AttachmentPolicy {
+        AttachmentPolicy {
+            max_bytes: self.gateway.max_attachment_bytes,
+            allowed_types: self.gateway.allowed_attachment_types.clone(),
+        }
+    }
+
     pub fn router_config(&self) -> RouterConfig {
         let bindings = self
             .routing
@@ -484,6 +557,7 @@ impl GatewayConfig {
                 self.channels.slack = Some(SlackConfig {
                     bot_token,
                     app_token,
+                    signing_secret: std::env::var("SLACK_SIGNING_SECRET").ok(),
                     model: None,
                     auto_approve: None,
                     profile: None,
@@ -562,6 +636,11 @@ impl GatewayConfig {
             self.channels.slack = Some(SlackConfig {
                 bot_token: bot_token.clone(),
                 app_token: app_token.clone(),
+                signing_secret: self
+                    .channels
+                    .slack
+                    .as_ref()
+                    .and_then(|value| value.signing_secret.clone()),
                 model: self
                     .channels
                     .slack
@@ -858,6 +937,19 @@ impl PersistedGatewayConfig {
                 delivery_context_ttl_hours: self.gateway.delivery_context_ttl_hours.unwrap_or(4),
                 approval_mode: self.gateway.approval_mode.unwrap_or_default(),
                 approval_allowlist: self.gateway.approval_allowlist.unwrap_or_default(),
+                typing_interval_secs: self
+                    .gateway
+                    .typing_interval_secs
+                    .unwrap_or(DEFAULT_TYPING_INTERVAL_SECS),
+                reply_mode: self.gateway.reply_mode.unwrap_or_default(),
+                max_attachment_bytes: self
+                    .gateway
+                    .max_attachment_bytes
+                    .unwrap_or(DEFAULT_MAX_ATTACHMENT_BYTES),
+                allowed_attachment_types: self
+                    .gateway
+                    .allowed_attachment_types
+                    .unwrap_or_else(default_allowed_attachment_types),
             },
             routing: RoutingConfig {
                 dm_scope: self.routing.dm_scope.unwrap_or_default(),
@@ -896,6 +988,14 @@ struct PersistedGatewaySettings {
     approval_mode: Option<ApprovalMode>,
     #[serde(default)]
     approval_allowlist: Option<Vec<String>>,
+    #[serde(default)]
+    typing_interval_secs: Option<u64>,
+    #[serde(default)]
+    reply_mode: Option<ReplyMode>,
+    #[serde(default)]
+    max_attachment_bytes: Option<u64>,
+    #[serde(default)]
+    allowed_attachment_types: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
@@ -937,6 +1037,11 @@ fn binding_to_runtime(binding: &BindingConfig) -> Binding {
             peer,
         },
         routing_key: binding.routing_key.clone(),
+        overrides: BindingOverrides {
+            model: normalize_optional_string(binding.model.clone()),
+            max_steps: binding.max_steps,
+            approval_mode: binding.approval_mode.clone(),
+        },
     }
 }
 
@@ -1147,6 +1252,7 @@ mod tests {
         config.channels.slack = Some(super::SlackConfig {
             bot_token: "xoxb-token".to_string(),
             app_token: "xapp-token".to_string(),
+            signing_secret: None,
             model: Some("anthropic/claude-sonnet-4-5".to_string()),
             auto_approve: None,
             profile: Some("ops".to_string()),
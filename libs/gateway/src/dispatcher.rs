@@ -5,7 +5,6 @@ use std::{
 };
 
 use chrono::Utc;
-use stakai::{Message, Role};
 use stakpak_agent_core::ProposedToolCall;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
@@ -15,6 +14,7 @@ use uuid::Uuid;
 use stakpak_shared::utils::truncate_chars_with_ellipsis;
 
 use crate::{
+    attachments::{AttachmentPolicy, render_rejection_notice, take_skipped, user_message},
     channels::{ApprovalButton, ButtonStyle, Channel},
     client::{
         AutoApproveOverride, CallerContextInput, MessageType, RunErrorPayload, RunOverrides,
@@ -22,12 +22,18 @@ use crate::{
         ToolDecisionInput,
     },
     config::{ApprovalMode, ChannelOverrides},
-    router::{RouterConfig, resolve_routing_key},
+    progressive::ProgressiveReply,
+    router::{RouterConfig, resolve_binding, resolve_routing_key},
     store::{GatewayStore, SessionMapping},
     targeting::{render_title_template, target_key_from_inbound},
-    types::{ChatType, DeliveryContext, InboundMessage, OutboundReply, PeerId},
+    types::{ChatType, DeliveryContext, InboundMessage, OutboundReply, PeerId, ReplyMode},
 };
 
+// Chat APIs allow roughly one edit per second per conversation.
+const PROGRESSIVE_EDIT_DEBOUNCE: Duration = Duration::from_millis(1500);
+// Below Discord's 2000-character message limit, the smallest of the channels.
+const PROGRESSIVE_MESSAGE_LIMIT: usize = 1900;
+
 pub trait RunOverrideResolver: Send + Sync {
     fn resolve_run_overrides(&self, profile_name: &str) -> Option<RunOverrides>;
 }
@@ -66,6 +72,9 @@ pub struct Dispatcher {
     channel_profiles: HashMap<String, String>,
     override_resolver: Arc<dyn RunOverrideResolver>,
     title_template: String,
+    typing_interval: Option<Duration>,
+    reply_mode: ReplyMode,
+    attachment_policy: AttachmentPolicy,
 }
 
 #[derive(Debug, Clone)]
@@ -74,6 +83,9 @@ struct ActiveRun {
     cancel: CancellationToken,
     approval_mode: ApprovalMode,
     approval_allowlist: HashSet<String>,
+    delivery: DeliveryContext,
+    // Message keys (see `message_key`) of the channel messages this run answers.
+    trigger_messages: HashSet<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -125,6 +137,7 @@ struct QueuedMessage {
     text: String,
     run_options: RunStartOptions,
     context: Vec<CallerContextInput>,
+    trigger_messages: HashSet<String>,
 }
 
 #[derive(Debug)]
@@ -141,6 +154,7 @@ struct RunContext {
     session_id: String,
     run_id: String,
     timeout_seconds: Option<u64>,
+    reply_mode: ReplyMode,
 }
 
 #[derive(Debug)]
@@ -204,9 +218,29 @@ impl Dispatcher {
             channel_profiles: HashMap::new(),
             override_resolver: Arc::new(NoopRunOverrideResolver),
             title_template,
+            typing_interval: None,
+            reply_mode: ReplyMode::Chunked,
+            attachment_policy: AttachmentPolicy::default(),
         }
     }
 
+    pub fn with_attachment_policy(mut self, attachment_policy: AttachmentPolicy) -> Self {
+        self.attachment_policy = attachment_policy;
+        self
+    }
+
+    pub fn with_reply_mode(mut self, reply_mode: ReplyMode) -> Self {
+        self.reply_mode = reply_mode;
+        self
+    }
+
+    /// Refresh the channel's typing indicator every `interval` while a run is
+    /// in progress. A zero interval disables the indicator.
+    pub fn with_typing_interval(mut self, interval: Duration) -> Self {
+        self.typing_interval = (!interval.is_zero()).then_some(interval);
+        self
+    }
+
     pub fn with_profile_resolution(
         mut self,
         channel_profiles: HashMap<String, String>,
@@ -265,6 +299,35 @@ impl Dispatcher {
             return Ok(());
         }
 
+        if inbound
+            .metadata
+            .get("type")
+            .and_then(|value| value.as_str())
+            == Some("cancel")
+        {
+            return self.handle_cancel_signal(inbound, run_tx).await;
+        }
+
+        if is_reset_command(&inbound.text) {
+            return self.handle_reset(inbound).await;
+        }
+
+        let mut inbound = inbound;
+        let mut rejections = take_skipped(&mut inbound.metadata);
+        rejections.extend(self.attachment_policy.filter(&mut inbound.media));
+        if !rejections.is_empty() {
+            let delivery = self.delivery_context_from_inbound(&inbound);
+            deliver_channel_text(
+                &self.channels,
+                &delivery,
+                render_rejection_notice(&rejections),
+            )
+            .await;
+            if inbound.text.trim().is_empty() && inbound.media.is_empty() {
+                return Ok(());
+            }
+        }
+
         let routing_key = resolve_routing_key(
             &self.router_config,
             &inbound.channel,
@@ -327,6 +390,7 @@ impl Dispatcher {
             text: inbound.text.clone(),
             run_options,
             context: caller_context,
+            trigger_messages: message_key(&inbound.metadata).into_iter().collect(),
             inbound,
         };
 
@@ -340,6 +404,52 @@ impl Dispatcher {
         self.start_run(mapping.session_id, queued, run_tx).await
     }
 
+    /// Forget the session mapped to the message's conversation so the next
+    /// message starts a fresh session. Refused while a run is in progress.
+    async fn handle_reset(&self, inbound: InboundMessage) -> Result<(), String> {
+        let routing_key = resolve_routing_key(
+            &self.router_config,
+            &inbound.channel,
+            &inbound.peer_id,
+            &inbound.chat_type,
+        );
+        let delivery = self.delivery_context_from_inbound(&inbound);
+
+        let mapping = self
+            .store
+            .get(&routing_key)
+            .await
+            .map_err(|error| format!("failed to get mapping: {error}"))?;
+
+        if let Some(mapping) = mapping.as_ref()
+            && self.is_run_active(&mapping.session_id)
+        {
+            deliver_channel_text(
+                &self.channels,
+                &delivery,
+                "⏳ A run is still in progress. Reset again once it has finished.",
+            )
+            .await;
+            return Ok(());
+        }
+
+        if mapping.is_some() {
+            self.store
+                .delete(&routing_key)
+                .await
+                .map_err(|error| format!("failed to delete mapping: {error}"))?;
+            info!(routing_key = %routing_key, "session reset from channel");
+        }
+
+        deliver_channel_text(
+            &self.channels,
+            &delivery,
+            "🔄 Session reset. Your next message starts a fresh conversation.",
+        )
+        .await;
+        Ok(())
+    }
+
     async fn handle_approval_response(
         self: &Arc<Self>,
         inbound: InboundMessage,
@@ -415,6 +525,105 @@ impl Dispatcher {
         Ok(())
     }
 
+    async fn handle_cancel_signal(
+        self: &Arc<Self>,
+        inbound: InboundMessage,
+        run_tx: mpsc::Sender<RunTaskResult>,
+    ) -> Result<(), String> {
+        let Some(key) = message_key(&inbound.metadata) else {
+            warn!(channel = %inbound.channel, "ignoring cancel signal without a message id");
+            return Ok(());
+        };
+
+        {
+            let mut guard = self
+                .pending_queues
+                .lock()
+                .map_err(|_| "failed to lock pending_queues".to_string())?;
+            for queue in guard.values_mut() {
+                queue.retain(|queued| {
+                    queued.inbound.channel != inbound.channel
+                        || !queued.trigger_messages.contains(&key)
+                });
+            }
+            guard.retain(|_, queue| !queue.is_empty());
+        }
+
+        let target = {
+            let guard = self
+                .active_runs
+                .lock()
+                .map_err(|_| "failed to lock active_runs".to_string())?;
+            guard.iter().find_map(|(session_id, active)| {
+                (active.delivery.channel == inbound.channel
+                    && active.trigger_messages.contains(&key))
+                .then(|| (session_id.clone(), active.clone()))
+            })
+        };
+
+        let Some((session_id, active)) = target else {
+            debug!(channel = %inbound.channel, message = %key, "no active run for cancelled message");
+            return Ok(());
+        };
+
+        let reason = render_cancel_reason(
+            inbound
+                .metadata
+                .get("reason")
+                .and_then(|value| value.as_str()),
+        );
+        info!(
+            session_id = %session_id,
+            run_id = %active.run_id,
+            reason = %reason,
+            "cancelling run: triggering message withdrawn"
+        );
+
+        active.cancel.cancel();
+        if let Err(error) = self.client.cancel_run(&session_id, &active.run_id).await {
+            warn!(error = %error, session_id = %session_id, run_id = %active.run_id, "cancel_run failed");
+        }
+
+        deliver_channel_text(
+            &self.channels,
+            &active.delivery,
+            format!("⏹️ Run cancelled — {reason}"),
+        )
+        .await;
+
+        // A run paused on an approval has no event consumer left to observe the
+        // cancellation token, so it is settled here instead of in `handle_run_result`.
+        let pending = {
+            let mut guard = self
+                .pending_approvals
+                .lock()
+                .map_err(|_| "failed to lock pending_approvals".to_string())?;
+            guard.remove(&session_id)
+        };
+
+        let Some(pending) = pending else {
+            return Ok(());
+        };
+
+        if let Some(channel) = self.channels.get(&pending.channel_name)
+            && let Err(error) = channel
+                .edit_message(
+                    &pending.prompt_message_id,
+                    "⏹️ Tools skipped — run cancelled",
+                )
+                .await
+        {
+            warn!(error = %error, "failed to edit approval prompt after cancel");
+        }
+
+        self.remove_active_run(&session_id, &active.run_id);
+        if let Some(cursor) = pending.cursor {
+            self.set_cursor(&session_id, cursor)?;
+        }
+
+        self.drain_queue(&session_id, run_tx).await
+    }
+
     async fn handle_run_result(
         self: &Arc<Self>,
         result: RunTaskResult,
@@ -849,6 +1058,18 @@ impl Dispatcher {
         let session_id_for_task = run_context.session_id.clone();
         let run_id_for_task = run_context.run_id.clone();
 
+        let typing_stop = cancel.child_token();
+        if let Some(interval) = self.typing_interval
+            && let Some(channel) = self.channels.get(&run_context.delivery.channel.0)
+        {
+            tokio::spawn(keep_typing(
+                channel.clone(),
+                run_context.delivery.clone(),
+                interval,
+                typing_stop.clone(),
+            ));
+        }
+
         tokio::spawn(async move {
             let outcome = consume_run_events(
                 client,
@@ -859,6 +1080,7 @@ impl Dispatcher {
                 cancel,
             )
             .await;
+            typing_stop.cancel();
 
             if let Err(error) = run_tx
                 .send(RunTaskResult {
@@ -880,7 +1102,7 @@ impl Dispatcher {
         run_tx: mpsc::Sender<RunTaskResult>,
     ) -> Result<(), String> {
         let channel_name = queued.inbound.channel.0.clone();
-        let run_overrides = self.build_run_overrides(&channel_name);
+        let run_overrides = self.build_inbound_run_overrides(&queued.inbound);
         let (run_approval_mode, run_approval_allowlist) =
             self.resolve_run_approval(&channel_name, run_overrides.as_ref());
 
@@ -894,7 +1116,7 @@ impl Dispatcher {
             self.resolve_effective_model(&channel_name, queued.run_options.model.clone())
         };
 
-        let message = Message::new(Role::User, queued.text.clone());
+        let message = user_message(&queued.text, &queued.inbound.media);
         let response = self
             .client
             .send_messages(
@@ -922,6 +1144,7 @@ impl Dispatcher {
 
         let run_id = response.run_id.to_string();
         let cancel = CancellationToken::new();
+        let delivery = self.delivery_context_from_inbound(&queued.inbound);
 
         {
             let mut guard = self
@@ -935,16 +1158,19 @@ impl Dispatcher {
                     cancel: cancel.clone(),
                     approval_mode: run_approval_mode.clone(),
                     approval_allowlist: run_approval_allowlist.clone(),
+                    delivery: delivery.clone(),
+                    trigger_messages: queued.trigger_messages.clone(),
                 },
             );
         }
 
         let run_context = RunContext {
             channels: self.channels.clone(),
-            delivery: self.delivery_context_from_inbound(&queued.inbound),
+            delivery,
             session_id: session_id.clone(),
             run_id,
             timeout_seconds: queued.run_options.timeout_seconds,
+            reply_mode: self.reply_mode,
         };
 
         let last_event_id = self.get_cursor(&session_id)?;
@@ -997,6 +1223,7 @@ impl Dispatcher {
             session_id: session_id.to_string(),
             run_id: run_id.to_string(),
             timeout_seconds,
+            reply_mode: self.reply_mode,
         };
 
         self.spawn_run_consumer(
@@ -1051,6 +1278,10 @@ impl Dispatcher {
             text: combined_text,
             run_options: latest.run_options.clone(),
             context: combined_context,
+            trigger_messages: queue
+                .iter()
+                .flat_map(|queued| queued.trigger_messages.iter().cloned())
+                .collect(),
         };
 
         if let Err(error) = self.start_run(session_id.to_string(), queued, run_tx).await {
@@ -1080,6 +1311,14 @@ impl Dispatcher {
         }
     }
 
+    /// Sessions with a run in progress, whose mappings must survive pruning.
+    pub fn active_session_ids(&self) -> HashSet<String> {
+        self.active_runs
+            .lock()
+            .map(|guard| guard.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
     pub fn is_run_active(&self, session_id: &str) -> bool {
         self.active_runs
             .lock()
@@ -1259,6 +1498,53 @@ impl Dispatcher {
         (approval_mode, approval_allowlist)
     }
 
+    /// Run overrides for an inbound message: the settings of its matching
+    /// binding layered over the channel's.
+    fn build_inbound_run_overrides(&self, inbound: &InboundMessage) -> Option<RunOverrides> {
+        let channel_name = inbound.channel.0.as_str();
+        let run_overrides = self.build_run_overrides(channel_name);
+        let Some(binding) = resolve_binding(
+            &self.router_config,
+            &inbound.channel,
+            &inbound.peer_id,
+            &inbound.chat_type,
+        ) else {
+            return run_overrides;
+        };
+
+        let binding = &binding.overrides;
+        let mut overrides = run_overrides.unwrap_or_default();
+        if binding.model.is_some() {
+            overrides.model = binding.model.clone();
+        }
+        if binding.max_steps.is_some() {
+            overrides.max_turns = binding.max_steps;
+        }
+        if let Some(approval_mode) = binding.approval_mode.as_ref() {
+            overrides.auto_approve = Some(match approval_mode {
+                ApprovalMode::AllowAll => AutoApproveOverride::Mode("all".to_string()),
+                ApprovalMode::DenyAll => AutoApproveOverride::Mode("none".to_string()),
+                ApprovalMode::Allowlist => match overrides.auto_approve.take() {
+                    Some(AutoApproveOverride::AllowList(tools)) => {
+                        AutoApproveOverride::AllowList(tools)
+                    }
+                    _ => {
+                        let (_, allowlist) = self.resolve_channel_approval(channel_name);
+                        let mut tools = allowlist.into_iter().collect::<Vec<_>>();
+                        tools.sort();
+                        AutoApproveOverride::AllowList(tools)
+                    }
+                },
+            });
+        }
+
+        if overrides.is_empty() {
+            None
+        } else {
+            Some(overrides)
+        }
+    }
+
     fn build_run_overrides(&self, channel_name: &str) -> Option<RunOverrides> {
         if let Some(profile_name) = self.channel_profiles.get(channel_name)
             && let Some(overrides) = self.override_resolver.resolve_run_overrides(profile_name)
@@ -1348,6 +1634,20 @@ async fn consume_run_events(
     };
 
     let mut streamed_buffer = String::new();
+    let mut progressive = match run_context.reply_mode {
+        ReplyMode::Chunked => None,
+        ReplyMode::Progressive => run_context
+            .channels
+            .get(&run_context.delivery.channel.0)
+            .map(|channel| {
+                ProgressiveReply::new(
+                    channel.clone(),
+                    &run_context.delivery,
+                    PROGRESSIVE_EDIT_DEBOUNCE,
+                    PROGRESSIVE_MESSAGE_LIMIT,
+                )
+            }),
+    };
     let mut last_stream_at = Instant::now();
     let mut cursor = last_event_id;
     let timeout_deadline = run_context
@@ -1368,7 +1668,7 @@ async fn consume_run_events(
                 return RunOutcome::Cancelled { cursor };
             }
             _ = &mut timeout_future => {
-                flush_stream_buffer(&run_context.channels, &run_context.delivery, &mut streamed_buffer, true).await;
+                finish_streamed_text(&run_context, &mut streamed_buffer, progressive.as_mut()).await;
                 deliver_channel_text(&run_context.channels, &run_context.delivery, "⏱️ Interactive run timed out.").await;
                 return RunOutcome::Error {
                     error: Some(RunErrorPayload {
@@ -1382,11 +1682,11 @@ async fn consume_run_events(
                 let event = match next {
                     Ok(Some(event)) => event,
                     Ok(None) => {
-                        flush_stream_buffer(&run_context.channels, &run_context.delivery, &mut streamed_buffer, true).await;
+                        finish_streamed_text(&run_context, &mut streamed_buffer, progressive.as_mut()).await;
                         return RunOutcome::StreamEnded { cursor };
                     }
                     Err(error) => {
-                        flush_stream_buffer(&run_context.channels, &run_context.delivery, &mut streamed_buffer, true).await;
+                        finish_streamed_text(&run_context, &mut streamed_buffer, progressive.as_mut()).await;
                         warn!(error = %error, "run event stream read failed");
                         return RunOutcome::Error {
                             error: None,
@@ -1406,6 +1706,11 @@ async fn consume_run_events(
                 match event.event_type.as_str() {
                     "text_delta" => {
                         if let Some(delta) = event.as_text_delta() {
+                            if let Some(progressive) = progressive.as_mut() {
+                                progressive.push(&delta).await;
+                                continue;
+                            }
+
                             streamed_buffer.push_str(&delta);
 
                             if should_flush_stream_buffer(&streamed_buffer, last_stream_at.elapsed()) {
@@ -1416,7 +1721,7 @@ async fn consume_run_events(
                     }
                     "tool_calls_proposed" => {
                         if let Some(proposed) = event.as_tool_calls_proposed() {
-                            flush_stream_buffer(&run_context.channels, &run_context.delivery, &mut streamed_buffer, true).await;
+                            finish_streamed_text(&run_context, &mut streamed_buffer, progressive.as_mut()).await;
 
                             match approval_mode {
                                 ApprovalMode::Allowlist => {
@@ -1496,17 +1801,12 @@ async fn consume_run_events(
                         }
                     }
                     "run_completed" => {
-                        flush_stream_buffer(&run_context.channels, &run_context.delivery, &mut streamed_buffer, true).await;
+                        finish_streamed_text(&run_context, &mut streamed_buffer, progressive.as_mut()).await;
                         return RunOutcome::Completed { cursor };
                     }
                     "run_error" => {
-                        flush_stream_buffer(
-                            &run_context.channels,
-                            &run_context.delivery,
-                            &mut streamed_buffer,
-                            true,
-                        )
-                        .await;
+                        finish_streamed_text(&run_context, &mut streamed_buffer, progressive.as_mut())
+                            .await;
                         let payload = event.as_run_error();
                         let error_text = payload
                             .as_ref()
@@ -1585,6 +1885,19 @@ fn last_safe_markdown_split(buffer: &str) -> Option<usize> {
     last_safe_split
 }
 
+async fn finish_streamed_text(
+    run_context: &RunContext,
+    buffer: &mut String,
+    progressive: Option<&mut ProgressiveReply>,
+) {
+    match progressive {
+        Some(progressive) => progressive.finish().await,
+        None => {
+            flush_stream_buffer(&run_context.channels, &run_context.delivery, buffer, true).await
+        }
+    }
+}
+
 async fn flush_stream_buffer(
     channels: &HashMap<String, Arc<dyn Channel>>,
     delivery: &DeliveryContext,
@@ -1612,6 +1925,37 @@ async fn flush_stream_buffer(
     deliver_channel_text(channels, delivery, text.trim()).await;
 }
 
+/// Send typing indicators every `interval` until `stop` is cancelled, giving
+/// up quietly on channels that have none.
+async fn keep_typing(
+    channel: Arc<dyn Channel>,
+    delivery: DeliveryContext,
+    interval: Duration,
+    stop: CancellationToken,
+) {
+    let reply = OutboundReply {
+        channel: delivery.channel,
+        peer_id: delivery.peer_id,
+        chat_type: delivery.chat_type,
+        text: String::new(),
+        metadata: delivery.channel_meta,
+    };
+
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = stop.cancelled() => return,
+            _ = ticker.tick() => {
+                if let Err(error) = channel.send_typing(reply.clone()).await {
+                    debug!(channel = %reply.channel, error = %error, "typing indicator unavailable");
+                    return;
+                }
+            }
+        }
+    }
+}
+
 async fn deliver_channel_text(
     channels: &HashMap<String, Arc<dyn Channel>>,
     delivery: &DeliveryContext,
@@ -1635,6 +1979,43 @@ async fn deliver_channel_text(
     }
 }
 
+/// Whether `text` is the in-channel `/reset` command. Telegram's
+/// `/reset@botname` form is accepted too.
+fn is_reset_command(text: &str) -> bool {
+    let text = text.trim();
+    text.eq_ignore_ascii_case("/reset")
+        || text.split_once('@').is_some_and(|(command, bot)| {
+            command.eq_ignore_ascii_case("/reset")
+                && !bot.is_empty()
+                && !bot.contains(char::is_whitespace)
+        })
+}
+
+/// Identify the channel message described by `metadata` as
+/// `<conversation>:<message>`, so a later delete or edit of the same message
+/// can be matched to the run it started.
+fn message_key(metadata: &serde_json::Value) -> Option<String> {
+    let field = |keys: &[&str]| {
+        keys.iter().find_map(|key| match metadata.get(*key)? {
+            serde_json::Value::String(text) if !text.is_empty() => Some(text.clone()),
+            serde_json::Value::Number(number) => Some(number.to_string()),
+            _ => None,
+        })
+    };
+
+    let conversation = field(&["chat_id", "channel_id", "channel"])?;
+    let message = field(&["message_id", "ts"])?;
+    Some(format!("{conversation}:{message}"))
+}
+
+fn render_cancel_reason(reason: Option<&str>) -> &'static str {
+    match reason {
+        Some("message_deleted") => "the original message was deleted",
+        Some("message_edited") => "the original message was edited",
+        _ => "cancel requested",
+    }
+}
+
 fn format_batched_queue_messages(queue: &[QueuedMessage]) -> String {
     if queue.len() <= 1 {
         return queue
@@ -2182,9 +2563,12 @@ mod tests {
         channels::{Channel, ChannelTestResult},
         client::{CallerContextInput, StakpakClient},
         config::{ApprovalMode, ChannelOverrides},
-        router::RouterConfig,
+        router::{Binding, BindingMatch, BindingOverrides, PeerMatch, PeerMatchKind, RouterConfig},
         store::GatewayStore,
-        types::{ChannelId, ChatType, DeliveryContext, InboundMessage, OutboundReply, PeerId},
+        types::{
+            ChannelId, ChatType, DeliveryContext, InboundMessage, MediaAttachment, OutboundReply,
+            PeerId,
+        },
     };
     use anyhow::Result;
     use async_trait::async_trait;
@@ -2226,6 +2610,7 @@ mod tests {
             text: text.to_string(),
             run_options: RunStartOptions::default(),
             context: Vec::new(),
+            trigger_messages: HashSet::new(),
         }
     }
 
@@ -2240,6 +2625,8 @@ mod tests {
     struct TestChannel {
         id: ChannelId,
         edits: Arc<AsyncMutex<Vec<(String, String)>>>,
+        sent: Arc<AsyncMutex<Vec<String>>>,
+        typing: Arc<AsyncMutex<Vec<Instant>>>,
     }
 
     impl TestChannel {
@@ -2247,6 +2634,8 @@ mod tests {
             Self {
                 id: ChannelId(id.to_string()),
                 edits: Arc::new(AsyncMutex::new(Vec::new())),
+                sent: Arc::new(AsyncMutex::new(Vec::new())),
+                typing: Arc::new(AsyncMutex::new(Vec::new())),
             }
         }
     }
@@ -2269,7 +2658,8 @@ mod tests {
             Ok(())
         }
 
-        async fn send(&self, _reply: OutboundReply) -> Result<()> {
+        async fn send(&self, reply: OutboundReply) -> Result<()> {
+            self.sent.lock().await.push(reply.text);
             Ok(())
         }
 
@@ -2281,6 +2671,11 @@ mod tests {
             Ok(())
         }
 
+        async fn send_typing(&self, _reply: OutboundReply) -> Result<()> {
+            self.typing.lock().await.push(Instant::now());
+            Ok(())
+        }
+
         async fn test(&self) -> Result<ChannelTestResult> {
             Ok(ChannelTestResult {
                 channel: self.id.0.clone(),
@@ -2299,6 +2694,36 @@ mod tests {
         StatusCode::OK
     }
 
+    async fn test_cancel_handler(
+        State(state): State<TestServerState>,
+        Path(_session_id): Path<String>,
+        Json(payload): Json<serde_json::Value>,
+    ) -> StatusCode {
+        state.resolve_payloads.lock().await.push(payload);
+        StatusCode::OK
+    }
+
+    async fn test_pending_events_handler(
+        Path(_session_id): Path<String>,
+    ) -> Sse<impl futures_util::Stream<Item = std::result::Result<Event, Infallible>>> {
+        Sse::new(stream::pending::<std::result::Result<Event, Infallible>>())
+    }
+
+    async fn test_delayed_events_handler(
+        State(state): State<TestServerState>,
+        Path(_session_id): Path<String>,
+    ) -> Sse<impl futures_util::Stream<Item = std::result::Result<Event, Infallible>>> {
+        let event = Event::default()
+            .id("3")
+            .event("run_completed")
+            .data(serde_json::json!({"run_id": state.run_id}).to_string());
+
+        Sse::new(stream::once(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            Ok::<Event, Infallible>(event)
+        }))
+    }
+
     async fn test_events_handler(
         State(state): State<TestServerState>,
         Path(_session_id): Path<String>,
@@ -2489,6 +2914,14 @@ mod tests {
                     cancel: CancellationToken::new(),
                     approval_mode: ApprovalMode::Allowlist,
                     approval_allowlist: HashSet::new(),
+                    delivery: DeliveryContext {
+                        channel: ChannelId("slack".to_string()),
+                        peer_id: PeerId("u1".to_string()),
+                        chat_type: ChatType::Direct,
+                        channel_meta: serde_json::json!({"channel": "C123"}),
+                        updated_at: Utc::now().timestamp_millis(),
+                    },
+                    trigger_messages: HashSet::new(),
                 },
             );
 
@@ -2582,6 +3015,450 @@ mod tests {
         server_handle.abort();
     }
 
+    #[tokio::test]
+    async fn deleting_triggering_message_cancels_running_run() {
+        let server_state = TestServerState {
+            run_id: "run-1".to_string(),
+            resolve_payloads: Arc::new(AsyncMutex::new(Vec::new())),
+            last_event_ids: Arc::new(AsyncMutex::new(Vec::new())),
+        };
+
+        let app = Router::new()
+            .route(
+                "/v1/sessions/{session_id}/cancel",
+                post(test_cancel_handler),
+            )
+            .route(
+                "/v1/sessions/{session_id}/events",
+                get(test_pending_events_handler),
+            )
+            .with_state(server_state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test listener");
+        let addr = listener.local_addr().expect("read listener addr");
+        let server_handle = tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let store = Arc::new(
+            GatewayStore::open_in_memory()
+                .await
+                .expect("open in-memory gateway store"),
+        );
+
+        let test_channel = Arc::new(TestChannel::new("discord"));
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("discord".to_string(), test_channel.clone());
+
+        let dispatcher = Arc::new(Dispatcher::new(
+            StakpakClient::new(format!("http://{addr}"), String::new()),
+            channels,
+            store,
+            RouterConfig::default(),
+            None,
+            ApprovalMode::Allowlist,
+            Vec::new(),
+            HashMap::new(),
+            "{channel}-{peer}".to_string(),
+        ));
+
+        let session_id = "session-1".to_string();
+        let run_id = "run-1".to_string();
+        let delivery = DeliveryContext {
+            channel: ChannelId("discord".to_string()),
+            peer_id: PeerId("u1".to_string()),
+            chat_type: ChatType::Direct,
+            channel_meta: serde_json::json!({"channel_id": "D1", "message_id": "m-1"}),
+            updated_at: Utc::now().timestamp_millis(),
+        };
+        let cancel = CancellationToken::new();
+
+        dispatcher
+            .active_runs
+            .lock()
+            .expect("lock active_runs")
+            .insert(
+                session_id.clone(),
+                ActiveRun {
+                    run_id: run_id.clone(),
+                    cancel: cancel.clone(),
+                    approval_mode: ApprovalMode::Allowlist,
+                    approval_allowlist: HashSet::new(),
+                    delivery: delivery.clone(),
+                    trigger_messages: HashSet::from(["D1:m-1".to_string()]),
+                },
+            );
+
+        let (run_tx, mut run_rx) = mpsc::channel(8);
+        dispatcher.spawn_run_consumer(
+            RunContext {
+                channels: dispatcher.channels.clone(),
+                delivery,
+                session_id: session_id.clone(),
+                run_id: run_id.clone(),
+                timeout_seconds: None,
+                reply_mode: ReplyMode::Chunked,
+            },
+            None,
+            ApprovalMode::Allowlist,
+            HashSet::new(),
+            cancel.clone(),
+            run_tx.clone(),
+        );
+
+        let other_message = InboundMessage {
+            channel: ChannelId("discord".to_string()),
+            peer_id: PeerId("unknown".to_string()),
+            chat_type: ChatType::Direct,
+            text: String::new(),
+            media: Vec::new(),
+            metadata: serde_json::json!({
+                "type": "cancel",
+                "reason": "message_deleted",
+                "channel_id": "D1",
+                "message_id": "m-2",
+            }),
+            timestamp: Utc::now(),
+        };
+        dispatcher
+            .handle_inbound(other_message, run_tx.clone())
+            .await
+            .expect("handle unrelated cancel signal");
+        assert!(
+            !cancel.is_cancelled(),
+            "deleting another message must not cancel the run"
+        );
+
+        let cancel_signal = InboundMessage {
+            channel: ChannelId("discord".to_string()),
+            peer_id: PeerId("unknown".to_string()),
+            chat_type: ChatType::Direct,
+            text: String::new(),
+            media: Vec::new(),
+            metadata: serde_json::json!({
+                "type": "cancel",
+                "reason": "message_deleted",
+                "channel_id": "D1",
+                "message_id": "m-1",
+            }),
+            timestamp: Utc::now(),
+        };
+        dispatcher
+            .handle_inbound(cancel_signal, run_tx)
+            .await
+            .expect("handle cancel signal");
+
+        let run_result = tokio::time::timeout(Duration::from_secs(3), run_rx.recv())
+            .await
+            .expect("timed out waiting for cancelled run result")
+            .expect("expected cancelled run result");
+        assert_eq!(run_result.run_id, run_id);
+        assert!(matches!(run_result.outcome, RunOutcome::Cancelled { .. }));
+
+        let cancel_payloads = server_state.resolve_payloads.lock().await.clone();
+        assert_eq!(
+            cancel_payloads,
+            vec![serde_json::json!({"run_id": "run-1"})]
+        );
+
+        let sent = test_channel.sent.lock().await.clone();
+        assert_eq!(
+            sent,
+            vec!["⏹️ Run cancelled — the original message was deleted".to_string()]
+        );
+
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn typing_indicator_repeats_during_run_and_stops_on_completion() {
+        let server_state = TestServerState {
+            run_id: "run-1".to_string(),
+            resolve_payloads: Arc::new(AsyncMutex::new(Vec::new())),
+            last_event_ids: Arc::new(AsyncMutex::new(Vec::new())),
+        };
+
+        let app = Router::new()
+            .route(
+                "/v1/sessions/{session_id}/events",
+                get(test_delayed_events_handler),
+            )
+            .with_state(server_state);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test listener");
+        let addr = listener.local_addr().expect("read listener addr");
+        let server_handle = tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let store = Arc::new(
+            GatewayStore::open_in_memory()
+                .await
+                .expect("open in-memory gateway store"),
+        );
+
+        let test_channel = Arc::new(TestChannel::new("telegram"));
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("telegram".to_string(), test_channel.clone());
+
+        let interval = Duration::from_millis(50);
+        let dispatcher = Arc::new(
+            Dispatcher::new(
+                StakpakClient::new(format!("http://{addr}"), String::new()),
+                channels,
+                store,
+                RouterConfig::default(),
+                None,
+                ApprovalMode::AllowAll,
+                Vec::new(),
+                HashMap::new(),
+                "{channel}-{peer}".to_string(),
+            )
+            .with_typing_interval(interval),
+        );
+
+        let (run_tx, mut run_rx) = mpsc::channel(8);
+        dispatcher.spawn_run_consumer(
+            RunContext {
+                channels: dispatcher.channels.clone(),
+                delivery: DeliveryContext {
+                    channel: ChannelId("telegram".to_string()),
+                    peer_id: PeerId("42".to_string()),
+                    chat_type: ChatType::Direct,
+                    channel_meta: serde_json::json!({"chat_id": 42}),
+                    updated_at: Utc::now().timestamp_millis(),
+                },
+                session_id: "session-1".to_string(),
+                run_id: "run-1".to_string(),
+                timeout_seconds: None,
+                reply_mode: ReplyMode::Chunked,
+            },
+            None,
+            ApprovalMode::AllowAll,
+            HashSet::new(),
+            CancellationToken::new(),
+            run_tx,
+        );
+
+        let run_result = tokio::time::timeout(Duration::from_secs(3), run_rx.recv())
+            .await
+            .expect("timed out waiting for run result")
+            .expect("expected run result");
+        assert!(matches!(
+            run_result.outcome,
+            RunOutcome::Completed { cursor: Some(3) }
+        ));
+
+        let typing = test_channel.typing.lock().await.clone();
+        assert!(
+            typing.len() >= 4,
+            "expected an indicator every 50ms during a 300ms run, got {}",
+            typing.len()
+        );
+        for pair in typing.windows(2) {
+            assert!(
+                pair[1].duration_since(pair[0]) >= interval - Duration::from_millis(10),
+                "indicators sent faster than the configured interval"
+            );
+        }
+
+        tokio::time::sleep(interval * 4).await;
+        assert_eq!(
+            test_channel.typing.lock().await.len(),
+            typing.len(),
+            "indicators should stop once the run completes"
+        );
+
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn reset_command_clears_idle_session_but_not_active_run() {
+        let store = Arc::new(
+            GatewayStore::open_in_memory()
+                .await
+                .expect("open in-memory gateway store"),
+        );
+
+        let test_channel = Arc::new(TestChannel::new("slack"));
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("slack".to_string(), test_channel.clone());
+
+        let dispatcher = Arc::new(Dispatcher::new(
+            StakpakClient::new("http://127.0.0.1:4096".to_string(), String::new()),
+            channels,
+            store.clone(),
+            RouterConfig::default(),
+            None,
+            ApprovalMode::AllowAll,
+            Vec::new(),
+            HashMap::new(),
+            "{channel}-{peer}".to_string(),
+        ));
+
+        let reset = queued("/reset", None, "u1").inbound;
+        let routing_key = resolve_routing_key(
+            &dispatcher.router_config,
+            &reset.channel,
+            &reset.peer_id,
+            &reset.chat_type,
+        );
+        let delivery = dispatcher.delivery_context_from_inbound(&reset);
+        store
+            .set(
+                &routing_key,
+                &SessionMapping {
+                    session_id: "session-1".to_string(),
+                    title: "u1".to_string(),
+                    delivery: delivery.clone(),
+                    created_at: delivery.updated_at,
+                },
+            )
+            .await
+            .expect("persist mapping");
+
+        dispatcher
+            .active_runs
+            .lock()
+            .expect("lock active_runs")
+            .insert(
+                "session-1".to_string(),
+                ActiveRun {
+                    run_id: "run-1".to_string(),
+                    cancel: CancellationToken::new(),
+                    approval_mode: ApprovalMode::AllowAll,
+                    approval_allowlist: HashSet::new(),
+                    delivery,
+                    trigger_messages: HashSet::new(),
+                },
+            );
+        assert_eq!(
+            dispatcher.active_session_ids(),
+            HashSet::from(["session-1".to_string()])
+        );
+
+        let (run_tx, _run_rx) = mpsc::channel(8);
+        dispatcher
+            .handle_inbound(reset.clone(), run_tx.clone())
+            .await
+            .expect("handle reset during run");
+        assert!(
+            store
+                .get(&routing_key)
+                .await
+                .expect("get mapping")
+                .is_some(),
+            "reset must not drop the mapping of an active run"
+        );
+
+        dispatcher
+            .active_runs
+            .lock()
+            .expect("lock active_runs")
+            .clear();
+        dispatcher
+            .handle_inbound(reset, run_tx)
+            .await
+            .expect("handle reset");
+        assert!(
+            store
+                .get(&routing_key)
+                .await
+                .expect("get mapping")
+                .is_none()
+        );
+
+        let sent = test_channel.sent.lock().await.clone();
+        assert_eq!(sent.len(), 2);
+        assert!(sent[0].contains("still in progress"));
+        assert!(sent[1].contains("Session reset"));
+    }
+
+    #[tokio::test]
+    async fn disallowed_attachments_are_skipped_with_a_notice() {
+        let store = Arc::new(
+            GatewayStore::open_in_memory()
+                .await
+                .expect("open in-memory gateway store"),
+        );
+
+        let test_channel = Arc::new(TestChannel::new("slack"));
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("slack".to_string(), test_channel.clone());
+
+        let dispatcher = Arc::new(
+            Dispatcher::new(
+                StakpakClient::new("http://127.0.0.1:4096".to_string(), String::new()),
+                channels,
+                store.clone(),
+                RouterConfig::default(),
+                None,
+                ApprovalMode::AllowAll,
+                Vec::new(),
+                HashMap::new(),
+                "{channel}-{peer}".to_string(),
+            )
+            .with_attachment_policy(AttachmentPolicy {
+                max_bytes: 1024,
+                allowed_types: vec!["image/*".to_string()],
+            }),
+        );
+
+        let mut inbound = queued("", None, "u1").inbound;
+        inbound.media = vec![
+            MediaAttachment {
+                mime_type: "application/zip".to_string(),
+                data: vec![0; 16],
+                filename: Some("logs.zip".to_string()),
+            },
+            MediaAttachment {
+                mime_type: "image/png".to_string(),
+                data: vec![0; 4096],
+                filename: Some("screenshot.png".to_string()),
+            },
+        ];
+
+        let (run_tx, _run_rx) = mpsc::channel(8);
+        dispatcher
+            .handle_inbound(inbound, run_tx)
+            .await
+            .expect("handle message with attachments");
+
+        let sent = test_channel.sent.lock().await.clone();
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].contains("logs.zip has a file type that is not accepted"));
+        assert!(sent[0].contains("screenshot.png is 4 KB, over the 1 KB limit"));
+        // Nothing was left to send, so no session was created.
+        assert!(store.list(10).await.expect("list mappings").is_empty());
+    }
+
+    #[test]
+    fn reset_command_matches_plain_and_addressed_forms() {
+        assert!(is_reset_command("/reset"));
+        assert!(is_reset_command("  /RESET \n"));
+        assert!(is_reset_command("/reset@stakpak_bot"));
+        assert!(!is_reset_command("/reset the database"));
+        assert!(!is_reset_command("please /reset"));
+        assert!(!is_reset_command("/reset@"));
+    }
+
+    #[test]
+    fn message_key_combines_conversation_and_message_ids() {
+        assert_eq!(
+            message_key(&serde_json::json!({"chat_id": 42, "message_id": 7})),
+            Some("42:7".to_string())
+        );
+        assert_eq!(
+            message_key(&serde_json::json!({"channel": "C1", "ts": "1700000000.1"})),
+            Some("C1:1700000000.1".to_string())
+        );
+        assert_eq!(message_key(&serde_json::json!({"channel_id": "D1"})), None);
+    }
+
     fn inbound() -> InboundMessage {
         InboundMessage {
             channel: ChannelId("slack".to_string()),
@@ -3182,6 +4059,71 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn binding_overrides_apply_to_matched_messages_only() {
+        let store = Arc::new(
+            GatewayStore::open_in_memory()
+                .await
+                .expect("open in-memory gateway store"),
+        );
+
+        let router_config = RouterConfig {
+            bindings: vec![Binding {
+                match_rule: BindingMatch {
+                    channel: ChannelId::from("slack"),
+                    peer: Some(PeerMatch {
+                        kind: PeerMatchKind::Direct,
+                        id: "U-oncall".to_string(),
+                    }),
+                },
+                routing_key: "oncall".to_string(),
+                overrides: BindingOverrides {
+                    model: Some("anthropic/claude-opus-4-5".to_string()),
+                    max_steps: Some(8),
+                    approval_mode: Some(ApprovalMode::DenyAll),
+                },
+            }],
+            ..RouterConfig::default()
+        };
+
+        let dispatcher = Dispatcher::new(
+            StakpakClient::new("http://127.0.0.1:4096".to_string(), String::new()),
+            HashMap::new(),
+            store,
+            router_config,
+            Some("openai/default-model".to_string()),
+            ApprovalMode::AllowAll,
+            Vec::new(),
+            HashMap::new(),
+            "{channel}-{peer}".to_string(),
+        );
+
+        let matched = queued("restart the api", None, "U-oncall");
+        let overrides = dispatcher
+            .build_inbound_run_overrides(&matched.inbound)
+            .expect("expected binding overrides");
+        assert_eq!(
+            overrides.model.as_deref(),
+            Some("anthropic/claude-opus-4-5")
+        );
+        assert_eq!(overrides.max_turns, Some(8));
+        let (mode, _) = dispatcher.resolve_run_approval("slack", Some(&overrides));
+        assert!(matches!(mode, ApprovalMode::DenyAll));
+
+        let unmatched = queued("restart the api", None, "U-other");
+        assert!(
+            dispatcher
+                .build_inbound_run_overrides(&unmatched.inbound)
+                .is_none()
+        );
+        assert_eq!(
+            dispatcher.resolve_effective_model("slack", None),
+            Some("openai/default-model".to_string())
+        );
+        let (mode, _) = dispatcher.resolve_run_approval("slack", None);
+        assert!(matches!(mode, ApprovalMode::AllowAll));
+    }
+
     #[tokio::test]
     async fn profile_resolver_overrides_inline_channel_overrides() {
         let store = Arc::new(
@@ -1,12 +1,15 @@
 pub mod api;
+pub mod attachments;
 pub mod channels;
 pub mod chunking;
 pub mod client;
 pub mod config;
 pub mod dispatcher;
+pub mod progressive;
 pub mod router;
 pub mod runtime;
 pub mod slack_blocks;
+pub mod slack_signature;
 pub mod store;
 pub mod targeting;
 pub mod types;
@@ -19,4 +22,5 @@ pub use runtime::{Gateway, build_channels};
 pub use store::{GatewayStore, SessionMapping};
 pub use types::{
     ChannelId, ChatType, DeliveryContext, InboundMessage, MediaAttachment, OutboundReply, PeerId,
+    ReplyMode,
 };
@@ -0,0 +1,380 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tracing::warn;
+
+use crate::{
+    channels::Channel,
+    chunking::chunk_text,
+    types::{DeliveryContext, OutboundReply},
+};
+
+const PLACEHOLDER_TEXT: &str = "…";
+
+/// A reply that is posted once and then edited in place as streamed text
+/// arrives.
+///
+/// Edits are debounced so a fast stream does not trip channel rate limits.
+/// Text that outgrows `message_limit` rolls over into a new message. When the
+/// channel cannot post or edit the message, the text not yet shown is sent as
+/// a regular message by `finish`.
+pub struct ProgressiveReply {
+    channel: Arc<dyn Channel>,
+    template: OutboundReply,
+    debounce: Duration,
+    message_limit: usize,
+    message_id: Option<String>,
+    text: String,
+    shown: usize,
+    last_edit_at: Option<Instant>,
+    fallback: bool,
+}
+
+impl ProgressiveReply {
+    pub fn new(
+        channel: Arc<dyn Channel>,
+        delivery: &DeliveryContext,
+        debounce: Duration,
+        message_limit: usize,
+    ) -> Self {
+        Self {
+            channel,
+            template: OutboundReply {
+                channel: delivery.channel.clone(),
+                peer_id: delivery.peer_id.clone(),
+                chat_type: delivery.chat_type.clone(),
+                text: String::new(),
+                metadata: delivery.channel_meta.clone(),
+            },
+            debounce,
+            message_limit,
+            message_id: None,
+            text: String::new(),
+            shown: 0,
+            last_edit_at: None,
+            fallback: false,
+        }
+    }
+
+    /// Append streamed text, posting the placeholder on the first visible text
+    /// and editing the message once the debounce interval has passed.
+    pub async fn push(&mut self, delta: &str) {
+        self.text.push_str(delta);
+        if self.fallback || self.text.trim().is_empty() {
+            return;
+        }
+
+        if self.message_id.is_none() {
+            self.post(PLACEHOLDER_TEXT).await;
+            return;
+        }
+
+        if self
+            .last_edit_at
+            .is_some_and(|at| at.elapsed() >= self.debounce)
+        {
+            self.update().await;
+        }
+    }
+
+    /// Show everything received so far and start a fresh message for any
+    /// text pushed afterwards.
+    pub async fn finish(&mut self) {
+        if !self.fallback && self.message_id.is_some() {
+            self.update().await;
+        }
+
+        if self.fallback {
+            let rest = self.text.get(self.shown..).unwrap_or_default().trim();
+            if !rest.is_empty() {
+                let reply = self.reply(rest);
+                if let Err(error) = self.channel.send(reply).await {
+                    warn!(error = %error, "failed to send channel reply");
+                }
+            }
+        } else if self.message_id.is_none() && !self.text.trim().is_empty() {
+            // Text arrived without a placeholder ever being posted.
+            let reply = self.reply(self.text.trim());
+            if let Err(error) = self.channel.send(reply).await {
+                warn!(error = %error, "failed to send channel reply");
+            }
+        }
+
+        self.message_id = None;
+        self.text.clear();
+        self.shown = 0;
+        self.last_edit_at = None;
+        self.fallback = false;
+    }
+
+    async fn post(&mut self, text: &str) {
+        let reply = self.reply(text);
+        match self.channel.send_editable(reply).await {
+            Ok(message_id) => {
+                self.message_id = Some(message_id);
+                self.last_edit_at = Some(Instant::now());
+            }
+            Err(error) => {
+                warn!(error = %error, "progressive reply unavailable; sending final message instead");
+                self.fallback = true;
+            }
+        }
+    }
+
+    async fn update(&mut self) {
+        let mut chunks = chunk_text(self.text.trim(), self.message_limit);
+        if chunks.len() > 1 {
+            // Complete the current message and carry the overflow into a new one.
+            let last = chunks.pop().unwrap_or_default();
+            let mut sent = chunks.into_iter();
+            if let Some(first) = sent.next() {
+                self.edit(&first).await;
+            }
+            if self.fallback {
+                return;
+            }
+            for chunk in sent {
+                let reply = self.reply(&chunk);
+                if let Err(error) = self.channel.send(reply).await {
+                    warn!(error = %error, "failed to send channel reply");
+                }
+            }
+
+            self.message_id = None;
+            self.text = last;
+            self.shown = 0;
+            let text = self.text.clone();
+            self.post(&text).await;
+            if !self.fallback {
+                self.shown = self.text.len();
+            }
+            return;
+        }
+
+        if self.shown == self.text.len() {
+            return;
+        }
+
+        let text = self.text.trim().to_string();
+        self.edit(&text).await;
+        if !self.fallback {
+            self.shown = self.text.len();
+        }
+    }
+
+    async fn edit(&mut self, text: &str) {
+        let Some(message_id) = self.message_id.as_deref() else {
+            return;
+        };
+
+        match self.channel.edit_message(message_id, text).await {
+            Ok(()) => self.last_edit_at = Some(Instant::now()),
+            Err(error) => {
+                warn!(error = %error, "failed to edit progressive reply; sending the rest as a new message");
+                self.fallback = true;
+            }
+        }
+    }
+
+    fn reply(&self, text: &str) -> OutboundReply {
+        OutboundReply {
+            text: text.to_string(),
+            ..self.template.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use anyhow::{Result, anyhow};
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use tokio::sync::{Mutex, mpsc};
+    use tokio_util::sync::CancellationToken;
+
+    use super::ProgressiveReply;
+    use crate::{
+        channels::{Channel, ChannelTestResult},
+        types::{ChannelId, ChatType, DeliveryContext, InboundMessage, OutboundReply, PeerId},
+    };
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Op {
+        Send(String),
+        Post(String),
+        Edit(String, String),
+    }
+
+    struct MockChannel {
+        id: ChannelId,
+        editable: bool,
+        ops: Mutex<Vec<Op>>,
+    }
+
+    impl MockChannel {
+        fn new(editable: bool) -> Arc<Self> {
+            Arc::new(Self {
+                id: ChannelId("mock".to_string()),
+                editable,
+                ops: Mutex::new(Vec::new()),
+            })
+        }
+
+        async fn ops(&self) -> Vec<Op> {
+            self.ops.lock().await.clone()
+        }
+    }
+
+    #[async_trait]
+    impl Channel for MockChannel {
+        fn id(&self) -> &ChannelId {
+            &self.id
+        }
+
+        fn display_name(&self) -> &str {
+            "Mock"
+        }
+
+        async fn start(
+            &self,
+            _inbound_tx: mpsc::Sender<InboundMessage>,
+            _cancel: CancellationToken,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        async fn send(&self, reply: OutboundReply) -> Result<()> {
+            self.ops.lock().await.push(Op::Send(reply.text));
+            Ok(())
+        }
+
+        async fn send_editable(&self, reply: OutboundReply) -> Result<String> {
+            if !self.editable {
+                return Err(anyhow!("mock does not support editing messages"));
+            }
+            let mut ops = self.ops.lock().await;
+            ops.push(Op::Post(reply.text));
+            Ok(format!("m{}", ops.len()))
+        }
+
+        async fn edit_message(&self, message_id: &str, new_text: &str) -> Result<()> {
+            self.ops
+                .lock()
+                .await
+                .push(Op::Edit(message_id.to_string(), new_text.to_string()));
+            Ok(())
+        }
+
+        async fn test(&self) -> Result<ChannelTestResult> {
+            Ok(ChannelTestResult {
+                channel: self.id.0.clone(),
+                identity: "mock".to_string(),
+                details: "ok".to_string(),
+            })
+        }
+    }
+
+    fn delivery() -> DeliveryContext {
+        DeliveryContext {
+            channel: ChannelId("mock".to_string()),
+            peer_id: PeerId("u1".to_string()),
+            chat_type: ChatType::Direct,
+            channel_meta: serde_json::json!({}),
+            updated_at: Utc::now().timestamp_millis(),
+        }
+    }
+
+    #[tokio::test]
+    async fn edits_placeholder_in_place_with_debounce() {
+        let channel = MockChannel::new(true);
+        let debounce = Duration::from_millis(100);
+        let mut reply = ProgressiveReply::new(channel.clone(), &delivery(), debounce, 1000);
+
+        reply.push("Hello").await;
+        reply.push(" world").await;
+        assert_eq!(channel.ops().await, vec![Op::Post("…".to_string())]);
+
+        tokio::time::sleep(debounce + Duration::from_millis(20)).await;
+        reply.push("!").await;
+        reply.push(" More").await;
+        reply.push(" text.").await;
+        reply.finish().await;
+
+        assert_eq!(
+            channel.ops().await,
+            vec![
+                Op::Post("…".to_string()),
+                Op::Edit("m1".to_string(), "Hello world!".to_string()),
+                Op::Edit("m1".to_string(), "Hello world! More text.".to_string()),
+            ]
+        );
+
+        // Text after `finish` starts a new message.
+        reply.push("Next").await;
+        reply.finish().await;
+        assert_eq!(
+            channel.ops().await[3..],
+            [
+                Op::Post("…".to_string()),
+                Op::Edit("m4".to_string(), "Next".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn finish_without_new_text_does_not_edit_again() {
+        let channel = MockChannel::new(true);
+        let mut reply = ProgressiveReply::new(channel.clone(), &delivery(), Duration::ZERO, 1000);
+
+        reply.push("Done").await;
+        reply.push(".").await;
+        reply.finish().await;
+
+        assert_eq!(
+            channel.ops().await,
+            vec![
+                Op::Post("…".to_string()),
+                Op::Edit("m1".to_string(), "Done.".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn long_text_rolls_over_into_a_new_message() {
+        let channel = MockChannel::new(true);
+        let mut reply = ProgressiveReply::new(channel.clone(), &delivery(), Duration::ZERO, 12);
+
+        reply.push("first part\n").await;
+        reply.push("second part").await;
+        reply.finish().await;
+
+        assert_eq!(
+            channel.ops().await,
+            vec![
+                Op::Post("…".to_string()),
+                Op::Edit("m1".to_string(), "first part\n".to_string()),
+                Op::Post("second part".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_single_message_without_edit_support() {
+        let channel = MockChannel::new(false);
+        let mut reply = ProgressiveReply::new(channel.clone(), &delivery(), Duration::ZERO, 1000);
+
+        reply.push("Hello").await;
+        reply.push(" world").await;
+        assert!(channel.ops().await.is_empty());
+
+        reply.finish().await;
+        assert_eq!(
+            channel.ops().await,
+            vec![Op::Send("Hello world".to_string())]
+        );
+    }
+}
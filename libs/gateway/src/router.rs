@@ -1,4 +1,7 @@
-use crate::types::{ChannelId, ChatType, PeerId};
+use crate::{
+    config::ApprovalMode,
+    types::{ChannelId, ChatType, PeerId},
+};
 
 #[derive(Debug, Clone, Default)]
 pub struct RouterConfig {
@@ -18,6 +21,16 @@ pub enum DmScope {
 pub struct Binding {
     pub match_rule: BindingMatch,
     pub routing_key: String,
+    pub overrides: BindingOverrides,
+}
+
+/// Run settings applied to messages matched by a binding. Unset fields fall
+/// back to the channel and gateway defaults.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct BindingOverrides {
+    pub model: Option<String>,
+    pub max_steps: Option<usize>,
+    pub approval_mode: Option<ApprovalMode>,
 }
 
 #[derive(Debug, Clone)]
@@ -44,19 +57,7 @@ pub fn resolve_routing_key(
     peer_id: &PeerId,
     chat_type: &ChatType,
 ) -> String {
-    if let Some(binding) = config
-        .bindings
-        .iter()
-        .find(|binding| binding_matches_peer(binding, channel, peer_id, chat_type))
-    {
-        return binding.routing_key.clone();
-    }
-
-    if let Some(binding) = config
-        .bindings
-        .iter()
-        .find(|binding| binding_matches_channel(binding, channel))
-    {
+    if let Some(binding) = resolve_binding(config, channel, peer_id, chat_type) {
         return binding.routing_key.clone();
     }
 
@@ -76,6 +77,26 @@ pub fn resolve_routing_key(
     }
 }
 
+/// Find the binding for a message: peer bindings take precedence over
+/// channel-wide ones.
+pub fn resolve_binding<'a>(
+    config: &'a RouterConfig,
+    channel: &ChannelId,
+    peer_id: &PeerId,
+    chat_type: &ChatType,
+) -> Option<&'a Binding> {
+    config
+        .bindings
+        .iter()
+        .find(|binding| binding_matches_peer(binding, channel, peer_id, chat_type))
+        .or_else(|| {
+            config
+                .bindings
+                .iter()
+                .find(|binding| binding_matches_channel(binding, channel))
+        })
+}
+
 fn binding_matches_peer(
     binding: &Binding,
     channel: &ChannelId,
@@ -109,7 +130,8 @@ fn binding_matches_channel(binding: &Binding, channel: &ChannelId) -> bool {
 #[cfg(test)]
 mod tests {
     use super::{
-        Binding, BindingMatch, DmScope, PeerMatch, PeerMatchKind, RouterConfig, resolve_routing_key,
+        Binding, BindingMatch, BindingOverrides, DmScope, PeerMatch, PeerMatchKind, RouterConfig,
+        resolve_routing_key,
     };
     use crate::types::{ChannelId, ChatType, PeerId};
 
@@ -214,6 +236,7 @@ mod tests {
                         }),
                     },
                     routing_key: "peer-bound".to_string(),
+                    overrides: BindingOverrides::default(),
                 },
                 Binding {
                     match_rule: BindingMatch {
@@ -221,6 +244,7 @@ mod tests {
                         peer: None,
                     },
                     routing_key: "channel-bound".to_string(),
+                    overrides: BindingOverrides::default(),
                 },
             ],
         };
@@ -245,6 +269,7 @@ mod tests {
                     peer: None,
                 },
                 routing_key: "channel-bound".to_string(),
+                overrides: BindingOverrides::default(),
             }],
         };
 
@@ -271,6 +296,7 @@ mod tests {
                     }),
                 },
                 routing_key: "group-bound".to_string(),
+                overrides: BindingOverrides::default(),
             }],
         };
 
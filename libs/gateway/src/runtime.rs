@@ -1,4 +1,8 @@
-use std::{collections::HashMap, sync::Arc, time::Instant};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::{Result, anyhow};
 use tokio::sync::mpsc;
@@ -82,7 +86,10 @@ impl Gateway {
             .with_profile_resolution(
                 profile_overrides.channel_profiles,
                 profile_overrides.override_resolver,
-            ),
+            )
+            .with_typing_interval(Duration::from_secs(config.gateway.typing_interval_secs))
+            .with_reply_mode(config.gateway.reply_mode)
+            .with_attachment_policy(config.attachment_policy()),
         );
 
         let api_state = Arc::new(GatewayApiState {
@@ -155,6 +162,7 @@ impl Gateway {
         });
 
         let prune_store = self.store.clone();
+        let prune_dispatcher = self.dispatcher.clone();
         let prune_after_hours = self.config.gateway.prune_after_hours;
         let prune_cancel = runtime_cancel.child_token();
         let prune_task = tokio::spawn(async move {
@@ -163,7 +171,8 @@ impl Gateway {
                     _ = prune_cancel.cancelled() => break,
                     _ = tokio::time::sleep(std::time::Duration::from_secs(60 * 60)) => {
                         let max_age_ms = (prune_after_hours as i64) * 60 * 60 * 1000;
-                        if let Err(error) = prune_store.prune(max_age_ms).await {
+                        let active_session_ids = prune_dispatcher.active_session_ids();
+                        if let Err(error) = prune_store.prune(max_age_ms, &active_session_ids).await {
                             warn!(error = %error, "failed to prune gateway sessions");
                         }
                         if let Err(error) = prune_store.prune_delivery_contexts().await {
@@ -211,28 +220,36 @@ impl Gateway {
 
 pub fn build_channels(config: &GatewayConfig) -> Result<HashMap<String, Arc<dyn Channel>>> {
     let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+    let attachment_policy = config.attachment_policy();
 
     if let Some(telegram) = &config.channels.telegram {
         channels.insert(
             "telegram".to_string(),
-            Arc::new(TelegramChannel::new(telegram.token.clone())),
+            Arc::new(
+                TelegramChannel::new(telegram.token.clone())
+                    .with_attachment_policy(attachment_policy.clone()),
+            ),
         );
     }
 
     if let Some(discord) = &config.channels.discord {
         channels.insert(
             "discord".to_string(),
-            Arc::new(DiscordChannel::new(discord.token.clone())),
+            Arc::new(
+                DiscordChannel::new(discord.token.clone())
+                    .with_attachment_policy(attachment_policy.clone()),
+            ),
         );
     }
 
     if let Some(slack) = &config.channels.slack {
         channels.insert(
             "slack".to_string(),
-            Arc::new(SlackChannel::new(
-                slack.bot_token.clone(),
-                slack.app_token.clone(),
-            )),
+            Arc::new(
+                SlackChannel::new(slack.bot_token.clone(), slack.app_token.clone())
+                    .with_signing_secret(slack.signing_secret.clone())
+                    .with_attachment_policy(attachment_policy),
+            ),
         );
     }
 
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::{collections::HashSet, path::Path};
 
 use anyhow::{Context, Result, anyhow};
 use libsql::{Connection, Database};
@@ -232,13 +232,47 @@ impl GatewayStore {
         Ok(())
     }
 
-    pub async fn prune(&self, max_age_ms: i64) -> Result<usize> {
+    /// Delete mappings idle for longer than `max_age_ms`. Mappings of sessions
+    /// in `active_session_ids` are kept so a long-running run keeps its thread.
+    pub async fn prune(
+        &self,
+        max_age_ms: i64,
+        active_session_ids: &HashSet<String>,
+    ) -> Result<usize> {
         let cutoff = now_millis() - max_age_ms;
         let conn = self.connection().await?;
-        let deleted = conn
-            .execute("DELETE FROM sessions WHERE updated_at < ?", [cutoff])
+        let mut rows = conn
+            .query(
+                "SELECT routing_key, session_id FROM sessions WHERE updated_at < ?",
+                [cutoff],
+            )
+            .await
+            .context("failed to query stale sessions")?;
+
+        let mut stale = Vec::new();
+        while let Some(row) = rows
+            .next()
             .await
-            .context("failed to prune stale sessions")?;
+            .context("failed to read stale session row")?
+        {
+            let routing_key: String = row.get(0).context("failed to parse routing_key")?;
+            let session_id: String = row.get(1).context("failed to parse session_id")?;
+            if !active_session_ids.contains(&session_id) {
+                stale.push(routing_key);
+            }
+        }
+
+        let mut deleted = 0;
+        for routing_key in stale {
+            // Re-check the age so a mapping refreshed since the query survives.
+            deleted += conn
+                .execute(
+                    "DELETE FROM sessions WHERE routing_key = ? AND updated_at < ?",
+                    (routing_key.as_str(), cutoff),
+                )
+                .await
+                .context("failed to prune stale sessions")?;
+        }
 
         Ok(deleted as usize)
     }
@@ -419,6 +453,8 @@ fn now_millis() -> i64 {
 
 #[cfg(all(test, feature = "libsql-test"))]
 mod tests {
+    use std::collections::HashSet;
+
     use serde_json::json;
 
     use super::{GatewayStore, SessionMapping, now_millis};
@@ -521,13 +557,40 @@ mod tests {
             .await
             .expect("set new");
 
-        let deleted = store.prune(5_000).await.expect("prune");
+        let deleted = store.prune(5_000, &HashSet::new()).await.expect("prune");
         assert_eq!(deleted, 1);
 
         assert!(store.get("old").await.expect("get old").is_none());
         assert!(store.get("new").await.expect("get new").is_some());
     }
 
+    #[tokio::test]
+    async fn prune_keeps_mappings_of_active_sessions() {
+        let store = GatewayStore::open_in_memory().await.expect("store");
+        let now = now_millis();
+
+        store
+            .set("idle", &sample_mapping("s-idle", now - 20_000))
+            .await
+            .expect("set idle");
+        store
+            .set("busy", &sample_mapping("s-busy", now - 20_000))
+            .await
+            .expect("set busy");
+
+        let active = HashSet::from(["s-busy".to_string()]);
+        let deleted = store.prune(5_000, &active).await.expect("prune");
+        assert_eq!(deleted, 1);
+
+        assert!(store.get("idle").await.expect("get idle").is_none());
+        assert!(store.get("busy").await.expect("get busy").is_some());
+
+        // Once the run has finished the mapping is evicted like any other.
+        let deleted = store.prune(5_000, &HashSet::new()).await.expect("prune");
+        assert_eq!(deleted, 1);
+        assert!(store.get("busy").await.expect("get busy").is_none());
+    }
+
     #[tokio::test]
     async fn set_overwrites_existing_key() {
         let store = GatewayStore::open_in_memory().await.expect("store");
@@ -80,6 +80,18 @@ pub struct OutboundReply {
     pub metadata: serde_json::Value,
 }
 
+/// How streamed agent output is delivered to a channel.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplyMode {
+    /// Post each completed paragraph as a new message.
+    #[default]
+    Chunked,
+    /// Post a placeholder and edit it in place as output arrives. Channels
+    /// that cannot edit messages get the whole reply as one final message.
+    Progressive,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeliveryContext {
     pub channel: ChannelId,
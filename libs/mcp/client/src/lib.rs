@@ -1,19 +1,28 @@
 use anyhow::Result;
+use reqwest::header::HeaderMap;
 use rmcp::{
     RoleClient, ServiceExt,
-    model::{CallToolRequestParam, ClientRequest, Meta, Request, Tool},
+    model::{
+        CallToolRequestParam, CallToolResult, CancelledNotification, CancelledNotificationMethod,
+        CancelledNotificationParam, ClientRequest, Meta, Request, ServerResult, Tool,
+    },
     service::{PeerRequestOptions, RequestHandle, RunningService},
     transport::StreamableHttpClientTransport,
     transport::streamable_http_client::StreamableHttpClientTransportConfig,
 };
 use stakpak_shared::cert_utils::CertificateChain;
 use stakpak_shared::models::integrations::openai::ToolCallResultProgress;
+use stakpak_shared::run_id::{run_id, run_id_headers};
+use std::fmt;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc::Sender;
 
 mod local;
+mod self_test;
 
 pub use local::LocalClientHandler;
+pub use self_test::{ConnectionStage, ConnectionTestError, test_connection};
 
 pub type McpClient = RunningService<RoleClient, LocalClientHandler>;
 
@@ -22,45 +31,117 @@ pub async fn connect(progress_tx: Option<Sender<ToolCallResultProgress>>) -> Res
     local::connect(progress_tx).await
 }
 
+/// HTTP/2 and keep-alive tuning for the MCP client's connection pool.
+///
+/// Over TLS, HTTP/2 is negotiated through ALPN whenever the server supports
+/// it; `prior_knowledge` forces HTTP/2 without negotiation (e.g. h2c).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectOptions {
+    /// Send HTTP/2 PING frames at this interval to keep the connection alive.
+    pub http2_keep_alive_interval: Option<Duration>,
+    /// Keep sending PINGs while no requests are in flight, so proxies do not
+    /// reset idle connections.
+    pub http2_keep_alive_while_idle: bool,
+    /// Speak HTTP/2 from the first byte instead of negotiating it.
+    pub prior_knowledge: bool,
+}
+
 /// Connect to an MCP server via HTTPS with optional mTLS
 pub async fn connect_https(
     url: &str,
     certificate_chain: Option<Arc<CertificateChain>>,
     progress_tx: Option<Sender<ToolCallResultProgress>>,
 ) -> Result<McpClient> {
+    connect_https_with_options(
+        url,
+        certificate_chain,
+        progress_tx,
+        &ConnectOptions::default(),
+    )
+    .await
+}
+
+/// Connect to an MCP server via HTTPS with optional mTLS and connection tuning
+pub async fn connect_https_with_options(
+    url: &str,
+    certificate_chain: Option<Arc<CertificateChain>>,
+    progress_tx: Option<Sender<ToolCallResultProgress>>,
+    options: &ConnectOptions,
+) -> Result<McpClient> {
+    let http_client = build_http_client(certificate_chain, options)?;
+    connect_with_http_client(url, http_client, progress_tx).await
+}
+
+/// Connect over the streamable HTTP transport using a pre-configured client.
+async fn connect_with_http_client(
+    url: &str,
+    http_client: reqwest::Client,
+    progress_tx: Option<Sender<ToolCallResultProgress>>,
+) -> Result<McpClient> {
+    let config = StreamableHttpClientTransportConfig::with_uri(url);
+    let transport =
+        StreamableHttpClientTransport::<reqwest::Client>::with_client(http_client, config);
+
+    let client_handler = LocalClientHandler::new(progress_tx);
+    let client: McpClient = client_handler.serve(transport).await?;
+
+    tracing::debug!(run_id = %run_id(), url, "Connected to MCP server");
+    Ok(client)
+}
+
+/// HTTP client for MCP transports; every request carries the run id header.
+fn build_http_client(
+    certificate_chain: Option<Arc<CertificateChain>>,
+    options: &ConnectOptions,
+) -> Result<reqwest::Client> {
+    let tls_config = certificate_chain
+        .map(|cert_chain| cert_chain.create_client_config())
+        .transpose()?;
+    build_http_client_with_tls(tls_config, HeaderMap::new(), options)
+}
+
+/// HTTP client with an explicit TLS config (platform-verified TLS when `None`)
+/// and extra default headers on top of the run id header.
+fn build_http_client_with_tls(
+    tls_config: Option<rustls::ClientConfig>,
+    extra_headers: HeaderMap,
+    options: &ConnectOptions,
+) -> Result<reqwest::Client> {
+    let mut headers = run_id_headers();
+    headers.extend(extra_headers);
+
     let mut client_builder = reqwest::Client::builder()
-        .pool_idle_timeout(std::time::Duration::from_secs(90))
+        .default_headers(headers)
+        .pool_idle_timeout(Duration::from_secs(90))
         .pool_max_idle_per_host(10)
-        .tcp_keepalive(std::time::Duration::from_secs(60));
+        .tcp_keepalive(Duration::from_secs(60))
+        .http2_keep_alive_interval(options.http2_keep_alive_interval)
+        .http2_keep_alive_while_idle(options.http2_keep_alive_while_idle);
+    if options.prior_knowledge {
+        client_builder = client_builder.http2_prior_knowledge();
+    }
 
     // Configure TLS: use mTLS cert chain if provided, otherwise use
     // platform-verified TLS so the OS CA store is trusted.
-    if let Some(cert_chain) = certificate_chain {
-        let tls_config = cert_chain.create_client_config()?;
-        client_builder = client_builder.use_preconfigured_tls(tls_config);
-    } else {
+    let tls_config = tls_config.or_else(|| {
         let arc_crypto_provider = std::sync::Arc::new(rustls::crypto::ring::default_provider());
-        if let Ok(tls_config) = rustls::ClientConfig::builder_with_provider(arc_crypto_provider)
+        rustls::ClientConfig::builder_with_provider(arc_crypto_provider)
             .with_safe_default_protocol_versions()
             .map(|builder| {
                 rustls_platform_verifier::BuilderVerifierExt::with_platform_verifier(builder)
                     .with_no_client_auth()
             })
-        {
-            client_builder = client_builder.use_preconfigured_tls(tls_config);
+            .ok()
+    });
+    if let Some(mut tls_config) = tls_config {
+        // reqwest leaves preconfigured TLS untouched, so offer HTTP/2 here.
+        if tls_config.alpn_protocols.is_empty() {
+            tls_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
         }
+        client_builder = client_builder.use_preconfigured_tls(tls_config);
     }
 
-    let http_client = client_builder.build()?;
-
-    let config = StreamableHttpClientTransportConfig::with_uri(url);
-    let transport =
-        StreamableHttpClientTransport::<reqwest::Client>::with_client(http_client, config);
-
-    let client_handler = LocalClientHandler::new(progress_tx);
-    let client: McpClient = client_handler.serve(transport).await?;
-
-    Ok(client)
+    Ok(client_builder.build()?)
 }
 
 /// Get all available tools from the MCP client
@@ -70,13 +151,24 @@ pub async fn get_tools(client: &McpClient) -> Result<Vec<Tool>> {
 }
 
 /// Call a tool on the MCP client
+///
+/// `metadata` is sent as the request's `_meta`; the Stakpak MCP server reads
+/// `session_id`, `trace_id` and `user_id` from it to attribute tool actions.
+/// The current run id is added as `run_id` unless the caller set one, since
+/// the connection's `x-stakpak-run-id` header is fixed when it is opened.
 pub async fn call_tool(
     client: &McpClient,
     params: CallToolRequestParam,
     metadata: Option<serde_json::Map<String, serde_json::Value>>,
 ) -> Result<RequestHandle<RoleClient>, String> {
+    let run_id = run_id();
+    tracing::debug!(run_id = %run_id, tool = %params.name, "Calling MCP tool");
+    let mut metadata = metadata.unwrap_or_default();
+    metadata
+        .entry("run_id")
+        .or_insert_with(|| serde_json::Value::String(run_id));
     let options = PeerRequestOptions {
-        meta: Some(Meta(metadata.unwrap_or_default())),
+        meta: Some(Meta(metadata)),
         ..Default::default()
     };
     client
@@ -87,3 +179,127 @@ pub async fn call_tool(
         .await
         .map_err(|e| e.to_string())
 }
+
+/// Why a tool call made through [`call_tool_with_timeout`] did not return a result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolCallError {
+    /// The request could not be sent to the server.
+    Send(String),
+    /// The tool did not answer in time; the request was cancelled on the server.
+    TimedOut { tool: String, timeout: Duration },
+    /// The server answered with an error.
+    Service(String),
+    /// The server answered with something other than a tool result.
+    UnexpectedResponse,
+}
+
+impl fmt::Display for ToolCallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolCallError::Send(e) => write!(f, "Failed to send tool call: {e}"),
+            ToolCallError::TimedOut { tool, timeout } => {
+                write!(
+                    f,
+                    "Tool '{tool}' timed out after {}s",
+                    timeout.as_secs_f64()
+                )
+            }
+            ToolCallError::Service(e) => write!(f, "MCP tool execution error: {e}"),
+            ToolCallError::UnexpectedResponse => {
+                write!(f, "Unexpected response type from MCP server")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ToolCallError {}
+
+/// Call a tool and wait at most `timeout` for its result. On expiry the
+/// request is cancelled on the server so the tool stops running.
+pub async fn call_tool_with_timeout(
+    client: &McpClient,
+    params: CallToolRequestParam,
+    session_id: Option<&str>,
+    timeout: Duration,
+) -> Result<CallToolResult, ToolCallError> {
+    let tool = params.name.to_string();
+    let metadata = session_id.map(|session_id| {
+        serde_json::Map::from_iter([(
+            "session_id".to_string(),
+            serde_json::Value::String(session_id.to_string()),
+        )])
+    });
+
+    let handle = call_tool(client, params, metadata)
+        .await
+        .map_err(ToolCallError::Send)?;
+    let peer = handle.peer.clone();
+    let request_id = handle.id.clone();
+
+    match tokio::time::timeout(timeout, handle.await_response()).await {
+        Ok(Ok(ServerResult::CallToolResult(result))) => Ok(result),
+        Ok(Ok(_)) => Err(ToolCallError::UnexpectedResponse),
+        Ok(Err(e)) => Err(ToolCallError::Service(e.to_string())),
+        Err(_) => {
+            tracing::warn!(run_id = %run_id(), tool = %tool, ?timeout, "MCP tool call timed out");
+            let notification = CancelledNotification {
+                params: CancelledNotificationParam {
+                    request_id,
+                    reason: Some(format!("timed out after {}s", timeout.as_secs_f64())),
+                },
+                method: CancelledNotificationMethod,
+                extensions: Default::default(),
+            };
+            let _ = peer.send_notification(notification.into()).await;
+            Err(ToolCallError::TimedOut { tool, timeout })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stakpak_shared::run_id::RUN_ID_HEADER;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Accept `count` HTTP requests and return the run id header value of each.
+    async fn capture_run_id_headers(listener: TcpListener, count: usize) -> Vec<Option<String>> {
+        let mut seen = Vec::new();
+        for _ in 0..count {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 8192];
+            let n = stream.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            seen.push(request.lines().find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.eq_ignore_ascii_case(RUN_ID_HEADER)
+                    .then(|| value.trim().to_string())
+            }));
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .await
+                .unwrap();
+        }
+        seen
+    }
+
+    #[tokio::test]
+    async fn outgoing_requests_carry_a_stable_run_id() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        let server = tokio::spawn(capture_run_id_headers(listener, 2));
+
+        let client = build_http_client(None, &ConnectOptions::default()).unwrap();
+        client.post(&url).send().await.unwrap();
+        build_http_client(None, &ConnectOptions::default())
+            .unwrap()
+            .get(&url)
+            .send()
+            .await
+            .unwrap();
+
+        let seen = server.await.unwrap();
+        assert_eq!(seen, vec![Some(run_id()); 2]);
+    }
+}
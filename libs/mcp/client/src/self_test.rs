@@ -0,0 +1,171 @@
+//! Staged connectivity self-test against a running MCP server.
+//!
+//! Each stage is checked separately so a failure points at the layer that is
+//! misconfigured: the TLS handshake (certificates), authentication (token or
+//! MCP session initialization), or tool listing.
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
+use rmcp::model::Tool;
+
+use crate::{ConnectOptions, build_http_client_with_tls, connect_with_http_client, get_tools};
+
+/// How long each stage may take before it is reported as failed.
+const STAGE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A step of the connection self-test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionStage {
+    TlsHandshake,
+    Authentication,
+    ToolListing,
+}
+
+impl ConnectionStage {
+    /// All stages in the order they run.
+    pub const ALL: [ConnectionStage; 3] = [
+        ConnectionStage::TlsHandshake,
+        ConnectionStage::Authentication,
+        ConnectionStage::ToolListing,
+    ];
+}
+
+impl fmt::Display for ConnectionStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionStage::TlsHandshake => write!(f, "TLS handshake"),
+            ConnectionStage::Authentication => write!(f, "Authentication"),
+            ConnectionStage::ToolListing => write!(f, "Tool listing"),
+        }
+    }
+}
+
+/// The stage at which the self-test failed and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionTestError {
+    pub stage: ConnectionStage,
+    pub message: String,
+}
+
+impl ConnectionTestError {
+    fn new(stage: ConnectionStage, message: impl Into<String>) -> Self {
+        Self {
+            stage,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConnectionTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} failed: {}", self.stage, self.message)
+    }
+}
+
+impl StdError for ConnectionTestError {}
+
+/// Run the TLS handshake, MCP session initialization and `list_tools` against
+/// `url`, returning the listed tools or the first stage that failed.
+///
+/// `tls_config` carries the trusted CA and, for mTLS, the client certificate;
+/// when `None` the platform certificate store is used. `auth_token` is sent as
+/// a bearer token on every request.
+pub async fn test_connection(
+    url: &str,
+    tls_config: Option<rustls::ClientConfig>,
+    auth_token: Option<&str>,
+) -> Result<Vec<Tool>, ConnectionTestError> {
+    let mut headers = HeaderMap::new();
+    if let Some(token) = auth_token {
+        let value = HeaderValue::from_str(&format!("Bearer {token}")).map_err(|e| {
+            ConnectionTestError::new(
+                ConnectionStage::Authentication,
+                format!("invalid auth token: {e}"),
+            )
+        })?;
+        headers.insert(AUTHORIZATION, value);
+    }
+
+    let http_client = build_http_client_with_tls(tls_config, headers, &ConnectOptions::default())
+        .map_err(|e| {
+        ConnectionTestError::new(
+            ConnectionStage::TlsHandshake,
+            format!("failed to configure TLS: {e:#}"),
+        )
+    })?;
+
+    // Probe the server origin first: any HTTP response proves the handshake
+    // (including client certificate verification) went through.
+    let mut origin = reqwest::Url::parse(url).map_err(|e| {
+        ConnectionTestError::new(
+            ConnectionStage::TlsHandshake,
+            format!("invalid URL '{url}': {e}"),
+        )
+    })?;
+    origin.set_path("/");
+    origin.set_query(None);
+
+    let probe = http_client
+        .get(origin)
+        .timeout(STAGE_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| ConnectionTestError::new(ConnectionStage::TlsHandshake, error_chain(&e)))?;
+    if matches!(
+        probe.status(),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+    ) {
+        return Err(ConnectionTestError::new(
+            ConnectionStage::Authentication,
+            format!("server responded with {}", probe.status()),
+        ));
+    }
+
+    let client = tokio::time::timeout(
+        STAGE_TIMEOUT,
+        connect_with_http_client(url, http_client, None),
+    )
+    .await
+    .map_err(|_| {
+        ConnectionTestError::new(
+            ConnectionStage::Authentication,
+            "timed out initializing the MCP session",
+        )
+    })?
+    .map_err(|e| {
+        ConnectionTestError::new(
+            ConnectionStage::Authentication,
+            format!("MCP session initialization was rejected: {e:#}"),
+        )
+    })?;
+
+    let tools = tokio::time::timeout(STAGE_TIMEOUT, get_tools(&client))
+        .await
+        .map_err(|_| {
+            ConnectionTestError::new(ConnectionStage::ToolListing, "timed out listing tools")
+        })
+        .and_then(|result| {
+            result.map_err(|e| {
+                ConnectionTestError::new(ConnectionStage::ToolListing, format!("{e:#}"))
+            })
+        });
+
+    let _ = client.cancel().await;
+    tools
+}
+
+/// Render an error with its sources; reqwest hides the TLS cause otherwise.
+fn error_chain(error: &dyn StdError) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
@@ -0,0 +1,207 @@
+//! Environment variable policy for `run_command` and `run_command_task`.
+//!
+//! Child processes only receive a minimal safe set of variables plus whatever
+//! the profile allows. Denied variables are never passed through, and their
+//! values are redacted from command output in case they leak in anyway.
+
+use globset::Glob;
+use serde::{Deserialize, Serialize};
+pub use stakpak_shared::secrets::redact_env_values;
+
+/// Variables passed to `run_command` children unless explicitly denied.
+const SAFE_ENV_VARS: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "LOGNAME",
+    "SHELL",
+    "TERM",
+    "COLORTERM",
+    "NO_COLOR",
+    "LANG",
+    "LANGUAGE",
+    "LC_*",
+    "TZ",
+    "TMPDIR",
+    "PWD",
+    "HOSTNAME",
+    "XDG_*",
+    // Tooling context the agent routinely needs; credential-looking names
+    // such as AWS_SECRET_ACCESS_KEY are still caught by the denylist
+    "KUBECONFIG",
+    "AWS_*",
+    "SSH_AUTH_SOCK",
+];
+
+/// Variables that look like credentials; always denied on top of the profile's denylist.
+const DEFAULT_DENIED_ENV_VARS: &[&str] = &[
+    "*TOKEN*",
+    "*SECRET*",
+    "*PASSWORD*",
+    "*PASSWD*",
+    "*API_KEY*",
+    "*ACCESS_KEY*",
+    "*PRIVATE_KEY*",
+    "*CREDENTIAL*",
+];
+
+/// Values shorter than this are not redacted, to avoid mangling unrelated output.
+const MIN_REDACTED_VALUE_LEN: usize = 4;
+
+/// Profile-level `run_command` environment settings.
+///
+/// Entries are variable names or glob patterns (`AWS_*`, `*` for everything).
+/// A variable matching both lists is denied.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunCommandEnvConfig {
+    /// Variables passed through in addition to the safe defaults.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    /// Variables never passed through, and redacted from output.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+}
+
+impl RunCommandEnvConfig {
+    pub fn is_allowed(&self, name: &str) -> bool {
+        if self.is_denied(name) {
+            return false;
+        }
+
+        SAFE_ENV_VARS
+            .iter()
+            .copied()
+            .chain(self.allow.iter().map(String::as_str))
+            .any(|pattern| matches_env_pattern(pattern, name))
+    }
+
+    pub fn is_denied(&self, name: &str) -> bool {
+        DEFAULT_DENIED_ENV_VARS
+            .iter()
+            .copied()
+            .chain(self.deny.iter().map(String::as_str))
+            .any(|pattern| matches_env_pattern(pattern, name))
+    }
+
+    /// The subset of `vars` a child process may inherit.
+    pub fn child_env(
+        &self,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Vec<(String, String)> {
+        vars.into_iter()
+            .filter(|(name, _)| self.is_allowed(name))
+            .collect()
+    }
+
+    /// Denied variables from `vars` whose values should be scrubbed from output.
+    pub fn redactions(
+        &self,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Vec<(String, String)> {
+        let mut redactions: Vec<(String, String)> = vars
+            .into_iter()
+            .filter(|(name, value)| value.len() >= MIN_REDACTED_VALUE_LEN && self.is_denied(name))
+            .collect();
+        // Replace longer values first so a value containing another is fully hidden
+        redactions.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+        redactions
+    }
+}
+
+fn matches_env_pattern(pattern: &str, name: &str) -> bool {
+    if !pattern.contains(['*', '?', '[']) {
+        return pattern == name;
+    }
+
+    Glob::new(pattern)
+        .map(|glob| glob.compile_matcher().is_match(name))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn default_policy_passes_only_safe_set() {
+        let config = RunCommandEnvConfig::default();
+        let env = config.child_env(vars(&[
+            ("PATH", "/usr/bin"),
+            ("LC_ALL", "C"),
+            ("KUBECONFIG", "/home/me/.kube/config"),
+            ("AWS_PROFILE", "prod"),
+            ("AWS_SECRET_ACCESS_KEY", "wJalrXUtnFEMI"),
+            ("SSH_AUTH_SOCK", "/tmp/ssh-agent.sock"),
+            ("EDITOR", "vim"),
+            ("GITHUB_TOKEN", "ghp_abcdef"),
+        ]));
+
+        let names: Vec<&str> = env.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "PATH",
+                "LC_ALL",
+                "KUBECONFIG",
+                "AWS_PROFILE",
+                "SSH_AUTH_SOCK"
+            ]
+        );
+    }
+
+    #[test]
+    fn allowlist_extends_and_denylist_wins() {
+        let config = RunCommandEnvConfig {
+            allow: vec!["EDITOR".to_string(), "GOOGLE_*".to_string()],
+            deny: vec!["AWS_PROFILE".to_string()],
+        };
+
+        assert!(config.is_allowed("EDITOR"));
+        assert!(config.is_allowed("GOOGLE_CLOUD_PROJECT"));
+        assert!(config.is_allowed("AWS_REGION"));
+        assert!(!config.is_allowed("AWS_PROFILE"));
+        assert!(!config.is_allowed("AWS_SECRET_ACCESS_KEY"));
+    }
+
+    #[test]
+    fn wildcard_allow_still_excludes_credentials() {
+        let config = RunCommandEnvConfig {
+            allow: vec!["*".to_string()],
+            deny: Vec::new(),
+        };
+
+        assert!(config.is_allowed("EDITOR"));
+        assert!(!config.is_allowed("STAKPAK_API_KEY"));
+        assert!(!config.is_allowed("DB_PASSWORD"));
+    }
+
+    #[test]
+    fn denied_values_are_redacted_from_output() {
+        let config = RunCommandEnvConfig {
+            allow: Vec::new(),
+            deny: vec!["DATABASE_URL".to_string()],
+        };
+        let redactions = config.redactions(vars(&[
+            ("DATABASE_URL", "postgres://admin:hunter22@db/prod"),
+            ("GITHUB_TOKEN", "ghp_abcdef"),
+            ("PATH", "/usr/bin"),
+            ("SHORT_TOKEN", "abc"),
+        ]));
+
+        let output = redact_env_values(
+            "$ psql postgres://admin:hunter22@db/prod -c 'select 1' # ghp_abcdef abc",
+            &redactions,
+        );
+        assert_eq!(
+            output,
+            "$ psql [REDACTED:DATABASE_URL] -c 'select 1' # [REDACTED:GITHUB_TOKEN] abc"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Graceful drain on shutdown.
+//!
+//! When shutdown is requested the server stops taking new tool calls, then
+//! waits up to a grace period for the calls already running and the task
+//! manager's background tasks to finish before shutting the task manager down.
+
+use stakpak_shared::task_manager::{TaskManagerHandle, TaskStatus};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// How long in-flight work may keep running after shutdown is requested.
+pub const DEFAULT_SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// How often background tasks are polled while draining.
+const TASK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Counts in-flight tool calls and refuses new ones once draining starts.
+#[derive(Debug, Default)]
+pub struct ToolCallTracker {
+    active: AtomicUsize,
+    draining: AtomicBool,
+    idle: Notify,
+}
+
+impl ToolCallTracker {
+    /// Register a tool call, or `None` when the server is shutting down.
+    /// The call counts as active until the returned guard is dropped.
+    pub fn begin(self: &Arc<Self>) -> Option<ToolCallGuard> {
+        if self.draining.load(Ordering::SeqCst) {
+            return None;
+        }
+        self.active.fetch_add(1, Ordering::SeqCst);
+        Some(ToolCallGuard {
+            tracker: Arc::clone(self),
+        })
+    }
+
+    pub fn active(&self) -> usize {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    pub fn start_draining(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    async fn wait_idle(&self) {
+        loop {
+            let idle = self.idle.notified();
+            if self.active() == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+/// Marks a tool call as finished when dropped.
+#[derive(Debug)]
+pub struct ToolCallGuard {
+    tracker: Arc<ToolCallTracker>,
+}
+
+impl Drop for ToolCallGuard {
+    fn drop(&mut self) {
+        if self.tracker.active.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.tracker.idle.notify_waiters();
+        }
+    }
+}
+
+/// Stop accepting tool calls, wait up to `grace_period` for running calls and
+/// background tasks, then shut the task manager down, terminating whatever is
+/// still running.
+pub async fn drain(
+    tool_calls: &ToolCallTracker,
+    task_manager_handle: &TaskManagerHandle,
+    grace_period: Duration,
+) {
+    tool_calls.start_draining();
+
+    let calls_at_start = tool_calls.active();
+    let tasks_at_start = running_tasks(task_manager_handle).await;
+    if calls_at_start + tasks_at_start > 0 {
+        tracing::info!(
+            tool_calls = calls_at_start,
+            tasks = tasks_at_start,
+            grace_period_secs = grace_period.as_secs_f64(),
+            "Draining in-flight work before shutdown"
+        );
+
+        let _ = tokio::time::timeout(grace_period, async {
+            tool_calls.wait_idle().await;
+            while running_tasks(task_manager_handle).await > 0 {
+                tokio::time::sleep(TASK_POLL_INTERVAL).await;
+            }
+        })
+        .await;
+    }
+
+    let forced_calls = tool_calls.active();
+    let forced_tasks = running_tasks(task_manager_handle).await;
+    let drained =
+        calls_at_start.saturating_sub(forced_calls) + tasks_at_start.saturating_sub(forced_tasks);
+    let forced = forced_calls + forced_tasks;
+    if forced > 0 {
+        tracing::warn!(
+            drained,
+            forced,
+            "Grace period elapsed; terminating remaining tool calls and tasks"
+        );
+    } else {
+        tracing::info!(drained, forced, "Drained in-flight work");
+    }
+
+    tracing::info!("Shutting down task manager...");
+    if let Err(e) = task_manager_handle.shutdown().await {
+        tracing::error!("Failed to shutdown task manager: {}", e);
+    } else {
+        tracing::info!("Task manager shut down successfully");
+    }
+}
+
+/// Background tasks that are still doing work. Paused tasks wait for user
+/// input that will not arrive during shutdown, so they are not waited on.
+async fn running_tasks(task_manager_handle: &TaskManagerHandle) -> usize {
+    task_manager_handle
+        .get_all_tasks()
+        .await
+        .map(|tasks| {
+            tasks
+                .iter()
+                .filter(|task| matches!(task.status, TaskStatus::Pending | TaskStatus::Running))
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn tracker_refuses_calls_once_draining() {
+        let tracker = Arc::new(ToolCallTracker::default());
+        let call = tracker.begin().unwrap();
+        assert_eq!(tracker.active(), 1);
+
+        tracker.start_draining();
+        assert!(tracker.begin().is_none());
+
+        let waiter = tokio::spawn({
+            let tracker = tracker.clone();
+            async move { tracker.wait_idle().await }
+        });
+        drop(call);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(tracker.active(), 0);
+    }
+}
@@ -1,28 +1,43 @@
 use anyhow::Result;
+pub use command_env::RunCommandEnvConfig;
 use rmcp::{
     ServiceExt,
+    handler::server::tool::ToolRouter,
+    model::Tool,
     transport::{
         stdio,
         streamable_http_server::{StreamableHttpService, session::local::LocalSessionManager},
     },
 };
+use std::future::IntoFuture;
 use std::hash::Hash;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::{net::TcpListener, sync::broadcast::Receiver};
 pub use tool_container::ToolContainer;
-use tracing::error;
+use tracing::{Instrument, error};
 
 use stakpak_api::AgentProvider;
 use stakpak_shared::cert_utils::CertificateChain;
+use stakpak_shared::run_id::RUN_ID_HEADER;
 use stakpak_shared::task_manager::{TaskManager, TaskManagerHandle};
 
+pub mod command_env;
+pub mod drain;
 pub mod integrations;
 pub mod local_tools;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod patch;
 pub mod remote_tools;
+pub mod request_metadata;
+pub mod session_workdir;
 pub mod subagent_tools;
 pub mod tool_container;
 
+use drain::ToolCallTracker;
+
 pub mod tool_names {
     pub const VIEW: &str = "view";
     pub const CREATE: &str = "create";
@@ -35,10 +50,22 @@ pub mod tool_names {
     pub const LOAD_SKILL: &str = "load_skill";
     pub const LOCAL_CODE_SEARCH: &str = "local_code_search";
     pub const DELETE_FILE: &str = "delete_file";
+    pub const SEARCH_FILES: &str = "search_files";
+    pub const APPLY_PATCH: &str = "apply_patch";
+    pub const GIT_STATUS: &str = "git_status";
+    pub const GIT_DIFF: &str = "git_diff";
 
     const FS_FILE_READ: &[&str] = &[VIEW];
-    const FS_FILE_WRITE: &[&str] = &[CREATE, CREATE_FILE, STR_REPLACE, EDIT_FILE];
-    pub const AUTO_APPROVED: &[&str] = &[VIEW, SEARCH_DOCS, LOAD_SKILL, LOCAL_CODE_SEARCH];
+    const FS_FILE_WRITE: &[&str] = &[CREATE, CREATE_FILE, STR_REPLACE, EDIT_FILE, APPLY_PATCH];
+    pub const AUTO_APPROVED: &[&str] = &[
+        VIEW,
+        SEARCH_FILES,
+        GIT_STATUS,
+        GIT_DIFF,
+        SEARCH_DOCS,
+        LOAD_SKILL,
+        LOCAL_CODE_SEARCH,
+    ];
 
     pub fn is_fs_file_read(name: &str) -> bool {
         FS_FILE_READ.contains(&name)
@@ -57,6 +84,9 @@ pub mod tool_names {
     }
 }
 
+/// Default cap on the files the file tools load into memory (10 MiB).
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
 #[derive(Clone, Debug, Default)]
 pub struct EnabledToolsConfig {
     pub slack: bool,
@@ -140,17 +170,37 @@ pub struct MCPServerConfig {
     /// instead of building one from `certificate_chain`.
     pub server_tls_config: Option<Arc<rustls::ServerConfig>>,
     pub subagent_config: SubagentConfig,
+    /// Which environment variables `run_command` passes to its child process.
+    pub run_command_env: RunCommandEnvConfig,
+    /// Largest file, in bytes, that `view`, `create` and `str_replace` load
+    /// into memory.
+    pub max_file_size: u64,
     /// Optional pre-created TaskManagerHandle. When provided, the server uses this
     /// instead of creating its own. This allows external code (e.g., the TUI) to
     /// query task status directly.
     pub task_manager_handle: Option<Arc<TaskManagerHandle>>,
+    /// How long in-flight tool calls and background tasks may keep running
+    /// once shutdown is requested before they are terminated.
+    pub shutdown_grace_period: Duration,
 }
 
-/// Create graceful shutdown handler
+/// How long connections may stay open once draining has finished.
+const CONNECTION_CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Create graceful shutdown handler: wait for a shutdown request, then drain
+/// in-flight tool calls and background tasks.
 async fn create_shutdown_handler(
     shutdown_rx: Option<Receiver<()>>,
-    task_manager_handle: Option<std::sync::Arc<stakpak_shared::task_manager::TaskManagerHandle>>,
+    tool_calls: Arc<ToolCallTracker>,
+    task_manager_handle: Arc<TaskManagerHandle>,
+    grace_period: Duration,
 ) {
+    wait_for_shutdown_signal(shutdown_rx).await;
+    drain::drain(&tool_calls, &task_manager_handle, grace_period).await;
+}
+
+/// Wait for an external shutdown request, or SIGINT/SIGTERM when none is given.
+async fn wait_for_shutdown_signal(shutdown_rx: Option<Receiver<()>>) {
     if let Some(mut shutdown_rx) = shutdown_rx {
         let _ = shutdown_rx.recv().await;
     } else {
@@ -209,87 +259,74 @@ async fn create_shutdown_handler(
             }
         }
     }
+}
 
-    // Shutdown task manager if available
-    if let Some(task_manager_handle) = task_manager_handle {
-        tracing::info!("Shutting down task manager...");
-        if let Err(e) = task_manager_handle.shutdown().await {
-            tracing::error!("Failed to shutdown task manager: {}", e);
-        } else {
-            tracing::info!("Task manager shut down successfully");
+/// The tool router for `tool_mode`, without a container or running server
+pub fn tool_router(
+    tool_mode: &ToolMode,
+    enabled_tools: &EnabledToolsConfig,
+    enable_subagents: bool,
+) -> ToolRouter<ToolContainer> {
+    let mut tool_router = match tool_mode {
+        ToolMode::LocalOnly => ToolContainer::tool_router_local(),
+        ToolMode::RemoteOnly => ToolContainer::tool_router_remote(),
+        ToolMode::Combined => {
+            ToolContainer::tool_router_local() + ToolContainer::tool_router_remote()
         }
+    };
+
+    if enabled_tools.slack && *tool_mode != ToolMode::LocalOnly {
+        tool_router += ToolContainer::tool_router_slack();
     }
+
+    if enable_subagents {
+        tool_router += ToolContainer::tool_router_subagent();
+    }
+
+    tool_router
+}
+
+/// The tools a server in `tool_mode` exposes, sorted by name
+pub fn list_tools(
+    tool_mode: &ToolMode,
+    enabled_tools: &EnabledToolsConfig,
+    enable_subagents: bool,
+) -> Vec<Tool> {
+    let mut tools = tool_router(tool_mode, enabled_tools, enable_subagents).list_all();
+    tools.sort_by(|a, b| a.name.cmp(&b.name));
+    tools
 }
 
 fn build_tool_container(
     config: &MCPServerConfig,
     task_manager_handle: Arc<TaskManagerHandle>,
 ) -> Result<ToolContainer> {
-    let skill_directories = config.skill_directories.clone();
-    let tool_container = match config.tool_mode {
-        ToolMode::LocalOnly => {
-            let mut tool_router = ToolContainer::tool_router_local();
-
-            if config.enable_subagents {
-                tool_router += ToolContainer::tool_router_subagent();
-            }
-
-            ToolContainer::new(
-                None,
-                config.enabled_tools.clone(),
-                task_manager_handle.clone(),
-                tool_router,
-                skill_directories,
-                config.subagent_config.clone(),
-            )
-        }
-        ToolMode::RemoteOnly => {
-            let mut tool_router = ToolContainer::tool_router_remote();
-            if config.enabled_tools.slack {
-                tool_router += ToolContainer::tool_router_slack();
-            }
-
-            if config.enable_subagents {
-                tool_router += ToolContainer::tool_router_subagent();
-            }
-
-            ToolContainer::new(
-                config.client.clone(),
-                config.enabled_tools.clone(),
-                task_manager_handle.clone(),
-                tool_router,
-                skill_directories,
-                config.subagent_config.clone(),
-            )
-        }
-        ToolMode::Combined => {
-            let mut tool_router =
-                ToolContainer::tool_router_local() + ToolContainer::tool_router_remote();
-
-            if config.enabled_tools.slack {
-                tool_router += ToolContainer::tool_router_slack();
-            }
-
-            if config.enable_subagents {
-                tool_router += ToolContainer::tool_router_subagent();
-            }
+    // Local-only tools never call the Stakpak API
+    let client = match config.tool_mode {
+        ToolMode::LocalOnly => None,
+        ToolMode::RemoteOnly | ToolMode::Combined => config.client.clone(),
+    };
 
-            ToolContainer::new(
-                config.client.clone(),
-                config.enabled_tools.clone(),
-                task_manager_handle.clone(),
-                tool_router,
-                skill_directories,
-                config.subagent_config.clone(),
-            )
-        }
-    }
+    let tool_container = ToolContainer::new(
+        client,
+        config.enabled_tools.clone(),
+        task_manager_handle,
+        tool_router(
+            &config.tool_mode,
+            &config.enabled_tools,
+            config.enable_subagents,
+        ),
+        config.skill_directories.clone(),
+        config.subagent_config.clone(),
+    )
     .map_err(|e| {
         error!("Failed to create tool container: {}", e);
         anyhow::anyhow!("Failed to create tool container: {}", e)
     })?;
 
-    Ok(tool_container)
+    Ok(tool_container
+        .with_run_command_env(config.run_command_env.clone())
+        .with_max_file_size(config.max_file_size))
 }
 
 /// Create or reuse a TaskManagerHandle from config.
@@ -307,6 +344,73 @@ fn get_or_create_task_manager(config: &MCPServerConfig) -> Arc<TaskManagerHandle
     }
 }
 
+/// Run each MCP request inside a span tagged with the caller's run id.
+async fn run_id_span(
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let run_id = request
+        .headers()
+        .get(RUN_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("-")
+        .to_string();
+    next.run(request)
+        .instrument(tracing::info_span!("mcp_request", run_id = %run_id))
+        .await
+}
+
+/// Liveness and readiness probes for load balancers and orchestrators. They
+/// are served outside `/mcp` and need no authentication.
+///
+/// The tool container is built before the router, so once the server accepts
+/// connections readiness only depends on the task manager still running.
+fn health_router(task_manager_handle: Arc<TaskManagerHandle>) -> axum::Router {
+    axum::Router::new()
+        .route("/healthz", axum::routing::get(|| async { "ok" }))
+        .route(
+            "/readyz",
+            axum::routing::get(move || {
+                let task_manager_handle = task_manager_handle.clone();
+                async move {
+                    if task_manager_handle.is_running() {
+                        (axum::http::StatusCode::OK, "ready")
+                    } else {
+                        (
+                            axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                            "task manager is not running",
+                        )
+                    }
+                }
+            }),
+        )
+}
+
+/// Prometheus scrape endpoint, unauthenticated like the health probes.
+#[cfg(feature = "metrics")]
+fn metrics_router(
+    tool_metrics: Arc<metrics::ToolMetrics>,
+    session_manager: Arc<LocalSessionManager>,
+) -> axum::Router {
+    axum::Router::new().route(
+        "/metrics",
+        axum::routing::get(move || {
+            let tool_metrics = tool_metrics.clone();
+            let session_manager = session_manager.clone();
+            async move {
+                let active_sessions = session_manager.sessions.read().await.len();
+                (
+                    [(
+                        axum::http::header::CONTENT_TYPE,
+                        "text/plain; version=0.0.4; charset=utf-8",
+                    )],
+                    tool_metrics.render(active_sessions),
+                )
+            }
+        }),
+    )
+}
+
 /// Internal helper function that contains the common server initialization logic
 async fn start_server_internal(
     config: MCPServerConfig,
@@ -314,16 +418,28 @@ async fn start_server_internal(
     shutdown_rx: Option<Receiver<()>>,
 ) -> Result<()> {
     let task_manager_handle = get_or_create_task_manager(&config);
+    let shutdown_grace_period = config.shutdown_grace_period;
 
     let tool_container = build_tool_container(&config, task_manager_handle.clone())?;
+    let tool_calls = tool_container.tool_calls.clone();
+
+    #[cfg(feature = "metrics")]
+    let tool_metrics = tool_container.metrics.clone();
+    let session_manager = Arc::new(LocalSessionManager::default());
 
     let service = StreamableHttpService::new(
         move || Ok(tool_container.to_owned()),
-        LocalSessionManager::default().into(),
+        session_manager.clone(),
         Default::default(),
     );
 
-    let router = axum::Router::new().nest_service("/mcp", service);
+    let router = axum::Router::new()
+        .nest_service("/mcp", service)
+        .layer(axum::middleware::from_fn(run_id_span))
+        .merge(health_router(task_manager_handle.clone()));
+
+    #[cfg(feature = "metrics")]
+    let router = router.merge(metrics_router(tool_metrics, session_manager));
 
     let tls_config = if let Some(pre_built) = config.server_tls_config {
         Some(pre_built)
@@ -334,13 +450,25 @@ async fn start_server_internal(
     };
 
     if let Some(tls_config) = tls_config {
-        let rustls_config = axum_server::tls_rustls::RustlsConfig::from_config(tls_config);
+        // Let clients negotiate HTTP/2 so tool calls share one connection.
+        let mut tls_config = Arc::unwrap_or_clone(tls_config);
+        if tls_config.alpn_protocols.is_empty() {
+            tls_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        }
+        let rustls_config =
+            axum_server::tls_rustls::RustlsConfig::from_config(Arc::new(tls_config));
 
         let handle = axum_server::Handle::new();
         let shutdown_handle = handle.clone();
         tokio::spawn(async move {
-            create_shutdown_handler(shutdown_rx, Some(task_manager_handle.clone())).await;
-            shutdown_handle.graceful_shutdown(None);
+            create_shutdown_handler(
+                shutdown_rx,
+                tool_calls,
+                task_manager_handle,
+                shutdown_grace_period,
+            )
+            .await;
+            shutdown_handle.graceful_shutdown(Some(CONNECTION_CLOSE_TIMEOUT));
         });
 
         axum_server::from_tcp_rustls(tcp_listener.into_std()?, rustls_config)
@@ -348,12 +476,33 @@ async fn start_server_internal(
             .serve(router.into_make_service())
             .await?;
     } else {
-        axum::serve(tcp_listener, router)
-            .with_graceful_shutdown(create_shutdown_handler(
+        let (drained_tx, mut drained_rx) = tokio::sync::watch::channel(false);
+        let shutdown = async move {
+            create_shutdown_handler(
                 shutdown_rx,
-                Some(task_manager_handle.clone()),
-            ))
-            .await?;
+                tool_calls,
+                task_manager_handle,
+                shutdown_grace_period,
+            )
+            .await;
+            let _ = drained_tx.send(true);
+        };
+        let server = axum::serve(tcp_listener, router).with_graceful_shutdown(shutdown);
+
+        // Streaming sessions can hold connections open indefinitely, so stop
+        // waiting for them a little while after draining has finished.
+        tokio::select! {
+            result = server.into_future() => result?,
+            _ = async {
+                if drained_rx.wait_for(|drained| *drained).await.is_ok() {
+                    tokio::time::sleep(CONNECTION_CLOSE_TIMEOUT).await;
+                } else {
+                    std::future::pending::<()>().await;
+                }
+            } => {
+                tracing::warn!("Closing connections still open after shutdown");
+            }
+        }
     }
 
     Ok(())
@@ -379,8 +528,10 @@ pub async fn start_server_stdio(
     shutdown_rx: Option<Receiver<()>>,
 ) -> Result<()> {
     let task_manager_handle = get_or_create_task_manager(&config);
+    let shutdown_grace_period = config.shutdown_grace_period;
 
     let tool_container = build_tool_container(&config, task_manager_handle.clone())?;
+    let tool_calls = tool_container.tool_calls.clone();
 
     let running_service = tool_container.serve(stdio()).await.map_err(|e| {
         error!("Failed to start stdio MCP server: {}", e);
@@ -393,7 +544,13 @@ pub async fn start_server_stdio(
 
     // Graceful shutdown: on signal or external shutdown, cancel the running service.
     tokio::spawn(async move {
-        create_shutdown_handler(shutdown_rx, Some(shutdown_task_manager)).await;
+        create_shutdown_handler(
+            shutdown_rx,
+            tool_calls,
+            shutdown_task_manager,
+            shutdown_grace_period,
+        )
+        .await;
         cancellation_token.cancel();
     });
 
@@ -412,3 +569,561 @@ pub async fn start_server_stdio(
 
     wait_result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_config(task_manager_handle: Arc<TaskManagerHandle>) -> MCPServerConfig {
+        MCPServerConfig {
+            client: None,
+            bind_address: "127.0.0.1:0".to_string(),
+            enabled_tools: EnabledToolsConfig::default(),
+            tool_mode: ToolMode::LocalOnly,
+            enable_subagents: false,
+            certificate_chain: Arc::new(None),
+            skill_directories: Vec::new(),
+            server_tls_config: None,
+            subagent_config: SubagentConfig::default(),
+            run_command_env: RunCommandEnvConfig::default(),
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            task_manager_handle: Some(task_manager_handle),
+            shutdown_grace_period: drain::DEFAULT_SHUTDOWN_GRACE_PERIOD,
+        }
+    }
+
+    #[test]
+    fn local_tool_list_includes_core_tools() {
+        let tools = list_tools(&ToolMode::LocalOnly, &EnabledToolsConfig::default(), false);
+        let names: Vec<&str> = tools.iter().map(|tool| &*tool.name).collect();
+
+        assert!(!names.is_empty());
+        assert!(names.contains(&tool_names::VIEW));
+        assert!(names.contains(&tool_names::RUN_COMMAND));
+        // Remote tools need the Stakpak API and stay out of local mode
+        assert!(!names.contains(&tool_names::SEARCH_DOCS));
+    }
+
+    #[tokio::test]
+    async fn health_and_readiness_endpoints() {
+        let task_manager = TaskManager::new();
+        let task_manager_handle = task_manager.handle();
+        let task_manager_task = tokio::spawn(task_manager.run());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let server = tokio::spawn(start_server(
+            local_config(task_manager_handle.clone()),
+            Some(listener),
+            Some(shutdown_rx),
+        ));
+
+        let http = reqwest::Client::new();
+        let healthz = http
+            .get(format!("{base_url}/healthz"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(healthz.status(), reqwest::StatusCode::OK);
+
+        let readyz = http.get(format!("{base_url}/readyz")).send().await.unwrap();
+        assert_eq!(readyz.status(), reqwest::StatusCode::OK);
+
+        // Once the task manager stops the server is alive but not ready.
+        task_manager_handle.shutdown().await.unwrap();
+        task_manager_task.await.unwrap();
+
+        let healthz = http
+            .get(format!("{base_url}/healthz"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(healthz.status(), reqwest::StatusCode::OK);
+
+        let readyz = http.get(format!("{base_url}/readyz")).send().await.unwrap();
+        assert_eq!(readyz.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn metrics_endpoint_reports_tool_calls() {
+        use rmcp::{
+            ServiceExt, model::CallToolRequestParam, transport::StreamableHttpClientTransport,
+        };
+
+        let task_manager = TaskManager::new();
+        let task_manager_handle = task_manager.handle();
+        tokio::spawn(task_manager.run());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let server = tokio::spawn(start_server(
+            local_config(task_manager_handle),
+            Some(listener),
+            Some(shutdown_rx),
+        ));
+
+        let workdir = tempfile::tempdir().unwrap();
+        let file = workdir.path().join("hello.txt");
+        std::fs::write(&file, "hello\n").unwrap();
+
+        let transport = StreamableHttpClientTransport::from_uri(format!("{base_url}/mcp"));
+        let client = ().serve(transport).await.unwrap();
+        client
+            .call_tool(CallToolRequestParam {
+                name: tool_names::VIEW.into(),
+                arguments: serde_json::json!({ "path": file.to_string_lossy() })
+                    .as_object()
+                    .cloned(),
+            })
+            .await
+            .unwrap();
+
+        let response = reqwest::get(format!("{base_url}/metrics")).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let body = response.text().await.unwrap();
+
+        // Every sample line must be `name{labels} value` with a numeric value.
+        let mut samples = std::collections::HashMap::new();
+        for line in body.lines().filter(|line| !line.starts_with('#')) {
+            let (series, value) = line.rsplit_once(' ').unwrap();
+            let value: f64 = value.parse().unwrap();
+            samples.insert(series.to_string(), value);
+        }
+
+        assert_eq!(
+            samples.get("stakpak_mcp_tool_calls_total{tool=\"view\"}"),
+            Some(&1.0)
+        );
+        assert_eq!(
+            samples.get("stakpak_mcp_tool_errors_total{tool=\"view\"}"),
+            Some(&0.0)
+        );
+        assert_eq!(
+            samples.get("stakpak_mcp_tool_call_duration_seconds_count{tool=\"view\"}"),
+            Some(&1.0)
+        );
+        assert_eq!(samples.get("stakpak_mcp_active_sessions"), Some(&1.0));
+
+        client.cancel().await.unwrap();
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn shutdown_lets_running_tool_calls_finish_within_grace_period() {
+        use rmcp::{model::CallToolRequestParam, transport::StreamableHttpClientTransport};
+
+        let task_manager = TaskManager::new();
+        let task_manager_handle = task_manager.handle();
+        tokio::spawn(task_manager.run());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let mut config = local_config(task_manager_handle);
+        config.shutdown_grace_period = Duration::from_secs(10);
+        let server = tokio::spawn(start_server(config, Some(listener), Some(shutdown_rx)));
+
+        let transport = StreamableHttpClientTransport::from_uri(format!("{base_url}/mcp"));
+        let client = ().serve(transport).await.unwrap();
+        let long_call = tokio::spawn({
+            let peer = client.peer().clone();
+            async move {
+                peer.call_tool(CallToolRequestParam {
+                    name: tool_names::RUN_COMMAND.into(),
+                    arguments: serde_json::json!({ "command": "sleep 1 && echo finished" })
+                        .as_object()
+                        .cloned(),
+                })
+                .await
+            }
+        });
+
+        // Request shutdown while the command is still running.
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        shutdown_tx.send(()).unwrap();
+
+        let result = long_call.await.unwrap().unwrap();
+        assert_ne!(result.is_error, Some(true));
+        let output = serde_json::to_string(&result.content).unwrap();
+        assert!(output.contains("finished"), "{output}");
+
+        client.cancel().await.unwrap();
+        tokio::time::timeout(Duration::from_secs(10), server)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn sessions_with_different_working_directories_are_isolated() {
+        use rmcp::{
+            RoleClient,
+            model::{
+                CallToolRequestParam, CallToolResult, ClientRequest, Meta, Request, ServerResult,
+            },
+            service::{PeerRequestOptions, RunningService},
+            transport::StreamableHttpClientTransport,
+        };
+        use session_workdir::WORKING_DIRECTORY_META_KEY;
+
+        async fn call(
+            client: &RunningService<RoleClient, ()>,
+            session_id: &str,
+            working_directory: Option<&std::path::Path>,
+            tool: &'static str,
+            arguments: serde_json::Value,
+        ) -> CallToolResult {
+            let mut meta = serde_json::Map::new();
+            meta.insert("session_id".to_string(), session_id.into());
+            if let Some(dir) = working_directory {
+                meta.insert(
+                    WORKING_DIRECTORY_META_KEY.to_string(),
+                    dir.to_string_lossy().into(),
+                );
+            }
+            let handle = client
+                .send_cancellable_request(
+                    ClientRequest::CallToolRequest(Request::new(CallToolRequestParam {
+                        name: tool.into(),
+                        arguments: arguments.as_object().cloned(),
+                    })),
+                    PeerRequestOptions {
+                        meta: Some(Meta(meta)),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+            match handle.await_response().await.unwrap() {
+                ServerResult::CallToolResult(result) => result,
+                other => panic!("unexpected response: {other:?}"),
+            }
+        }
+
+        fn text(result: &CallToolResult) -> String {
+            serde_json::to_string(&result.content).unwrap()
+        }
+
+        let task_manager = TaskManager::new();
+        let task_manager_handle = task_manager.handle();
+        tokio::spawn(task_manager.run());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let server = tokio::spawn(start_server(
+            local_config(task_manager_handle),
+            Some(listener),
+            Some(shutdown_rx),
+        ));
+
+        let transport = StreamableHttpClientTransport::from_uri(format!("{base_url}/mcp"));
+        let client = ().serve(transport).await.unwrap();
+
+        let dir_a = tempfile::tempdir().unwrap();
+        let dir_b = tempfile::tempdir().unwrap();
+
+        // The first call of each session pins its working directory.
+        let created = call(
+            &client,
+            "session-a",
+            Some(dir_a.path()),
+            tool_names::CREATE,
+            serde_json::json!({ "path": "notes.txt", "file_text": "from a\n" }),
+        )
+        .await;
+        assert_ne!(created.is_error, Some(true), "{}", text(&created));
+        let created = call(
+            &client,
+            "session-b",
+            Some(dir_b.path()),
+            tool_names::CREATE,
+            serde_json::json!({ "path": "notes.txt", "file_text": "from b\n" }),
+        )
+        .await;
+        assert_ne!(created.is_error, Some(true), "{}", text(&created));
+
+        assert_eq!(
+            std::fs::read_to_string(dir_a.path().join("notes.txt")).unwrap(),
+            "from a\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir_b.path().join("notes.txt")).unwrap(),
+            "from b\n"
+        );
+
+        // Later calls reuse the session's directory.
+        let viewed = call(
+            &client,
+            "session-a",
+            None,
+            tool_names::VIEW,
+            serde_json::json!({ "path": "notes.txt" }),
+        )
+        .await;
+        assert!(text(&viewed).contains("from a"), "{}", text(&viewed));
+        assert!(!text(&viewed).contains("from b"));
+
+        let ran = call(
+            &client,
+            "session-b",
+            None,
+            tool_names::RUN_COMMAND,
+            serde_json::json!({ "command": "cat notes.txt" }),
+        )
+        .await;
+        assert!(text(&ran).contains("from b"), "{}", text(&ran));
+        assert!(!text(&ran).contains("from a"));
+
+        // Neither session can reach into the other's directory.
+        let other_dir = dir_b.path().file_name().unwrap().to_string_lossy();
+        for path in [
+            format!("../{other_dir}/notes.txt"),
+            dir_b
+                .path()
+                .join("notes.txt")
+                .to_string_lossy()
+                .into_owned(),
+        ] {
+            let escaped = call(
+                &client,
+                "session-a",
+                None,
+                tool_names::VIEW,
+                serde_json::json!({ "path": path }),
+            )
+            .await;
+            assert_eq!(escaped.is_error, Some(true));
+            assert!(text(&escaped).contains("PATH_OUTSIDE_SESSION_ROOT"));
+        }
+
+        client.cancel().await.unwrap();
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn mtls_self_test_reaches_tool_listing() {
+        use stakpak_mcp_client::{ConnectionStage, test_connection};
+        use stakpak_shared::cert_utils::create_client_config_from_pem;
+
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+        let task_manager = TaskManager::new();
+        let task_manager_handle = task_manager.handle();
+        tokio::spawn(task_manager.run());
+
+        let chain = CertificateChain::generate().unwrap();
+        let ca_pem = chain.get_ca_cert_pem().unwrap();
+        let client_cert_pem = chain.get_client_cert_pem().unwrap();
+        let client_key_pem = chain.get_client_key_pem().unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("https://{}/mcp", listener.local_addr().unwrap());
+        let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let mut config = local_config(task_manager_handle);
+        config.certificate_chain = Arc::new(Some(chain));
+        let server = tokio::spawn(start_server(config, Some(listener), Some(shutdown_rx)));
+
+        let tls_config =
+            create_client_config_from_pem(&ca_pem, Some((&client_cert_pem, &client_key_pem)))
+                .unwrap();
+        let tools = test_connection(&url, Some(tls_config), None).await.unwrap();
+        assert!(tools.iter().any(|tool| tool.name == tool_names::VIEW));
+
+        // Without a client certificate the server rejects the handshake.
+        let tls_config = create_client_config_from_pem(&ca_pem, None).unwrap();
+        let err = test_connection(&url, Some(tls_config), None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.stage, ConnectionStage::TlsHandshake, "{err}");
+
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn sequential_tool_calls_reuse_one_http2_connection() {
+        use rmcp::model::{CallToolRequestParam, ServerResult};
+        use stakpak_mcp_client::{ConnectOptions, call_tool, connect_https_with_options};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let task_manager = TaskManager::new();
+        let task_manager_handle = task_manager.handle();
+        tokio::spawn(task_manager.run());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let server = tokio::spawn(start_server(
+            local_config(task_manager_handle),
+            Some(listener),
+            Some(shutdown_rx),
+        ));
+
+        // Count TCP connections by forwarding through a local relay.
+        let relay = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let relay_url = format!("http://{}/mcp", relay.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        tokio::spawn({
+            let connections = connections.clone();
+            async move {
+                loop {
+                    let (mut inbound, _) = relay.accept().await.unwrap();
+                    connections.fetch_add(1, Ordering::SeqCst);
+                    tokio::spawn(async move {
+                        let mut outbound =
+                            tokio::net::TcpStream::connect(server_addr).await.unwrap();
+                        let _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await;
+                    });
+                }
+            }
+        });
+
+        let options = ConnectOptions {
+            http2_keep_alive_interval: Some(Duration::from_secs(5)),
+            http2_keep_alive_while_idle: true,
+            prior_knowledge: true,
+        };
+        let client = connect_https_with_options(&relay_url, None, None, &options)
+            .await
+            .unwrap();
+
+        let workdir = tempfile::tempdir().unwrap();
+        let file = workdir.path().join("hello.txt");
+        std::fs::write(&file, "hello\n").unwrap();
+        for _ in 0..20 {
+            let handle = call_tool(
+                &client,
+                CallToolRequestParam {
+                    name: tool_names::VIEW.into(),
+                    arguments: serde_json::json!({ "path": file.to_string_lossy() })
+                        .as_object()
+                        .cloned(),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+            match handle.await_response().await.unwrap() {
+                ServerResult::CallToolResult(result) => assert_ne!(result.is_error, Some(true)),
+                other => panic!("unexpected response: {other:?}"),
+            }
+        }
+
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+        client.cancel().await.unwrap();
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn tool_call_metadata_reaches_run_command() {
+        use rmcp::model::{CallToolRequestParam, ServerResult};
+        use stakpak_mcp_client::{call_tool, connect_https};
+
+        let task_manager = TaskManager::new();
+        let task_manager_handle = task_manager.handle();
+        tokio::spawn(task_manager.run());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let server = tokio::spawn(start_server(
+            local_config(task_manager_handle),
+            Some(listener),
+            Some(shutdown_rx),
+        ));
+
+        let client = connect_https(&url, None, None).await.unwrap();
+        let metadata = serde_json::json!({
+            "session_id": "session-1",
+            "trace_id": "trace-abc",
+            "user_id": "user-42",
+        });
+        let handle = call_tool(
+            &client,
+            CallToolRequestParam {
+                name: tool_names::RUN_COMMAND.into(),
+                arguments: serde_json::json!({
+                    "command": "echo \"trace=$STAKPAK_TRACE_ID user=$STAKPAK_USER_ID\""
+                })
+                .as_object()
+                .cloned(),
+            },
+            metadata.as_object().cloned(),
+        )
+        .await
+        .unwrap();
+        let result = match handle.await_response().await.unwrap() {
+            ServerResult::CallToolResult(result) => result,
+            other => panic!("unexpected response: {other:?}"),
+        };
+        let output = serde_json::to_string(&result.content).unwrap();
+        assert!(output.contains("trace=trace-abc user=user-42"), "{output}");
+
+        client.cancel().await.unwrap();
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn timed_out_tool_call_is_cancelled_on_the_server() {
+        use rmcp::model::CallToolRequestParam;
+        use stakpak_mcp_client::{ToolCallError, call_tool_with_timeout, connect_https};
+
+        let task_manager = TaskManager::new();
+        let task_manager_handle = task_manager.handle();
+        tokio::spawn(task_manager.run());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let server = tokio::spawn(start_server(
+            local_config(task_manager_handle),
+            Some(listener),
+            Some(shutdown_rx),
+        ));
+
+        let client = connect_https(&url, None, None).await.unwrap();
+        let workdir = tempfile::tempdir().unwrap();
+        let marker = workdir.path().join("finished");
+
+        let err = call_tool_with_timeout(
+            &client,
+            CallToolRequestParam {
+                name: tool_names::RUN_COMMAND.into(),
+                arguments: serde_json::json!({
+                    "command": format!("sleep 1 && touch {}", marker.display())
+                })
+                .as_object()
+                .cloned(),
+            },
+            Some("session-1"),
+            Duration::from_millis(300),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(err, ToolCallError::TimedOut { ref tool, .. } if tool == tool_names::RUN_COMMAND),
+            "{err}"
+        );
+
+        // The server killed the command, so it never reaches `touch`.
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(!marker.exists());
+
+        client.cancel().await.unwrap();
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+}
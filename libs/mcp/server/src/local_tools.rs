@@ -1,3 +1,5 @@
+use crate::command_env::redact_env_values;
+use crate::patch::{apply_patches, parse_unified_diff};
 use crate::tool_container::ToolContainer;
 use rmcp::service::RequestContext;
 use rmcp::{ErrorData as McpError, handler::server::wrapper::Parameters, model::*, schemars, tool};
@@ -8,14 +10,15 @@ use stakpak_shared::remote_connection::{
     PathLocation, RemoteConnection, RemoteConnectionInfo, RemoteFileSystemProvider,
 };
 
-use globset::Glob;
-use grep_regex::RegexMatcher;
-use grep_searcher::Searcher;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use grep_regex::{RegexMatcher, RegexMatcherBuilder};
 use grep_searcher::sinks::UTF8;
+use grep_searcher::{BinaryDetection, Searcher, SearcherBuilder, Sink, SinkContext, SinkMatch};
 use html2md;
 use ignore::WalkBuilder;
 use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
 use serde_json::json;
+use sha2::{Digest, Sha256};
 use similar::TextDiff;
 use stakpak_shared::models::async_manifest::{AsyncManifest, PendingToolCall};
 use stakpak_shared::models::integrations::mcp::CallToolResultExt;
@@ -28,7 +31,7 @@ use stakpak_shared::utils::{
     LocalFileSystemProvider, generate_directory_tree, sanitize_text_output,
 };
 use std::fs::{self};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
@@ -195,7 +198,7 @@ pub struct ViewRequest {
     )]
     pub path: String,
     #[schemars(
-        description = "Optional line range to view [start_line, end_line]. Line numbers are 1-indexed. Use -1 for end_line to read to end of file."
+        description = "Optional line range to view [start_line, end_line]. Line numbers are 1-indexed. Use -1 for end_line to read to end of file. Out-of-range values are clamped to the file length."
     )]
     pub view_range: Option<[i32; 2]>,
     #[schemars(
@@ -226,6 +229,77 @@ pub struct ViewOptions<'a> {
     pub glob: Option<&'a str>,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchFilesRequest {
+    #[schemars(
+        description = "Regex pattern (or literal text when 'literal' is true) to search for"
+    )]
+    pub pattern: String,
+    #[schemars(description = "Directory or file to search (default: current directory)")]
+    pub path: Option<String>,
+    #[schemars(
+        description = "Treat the pattern as literal text instead of a regex (default: false)"
+    )]
+    pub literal: Option<bool>,
+    #[schemars(description = "Match case-insensitively (default: false)")]
+    pub case_insensitive: Option<bool>,
+    #[schemars(
+        description = "Only search files matching any of these globs (e.g., ['*.rs', 'src/**/*.ts'])"
+    )]
+    pub include: Option<Vec<String>>,
+    #[schemars(description = "Skip files matching any of these globs (e.g., ['**/tests/**'])")]
+    pub exclude: Option<Vec<String>>,
+    #[schemars(description = "Lines of context to show around each match (default: 0, max: 10)")]
+    pub context_lines: Option<usize>,
+    #[schemars(description = "Maximum number of matches to return (default: 100, max: 500)")]
+    pub max_results: Option<usize>,
+}
+
+/// Options for `search_files` (used internally to reduce function arguments)
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilesOptions {
+    pub literal: bool,
+    pub case_insensitive: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub context_lines: usize,
+    pub max_results: usize,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GitStatusRequest {
+    #[schemars(description = "Directory inside the repository (default: current directory)")]
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GitDiffRequest {
+    #[schemars(description = "Directory inside the repository (default: current directory)")]
+    pub path: Option<String>,
+    #[schemars(
+        description = "Show staged changes (index vs HEAD) instead of unstaged changes (default: false)"
+    )]
+    pub staged: Option<bool>,
+    #[schemars(description = "Only diff these files or directories")]
+    pub files: Option<Vec<String>>,
+    #[schemars(
+        description = "Maximum number of characters of diff to return (default: 50000, max: 200000)"
+    )]
+    pub max_chars: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ApplyPatchRequest {
+    #[schemars(
+        description = "Unified diff to apply (as produced by 'git diff' or 'diff -u'), possibly touching several files"
+    )]
+    pub patch: String,
+    #[schemars(
+        description = "Directory the paths in the patch are relative to (default: current directory)"
+    )]
+    pub base_dir: Option<String>,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct StrReplaceRequest {
     #[schemars(
@@ -262,6 +336,10 @@ pub struct CreateRequest {
         description = "The content to write to the new file, when creating code, ensure the new text has proper syntax, indentation, and follows the codebase style."
     )]
     pub file_text: String,
+    #[schemars(
+        description = "Replace the file if it already exists (default: false). Without it, creating over an existing file fails and reports the existing content's hash."
+    )]
+    pub overwrite: Option<bool>,
     #[schemars(description = "Optional password for remote connection (if path is remote)")]
     pub password: Option<String>,
     #[schemars(
@@ -386,7 +464,7 @@ Use the get_all_tasks tool to monitor task progress, or the cancel_task tool to
     )]
     pub async fn run_command_task(
         &self,
-        _ctx: RequestContext<RoleServer>,
+        ctx: RequestContext<RoleServer>,
         Parameters(RunCommandRequest {
             command,
             description,
@@ -394,6 +472,10 @@ Use the get_all_tasks tool to monitor task progress, or the cancel_task tool to
         }): Parameters<RunCommandRequest>,
     ) -> Result<CallToolResult, McpError> {
         let timeout_duration = timeout.map(std::time::Duration::from_secs);
+        let working_dir = match self.session_root(&ctx) {
+            Ok(root) => root.map(|root| root.path().to_path_buf()),
+            Err(error_result) => return Ok(error_result),
+        };
 
         let result = self
             .get_task_manager()
@@ -403,7 +485,8 @@ Use the get_all_tasks tool to monitor task progress, or the cancel_task tool to
                     description,
                     timeout: timeout_duration,
                     remote_connection: None,
-                    child_env: self.task_child_env_defaults(None),
+                    working_dir,
+                    ..self.task_env_options(None)
                 },
             )
             .await;
@@ -458,7 +541,7 @@ Use the get_all_tasks tool to monitor task progress, or the cancel_task tool to
             private_key_path,
         };
 
-        let child_env = self.task_child_env_defaults(Some(&remote_connection));
+        let env_options = self.task_env_options(Some(&remote_connection));
         let result = self
             .get_task_manager()
             .start_task(
@@ -467,7 +550,8 @@ Use the get_all_tasks tool to monitor task progress, or the cancel_task tool to
                     description,
                     timeout: timeout_duration,
                     remote_connection: Some(remote_connection),
-                    child_env,
+                    working_dir: None,
+                    ..env_options
                 },
             )
             .await;
@@ -822,10 +906,13 @@ GLOB (File Filtering):
   * glob='**/*.ts' - All TypeScript files (recursive)
   * glob='test_*.py' - Python test files
 
-A maximum of 300 lines will be shown at a time, the rest will be truncated."
+A maximum of 300 lines will be shown at a time. Larger files report the total line count and the view_range to request for the next page.
+
+Files over the server's size limit (10 MiB by default) can only be read with 'view_range' or 'grep'."
     )]
     pub async fn view(
         &self,
+        ctx: RequestContext<RoleServer>,
         Parameters(ViewRequest {
             path,
             view_range,
@@ -860,6 +947,10 @@ A maximum of 300 lines will be shown at a time, the rest will be truncated."
             }
         } else {
             // Handle local file/directory viewing
+            let path = match self.resolve_local_path(&ctx, &path) {
+                Ok(path) => path,
+                Err(error_result) => return Ok(error_result),
+            };
             let opts = ViewOptions {
                 view_range,
                 max_lines: MAX_LINES,
@@ -871,6 +962,118 @@ A maximum of 300 lines will be shown at a time, the rest will be truncated."
         }
     }
 
+    #[tool(
+        description = "Search file contents under a local directory for a regex or literal pattern.
+
+Returns matches as 'file:line:text' (context lines as 'file-line-text'), relative to the searched path.
+- Respects .gitignore and skips binary files
+- Use 'include'/'exclude' globs to narrow the files searched (e.g., include=['*.rs'])
+- Use 'literal' to search for text containing regex characters
+- Stops after 'max_results' matches (default 100)
+
+Prefer this over running grep through run_command."
+    )]
+    pub async fn search_files(
+        &self,
+        ctx: RequestContext<RoleServer>,
+        Parameters(SearchFilesRequest {
+            pattern,
+            path,
+            literal,
+            case_insensitive,
+            include,
+            exclude,
+            context_lines,
+            max_results,
+        }): Parameters<SearchFilesRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        const DEFAULT_MAX_RESULTS: usize = 100;
+        const MAX_RESULTS_CAP: usize = 500;
+        const MAX_CONTEXT_LINES: usize = 10;
+
+        let path = match self.resolve_local_path(&ctx, path.as_deref().unwrap_or(".")) {
+            Ok(path) => path,
+            Err(error_result) => return Ok(error_result),
+        };
+        let opts = SearchFilesOptions {
+            literal: literal.unwrap_or(false),
+            case_insensitive: case_insensitive.unwrap_or(false),
+            include: include.unwrap_or_default(),
+            exclude: exclude.unwrap_or_default(),
+            context_lines: context_lines.unwrap_or(0).min(MAX_CONTEXT_LINES),
+            max_results: max_results
+                .unwrap_or(DEFAULT_MAX_RESULTS)
+                .clamp(1, MAX_RESULTS_CAP),
+        };
+
+        Ok(search_files_in_path(&path, &pattern, &opts))
+    }
+
+    #[tool(
+        description = "Show the git status of the repository containing a local directory.
+
+Lists the current branch, then staged, unstaged, untracked and conflicted files.
+Paths are relative to the repository root. Prefer this over running 'git status' through run_command."
+    )]
+    pub async fn git_status(
+        &self,
+        ctx: RequestContext<RoleServer>,
+        Parameters(GitStatusRequest { path }): Parameters<GitStatusRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let path = match self.resolve_local_path(&ctx, path.as_deref().unwrap_or(".")) {
+            Ok(path) => path,
+            Err(error_result) => return Ok(error_result),
+        };
+
+        Ok(git_status_in_dir(Path::new(&path)).await)
+    }
+
+    #[tool(
+        description = "Show the git diff of the repository containing a local directory.
+
+- By default shows unstaged changes (working tree vs index); set 'staged' for changes staged for commit
+- Use 'files' to limit the diff to specific files or directories
+- Output is capped at 'max_chars' characters (default 50000); narrow with 'files' when truncated
+
+Untracked files are not included; use git_status to list them. Prefer this over running 'git diff' through run_command."
+    )]
+    pub async fn git_diff(
+        &self,
+        ctx: RequestContext<RoleServer>,
+        Parameters(GitDiffRequest {
+            path,
+            staged,
+            files,
+            max_chars,
+        }): Parameters<GitDiffRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        const DEFAULT_MAX_CHARS: usize = 50_000;
+        const MAX_CHARS_CAP: usize = 200_000;
+
+        let path = match self.resolve_local_path(&ctx, path.as_deref().unwrap_or(".")) {
+            Ok(path) => path,
+            Err(error_result) => return Ok(error_result),
+        };
+        let mut pathspecs = Vec::new();
+        for file in files.unwrap_or_default() {
+            match self.resolve_local_path(&ctx, &file) {
+                Ok(file) => pathspecs.push(file),
+                Err(error_result) => return Ok(error_result),
+            }
+        }
+        let max_chars = max_chars
+            .unwrap_or(DEFAULT_MAX_CHARS)
+            .clamp(1, MAX_CHARS_CAP);
+
+        Ok(git_diff_in_dir(
+            Path::new(&path),
+            staged.unwrap_or(false),
+            &pathspecs,
+            max_chars,
+        )
+        .await)
+    }
+
     #[tool(
         description = "Replace a specific string in a local or remote file with new text. The old_str must match exactly including whitespace and indentation.
 
@@ -888,6 +1091,7 @@ When replacing code, ensure the new text maintains proper syntax, indentation, a
     )]
     pub async fn str_replace(
         &self,
+        ctx: RequestContext<RoleServer>,
         Parameters(StrReplaceRequest {
             path,
             old_str,
@@ -919,13 +1123,93 @@ When replacing code, ensure the new text maintains proper syntax, indentation, a
             }
         } else {
             // Handle local file replacement
+            let path = match self.resolve_local_path(&ctx, &path) {
+                Ok(path) => path,
+                Err(error_result) => return Ok(error_result),
+            };
             self.str_replace_local(&path, &old_str, &new_str, replace_all)
                 .await
         }
     }
 
     #[tool(
-        description = "Create a new local or remote file with the specified content. Will fail if file already exists. When creating code, ensure the new text has proper syntax, indentation, and follows the codebase style. Parent directories will be created automatically if they don't exist.
+        description = "Apply a unified diff to local files. Prefer this over str_replace for edits with several hunks or files.
+
+BEHAVIOR:
+- All-or-nothing: if any hunk fails to apply, no file is changed
+- Context and removed lines must match the file exactly; hunks may sit at a different line than their header says
+- Supports new files ('--- /dev/null'), deletions ('+++ /dev/null') and renames ('rename from'/'rename to', or different '---' and '+++' paths)
+- 'a/' and 'b/' path prefixes from git diffs are stripped
+- On conflict, the error names the failing file and hunk and the first line that differs
+
+Returns the list of files that were created, modified, renamed or deleted."
+    )]
+    pub async fn apply_patch(
+        &self,
+        ctx: RequestContext<RoleServer>,
+        Parameters(ApplyPatchRequest { patch, base_dir }): Parameters<ApplyPatchRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let base_dir = match self.resolve_local_path(&ctx, base_dir.as_deref().unwrap_or(".")) {
+            Ok(base_dir) => PathBuf::from(base_dir),
+            Err(error_result) => return Ok(error_result),
+        };
+
+        let patches = match parse_unified_diff(&patch) {
+            Ok(patches) => patches,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![
+                    Content::text(e.code()),
+                    Content::text(e.to_string()),
+                ]));
+            }
+        };
+        // Each file must stay inside the session root, not just the base dir
+        for path in patches.iter().flat_map(|patch| patch.paths()) {
+            if let Err(error_result) =
+                self.resolve_local_path(&ctx, &base_dir.join(path).to_string_lossy())
+            {
+                return Ok(error_result);
+            }
+        }
+
+        let result = apply_patches(&base_dir, &patches);
+
+        match result {
+            Ok(changes) => {
+                let summary = changes
+                    .iter()
+                    .map(|change| {
+                        let path = match &change.renamed_from {
+                            Some(from) => {
+                                format!("{} -> {}", from.display(), change.path.display())
+                            }
+                            None => change.path.display().to_string(),
+                        };
+                        format!(
+                            "  {} {} (+{} -{})",
+                            change.kind, path, change.added, change.removed
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Successfully applied patch to {} files:\n{}",
+                    changes.len(),
+                    summary
+                ))]))
+            }
+            Err(e) => {
+                error!("Failed to apply patch: {}", e);
+                Ok(CallToolResult::error(vec![
+                    Content::text(e.code()),
+                    Content::text(e.to_string()),
+                ]))
+            }
+        }
+    }
+
+    #[tool(
+        description = "Create a new local or remote file with the specified content. Fails if the file already exists unless 'overwrite' is true; the error includes the existing content's sha256 so you can check it is the file you expect before overwriting. When creating code, ensure the new text has proper syntax, indentation, and follows the codebase style. Parent directories will be created automatically if they don't exist.
 
 REMOTE FILE CREATION:
 - Use path formats: 'user@host:/path' or 'user@host#port:/path' for remote files
@@ -940,13 +1224,17 @@ REMOTE FILE CREATION:
     )]
     pub async fn create(
         &self,
+        ctx: RequestContext<RoleServer>,
         Parameters(CreateRequest {
             path,
             file_text,
+            overwrite,
             password,
             private_key_path,
         }): Parameters<CreateRequest>,
     ) -> Result<CallToolResult, McpError> {
+        let overwrite = overwrite.unwrap_or(false);
+
         // Check if this is a remote path
         if Self::is_remote_path(&path) {
             // Handle remote file creation
@@ -955,14 +1243,18 @@ REMOTE FILE CREATION:
                 .await
             {
                 Ok((conn, remote_path)) => {
-                    self.create_remote(&conn, &remote_path, &path, &file_text)
+                    self.create_remote(&conn, &remote_path, &path, &file_text, overwrite)
                         .await
                 }
                 Err(error_result) => Ok(error_result),
             }
         } else {
             // Handle local file creation
-            self.create_local(&path, &file_text)
+            let path = match self.resolve_local_path(&ctx, &path) {
+                Ok(path) => path,
+                Err(error_result) => return Ok(error_result),
+            };
+            self.create_local(&path, &file_text, overwrite)
         }
     }
 
@@ -1120,7 +1412,7 @@ SAFETY NOTES:
     )]
     pub async fn remove(
         &self,
-        _ctx: RequestContext<RoleServer>,
+        ctx: RequestContext<RoleServer>,
         Parameters(RemoveRequest {
             path,
             recursive,
@@ -1142,6 +1434,10 @@ SAFETY NOTES:
                 Err(error_result) => Ok(error_result),
             }
         } else {
+            let path = match self.resolve_local_path(&ctx, &path) {
+                Ok(path) => path,
+                Err(error_result) => return Ok(error_result),
+            };
             self.remove_local_path(&path, recursive).await
         }
     }
@@ -1246,13 +1542,19 @@ SAFETY NOTES:
     }
 
     fn apply_local_command_env(&self, cmd: &mut Command) {
+        cmd.env_clear()
+            .envs(self.local_runtime_defaults.child_env());
         if let Some(profile_name) = self.local_runtime_defaults.active_profile_name() {
             cmd.env("STAKPAK_PROFILE", profile_name);
         }
     }
 
     fn local_child_env_defaults(&self) -> std::collections::HashMap<String, String> {
-        let mut child_env = std::collections::HashMap::new();
+        let mut child_env: std::collections::HashMap<String, String> = self
+            .local_runtime_defaults
+            .child_env()
+            .into_iter()
+            .collect();
         if let Some(profile_name) = self.local_runtime_defaults.active_profile_name() {
             child_env.insert("STAKPAK_PROFILE".to_string(), profile_name.to_string());
         }
@@ -1270,6 +1572,20 @@ SAFETY NOTES:
         }
     }
 
+    /// Environment policy for a background task: local tasks start from the
+    /// filtered env only, and denied values are scrubbed from every task's output.
+    fn task_env_options(
+        &self,
+        remote_connection: Option<&RemoteConnectionInfo>,
+    ) -> StartTaskOptions {
+        StartTaskOptions {
+            child_env: self.task_child_env_defaults(remote_connection),
+            clear_env: remote_connection.is_none(),
+            redactions: self.local_runtime_defaults.env_redactions(),
+            ..StartTaskOptions::default()
+        }
+    }
+
     /// Execute command either locally or remotely based on parameters.
     async fn execute_command_unified(
         &self,
@@ -1332,13 +1648,19 @@ SAFETY NOTES:
         timeout: Option<u64>,
         ctx: &RequestContext<RoleServer>,
     ) -> Result<CommandResult, CallToolResult> {
+        let working_dir = self.session_root(ctx)?;
+
         let mut cmd = Command::new("sh");
         cmd.arg("-c")
             .arg(actual_command)
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped());
+        if let Some(working_dir) = working_dir {
+            cmd.current_dir(working_dir.path());
+        }
         self.apply_local_command_env(&mut cmd);
+        cmd.envs(self.request_metadata(ctx).child_env());
         #[cfg(unix)]
         {
             cmd.env("DEBIAN_FRONTEND", "noninteractive")
@@ -1369,6 +1691,7 @@ SAFETY NOTES:
         let mut stderr_buf = String::new();
         let mut result = String::new();
         let progress_id = Uuid::new_v4();
+        let redactions = self.local_runtime_defaults.env_redactions();
 
         // Stall detection: track last output time and stall start time for incrementing counter
         let mut last_output_time = std::time::Instant::now();
@@ -1388,7 +1711,7 @@ SAFETY NOTES:
                         Ok(Ok(_)) => {
                             last_output_time = std::time::Instant::now();
                             stall_start_time = None; // Reset stall tracking on output
-                            let line = $buf.trim_end_matches('\n').to_string();
+                            let line = redact_env_values($buf.trim_end_matches('\n'), &redactions);
                             $buf.clear();
                             result.push_str(&format!("{}\n", line));
                             let _ = ctx
@@ -1599,6 +1922,18 @@ SAFETY NOTES:
                 return self.grep_local_file(path, grep_pattern, opts.max_lines);
             }
 
+            // Page through oversized files instead of loading them
+            let max_file_size = self.local_runtime_defaults.max_file_size();
+            let size = fs::metadata(path_obj).map(|m| m.len()).unwrap_or(0);
+            if size > max_file_size {
+                return Ok(match opts.view_range {
+                    Some(view_range) => {
+                        view_large_file_range(path, view_range, opts.max_lines, max_file_size)
+                    }
+                    None => file_too_large_error(path, size, max_file_size, "view"),
+                });
+            }
+
             // Read file contents
             match fs::read_to_string(path) {
                 Ok(content) => {
@@ -2046,6 +2381,20 @@ SAFETY NOTES:
                     .await;
             }
 
+            // A view_range is how oversized files are paged, so only gate full views
+            let max_file_size = self.local_runtime_defaults.max_file_size();
+            if opts.view_range.is_none()
+                && let Ok(size) = conn.file_size(remote_path).await
+                && size > max_file_size
+            {
+                return Ok(file_too_large_error(
+                    original_path,
+                    size,
+                    max_file_size,
+                    "view",
+                ));
+            }
+
             // Read remote file contents
             match conn.read_file_to_string(remote_path).await {
                 Ok(content) => {
@@ -2355,92 +2704,100 @@ SAFETY NOTES:
         max_lines: usize,
         prefix: &str,
     ) -> Result<String, McpError> {
-        let result = if let Some([start, end]) = view_range {
-            let lines: Vec<&str> = content.lines().collect();
-            let start_idx = if start <= 0 { 0 } else { (start - 1) as usize };
-            let end_idx = if end == -1 {
-                lines.len()
-            } else {
-                std::cmp::min(end as usize, lines.len())
-            };
+        let lines: Vec<&str> = content.lines().collect();
+        let total = lines.len();
+
+        if view_range.is_none() && total <= max_lines {
+            return Ok(format!(
+                "{}: {} ({} lines)\n{}",
+                prefix,
+                path,
+                total,
+                Self::number_lines(&lines, 0)
+            ));
+        }
 
-            if start_idx >= lines.len() {
-                return Err(McpError::internal_error(
-                    "Invalid range",
-                    Some(json!({
-                        "error": format!("Start line {} is beyond file length {}", start, lines.len())
-                    })),
-                ));
-            }
+        let (start_idx, end_idx, clamped) = Self::clamp_view_range(view_range, total);
+        let shown_end = std::cmp::min(end_idx, start_idx + max_lines);
+        let selected_lines = &lines[start_idx..shown_end];
 
-            let selected_lines = &lines[start_idx..end_idx];
-            if selected_lines.len() <= max_lines {
-                format!(
-                    "{}: {} (lines {}-{})\n{}",
-                    prefix,
-                    path,
-                    start_idx + 1,
-                    end_idx,
-                    selected_lines
-                        .iter()
-                        .enumerate()
-                        .map(|(i, line)| format!("{:3}: {}", start_idx + i + 1, line))
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                )
-            } else {
-                let selected_lines = selected_lines.iter().take(max_lines).collect::<Vec<_>>();
-                format!(
-                    "{}: {} (showing lines {}-{}, only the first {} lines of your view range)\n{}\n...",
-                    prefix,
-                    path,
-                    start_idx + 1,
-                    start_idx + 1 + max_lines,
-                    max_lines,
-                    selected_lines
-                        .iter()
-                        .enumerate()
-                        .map(|(i, line)| format!("{:4}: {}", start_idx + i + 1, line))
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                )
-            }
+        let mut result = if selected_lines.is_empty() {
+            format!("{}: {} (0 lines)", prefix, path)
         } else {
-            let lines: Vec<&str> = content.lines().collect();
-            if lines.len() <= max_lines {
-                format!(
-                    "{}: {} ({} lines)\n{}",
-                    prefix,
-                    path,
-                    lines.len(),
-                    lines
-                        .iter()
-                        .enumerate()
-                        .map(|(i, line)| format!("{:3}: {}", i + 1, line))
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                )
-            } else {
-                let selected_lines = lines.iter().take(max_lines).collect::<Vec<_>>();
-                format!(
-                    "{}: {} (showing {} / {} lines)\n{}\n...",
-                    prefix,
-                    path,
-                    max_lines,
-                    lines.len(),
-                    selected_lines
-                        .iter()
-                        .enumerate()
-                        .map(|(i, line)| format!("{:3}: {}", i + 1, line))
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                )
-            }
+            format!(
+                "{}: {} (lines {}-{} of {})\n{}",
+                prefix,
+                path,
+                start_idx + 1,
+                shown_end,
+                total,
+                Self::number_lines(selected_lines, start_idx)
+            )
         };
 
+        if clamped && let Some([start, end]) = view_range {
+            result.push_str(&format!(
+                "\n[requested lines {}-{} were clamped to the file length of {} lines]",
+                start, end, total
+            ));
+        }
+
+        if shown_end < total {
+            result.push_str(&format!(
+                "\n... {} more lines. Use view_range [{}, {}] to see the next page.",
+                total - shown_end,
+                shown_end + 1,
+                std::cmp::min(shown_end + max_lines, total)
+            ));
+        }
+
         Ok(result)
     }
 
+    /// Resolve a 1-indexed, inclusive `view_range` to a 0-indexed half-open range
+    /// within `total` lines. Returns whether the request had to be clamped.
+    fn clamp_view_range(view_range: Option<[i32; 2]>, total: usize) -> (usize, usize, bool) {
+        let Some([start, end]) = view_range else {
+            return (0, total, false);
+        };
+
+        let requested_start = if start <= 0 { 0 } else { (start - 1) as usize };
+        let requested_end = if end < 0 {
+            total
+        } else {
+            std::cmp::max(end as usize, requested_start + 1)
+        };
+
+        let start_idx = std::cmp::min(requested_start, total.saturating_sub(1));
+        let end_idx = std::cmp::max(std::cmp::min(requested_end, total), start_idx);
+        let clamped = requested_start >= total || (end >= 0 && end as usize > total);
+
+        (start_idx, end_idx, clamped)
+    }
+
+    fn number_lines(lines: &[&str], start_idx: usize) -> String {
+        lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| format!("{:3}: {}", start_idx + i + 1, Self::cap_line(line)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Truncate very long lines (e.g. minified files) so one line can't flood the context.
+    fn cap_line(line: &str) -> std::borrow::Cow<'_, str> {
+        const MAX_LINE_CHARS: usize = 2000;
+
+        match line.char_indices().nth(MAX_LINE_CHARS) {
+            Some((byte_idx, _)) => std::borrow::Cow::Owned(format!(
+                "{}... [line truncated, {} chars]",
+                line.get(..byte_idx).unwrap_or_default(),
+                line.chars().count()
+            )),
+            None => std::borrow::Cow::Borrowed(line),
+        }
+    }
+
     fn create_unified_diff(
         &self,
         original: &str,
@@ -2477,6 +2834,18 @@ SAFETY NOTES:
             ]));
         }
 
+        let max_file_size = self.local_runtime_defaults.max_file_size();
+        if let Ok(size) = conn.file_size(remote_path).await
+            && size > max_file_size
+        {
+            return Ok(file_too_large_error(
+                original_path,
+                size,
+                max_file_size,
+                "str_replace",
+            ));
+        }
+
         let content = match conn.read_file_to_string(remote_path).await {
             Ok(content) => content,
             Err(e) => {
@@ -2545,6 +2914,18 @@ SAFETY NOTES:
             ]));
         }
 
+        let max_file_size = self.local_runtime_defaults.max_file_size();
+        if let Ok(metadata) = fs::metadata(path)
+            && metadata.len() > max_file_size
+        {
+            return Ok(file_too_large_error(
+                path,
+                metadata.len(),
+                max_file_size,
+                "str_replace",
+            ));
+        }
+
         let original_content = match fs::read_to_string(path) {
             Ok(content) => content,
             Err(e) => {
@@ -2624,12 +3005,36 @@ SAFETY NOTES:
         remote_path: &str,
         original_path: &str,
         file_text: &str,
+        overwrite: bool,
     ) -> Result<CallToolResult, McpError> {
-        if conn.exists(remote_path).await {
-            return Ok(CallToolResult::error(vec![
-                Content::text("FILE_EXISTS"),
-                Content::text(format!("Remote file already exists: {}", original_path)),
-            ]));
+        let max_file_size = self.local_runtime_defaults.max_file_size();
+        if file_text.len() as u64 > max_file_size {
+            return Ok(file_too_large_error(
+                original_path,
+                file_text.len() as u64,
+                max_file_size,
+                "create",
+            ));
+        }
+
+        let existed = conn.exists(remote_path).await;
+        if existed {
+            if conn.is_directory(remote_path).await {
+                return Ok(CallToolResult::error(vec![
+                    Content::text("PATH_IS_DIRECTORY"),
+                    Content::text(format!("Remote path is a directory: {}", original_path)),
+                ]));
+            }
+            if !overwrite {
+                let hash = match conn.read_file(remote_path).await {
+                    Ok(content) => content_sha256(&content),
+                    Err(_) => "unknown".to_string(),
+                };
+                return Ok(file_exists_error(
+                    &format!("Remote file already exists: {}", original_path),
+                    &hash,
+                ));
+            }
         }
 
         // Create parent directories if needed
@@ -2666,21 +3071,54 @@ SAFETY NOTES:
         }
 
         let lines = file_text.lines().count();
+        let verb = if existed { "overwrote" } else { "created" };
         Ok(CallToolResult::success(vec![Content::text(format!(
-            "Successfully created remote file {} with {} lines",
-            original_path, lines
+            "Successfully {} remote file {} with {} lines",
+            verb, original_path, lines
         ))]))
     }
 
-    /// Create a local file with the specified content
-    fn create_local(&self, path: &str, file_text: &str) -> Result<CallToolResult, McpError> {
+    /// Create a local file with the specified content, replacing an existing
+    /// file only when `overwrite` is set
+    fn create_local(
+        &self,
+        path: &str,
+        file_text: &str,
+        overwrite: bool,
+    ) -> Result<CallToolResult, McpError> {
+        let max_file_size = self.local_runtime_defaults.max_file_size();
+        if file_text.len() as u64 > max_file_size {
+            return Ok(file_too_large_error(
+                path,
+                file_text.len() as u64,
+                max_file_size,
+                "create",
+            ));
+        }
+
         let path_obj = Path::new(&path);
 
-        if path_obj.exists() {
-            return Ok(CallToolResult::error(vec![
-                Content::text("FILE_EXISTS"),
-                Content::text(format!("File already exists: {}", path)),
-            ]));
+        let existed = path_obj.exists();
+        if existed {
+            if path_obj.is_dir() {
+                return Ok(CallToolResult::error(vec![
+                    Content::text("PATH_IS_DIRECTORY"),
+                    Content::text(format!("Path is a directory: {}", path)),
+                ]));
+            }
+            if !overwrite {
+                let hash = fs::File::open(path_obj)
+                    .and_then(|mut file| {
+                        let mut hasher = Sha256::new();
+                        std::io::copy(&mut file, &mut hasher)?;
+                        Ok(format!("{:x}", hasher.finalize()))
+                    })
+                    .unwrap_or_else(|_| "unknown".to_string());
+                return Ok(file_exists_error(
+                    &format!("File already exists: {}", path),
+                    &hash,
+                ));
+            }
         }
 
         // Create parent directories if they don't exist
@@ -2699,9 +3137,10 @@ SAFETY NOTES:
                 let lines = fs::read_to_string(path)
                     .map(|content| content.lines().count())
                     .unwrap_or(0);
+                let verb = if existed { "overwrote" } else { "created" };
                 Ok(CallToolResult::success(vec![Content::text(format!(
-                    "Successfully created file {} with {} lines",
-                    path, lines
+                    "Successfully {} file {} with {} lines",
+                    verb, path, lines
                 ))]))
             }
             Err(e) => Ok(CallToolResult::error(vec![
@@ -3102,6 +3541,531 @@ WHEN TO USE:
     }
 }
 
+/// Collects `search_files` output for a single file, ripgrep style.
+struct SearchFilesSink<'a> {
+    relative: &'a str,
+    lines: &'a mut Vec<String>,
+    match_count: &'a mut usize,
+    max_results: usize,
+}
+
+impl Sink for SearchFilesSink<'_> {
+    type Error = std::io::Error;
+
+    fn matched(&mut self, _searcher: &Searcher, mat: &SinkMatch<'_>) -> Result<bool, Self::Error> {
+        if *self.match_count >= self.max_results {
+            return Ok(false);
+        }
+        *self.match_count += 1;
+        self.lines.push(format!(
+            "{}:{}:{}",
+            self.relative,
+            mat.line_number().unwrap_or_default(),
+            String::from_utf8_lossy(mat.bytes()).trim_end()
+        ));
+        Ok(*self.match_count < self.max_results)
+    }
+
+    fn context(
+        &mut self,
+        _searcher: &Searcher,
+        context: &SinkContext<'_>,
+    ) -> Result<bool, Self::Error> {
+        self.lines.push(format!(
+            "{}-{}-{}",
+            self.relative,
+            context.line_number().unwrap_or_default(),
+            String::from_utf8_lossy(context.bytes()).trim_end()
+        ));
+        Ok(true)
+    }
+
+    fn context_break(&mut self, _searcher: &Searcher) -> Result<bool, Self::Error> {
+        self.lines.push("--".to_string());
+        Ok(true)
+    }
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<Option<GlobSet>, String> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob =
+            Glob::new(pattern).map_err(|e| format!("Invalid glob pattern '{}': {}", pattern, e))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map(Some)
+        .map_err(|e| format!("Invalid glob patterns: {}", e))
+}
+
+/// Search file contents under `path`, respecting .gitignore and the include/exclude globs.
+fn search_files_in_path(path: &str, pattern: &str, opts: &SearchFilesOptions) -> CallToolResult {
+    let matcher = match RegexMatcherBuilder::new()
+        .case_insensitive(opts.case_insensitive)
+        .fixed_strings(opts.literal)
+        .build(pattern)
+    {
+        Ok(matcher) => matcher,
+        Err(e) => {
+            return CallToolResult::error(vec![
+                Content::text("INVALID_REGEX"),
+                Content::text(format!("Invalid regex pattern '{}': {}", pattern, e)),
+            ]);
+        }
+    };
+
+    let (include, exclude) = match (build_glob_set(&opts.include), build_glob_set(&opts.exclude)) {
+        (Ok(include), Ok(exclude)) => (include, exclude),
+        (Err(e), _) | (_, Err(e)) => {
+            return CallToolResult::error(vec![Content::text("INVALID_GLOB"), Content::text(e)]);
+        }
+    };
+
+    let base_path = Path::new(path);
+    if !base_path.exists() {
+        return CallToolResult::error(vec![
+            Content::text("PATH_NOT_FOUND"),
+            Content::text(format!("Path does not exist: {}", path)),
+        ]);
+    }
+
+    let mut searcher = SearcherBuilder::new()
+        .line_number(true)
+        .before_context(opts.context_lines)
+        .after_context(opts.context_lines)
+        .binary_detection(BinaryDetection::quit(b'\x00'))
+        .build();
+
+    let walker = WalkBuilder::new(path)
+        .hidden(false)
+        .git_ignore(true)
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut match_count = 0;
+    let mut files_with_matches = 0;
+
+    for entry in walker.flatten() {
+        if match_count >= opts.max_results {
+            break;
+        }
+
+        let entry_path = entry.path();
+        if !entry_path.is_file() {
+            continue;
+        }
+
+        let relative = entry_path
+            .strip_prefix(base_path)
+            .ok()
+            .filter(|r| !r.as_os_str().is_empty())
+            .map(|r| r.to_string_lossy().to_string())
+            .unwrap_or_else(|| entry_path.to_string_lossy().to_string());
+        let file_name = entry_path.file_name().unwrap_or_default();
+        let glob_matches = |set: &GlobSet| set.is_match(&relative) || set.is_match(file_name);
+
+        if include.as_ref().is_some_and(|set| !glob_matches(set))
+            || exclude.as_ref().is_some_and(glob_matches)
+        {
+            continue;
+        }
+
+        let mut file_lines = Vec::new();
+        let before = match_count;
+        let _ = searcher.search_path(
+            &matcher,
+            entry_path,
+            SearchFilesSink {
+                relative: &relative,
+                lines: &mut file_lines,
+                match_count: &mut match_count,
+                max_results: opts.max_results,
+            },
+        );
+
+        if match_count > before {
+            files_with_matches += 1;
+            if opts.context_lines > 0 && !lines.is_empty() {
+                lines.push("--".to_string());
+            }
+            lines.extend(file_lines);
+        }
+    }
+
+    if match_count == 0 {
+        return CallToolResult::success(vec![Content::text(format!(
+            "No matches for '{}' in {}",
+            pattern, path
+        ))]);
+    }
+
+    let truncated = if match_count >= opts.max_results {
+        format!(
+            "\n\n... (stopped after {} matches; narrow the pattern or use include globs)",
+            opts.max_results
+        )
+    } else {
+        String::new()
+    };
+
+    CallToolResult::success(vec![Content::text(format!(
+        "Search results for '{}' in \"{}\" ({} matches in {} files):\n\n{}{}",
+        pattern,
+        path,
+        match_count,
+        files_with_matches,
+        lines.join("\n"),
+        truncated
+    ))])
+}
+
+/// Error for a file over the configured `max_file_size`, pointing at ways to
+/// work with it that do not load the whole file.
+fn file_too_large_error(path: &str, size: u64, max_file_size: u64, tool: &str) -> CallToolResult {
+    let hint = match tool {
+        "view" => "Use view with 'view_range' to page through it, or 'grep' to search it",
+        "create" => "Write it in smaller files, or generate it with run_command",
+        _ => {
+            "Use view with 'view_range' or 'grep' to find the lines, then edit them with run_command (e.g. sed)"
+        }
+    };
+    CallToolResult::error(vec![
+        Content::text("FILE_TOO_LARGE"),
+        Content::text(format!(
+            "{} is {} bytes, over the {} byte limit for {}. {}.",
+            path, size, max_file_size, tool, hint
+        )),
+    ])
+}
+
+/// Read one line into `buf`, keeping at most `max_bytes` of it and skipping
+/// the rest.
+fn read_capped_line(
+    reader: &mut impl std::io::BufRead,
+    buf: &mut Vec<u8>,
+    max_bytes: u64,
+) -> std::io::Result<usize> {
+    use std::io::{BufRead, Read};
+
+    let read = reader.by_ref().take(max_bytes).read_until(b'\n', buf)?;
+    if read > 0 && buf.last() != Some(&b'\n') {
+        reader.skip_until(b'\n')?;
+    }
+    Ok(read)
+}
+
+/// Read a 1-indexed, inclusive line range from a file too large to load,
+/// streaming up to the last requested line.
+fn view_large_file_range(
+    path: &str,
+    view_range: [i32; 2],
+    max_lines: usize,
+    max_file_size: u64,
+) -> CallToolResult {
+    // Longer lines are cut here while reading and capped further for display
+    const MAX_LINE_BYTES: u64 = 64 * 1024;
+
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) => {
+            return CallToolResult::error(vec![
+                Content::text("READ_ERROR"),
+                Content::text(format!("Cannot read file: {}", e)),
+            ]);
+        }
+    };
+
+    let [start, end] = view_range;
+    let start_idx = if start <= 0 { 0 } else { (start - 1) as usize };
+    let end_idx = if end < 0 {
+        start_idx + max_lines
+    } else {
+        (end as usize).max(start_idx + 1).min(start_idx + max_lines)
+    };
+
+    let mut reader = std::io::BufReader::new(file);
+    let mut buf = Vec::new();
+    let mut lines = Vec::new();
+    let mut line_idx = 0;
+    while line_idx < end_idx {
+        buf.clear();
+        let read = read_capped_line(&mut reader, &mut buf, MAX_LINE_BYTES);
+        match read {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => {
+                return CallToolResult::error(vec![
+                    Content::text("READ_ERROR"),
+                    Content::text(format!("Cannot read file: {}", e)),
+                ]);
+            }
+        }
+        if line_idx >= start_idx {
+            let line = String::from_utf8_lossy(&buf);
+            lines.push(line.trim_end_matches(['\n', '\r']).to_string());
+        }
+        line_idx += 1;
+    }
+
+    if lines.is_empty() {
+        return CallToolResult::success(vec![Content::text(format!(
+            "File: {} has only {} lines",
+            path, line_idx
+        ))]);
+    }
+
+    let shown_end = start_idx + lines.len();
+    let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+    CallToolResult::success(vec![Content::text(format!(
+        "File: {} (lines {}-{}; over the {} byte limit, so the total line count is not shown)\n{}\n... Use view_range [{}, {}] to see the next page.",
+        path,
+        start_idx + 1,
+        shown_end,
+        max_file_size,
+        ToolContainer::number_lines(&lines, start_idx),
+        shown_end + 1,
+        shown_end + max_lines
+    ))])
+}
+
+/// Hex sha256 of file content, as reported in `FILE_EXISTS` errors.
+pub fn content_sha256(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// Error returned by `create` when the target exists and `overwrite` is not set.
+pub fn file_exists_error(message: &str, hash: &str) -> CallToolResult {
+    CallToolResult::error(vec![
+        Content::text("FILE_EXISTS"),
+        Content::text(format!(
+            "{} (sha256: {}). Set overwrite=true to replace it, or use str_replace to edit it.",
+            message, hash
+        )),
+    ])
+}
+
+/// Run a read-only git command in `dir`.
+///
+/// Pagers, external diff drivers, textconv filters, fsmonitor hooks and
+/// optional index locks are disabled so the command cannot run repository
+/// supplied programs or contend with the user's own git processes.
+async fn run_git(dir: &Path, args: &[&str]) -> Result<String, CallToolResult> {
+    const GIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(dir)
+        .args([
+            "--no-pager",
+            "-c",
+            "core.fsmonitor=false",
+            "-c",
+            "color.ui=false",
+        ])
+        .args(args)
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+
+    let output = match tokio_timeout(GIT_TIMEOUT, command.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => {
+            return Err(CallToolResult::error(vec![
+                Content::text("GIT_UNAVAILABLE"),
+                Content::text(format!("Failed to run git: {}", e)),
+            ]));
+        }
+        Err(_) => {
+            return Err(CallToolResult::error(vec![
+                Content::text("GIT_TIMEOUT"),
+                Content::text(format!(
+                    "git {} timed out after {}s",
+                    args.first().unwrap_or(&""),
+                    GIT_TIMEOUT.as_secs()
+                )),
+            ]));
+        }
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let code = if stderr.contains("not a git repository") {
+            "NOT_A_GIT_REPOSITORY"
+        } else {
+            "GIT_ERROR"
+        };
+        return Err(CallToolResult::error(vec![
+            Content::text(code),
+            Content::text(stderr.trim().to_string()),
+        ]));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Files reported by `git status`, grouped the way they are shown to the agent.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct GitStatusSummary {
+    branch: Option<String>,
+    staged: Vec<String>,
+    unstaged: Vec<String>,
+    untracked: Vec<String>,
+    conflicted: Vec<String>,
+}
+
+impl GitStatusSummary {
+    /// Parse `git status --porcelain=v1 -z --branch` output.
+    fn parse(output: &str) -> Self {
+        let mut summary = Self::default();
+        let mut entries = output.split('\0').filter(|entry| !entry.is_empty());
+
+        while let Some(entry) = entries.next() {
+            if let Some(branch) = entry.strip_prefix("## ") {
+                summary.branch = Some(branch.to_string());
+                continue;
+            }
+
+            let mut chars = entry.chars();
+            let (Some(index), Some(worktree), Some(' ')) =
+                (chars.next(), chars.next(), chars.next())
+            else {
+                continue;
+            };
+            let path: String = chars.collect();
+
+            match (index, worktree) {
+                ('?', '?') => summary.untracked.push(path),
+                ('!', '!') => {}
+                ('U', _) | (_, 'U') | ('A', 'A') | ('D', 'D') => summary
+                    .conflicted
+                    .push(format!("{}{} {}", index, worktree, path)),
+                _ => {
+                    // Renames and copies are followed by the original path
+                    let display = if matches!(index, 'R' | 'C') {
+                        match entries.next() {
+                            Some(original) => format!("{} -> {}", original, path),
+                            None => path,
+                        }
+                    } else {
+                        path
+                    };
+                    if index != ' ' {
+                        summary.staged.push(format!("{} {}", index, display));
+                    }
+                    if worktree != ' ' {
+                        summary.unstaged.push(format!("{} {}", worktree, display));
+                    }
+                }
+            }
+        }
+
+        summary
+    }
+
+    fn is_clean(&self) -> bool {
+        self.staged.is_empty()
+            && self.unstaged.is_empty()
+            && self.untracked.is_empty()
+            && self.conflicted.is_empty()
+    }
+
+    fn render(&self) -> String {
+        let mut out = match &self.branch {
+            Some(branch) => format!("On branch {}", branch),
+            None => "Git status".to_string(),
+        };
+
+        if self.is_clean() {
+            out.push_str("\n\nNothing to commit, working tree clean");
+            return out;
+        }
+
+        for (title, files) in [
+            ("Staged", &self.staged),
+            ("Unstaged", &self.unstaged),
+            ("Untracked", &self.untracked),
+            ("Conflicted", &self.conflicted),
+        ] {
+            if files.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("\n\n{} ({}):", title, files.len()));
+            for file in files {
+                out.push_str("\n  ");
+                out.push_str(file);
+            }
+        }
+        out
+    }
+}
+
+async fn git_status_in_dir(dir: &Path) -> CallToolResult {
+    match run_git(
+        dir,
+        &[
+            "status",
+            "--porcelain=v1",
+            "-z",
+            "--branch",
+            "--untracked-files=all",
+        ],
+    )
+    .await
+    {
+        Ok(output) => CallToolResult::success(vec![Content::text(
+            GitStatusSummary::parse(&output).render(),
+        )]),
+        Err(error_result) => error_result,
+    }
+}
+
+async fn git_diff_in_dir(
+    dir: &Path,
+    staged: bool,
+    pathspecs: &[String],
+    max_chars: usize,
+) -> CallToolResult {
+    let mut args = vec!["diff", "--no-ext-diff", "--no-textconv"];
+    if staged {
+        args.push("--cached");
+    }
+    args.push("--");
+    args.extend(pathspecs.iter().map(String::as_str));
+
+    let diff = match run_git(dir, &args).await {
+        Ok(diff) => diff,
+        Err(error_result) => return error_result,
+    };
+
+    let kind = if staged { "staged" } else { "unstaged" };
+    if diff.trim().is_empty() {
+        return CallToolResult::success(vec![Content::text(format!("No {} changes", kind))]);
+    }
+
+    let total_chars = diff.chars().count();
+    if total_chars <= max_chars {
+        return CallToolResult::success(vec![Content::text(diff)]);
+    }
+
+    let truncated: String = diff.chars().take(max_chars).collect();
+    CallToolResult::success(vec![Content::text(format!(
+        "{}\n\n... ({} diff truncated at {} of {} characters; pass 'files' to narrow it)",
+        truncated.trim_end(),
+        kind,
+        max_chars,
+        total_chars
+    ))])
+}
+
 /// Normalize a single character: map common Unicode "fancy" characters to their
 /// ASCII equivalents.  Most mappings are 1-to-1, but some are 1-to-many (e.g.
 /// `…` → `...`).  Returns `None` when the character requires no normalisation.
@@ -3441,6 +4405,463 @@ mod tests {
         assert_eq!(output, "readonly");
     }
 
+    fn parent_env(pairs: &[(&str, &str)]) -> std::collections::HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn run_command_spawn_omits_denied_env_and_redacts_output() {
+        let container = local_container_with_profile(None)
+            .with_run_command_env(crate::RunCommandEnvConfig {
+                allow: vec!["APP_*".to_string()],
+                deny: Vec::new(),
+            })
+            .with_parent_env(parent_env(&[
+                ("APP_DEPLOY_TOKEN", "tok_live_5f2a9c"),
+                ("APP_REGION", "eu-west-1"),
+            ]));
+
+        let output = run_profile_probe(
+            &container,
+            "printf '%s,%s' \"${APP_DEPLOY_TOKEN:-missing}\" \"$APP_REGION\"",
+        )
+        .await;
+        assert_eq!(output, "missing,eu-west-1");
+
+        let redactions = container.local_runtime_defaults.env_redactions();
+        assert_eq!(
+            redact_env_values("curl -H 'Authorization: tok_live_5f2a9c'", &redactions),
+            "curl -H 'Authorization: [REDACTED:APP_DEPLOY_TOKEN]'"
+        );
+    }
+
+    fn numbered_content(total: usize) -> String {
+        (1..=total)
+            .map(|i| format!("line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn view_range_returns_slice_with_total_and_next_page_hint() {
+        let container = local_container_with_profile(None);
+        let result = container
+            .format_file_content(&numbered_content(10), "f.txt", Some([3, 5]), 300, "File")
+            .expect("ranged view should format");
+
+        assert_eq!(
+            result,
+            "File: f.txt (lines 3-5 of 10)\n  3: line 3\n  4: line 4\n  5: line 5\n\
+             ... 5 more lines. Use view_range [6, 10] to see the next page."
+        );
+    }
+
+    #[test]
+    fn view_range_beyond_file_length_is_clamped() {
+        let container = local_container_with_profile(None);
+        let result = container
+            .format_file_content(&numbered_content(10), "f.txt", Some([50, 80]), 300, "File")
+            .expect("out-of-range view should be clamped, not rejected");
+
+        assert!(result.starts_with("File: f.txt (lines 10-10 of 10)\n 10: line 10"));
+        assert!(
+            result.contains("[requested lines 50-80 were clamped to the file length of 10 lines]")
+        );
+        assert!(!result.contains("more lines"));
+
+        let reversed = container
+            .format_file_content(&numbered_content(10), "f.txt", Some([7, 2]), 300, "File")
+            .expect("reversed range should format");
+        assert!(reversed.starts_with("File: f.txt (lines 7-7 of 10)"));
+    }
+
+    #[test]
+    fn view_without_range_caps_large_files_and_reports_total_lines() {
+        let container = local_container_with_profile(None);
+        let result = container
+            .format_file_content(&numbered_content(450), "big.txt", None, 300, "File")
+            .expect("large view should format");
+
+        assert!(result.starts_with("File: big.txt (lines 1-300 of 450)\n"));
+        assert!(result.contains("300: line 300"));
+        assert!(!result.contains("301: line 301"));
+        assert!(
+            result.ends_with("... 150 more lines. Use view_range [301, 450] to see the next page.")
+        );
+
+        let small = container
+            .format_file_content(&numbered_content(3), "small.txt", None, 300, "File")
+            .expect("small view should format");
+        assert_eq!(
+            small,
+            "File: small.txt (3 lines)\n  1: line 1\n  2: line 2\n  3: line 3"
+        );
+    }
+
+    #[test]
+    fn view_truncates_very_long_lines() {
+        let container = local_container_with_profile(None);
+        let long_line = "x".repeat(5000);
+        let result = container
+            .format_file_content(&long_line, "min.js", None, 300, "File")
+            .expect("long line view should format");
+
+        assert!(result.contains("... [line truncated, 5000 chars]"));
+        assert!(result.len() < 2200);
+    }
+
+    fn search_tree() -> tempfile::TempDir {
+        let dir = tempfile::TempDir::new().expect("temp dir should be created");
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).expect("src dir");
+        std::fs::create_dir_all(root.join("target")).expect("target dir");
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "fn parse_config() {}\nfn main() { parse_config(); }\nlet x = a.b(c);\n",
+        )
+        .expect("lib.rs");
+        std::fs::write(
+            root.join("src/lib_test.rs"),
+            "#[test]\nfn parse_config_works() { parse_config(); }\n",
+        )
+        .expect("lib_test.rs");
+        std::fs::write(root.join("README.md"), "Call parse_config() first.\n").expect("readme");
+        std::fs::write(root.join("target/out.rs"), "fn parse_config() {}\n").expect("out.rs");
+        std::fs::write(root.join(".gitignore"), "target/\n").expect("gitignore");
+        dir
+    }
+
+    fn search_text(path: &std::path::Path, pattern: &str, opts: &SearchFilesOptions) -> String {
+        let result = search_files_in_path(&path.to_string_lossy(), pattern, opts);
+        assert_ne!(result.is_error, Some(true), "search should succeed");
+        result
+            .content
+            .iter()
+            .filter_map(|c| c.as_text().map(|t| t.text.clone()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn search_files_literal_match_respects_gitignore_and_excludes() {
+        let dir = search_tree();
+        let opts = SearchFilesOptions {
+            literal: true,
+            exclude: vec!["*_test.rs".to_string()],
+            max_results: 100,
+            ..Default::default()
+        };
+
+        let output = search_text(dir.path(), "parse_config()", &opts);
+
+        assert!(output.contains("src/lib.rs:1:fn parse_config() {}"));
+        assert!(output.contains("src/lib.rs:2:fn main() { parse_config(); }"));
+        assert!(output.contains("README.md:1:Call parse_config() first."));
+        assert!(!output.contains("lib_test.rs"), "excluded file: {output}");
+        assert!(!output.contains("target/"), "gitignored file: {output}");
+
+        // Regex metacharacters are matched literally
+        let dots = search_text(dir.path(), "a.b(c)", &opts);
+        assert!(dots.contains("src/lib.rs:3:let x = a.b(c);"));
+    }
+
+    #[test]
+    fn search_files_regex_match_with_include_and_cap() {
+        let dir = search_tree();
+        let opts = SearchFilesOptions {
+            include: vec!["*.rs".to_string()],
+            max_results: 100,
+            ..Default::default()
+        };
+
+        let output = search_text(dir.path(), r"fn \w+_works\(", &opts);
+        assert!(output.contains("src/lib_test.rs:2:fn parse_config_works()"));
+        assert!(output.contains("(1 matches in 1 files)"));
+
+        let capped = search_text(
+            dir.path(),
+            "parse_config",
+            &SearchFilesOptions {
+                max_results: 2,
+                ..opts
+            },
+        );
+        assert!(capped.contains("(2 matches in"));
+        assert!(capped.contains("stopped after 2 matches"));
+        assert!(!capped.contains("README.md"), "include filter: {capped}");
+    }
+
+    #[test]
+    fn search_files_reports_invalid_regex() {
+        let dir = search_tree();
+        let result = search_files_in_path(
+            &dir.path().to_string_lossy(),
+            "fn (",
+            &SearchFilesOptions {
+                max_results: 10,
+                ..Default::default()
+            },
+        );
+        assert_eq!(result.is_error, Some(true));
+    }
+
+    #[test]
+    fn create_writes_new_file() {
+        let container = local_container_with_profile(None);
+        let dir = tempfile::TempDir::new().expect("temp dir should be created");
+        let path = dir.path().join("nested/new.txt");
+
+        let result = container
+            .create_local(&path.to_string_lossy(), "hello\n", false)
+            .expect("create should not fail");
+
+        assert_ne!(result.is_error, Some(true));
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("file should exist"),
+            "hello\n"
+        );
+    }
+
+    #[test]
+    fn create_over_existing_file_reports_hash_and_keeps_content() {
+        let container = local_container_with_profile(None);
+        let dir = tempfile::TempDir::new().expect("temp dir should be created");
+        let path = dir.path().join("existing.txt");
+        std::fs::write(&path, "original\n").expect("existing file");
+
+        let result = container
+            .create_local(&path.to_string_lossy(), "replacement\n", false)
+            .expect("create should not fail");
+
+        assert_eq!(result.is_error, Some(true));
+        let text = result_text(&result);
+        assert!(text.starts_with("FILE_EXISTS"), "{text}");
+        assert!(
+            text.contains(&format!("sha256: {}", content_sha256(b"original\n"))),
+            "{text}"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("file should exist"),
+            "original\n"
+        );
+    }
+
+    #[test]
+    fn create_with_overwrite_replaces_existing_file() {
+        let container = local_container_with_profile(None);
+        let dir = tempfile::TempDir::new().expect("temp dir should be created");
+        let path = dir.path().join("existing.txt");
+        std::fs::write(&path, "original\n").expect("existing file");
+
+        let result = container
+            .create_local(&path.to_string_lossy(), "replacement\n", true)
+            .expect("create should not fail");
+
+        assert_ne!(result.is_error, Some(true));
+        assert!(result_text(&result).contains("Successfully overwrote file"));
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("file should exist"),
+            "replacement\n"
+        );
+    }
+
+    fn oversized_file(dir: &tempfile::TempDir) -> String {
+        let path = dir.path().join("big.log");
+        let content: String = (1..=100).map(|i| format!("line {i}\n")).collect();
+        std::fs::write(&path, content).expect("big file");
+        path.to_string_lossy().into_owned()
+    }
+
+    fn assert_file_too_large(result: &CallToolResult) {
+        assert_eq!(result.is_error, Some(true));
+        let text = result_text(result);
+        assert!(text.starts_with("FILE_TOO_LARGE"), "{text}");
+        assert!(text.contains("over the 64 byte limit"), "{text}");
+    }
+
+    #[tokio::test]
+    async fn view_rejects_oversized_file_without_range() {
+        let container = local_container_with_profile(None).with_max_file_size(64);
+        let dir = tempfile::TempDir::new().expect("temp dir should be created");
+        let path = oversized_file(&dir);
+        let opts = ViewOptions {
+            view_range: None,
+            max_lines: 300,
+            tree: None,
+            grep: None,
+            glob: None,
+        };
+
+        let result = container
+            .view_local_path(&path, &opts)
+            .await
+            .expect("view should not fail");
+
+        assert_file_too_large(&result);
+        assert!(result_text(&result).contains("view_range"));
+    }
+
+    #[tokio::test]
+    async fn view_pages_oversized_file_with_range() {
+        let container = local_container_with_profile(None).with_max_file_size(64);
+        let dir = tempfile::TempDir::new().expect("temp dir should be created");
+        let path = oversized_file(&dir);
+        let opts = ViewOptions {
+            view_range: Some([50, 52]),
+            max_lines: 300,
+            tree: None,
+            grep: None,
+            glob: None,
+        };
+
+        let result = container
+            .view_local_path(&path, &opts)
+            .await
+            .expect("view should not fail");
+
+        assert_ne!(result.is_error, Some(true));
+        let text = result_text(&result);
+        assert!(
+            text.contains(" 50: line 50\n 51: line 51\n 52: line 52"),
+            "{text}"
+        );
+        assert!(!text.contains("line 53"), "{text}");
+        assert!(text.contains("view_range [53, 352]"), "{text}");
+    }
+
+    #[tokio::test]
+    async fn str_replace_rejects_oversized_file() {
+        let container = local_container_with_profile(None).with_max_file_size(64);
+        let dir = tempfile::TempDir::new().expect("temp dir should be created");
+        let path = oversized_file(&dir);
+
+        let result = container
+            .str_replace_local(&path, "line 1\n", "first\n", None)
+            .await
+            .expect("str_replace should not fail");
+
+        assert_file_too_large(&result);
+        assert!(
+            std::fs::read_to_string(&path)
+                .expect("file should exist")
+                .starts_with("line 1\n")
+        );
+    }
+
+    #[test]
+    fn create_rejects_oversized_content() {
+        let container = local_container_with_profile(None).with_max_file_size(64);
+        let dir = tempfile::TempDir::new().expect("temp dir should be created");
+        let path = dir.path().join("big.txt");
+
+        let result = container
+            .create_local(&path.to_string_lossy(), &"x".repeat(65), false)
+            .expect("create should not fail");
+
+        assert_file_too_large(&result);
+        assert!(!path.exists());
+    }
+
+    fn git(dir: &std::path::Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .status()
+            .expect("git should run");
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// A repo with one staged edit, one unstaged edit, a staged rename and an
+    /// untracked file.
+    fn git_tree() -> tempfile::TempDir {
+        let dir = tempfile::TempDir::new().expect("temp dir should be created");
+        let root = dir.path();
+        git(root, &["init", "-q", "-b", "main"]);
+        std::fs::create_dir_all(root.join("src")).expect("src dir");
+        std::fs::write(root.join("src/lib.rs"), "fn one() {}\n").expect("lib.rs");
+        std::fs::write(root.join("README.md"), "# Readme\n").expect("readme");
+        std::fs::write(root.join("old.txt"), "rename me\n").expect("old.txt");
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "initial"]);
+
+        std::fs::write(root.join("src/lib.rs"), "fn one() {}\nfn staged() {}\n").expect("lib.rs");
+        git(root, &["add", "src/lib.rs"]);
+        git(root, &["mv", "old.txt", "new.txt"]);
+        std::fs::write(root.join("README.md"), "# Readme\n\nUnstaged line\n").expect("readme");
+        std::fs::write(root.join("notes.txt"), "scratch\n").expect("notes");
+        dir
+    }
+
+    fn result_text(result: &CallToolResult) -> String {
+        result
+            .content
+            .iter()
+            .filter_map(|c| c.as_text().map(|t| t.text.clone()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[tokio::test]
+    async fn git_status_groups_staged_unstaged_and_untracked_files() {
+        let dir = git_tree();
+
+        let result = git_status_in_dir(&dir.path().join("src")).await;
+        assert_ne!(result.is_error, Some(true), "{}", result_text(&result));
+        let output = result_text(&result);
+
+        assert!(output.starts_with("On branch main"), "{output}");
+        assert!(
+            output.contains("Staged (2):\n  R old.txt -> new.txt\n  M src/lib.rs"),
+            "{output}"
+        );
+        assert!(output.contains("Unstaged (1):\n  M README.md"), "{output}");
+        assert!(output.contains("Untracked (1):\n  notes.txt"), "{output}");
+    }
+
+    #[tokio::test]
+    async fn git_diff_separates_staged_and_unstaged_changes() {
+        let dir = git_tree();
+
+        let unstaged = result_text(&git_diff_in_dir(dir.path(), false, &[], 50_000).await);
+        assert!(unstaged.contains("+Unstaged line"), "{unstaged}");
+        assert!(!unstaged.contains("fn staged()"), "{unstaged}");
+
+        let staged = result_text(&git_diff_in_dir(dir.path(), true, &[], 50_000).await);
+        assert!(staged.contains("+fn staged() {}"), "{staged}");
+        assert!(!staged.contains("Unstaged line"), "{staged}");
+
+        let filtered =
+            result_text(&git_diff_in_dir(dir.path(), true, &["new.txt".to_string()], 50_000).await);
+        assert!(!filtered.contains("src/lib.rs"), "{filtered}");
+    }
+
+    #[tokio::test]
+    async fn git_diff_truncates_large_diffs() {
+        let dir = git_tree();
+
+        let output = result_text(&git_diff_in_dir(dir.path(), true, &[], 40).await);
+
+        assert!(
+            output.contains("staged diff truncated at 40 of"),
+            "{output}"
+        );
+    }
+
+    #[tokio::test]
+    async fn git_status_reports_non_repositories() {
+        let dir = tempfile::TempDir::new().expect("temp dir should be created");
+
+        let result = git_status_in_dir(dir.path()).await;
+
+        assert_eq!(result.is_error, Some(true));
+        assert!(result_text(&result).starts_with("NOT_A_GIT_REPOSITORY"));
+    }
+
     #[test]
     fn remote_command_task_gets_no_local_profile_child_env_defaults() {
         let container = local_container_with_profile(Some("ops"));
@@ -3458,6 +4879,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn local_command_task_env_is_filtered_by_policy() {
+        let container = local_container_with_profile(None)
+            .with_run_command_env(crate::RunCommandEnvConfig {
+                allow: vec!["APP_*".to_string()],
+                deny: Vec::new(),
+            })
+            .with_parent_env(parent_env(&[
+                ("APP_DEPLOY_TOKEN", "tok_live_7d1e4b"),
+                ("APP_REGION", "eu-west-1"),
+            ]));
+
+        let options = container.task_env_options(None);
+
+        assert!(options.clear_env);
+        assert_eq!(
+            options.child_env.get("APP_REGION").map(String::as_str),
+            Some("eu-west-1")
+        );
+        assert!(!options.child_env.contains_key("APP_DEPLOY_TOKEN"));
+        assert!(options.redactions.contains(&(
+            "APP_DEPLOY_TOKEN".to_string(),
+            "tok_live_7d1e4b".to_string()
+        )));
+    }
+
     // ---------------------------------------------------------------
     // validate_remote_connection
     // ---------------------------------------------------------------
@@ -0,0 +1,241 @@
+//! Prometheus metrics for tool calls, rendered in the text exposition format.
+//!
+//! Every tool the server exposes is listed from startup with zero counts, so a
+//! dashboard can tell "never called" apart from "not scraped". Calls to names
+//! the router does not know are counted under the `unknown` tool to keep the
+//! label set bounded.
+
+use rmcp::{ErrorData as McpError, model::CallToolResult};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Upper bounds, in seconds, of the tool call duration histogram buckets.
+const DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0,
+];
+
+const UNKNOWN_TOOL: &str = "unknown";
+
+#[derive(Debug, Default)]
+struct ToolStats {
+    calls: u64,
+    errors: u64,
+    /// Non-cumulative count per bucket; the last entry is `+Inf`.
+    buckets: Vec<u64>,
+    duration_sum: f64,
+}
+
+impl ToolStats {
+    fn new() -> Self {
+        Self {
+            buckets: vec![0; DURATION_BUCKETS.len() + 1],
+            ..Self::default()
+        }
+    }
+
+    fn record(&mut self, duration: Duration, failed: bool) {
+        let seconds = duration.as_secs_f64();
+        let bucket = DURATION_BUCKETS
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(DURATION_BUCKETS.len());
+
+        self.calls += 1;
+        if failed {
+            self.errors += 1;
+        }
+        self.buckets[bucket] += 1;
+        self.duration_sum += seconds;
+    }
+}
+
+/// Call counts, errors and durations per tool.
+#[derive(Debug)]
+pub struct ToolMetrics {
+    tools: Mutex<BTreeMap<String, ToolStats>>,
+}
+
+impl ToolMetrics {
+    pub fn new<I, S>(tool_names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut tools: BTreeMap<String, ToolStats> = tool_names
+            .into_iter()
+            .map(|name| (name.into(), ToolStats::new()))
+            .collect();
+        tools.insert(UNKNOWN_TOOL.to_string(), ToolStats::new());
+
+        Self {
+            tools: Mutex::new(tools),
+        }
+    }
+
+    /// Start timing a call to `tool`; pass its result to
+    /// [`ToolCallTimer::finish`] once the call completes.
+    pub fn start_call<'a>(&'a self, tool: &str) -> ToolCallTimer<'a> {
+        ToolCallTimer {
+            metrics: self,
+            tool: tool.to_string(),
+            started_at: Instant::now(),
+        }
+    }
+
+    fn record(&self, tool: &str, duration: Duration, failed: bool) {
+        let mut tools = self.lock();
+        let key = if tools.contains_key(tool) {
+            tool
+        } else {
+            UNKNOWN_TOOL
+        };
+        if let Some(stats) = tools.get_mut(key) {
+            stats.record(duration, failed);
+        }
+    }
+
+    /// Render all metrics in the Prometheus text exposition format.
+    pub fn render(&self, active_sessions: usize) -> String {
+        let tools = self.lock();
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            "# HELP stakpak_mcp_tool_calls_total Tool calls handled by the MCP server."
+        );
+        let _ = writeln!(out, "# TYPE stakpak_mcp_tool_calls_total counter");
+        for (tool, stats) in tools.iter() {
+            let _ = writeln!(
+                out,
+                "stakpak_mcp_tool_calls_total{{tool=\"{}\"}} {}",
+                escape_label(tool),
+                stats.calls
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP stakpak_mcp_tool_errors_total Tool calls that failed or returned an error result."
+        );
+        let _ = writeln!(out, "# TYPE stakpak_mcp_tool_errors_total counter");
+        for (tool, stats) in tools.iter() {
+            let _ = writeln!(
+                out,
+                "stakpak_mcp_tool_errors_total{{tool=\"{}\"}} {}",
+                escape_label(tool),
+                stats.errors
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP stakpak_mcp_tool_call_duration_seconds Time spent handling tool calls."
+        );
+        let _ = writeln!(
+            out,
+            "# TYPE stakpak_mcp_tool_call_duration_seconds histogram"
+        );
+        for (tool, stats) in tools.iter() {
+            let tool = escape_label(tool);
+            let mut cumulative = 0;
+            for (index, count) in stats.buckets.iter().enumerate() {
+                cumulative += count;
+                let le = DURATION_BUCKETS
+                    .get(index)
+                    .map_or_else(|| "+Inf".to_string(), |bound| bound.to_string());
+                let _ = writeln!(
+                    out,
+                    "stakpak_mcp_tool_call_duration_seconds_bucket{{tool=\"{tool}\",le=\"{le}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "stakpak_mcp_tool_call_duration_seconds_sum{{tool=\"{tool}\"}} {}",
+                stats.duration_sum
+            );
+            let _ = writeln!(
+                out,
+                "stakpak_mcp_tool_call_duration_seconds_count{{tool=\"{tool}\"}} {}",
+                stats.calls
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP stakpak_mcp_active_sessions MCP sessions currently open."
+        );
+        let _ = writeln!(out, "# TYPE stakpak_mcp_active_sessions gauge");
+        let _ = writeln!(out, "stakpak_mcp_active_sessions {active_sessions}");
+
+        out
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, ToolStats>> {
+        self.tools
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// An in-flight tool call started with [`ToolMetrics::start_call`].
+pub struct ToolCallTimer<'a> {
+    metrics: &'a ToolMetrics,
+    tool: String,
+    started_at: Instant,
+}
+
+impl ToolCallTimer<'_> {
+    /// Record the call, counting protocol errors and error results as failures.
+    pub fn finish(self, result: &Result<CallToolResult, McpError>) {
+        let failed = match result {
+            Ok(result) => result.is_error.unwrap_or(false),
+            Err(_) => true,
+        };
+        self.metrics
+            .record(&self.tool, self.started_at.elapsed(), failed);
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_counters_and_cumulative_buckets() {
+        let metrics = ToolMetrics::new(["view", "run_command"]);
+        metrics.record("view", Duration::from_millis(3), false);
+        metrics.record("view", Duration::from_millis(200), true);
+        metrics.record("no_such_tool", Duration::from_millis(1), true);
+
+        let text = metrics.render(2);
+        assert!(text.contains("stakpak_mcp_tool_calls_total{tool=\"view\"} 2"));
+        assert!(text.contains("stakpak_mcp_tool_calls_total{tool=\"run_command\"} 0"));
+        assert!(text.contains("stakpak_mcp_tool_errors_total{tool=\"view\"} 1"));
+        assert!(text.contains("stakpak_mcp_tool_calls_total{tool=\"unknown\"} 1"));
+        assert!(text.contains(
+            "stakpak_mcp_tool_call_duration_seconds_bucket{tool=\"view\",le=\"0.005\"} 1"
+        ));
+        assert!(text.contains(
+            "stakpak_mcp_tool_call_duration_seconds_bucket{tool=\"view\",le=\"0.25\"} 2"
+        ));
+        assert!(text.contains(
+            "stakpak_mcp_tool_call_duration_seconds_bucket{tool=\"view\",le=\"+Inf\"} 2"
+        ));
+        assert!(text.contains("stakpak_mcp_tool_call_duration_seconds_count{tool=\"view\"} 2"));
+        assert!(text.contains("stakpak_mcp_active_sessions 2"));
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
@@ -0,0 +1,950 @@
+//! Unified diff parsing and application for the `apply_patch` tool.
+//!
+//! A patch is applied all-or-nothing: every hunk of every file is matched
+//! against the current contents in memory first, and files are only written
+//! once the whole patch applies cleanly.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// How far (in lines) a hunk may drift from its header position and still apply.
+const MAX_HUNK_OFFSET: usize = 200;
+
+const DEV_NULL: &str = "/dev/null";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hunk {
+    header: String,
+    old_start: usize,
+    lines: Vec<HunkLine>,
+    /// `\ No newline at end of file` follows the last old-side line.
+    old_missing_newline: bool,
+    /// `\ No newline at end of file` follows the last new-side line.
+    new_missing_newline: bool,
+}
+
+impl Hunk {
+    fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Remove(text) => Some(text.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect()
+    }
+
+    fn new_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Add(text) => Some(text.as_str()),
+                HunkLine::Remove(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// The hunks for a single file in a unified diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePatch {
+    /// Path on the `---` side, `None` when the file is being created.
+    pub old_path: Option<String>,
+    /// Path on the `+++` side, `None` when the file is being deleted.
+    pub new_path: Option<String>,
+    hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    /// The `---` and `+++` paths that are set.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.old_path
+            .iter()
+            .chain(self.new_path.iter())
+            .map(String::as_str)
+    }
+
+    /// The path this patch applies to, preferring the new side.
+    pub fn path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchError {
+    /// The diff text could not be parsed.
+    Parse {
+        line: usize,
+        message: String,
+    },
+    /// A hunk's context or removed lines do not match the file.
+    HunkMismatch {
+        path: String,
+        hunk: usize,
+        header: String,
+        message: String,
+    },
+    /// A file path is absolute or climbs out of the base directory.
+    InvalidPath {
+        path: String,
+    },
+    Io {
+        path: String,
+        message: String,
+    },
+}
+
+impl PatchError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            PatchError::Parse { .. } => "INVALID_PATCH",
+            PatchError::HunkMismatch { .. } => "PATCH_CONFLICT",
+            PatchError::InvalidPath { .. } => "INVALID_PATH",
+            PatchError::Io { .. } => "FILE_ERROR",
+        }
+    }
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::Parse { line, message } => {
+                write!(f, "Invalid patch at line {}: {}", line, message)
+            }
+            PatchError::HunkMismatch {
+                path,
+                hunk,
+                header,
+                message,
+            } => write!(f, "Hunk #{} ({}) in {}: {}", hunk, header, path, message),
+            PatchError::InvalidPath { path } => write!(
+                f,
+                "{}: patch paths must be relative and may not contain '..'",
+                path
+            ),
+            PatchError::Io { path, message } => write!(f, "{}: {}", path, message),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// What happened to a file when a patch was applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChangeKind {
+    Created,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+impl fmt::Display for FileChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FileChangeKind::Created => "created",
+            FileChangeKind::Modified => "modified",
+            FileChangeKind::Deleted => "deleted",
+            FileChangeKind::Renamed => "renamed",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub kind: FileChangeKind,
+    pub added: usize,
+    pub removed: usize,
+    /// Previous path, for renamed files.
+    pub renamed_from: Option<PathBuf>,
+}
+
+/// Parse a unified diff (as produced by `git diff` or `diff -u`) into per-file patches.
+pub fn parse_unified_diff(diff: &str) -> Result<Vec<FilePatch>, PatchError> {
+    let lines: Vec<&str> = diff.lines().collect();
+    let mut patches = Vec::new();
+    let mut rename = (None, None);
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        if line.starts_with("diff --git ") {
+            patches.extend(take_pure_rename(&mut rename));
+        } else if let Some(from) = line.strip_prefix("rename from ") {
+            rename.0 = Some(from.trim().to_string());
+        } else if let Some(to) = line.strip_prefix("rename to ") {
+            rename.1 = Some(to.trim().to_string());
+        }
+
+        let Some(old_header) = line.strip_prefix("--- ") else {
+            // Skip `diff --git`, `index`, mode lines and any prose around the diff
+            i += 1;
+            continue;
+        };
+        // The file headers name both sides of a rename with content changes
+        rename = (None, None);
+        let Some(new_header) = lines.get(i + 1).and_then(|l| l.strip_prefix("+++ ")) else {
+            return Err(PatchError::Parse {
+                line: i + 2,
+                message: "expected '+++' header after '---' header".to_string(),
+            });
+        };
+
+        let old_path = parse_header_path(old_header, "a/");
+        let new_path = parse_header_path(new_header, "b/");
+        if old_path.is_none() && new_path.is_none() {
+            return Err(PatchError::Parse {
+                line: i + 1,
+                message: "both sides of the file header are /dev/null".to_string(),
+            });
+        }
+        i += 2;
+
+        let mut hunks = Vec::new();
+        while i < lines.len() && lines[i].starts_with("@@") {
+            let (hunk, next) = parse_hunk(&lines, i)?;
+            hunks.push(hunk);
+            i = next;
+        }
+
+        if hunks.is_empty() {
+            return Err(PatchError::Parse {
+                line: i + 1,
+                message: format!(
+                    "no hunks for {}",
+                    new_path
+                        .as_deref()
+                        .or(old_path.as_deref())
+                        .unwrap_or("file")
+                ),
+            });
+        }
+
+        patches.push(FilePatch {
+            old_path,
+            new_path,
+            hunks,
+        });
+    }
+    patches.extend(take_pure_rename(&mut rename));
+
+    if patches.is_empty() {
+        return Err(PatchError::Parse {
+            line: 1,
+            message: "no file headers ('--- a/path' / '+++ b/path') found".to_string(),
+        });
+    }
+
+    Ok(patches)
+}
+
+/// A git rename with no content changes has `rename from`/`rename to` lines but no hunks.
+fn take_pure_rename(rename: &mut (Option<String>, Option<String>)) -> Option<FilePatch> {
+    match std::mem::take(rename) {
+        (Some(from), Some(to)) => Some(FilePatch {
+            old_path: Some(from),
+            new_path: Some(to),
+            hunks: Vec::new(),
+        }),
+        _ => None,
+    }
+}
+
+fn parse_header_path(header: &str, git_prefix: &str) -> Option<String> {
+    // Drop the optional timestamp that `diff -u` appends after a tab
+    let path = header.split('\t').next().unwrap_or_default().trim();
+    if path == DEV_NULL {
+        return None;
+    }
+    Some(path.strip_prefix(git_prefix).unwrap_or(path).to_string())
+}
+
+fn parse_hunk(lines: &[&str], start: usize) -> Result<(Hunk, usize), PatchError> {
+    let header = lines[start];
+    let parse_error = |message: String| PatchError::Parse {
+        line: start + 1,
+        message,
+    };
+
+    let ranges = header
+        .strip_prefix("@@ ")
+        .and_then(|rest| rest.split(" @@").next())
+        .ok_or_else(|| parse_error(format!("malformed hunk header '{}'", header)))?;
+    let mut parts = ranges.split_whitespace();
+    let (old_start, old_count) = parts
+        .next()
+        .and_then(|r| r.strip_prefix('-'))
+        .and_then(parse_range)
+        .ok_or_else(|| parse_error(format!("malformed old range in '{}'", header)))?;
+    let (_, new_count) = parts
+        .next()
+        .and_then(|r| r.strip_prefix('+'))
+        .and_then(parse_range)
+        .ok_or_else(|| parse_error(format!("malformed new range in '{}'", header)))?;
+
+    let mut hunk = Hunk {
+        header: format!("@@ {} @@", ranges),
+        old_start,
+        lines: Vec::new(),
+        old_missing_newline: false,
+        new_missing_newline: false,
+    };
+    let (mut old_seen, mut new_seen) = (0, 0);
+    let mut i = start + 1;
+
+    while i < lines.len() && (old_seen < old_count || new_seen < new_count) {
+        let line = lines[i];
+        if let Some(text) = line.strip_prefix('+') {
+            hunk.lines.push(HunkLine::Add(text.to_string()));
+            new_seen += 1;
+        } else if let Some(text) = line.strip_prefix('-') {
+            hunk.lines.push(HunkLine::Remove(text.to_string()));
+            old_seen += 1;
+        } else if let Some(text) = line.strip_prefix(' ') {
+            hunk.lines.push(HunkLine::Context(text.to_string()));
+            old_seen += 1;
+            new_seen += 1;
+        } else if line.is_empty() {
+            // Some editors strip the single space from blank context lines
+            hunk.lines.push(HunkLine::Context(String::new()));
+            old_seen += 1;
+            new_seen += 1;
+        } else if line.starts_with('\\') {
+            mark_missing_newline(&mut hunk);
+        } else {
+            return Err(PatchError::Parse {
+                line: i + 1,
+                message: format!("unexpected line in hunk '{}'", line),
+            });
+        }
+        i += 1;
+    }
+
+    if old_seen != old_count || new_seen != new_count {
+        return Err(parse_error(format!(
+            "hunk '{}' expects {} old and {} new lines but has {} and {}",
+            hunk.header, old_count, new_count, old_seen, new_seen
+        )));
+    }
+
+    // A trailing `\ No newline at end of file` belongs to the last hunk line
+    if lines.get(i).is_some_and(|l| l.starts_with('\\')) {
+        mark_missing_newline(&mut hunk);
+        i += 1;
+    }
+
+    Ok((hunk, i))
+}
+
+fn mark_missing_newline(hunk: &mut Hunk) {
+    match hunk.lines.last() {
+        Some(HunkLine::Add(_)) => hunk.new_missing_newline = true,
+        Some(HunkLine::Remove(_)) => hunk.old_missing_newline = true,
+        Some(HunkLine::Context(_)) => {
+            hunk.old_missing_newline = true;
+            hunk.new_missing_newline = true;
+        }
+        None => {}
+    }
+}
+
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// Apply a file's hunks to `original`, returning the patched contents.
+fn apply_file_patch(original: &str, patch: &FilePatch) -> Result<String, PatchError> {
+    if patch.hunks.is_empty() {
+        return Ok(original.to_string());
+    }
+    let line_ending = if original.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut trailing_newline = original.is_empty() || original.ends_with('\n');
+    let source: Vec<&str> = original.lines().collect();
+    let mut result: Vec<&str> = Vec::with_capacity(source.len());
+    let mut cursor = 0;
+
+    for (index, hunk) in patch.hunks.iter().enumerate() {
+        let mismatch = |message: String| PatchError::HunkMismatch {
+            path: patch.path().to_string(),
+            hunk: index + 1,
+            header: hunk.header.clone(),
+            message,
+        };
+
+        let old_lines = hunk.old_lines();
+        let position = locate_hunk(&source, &old_lines, hunk.old_start, cursor)
+            .ok_or_else(|| mismatch(describe_mismatch(&source, &old_lines, hunk.old_start)))?;
+
+        result.extend_from_slice(&source[cursor..position]);
+        result.extend(hunk.new_lines());
+        cursor = position + old_lines.len();
+
+        if cursor == source.len() {
+            if hunk.new_missing_newline {
+                trailing_newline = false;
+            } else if hunk.old_missing_newline {
+                trailing_newline = true;
+            }
+        }
+    }
+    result.extend_from_slice(&source[cursor..]);
+
+    let mut patched = result.join(line_ending);
+    if trailing_newline && !result.is_empty() {
+        patched.push_str(line_ending);
+    }
+    Ok(patched)
+}
+
+/// Find where `old_lines` occur in `source`, nearest the header position first.
+fn locate_hunk(
+    source: &[&str],
+    old_lines: &[&str],
+    old_start: usize,
+    min_position: usize,
+) -> Option<usize> {
+    // An empty old side (`-N,0`) means "insert after line N"
+    if old_lines.is_empty() {
+        return Some(old_start.max(min_position).min(source.len()));
+    }
+
+    let expected = old_start.saturating_sub(1).max(min_position);
+
+    let matches_at = |position: usize| {
+        position >= min_position
+            && position + old_lines.len() <= source.len()
+            && source[position..position + old_lines.len()]
+                .iter()
+                .zip(old_lines)
+                .all(|(actual, wanted)| actual.trim_end_matches('\r') == *wanted)
+    };
+
+    (0..=MAX_HUNK_OFFSET).find_map(|offset| {
+        if matches_at(expected + offset) {
+            Some(expected + offset)
+        } else if offset > 0 && expected >= offset && matches_at(expected - offset) {
+            Some(expected - offset)
+        } else {
+            None
+        }
+    })
+}
+
+/// Explain the first line that differs at the hunk's header position.
+fn describe_mismatch(source: &[&str], old_lines: &[&str], old_start: usize) -> String {
+    let start = old_start.saturating_sub(1);
+    for (offset, wanted) in old_lines.iter().enumerate() {
+        let line_number = start + offset + 1;
+        match source.get(start + offset) {
+            Some(actual) if actual.trim_end_matches('\r') == *wanted => continue,
+            Some(actual) => {
+                return format!(
+                    "context mismatch at line {}: expected {:?}, found {:?}",
+                    line_number,
+                    wanted,
+                    actual.trim_end_matches('\r')
+                );
+            }
+            None => {
+                return format!(
+                    "expected {:?} at line {}, but the file has only {} lines",
+                    wanted,
+                    line_number,
+                    source.len()
+                );
+            }
+        }
+    }
+    "context lines were not found in the expected order".to_string()
+}
+
+/// Contents of one file before and after the patch.
+struct PlannedFile {
+    path: PathBuf,
+    /// `None` when the file did not exist.
+    original: Option<String>,
+    /// `None` when the file is removed.
+    content: Option<String>,
+}
+
+/// Every file a patch touches, in the order they were first touched.
+#[derive(Default)]
+struct PlannedFiles {
+    files: Vec<PlannedFile>,
+    index: HashMap<PathBuf, usize>,
+}
+
+impl PlannedFiles {
+    /// The planned state of `path`, read from disk the first time it is touched.
+    fn entry(&mut self, path: &Path) -> std::io::Result<&mut PlannedFile> {
+        if let Some(&index) = self.index.get(path) {
+            return Ok(&mut self.files[index]);
+        }
+
+        let original = if path.exists() {
+            Some(fs::read_to_string(path)?)
+        } else {
+            None
+        };
+        let index = self.files.len();
+        self.index.insert(path.to_path_buf(), index);
+        self.files.push(PlannedFile {
+            path: path.to_path_buf(),
+            content: original.clone(),
+            original,
+        });
+        Ok(&mut self.files[index])
+    }
+}
+
+/// Apply `patches` to files under `base_dir`, writing nothing unless every hunk applies.
+pub fn apply_patches(
+    base_dir: &Path,
+    patches: &[FilePatch],
+) -> Result<Vec<FileChange>, PatchError> {
+    // Check every path before reading or planning anything
+    for path in patches.iter().flat_map(FilePatch::paths) {
+        let stays_inside = Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        if !stays_inside {
+            return Err(PatchError::InvalidPath {
+                path: path.to_string(),
+            });
+        }
+    }
+
+    let mut planned = PlannedFiles::default();
+    let mut changes: Vec<FileChange> = Vec::new();
+
+    for patch in patches {
+        let io_error = |message: String| PatchError::Io {
+            path: patch.path().to_string(),
+            message,
+        };
+        let read_error = |e: std::io::Error| io_error(format!("cannot read: {}", e));
+
+        let source = base_dir.join(patch.old_path.as_deref().unwrap_or(patch.path()));
+        let path = base_dir.join(patch.path());
+        let renamed_from = (patch.old_path.is_some() && patch.new_path.is_some() && source != path)
+            .then(|| source.clone());
+
+        // A file patched twice applies its second patch to the first result
+        let existing = planned.entry(&source).map_err(read_error)?.content.clone();
+        let original = match (&patch.old_path, existing) {
+            (None, Some(_)) => return Err(io_error("file already exists".to_string())),
+            (None, None) => String::new(),
+            (Some(_), Some(content)) => content,
+            (Some(_), None) => {
+                return Err(io_error("cannot read: file does not exist".to_string()));
+            }
+        };
+
+        let patched = apply_file_patch(&original, patch)?;
+        let kind = match (&patch.old_path, &patch.new_path) {
+            (None, _) => FileChangeKind::Created,
+            (_, None) => {
+                if !patched.trim().is_empty() {
+                    return Err(io_error(
+                        "deletion patch does not remove the whole file".to_string(),
+                    ));
+                }
+                FileChangeKind::Deleted
+            }
+            _ if renamed_from.is_some() => FileChangeKind::Renamed,
+            _ => FileChangeKind::Modified,
+        };
+
+        if renamed_from.is_some() {
+            let target = planned.entry(&path).map_err(read_error)?;
+            if target.content.is_some() {
+                return Err(io_error(format!(
+                    "cannot rename onto existing file {}",
+                    path.display()
+                )));
+            }
+            target.content = Some(patched);
+            planned.entry(&source).map_err(read_error)?.content = None;
+        } else {
+            planned.entry(&path).map_err(read_error)?.content =
+                (kind != FileChangeKind::Deleted).then_some(patched);
+        }
+
+        let hunk_lines = || patch.hunks.iter().flat_map(|hunk| hunk.lines.iter());
+        let added = hunk_lines()
+            .filter(|l| matches!(l, HunkLine::Add(_)))
+            .count();
+        let removed = hunk_lines()
+            .filter(|l| matches!(l, HunkLine::Remove(_)))
+            .count();
+
+        match changes.iter_mut().find(|change| change.path == path) {
+            Some(change) => {
+                change.added += added;
+                change.removed += removed;
+                change.kind = match (change.kind, kind) {
+                    (FileChangeKind::Created, FileChangeKind::Deleted) => FileChangeKind::Deleted,
+                    (FileChangeKind::Created, _) => FileChangeKind::Created,
+                    (FileChangeKind::Renamed, FileChangeKind::Modified) => FileChangeKind::Renamed,
+                    (_, kind) => kind,
+                };
+                if renamed_from.is_some() {
+                    change.renamed_from = renamed_from;
+                }
+            }
+            None => changes.push(FileChange {
+                path,
+                kind,
+                added,
+                removed,
+                renamed_from,
+            }),
+        }
+    }
+
+    write_planned(&planned.files)?;
+    Ok(changes)
+}
+
+/// Write every planned change. If one fails, restore the files already
+/// written and remove any directories created along the way.
+fn write_planned(files: &[PlannedFile]) -> Result<(), PatchError> {
+    let pending: Vec<&PlannedFile> = files
+        .iter()
+        .filter(|file| file.content != file.original)
+        .collect();
+    let mut created_dirs = Vec::new();
+
+    for (written, file) in pending.iter().enumerate() {
+        let result = match &file.content {
+            Some(content) => create_parent_dirs(&file.path, &mut created_dirs)
+                .and_then(|_| fs::write(&file.path, content)),
+            None => fs::remove_file(&file.path),
+        };
+
+        if let Err(e) = result {
+            // Include the failing file: a failed write may have truncated it
+            for file in pending.iter().take(written + 1) {
+                let _ = match &file.original {
+                    Some(original) => fs::write(&file.path, original),
+                    None => fs::remove_file(&file.path),
+                };
+            }
+            for dir in created_dirs.iter().rev() {
+                let _ = fs::remove_dir(dir);
+            }
+            return Err(PatchError::Io {
+                path: file.path.display().to_string(),
+                message: format!("failed to write, no files were changed: {}", e),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Create the missing parent directories of `path`, recording each one created.
+fn create_parent_dirs(path: &Path, created: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    let Some(parent) = path.parent() else {
+        return Ok(());
+    };
+    let missing: Vec<&Path> = parent
+        .ancestors()
+        .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+        .collect();
+    for dir in missing.into_iter().rev() {
+        fs::create_dir(dir)?;
+        created.push(dir.to_path_buf());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &TempDir, name: &str, content: &str) {
+        let path = dir.path().join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(path, content).unwrap();
+    }
+
+    fn read(dir: &TempDir, name: &str) -> String {
+        fs::read_to_string(dir.path().join(name)).unwrap()
+    }
+
+    fn apply(dir: &TempDir, diff: &str) -> Result<Vec<FileChange>, PatchError> {
+        apply_patches(dir.path(), &parse_unified_diff(diff)?)
+    }
+
+    #[test]
+    fn clean_apply_with_multiple_hunks() {
+        let dir = TempDir::new().unwrap();
+        let original: String = (1..=20).map(|i| format!("line {}\n", i)).collect();
+        write(&dir, "src/main.rs", &original);
+
+        let diff = "\
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -2,3 +2,3 @@
+ line 2
+-line 3
++line three
+ line 4
+@@ -17,3 +17,4 @@
+ line 17
+ line 18
++line 18.5
+ line 19
+";
+        let changes = apply(&dir, diff).unwrap();
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, FileChangeKind::Modified);
+        assert_eq!((changes[0].added, changes[0].removed), (2, 1));
+        let patched = read(&dir, "src/main.rs");
+        assert!(patched.contains("line 2\nline three\nline 4\n"));
+        assert!(patched.contains("line 18\nline 18.5\nline 19\nline 20\n"));
+    }
+
+    #[test]
+    fn context_mismatch_reports_failing_hunk_and_changes_nothing() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "a.txt", "alpha\nbeta\ngamma\n");
+        write(&dir, "b.txt", "one\ntwo\nthree\n");
+
+        let diff = "\
+--- a/a.txt
++++ b/a.txt
+@@ -1,2 +1,2 @@
+-alpha
++ALPHA
+ beta
+--- a/b.txt
++++ b/b.txt
+@@ -1,3 +1,3 @@
+ one
+-TWO
++2
+ three
+";
+        let err = apply(&dir, diff).unwrap_err();
+
+        assert_eq!(err.code(), "PATCH_CONFLICT");
+        assert_eq!(
+            err.to_string(),
+            "Hunk #1 (@@ -1,3 +1,3 @@) in b.txt: context mismatch at line 2: \
+             expected \"TWO\", found \"two\""
+        );
+        assert_eq!(read(&dir, "a.txt"), "alpha\nbeta\ngamma\n");
+        assert_eq!(read(&dir, "b.txt"), "one\ntwo\nthree\n");
+    }
+
+    #[test]
+    fn paths_outside_the_base_dir_are_rejected_and_nothing_is_written() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "x", "outside\n");
+        write(&dir, "base/inside.txt", "inside\n");
+        let base = dir.path().join("base");
+
+        let diff = "\
+--- a/inside.txt
++++ b/inside.txt
+@@ -1 +1 @@
+-inside
++patched
+--- a/../x
++++ b/../x
+@@ -1 +1 @@
+-outside
++escaped
+";
+        let err = apply_patches(&base, &parse_unified_diff(diff).unwrap()).unwrap_err();
+
+        assert_eq!(err.code(), "INVALID_PATH");
+        assert_eq!(read(&dir, "x"), "outside\n");
+        assert_eq!(read(&dir, "base/inside.txt"), "inside\n");
+
+        let absolute = format!(
+            "--- /dev/null\n+++ {}\n@@ -0,0 +1 @@\n+created\n",
+            dir.path().join("abs.txt").display()
+        );
+        let err = apply_patches(&base, &parse_unified_diff(&absolute).unwrap()).unwrap_err();
+        assert_eq!(err.code(), "INVALID_PATH");
+        assert!(!dir.path().join("abs.txt").exists());
+    }
+
+    #[test]
+    fn multi_file_patch_creates_modifies_and_deletes() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "keep.txt", "a\nb\n");
+        write(&dir, "old.txt", "gone\n");
+
+        let diff = "\
+diff --git a/keep.txt b/keep.txt
+index 1111111..2222222 100644
+--- a/keep.txt
++++ b/keep.txt
+@@ -1,2 +1,2 @@
+ a
+-b
++c
+diff --git a/new/file.txt b/new/file.txt
+new file mode 100644
+--- /dev/null
++++ b/new/file.txt
+@@ -0,0 +1,2 @@
++hello
++world
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+";
+        let changes = apply(&dir, diff).unwrap();
+
+        let kinds: Vec<FileChangeKind> = changes.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                FileChangeKind::Modified,
+                FileChangeKind::Created,
+                FileChangeKind::Deleted
+            ]
+        );
+        assert_eq!(read(&dir, "keep.txt"), "a\nc\n");
+        assert_eq!(read(&dir, "new/file.txt"), "hello\nworld\n");
+        assert!(!dir.path().join("old.txt").exists());
+    }
+
+    #[test]
+    fn rename_with_changes_moves_the_file() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "src/old.rs", "fn a() {}\nfn b() {}\n");
+
+        let diff = "\
+diff --git a/src/old.rs b/src/new.rs
+similarity index 80%
+rename from src/old.rs
+rename to src/new.rs
+--- a/src/old.rs
++++ b/src/new.rs
+@@ -1,2 +1,2 @@
+ fn a() {}
+-fn b() {}
++fn c() {}
+";
+        let changes = apply(&dir, diff).unwrap();
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, FileChangeKind::Renamed);
+        assert_eq!(changes[0].path, dir.path().join("src/new.rs"));
+        assert_eq!(
+            changes[0].renamed_from.as_deref(),
+            Some(dir.path().join("src/old.rs").as_path())
+        );
+        assert_eq!(read(&dir, "src/new.rs"), "fn a() {}\nfn c() {}\n");
+        assert!(!dir.path().join("src/old.rs").exists());
+    }
+
+    #[test]
+    fn pure_rename_without_hunks_moves_the_file() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "a.txt", "same\n");
+
+        let diff = "\
+diff --git a/a.txt b/docs/b.txt
+similarity index 100%
+rename from a.txt
+rename to docs/b.txt
+";
+        let changes = apply(&dir, diff).unwrap();
+
+        assert_eq!(changes[0].kind, FileChangeKind::Renamed);
+        assert_eq!(read(&dir, "docs/b.txt"), "same\n");
+        assert!(!dir.path().join("a.txt").exists());
+    }
+
+    #[test]
+    fn rename_onto_existing_file_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "a.txt", "a\n");
+        write(&dir, "b.txt", "b\n");
+
+        let diff = "\
+diff --git a/a.txt b/b.txt
+rename from a.txt
+rename to b.txt
+";
+        let err = apply(&dir, diff).unwrap_err();
+
+        assert_eq!(err.code(), "FILE_ERROR");
+        assert_eq!(read(&dir, "a.txt"), "a\n");
+        assert_eq!(read(&dir, "b.txt"), "b\n");
+    }
+
+    #[test]
+    fn failed_write_removes_created_directories() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "plain.txt", "not a directory\n");
+
+        // The second file's parent is a regular file, so writing it fails
+        let diff = "\
+--- /dev/null
++++ b/new/nested/a.txt
+@@ -0,0 +1 @@
++a
+--- /dev/null
++++ b/plain.txt/b.txt
+@@ -0,0 +1 @@
++b
+";
+        let err = apply(&dir, diff).unwrap_err();
+
+        assert_eq!(err.code(), "FILE_ERROR");
+        assert!(!dir.path().join("new").exists());
+        assert_eq!(read(&dir, "plain.txt"), "not a directory\n");
+    }
+
+    #[test]
+    fn hunk_applies_when_lines_shifted_and_preserves_missing_newline() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "f.txt", "header\nx\ny\nz");
+
+        let diff = "\
+--- a/f.txt
++++ b/f.txt
+@@ -1,3 +1,3 @@
+ x
+-y
++Y
+ z
+\\ No newline at end of file
+";
+        apply(&dir, diff).unwrap();
+        assert_eq!(read(&dir, "f.txt"), "header\nx\nY\nz");
+    }
+}
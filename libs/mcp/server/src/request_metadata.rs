@@ -0,0 +1,57 @@
+//! Caller attribution carried in a tool call's `_meta`.
+//!
+//! Clients send a free-form metadata map with every tool call. The session
+//! id, trace id and user id are picked out so tools can attribute the
+//! actions they take; all other keys stay available in
+//! [`RequestMetadata::fields`].
+
+use serde_json::{Map, Value};
+
+/// Tool call `_meta` key holding the session id.
+pub const SESSION_ID_META_KEY: &str = "session_id";
+/// Tool call `_meta` key holding the caller's trace id.
+pub const TRACE_ID_META_KEY: &str = "trace_id";
+/// Tool call `_meta` key holding the id of the user the call acts for.
+pub const USER_ID_META_KEY: &str = "user_id";
+
+/// Environment variable exposing the trace id to commands a tool runs.
+pub const TRACE_ID_ENV: &str = "STAKPAK_TRACE_ID";
+/// Environment variable exposing the user id to commands a tool runs.
+pub const USER_ID_ENV: &str = "STAKPAK_USER_ID";
+
+/// The metadata a client attached to a tool call.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestMetadata {
+    pub session_id: Option<String>,
+    pub trace_id: Option<String>,
+    pub user_id: Option<String>,
+    /// The full metadata map as sent by the client.
+    pub fields: Map<String, Value>,
+}
+
+impl RequestMetadata {
+    pub fn from_meta(meta: &Map<String, Value>) -> Self {
+        let string = |key: &str| {
+            meta.get(key)
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(ToString::to_string)
+        };
+        Self {
+            session_id: string(SESSION_ID_META_KEY),
+            trace_id: string(TRACE_ID_META_KEY),
+            user_id: string(USER_ID_META_KEY),
+            fields: meta.clone(),
+        }
+    }
+
+    /// Environment variables that pass the caller's identity on to child
+    /// processes started by a tool.
+    pub fn child_env(&self) -> Vec<(&'static str, String)> {
+        [(TRACE_ID_ENV, &self.trace_id), (USER_ID_ENV, &self.user_id)]
+            .into_iter()
+            .filter_map(|(name, value)| value.clone().map(|value| (name, value)))
+            .collect()
+    }
+}
@@ -0,0 +1,159 @@
+//! Per-session working directories.
+//!
+//! A client can pin a session to a directory by sending
+//! [`WORKING_DIRECTORY_META_KEY`] in a tool call's `_meta` together with the
+//! session id. Later calls from that session resolve relative paths against
+//! the directory and may not reach outside it. Sessions without a working
+//! directory keep using the server's current directory, unrestricted.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+
+/// Tool call `_meta` key naming the session's working directory.
+pub const WORKING_DIRECTORY_META_KEY: &str = "working_directory";
+
+/// The directory a session's tool calls run in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionRoot(PathBuf);
+
+impl SessionRoot {
+    /// Use `path`, which must be an existing directory, as a session root.
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        let root = path.as_ref().canonicalize()?;
+        if !root.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotADirectory,
+                format!("{} is not a directory", root.display()),
+            ));
+        }
+        Ok(Self(root))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Resolve a tool path against the root, rejecting paths that end up
+    /// outside it through `..`, an absolute path or a symlink.
+    pub fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        let requested = Path::new(path);
+        let joined = if requested.is_absolute() {
+            requested.to_path_buf()
+        } else {
+            self.0.join(requested)
+        };
+        let normalized = normalize(&joined);
+
+        // Follow symlinks through the part of the path that already exists;
+        // the rest is about to be created and cannot be a link yet.
+        let mut existing = normalized.as_path();
+        let mut missing = Vec::new();
+        while !existing.exists() {
+            let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+                break;
+            };
+            missing.push(name);
+            existing = parent;
+        }
+        let mut resolved = existing
+            .canonicalize()
+            .unwrap_or_else(|_| existing.to_path_buf());
+        resolved.extend(missing.into_iter().rev());
+
+        if resolved.starts_with(&self.0) {
+            Ok(resolved)
+        } else {
+            Err(format!(
+                "Path '{}' is outside the session working directory '{}'",
+                path,
+                self.0.display()
+            ))
+        }
+    }
+}
+
+/// Working directories of the sessions that set one, by session id.
+#[derive(Debug, Default)]
+pub struct SessionWorkdirs {
+    roots: RwLock<HashMap<String, SessionRoot>>,
+}
+
+impl SessionWorkdirs {
+    pub fn set(&self, session_id: &str, root: SessionRoot) {
+        self.roots
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(session_id.to_string(), root);
+    }
+
+    pub fn get(&self, session_id: &str) -> Option<SessionRoot> {
+        self.roots
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(session_id)
+            .cloned()
+    }
+}
+
+/// Remove `.` and `..` components without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_relative_paths_inside_the_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = SessionRoot::new(dir.path()).unwrap();
+
+        assert_eq!(
+            root.resolve("src/main.rs").unwrap(),
+            root.path().join("src/main.rs")
+        );
+        assert_eq!(
+            root.resolve("./a/../b.txt").unwrap(),
+            root.path().join("b.txt")
+        );
+        assert_eq!(
+            root.resolve(&root.path().join("c.txt").to_string_lossy())
+                .unwrap(),
+            root.path().join("c.txt")
+        );
+    }
+
+    #[test]
+    fn rejects_escapes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = SessionRoot::new(dir.path()).unwrap();
+
+        assert!(root.resolve("../outside.txt").is_err());
+        assert!(root.resolve("a/../../outside.txt").is_err());
+        assert!(root.resolve("/etc/passwd").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlinks_pointing_outside() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("link")).unwrap();
+        let root = SessionRoot::new(dir.path()).unwrap();
+
+        assert!(root.resolve("link/secret.txt").is_err());
+    }
+}
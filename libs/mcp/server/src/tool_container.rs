@@ -1,18 +1,37 @@
-use super::{EnabledToolsConfig, SubagentConfig};
-use rmcp::tool_handler;
+use super::{DEFAULT_MAX_FILE_SIZE, EnabledToolsConfig, RunCommandEnvConfig, SubagentConfig};
+use crate::drain::ToolCallTracker;
+#[cfg(feature = "metrics")]
+use crate::metrics::ToolMetrics;
+use crate::request_metadata::{RequestMetadata, SESSION_ID_META_KEY};
+use crate::session_workdir::{SessionRoot, SessionWorkdirs, WORKING_DIRECTORY_META_KEY};
 use rmcp::{
-    ErrorData as McpError, RoleServer, ServerHandler, handler::server::tool::ToolRouter, model::*,
-    service::RequestContext, tool_router,
+    ErrorData as McpError, RoleServer, ServerHandler,
+    handler::server::tool::{ToolCallContext, ToolRouter},
+    model::*,
+    service::RequestContext,
+    tool_router,
 };
 use stakpak_api::AgentProvider;
 use stakpak_shared::remote_connection::RemoteConnectionManager;
 use stakpak_shared::task_manager::TaskManagerHandle;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use tracing::Instrument;
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct LocalToolRuntimeDefaults {
     active_profile_name: Option<String>,
+    command_env: RunCommandEnvConfig,
+    /// Environment the policy filters; `None` reads the process environment.
+    parent_env: Option<HashMap<String, String>>,
+    max_file_size: u64,
+}
+
+impl Default for LocalToolRuntimeDefaults {
+    fn default() -> Self {
+        Self::new(None)
+    }
 }
 
 impl LocalToolRuntimeDefaults {
@@ -23,12 +42,41 @@ impl LocalToolRuntimeDefaults {
 
         Self {
             active_profile_name,
+            command_env: RunCommandEnvConfig::default(),
+            parent_env: None,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
         }
     }
 
     pub fn active_profile_name(&self) -> Option<&str> {
         self.active_profile_name.as_deref()
     }
+
+    pub fn command_env(&self) -> &RunCommandEnvConfig {
+        &self.command_env
+    }
+
+    /// Variables from the parent environment a local child may inherit.
+    pub fn child_env(&self) -> Vec<(String, String)> {
+        self.command_env.child_env(self.parent_env())
+    }
+
+    /// Denied parent variables whose values are scrubbed from command output.
+    pub fn env_redactions(&self) -> Vec<(String, String)> {
+        self.command_env.redactions(self.parent_env())
+    }
+
+    fn parent_env(&self) -> Vec<(String, String)> {
+        match &self.parent_env {
+            Some(env) => env.clone().into_iter().collect(),
+            None => std::env::vars().collect(),
+        }
+    }
+
+    /// Largest file, in bytes, the file tools load into memory.
+    pub fn max_file_size(&self) -> u64 {
+        self.max_file_size
+    }
 }
 
 #[derive(Clone)]
@@ -41,6 +89,12 @@ pub struct ToolContainer {
     pub skill_directories: Vec<PathBuf>,
     pub subagent_config: SubagentConfig,
     pub local_runtime_defaults: LocalToolRuntimeDefaults,
+    /// In-flight tool calls, shared by every session so shutdown can drain them.
+    pub tool_calls: Arc<ToolCallTracker>,
+    /// Working directories pinned by sessions, shared by every session.
+    pub session_workdirs: Arc<SessionWorkdirs>,
+    #[cfg(feature = "metrics")]
+    pub metrics: Arc<ToolMetrics>,
 }
 
 #[tool_router]
@@ -55,6 +109,13 @@ impl ToolContainer {
     ) -> Result<Self, String> {
         let local_runtime_defaults =
             LocalToolRuntimeDefaults::new(subagent_config.profile_name.clone());
+        #[cfg(feature = "metrics")]
+        let metrics = Arc::new(ToolMetrics::new(
+            tool_router
+                .list_all()
+                .into_iter()
+                .map(|tool| tool.name.to_string()),
+        ));
 
         Ok(Self {
             client,
@@ -65,9 +126,32 @@ impl ToolContainer {
             skill_directories,
             subagent_config,
             local_runtime_defaults,
+            tool_calls: Arc::new(ToolCallTracker::default()),
+            session_workdirs: Arc::new(SessionWorkdirs::default()),
+            #[cfg(feature = "metrics")]
+            metrics,
         })
     }
 
+    /// Restrict the environment `run_command` children inherit.
+    pub fn with_run_command_env(mut self, command_env: RunCommandEnvConfig) -> Self {
+        self.local_runtime_defaults.command_env = command_env;
+        self
+    }
+
+    /// Filter `env` instead of the process environment for `run_command`
+    /// children and output redaction.
+    pub fn with_parent_env(mut self, env: HashMap<String, String>) -> Self {
+        self.local_runtime_defaults.parent_env = Some(env);
+        self
+    }
+
+    /// Cap the size of files `view`, `create` and `str_replace` load into memory.
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.local_runtime_defaults.max_file_size = max_file_size;
+        self
+    }
+
     pub fn get_client(&self) -> Option<&Arc<dyn AgentProvider>> {
         self.client.as_ref()
     }
@@ -82,12 +166,69 @@ impl ToolContainer {
 
     pub fn get_session_id(&self, ctx: &RequestContext<RoleServer>) -> Option<String> {
         ctx.meta
-            .get("session_id")
+            .get(SESSION_ID_META_KEY)
             .and_then(|s| s.as_str().map(|s| s.to_string()))
     }
+
+    /// Session, trace and user ids plus any other metadata the client sent
+    /// with the tool call.
+    pub fn request_metadata(&self, ctx: &RequestContext<RoleServer>) -> RequestMetadata {
+        RequestMetadata::from_meta(&ctx.meta)
+    }
+
+    /// The working directory of the calling session, or `None` to use the
+    /// server's current directory. A `working_directory` in the call's meta
+    /// is remembered for later calls from the same session.
+    pub fn session_root(
+        &self,
+        ctx: &RequestContext<RoleServer>,
+    ) -> Result<Option<SessionRoot>, CallToolResult> {
+        let session_id = self.get_session_id(ctx);
+
+        let Some(requested) = ctx
+            .meta
+            .get(WORKING_DIRECTORY_META_KEY)
+            .and_then(|value| value.as_str())
+        else {
+            return Ok(session_id.and_then(|id| self.session_workdirs.get(&id)));
+        };
+
+        let root = SessionRoot::new(requested).map_err(|e| {
+            CallToolResult::error(vec![
+                Content::text("INVALID_WORKING_DIRECTORY"),
+                Content::text(format!(
+                    "Cannot use '{}' as the working directory: {}",
+                    requested, e
+                )),
+            ])
+        })?;
+        if let Some(session_id) = session_id {
+            self.session_workdirs.set(&session_id, root.clone());
+        }
+        Ok(Some(root))
+    }
+
+    /// Resolve a local tool path against the calling session's working
+    /// directory. Paths are returned unchanged for sessions without one.
+    pub fn resolve_local_path(
+        &self,
+        ctx: &RequestContext<RoleServer>,
+        path: &str,
+    ) -> Result<String, CallToolResult> {
+        let Some(root) = self.session_root(ctx)? else {
+            return Ok(path.to_string());
+        };
+        root.resolve(path)
+            .map(|resolved| resolved.to_string_lossy().into_owned())
+            .map_err(|e| {
+                CallToolResult::error(vec![
+                    Content::text("PATH_OUTSIDE_SESSION_ROOT"),
+                    Content::text(e),
+                ])
+            })
+    }
 }
 
-#[tool_handler]
 impl ServerHandler for ToolContainer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -105,4 +246,51 @@ impl ServerHandler for ToolContainer {
     ) -> Result<InitializeResult, McpError> {
         Ok(self.get_info())
     }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult {
+            tools: self.tool_router.list_all(),
+            next_cursor: None,
+            meta: Default::default(),
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let Some(_call) = self.tool_calls.begin() else {
+            return Err(McpError::internal_error(
+                "MCP server is shutting down; not accepting new tool calls",
+                None,
+            ));
+        };
+
+        #[cfg(feature = "metrics")]
+        let timer = self.metrics.start_call(&request.name);
+
+        let metadata = self.request_metadata(&context);
+        let span = tracing::info_span!(
+            "tool_call",
+            tool = %request.name,
+            session_id = metadata.session_id.as_deref(),
+            trace_id = metadata.trace_id.as_deref(),
+            user_id = metadata.user_id.as_deref(),
+        );
+        let result = self
+            .tool_router
+            .call(ToolCallContext::new(self, request, context))
+            .instrument(span)
+            .await;
+
+        #[cfg(feature = "metrics")]
+        timer.finish(&result);
+
+        result
+    }
 }
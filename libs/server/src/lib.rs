@@ -31,7 +31,8 @@ pub use sandbox::{
 pub use session_actor::{build_checkpoint_envelope, build_run_context, spawn_session_actor};
 pub use session_manager::SessionManager;
 pub use stakpak_agent_core::{
-    SAFE_AUTOPILOT_TOOLS, ToolApprovalAction, ToolApprovalPolicy, strip_tool_prefix,
+    ArgumentPredicate, LoopDetectionConfig, SAFE_AUTOPILOT_TOOLS, ToolApprovalAction,
+    ToolApprovalPolicy, strip_tool_prefix,
 };
 pub use state::AppState;
 pub use types::{AutoApproveOverride, RunConfig, RunOverrides, SessionHandle, SessionRuntimeState};
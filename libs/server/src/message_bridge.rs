@@ -48,6 +48,36 @@ mod tests {
         assert_eq!(back[1].text(), Some("hello".to_string()));
     }
 
+    #[test]
+    fn preserves_mixed_text_and_image_content_through_storage() {
+        let messages = vec![Message::new(
+            Role::User,
+            MessageContent::Parts(vec![
+                ContentPart::text("what is wrong with this dashboard?"),
+                ContentPart::image("data:image/png;base64,iVBORw0KGgo="),
+                ContentPart::image("https://example.com/graph.png"),
+            ]),
+        )];
+
+        let stored = serde_json::to_string(&stakai_to_chat(&messages)).unwrap();
+        let back = chat_to_stakai(serde_json::from_str(&stored).unwrap());
+
+        assert_eq!(back.len(), 1);
+        let MessageContent::Parts(parts) = &back[0].content else {
+            panic!("expected parts content, got {:?}", back[0].content);
+        };
+        assert_eq!(parts.len(), 3);
+        assert!(
+            matches!(&parts[0], ContentPart::Text { text, .. } if text == "what is wrong with this dashboard?")
+        );
+        assert!(
+            matches!(&parts[1], ContentPart::Image { url, .. } if url == "data:image/png;base64,iVBORw0KGgo=")
+        );
+        assert!(
+            matches!(&parts[2], ContentPart::Image { url, .. } if url == "https://example.com/graph.png")
+        );
+    }
+
     #[test]
     fn preserves_tool_result_tool_call_id_through_storage_adapter() {
         let messages = vec![Message::new(
@@ -954,6 +954,7 @@ fn resolve_tool_approval_override(
         AutoApproveOverride::AllowList(tools) => stakpak_agent_core::ToolApprovalPolicy::Custom {
             rules: std::collections::HashMap::new(),
             default: stakpak_agent_core::ToolApprovalAction::Ask,
+            argument_rules: std::collections::HashMap::new(),
         }
         .with_overrides(tools.iter().filter_map(|tool| {
             let normalized = stakpak_agent_core::strip_tool_prefix(tool)
@@ -1192,6 +1193,8 @@ fn event_name(event: &AgentEvent) -> &'static str {
         AgentEvent::TurnCompleted { .. } => "turn_completed",
         AgentEvent::RunCompleted { .. } => "run_completed",
         AgentEvent::RunError { .. } => "run_error",
+        AgentEvent::RunPaused { .. } => "run_paused",
+        AgentEvent::RunResumed { .. } => "run_resumed",
         AgentEvent::TextDelta { .. } => "text_delta",
         AgentEvent::ThinkingDelta { .. } => "thinking_delta",
         AgentEvent::TextComplete { .. } => "text_complete",
@@ -1202,6 +1205,7 @@ fn event_name(event: &AgentEvent) -> &'static str {
         AgentEvent::ToolExecutionCompleted { .. } => "tool_execution_completed",
         AgentEvent::ToolRejected { .. } => "tool_rejected",
         AgentEvent::RetryAttempt { .. } => "retry_attempt",
+        AgentEvent::RetryBudgetExhausted { .. } => "retry_budget_exhausted",
         AgentEvent::CompactionStarted { .. } => "compaction_started",
         AgentEvent::CompactionCompleted { .. } => "compaction_completed",
         AgentEvent::UsageReport { .. } => "usage_report",
@@ -1335,6 +1339,7 @@ mod tests {
             ToolApprovalPolicy::Custom {
                 rules: HashMap::from([("stakpak__view".to_string(), ToolApprovalAction::Approve)]),
                 default: ToolApprovalAction::Ask,
+                argument_rules: HashMap::new(),
             },
         )
         .with_checkpoint_store(Arc::new(crate::CheckpointStore::new(checkpoint_root))))
@@ -2810,6 +2815,7 @@ mod tests {
         let default = ToolApprovalPolicy::Custom {
             rules: HashMap::from([("view".to_string(), ToolApprovalAction::Approve)]),
             default: ToolApprovalAction::Ask,
+            argument_rules: HashMap::new(),
         };
 
         let resolved = resolve_tool_approval_override(
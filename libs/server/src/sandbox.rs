@@ -32,6 +32,7 @@ use stakpak_mcp_client::McpClient;
 use stakpak_mcp_proxy::client::{ClientPoolConfig, ServerConfig};
 use stakpak_mcp_proxy::server::start_proxy_server;
 use stakpak_shared::cert_utils::{CertificateChain, MtlsIdentity};
+use stakpak_shared::secret_manager::ToolRedactionPolicy;
 use std::collections::HashMap;
 use std::path::Path;
 use std::process::ExitStatus;
@@ -456,6 +457,7 @@ impl SandboxedMcpServer {
                 proxy_chain_for_server,
                 true,  // redact_secrets
                 false, // privacy_mode
+                ToolRedactionPolicy::default(),
                 Some(proxy_shutdown_rx),
             )
             .await
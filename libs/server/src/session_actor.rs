@@ -14,13 +14,15 @@ use serde_json::json;
 use stakai::{ContentPart, Message, MessageContent, Role};
 use stakpak_agent_core::{
     AgentCommand, AgentConfig, AgentEvent, AgentHook, AgentRunContext, BudgetAwareContextReducer,
-    CheckpointEnvelopeV1, CompactionConfig, PassthroughCompactionEngine, ProposedToolCall,
-    RetryConfig, ToolExecutionResult, ToolExecutor, run_agent,
+    CheckpointEnvelopeV1, CompactionConfig, DEFAULT_RUN_RETRY_BUDGET, PassthroughCompactionEngine,
+    ProposedToolCall, RetryConfig, ToolErrorKind, ToolExecutionResult, ToolExecutor, run_agent,
 };
-use stakpak_api::CreateCheckpointRequest;
+use stakpak_api::{CreateCheckpointRequest, RecordToolAuditRequest, ToolAuditStatus};
 use stakpak_mcp_client::McpClient;
-use stakpak_shared::utils::sanitize_text_output;
-use std::{path::Path, sync::Arc};
+use stakpak_shared::models::integrations::openai::{FunctionCall, ToolCall};
+use stakpak_shared::run_id::scope_run_id;
+use stakpak_shared::utils::{is_tool_denied, sanitize_text_output};
+use std::{collections::HashMap, path::Path, sync::Arc};
 use tokio::sync::{Mutex, mpsc};
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
@@ -29,7 +31,7 @@ const CHECKPOINT_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from
 pub(crate) const ACTIVE_MODEL_METADATA_KEY: &str = "active_model";
 
 pub fn build_run_context(session_id: Uuid, run_id: Uuid) -> AgentRunContext {
-    AgentRunContext { run_id, session_id }
+    AgentRunContext::new(run_id, session_id).with_retry_budget(DEFAULT_RUN_RETRY_BUDGET)
 }
 
 pub fn build_checkpoint_envelope(
@@ -55,7 +57,7 @@ pub fn spawn_session_actor(
     let handle = SessionHandle::new(command_tx, cancel.clone());
 
     let state_for_task = state.clone();
-    tokio::spawn(async move {
+    let run = async move {
         let actor_result = run_session_actor(
             state_for_task.clone(),
             session_id,
@@ -74,7 +76,8 @@ pub fn spawn_session_actor(
             .run_manager
             .mark_run_finished(session_id, run_id, finish_result)
             .await;
-    });
+    };
+    tokio::spawn(scope_run_id(run_id.to_string(), run));
 
     Ok(handle)
 }
@@ -170,6 +173,16 @@ async fn run_session_actor(
             )
         };
 
+    // denied_tools wins over the approval policy: hide the tools and refuse calls to them
+    let run_tools = run_tools
+        .into_iter()
+        .filter(|tool| !state.is_tool_denied(&tool.function.name))
+        .collect::<Vec<_>>();
+    let tool_executor = DeniedToolsExecutor {
+        denied_tools: state.denied_tools.clone(),
+        inner: tool_executor,
+    };
+
     let is_new_session = is_new_session_history(&initial_messages);
     let session_cwd = resolve_session_cwd(&state, session_id).await;
     let environment = EnvironmentContext::snapshot(&session_cwd).await;
@@ -243,7 +256,9 @@ async fn run_session_actor(
 
     let event_state = state.clone();
     let event_forwarder = tokio::spawn(async move {
+        let mut tool_audit = ToolAuditRecorder::default();
         while let Some(event) = core_event_rx.recv().await {
+            tool_audit.record(&event_state, session_id, &event).await;
             handle_core_event(&event_state, session_id, run_id, event).await;
         }
     });
@@ -252,6 +267,11 @@ async fn run_session_actor(
     // window calculations. This is conservative — the actual response may be shorter,
     // but reserving the full limit avoids mid-response context truncation.
     let max_output_tokens = run_config.model.limit.output as u32;
+    // Size the context budget from models.dev limits when the cache has them.
+    let compaction = match stakai::load_models_dev_cache() {
+        Ok(registry) => CompactionConfig::default().with_model_registry(registry),
+        Err(_) => CompactionConfig::default(),
+    };
     let agent_config = AgentConfig {
         model: run_config.model.clone(),
         system_prompt: session_context.system_prompt,
@@ -260,7 +280,8 @@ async fn run_session_actor(
         provider_options: None,
         tool_approval: run_config.tool_approval_policy.clone(),
         retry: RetryConfig::default(),
-        compaction: CompactionConfig::default(),
+        compaction,
+        loop_detection: state.loop_detection.clone(),
         tools: run_tools,
     };
 
@@ -279,7 +300,7 @@ async fn run_session_actor(
         initial_messages,
         &mut initial_metadata,
         user_message,
-        tool_executor.as_ref(),
+        &tool_executor,
         &hooks,
         core_event_tx,
         command_rx,
@@ -392,6 +413,84 @@ async fn handle_core_event(state: &AppState, session_id: Uuid, run_id: Uuid, eve
     state.events.publish(session_id, Some(run_id), event).await;
 }
 
+/// Records how each tool call of a run ended in the session's tool audit log,
+/// keeping proposed calls around so entries carry their arguments.
+#[derive(Default)]
+struct ToolAuditRecorder {
+    proposed: HashMap<String, ToolCall>,
+}
+
+impl ToolAuditRecorder {
+    async fn record(&mut self, state: &AppState, session_id: Uuid, event: &AgentEvent) {
+        let request = match event {
+            AgentEvent::ToolCallsProposed { tool_calls, .. } => {
+                self.proposed.extend(tool_calls.iter().map(|tool_call| {
+                    (
+                        tool_call.id.clone(),
+                        audited_tool_call(&tool_call.id, &tool_call.name, &tool_call.arguments),
+                    )
+                }));
+                return;
+            }
+            AgentEvent::ToolExecutionCompleted {
+                tool_call_id,
+                tool_name,
+                result,
+                is_error,
+                ..
+            } => {
+                let status = if result == "TOOL_CALL_CANCELLED" {
+                    ToolAuditStatus::Cancelled
+                } else if *is_error {
+                    ToolAuditStatus::Failed
+                } else {
+                    ToolAuditStatus::Succeeded
+                };
+                let tool_call = self.take(tool_call_id, tool_name);
+                RecordToolAuditRequest::approved(&tool_call, status, result)
+            }
+            AgentEvent::ToolRejected {
+                tool_call_id,
+                tool_name,
+                ..
+            } => RecordToolAuditRequest::rejected(&self.take(tool_call_id, tool_name)),
+            _ => return,
+        };
+
+        // Backends without an audit log reject the write; that must not fail the run
+        if let Err(error) = state
+            .session_store
+            .record_tool_audit(session_id, &request)
+            .await
+        {
+            tracing::debug!(
+                session_id = %session_id,
+                tool = %request.tool_name,
+                error = %error,
+                "Failed to record tool audit"
+            );
+        }
+    }
+
+    fn take(&mut self, tool_call_id: &str, tool_name: &str) -> ToolCall {
+        self.proposed
+            .remove(tool_call_id)
+            .unwrap_or_else(|| audited_tool_call(tool_call_id, tool_name, &json!({})))
+    }
+}
+
+fn audited_tool_call(id: &str, name: &str, arguments: &serde_json::Value) -> ToolCall {
+    ToolCall {
+        id: id.to_string(),
+        r#type: "function".to_string(),
+        function: FunctionCall {
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+        },
+        metadata: None,
+    }
+}
+
 #[derive(Clone)]
 struct ServerToolExecutor {
     state: AppState,
@@ -434,6 +533,34 @@ impl ToolExecutor for SandboxedToolExecutor {
     }
 }
 
+/// Refuses calls to denied tools before they reach the wrapped executor.
+struct DeniedToolsExecutor {
+    denied_tools: Arc<Vec<String>>,
+    inner: Box<dyn ToolExecutor + Send + Sync>,
+}
+
+#[async_trait]
+impl ToolExecutor for DeniedToolsExecutor {
+    async fn execute_tool_call(
+        &self,
+        run: &AgentRunContext,
+        tool_call: &ProposedToolCall,
+        cancel: &CancellationToken,
+    ) -> Result<ToolExecutionResult, stakpak_agent_core::AgentError> {
+        if is_tool_denied(&self.denied_tools, &tool_call.name) {
+            return Ok(ToolExecutionResult::Completed {
+                result: format!(
+                    "Tool '{}' is denied by this server's denied_tools",
+                    tool_call.name
+                ),
+                is_error: true,
+                error_kind: Some(ToolErrorKind::PermissionDenied),
+            });
+        }
+        self.inner.execute_tool_call(run, tool_call, cancel).await
+    }
+}
+
 struct CheckpointRuntime {
     state: AppState,
     session_id: Uuid,
@@ -565,6 +692,16 @@ impl AgentHook for ServerCheckpointHook {
         Ok(())
     }
 
+    async fn on_pause(
+        &self,
+        _run: &AgentRunContext,
+        messages: &[Message],
+    ) -> Result<(), stakpak_agent_core::AgentError> {
+        self.checkpoint_runtime.update_messages(messages).await;
+        let _ = self.checkpoint_runtime.persist_snapshot().await;
+        Ok(())
+    }
+
     async fn on_error(
         &self,
         _run: &AgentRunContext,
@@ -588,6 +725,7 @@ async fn execute_mcp_tool_call(
         return ToolExecutionResult::Completed {
             result: "MCP client is not initialized".to_string(),
             is_error: true,
+            error_kind: None,
         };
     };
 
@@ -640,6 +778,8 @@ async fn execute_mcp_tool_call_with_client(
             return ToolExecutionResult::Completed {
                 result: format!("MCP tool call failed: {error}"),
                 is_error: true,
+                // The request never reached the tool server
+                error_kind: Some(ToolErrorKind::Transient),
             };
         }
     };
@@ -664,19 +804,33 @@ async fn execute_mcp_tool_call_with_client(
         server_result = request_handle.await_response() => {
             match server_result {
                 Ok(ServerResult::CallToolResult(result)) => {
+                    let is_error = result.is_error.unwrap_or(false);
+                    let rendered = render_call_tool_result(&result);
+                    let error_kind = if is_error {
+                        ToolErrorKind::classify(&rendered)
+                    } else {
+                        None
+                    };
                     ToolExecutionResult::Completed {
-                        result: render_call_tool_result(&result),
-                        is_error: result.is_error.unwrap_or(false),
+                        result: rendered,
+                        is_error,
+                        error_kind,
                     }
                 }
                 Ok(_) => ToolExecutionResult::Completed {
                     result: "Unexpected MCP response type".to_string(),
                     is_error: true,
+                    error_kind: None,
                 },
-                Err(error) => ToolExecutionResult::Completed {
-                    result: format!("MCP tool execution error: {error}"),
-                    is_error: true,
-                },
+                Err(error) => {
+                    let result = format!("MCP tool execution error: {error}");
+                    let error_kind = ToolErrorKind::classify(&result);
+                    ToolExecutionResult::Completed {
+                        result,
+                        is_error: true,
+                        error_kind,
+                    }
+                }
             }
         }
     }
@@ -931,4 +1085,142 @@ mod tests {
 
         assert_eq!(result.text().unwrap_or_default(), "hello");
     }
+
+    struct EchoExecutor;
+
+    #[async_trait]
+    impl ToolExecutor for EchoExecutor {
+        async fn execute_tool_call(
+            &self,
+            _run: &AgentRunContext,
+            tool_call: &ProposedToolCall,
+            _cancel: &CancellationToken,
+        ) -> Result<ToolExecutionResult, stakpak_agent_core::AgentError> {
+            Ok(ToolExecutionResult::Completed {
+                result: format!("ran {}", tool_call.name),
+                is_error: false,
+                error_kind: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn denied_tools_are_refused_at_dispatch() {
+        let executor = DeniedToolsExecutor {
+            denied_tools: Arc::new(vec!["run_command".to_string()]),
+            inner: Box::new(EchoExecutor),
+        };
+        let run = build_run_context(Uuid::new_v4(), Uuid::new_v4());
+        let call = |name: &str| ProposedToolCall {
+            id: "call-1".to_string(),
+            name: name.to_string(),
+            arguments: json!({}),
+            metadata: None,
+        };
+
+        let denied = executor
+            .execute_tool_call(
+                &run,
+                &call("stakpak__run_command"),
+                &CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            denied,
+            ToolExecutionResult::Completed {
+                is_error: true,
+                error_kind: Some(ToolErrorKind::PermissionDenied),
+                ..
+            }
+        ));
+
+        let allowed = executor
+            .execute_tool_call(&run, &call("stakpak__view"), &CancellationToken::new())
+            .await
+            .unwrap();
+        assert!(matches!(
+            allowed,
+            ToolExecutionResult::Completed { ref result, is_error: false, .. } if result == "ran stakpak__view"
+        ));
+    }
+
+    #[tokio::test]
+    async fn tool_audit_recorder_records_every_outcome() {
+        use stakpak_api::SessionStorage;
+
+        let storage = Arc::new(stakpak_api::LocalStorage::new(":memory:").await.unwrap());
+        let session = storage
+            .create_session(&stakpak_api::StorageCreateSessionRequest::new(
+                "Audit",
+                Vec::new(),
+            ))
+            .await
+            .unwrap();
+        let model = stakai::Model::custom("test-model", "openai");
+        let state = AppState::new(
+            storage.clone(),
+            Arc::new(crate::EventLog::new(16)),
+            Arc::new(crate::IdempotencyStore::new(
+                std::time::Duration::from_secs(60),
+            )),
+            Arc::new(stakai::Inference::new()),
+            vec![model.clone()],
+            Some(model),
+            stakpak_agent_core::ToolApprovalPolicy::All,
+        );
+        let run_id = Uuid::new_v4();
+        let call = |id: &str, name: &str| ProposedToolCall {
+            id: id.to_string(),
+            name: name.to_string(),
+            arguments: json!({"path": "README.md"}),
+            metadata: None,
+        };
+
+        let mut recorder = ToolAuditRecorder::default();
+        let events = [
+            AgentEvent::ToolCallsProposed {
+                run_id,
+                tool_calls: vec![call("tc_1", "view"), call("tc_2", "run_command")],
+            },
+            AgentEvent::ToolExecutionCompleted {
+                run_id,
+                tool_call_id: "tc_1".to_string(),
+                tool_name: "view".to_string(),
+                result: "# README".to_string(),
+                is_error: false,
+            },
+            AgentEvent::ToolRejected {
+                run_id,
+                tool_call_id: "tc_2".to_string(),
+                tool_name: "run_command".to_string(),
+                reason: "Tool call rejected by user".to_string(),
+            },
+            AgentEvent::ToolExecutionCompleted {
+                run_id,
+                tool_call_id: "tc_3".to_string(),
+                tool_name: "create".to_string(),
+                result: "TOOL_CALL_CANCELLED".to_string(),
+                is_error: true,
+            },
+        ];
+        for event in &events {
+            recorder.record(&state, session.session_id, event).await;
+        }
+
+        let audit = storage.get_tool_audit(session.session_id).await.unwrap();
+        let outcomes = audit
+            .iter()
+            .map(|entry| (entry.tool_call_id.as_str(), entry.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            vec![
+                ("tc_1", ToolAuditStatus::Succeeded),
+                ("tc_2", ToolAuditStatus::NotRun),
+                ("tc_3", ToolAuditStatus::Cancelled),
+            ]
+        );
+        assert!(audit[0].arguments.contains("README.md"));
+    }
 }
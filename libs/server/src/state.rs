@@ -7,7 +7,7 @@ use crate::{
     sandbox::{PersistentSandbox, SandboxConfig, SandboxMode},
     session_manager::SessionManager,
 };
-use stakpak_agent_core::{ProposedToolCall, ToolApprovalPolicy};
+use stakpak_agent_core::{LoopDetectionConfig, ProposedToolCall, ToolApprovalPolicy};
 use stakpak_api::SessionStorage;
 use stakpak_mcp_client::McpClient;
 use std::{collections::HashMap, sync::Arc, time::Instant};
@@ -33,6 +33,8 @@ pub struct AppState {
     pub models: Arc<Vec<stakai::Model>>,
     pub default_model: Option<stakai::Model>,
     pub tool_approval_policy: ToolApprovalPolicy,
+    /// Tools removed from every run regardless of the approval policy.
+    pub denied_tools: Arc<Vec<String>>,
     pub started_at: Instant,
     pub mcp_client: Option<Arc<McpClient>>,
     pub mcp_tools: Arc<RwLock<Vec<stakai::Tool>>>,
@@ -44,6 +46,8 @@ pub struct AppState {
     pub persistent_sandbox: Option<Arc<PersistentSandbox>>,
     pub base_system_prompt: Option<String>,
     pub context_budget: ContextBudget,
+    /// Stops runs stuck repeating the same tool calls.
+    pub loop_detection: LoopDetectionConfig,
     /// Base directory for project context discovery (AGENTS.md, APPS.md).
     /// Falls back to process cwd if not set. Should be set to the directory
     /// where `stakpak up` was run so gateway sessions can discover project files.
@@ -75,6 +79,7 @@ impl AppState {
             models: Arc::new(models),
             default_model,
             tool_approval_policy,
+            denied_tools: Arc::new(Vec::new()),
             started_at: Instant::now(),
             mcp_client: None,
             mcp_tools: Arc::new(RwLock::new(Vec::new())),
@@ -84,6 +89,7 @@ impl AppState {
             persistent_sandbox: None,
             base_system_prompt: None,
             context_budget: ContextBudget::default(),
+            loop_detection: LoopDetectionConfig::default(),
             project_dir: None,
             skills_context: Arc::new(RwLock::new(Vec::new())),
             pending_tools: Arc::new(RwLock::new(HashMap::new())),
@@ -104,6 +110,16 @@ impl AppState {
         self
     }
 
+    pub fn with_denied_tools(mut self, denied_tools: Vec<String>) -> Self {
+        self.denied_tools = Arc::new(denied_tools);
+        self
+    }
+
+    /// Whether a tool is on the deny list, ignoring the MCP server prefix.
+    pub fn is_tool_denied(&self, tool_name: &str) -> bool {
+        stakpak_shared::utils::is_tool_denied(&self.denied_tools, tool_name)
+    }
+
     pub fn with_sandbox(mut self, sandbox_config: SandboxConfig) -> Self {
         self.sandbox_config = Some(sandbox_config);
         self
@@ -134,6 +150,11 @@ impl AppState {
         self
     }
 
+    pub fn with_loop_detection(mut self, loop_detection: LoopDetectionConfig) -> Self {
+        self.loop_detection = loop_detection;
+        self
+    }
+
     pub fn with_project_dir(mut self, dir: Option<String>) -> Self {
         self.project_dir = dir.filter(|value| !value.trim().is_empty());
         self
@@ -159,7 +180,13 @@ impl AppState {
     }
 
     pub async fn current_mcp_tools(&self) -> Vec<stakai::Tool> {
-        self.mcp_tools.read().await.clone()
+        self.mcp_tools
+            .read()
+            .await
+            .iter()
+            .filter(|tool| !self.is_tool_denied(&tool.function.name))
+            .cloned()
+            .collect()
     }
 
     pub async fn refresh_mcp_tools(&self) -> Result<usize, String> {
@@ -0,0 +1,45 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use stakpak_shared::secrets::gitleaks::{detect_secrets, initialize_gitleaks_config};
+use stakpak_shared::secrets::redact_secrets;
+use std::collections::HashMap;
+use std::hint::black_box;
+
+/// Typical streamed tool output: build logs without any secrets
+fn build_log(lines: usize) -> String {
+    (0..lines)
+        .map(|i| format!("[{i:05}] Compiling module_{i} v0.1.0 (/workspace/crates/module_{i})\n"))
+        .collect()
+}
+
+/// The same output with a credential in the middle
+fn build_log_with_secret(lines: usize) -> String {
+    let mut log = build_log(lines);
+    let key = ["AKIA", "IOSFODNN7EX23PLE"].concat();
+    log.push_str(&format!("export AWS_ACCESS_KEY_ID={key}\n"));
+    log.push_str(&build_log(lines));
+    log
+}
+
+fn bench_secret_detection(c: &mut Criterion) {
+    initialize_gitleaks_config(false);
+    let clean = build_log(200);
+    let with_secret = build_log_with_secret(100);
+
+    c.bench_function("detect_secrets/clean_output", |b| {
+        b.iter(|| detect_secrets(black_box(&clean), None, false))
+    });
+    c.bench_function("detect_secrets/output_with_secret", |b| {
+        b.iter(|| detect_secrets(black_box(&with_secret), None, false))
+    });
+    c.bench_function("redact_secrets/streamed_chunks", |b| {
+        let chunks: Vec<&str> = clean.lines().collect();
+        b.iter(|| {
+            for chunk in &chunks {
+                black_box(redact_secrets(chunk, None, &HashMap::new(), false));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_secret_detection);
+criterion_main!(benches);
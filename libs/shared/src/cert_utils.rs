@@ -402,22 +402,43 @@ impl LoadedCertificateChain {
     }
 
     pub fn create_client_config(&self) -> Result<ClientConfig> {
-        let client_certs: Vec<CertificateDer<'static>> =
-            CertificateDer::pem_slice_iter(self.client_cert_pem.as_bytes())
-                .collect::<std::result::Result<Vec<_>, _>>()
-                .context("Failed to parse client certificate PEM")?;
+        create_client_config_from_pem(
+            &self.ca_cert_pem,
+            Some((&self.client_cert_pem, &self.client_key_pem)),
+        )
+    }
+}
 
-        let client_key = PrivateKeyDer::from_pem_slice(self.client_key_pem.as_bytes())
-            .context("Failed to parse client private key PEM")?;
+/// Build a `rustls::ClientConfig` that trusts the given CA PEM and, when a
+/// `(certificate, private key)` PEM pair is provided, presents it as the
+/// client certificate for mTLS.
+pub fn create_client_config_from_pem(
+    ca_cert_pem: &str,
+    client_identity: Option<(&str, &str)>,
+) -> Result<ClientConfig> {
+    let mut root_cert_store = RootCertStore::empty();
+    for cert in CertificateDer::pem_slice_iter(ca_cert_pem.as_bytes()) {
+        let cert = cert.context("Failed to parse CA certificate PEM")?;
+        root_cert_store
+            .add(cert)
+            .context("Failed to add CA certificate to root store")?;
+    }
 
-        let root_cert_store = self.parse_root_cert_store()?;
+    let builder = ClientConfig::builder().with_root_certificates(root_cert_store);
 
-        let config = ClientConfig::builder()
-            .with_root_certificates(root_cert_store)
-            .with_client_auth_cert(client_certs, client_key)?;
+    let Some((client_cert_pem, client_key_pem)) = client_identity else {
+        return Ok(builder.with_no_client_auth());
+    };
 
-        Ok(config)
-    }
+    let client_certs: Vec<CertificateDer<'static>> =
+        CertificateDer::pem_slice_iter(client_cert_pem.as_bytes())
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .context("Failed to parse client certificate PEM")?;
+
+    let client_key = PrivateKeyDer::from_pem_slice(client_key_pem.as_bytes())
+        .context("Failed to parse client private key PEM")?;
+
+    Ok(builder.with_client_auth_cert(client_certs, client_key)?)
 }
 
 #[cfg(test)]
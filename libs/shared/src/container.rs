@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::net::TcpListener;
 use std::process::Command;
@@ -170,15 +171,143 @@ pub fn warden_ak_store_args(host_knowledge_root: Option<&std::path::Path>) -> Ve
     }
 }
 
+// ── Container runtime selection ────────────────────────────────────────────
+
+/// Which container runtime warden should drive, as written in config or on the
+/// command line (`runtime = "docker" | "podman" | "auto"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerRuntimeSetting {
+    /// Use whichever runtime is on `PATH`, preferring Docker.
+    #[default]
+    Auto,
+    Docker,
+    Podman,
+}
+
+impl std::str::FromStr for ContainerRuntimeSetting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "docker" => Ok(Self::Docker),
+            "podman" => Ok(Self::Podman),
+            other => Err(format!(
+                "Unknown container runtime '{other}' (expected docker, podman, or auto)"
+            )),
+        }
+    }
+}
+
+impl ContainerRuntimeSetting {
+    pub fn is_auto(&self) -> bool {
+        *self == ContainerRuntimeSetting::Auto
+    }
+
+    /// Resolve the setting to a concrete runtime by looking at `PATH`.
+    ///
+    /// `Auto` falls back to Docker when neither binary is found so the
+    /// resulting error message names the runtime most users expect.
+    pub fn resolve(self) -> ContainerRuntime {
+        self.resolve_with(is_binary_on_path)
+    }
+
+    fn resolve_with(self, on_path: impl Fn(&str) -> bool) -> ContainerRuntime {
+        match self {
+            Self::Docker => ContainerRuntime::Docker,
+            Self::Podman => ContainerRuntime::Podman,
+            Self::Auto => {
+                if on_path(ContainerRuntime::Docker.binary()) {
+                    ContainerRuntime::Docker
+                } else if on_path(ContainerRuntime::Podman.binary()) {
+                    ContainerRuntime::Podman
+                } else {
+                    ContainerRuntime::Docker
+                }
+            }
+        }
+    }
+}
+
+/// A concrete container runtime whose CLI we invoke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    /// Name of the runtime's CLI binary.
+    pub fn binary(self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+        }
+    }
+
+    /// Translate a `host:container[:opts]` volume spec to this runtime's syntax.
+    ///
+    /// Podman on SELinux hosts refuses to read bind mounts unless they are
+    /// relabeled, so bind mounts get the shared `z` option appended (shared
+    /// rather than private `Z` because parallel sandboxes mount the same host
+    /// paths). Named volumes and specs that already carry a label are left
+    /// untouched. Docker specs are returned as-is.
+    pub fn translate_volume(self, volume: &str) -> String {
+        if self == ContainerRuntime::Docker || is_named_volume(volume_host_part(volume)) {
+            return volume.to_string();
+        }
+
+        let mut parts = volume.splitn(3, ':');
+        let (Some(host), Some(container)) = (parts.next(), parts.next()) else {
+            return volume.to_string();
+        };
+
+        match parts.next() {
+            Some(opts) if opts.split(',').any(|o| o == "z" || o == "Z") => volume.to_string(),
+            Some(opts) if !opts.is_empty() => format!("{host}:{container}:{opts},z"),
+            _ => format!("{host}:{container}:z"),
+        }
+    }
+
+    /// Extra `warden wrap` flags this runtime needs.
+    ///
+    /// Rootless Podman maps the container's root to the invoking host user, so
+    /// `--userns keep-id` is required for bind-mounted files to keep the host
+    /// UID inside the container instead of showing up as `nobody`.
+    pub fn wrap_args(self) -> Vec<String> {
+        match self {
+            ContainerRuntime::Docker => Vec::new(),
+            ContainerRuntime::Podman => vec!["--userns".to_string(), "keep-id".to_string()],
+        }
+    }
+}
+
+/// Check whether an executable named `binary` exists in any `PATH` directory.
+pub fn is_binary_on_path(binary: &str) -> bool {
+    let Some(path) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&path).any(|dir| {
+        let candidate = dir.join(binary);
+        candidate.is_file() || (cfg!(windows) && candidate.with_extension("exe").is_file())
+    })
+}
+
 /// Pre-create any Docker named volumes found in [`stakpak_agent_default_mounts`].
 ///
 /// Running `docker volume create` is idempotent and prevents a race condition
 /// when multiple sandbox containers first-use the same named volume in parallel.
 pub fn ensure_named_volumes_exist() {
+    ensure_named_volumes_exist_for(ContainerRuntime::Docker);
+}
+
+/// Same as [`ensure_named_volumes_exist`] but for an explicit runtime.
+pub fn ensure_named_volumes_exist_for(runtime: ContainerRuntime) {
     for vol in stakpak_agent_default_mounts() {
         let host_part = volume_host_part(&vol);
         if is_named_volume(host_part) {
-            let _ = Command::new("docker")
+            let _ = Command::new(runtime.binary())
                 .args(["volume", "create", host_part])
                 .stdout(std::process::Stdio::null())
                 .stderr(std::process::Stdio::null())
@@ -441,6 +570,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn container_runtime_setting_parses_known_values() {
+        assert_eq!(
+            "docker".parse::<ContainerRuntimeSetting>(),
+            Ok(ContainerRuntimeSetting::Docker)
+        );
+        assert_eq!(
+            "Podman".parse::<ContainerRuntimeSetting>(),
+            Ok(ContainerRuntimeSetting::Podman)
+        );
+        assert_eq!(
+            "auto".parse::<ContainerRuntimeSetting>(),
+            Ok(ContainerRuntimeSetting::Auto)
+        );
+        assert!("lxc".parse::<ContainerRuntimeSetting>().is_err());
+    }
+
+    #[test]
+    fn auto_runtime_prefers_docker_then_podman() {
+        let auto = ContainerRuntimeSetting::Auto;
+        assert_eq!(auto.resolve_with(|_| true), ContainerRuntime::Docker);
+        assert_eq!(
+            auto.resolve_with(|bin| bin == "podman"),
+            ContainerRuntime::Podman
+        );
+        assert_eq!(auto.resolve_with(|_| false), ContainerRuntime::Docker);
+    }
+
+    #[test]
+    fn explicit_runtime_ignores_path() {
+        assert_eq!(
+            ContainerRuntimeSetting::Podman.resolve_with(|_| false),
+            ContainerRuntime::Podman
+        );
+        assert_eq!(
+            ContainerRuntimeSetting::Docker.resolve_with(|bin| bin == "podman"),
+            ContainerRuntime::Docker
+        );
+    }
+
+    #[test]
+    fn docker_volumes_are_not_translated() {
+        let docker = ContainerRuntime::Docker;
+        assert_eq!(docker.translate_volume("./:/agent:ro"), "./:/agent:ro");
+        assert_eq!(docker.translate_volume("/tmp:/tmp"), "/tmp:/tmp");
+        assert!(docker.wrap_args().is_empty());
+    }
+
+    #[test]
+    fn podman_bind_mounts_get_selinux_relabel() {
+        let podman = ContainerRuntime::Podman;
+        assert_eq!(podman.translate_volume("./:/agent:ro"), "./:/agent:ro,z");
+        assert_eq!(podman.translate_volume("/tmp:/tmp"), "/tmp:/tmp:z");
+        assert_eq!(podman.translate_volume("/tmp:/tmp:Z"), "/tmp:/tmp:Z");
+        assert_eq!(podman.translate_volume("/tmp:/tmp:ro,z"), "/tmp:/tmp:ro,z");
+    }
+
+    #[test]
+    fn podman_named_volumes_are_not_relabeled() {
+        let named = "stakpak-aqua-cache:/home/agent/.local/share/aquaproj-aqua";
+        assert_eq!(ContainerRuntime::Podman.translate_volume(named), named);
+    }
+
+    #[test]
+    fn podman_wrap_args_keep_host_userns() {
+        assert_eq!(
+            ContainerRuntime::Podman.wrap_args(),
+            vec!["--userns".to_string(), "keep-id".to_string()]
+        );
+    }
+
     #[test]
     fn resolve_ak_store_returns_none_when_unset() {
         let _guard = ENV_LOCK.lock().unwrap();
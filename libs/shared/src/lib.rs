@@ -12,6 +12,7 @@ pub mod oauth;
 pub mod paths;
 pub mod remote_connection;
 pub mod remote_store;
+pub mod run_id;
 pub mod secret_manager;
 pub mod secrets;
 pub mod task_manager;
@@ -707,6 +707,13 @@ pub struct ChatCompletionResponse {
     pub metadata: Option<serde_json::Value>,
 }
 
+impl ChatCompletionResponse {
+    /// Checkpoint the response was saved to, as reported in its metadata.
+    pub fn checkpoint_id(&self) -> Option<Uuid> {
+        checkpoint_id_from_metadata(self.metadata.as_ref())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ChatCompletionChoice {
     pub index: usize,
@@ -759,6 +766,20 @@ pub struct ChatCompletionStreamResponse {
     pub metadata: Option<serde_json::Value>,
 }
 
+impl ChatCompletionStreamResponse {
+    /// Checkpoint id carried by the stream's metadata chunk, if this is one.
+    pub fn checkpoint_id(&self) -> Option<Uuid> {
+        checkpoint_id_from_metadata(self.metadata.as_ref())
+    }
+}
+
+fn checkpoint_id_from_metadata(metadata: Option<&serde_json::Value>) -> Option<Uuid> {
+    metadata?
+        .get("checkpoint_id")?
+        .as_str()
+        .and_then(|id| Uuid::parse_str(id).ok())
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ChatCompletionStreamChoice {
     pub index: usize,
@@ -858,10 +879,7 @@ impl From<LLMMessage> for ChatMessage {
                                 r#type: "image_url".to_string(),
                                 text: None,
                                 image_url: Some(ImageUrl {
-                                    url: format!(
-                                        "data:{};base64,{}",
-                                        source.media_type, source.data
-                                    ),
+                                    url: source.to_url(),
                                     detail: None,
                                 }),
                             });
@@ -912,29 +930,8 @@ impl From<ChatMessage> for LLMMessage {
                     if let Some(text) = part.text {
                         content_parts.push(LLMMessageTypedContent::Text { text });
                     } else if let Some(image_url) = part.image_url {
-                        let (media_type, data) = if image_url.url.starts_with("data:") {
-                            let parts: Vec<&str> = image_url.url.splitn(2, ',').collect();
-                            if parts.len() == 2 {
-                                let meta = parts[0];
-                                let data = parts[1];
-                                let media_type = meta
-                                    .trim_start_matches("data:")
-                                    .trim_end_matches(";base64")
-                                    .to_string();
-                                (media_type, data.to_string())
-                            } else {
-                                ("image/jpeg".to_string(), image_url.url)
-                            }
-                        } else {
-                            ("image/jpeg".to_string(), image_url.url)
-                        };
-
                         content_parts.push(LLMMessageTypedContent::Image {
-                            source: LLMMessageImageSource {
-                                r#type: "base64".to_string(),
-                                media_type,
-                                data,
-                            },
+                            source: LLMMessageImageSource::from_url(&image_url.url),
                         });
                     }
                 }
@@ -1310,4 +1307,40 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn test_checkpoint_id_round_trips_through_stream_metadata() {
+        let checkpoint_id = Uuid::new_v4();
+        // The metadata chunk as the agent client sends it on the wire.
+        let wire = format!(
+            r#"{{"id":"req","object":"chat.completion.chunk","created":0,"model":"","choices":[],"usage":null,"metadata":{{"session_id":"{}","checkpoint_id":"{checkpoint_id}"}}}}"#,
+            Uuid::new_v4()
+        );
+        let chunk: ChatCompletionStreamResponse = serde_json::from_str(&wire).unwrap();
+        assert_eq!(chunk.checkpoint_id(), Some(checkpoint_id));
+
+        let chunk: ChatCompletionStreamResponse =
+            serde_json::from_str(&serde_json::to_string(&chunk).unwrap()).unwrap();
+        assert_eq!(chunk.checkpoint_id(), Some(checkpoint_id));
+
+        let response = ChatCompletionResponse {
+            id: chunk.id.clone(),
+            object: "chat.completion".to_string(),
+            created: 0,
+            model: String::new(),
+            choices: vec![],
+            usage: LLMTokenUsage::default(),
+            system_fingerprint: None,
+            metadata: chunk.metadata.clone(),
+        };
+        let response: ChatCompletionResponse =
+            serde_json::from_str(&serde_json::to_string(&response).unwrap()).unwrap();
+        assert_eq!(response.checkpoint_id(), Some(checkpoint_id));
+
+        let not_a_uuid: ChatCompletionStreamResponse = serde_json::from_str(
+            r#"{"id":"req","object":"chat.completion.chunk","created":0,"model":"","choices":[],"metadata":{"checkpoint_id":"latest"}}"#,
+        )
+        .unwrap();
+        assert_eq!(not_a_uuid.checkpoint_id(), None);
+    }
 }
@@ -3,6 +3,7 @@ use crate::local_store::LocalStore;
 use crate::models::error::{AgentError, BadRequestErrorMessage};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
@@ -59,6 +60,266 @@ pub struct ValidationResult {
     pub new_query: Option<String>,
 }
 
+impl AnalysisResult {
+    /// Parse a query analysis emitted by a model.
+    pub fn from_llm_output(raw: &str) -> Result<Self, AgentError> {
+        parse_llm_json(raw).map_err(|e| {
+            AgentError::BadRequest(BadRequestErrorMessage::ApiError(format!(
+                "Failed to parse query analysis: {}",
+                e
+            )))
+        })
+    }
+}
+
+impl ValidationResult {
+    /// Parse a search result validation emitted by a model.
+    pub fn from_llm_output(raw: &str) -> Result<Self, AgentError> {
+        parse_llm_json(raw).map_err(|e| {
+            AgentError::BadRequest(BadRequestErrorMessage::ApiError(format!(
+                "Failed to parse search validation: {}",
+                e
+            )))
+        })
+    }
+}
+
+/// Sent back to a model whose reply did not parse, with the parse error appended.
+pub const STRICT_JSON_REPAIR_PROMPT: &str = "Your reply could not be parsed as the requested JSON. Reply again with only the JSON object, no prose and no code fences. The parse error was: ";
+
+/// Parse a model's `reply` with `parse`. When it does not parse, `reprompt`
+/// is called once with the bad reply and the error to ask the model for
+/// strict JSON, and its answer is parsed instead.
+pub async fn parse_llm_reply<T, Fut>(
+    reply: String,
+    parse: impl Fn(&str) -> Result<T, AgentError>,
+    reprompt: impl FnOnce(String, String) -> Fut,
+) -> Result<T, AgentError>
+where
+    Fut: std::future::Future<Output = Result<String, AgentError>>,
+{
+    match parse(&reply) {
+        Ok(value) => Ok(value),
+        Err(error) => {
+            let retry = reprompt(reply, error.to_string()).await?;
+            parse(&retry)
+        }
+    }
+}
+
+/// Parse JSON written by a model, tolerating ```json fences and surrounding prose.
+fn parse_llm_json<T: DeserializeOwned>(raw: &str) -> Result<T, serde_json::Error> {
+    let trimmed = raw.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Ok(value);
+    }
+
+    // The body of the first fenced block, minus its language tag
+    if let Some(fenced) = trimmed.split("```").nth(1) {
+        let body = fenced.strip_prefix("json").unwrap_or(fenced);
+        if let Ok(value) = serde_json::from_str(body.trim()) {
+            return Ok(value);
+        }
+    }
+
+    // The outermost object in prose like "Here is the result: {...} Hope it helps"
+    let object = match (trimmed.find('{'), trimmed.rfind('}')) {
+        (Some(start), Some(end)) if start < end => trimmed.get(start..=end),
+        _ => None,
+    };
+    serde_json::from_str(object.unwrap_or(trimmed))
+}
+
+/// Longest scraped page handed to the model, in characters
+pub const MAX_SCRAPED_CONTENT_CHARS: usize = 20_000;
+
+const BINARY_EXTENSIONS: &[&str] = &[
+    ".pdf", ".png", ".jpg", ".jpeg", ".gif", ".webp", ".ico", ".svgz", ".zip", ".gz", ".tgz",
+    ".tar", ".bz2", ".xz", ".7z", ".exe", ".dmg", ".deb", ".rpm", ".whl", ".jar", ".wasm", ".mp3",
+    ".mp4", ".mov", ".woff", ".woff2", ".ttf", ".bin",
+];
+
+impl ScrapedContent {
+    /// Content type reported by the scraper, if any
+    pub fn content_type(&self) -> Option<&str> {
+        let metadata = self.metadata.as_ref()?;
+        ["content_type", "contentType", "mime_type", "content-type"]
+            .iter()
+            .find_map(|key| metadata.get(*key).and_then(|value| value.as_str()))
+    }
+
+    /// Whether the page is text the model can read, judged by its reported
+    /// content type, its URL extension and NUL bytes in the body
+    pub fn is_text(&self) -> bool {
+        if let Some(content_type) = self.content_type() {
+            let content_type = content_type.to_ascii_lowercase();
+            let textual = content_type.starts_with("text/")
+                || [
+                    "json",
+                    "xml",
+                    "html",
+                    "javascript",
+                    "yaml",
+                    "toml",
+                    "markdown",
+                ]
+                .iter()
+                .any(|kind| content_type.contains(kind));
+            if !textual {
+                return false;
+            }
+        }
+
+        let path = self
+            .url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if BINARY_EXTENSIONS.iter().any(|ext| path.ends_with(ext)) {
+            return false;
+        }
+
+        !self
+            .content
+            .as_deref()
+            .is_some_and(|content| content.contains('\0'))
+    }
+
+    /// Convert HTML to text and cap the content at `max_chars`, recording
+    /// truncation in the metadata. Returns `None` for non-text pages.
+    pub fn into_clean_text(mut self, max_chars: usize) -> Option<Self> {
+        if !self.is_text() {
+            return None;
+        }
+
+        let Some(content) = self.content.take() else {
+            return Some(self);
+        };
+        let is_html = self
+            .content_type()
+            .is_some_and(|content_type| content_type.to_ascii_lowercase().contains("html"))
+            || looks_like_html(&content);
+        let content = if is_html {
+            html_to_text(&content)
+        } else {
+            content
+        };
+
+        let original_chars = content.chars().count();
+        if original_chars <= max_chars {
+            self.content = Some(content);
+            return Some(self);
+        }
+
+        self.content = Some(content.chars().take(max_chars).collect());
+        let mut metadata = match self.metadata.take() {
+            Some(serde_json::Value::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        };
+        metadata.insert("truncated".to_string(), serde_json::Value::Bool(true));
+        metadata.insert("original_length".to_string(), original_chars.into());
+        self.metadata = Some(serde_json::Value::Object(metadata));
+        Some(self)
+    }
+
+    /// Whether `into_clean_text` cut the content short
+    pub fn is_truncated(&self) -> bool {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get("truncated"))
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false)
+    }
+}
+
+fn looks_like_html(content: &str) -> bool {
+    let head: String = content
+        .trim_start()
+        .chars()
+        .take(512)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    head.starts_with("<!doctype html")
+        || head.starts_with("<html")
+        || head.contains("<head>")
+        || head.contains("<body")
+}
+
+/// Strip tags, scripts and styles from HTML, keeping block structure as line breaks
+fn html_to_text(html: &str) -> String {
+    const BLOCK_TAGS: &[&str] = &[
+        "p",
+        "div",
+        "br",
+        "li",
+        "tr",
+        "h1",
+        "h2",
+        "h3",
+        "h4",
+        "h5",
+        "h6",
+        "pre",
+        "section",
+        "article",
+        "header",
+        "footer",
+        "table",
+        "ul",
+        "ol",
+        "blockquote",
+    ];
+
+    let mut text = String::with_capacity(html.len() / 2);
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(rest.get(..start).unwrap_or_default());
+        let after = rest.get(start + 1..).unwrap_or_default();
+        let Some(end) = after.find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = after.get(..end).unwrap_or_default().to_ascii_lowercase();
+        rest = after.get(end + 1..).unwrap_or_default();
+
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+        if !tag.starts_with('/') && (name == "script" || name == "style") {
+            // Skip the element body up to its closing tag
+            let closing = format!("</{}", name);
+            let lower = rest.to_ascii_lowercase();
+            rest = match lower.find(&closing) {
+                Some(idx) => rest
+                    .get(idx..)
+                    .and_then(|tail| tail.find('>').and_then(|gt| tail.get(gt + 1..)))
+                    .unwrap_or_default(),
+                None => "",
+            };
+        } else if BLOCK_TAGS.contains(&name.as_str()) {
+            text.push('\n');
+        }
+    }
+    text.push_str(rest);
+
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 impl SearchRequest {
     pub fn validate(&self) -> Result<(), AgentError> {
         if self.query.trim().is_empty() {
@@ -231,6 +492,48 @@ impl SearchClient {
     }
 }
 
+/// Web search backend used for local docs search
+#[async_trait::async_trait]
+pub trait SearchBackend: Send + Sync {
+    /// Searches the web and scrapes the top results
+    async fn search_and_scrape(
+        &self,
+        query: String,
+        whitelist: Option<Vec<String>>,
+    ) -> Result<Vec<ScrapedContent>, AgentError>;
+}
+
+/// Any HTTP service speaking the searchpak API, e.g. a self-hosted instance
+#[async_trait::async_trait]
+impl SearchBackend for SearchClient {
+    async fn search_and_scrape(
+        &self,
+        query: String,
+        whitelist: Option<Vec<String>>,
+    ) -> Result<Vec<ScrapedContent>, AgentError> {
+        SearchClient::search_and_scrape(self, query, whitelist).await
+    }
+}
+
+/// The bundled searchpak container, started on first use
+#[derive(Debug, Default)]
+pub struct BundledSearchBackend;
+
+#[async_trait::async_trait]
+impl SearchBackend for BundledSearchBackend {
+    async fn search_and_scrape(
+        &self,
+        query: String,
+        whitelist: Option<Vec<String>>,
+    ) -> Result<Vec<ScrapedContent>, AgentError> {
+        let config = SearchServicesOrchestrator::start().await?;
+        let api_url = format!("http://localhost:{}", config.api_port);
+        SearchClient::new(api_url)
+            .search_and_scrape(query, whitelist)
+            .await
+    }
+}
+
 #[derive(Debug)]
 pub struct SearchServicesOrchestrator;
 
@@ -422,3 +725,184 @@ impl Drop for SearchServicesOrchestrator {
         let _ = Self::stop_sync();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANALYSIS: &str = r#"{"required_documentation": ["terraform aws provider"], "reformulated_query": "terraform aws_s3_bucket"}"#;
+
+    fn expected_analysis() -> AnalysisResult {
+        AnalysisResult {
+            required_documentation: vec!["terraform aws provider".to_string()],
+            reformulated_query: "terraform aws_s3_bucket".to_string(),
+        }
+    }
+
+    #[test]
+    fn analysis_parses_bare_json() {
+        assert_eq!(
+            AnalysisResult::from_llm_output(ANALYSIS).unwrap(),
+            expected_analysis()
+        );
+    }
+
+    #[test]
+    fn analysis_parses_fenced_json() {
+        let raw = format!("```json\n{ANALYSIS}\n```");
+        assert_eq!(
+            AnalysisResult::from_llm_output(&raw).unwrap(),
+            expected_analysis()
+        );
+    }
+
+    #[test]
+    fn analysis_parses_prose_wrapped_json() {
+        let raw = format!("Here is my analysis:\n{ANALYSIS}\nLet me know if you need more.");
+        assert_eq!(
+            AnalysisResult::from_llm_output(&raw).unwrap(),
+            expected_analysis()
+        );
+    }
+
+    #[test]
+    fn validation_parses_fenced_json_with_prose() {
+        let raw = r#"Sure, the docs cover it.
+
+```
+{"is_satisfied": true, "valid_docs": [], "needed_urls": [], "new_query": null}
+```"#;
+        let result = ValidationResult::from_llm_output(raw).unwrap();
+        assert!(result.is_satisfied);
+        assert!(result.needed_urls.is_empty());
+    }
+
+    #[test]
+    fn non_json_output_is_an_error() {
+        assert!(AnalysisResult::from_llm_output("I could not analyze that query.").is_err());
+    }
+
+    #[test]
+    fn validation_accepts_docs_listed_by_url_only() {
+        let raw = r#"{"is_satisfied": false, "valid_docs": [{"url": "https://docs.example.com/tf"}], "needed_urls": ["https://docs.example.com/s3"], "new_query": "terraform s3 backend"}"#;
+        let result = ValidationResult::from_llm_output(raw).unwrap();
+        assert_eq!(result.valid_docs[0].url, "https://docs.example.com/tf");
+        assert_eq!(result.valid_docs[0].content, None);
+        assert_eq!(result.new_query.as_deref(), Some("terraform s3 backend"));
+    }
+
+    #[tokio::test]
+    async fn unparsable_reply_is_repaired_once() {
+        let reprompts = std::sync::Mutex::new(Vec::new());
+        let result = parse_llm_reply(
+            "I think you should search for the aws provider docs.".to_string(),
+            AnalysisResult::from_llm_output,
+            |reply, error| {
+                reprompts.lock().unwrap().push((reply, error));
+                async { Ok(format!("```json\n{ANALYSIS}\n```")) }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result, expected_analysis());
+        let reprompts = reprompts.lock().unwrap();
+        assert_eq!(reprompts.len(), 1);
+        assert_eq!(
+            reprompts[0].0,
+            "I think you should search for the aws provider docs."
+        );
+        assert!(reprompts[0].1.contains("Failed to parse query analysis"));
+    }
+
+    #[tokio::test]
+    async fn parsable_reply_is_not_repaired() {
+        let result = parse_llm_reply(
+            ANALYSIS.to_string(),
+            AnalysisResult::from_llm_output,
+            |_, _| async {
+                // Reprompting a valid reply would surface as this error.
+                Err(AgentError::InternalError)
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(result, expected_analysis());
+    }
+
+    #[tokio::test]
+    async fn reply_still_unparsable_after_repair_is_an_error() {
+        let result = parse_llm_reply(
+            "no json here".to_string(),
+            AnalysisResult::from_llm_output,
+            |_, _| async { Ok("still no json".to_string()) },
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    fn page(url: &str, content: &str, content_type: Option<&str>) -> ScrapedContent {
+        ScrapedContent {
+            url: url.to_string(),
+            title: None,
+            content: Some(content.to_string()),
+            metadata: content_type.map(|t| serde_json::json!({ "content_type": t })),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn oversized_page_is_truncated() {
+        let long = "terraform ".repeat(100);
+        let cleaned = page("https://docs.example.com/tf", &long, Some("text/plain"))
+            .into_clean_text(50)
+            .unwrap();
+
+        assert_eq!(
+            cleaned.content.as_deref().map(|c| c.chars().count()),
+            Some(50)
+        );
+        assert!(cleaned.is_truncated());
+        let metadata = cleaned.metadata.unwrap();
+        assert_eq!(metadata["original_length"], 1000);
+        assert_eq!(metadata["content_type"], "text/plain");
+    }
+
+    #[test]
+    fn short_page_is_not_truncated() {
+        let cleaned = page("https://docs.example.com/tf", "short", None)
+            .into_clean_text(50)
+            .unwrap();
+
+        assert_eq!(cleaned.content.as_deref(), Some("short"));
+        assert!(!cleaned.is_truncated());
+        assert!(cleaned.metadata.is_none());
+    }
+
+    #[test]
+    fn binary_pages_are_skipped() {
+        let pdf = page("https://example.com/guide.pdf?download=1", "%PDF-1.7", None);
+        assert!(pdf.into_clean_text(1000).is_none());
+
+        let image = page("https://example.com/logo", "\u{0}PNG", Some("image/png"));
+        assert!(image.into_clean_text(1000).is_none());
+
+        let nul = page("https://example.com/blob", "abc\u{0}def", None);
+        assert!(nul.into_clean_text(1000).is_none());
+    }
+
+    #[test]
+    fn html_is_converted_to_text() {
+        let html = "<!DOCTYPE html><html><head><style>p { color: red; }</style>\
+            <script>alert('x')</script></head><body><h1>Install</h1>\
+            <p>Run <code>brew install stakpak</code> &amp; enjoy</p></body></html>";
+        let cleaned = page("https://docs.example.com", html, None)
+            .into_clean_text(1000)
+            .unwrap();
+
+        assert_eq!(
+            cleaned.content.as_deref(),
+            Some("Install\nRun brew install stakpak & enjoy")
+        );
+    }
+}
@@ -53,6 +53,7 @@
 
 use serde::{Deserialize, Serialize};
 use stakai::Model;
+use stakai::providers::WeightedApiKey;
 use std::collections::HashMap;
 
 use super::auth::ProviderAuth;
@@ -109,6 +110,9 @@ pub enum ProviderConfig {
         /// Authentication credentials (preferred over api_key)
         #[serde(skip_serializing_if = "Option::is_none")]
         auth: Option<ProviderAuth>,
+        /// Extra API keys to balance requests across, with optional weights
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        api_keys: Vec<WeightedApiKey>,
     },
     /// Anthropic provider configuration
     Anthropic {
@@ -123,6 +127,9 @@ pub enum ProviderConfig {
         /// Authentication credentials (preferred over api_key/access_token)
         #[serde(skip_serializing_if = "Option::is_none")]
         auth: Option<ProviderAuth>,
+        /// Extra API keys to balance requests across, with optional weights
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        api_keys: Vec<WeightedApiKey>,
     },
     /// Google Gemini provider configuration
     Gemini {
@@ -297,6 +304,30 @@ impl ProviderConfig {
         }
     }
 
+    /// Weighted API keys to balance requests across (OpenAI and Anthropic)
+    ///
+    /// Empty unless `api_keys` is configured. A single `api_key` set alongside
+    /// it joins the pool with weight 1 rather than being ignored.
+    pub fn api_keys(&self) -> Vec<WeightedApiKey> {
+        let configured = match self {
+            ProviderConfig::OpenAI { api_keys, .. }
+            | ProviderConfig::Anthropic { api_keys, .. } => api_keys.as_slice(),
+            _ => &[],
+        };
+        if configured.is_empty() {
+            return Vec::new();
+        }
+
+        let mut keys = Vec::with_capacity(configured.len() + 1);
+        if let Some(key) = self.api_key()
+            && !configured.iter().any(|pooled| pooled.key == key)
+        {
+            keys.push(WeightedApiKey::new(key, 1));
+        }
+        keys.extend(configured.iter().cloned());
+        keys
+    }
+
     /// Get the auth credentials reference
     fn get_auth_ref(&self) -> Option<&ProviderAuth> {
         match self {
@@ -526,6 +557,7 @@ impl ProviderConfig {
             api_key,
             api_endpoint: None,
             auth: None,
+            api_keys: Vec::new(),
         }
     }
 
@@ -535,6 +567,7 @@ impl ProviderConfig {
             api_key: None,
             api_endpoint: None,
             auth: Some(auth),
+            api_keys: Vec::new(),
         }
     }
 
@@ -545,6 +578,7 @@ impl ProviderConfig {
             api_endpoint: None,
             access_token,
             auth: None,
+            api_keys: Vec::new(),
         }
     }
 
@@ -555,6 +589,7 @@ impl ProviderConfig {
             api_endpoint: None,
             access_token: None,
             auth: Some(auth),
+            api_keys: Vec::new(),
         }
     }
 
@@ -672,12 +707,14 @@ impl ProviderConfig {
                 api_key: None,
                 api_endpoint: None,
                 auth: None,
+                api_keys: Vec::new(),
             }),
             "anthropic" => Some(ProviderConfig::Anthropic {
                 api_key: None,
                 api_endpoint: None,
                 access_token: None,
                 auth: None,
+                api_keys: Vec::new(),
             }),
             "gemini" => Some(ProviderConfig::Gemini {
                 api_key: None,
@@ -711,6 +748,9 @@ impl ProviderConfig {
 pub struct LLMProviderConfig {
     /// All provider configurations (key = provider name)
     pub providers: HashMap<String, ProviderConfig>,
+    /// Maximum in-flight requests per provider (key = provider name).
+    /// Providers without an entry are not limited.
+    pub max_concurrent_requests: HashMap<String, usize>,
 }
 
 impl LLMProviderConfig {
@@ -718,6 +758,7 @@ impl LLMProviderConfig {
     pub fn new() -> Self {
         Self {
             providers: HashMap::new(),
+            max_concurrent_requests: HashMap::new(),
         }
     }
 
@@ -735,6 +776,11 @@ impl LLMProviderConfig {
     pub fn is_empty(&self) -> bool {
         self.providers.is_empty()
     }
+
+    /// Limit how many requests may be in flight to a provider at once
+    pub fn set_max_concurrent_requests(&mut self, name: impl Into<String>, limit: usize) {
+        self.max_concurrent_requests.insert(name.into(), limit);
+    }
 }
 
 /// Provider-specific options for LLM requests
@@ -941,6 +987,40 @@ pub struct LLMMessageImageSource {
     pub data: String,
 }
 
+impl LLMMessageImageSource {
+    /// Build a source from an image URL. Base64 `data:` URIs are split into
+    /// media type and payload; any other URL is kept verbatim as a `url`
+    /// source so it is not mistaken for base64 data.
+    pub fn from_url(url: &str) -> Self {
+        if let Some((header, data)) = url.split_once(',')
+            && let Some(media_type) = header
+                .strip_prefix("data:")
+                .and_then(|header| header.strip_suffix(";base64"))
+        {
+            return Self {
+                r#type: "base64".to_string(),
+                media_type: media_type.to_string(),
+                data: data.to_string(),
+            };
+        }
+
+        Self {
+            r#type: "url".to_string(),
+            media_type: String::new(),
+            data: url.to_string(),
+        }
+    }
+
+    /// The image as a URL, the inverse of [`Self::from_url`].
+    pub fn to_url(&self) -> String {
+        if self.r#type == "url" {
+            self.data.clone()
+        } else {
+            format!("data:{};base64,{}", self.media_type, self.data)
+        }
+    }
+}
+
 impl Default for LLMMessageTypedContent {
     fn default() -> Self {
         LLMMessageTypedContent::Text {
@@ -1116,6 +1196,7 @@ mod tests {
             api_key: Some("sk-test".to_string()),
             api_endpoint: None,
             auth: None,
+            api_keys: Vec::new(),
         };
         let json = serde_json::to_string(&config).unwrap();
         assert!(json.contains("\"type\":\"openai\""));
@@ -1129,6 +1210,7 @@ mod tests {
             api_key: Some("sk-test".to_string()),
             api_endpoint: Some("https://custom.openai.com/v1".to_string()),
             auth: None,
+            api_keys: Vec::new(),
         };
         let json = serde_json::to_string(&config).unwrap();
         assert!(json.contains("\"api_endpoint\":\"https://custom.openai.com/v1\""));
@@ -1141,6 +1223,7 @@ mod tests {
             api_endpoint: None,
             access_token: Some("oauth-token".to_string()),
             auth: None,
+            api_keys: Vec::new(),
         };
         let json = serde_json::to_string(&config).unwrap();
         assert!(json.contains("\"type\":\"anthropic\""));
@@ -1148,6 +1231,47 @@ mod tests {
         assert!(json.contains("\"access_token\":\"oauth-token\""));
     }
 
+    #[test]
+    fn test_provider_config_api_key_joins_api_keys_pool() {
+        let config = ProviderConfig::OpenAI {
+            api_key: Some("sk-single".to_string()),
+            api_endpoint: None,
+            auth: None,
+            api_keys: vec![
+                WeightedApiKey::new("sk-pooled", 3),
+                WeightedApiKey::new("sk-single", 2),
+            ],
+        };
+        let keys = config.api_keys();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0].key, "sk-pooled");
+        assert_eq!(keys[1].weight, 2);
+
+        let config = ProviderConfig::Anthropic {
+            api_key: Some("sk-ant-single".to_string()),
+            api_endpoint: None,
+            access_token: None,
+            auth: None,
+            api_keys: vec![WeightedApiKey::new("sk-ant-pooled", 3)],
+        };
+        let keys = config.api_keys();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0].key, "sk-ant-single");
+        assert_eq!(keys[0].weight, 1);
+        assert_eq!(keys[1].key, "sk-ant-pooled");
+    }
+
+    #[test]
+    fn test_provider_config_api_keys_empty_without_pool() {
+        let config = ProviderConfig::OpenAI {
+            api_key: Some("sk-single".to_string()),
+            api_endpoint: None,
+            auth: None,
+            api_keys: Vec::new(),
+        };
+        assert!(config.api_keys().is_empty());
+    }
+
     #[test]
     fn test_provider_config_gemini_serialization() {
         let config = ProviderConfig::Gemini {
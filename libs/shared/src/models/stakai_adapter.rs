@@ -3,6 +3,7 @@
 //! This module provides conversion functions and a wrapper client to use the StakAI SDK
 //! with the CLI's existing LLM types, enabling BYOM (Bring Your Own Model) functionality.
 
+use crate::models::auth::ProviderAuth;
 use crate::models::error::{AgentError, BadRequestErrorMessage};
 use crate::models::llm::{
     GenerationDelta, GenerationDeltaToolUse, LLMChoice, LLMCompletionResponse, LLMInput,
@@ -20,6 +21,9 @@ use stakai::{
     providers::openai::OpenAIConfig as StakaiOpenAIConfig, providers::openrouter::OpenRouterConfig,
     registry::ProviderRegistry,
 };
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 /// Convert CLI LLMMessage to StakAI Message
 pub fn to_stakai_message(msg: &LLMMessage) -> Message {
@@ -47,10 +51,7 @@ pub fn to_stakai_message(msg: &LLMMessage) -> Message {
 fn to_stakai_content_part(part: &LLMMessageTypedContent) -> ContentPart {
     match part {
         LLMMessageTypedContent::Text { text } => ContentPart::text(text),
-        LLMMessageTypedContent::Image { source } => {
-            // Convert base64 image to data URI
-            ContentPart::image(format!("data:{};base64,{}", source.media_type, source.data))
-        }
+        LLMMessageTypedContent::Image { source } => ContentPart::image(source.to_url()),
         LLMMessageTypedContent::ToolCall {
             id,
             name,
@@ -98,32 +99,9 @@ pub fn from_stakai_message(msg: &Message) -> LLMMessage {
 fn from_stakai_content_part(part: &ContentPart) -> LLMMessageTypedContent {
     match part {
         ContentPart::Text { text, .. } => LLMMessageTypedContent::Text { text: text.clone() },
-        ContentPart::Image { url, .. } => {
-            // Parse data URI back to base64
-            let (media_type, data) = if url.starts_with("data:") {
-                let parts: Vec<&str> = url.splitn(2, ',').collect();
-                if parts.len() == 2 {
-                    let media = parts[0]
-                        .strip_prefix("data:")
-                        .unwrap_or("image/png")
-                        .strip_suffix(";base64")
-                        .unwrap_or("image/png");
-                    (media.to_string(), parts[1].to_string())
-                } else {
-                    ("image/png".to_string(), url.clone())
-                }
-            } else {
-                ("image/png".to_string(), url.clone())
-            };
-
-            LLMMessageTypedContent::Image {
-                source: LLMMessageImageSource {
-                    r#type: "base64".to_string(),
-                    media_type,
-                    data,
-                },
-            }
-        }
+        ContentPart::Image { url, .. } => LLMMessageTypedContent::Image {
+            source: LLMMessageImageSource::from_url(url),
+        },
         ContentPart::ToolCall {
             id,
             name,
@@ -417,13 +395,35 @@ pub fn from_stakai_response(response: GenerateResponse, model: &str) -> LLMCompl
 fn resolve_stakai_openai_config(
     provider_config: &ProviderConfig,
 ) -> Result<Option<StakaiOpenAIConfig>, String> {
+    // A provider configured only with `api_keys` authenticates with the pool
+    let auth = provider_config.get_auth().or_else(|| {
+        provider_config
+            .api_keys()
+            .first()
+            .map(|pooled| ProviderAuth::api_key(&pooled.key))
+    });
     let resolved = resolve_openai_runtime(OpenAIBackendResolutionInput::new(
         Some(provider_config.clone()),
-        provider_config.get_auth(),
+        auth,
     ))
     .map_err(|error| format!("Failed to resolve OpenAI runtime config: {}", error))?;
 
-    Ok(resolved.map(|config| config.to_stakai_config()))
+    Ok(resolved.map(|config| {
+        config
+            .to_stakai_config()
+            .with_api_keys(provider_config.api_keys())
+    }))
+}
+
+/// The Anthropic API key, falling back to the first pooled key
+fn anthropic_api_key(provider_config: &ProviderConfig) -> Option<String> {
+    provider_config.api_key().map(str::to_string).or_else(|| {
+        provider_config
+            .api_keys()
+            .into_iter()
+            .next()
+            .map(|pooled| pooled.key)
+    })
 }
 
 /// Build StakAI InferenceConfig from CLI LLMProviderConfig
@@ -447,9 +447,10 @@ pub fn build_inference_config(config: &LLMProviderConfig) -> Result<InferenceCon
                         cfg = cfg.with_base_url(endpoint);
                     }
                     Some(cfg)
-                } else if let Some(key) = provider_config.api_key() {
+                } else if let Some(key) = anthropic_api_key(provider_config) {
                     // API key authentication - uses x-api-key header
-                    let mut cfg = StakaiAnthropicConfig::new(key);
+                    let mut cfg =
+                        StakaiAnthropicConfig::new(key).with_api_keys(provider_config.api_keys());
                     if let Some(endpoint) = api_endpoint {
                         cfg = cfg.with_base_url(endpoint);
                     }
@@ -554,8 +555,9 @@ fn build_provider_registry_direct(config: &LLMProviderConfig) -> Result<Provider
                         cfg = cfg.with_base_url(endpoint);
                     }
                     Some(cfg)
-                } else if let Some(key) = provider_config.api_key() {
-                    let mut cfg = StakaiAnthropicConfig::new(key);
+                } else if let Some(key) = anthropic_api_key(provider_config) {
+                    let mut cfg =
+                        StakaiAnthropicConfig::new(key).with_api_keys(provider_config.api_keys());
                     if let Some(endpoint) = api_endpoint {
                         cfg = cfg.with_base_url(endpoint);
                     }
@@ -586,8 +588,11 @@ fn build_provider_registry_direct(config: &LLMProviderConfig) -> Result<Provider
                 let Some(api_key) = provider_config.api_key() else {
                     continue;
                 };
+                // Non-streaming completions are read as a stream so long
+                // responses don't hit the read timeout before the first byte
                 let mut stakpak_config = StakpakProviderConfig::new(api_key.to_string())
-                    .with_user_agent(format!("Stakpak/{}", env!("CARGO_PKG_VERSION")));
+                    .with_user_agent(format!("Stakpak/{}", env!("CARGO_PKG_VERSION")))
+                    .with_stream_generate(true);
                 if let Some(endpoint) = api_endpoint {
                     stakpak_config = stakpak_config.with_base_url(endpoint.clone());
                 }
@@ -675,6 +680,8 @@ pub fn get_stakai_model_string(model: &Model) -> String {
 #[derive(Clone)]
 pub struct StakAIClient {
     inference: Inference,
+    /// Per-provider permits bounding in-flight requests; excess requests queue
+    concurrency_limits: Arc<HashMap<String, Arc<Semaphore>>>,
 }
 
 impl StakAIClient {
@@ -691,7 +698,11 @@ impl StakAIClient {
                 AgentError::BadRequest(BadRequestErrorMessage::InvalidAgentInput(e.to_string()))
             })?;
 
-        Ok(Self { inference })
+        Ok(Self {
+            inference,
+            concurrency_limits: Arc::default(),
+        }
+        .with_concurrency_limits(&config.max_concurrent_requests))
     }
 
     /// Create a new StakAI client with custom provider registry
@@ -703,11 +714,37 @@ impl StakAIClient {
                 AgentError::BadRequest(BadRequestErrorMessage::InvalidAgentInput(e.to_string()))
             })?;
 
-        Ok(Self { inference })
+        Ok(Self {
+            inference,
+            concurrency_limits: Arc::default(),
+        })
+    }
+
+    /// Bound in-flight requests per provider (key = provider name).
+    ///
+    /// A limit of 0 is treated as 1 so requests are never blocked forever.
+    pub fn with_concurrency_limits(mut self, limits: &HashMap<String, usize>) -> Self {
+        self.concurrency_limits = Arc::new(
+            limits
+                .iter()
+                .map(|(provider, limit)| {
+                    (provider.clone(), Arc::new(Semaphore::new((*limit).max(1))))
+                })
+                .collect(),
+        );
+        self
+    }
+
+    /// Wait for a request slot on the model's provider, if it is limited.
+    async fn acquire_request_permit(&self, model: &Model) -> Option<OwnedSemaphorePermit> {
+        let semaphore = self.concurrency_limits.get(&model.provider)?.clone();
+        // The semaphore is never closed, so acquiring only fails if that changes.
+        semaphore.acquire_owned().await.ok()
     }
 
     /// Non-streaming chat completion
     pub async fn chat(&self, input: LLMInput) -> Result<LLMCompletionResponse, AgentError> {
+        let _permit = self.acquire_request_permit(&input.model).await;
         let messages: Vec<Message> = input.messages.iter().map(to_stakai_message).collect();
 
         let mut options = GenerateOptions::new().max_tokens(input.max_tokens);
@@ -752,6 +789,8 @@ impl StakAIClient {
         &self,
         input: LLMStreamInput,
     ) -> Result<LLMCompletionResponse, AgentError> {
+        // Held until the stream is fully consumed
+        let _permit = self.acquire_request_permit(&input.model).await;
         let messages: Vec<Message> = input.messages.iter().map(to_stakai_message).collect();
 
         let mut options = GenerateOptions::new().max_tokens(input.max_tokens);
@@ -1464,6 +1503,7 @@ mod tests {
             finish_reason: FinishReason::stop(),
             metadata: None,
             warnings: None,
+            model: None,
         };
 
         let llm_response = from_stakai_response(response, "gpt-4");
@@ -1506,6 +1546,7 @@ mod tests {
             finish_reason: FinishReason::tool_calls(),
             metadata: None,
             warnings: None,
+            model: None,
         };
 
         let llm_response = from_stakai_response(response, "claude-3");
@@ -1667,6 +1708,7 @@ mod tests {
                 api_key: Some("sk-test-key".to_string()),
                 api_endpoint: Some("https://api.openai.com/v1".to_string()),
                 auth: None,
+                api_keys: Vec::new(),
             },
         );
 
@@ -1686,6 +1728,7 @@ mod tests {
                 api_endpoint: None,
                 access_token: None,
                 auth: None,
+                api_keys: Vec::new(),
             },
         );
 
@@ -1723,6 +1766,7 @@ mod tests {
                 api_endpoint: None,
                 access_token: None,
                 auth: None,
+                api_keys: Vec::new(),
             },
         );
         config.add_provider(
@@ -1731,6 +1775,7 @@ mod tests {
                 api_key: Some("sk-openai-test".to_string()),
                 api_endpoint: None,
                 auth: None,
+                api_keys: Vec::new(),
             },
         );
         config.add_provider(
@@ -1807,6 +1852,7 @@ mod tests {
                     i64::MAX,
                     "ChatGPT Plus/Pro",
                 )),
+                api_keys: Vec::new(),
             },
         );
 
@@ -1889,4 +1935,86 @@ mod tests {
         assert_eq!(back.description, original.description);
         assert_eq!(back.input_schema, original.input_schema);
     }
+
+    // ==================== Concurrency Limit Tests ====================
+
+    /// Tracks how many requests are in flight at once.
+    struct SlowProvider {
+        in_flight: Arc<std::sync::atomic::AtomicUsize>,
+        peak: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl stakai::provider::Provider for SlowProvider {
+        fn provider_id(&self) -> &str {
+            "slow"
+        }
+
+        fn build_headers(&self, _custom_headers: Option<&Headers>) -> Headers {
+            Headers::new()
+        }
+
+        async fn generate(&self, _request: GenerateRequest) -> stakai::Result<GenerateResponse> {
+            use std::sync::atomic::Ordering;
+
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(GenerateResponse {
+                content: vec![stakai::ResponseContent::Text {
+                    text: "ok".to_string(),
+                }],
+                usage: Usage::default(),
+                finish_reason: FinishReason::stop(),
+                metadata: None,
+                warnings: None,
+                model: None,
+            })
+        }
+
+        async fn stream(
+            &self,
+            _request: GenerateRequest,
+        ) -> stakai::Result<stakai::GenerateStream> {
+            Err(stakai::Error::ProviderError(
+                "streaming is not supported".to_string(),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_bounds_in_flight_requests() {
+        let in_flight = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let peak = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let registry = ProviderRegistry::new().register(
+            "slow",
+            SlowProvider {
+                in_flight: in_flight.clone(),
+                peak: peak.clone(),
+            },
+        );
+        let client = StakAIClient::with_registry(registry)
+            .unwrap()
+            .with_concurrency_limits(&HashMap::from([("slow".to_string(), 2)]));
+
+        let requests = (0..6).map(|_| {
+            client.chat(LLMInput {
+                model: Model::custom("slow-model", "slow"),
+                messages: vec![LLMMessage {
+                    role: "user".to_string(),
+                    content: LLMMessageContent::String("hi".to_string()),
+                }],
+                max_tokens: 16,
+                tools: None,
+                provider_options: None,
+                headers: None,
+            })
+        });
+        let results = futures::future::join_all(requests).await;
+
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(peak.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
 }
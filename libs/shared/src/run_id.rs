@@ -0,0 +1,118 @@
+//! Per-run correlation id.
+//!
+//! A run is one agent session: a one-shot CLI invocation, an interactive
+//! session (a new id is started whenever the TUI starts or switches
+//! sessions), or a server run. The current id is sent as the
+//! `x-stakpak-run-id` header on MCP and provider requests and recorded on
+//! tracing spans, so logs from the CLI, MCP server and providers can be tied
+//! to one run. Set `STAKPAK_RUN_ID` to join a run started by another process.
+//!
+//! Processes that serve several runs at once scope each one with
+//! [`scope_run_id`] instead of replacing the process-wide id.
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::future::Future;
+use std::sync::{LazyLock, RwLock};
+
+/// Header carrying the run id on outgoing requests.
+pub const RUN_ID_HEADER: &str = "x-stakpak-run-id";
+
+/// Environment variable that overrides the first generated run id.
+pub const RUN_ID_ENV: &str = "STAKPAK_RUN_ID";
+
+static PROCESS_RUN_ID: LazyLock<RwLock<String>> = LazyLock::new(|| {
+    RwLock::new(
+        std::env::var(RUN_ID_ENV)
+            .ok()
+            .map(|id| id.trim().to_string())
+            .filter(|id| is_valid_run_id(id))
+            .unwrap_or_else(new_run_id),
+    )
+});
+
+tokio::task_local! {
+    static SCOPED_RUN_ID: String;
+}
+
+/// The correlation id of the current run: the one set by [`scope_run_id`]
+/// for this task, or else the process's current run.
+pub fn run_id() -> String {
+    SCOPED_RUN_ID
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| match PROCESS_RUN_ID.read() {
+            Ok(id) => id.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        })
+}
+
+/// A fresh run id.
+pub fn new_run_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Start a new run for this process, e.g. when an interactive session starts,
+/// and return its id.
+pub fn start_new_run() -> String {
+    let id = new_run_id();
+    match PROCESS_RUN_ID.write() {
+        Ok(mut current) => *current = id.clone(),
+        Err(poisoned) => *poisoned.into_inner() = id.clone(),
+    }
+    id
+}
+
+/// Run `future` as its own run, leaving the process-wide id untouched.
+pub async fn scope_run_id<F: Future>(run_id: String, future: F) -> F::Output {
+    SCOPED_RUN_ID.scope(run_id, future).await
+}
+
+/// Headers carrying the current run id.
+pub fn run_id_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = HeaderValue::from_str(&run_id()) {
+        headers.insert(HeaderName::from_static(RUN_ID_HEADER), value);
+    }
+    headers
+}
+
+fn is_valid_run_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One test: others in this process would race on the process-wide id
+    #[tokio::test]
+    async fn run_id_is_stable_until_a_new_run_starts() {
+        let id = run_id();
+        assert_eq!(run_id(), id);
+        assert_eq!(
+            run_id_headers()
+                .get(RUN_ID_HEADER)
+                .and_then(|v| v.to_str().ok()),
+            Some(id.as_str())
+        );
+
+        let scoped = scope_run_id("server-run-1".to_string(), async { run_id() }).await;
+        assert_eq!(scoped, "server-run-1");
+        assert_eq!(run_id(), id);
+
+        let next = start_new_run();
+        assert_ne!(next, id);
+        assert_eq!(run_id(), next);
+    }
+
+    #[test]
+    fn env_override_must_be_header_safe() {
+        assert!(is_valid_run_id("ci-1234.attempt_2"));
+        assert!(!is_valid_run_id(""));
+        assert!(!is_valid_run_id("has space"));
+        assert!(!is_valid_run_id("line\nbreak"));
+    }
+}
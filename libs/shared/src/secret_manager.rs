@@ -1,8 +1,96 @@
 use crate::local_store::LocalStore;
-use crate::secrets::{redact_password, redact_secrets, restore_secrets};
+use crate::secrets::{
+    RedactionResult, redact_password, redact_secrets, redact_secrets_in_json_with, restore_secrets,
+};
+use serde::{Deserialize, Serialize};
 use serde_json;
 use std::collections::HashMap;
-use tracing::{error, warn};
+use tracing::{debug, error, warn};
+
+/// How a tool's output is redacted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolOutputRedaction {
+    /// Redact detected secrets for both the model and the user
+    #[default]
+    Redact,
+    /// Redact detected secrets for the model but show the user the original output,
+    /// e.g. for a key-generation tool whose output the user needs to copy
+    ShowToUser,
+    /// Treat the whole output as one secret, e.g. a bare generated password that
+    /// secret detection cannot recognize
+    FullyRedact,
+}
+
+impl ToolOutputRedaction {
+    /// Redact `output` for the model, extending `redaction_map`
+    pub fn redact(
+        self,
+        output: &str,
+        redaction_map: &HashMap<String, String>,
+        privacy_mode: bool,
+    ) -> RedactionResult {
+        match self {
+            ToolOutputRedaction::FullyRedact => redact_password(output, output, redaction_map),
+            ToolOutputRedaction::Redact | ToolOutputRedaction::ShowToUser => {
+                redact_secrets(output, None, redaction_map, privacy_mode)
+            }
+        }
+    }
+
+    /// The text to show the user for `model_output`, the output the model saw
+    pub fn user_visible(
+        self,
+        model_output: &str,
+        redaction_map: &HashMap<String, String>,
+    ) -> String {
+        match self {
+            ToolOutputRedaction::ShowToUser => restore_secrets(model_output, redaction_map),
+            ToolOutputRedaction::Redact | ToolOutputRedaction::FullyRedact => {
+                model_output.to_string()
+            }
+        }
+    }
+}
+
+/// Output redaction policy per tool, keyed by tool name without the MCP
+/// server prefix. Tools without an entry use [`ToolOutputRedaction::Redact`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ToolRedactionPolicy {
+    tools: HashMap<String, ToolOutputRedaction>,
+}
+
+impl Default for ToolRedactionPolicy {
+    fn default() -> Self {
+        // generate_password returns a bare password without keyword context,
+        // so secret detection alone would leave it in the model context.
+        Self {
+            tools: HashMap::from([(
+                "generate_password".to_string(),
+                ToolOutputRedaction::FullyRedact,
+            )]),
+        }
+    }
+}
+
+impl ToolRedactionPolicy {
+    /// The default policy with `overrides` applied on top
+    pub fn with_overrides(mut self, overrides: &HashMap<String, ToolOutputRedaction>) -> Self {
+        self.tools.extend(
+            overrides
+                .iter()
+                .map(|(name, redaction)| (name.clone(), *redaction)),
+        );
+        self
+    }
+
+    /// The policy for a tool, accepting names with or without the MCP server prefix
+    pub fn for_tool(&self, tool_name: &str) -> ToolOutputRedaction {
+        let tool_name = crate::utils::strip_tool_name(tool_name);
+        self.tools.get(tool_name).copied().unwrap_or_default()
+    }
+}
 
 /// Handles secret redaction and restoration across different tool types
 #[derive(Clone)]
@@ -86,6 +174,7 @@ impl SecretManager {
         let existing_redaction_map = self.load_session_redaction_map();
         let redaction_result =
             redact_secrets(content, path, &existing_redaction_map, self.privacy_mode);
+        log_redaction_debug(&redaction_result, path);
 
         // Add new redactions to session map
         self.add_to_session_redaction_map(&redaction_result.redaction_map);
@@ -107,4 +196,164 @@ impl SecretManager {
 
         redaction_result.redacted_string
     }
+
+    /// Redact secrets in every string of a JSON value and add to session map
+    pub fn redact_and_store_json(&self, value: &mut serde_json::Value) {
+        if !self.redact_secrets {
+            return;
+        }
+
+        let existing_redaction_map = self.load_session_redaction_map();
+        let redaction_map = redact_secrets_in_json_with(
+            value,
+            &existing_redaction_map,
+            self.privacy_mode,
+            &mut |result| log_redaction_debug(result, None),
+        );
+
+        if redaction_map.len() > existing_redaction_map.len() {
+            self.save_session_redaction_map(&redaction_map);
+        }
+    }
+
+    /// Redact secrets in a tool call's JSON arguments and add to session map.
+    /// Arguments that are not valid JSON are redacted as plain text.
+    pub fn redact_and_store_tool_arguments(&self, arguments: &str) -> String {
+        if !self.redact_secrets {
+            return arguments.to_string();
+        }
+
+        match serde_json::from_str::<serde_json::Value>(arguments) {
+            Ok(mut value) => {
+                self.redact_and_store_json(&mut value);
+                serde_json::to_string(&value).unwrap_or_else(|_| arguments.to_string())
+            }
+            Err(_) => self.redact_and_store_secrets(arguments, None),
+        }
+    }
+
+    /// Redact a tool's output for the model under `redaction` and add new
+    /// redactions to the session map
+    pub fn redact_and_store_tool_output(
+        &self,
+        content: &str,
+        redaction: ToolOutputRedaction,
+    ) -> String {
+        if !self.redact_secrets {
+            return content.to_string();
+        }
+
+        let existing_redaction_map = self.load_session_redaction_map();
+        let redaction_result =
+            redaction.redact(content, &existing_redaction_map, self.privacy_mode);
+        log_redaction_debug(&redaction_result, None);
+
+        self.add_to_session_redaction_map(&redaction_result.redaction_map);
+
+        redaction_result.redacted_string
+    }
+
+    /// The text to show the user for tool output the model saw as `model_output`
+    pub fn user_visible_tool_output(
+        &self,
+        model_output: &str,
+        redaction: ToolOutputRedaction,
+    ) -> String {
+        if redaction != ToolOutputRedaction::ShowToUser {
+            return model_output.to_string();
+        }
+        redaction.user_visible(model_output, &self.load_session_redaction_map())
+    }
+}
+
+/// Logs the rule and spans behind each redaction when redaction debug mode is on
+fn log_redaction_debug(result: &RedactionResult, path: Option<&str>) {
+    let Some(debug_info) = &result.debug_info else {
+        return;
+    };
+    for (key, info) in debug_info {
+        debug!(
+            redaction_key = %key,
+            rule_id = %info.rule_id,
+            spans = ?info.spans,
+            path = path.unwrap_or_default(),
+            "Redacted secret"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_key() -> String {
+        ["abc123def456", "ghi789jkl012", "mno345pqr678"].concat()
+    }
+
+    /// Model- and user-visible text for `output` under `redaction`
+    fn views(output: &str, redaction: ToolOutputRedaction) -> (String, String) {
+        let redacted = redaction.redact(output, &HashMap::new(), false);
+        let user = redaction.user_visible(&redacted.redacted_string, &redacted.redaction_map);
+        (redacted.redacted_string, user)
+    }
+
+    #[test]
+    fn redact_hides_secrets_from_model_and_user() {
+        let output = format!("export API_KEY={}", api_key());
+
+        let (model, user) = views(&output, ToolOutputRedaction::Redact);
+
+        assert!(!model.contains(&api_key()), "{model}");
+        assert!(model.contains("[REDACTED_SECRET:"), "{model}");
+        assert_eq!(user, model);
+    }
+
+    #[test]
+    fn show_to_user_redacts_only_for_the_model() {
+        let output = format!("Generated key:\nexport API_KEY={}", api_key());
+
+        let (model, user) = views(&output, ToolOutputRedaction::ShowToUser);
+
+        assert!(!model.contains(&api_key()), "{model}");
+        assert_eq!(user, output);
+        assert_ne!(user, model);
+    }
+
+    #[test]
+    fn fully_redact_hides_undetectable_output() {
+        let password = "K9x!mP2#nQ8rT4v";
+
+        let (model, user) = views(password, ToolOutputRedaction::FullyRedact);
+        assert!(model.starts_with("[REDACTED_SECRET:password:"), "{model}");
+        assert_eq!(user, model);
+    }
+
+    #[test]
+    fn policy_defaults_and_overrides() {
+        let policy = ToolRedactionPolicy::default();
+        assert_eq!(
+            policy.for_tool("stakpak__generate_password"),
+            ToolOutputRedaction::FullyRedact
+        );
+        assert_eq!(policy.for_tool("run_command"), ToolOutputRedaction::Redact);
+
+        let policy = policy.with_overrides(&HashMap::from([
+            (
+                "generate_ssh_key".to_string(),
+                ToolOutputRedaction::ShowToUser,
+            ),
+            (
+                "generate_password".to_string(),
+                ToolOutputRedaction::ShowToUser,
+            ),
+        ]));
+        assert_eq!(
+            policy.for_tool("stakpak__generate_ssh_key"),
+            ToolOutputRedaction::ShowToUser
+        );
+        assert_eq!(
+            policy.for_tool("generate_password"),
+            ToolOutputRedaction::ShowToUser
+        );
+    }
 }
@@ -1,7 +1,8 @@
 // Secret redaction implementation based on gitleaks (https://github.com/gitleaks/gitleaks)
+use aho_corasick::AhoCorasick;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::sync::LazyLock;
+use std::sync::{LazyLock, PoisonError, RwLock};
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct GitleaksConfig {
@@ -9,6 +10,78 @@ pub struct GitleaksConfig {
     pub title: Option<String>,
     pub allowlist: Option<Allowlist>,
     pub rules: Vec<Rule>,
+    /// Keyword automaton over all rules (not serialized)
+    #[serde(skip)]
+    pub keyword_index: KeywordIndex,
+}
+
+/// A single Aho-Corasick automaton over every rule keyword, so one pass over
+/// the input decides which rules can match before any regex runs
+#[derive(Debug, Clone, Default)]
+pub struct KeywordIndex {
+    matcher: Option<AhoCorasick>,
+    /// Rule indices for each pattern in `matcher`
+    pattern_rules: Vec<Vec<usize>>,
+    /// Rules without keywords, which always run
+    unfiltered_rules: Vec<usize>,
+    rule_count: usize,
+}
+
+impl KeywordIndex {
+    pub fn new(rules: &[Rule]) -> Self {
+        let mut patterns: Vec<String> = Vec::new();
+        let mut pattern_rules: Vec<Vec<usize>> = Vec::new();
+        let mut unfiltered_rules = Vec::new();
+
+        for (rule_index, rule) in rules.iter().enumerate() {
+            if rule.keywords.is_empty() {
+                unfiltered_rules.push(rule_index);
+                continue;
+            }
+            for keyword in &rule.keywords {
+                let keyword = keyword.to_lowercase();
+                match patterns.iter().position(|pattern| *pattern == keyword) {
+                    Some(pattern_index) => pattern_rules[pattern_index].push(rule_index),
+                    None => {
+                        patterns.push(keyword);
+                        pattern_rules.push(vec![rule_index]);
+                    }
+                }
+            }
+        }
+
+        // Keywords are ASCII in practice; a build failure falls back to
+        // running every rule rather than skipping any
+        let matcher = AhoCorasick::builder()
+            .ascii_case_insensitive(true)
+            .build(&patterns)
+            .ok();
+
+        Self {
+            matcher,
+            pattern_rules,
+            unfiltered_rules,
+            rule_count: rules.len(),
+        }
+    }
+
+    /// Which rules should run on `input`, indexed like the rules passed to `new`
+    pub fn rules_to_scan(&self, input: &str) -> Vec<bool> {
+        let Some(matcher) = &self.matcher else {
+            return vec![true; self.rule_count];
+        };
+
+        let mut scan = vec![false; self.rule_count];
+        for &rule_index in &self.unfiltered_rules {
+            scan[rule_index] = true;
+        }
+        for mat in matcher.find_overlapping_iter(input) {
+            for &rule_index in &self.pattern_rules[mat.pattern().as_usize()] {
+                scan[rule_index] = true;
+            }
+        }
+        scan
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -217,6 +290,7 @@ impl RegexCompilable for GitleaksConfig {
             }
         }
         self.rules = compiled_rules;
+        self.keyword_index = KeywordIndex::new(&self.rules);
 
         errors
     }
@@ -230,6 +304,17 @@ pub static GITLEAKS_CONFIG: LazyLock<GitleaksConfig> =
 pub static GITLEAKS_CONFIG_WITH_PRIVACY: LazyLock<GitleaksConfig> =
     LazyLock::new(|| create_gitleaks_config(true));
 
+/// User-registered privacy rules, applied on top of the built-in ones in privacy mode
+static CUSTOM_PRIVACY_RULES: LazyLock<RwLock<Vec<Rule>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// Replaces the registered custom privacy rules. Rules must already be compiled.
+pub fn set_custom_privacy_rules(rules: Vec<Rule>) {
+    *CUSTOM_PRIVACY_RULES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = rules;
+}
+
 /// Creates a gitleaks configuration with optional privacy rules
 fn create_gitleaks_config(include_privacy_rules: bool) -> GitleaksConfig {
     // Load main gitleaks configuration
@@ -364,70 +449,88 @@ pub fn detect_secrets(input: &str, path: Option<&str>, privacy_mode: bool) -> Ve
         &*GITLEAKS_CONFIG
     };
 
-    // Apply each compiled rule from the configuration
-    for rule in &config.rules {
-        // Skip rules that don't have regex patterns (e.g., path-only rules)
-        let regex = match &rule.compiled_regex {
-            Some(regex) => regex,
-            None => continue,
-        };
+    // Apply each compiled rule whose keywords appear in the input
+    let rules_to_scan = config.keyword_index.rules_to_scan(input);
+    for (rule, scan) in config.rules.iter().zip(rules_to_scan) {
+        if scan {
+            detect_with_rule(input, path, rule, &config.allowlist, &mut detected_secrets);
+        }
+    }
 
-        // Pre-filter: Skip rule if none of its keywords are present in the input
-        if !rule.keywords.is_empty() && !contains_any_keyword(input, &rule.keywords) {
-            continue;
+    if privacy_mode {
+        let custom_rules = CUSTOM_PRIVACY_RULES
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        for rule in custom_rules.iter() {
+            detect_with_rule(input, path, rule, &config.allowlist, &mut detected_secrets);
         }
+    }
 
-        // Find all matches for this rule using the pre-compiled regex
-        for mat in regex.find_iter(input) {
-            let match_text = mat.as_str();
-            let start_pos = mat.start();
-            let end_pos = mat.end();
-
-            // Check if this match should be filtered out
-            if should_allow_match(
-                input,
-                path,
-                match_text,
-                start_pos,
-                end_pos,
-                rule,
-                &config.allowlist,
-            ) {
-                continue;
-            }
+    detected_secrets
+}
 
-            // Extract the captured secret value and its position
-            let (secret_value, secret_start, secret_end) =
-                if let Some(captures) = regex.captures_at(input, start_pos) {
-                    // Try to get the first capture group, fallback to full match
-                    if let Some(capture) = captures.get(1) {
-                        // Capture positions are already relative to the full input
-                        (capture.as_str().to_string(), capture.start(), capture.end())
-                    } else {
-                        (match_text.to_string(), start_pos, end_pos)
-                    }
+/// Collects the matches of a single rule into `detected_secrets`
+fn detect_with_rule(
+    input: &str,
+    path: Option<&str>,
+    rule: &Rule,
+    global_allowlist: &Option<Allowlist>,
+    detected_secrets: &mut Vec<DetectedSecret>,
+) {
+    // Skip rules that don't have regex patterns (e.g., path-only rules)
+    let regex = match &rule.compiled_regex {
+        Some(regex) => regex,
+        None => return,
+    };
+
+    // Find all matches for this rule using the pre-compiled regex
+    for mat in regex.find_iter(input) {
+        let match_text = mat.as_str();
+        let start_pos = mat.start();
+        let end_pos = mat.end();
+
+        // Check if this match should be filtered out
+        if should_allow_match(
+            input,
+            path,
+            match_text,
+            start_pos,
+            end_pos,
+            rule,
+            global_allowlist,
+        ) {
+            continue;
+        }
+
+        // Extract the captured secret value and its position
+        let (secret_value, secret_start, secret_end) =
+            if let Some(captures) = regex.captures_at(input, start_pos) {
+                // Try to get the first capture group, fallback to full match
+                if let Some(capture) = captures.get(1) {
+                    // Capture positions are already relative to the full input
+                    (capture.as_str().to_string(), capture.start(), capture.end())
                 } else {
                     (match_text.to_string(), start_pos, end_pos)
-                };
-
-            // Check entropy if specified - apply to the captured secret value, not the full match
-            if let Some(entropy_threshold) = rule.entropy {
-                let calculated_entropy = calculate_entropy(&secret_value);
-                if calculated_entropy < entropy_threshold {
-                    continue;
                 }
-            }
+            } else {
+                (match_text.to_string(), start_pos, end_pos)
+            };
 
-            detected_secrets.push(DetectedSecret {
-                rule_id: rule.id.clone(),
-                value: secret_value,
-                start_pos: secret_start,
-                end_pos: secret_end,
-            });
+        // Check entropy if specified - apply to the captured secret value, not the full match
+        if let Some(entropy_threshold) = rule.entropy {
+            let calculated_entropy = calculate_entropy(&secret_value);
+            if calculated_entropy < entropy_threshold {
+                continue;
+            }
         }
-    }
 
-    detected_secrets
+        detected_secrets.push(DetectedSecret {
+            rule_id: rule.id.clone(),
+            value: secret_value,
+            start_pos: secret_start,
+            end_pos: secret_end,
+        });
+    }
 }
 
 /// Check if a match should be allowed (filtered out) based on allowlists
@@ -1084,4 +1187,32 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_keyword_prescan_skips_rules_on_keyword_free_input() {
+        let config = &*GITLEAKS_CONFIG;
+        let keyword_rules = config
+            .rules
+            .iter()
+            .filter(|rule| !rule.keywords.is_empty())
+            .count();
+        assert!(keyword_rules > 0);
+
+        let scan = config.keyword_index.rules_to_scan("12 + 30 = 42");
+        assert_eq!(scan.len(), config.rules.len());
+        let skipped = scan.iter().filter(|run| !**run).count();
+        assert_eq!(skipped, keyword_rules);
+    }
+
+    #[test]
+    fn test_keyword_prescan_matches_per_rule_keyword_check() {
+        let config = &*GITLEAKS_CONFIG_WITH_PRIVACY;
+        let input = "export GITHUB_TOKEN=ghp_abc; Slack xoxb hook; AWS AKIA key; password=hunter2";
+
+        let scan = config.keyword_index.rules_to_scan(input);
+        for (rule, run) in config.rules.iter().zip(scan) {
+            let expected = rule.keywords.is_empty() || contains_any_keyword(input, &rule.keywords);
+            assert_eq!(run, expected, "rule '{}'", rule.id);
+        }
+    }
 }
@@ -7,9 +7,12 @@
 //! immediately fail with `SQLITE_BUSY` instead of retrying.
 //!
 //! This module centralises the PRAGMA values and application logic so the
-//! three stores stay consistent.
+//! three stores stay consistent, plus a file lock that serializes schema setup
+//! across processes sharing one database file.
 
 use libsql::Connection;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 const SYNCHRONOUS_NORMAL: i64 = 1;
@@ -152,6 +155,40 @@ pub async fn apply_database_pragmas(conn: &Connection) -> Result<(), PragmaError
     Ok(())
 }
 
+/// Exclusive cross-process lock held while a store initializes its schema.
+///
+/// Several `stakpak` processes may open the same database file at once.
+/// SQLite serializes individual writes, but migrations run as a sequence of
+/// statements, so two processes could both see an old schema version and race
+/// to apply the same migration. The lock is released when the guard is dropped.
+#[derive(Debug)]
+pub struct SchemaInitLock {
+    _file: File,
+}
+
+/// Path of the lock file guarding schema init for `db_path` (`<db>.init.lock`).
+pub fn schema_init_lock_path(db_path: &Path) -> PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push(".init.lock");
+    PathBuf::from(path)
+}
+
+/// Block (off the async runtime) until the schema-init lock for `db_path` is held.
+pub async fn acquire_schema_init_lock(db_path: &Path) -> std::io::Result<SchemaInitLock> {
+    let lock_path = schema_init_lock_path(db_path);
+    tokio::task::spawn_blocking(move || {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)?;
+        file.lock()?;
+        Ok(SchemaInitLock { _file: file })
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -271,4 +308,28 @@ mod tests {
             result.err()
         );
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn schema_init_lock_is_exclusive_until_dropped() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let db_path = dir.path().join("locked.db");
+
+        let first = acquire_schema_init_lock(&db_path)
+            .await
+            .expect("first lock");
+        assert!(schema_init_lock_path(&db_path).exists());
+
+        let second_path = db_path.clone();
+        let mut second = tokio::spawn(async move { acquire_schema_init_lock(&second_path).await });
+
+        let waited = tokio::time::timeout(std::time::Duration::from_millis(100), &mut second).await;
+        assert!(waited.is_err(), "second lock must wait for the first");
+
+        drop(first);
+        let second = tokio::time::timeout(std::time::Duration::from_secs(5), second)
+            .await
+            .expect("second lock should be granted after release")
+            .expect("task panicked");
+        assert!(second.is_ok());
+    }
 }
@@ -1,7 +1,8 @@
 use crate::helper::generate_simple_id;
 use crate::remote_connection::{RemoteConnectionInfo, RemoteConnectionManager};
+use crate::secrets::redact_env_values;
 use chrono::{DateTime, Utc};
-use std::{collections::HashMap, process::Stdio, sync::Arc, time::Duration};
+use std::{collections::HashMap, path::PathBuf, process::Stdio, sync::Arc, time::Duration};
 use tokio::{
     io::{AsyncBufReadExt, BufReader},
     process::Command,
@@ -103,6 +104,9 @@ pub struct Task {
     pub timeout: Option<Duration>,
     pub pause_info: Option<PauseInfo>,
     pub child_env: HashMap<String, String>,
+    pub clear_env: bool,
+    pub redactions: Vec<(String, String)>,
+    pub working_dir: Option<PathBuf>,
 }
 
 pub struct TaskEntry {
@@ -144,7 +148,10 @@ impl From<&Task> for TaskInfo {
             status: task.status.clone(),
             command: task.command.clone(),
             description: task.description.clone(),
-            output: task.output.clone(),
+            output: task
+                .output
+                .as_deref()
+                .map(|output| redact_env_values(output, &task.redactions)),
             start_time: task.start_time,
             duration,
             pause_info: task.pause_info.clone(),
@@ -199,6 +206,8 @@ struct TaskExecution {
     remote_connection: Option<RemoteConnectionInfo>,
     task_timeout: Option<Duration>,
     child_env: HashMap<String, String>,
+    clear_env: bool,
+    working_dir: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -207,6 +216,13 @@ pub struct StartTaskOptions {
     pub timeout: Option<Duration>,
     pub remote_connection: Option<RemoteConnectionInfo>,
     pub child_env: HashMap<String, String>,
+    /// Start local tasks with only `child_env` instead of inheriting this
+    /// process's environment.
+    pub clear_env: bool,
+    /// `(name, value)` pairs scrubbed from task output, see [`redact_env_values`].
+    pub redactions: Vec<(String, String)>,
+    /// Directory local tasks run in; defaults to the process's current directory.
+    pub working_dir: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -458,6 +474,9 @@ impl TaskManager {
             timeout,
             remote_connection,
             child_env,
+            clear_env,
+            redactions,
+            working_dir,
         } = options;
 
         let task = Task {
@@ -473,6 +492,9 @@ impl TaskManager {
             timeout,
             pause_info: None,
             child_env: child_env.clone(),
+            clear_env,
+            redactions,
+            working_dir: working_dir.clone(),
         };
 
         let (cancel_tx, cancel_rx) = oneshot::channel();
@@ -488,6 +510,8 @@ impl TaskManager {
             remote_connection,
             task_timeout: timeout,
             child_env,
+            clear_env,
+            working_dir,
         };
 
         let handle = tokio::spawn(Self::execute_task(
@@ -545,6 +569,8 @@ impl TaskManager {
         let remote_connection = entry.task.remote_connection.clone();
         let timeout = entry.task.timeout;
         let child_env = entry.task.child_env.clone();
+        let clear_env = entry.task.clear_env;
+        let working_dir = entry.task.working_dir.clone();
 
         let execution = TaskExecution {
             id: id.clone(),
@@ -552,6 +578,8 @@ impl TaskManager {
             remote_connection: remote_connection.clone(),
             task_timeout: timeout,
             child_env,
+            clear_env,
+            working_dir,
         };
 
         let handle = tokio::spawn(Self::execute_task(
@@ -604,6 +632,8 @@ impl TaskManager {
             remote_connection,
             task_timeout,
             child_env,
+            clear_env,
+            working_dir,
         } = execution;
         let completion = if let Some(remote_info) = remote_connection {
             // Remote execution
@@ -626,6 +656,8 @@ impl TaskManager {
                 process_tx,
                 &task_tx,
                 child_env,
+                clear_env,
+                working_dir,
             )
             .await
         };
@@ -637,6 +669,7 @@ impl TaskManager {
         });
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn execute_local_task(
         id: TaskId,
         command: String,
@@ -645,6 +678,8 @@ impl TaskManager {
         process_tx: oneshot::Sender<u32>,
         task_tx: &mpsc::UnboundedSender<TaskMessage>,
         child_env: HashMap<String, String>,
+        clear_env: bool,
+        working_dir: Option<PathBuf>,
     ) -> TaskCompletion {
         let mut cmd = Command::new("sh");
         cmd.arg("-c")
@@ -652,9 +687,15 @@ impl TaskManager {
             .stdin(Stdio::null())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
+        if clear_env {
+            cmd.env_clear();
+        }
         for (key, value) in child_env {
             cmd.env(key, value);
         }
+        if let Some(working_dir) = working_dir {
+            cmd.current_dir(working_dir);
+        }
         #[cfg(unix)]
         {
             cmd.env("DEBIAN_FRONTEND", "noninteractive")
@@ -939,6 +980,11 @@ impl Drop for TaskManagerHandle {
 }
 
 impl TaskManagerHandle {
+    /// Whether the TaskManager behind this handle is still processing messages.
+    pub fn is_running(&self) -> bool {
+        !self.tx.is_closed()
+    }
+
     pub async fn start_task(
         &self,
         command: String,
@@ -1242,6 +1288,53 @@ mod tests {
             .expect("Failed to shutdown task manager");
     }
 
+    #[tokio::test]
+    async fn task_with_clear_env_gets_only_child_env_and_redacted_output() {
+        let task_manager = TaskManager::new();
+        let handle = task_manager.handle();
+
+        let _manager_handle = tokio::spawn(async move {
+            task_manager.run().await;
+        });
+
+        let mut child_env = HashMap::new();
+        child_env.insert(
+            "STAKPAK_TEST_TASK_REGION".to_string(),
+            "eu-west-1".to_string(),
+        );
+
+        let task_info = handle
+            .start_task(
+                "printf '%s,%s,%s' \"$STAKPAK_TEST_TASK_REGION\" \"${HOME:-missing}\" tok_live_9c2f".to_string(),
+                StartTaskOptions {
+                    child_env,
+                    clear_env: true,
+                    redactions: vec![("DEPLOY_TOKEN".to_string(), "tok_live_9c2f".to_string())],
+                    ..StartTaskOptions::default()
+                },
+            )
+            .await
+            .expect("task should start with a cleared env");
+
+        sleep(Duration::from_millis(500)).await;
+
+        let details = handle
+            .get_task_details(task_info.id.clone())
+            .await
+            .expect("task details request should succeed")
+            .expect("task details should exist");
+
+        assert_eq!(
+            details.output.as_deref(),
+            Some("eu-west-1,missing,[REDACTED:DEPLOY_TOKEN]\n")
+        );
+
+        handle
+            .shutdown()
+            .await
+            .expect("Failed to shutdown task manager");
+    }
+
     #[tokio::test]
     async fn resumed_local_task_reuses_child_env_defaults() {
         let task_manager = TaskManager::new();
@@ -751,6 +751,17 @@ pub fn strip_tool_name(name: &str) -> &str {
     backward_compatibility_mapping(result)
 }
 
+/// Whether `tool_name` is on the `denied_tools` list.
+///
+/// Both sides are compared with [`strip_tool_name`], so a denial of
+/// `run_command` also matches `stakpak__run_command` and vice versa.
+pub fn is_tool_denied(denied_tools: &[String], tool_name: &str) -> bool {
+    let name = strip_tool_name(tool_name);
+    denied_tools
+        .iter()
+        .any(|denied| strip_tool_name(denied.trim()) == name)
+}
+
 /// Map legacy tool names to their current counterparts.
 /// Currently handles mapping "read_rulebook" to "load_skill".
 pub fn backward_compatibility_mapping(name: &str) -> &str {
@@ -906,6 +917,17 @@ temp*
         assert_eq!(strip_tool_name("empty_suffix()"), "empty_suffix");
     }
 
+    #[test]
+    fn test_is_tool_denied_ignores_server_prefix() {
+        let denied = vec!["run_command".to_string(), " stakpak__create ".to_string()];
+        assert!(is_tool_denied(&denied, "stakpak__run_command"));
+        assert!(is_tool_denied(&denied, "run_command"));
+        assert!(is_tool_denied(&denied, "create"));
+        assert!(is_tool_denied(&denied, "stakpak__create"));
+        assert!(!is_tool_denied(&denied, "stakpak__view"));
+        assert!(!is_tool_denied(&[], "run_command"));
+    }
+
     #[test]
     fn test_backward_compatibility_mapping() {
         assert_eq!(
@@ -7,7 +7,8 @@ use stakpak_shared::models::{
 };
 use uuid::Uuid;
 
-use crate::app::{ExistingPlanPrompt, LoadingOperation, SessionInfo};
+use crate::app::{ExistingPlanPrompt, LoadingOperation, SessionInfo, ShortcutsPopupMode};
+use crate::services::auto_approve::ExactToolApproval;
 use crate::services::banner::BannerStyle;
 use crate::services::board_tasks::FetchTasksResult;
 
@@ -23,6 +24,8 @@ pub enum InputEvent {
     StreamToolCallProgress(Vec<ToolCallStreamInfo>),
     StartLoadingOperation(LoadingOperation),
     EndLoadingOperation(LoadingOperation),
+    /// The assistant finished its turn without requesting any tool calls
+    AssistantTurnComplete,
     InputChanged(char),
     ShellMode,
     RunShellCommand(String),
@@ -67,6 +70,8 @@ pub enum InputEvent {
     HandlePaste(String),
     /// Ctrl+V clipboard image paste (non-text, via system clipboard).
     HandleClipboardImagePaste,
+    /// Alt+E: expand collapsed paste placeholders into the full pasted text.
+    ExpandPastedContent,
     InputDelete,
     InputDeleteWord,
     InputCursorStart,
@@ -105,6 +110,10 @@ pub enum InputEvent {
     ApprovalBarNextAction,
     ApprovalBarPrevAction,
     ApprovalBarCollapse,
+    /// Approve the selected action and auto-approve identical calls for the session
+    ApprovalBarAllowExactForSession,
+    /// Exact tool approvals restored from a resumed checkpoint
+    SessionApprovalsLoaded(Vec<ExactToolApproval>),
     // Profile switcher events
     ShowProfileSwitcher,
     ProfilesLoaded(Vec<String>, String),
@@ -121,6 +130,8 @@ pub enum InputEvent {
     // Shortcuts popup events
     ShowShortcuts,
     ShortcutsCancel,
+    /// Switch the shortcuts popup to a tab, e.g. when its header is clicked
+    ShortcutsSelectTab(ShortcutsPopupMode),
 
     // Rulebook switcher events
     ShowRulebookSwitcher,
@@ -151,6 +162,9 @@ pub enum InputEvent {
     ModelSwitcherCancel,
     ModelSwitcherSearchInputChanged(char),
     ModelSwitcherSearchBackspace,
+    /// Switch to the model with this id from `/model <id>`, or restore a resumed
+    /// session's model when the flag is set
+    SwitchModel(String, bool),
     /// Update recent models list (sent after model switch is saved)
     RecentModelsUpdated(Vec<String>),
 
@@ -248,6 +262,9 @@ impl InputEvent {
                 | InputEvent::AssistantMessage(_)
                 | InputEvent::StartLoadingOperation(_)
                 | InputEvent::EndLoadingOperation(_)
+                | InputEvent::AssistantTurnComplete
+                | InputEvent::SessionApprovalsLoaded(_)
+                | InputEvent::SwitchModel(..)
                 | InputEvent::StreamUsage(_)
                 | InputEvent::StreamModel(_)
                 | InputEvent::StreamToolCallProgress(_)
@@ -294,9 +311,13 @@ pub enum OutputEvent {
     ),
     AcceptTool(ToolCall),
     RejectTool(ToolCall, bool),
+    /// Re-issue a past tool call, possibly with edited arguments, as a new call
+    RetryToolCall(ToolCall),
     ListSessions,
     SwitchToSession(String),
     NewSession,
+    /// Regenerate the current session's title from its messages
+    RegenerateSessionTitle,
     SendToolResult(ToolCallResult, bool, Vec<ToolCall>),
     ResumeSession,
     RequestProfileSwitch(String),
@@ -304,7 +325,9 @@ pub enum OutputEvent {
     RequestCurrentRulebooks,
     RequestTotalUsage,
     RequestAvailableModels,
-    SwitchToModel(Model),
+    /// Switch the active model. The flag is set when restoring a resumed
+    /// session's model, which leaves the recent models list alone
+    SwitchToModel(Model, bool),
     /// Save recent models list to config (used when initial model is added)
     SaveRecentModels(Vec<String>),
     /// Plan mode activated via /plan command. Contains optional inline prompt.
@@ -319,4 +342,6 @@ pub enum OutputEvent {
     AskUserResponse(ToolCallResult),
     /// Save auto-approve settings to the profile config (tool names set to Auto)
     SaveAutoApproveToProfile(Vec<String>),
+    /// Tool call allowed verbatim for the rest of the session (recorded in checkpoint metadata)
+    AllowToolCallForSession(ExactToolApproval),
 }
@@ -8,6 +8,8 @@ use crate::services::auto_approve::AutoApproveManager;
 use crate::services::auto_approve::AutoApprovePolicy;
 use crate::services::banner::BannerMessage;
 use crate::services::file_search::FileSearch;
+use crate::services::hit_test::SharedHitTestMap;
+use crate::services::input_history::InputHistory;
 use crate::services::message::Message;
 use crate::services::shell_mode::ShellCommand;
 use crate::services::text_selection::SelectionState;
@@ -235,6 +237,8 @@ pub struct InputState {
     pub attached_images: Vec<AttachedImage>,
     pub pending_path_start: Option<usize>,
     pub interactive_commands: Vec<String>,
+    /// Submitted inputs recalled with Up/Down from an empty input
+    pub history: InputHistory,
 }
 
 impl Default for InputState {
@@ -259,6 +263,7 @@ impl Default for InputState {
             attached_images: Vec::new(),
             pending_path_start: None,
             interactive_commands: Vec::new(),
+            history: InputHistory::default(),
         }
     }
 }
@@ -305,6 +310,8 @@ pub struct MessagesScrollingState {
     /// Maps line ranges to message info for click detection
     /// Format: Vec<(start_line, end_line, message_id, is_user_message, message_text, user_message_index)>
     pub line_to_message_map: Vec<(usize, usize, Uuid, bool, String, usize)>,
+    /// Tool result messages expanded inline to show their full output
+    pub expanded_tool_results: HashSet<Uuid>,
 }
 
 impl Default for MessagesScrollingState {
@@ -332,6 +339,25 @@ impl Default for MessagesScrollingState {
             render_metrics: RenderMetrics::new(),
             last_render_width: 0,
             line_to_message_map: Vec::new(),
+            expanded_tool_results: HashSet::new(),
+        }
+    }
+}
+
+impl MessagesScrollingState {
+    /// Toggle the inline expansion of a tool result message.
+    /// Returns the new expanded state, or `None` if the message is not a tool result.
+    pub fn toggle_tool_result_expanded(&mut self, message_id: Uuid) -> Option<bool> {
+        self.messages
+            .iter()
+            .find(|m| m.id == message_id)?
+            .tool_result_preview()?;
+
+        if self.expanded_tool_results.remove(&message_id) {
+            Some(false)
+        } else {
+            self.expanded_tool_results.insert(message_id);
+            Some(true)
         }
     }
 }
@@ -405,6 +431,10 @@ pub struct QuitIntentState {
 pub struct TerminalUiState {
     pub mouse_capture_enabled: bool,
     pub terminal_size: ratatui::layout::Size,
+    /// Clickable regions of the last drawn frame, read by the input thread.
+    pub hit_test: SharedHitTestMap,
+    /// Whether the model/usage/cost footer is shown.
+    pub show_status_bar: bool,
 }
 
 impl Default for TerminalUiState {
@@ -415,6 +445,8 @@ impl Default for TerminalUiState {
                 width: 0,
                 height: 0,
             },
+            hit_test: SharedHitTestMap::default(),
+            show_status_bar: true,
         }
     }
 }
@@ -524,6 +556,9 @@ pub struct ToolCallState {
     pub max_retry_attempts: usize,
     pub last_user_message_for_retry: Option<String>,
     pub is_retrying: bool,
+    /// Past failed tool call whose arguments are being edited in the input
+    /// field before it is re-issued
+    pub pending_retry: Option<ToolCall>,
     pub subagent_pause_info: HashMap<String, TaskPauseInfo>,
 }
 
@@ -572,6 +607,39 @@ pub struct SessionsState {
     pub account_info: String,
 }
 
+impl SessionsState {
+    /// Replace the session list, most recently updated first, and select the top entry.
+    pub fn set_sessions(&mut self, mut sessions: Vec<SessionInfo>) {
+        sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        self.sessions = sessions;
+        self.session_selected = 0;
+    }
+
+    /// Indices into `sessions` that match `search`, in display order.
+    pub fn filtered_indices(&self, search: &str) -> Vec<usize> {
+        self.sessions
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.matches_search(search))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// The selected session, only if it is visible under `search`.
+    pub fn selected_session(&self, search: &str) -> Option<&SessionInfo> {
+        self.sessions
+            .get(self.session_selected)
+            .filter(|s| s.matches_search(search))
+    }
+
+    /// Move the selection to the first session matching `search`, if any.
+    pub fn select_first_match(&mut self, search: &str) {
+        if let Some(&first) = self.filtered_indices(search).first() {
+            self.session_selected = first;
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct SessionToolCallsState {
     pub session_tool_calls_queue: HashMap<String, ToolCallStatus>,
@@ -609,6 +677,10 @@ pub struct ModelSwitcherState {
     pub available_models: Vec<Model>,
     pub current_model: Option<Model>,
     pub recent_models: Vec<String>,
+    /// Model requested by id before the registry was loaded, and whether it
+    /// restores a resumed session's model; switched to once
+    /// `AvailableModelsLoaded` arrives.
+    pub pending_model_id: Option<(String, bool)>,
 }
 
 #[derive(Default)]
@@ -622,10 +694,37 @@ pub struct CommandPaletteState {
 #[derive(Default)]
 pub struct ShortcutsPanelState {
     pub is_visible: bool,
-    pub scroll: usize,
+    /// Scroll offset of each tab, indexed by [`ShortcutsPopupMode::index`].
+    pub scroll_by_tab: Vec<usize>,
     pub mode: ShortcutsPopupMode,
 }
 
+impl ShortcutsPanelState {
+    /// Scroll offset of the active tab.
+    pub fn scroll(&self) -> usize {
+        self.scroll_by_tab
+            .get(self.mode.index())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Set the scroll offset of the active tab.
+    pub fn set_scroll(&mut self, scroll: usize) {
+        let index = self.mode.index();
+        if self.scroll_by_tab.len() <= index {
+            self.scroll_by_tab.resize(index + 1, 0);
+        }
+        if let Some(slot) = self.scroll_by_tab.get_mut(index) {
+            *slot = scroll;
+        }
+    }
+
+    /// Scroll every tab back to the top, e.g. when their content changes.
+    pub fn reset_scroll(&mut self) {
+        self.scroll_by_tab.clear();
+    }
+}
+
 #[derive(Default)]
 pub struct FileChangesPopupState {
     pub is_visible: bool,
@@ -748,6 +847,20 @@ pub struct SessionInfo {
     pub checkpoints: Vec<String>,
 }
 
+impl SessionInfo {
+    /// Case-insensitive match against the title, id, or `updated_at` date.
+    /// An empty search matches everything.
+    pub fn matches_search(&self, search: &str) -> bool {
+        if search.is_empty() {
+            return true;
+        }
+        let search = search.to_lowercase();
+        self.title.to_lowercase().contains(&search)
+            || self.id.to_lowercase().contains(&search)
+            || self.updated_at.to_lowercase().contains(&search)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum LoadingType {
     Llm,
@@ -775,7 +888,7 @@ pub enum ToolCallStatus {
 }
 
 /// Mode for the unified shortcuts/commands/sessions popup
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ShortcutsPopupMode {
     #[default]
     Commands,
@@ -783,6 +896,17 @@ pub enum ShortcutsPopupMode {
     Sessions,
 }
 
+impl ShortcutsPopupMode {
+    /// Position of the tab in the popup header.
+    pub fn index(self) -> usize {
+        match self {
+            ShortcutsPopupMode::Commands => 0,
+            ShortcutsPopupMode::Shortcuts => 1,
+            ShortcutsPopupMode::Sessions => 2,
+        }
+    }
+}
+
 /// Mode for the model switcher popup filter tabs
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum ModelSwitcherMode {
@@ -930,6 +1054,57 @@ mod tests {
         FunctionCall, ToolCall, ToolCallResultStatus,
     };
 
+    fn session(id: &str, title: &str, updated_at: &str) -> SessionInfo {
+        SessionInfo {
+            title: title.to_string(),
+            id: id.to_string(),
+            updated_at: updated_at.to_string(),
+            checkpoints: vec![format!("{id}-checkpoint")],
+        }
+    }
+
+    fn sessions_state() -> SessionsState {
+        let mut state = SessionsState::default();
+        state.set_sessions(vec![
+            session("a1", "Fix terraform drift", "2025-01-10T09:00:00Z"),
+            session("b2", "Deploy k8s ingress", "2025-03-02T14:30:00Z"),
+            session("c3", "Terraform module cleanup", "2025-02-20T08:15:00Z"),
+        ]);
+        state
+    }
+
+    #[test]
+    fn set_sessions_orders_most_recent_first() {
+        let state = sessions_state();
+        let ids: Vec<&str> = state.sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["b2", "c3", "a1"]);
+        assert_eq!(state.session_selected, 0);
+    }
+
+    #[test]
+    fn session_search_matches_title_and_date() {
+        let state = sessions_state();
+        assert_eq!(state.filtered_indices(""), vec![0, 1, 2]);
+        // Title match is case-insensitive
+        assert_eq!(state.filtered_indices("TERRAFORM"), vec![1, 2]);
+        // updated_at match
+        assert_eq!(state.filtered_indices("2025-03"), vec![0]);
+        assert!(state.filtered_indices("nothing-like-this").is_empty());
+    }
+
+    #[test]
+    fn selected_session_hidden_by_search_is_not_selectable() {
+        let mut state = sessions_state();
+        // "Deploy k8s ingress" is selected but filtered out
+        assert!(state.selected_session("terraform").is_none());
+
+        state.select_first_match("terraform");
+        assert_eq!(
+            state.selected_session("terraform").map(|s| s.id.as_str()),
+            Some("c3")
+        );
+    }
+
     fn tool_result(id: &str) -> ToolCallResult {
         ToolCallResult {
             call: ToolCall {
@@ -946,6 +1121,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn toggle_tool_result_expanded_flips_only_tool_result_previews() {
+        let mut state = MessagesScrollingState::default();
+        let preview = Message::render_collapsed_command_message(tool_result("t1"));
+        let popup = Message::render_full_content_message(tool_result("t1"));
+        let user = Message::user("hi", None);
+        let (preview_id, popup_id, user_id) = (preview.id, popup.id, user.id);
+        state.messages = vec![user, preview, popup];
+
+        assert_eq!(state.toggle_tool_result_expanded(preview_id), Some(true));
+        assert!(state.expanded_tool_results.contains(&preview_id));
+        assert_eq!(state.toggle_tool_result_expanded(preview_id), Some(false));
+        assert!(state.expanded_tool_results.is_empty());
+
+        // Only main-view tool result previews can be expanded
+        assert_eq!(state.toggle_tool_result_expanded(user_id), None);
+        assert_eq!(state.toggle_tool_result_expanded(popup_id), None);
+        assert_eq!(state.toggle_tool_result_expanded(Uuid::new_v4()), None);
+        assert!(state.expanded_tool_results.is_empty());
+    }
+
     #[test]
     fn pending_user_message_merge_combines_all_parts() {
         let mut first = PendingUserMessage::new(
@@ -6,6 +6,7 @@ pub const SCROLL_LINES: usize = 1;
 pub const SCROLL_BUFFER_LINES: usize = 2;
 pub const DROPDOWN_MAX_HEIGHT: usize = 8;
 pub const MAX_PASTE_CHAR_COUNT: usize = 1000;
+pub const MAX_PASTE_LINE_COUNT: usize = 10;
 
 // ========== Error Messages ==========
 pub const EXCEEDED_API_LIMIT_ERROR: &str = "Exceeded API limit";
@@ -13,6 +14,7 @@ pub const EXCEEDED_API_LIMIT_ERROR_MESSAGE: &str = "Exceeded credits plan limit.
 
 // ========== File Paths ==========
 pub const AUTO_APPROVE_CONFIG_PATH: &str = ".stakpak/session/auto_approve.json";
+pub const INPUT_HISTORY_PATH: &str = ".stakpak/session/input_history.json";
 
 pub const SUMMARIZE_PROMPT_BASE: &str = "\
 You are the Stakpak session summarizer. You have full context of the session, including workspace state, current working directory, and file activity.\n\
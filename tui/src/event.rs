@@ -1,7 +1,15 @@
 use crate::app::InputEvent;
+use crate::services::hit_test::{HitTestMap, SharedHitTestMap};
 use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers, MouseEventKind};
 
-pub fn map_crossterm_event_to_input_event(event: Event) -> Option<InputEvent> {
+/// Translate a crossterm event into an [`InputEvent`]. Left clicks on a
+/// region registered in `hit_test` during the last `view()` become that
+/// region's event; clicks outside every region fall back to
+/// `MouseDragStart`, like clicks on the message list.
+pub fn map_crossterm_event_to_input_event(
+    event: Event,
+    hit_test: &SharedHitTestMap,
+) -> Option<InputEvent> {
     match event {
         Event::Key(key) => {
             if key.kind != KeyEventKind::Press {
@@ -54,6 +62,9 @@ pub fn map_crossterm_event_to_input_event(event: Event) -> Option<InputEvent> {
                 KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                     Some(InputEvent::InputCursorEnd)
                 }
+                KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::ALT) => {
+                    Some(InputEvent::ExpandPastedContent)
+                }
                 KeyCode::Char('x') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                     Some(InputEvent::FileChangesRevertFile)
                 }
@@ -173,9 +184,11 @@ pub fn map_crossterm_event_to_input_event(event: Event) -> Option<InputEvent> {
         Event::Mouse(me) => match me.kind {
             MouseEventKind::ScrollUp => Some(InputEvent::ScrollUp),
             MouseEventKind::ScrollDown => Some(InputEvent::ScrollDown),
-            MouseEventKind::Down(crossterm::event::MouseButton::Left) => {
-                Some(InputEvent::MouseDragStart(me.column, me.row))
-            }
+            MouseEventKind::Down(crossterm::event::MouseButton::Left) => Some(
+                HitTestMap::resolve_shared(hit_test, me.column, me.row)
+                    .map(|target| target.event(me.column, me.row))
+                    .unwrap_or(InputEvent::MouseDragStart(me.column, me.row)),
+            ),
             MouseEventKind::Drag(crossterm::event::MouseButton::Left) => {
                 Some(InputEvent::MouseDrag(me.column, me.row))
             }
@@ -190,3 +203,56 @@ pub fn map_crossterm_event_to_input_event(event: Event) -> Option<InputEvent> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::ShortcutsPopupMode;
+    use crate::services::hit_test::ClickTarget;
+    use crossterm::event::{MouseButton, MouseEvent};
+    use ratatui::layout::Rect;
+
+    fn left_click(column: u16, row: u16) -> Event {
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        })
+    }
+
+    #[test]
+    fn click_on_registered_region_maps_to_its_event() {
+        let hit_test = SharedHitTestMap::default();
+        if let Ok(mut map) = hit_test.write() {
+            map.register(
+                Rect::new(10, 2, 12, 1),
+                ClickTarget::ShortcutsTab(ShortcutsPopupMode::Sessions),
+            );
+            map.register(Rect::new(0, 20, 8, 1), ClickTarget::ApprovalAction(2));
+            map.register(Rect::new(0, 30, 40, 10), ClickTarget::Messages);
+            map.block(Rect::new(0, 35, 10, 2));
+        }
+
+        assert!(matches!(
+            map_crossterm_event_to_input_event(left_click(15, 2), &hit_test),
+            Some(InputEvent::ShortcutsSelectTab(ShortcutsPopupMode::Sessions))
+        ));
+        assert!(matches!(
+            map_crossterm_event_to_input_event(left_click(7, 20), &hit_test),
+            Some(InputEvent::ApprovalBarSelectAction(2))
+        ));
+        assert!(matches!(
+            map_crossterm_event_to_input_event(left_click(30, 5), &hit_test),
+            Some(InputEvent::MouseDragStart(30, 5))
+        ));
+        assert!(matches!(
+            map_crossterm_event_to_input_event(left_click(20, 32), &hit_test),
+            Some(InputEvent::MouseDragStart(20, 32))
+        ));
+        assert!(matches!(
+            map_crossterm_event_to_input_event(left_click(5, 36), &hit_test),
+            Some(InputEvent::MouseClick(5, 36))
+        ));
+    }
+}
@@ -3,12 +3,15 @@
 //! Contains the main TUI event loop and related helper functions.
 
 use crate::app::{AppState, AppStateOptions, InputEvent, OutputEvent};
+use crate::constants::INPUT_HISTORY_PATH;
 use crate::services::banner::BannerMessage;
 use crate::services::detect_term::ThemeColors;
 use crate::services::handlers::tool::{
     clear_streaming_tool_results, handle_tool_result, update_session_tool_calls_queue,
 };
+use crate::services::input_history::InputHistory;
 use crate::services::message::Message;
+use crate::services::transcript_log::{self, TranscriptLog};
 use crate::view::view;
 use crossterm::event::{
     DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
@@ -20,6 +23,7 @@ use stakpak_shared::models::integrations::openai::ToolCallResultStatus;
 use stakpak_shared::task_manager::TaskManagerHandle;
 use stakpak_shared::utils::strip_tool_name;
 use std::io;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
@@ -60,8 +64,9 @@ pub async fn run_tui(
     recent_models: Vec<String>,
     banner_message: Option<BannerMessage>,
     task_manager_handle: Arc<TaskManagerHandle>,
+    transcript_log_path: Option<PathBuf>,
 ) -> io::Result<()> {
-    let _guard = TerminalGuard;
+    let _guard = TerminalGuard::new();
 
     crossterm::terminal::enable_raw_mode()?;
 
@@ -106,6 +111,8 @@ pub async fn run_tui(
     });
 
     state.banner_state.message = banner_message;
+    state.input_state.history =
+        InputHistory::load(INPUT_HISTORY_PATH).with_privacy_mode(privacy_mode);
 
     // Mouse capture is always enabled
     state.terminal_ui_state.mouse_capture_enabled = true;
@@ -122,6 +129,10 @@ pub async fn run_tui(
         stakpak_shared::secrets::initialize_gitleaks_config(privacy_mode);
     });
 
+    // Debug transcript; a sink that fails to open or write is dropped
+    let mut transcript_log =
+        transcript_log_path.and_then(|path| TranscriptLog::open(path, privacy_mode).ok());
+
     // Set the current profile name and rulebook config
     state.profile_switcher_state.current_profile_name = current_profile_name;
     state.rulebook_switcher_state.rulebook_config = rulebook_config;
@@ -155,6 +166,7 @@ pub async fn run_tui(
     // Create atomic pause flag for input thread
     let input_paused = Arc::new(AtomicBool::new(false));
     let input_paused_thread = input_paused.clone();
+    let hit_test_thread = state.terminal_ui_state.hit_test.clone();
 
     // Spawn input handling thread
     // This thread reads from crossterm and converts to internal events
@@ -170,7 +182,8 @@ pub async fn run_tui(
             // Use poll with timeout instead of blocking read to allow checking pause flag
             if let Ok(true) = crossterm::event::poll(Duration::from_millis(50))
                 && let Ok(event) = crossterm::event::read()
-                && let Some(event) = crate::event::map_crossterm_event_to_input_event(event)
+                && let Some(event) =
+                    crate::event::map_crossterm_event_to_input_event(event, &hit_test_thread)
                 && internal_tx_thread.blocking_send(event).is_err()
             {
                 break;
@@ -248,6 +261,7 @@ pub async fn run_tui(
                                  ratatui::layout::Constraint::Length(0), // input (hidden when approval bar visible)
                                  ratatui::layout::Constraint::Length(0), // dropdown (hidden when approval bar visible)
                                  ratatui::layout::Constraint::Length(hint_height), // hint
+                                 ratatui::layout::Constraint::Length(u16::from(state.terminal_ui_state.show_status_bar)), // status bar
                              ])
                              .split(term_rect);
                          let message_area_width = outer_chunks[1].width.saturating_sub(2) as usize;
@@ -427,6 +441,7 @@ pub async fn run_tui(
                                 ratatui::layout::Constraint::Length(input_height as u16),
                                 ratatui::layout::Constraint::Length(dropdown_height),
                                 ratatui::layout::Constraint::Length(hint_height),
+                                ratatui::layout::Constraint::Length(u16::from(state.terminal_ui_state.show_status_bar)),
                             ])
                             .split(term_rect);
                         // Subtract 2 for padding (matches view.rs padded_message_area)
@@ -521,6 +536,7 @@ pub async fn run_tui(
                             ratatui::layout::Constraint::Length(input_height as u16),
                             ratatui::layout::Constraint::Length(dropdown_height),
                             ratatui::layout::Constraint::Length(hint_height),
+                            ratatui::layout::Constraint::Length(u16::from(state.terminal_ui_state.show_status_bar)),
                         ])
                         .split(term_rect);
                     // Subtract 2 for padding (matches view.rs padded_message_area)
@@ -685,9 +701,21 @@ pub async fn run_tui(
         }
         state.poll_file_search_results();
         state.update_session_empty_status();
+        if let Some(log) = transcript_log.as_mut() {
+            // Messages change in place while the agent works; log them once settled
+            if !transcript_log::is_settled(&state) {
+                log.mark_busy();
+            } else if log.sync(&state.messages_scrolling_state.messages).is_err() {
+                transcript_log = None;
+            }
+        }
         terminal.draw(|f| view(f, &mut state))?;
     }
 
+    if let Some(log) = transcript_log.as_mut() {
+        let _ = log.flush(&state.messages_scrolling_state.messages);
+    }
+
     let _ = shutdown_tx.send(());
     crossterm::terminal::disable_raw_mode()?;
     execute!(
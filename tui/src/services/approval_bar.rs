@@ -12,10 +12,12 @@
 //!
 //! - All tools start as Approved (✓) by default
 //! - Space toggles between Approved (✓) and Rejected (✗)
+//! - A approves the selected tool and allows identical calls for the rest of the session
 //! - Left/Right arrows navigate between tabs
 //! - Enter confirms all decisions and executes
 
 use crate::services::detect_term::ThemeColors;
+use crate::services::hit_test::{ClickTarget, HitTestMap};
 use ratatui::Frame;
 use ratatui::layout::Rect;
 use ratatui::style::{Modifier, Style};
@@ -309,8 +311,9 @@ impl ApprovalBar {
         (total_height as u16).min(15)
     }
 
-    /// Render the approval bar with wrapping support
-    pub fn render(&self, f: &mut Frame, area: Rect) {
+    /// Render the approval bar with wrapping support, registering each
+    /// button in `hit_test` so it can be clicked.
+    pub fn render(&self, f: &mut Frame, area: Rect, hit_test: &mut HitTestMap) {
         if !self.is_visible() || area.height < 4 {
             return;
         }
@@ -328,6 +331,8 @@ impl ApprovalBar {
         let mut lines: Vec<Vec<Span>> = Vec::new();
         let mut current_line: Vec<Span> = Vec::new();
         let mut current_width = 0;
+        // (line, column offset, width, action index) of every button
+        let mut buttons: Vec<(usize, usize, usize, usize)> = Vec::new();
 
         for (idx, action) in self.actions.iter().enumerate() {
             let is_selected = idx == self.selected_index;
@@ -357,6 +362,7 @@ impl ApprovalBar {
                 current_line.push(Span::raw(" "));
                 current_width += 1;
             }
+            buttons.push((lines.len(), current_width, button_width, idx));
 
             // Style like approval popup tabs
             if is_selected {
@@ -478,6 +484,15 @@ impl ApprovalBar {
                 Paragraph::new(Line::from(line_spans)),
                 Rect::new(area.x, current_y, area.width, 1),
             );
+            // Buttons start after the left border and its padding space
+            for &(_, offset, width, idx) in buttons.iter().filter(|(line, ..)| *line == line_idx) {
+                let x = area.x.saturating_add(2).saturating_add(offset as u16);
+                let width = (width as u16).min(area.right().saturating_sub(x));
+                hit_test.register(
+                    Rect::new(x, current_y, width, 1),
+                    ClickTarget::ApprovalAction(idx),
+                );
+            }
             current_y += 1;
         }
 
@@ -489,6 +504,9 @@ impl ApprovalBar {
                 Span::styled("space", Style::default().fg(ThemeColors::accent())),
                 Span::styled(" toggle", Style::default().fg(ThemeColors::muted())),
                 Span::raw("  "),
+                Span::styled("a", Style::default().fg(ThemeColors::accent())),
+                Span::styled(" always", Style::default().fg(ThemeColors::muted())),
+                Span::raw("  "),
                 Span::styled("←→", Style::default().fg(ThemeColors::accent())),
                 Span::styled(" navigate", Style::default().fg(ThemeColors::muted())),
                 Span::raw("  "),
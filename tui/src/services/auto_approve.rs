@@ -49,6 +49,9 @@ impl Default for AutoApproveConfig {
 
         // Auto-approve tools (always auto-approve):
         tools.insert("view".to_string(), AutoApprovePolicy::Auto);
+        tools.insert("search_files".to_string(), AutoApprovePolicy::Auto);
+        tools.insert("git_status".to_string(), AutoApprovePolicy::Auto);
+        tools.insert("git_diff".to_string(), AutoApprovePolicy::Auto);
         tools.insert("generate_password".to_string(), AutoApprovePolicy::Auto);
         tools.insert("search_docs".to_string(), AutoApprovePolicy::Auto);
         tools.insert("search_memory".to_string(), AutoApprovePolicy::Auto);
@@ -89,6 +92,7 @@ impl Default for AutoApproveConfig {
         // Prompt tools (always require confirmation):
         tools.insert("create".to_string(), AutoApprovePolicy::Prompt);
         tools.insert("str_replace".to_string(), AutoApprovePolicy::Prompt);
+        tools.insert("apply_patch".to_string(), AutoApprovePolicy::Prompt);
         tools.insert("generate_code".to_string(), AutoApprovePolicy::Prompt);
         tools.insert("run_command".to_string(), AutoApprovePolicy::Prompt);
         tools.insert("run_command_task".to_string(), AutoApprovePolicy::Prompt);
@@ -113,11 +117,38 @@ impl Default for AutoApproveConfig {
     }
 }
 
+/// A tool call the user allowed verbatim for the rest of the session.
+///
+/// Only calls with the same tool and identical arguments match; it is never
+/// written to the auto-approve config, but is persisted in checkpoint metadata.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExactToolApproval {
+    pub tool_name: String,
+    pub arguments: serde_json::Value,
+}
+
+impl ExactToolApproval {
+    pub fn from_tool_call(tool_call: &ToolCall) -> Self {
+        let arguments = serde_json::from_str(&tool_call.function.arguments)
+            .unwrap_or_else(|_| serde_json::Value::String(tool_call.function.arguments.clone()));
+
+        ExactToolApproval {
+            tool_name: strip_tool_name(&tool_call.function.name).to_string(),
+            arguments,
+        }
+    }
+
+    pub fn matches(&self, tool_call: &ToolCall) -> bool {
+        *self == Self::from_tool_call(tool_call)
+    }
+}
+
 pub struct AutoApproveManager {
     pub config: AutoApproveConfig,
     original_config: AutoApproveConfig,
     pub config_path: PathBuf,
     input_tx: Option<mpsc::Sender<InputEvent>>,
+    session_exact_approvals: Vec<ExactToolApproval>,
 }
 
 impl AutoApproveManager {
@@ -150,6 +181,7 @@ impl AutoApproveManager {
                     config: config.clone(),
                     config_path,
                     input_tx: input_tx.clone(),
+                    session_exact_approvals: Vec::new(),
                 }
             }
         }
@@ -176,6 +208,7 @@ impl AutoApproveManager {
             config: config.clone(),
             config_path,
             input_tx,
+            session_exact_approvals: Vec::new(),
         })
     }
 
@@ -256,6 +289,15 @@ impl AutoApproveManager {
         let binding = tool_call.function.name.clone();
         let tool_name = strip_tool_name(&binding);
 
+        // Calls the user allowed verbatim earlier in this session
+        if self
+            .session_exact_approvals
+            .iter()
+            .any(|approval| approval.matches(tool_call))
+        {
+            return AutoApprovePolicy::Auto;
+        }
+
         // For shell commands, resolve hierarchical scope keys
         if SHELL_TOOLS.contains(&tool_name)
             && let Some(action) =
@@ -373,6 +415,24 @@ impl AutoApproveManager {
         let _ = output_tx.try_send(crate::app::OutputEvent::SaveAutoApproveToProfile(tools));
     }
 
+    /// Auto-approve calls identical to `tool_call` for the rest of the session.
+    pub fn allow_exact_for_session(&mut self, tool_call: &ToolCall) -> ExactToolApproval {
+        let approval = ExactToolApproval::from_tool_call(tool_call);
+        if !self.session_exact_approvals.contains(&approval) {
+            self.session_exact_approvals.push(approval.clone());
+        }
+        approval
+    }
+
+    pub fn session_exact_approvals(&self) -> &[ExactToolApproval] {
+        &self.session_exact_approvals
+    }
+
+    /// Replace the session's exact approvals, e.g. when resuming a checkpoint.
+    pub fn set_session_exact_approvals(&mut self, approvals: Vec<ExactToolApproval>) {
+        self.session_exact_approvals = approvals;
+    }
+
     /// Snapshot the current config as the new baseline (after persisting).
     pub fn snapshot(&mut self) {
         self.original_config = self.config.clone();
@@ -552,6 +612,10 @@ mod tests {
         // Default config should still have its built-in tools
         assert_eq!(config.tools.get("view"), Some(&AutoApprovePolicy::Auto));
         assert_eq!(config.tools.get("create"), Some(&AutoApprovePolicy::Prompt));
+        assert_eq!(
+            config.tools.get("apply_patch"),
+            Some(&AutoApprovePolicy::Prompt)
+        );
     }
 
     #[test]
@@ -908,6 +972,7 @@ mod tests {
             config: AutoApproveConfig::default(),
             config_path: PathBuf::from(AUTO_APPROVE_CONFIG_PATH),
             input_tx: None,
+            session_exact_approvals: Vec::new(),
         };
         let tc = make_run_command_tool_call("stakpak ak search --tree");
 
@@ -921,6 +986,7 @@ mod tests {
             config: AutoApproveConfig::default(),
             config_path: PathBuf::from(AUTO_APPROVE_CONFIG_PATH),
             input_tx: None,
+            session_exact_approvals: Vec::new(),
         };
         let tc = make_run_command_tool_call("stakpak ak write notes.md");
 
@@ -934,6 +1000,7 @@ mod tests {
             config: AutoApproveConfig::default(),
             config_path: PathBuf::from(AUTO_APPROVE_CONFIG_PATH),
             input_tx: None,
+            session_exact_approvals: Vec::new(),
         };
         let tc = make_run_command_tool_call("stakpak update");
 
@@ -947,12 +1014,55 @@ mod tests {
             config: AutoApproveConfig::default(),
             config_path: PathBuf::from(AUTO_APPROVE_CONFIG_PATH),
             input_tx: None,
+            session_exact_approvals: Vec::new(),
         };
         let tc = make_run_command_tool_call("stakpak browser ak visit example.com");
 
         assert!(!manager.should_auto_approve(&tc));
     }
 
+    #[test]
+    fn exact_session_approval_auto_approves_identical_call_only() {
+        let mut manager = AutoApproveManager {
+            original_config: AutoApproveConfig::default(),
+            config: AutoApproveConfig::default(),
+            config_path: PathBuf::from(AUTO_APPROVE_CONFIG_PATH),
+            input_tx: None,
+            session_exact_approvals: Vec::new(),
+        };
+        let first = make_run_command_tool_call("kubectl rollout restart deploy/api");
+        assert!(!manager.should_auto_approve(&first));
+
+        manager.allow_exact_for_session(&first);
+
+        let mut second =
+            make_tool_call("stakpak__run_command", "kubectl rollout restart deploy/api");
+        second.id = "tc-2".to_string();
+        assert!(manager.should_auto_approve(&second));
+        assert!(manager.get_prompt_tool_calls(&[second]).is_empty());
+
+        let different = make_run_command_tool_call("kubectl rollout restart deploy/web");
+        assert!(!manager.should_auto_approve(&different));
+        assert_eq!(manager.get_prompt_tool_calls(&[different]).len(), 1);
+    }
+
+    #[test]
+    fn exact_session_approvals_can_be_restored_and_respect_disabled() {
+        let mut manager = AutoApproveManager {
+            original_config: AutoApproveConfig::default(),
+            config: AutoApproveConfig::default(),
+            config_path: PathBuf::from(AUTO_APPROVE_CONFIG_PATH),
+            input_tx: None,
+            session_exact_approvals: Vec::new(),
+        };
+        let tc = make_tool_call("create", "ignored");
+        manager.set_session_exact_approvals(vec![ExactToolApproval::from_tool_call(&tc)]);
+        assert!(manager.should_auto_approve(&tc));
+
+        manager.config.enabled = false;
+        assert!(!manager.should_auto_approve(&tc));
+    }
+
     #[test]
     fn resolve_shell_scope_parse_error_fails_closed() {
         let mut rules = HashMap::new();
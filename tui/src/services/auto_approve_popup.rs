@@ -266,6 +266,8 @@ pub fn render_auto_approve_popup(f: &mut Frame, state: &AppState) {
         Span::styled(" tool  ", Style::default().fg(ThemeColors::dark_gray())),
         Span::styled("←/→", Style::default().fg(ThemeColors::cyan())),
         Span::styled(" policy  ", Style::default().fg(ThemeColors::dark_gray())),
+        Span::styled("space", Style::default().fg(ThemeColors::cyan())),
+        Span::styled(" on/off  ", Style::default().fg(ThemeColors::dark_gray())),
         Span::styled("type", Style::default().fg(ThemeColors::cyan())),
         Span::styled(" filter  ", Style::default().fg(ThemeColors::dark_gray())),
         Span::styled("enter", Style::default().fg(ThemeColors::cyan())),
@@ -1739,7 +1739,10 @@ pub fn render_collapsed_command_message(
     let command_args = extract_truncated_command_arguments(&tool_call_result.call, None);
     let title = get_command_type_name(&tool_call_result.call);
 
-    let message = format!("Read {} lines (ctrl+t to expand)", result.lines().count());
+    let message = format!(
+        "Read {} lines (click or ctrl+t to expand)",
+        result.lines().count()
+    );
     let colors = LinesColors {
         dot: ThemeColors::dot_success(),
         title: ThemeColors::title_primary(),
@@ -204,7 +204,7 @@ pub fn commands_to_helper_commands() -> Vec<HelperCommand> {
         },
         HelperCommand {
             command: "/model".into(),
-            description: "Open model switcher to change AI model".into(),
+            description: "Switch AI model: /model [model id]".into(),
             source: CommandSource::BuiltIn,
         },
         HelperCommand {
@@ -232,6 +232,11 @@ pub fn commands_to_helper_commands() -> Vec<HelperCommand> {
             description: "Start a new session".into(),
             source: CommandSource::BuiltIn,
         },
+        HelperCommand {
+            command: "/retitle".into(),
+            description: "Regenerate the session title from the conversation so far".into(),
+            source: CommandSource::BuiltIn,
+        },
         HelperCommand {
             command: "/summarize".into(),
             description: "Summarize the session into summary.md for later resume".into(),
@@ -267,6 +272,11 @@ pub fn commands_to_helper_commands() -> Vec<HelperCommand> {
             description: "Open Tool Approval settings or enable auto-approval for a specific tool: /toggle_auto_approve <tool name>".into(),
             source: CommandSource::BuiltIn,
         },
+        HelperCommand {
+            command: "/status_bar".into(),
+            description: "Toggle the model, token usage and cost status bar".into(),
+            source: CommandSource::BuiltIn,
+        },
         HelperCommand {
             command: "/mouse_capture".into(),
             description: "Toggle mouse capture on/off".into(),
@@ -331,6 +341,21 @@ pub fn execute_command(command_id: CommandId<'_>, ctx: CommandContext) -> Result
             Ok(())
         }
         "/model" => {
+            // `/model <id>` switches directly; a bare `/model` opens the picker
+            let input = ctx.state.input().trim().to_string();
+            if let Some(model_id) = input
+                .strip_prefix("/model ")
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+            {
+                let _ = ctx
+                    .input_tx
+                    .try_send(InputEvent::SwitchModel(model_id.to_string(), false));
+                ctx.state.input_state.text_area.set_text("");
+                ctx.state.input_state.show_helper_dropdown = false;
+                return Ok(());
+            }
+
             // Show model switcher popup
             ctx.state.model_switcher_state.is_visible = true;
             ctx.state.model_switcher_state.is_selected = 0;
@@ -366,6 +391,12 @@ pub fn execute_command(command_id: CommandId<'_>, ctx: CommandContext) -> Result
             new_session(ctx.state, ctx.output_tx);
             Ok(())
         }
+        "/retitle" => {
+            let _ = ctx.output_tx.try_send(OutputEvent::RegenerateSessionTitle);
+            ctx.state.input_state.text_area.set_text("");
+            ctx.state.input_state.show_helper_dropdown = false;
+            Ok(())
+        }
         "/summarize" => {
             let prompt = build_summarize_prompt(ctx.state);
             ctx.state
@@ -443,6 +474,13 @@ pub fn execute_command(command_id: CommandId<'_>, ctx: CommandContext) -> Result
             ctx.state.input_state.show_helper_dropdown = false;
             Ok(())
         }
+        "/status_bar" => {
+            ctx.state.terminal_ui_state.show_status_bar =
+                !ctx.state.terminal_ui_state.show_status_bar;
+            ctx.state.input_state.text_area.set_text("");
+            ctx.state.input_state.show_helper_dropdown = false;
+            Ok(())
+        }
         "/mouse_capture" => {
             ctx.state.input_state.text_area.set_text("");
             ctx.state.input_state.show_helper_dropdown = false;
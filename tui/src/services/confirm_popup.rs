@@ -0,0 +1,278 @@
+//! A yes/no modal with two focusable buttons.
+//!
+//! Left/Right (or Tab) move focus between the buttons, Enter picks the
+//! focused one and Esc cancels. Buttons use the same selected/unselected
+//! styles as popup tabs. The buttons and help line always keep their rows;
+//! a message too tall for the popup scrolls with Up/Down.
+
+use crate::services::detect_term::ThemeColors;
+use crate::services::layout::PopupPosition;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+/// The two buttons of a [`ConfirmPopup`], left to right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConfirmChoice {
+    #[default]
+    Confirm,
+    Cancel,
+}
+
+impl ConfirmChoice {
+    fn toggled(self) -> Self {
+        match self {
+            ConfirmChoice::Confirm => ConfirmChoice::Cancel,
+            ConfirmChoice::Cancel => ConfirmChoice::Confirm,
+        }
+    }
+}
+
+/// What a key press did to the popup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopupEventResult {
+    /// The key is not one the popup handles.
+    Ignored,
+    /// The key changed the popup state; keep it open.
+    Handled,
+    /// The user made a choice; close the popup.
+    Chosen(ConfirmChoice),
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfirmPopup {
+    pub title: String,
+    pub message: String,
+    pub confirm_label: String,
+    pub cancel_label: String,
+    pub focused: ConfirmChoice,
+    /// First message row shown when the message scrolls.
+    pub scroll: u16,
+}
+
+/// Rows under the message: a blank line, the buttons and the help line.
+const FOOTER_HEIGHT: u16 = 3;
+
+impl ConfirmPopup {
+    pub fn new(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into(),
+            confirm_label: "Yes".to_string(),
+            cancel_label: "No".to_string(),
+            focused: ConfirmChoice::default(),
+            scroll: 0,
+        }
+    }
+
+    pub fn with_labels(mut self, confirm: impl Into<String>, cancel: impl Into<String>) -> Self {
+        self.confirm_label = confirm.into();
+        self.cancel_label = cancel.into();
+        self
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> PopupEventResult {
+        match key.code {
+            KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab => {
+                self.focused = self.focused.toggled();
+                PopupEventResult::Handled
+            }
+            KeyCode::Up => {
+                self.scroll = self.scroll.saturating_sub(1);
+                PopupEventResult::Handled
+            }
+            KeyCode::Down => {
+                // Clamped again to the wrapped height when rendering
+                let last_line = self.message.lines().count().saturating_sub(1);
+                self.scroll = self
+                    .scroll
+                    .saturating_add(1)
+                    .min(u16::try_from(last_line).unwrap_or(u16::MAX));
+                PopupEventResult::Handled
+            }
+            KeyCode::Enter => PopupEventResult::Chosen(self.focused),
+            KeyCode::Esc => PopupEventResult::Chosen(ConfirmChoice::Cancel),
+            _ => PopupEventResult::Ignored,
+        }
+    }
+
+    /// The button row, with the focused button highlighted.
+    pub fn buttons(&self) -> Line<'static> {
+        let button = |label: &str, choice: ConfirmChoice| {
+            let style = if self.focused == choice {
+                Style::default()
+                    .fg(ThemeColors::accent())
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(ThemeColors::muted())
+            };
+            Span::styled(format!("[ {label} ]"), style)
+        };
+        Line::from(vec![
+            button(&self.confirm_label, ConfirmChoice::Confirm),
+            Span::raw("   "),
+            button(&self.cancel_label, ConfirmChoice::Cancel),
+        ])
+    }
+}
+
+pub fn render_confirm_popup(f: &mut Frame, popup: &ConfirmPopup) {
+    let message = Text::from(
+        popup
+            .message
+            .lines()
+            .map(|line| Line::styled(line.to_string(), Style::default().fg(ThemeColors::text())))
+            .collect::<Vec<_>>(),
+    );
+
+    // Size the popup for the message plus the footer rows
+    let mut sizing = message.clone();
+    sizing.extend([Line::default(), popup.buttons(), Line::from(HELP)]);
+    let placement = PopupPosition::AutoFit {
+        max_width: 70,
+        max_height: 16,
+        min_width: 40,
+    }
+    .place(&sizing, f.area());
+    f.render_widget(Clear, placement.area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(ThemeColors::cyan()))
+        .title(Span::styled(
+            format!(" {} ", popup.title),
+            Style::default()
+                .fg(ThemeColors::title())
+                .add_modifier(Modifier::BOLD),
+        ));
+    let inner = block.inner(placement.area);
+    f.render_widget(block, placement.area);
+
+    let [message_area, footer_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(FOOTER_HEIGHT)])
+        .areas(inner);
+
+    let message = Paragraph::new(message).wrap(Wrap { trim: false });
+    let message_height =
+        u16::try_from(message.line_count(message_area.width.max(1))).unwrap_or(u16::MAX);
+    let max_scroll = message_height.saturating_sub(message_area.height);
+    f.render_widget(
+        message.scroll((popup.scroll.min(max_scroll), 0)),
+        message_area,
+    );
+
+    let help = if max_scroll > 0 {
+        format!("↑/↓ scroll · {HELP}")
+    } else {
+        HELP.to_string()
+    };
+    let footer = Text::from(vec![
+        Line::default(),
+        popup.buttons().centered(),
+        Line::styled(help, Style::default().fg(ThemeColors::muted())).centered(),
+    ]);
+    f.render_widget(Paragraph::new(footer), footer_area);
+}
+
+const HELP: &str = "←/→ switch · Enter select · Esc cancel";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn arrows_move_focus_between_buttons() {
+        let mut popup = ConfirmPopup::new("Delete", "Delete the session?");
+        assert_eq!(popup.focused, ConfirmChoice::Confirm);
+
+        assert_eq!(
+            popup.handle_key(key(KeyCode::Right)),
+            PopupEventResult::Handled
+        );
+        assert_eq!(popup.focused, ConfirmChoice::Cancel);
+
+        assert_eq!(
+            popup.handle_key(key(KeyCode::Left)),
+            PopupEventResult::Handled
+        );
+        assert_eq!(popup.focused, ConfirmChoice::Confirm);
+
+        assert_eq!(
+            popup.handle_key(key(KeyCode::Char('x'))),
+            PopupEventResult::Ignored
+        );
+    }
+
+    #[test]
+    fn enter_returns_focused_button_and_esc_cancels() {
+        let mut popup = ConfirmPopup::new("Delete", "Delete the session?");
+        assert_eq!(
+            popup.handle_key(key(KeyCode::Enter)),
+            PopupEventResult::Chosen(ConfirmChoice::Confirm)
+        );
+
+        popup.handle_key(key(KeyCode::Right));
+        assert_eq!(
+            popup.handle_key(key(KeyCode::Enter)),
+            PopupEventResult::Chosen(ConfirmChoice::Cancel)
+        );
+
+        popup.handle_key(key(KeyCode::Left));
+        assert_eq!(
+            popup.handle_key(key(KeyCode::Esc)),
+            PopupEventResult::Chosen(ConfirmChoice::Cancel)
+        );
+    }
+
+    #[test]
+    fn focused_button_uses_selected_tab_style() {
+        let popup =
+            ConfirmPopup::new("Delete", "Delete the session?").with_labels("Delete", "Keep");
+        let buttons = popup.buttons();
+
+        assert_eq!(buttons.spans[0].content, "[ Delete ]");
+        assert!(buttons.spans[0].style.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(buttons.spans[2].content, "[ Keep ]");
+        assert!(!buttons.spans[2].style.add_modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn tall_message_scrolls_above_the_buttons() {
+        let message = (1..=30)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut popup = ConfirmPopup::new("Delete", message);
+        for _ in 0..5 {
+            popup.handle_key(key(KeyCode::Down));
+        }
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| render_confirm_popup(f, &popup)).unwrap();
+        let screen = terminal
+            .backend()
+            .buffer()
+            .content()
+            .chunks(80)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert!(screen.contains("line 6"), "{screen}");
+        assert!(!screen.contains("line 5 "), "{screen}");
+        assert!(screen.contains("[ Yes ]"), "{screen}");
+        assert!(screen.contains("↑/↓ scroll"), "{screen}");
+    }
+}
@@ -779,10 +779,94 @@ impl AdaptiveColors {
     }
 }
 
+// ============================================================================
+// 256-color downsampling for terminals without truecolor
+// ============================================================================
+
+/// Channel values of the 6x6x6 color cube in the xterm 256-color palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Nearest xterm 256-color palette index for an RGB color, picking between
+/// the 6x6x6 color cube (16-231) and the grayscale ramp (232-255). The 16
+/// system colors are skipped since terminals let users redefine them.
+pub fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    fn cube_index(value: u8) -> u8 {
+        match value {
+            0..48 => 0,
+            48..115 => 1,
+            _ => (value - 35) / 40,
+        }
+    }
+    fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+        let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).unsigned_abs().pow(2);
+        d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+    }
+
+    let (ri, gi, bi) = (cube_index(r), cube_index(g), cube_index(b));
+    let level = |index: u8| CUBE_LEVELS.get(usize::from(index)).copied().unwrap_or(255);
+    let cube = (level(ri), level(gi), level(bi));
+    let cube_color = 16 + 36 * ri + 6 * gi + bi;
+
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let gray_index = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + 10 * gray_index;
+    let gray_color = 232 + gray_index;
+
+    if distance((gray, gray, gray), (r, g, b)) < distance(cube, (r, g, b)) {
+        gray_color
+    } else {
+        cube_color
+    }
+}
+
+/// Map a truecolor `Color::Rgb` to the nearest 256-color palette entry.
+/// Named and indexed colors pass through unchanged.
+pub fn downsample_color(color: Color) -> Color {
+    match color {
+        Color::Rgb(r, g, b) => Color::Indexed(rgb_to_ansi256(r, g, b)),
+        other => other,
+    }
+}
+
+/// Downsample every RGB foreground and background color in a rendered buffer.
+pub fn downsample_buffer(buffer: &mut ratatui::buffer::Buffer) {
+    for cell in &mut buffer.content {
+        cell.fg = downsample_color(cell.fg);
+        cell.bg = downsample_color(cell.bg);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn rgb_colors_map_to_nearest_256_color_index() {
+        assert_eq!(rgb_to_ansi256(0, 0, 0), 16);
+        assert_eq!(rgb_to_ansi256(255, 255, 255), 231);
+        assert_eq!(rgb_to_ansi256(255, 0, 0), 196);
+        assert_eq!(rgb_to_ansi256(0, 255, 0), 46);
+        assert_eq!(rgb_to_ansi256(0, 0, 255), 21);
+        // Mid gray is an exact grayscale ramp entry.
+        assert_eq!(rgb_to_ansi256(128, 128, 128), 244);
+        // The theme red lands on the closest cube color.
+        assert_eq!(rgb_to_ansi256(239, 100, 97), 203);
+    }
+
+    #[test]
+    fn downsampling_only_touches_rgb_colors() {
+        assert_eq!(downsample_color(Color::Rgb(255, 0, 0)), Color::Indexed(196));
+        assert_eq!(downsample_color(Color::LightRed), Color::LightRed);
+        assert_eq!(downsample_color(Color::Indexed(208)), Color::Indexed(208));
+
+        let mut buffer = ratatui::buffer::Buffer::empty(ratatui::layout::Rect::new(0, 0, 2, 1));
+        buffer.content[0].fg = Color::Rgb(0, 0, 255);
+        buffer.content[1].bg = Color::Rgb(128, 128, 128);
+        downsample_buffer(&mut buffer);
+        assert_eq!(buffer.content[0].fg, Color::Indexed(21));
+        assert_eq!(buffer.content[1].bg, Color::Indexed(244));
+    }
+
     #[test]
     fn test_detect_terminal_default() {
         let info = TerminalInfo::default();
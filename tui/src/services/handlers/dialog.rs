@@ -128,6 +128,10 @@ pub fn handle_esc_event(
             crate::services::text_selection::SelectionState::default();
         return;
     }
+    if state.tool_call_state.pending_retry.is_some() {
+        super::tool::cancel_tool_call_retry(state);
+        return;
+    }
 
     // Common handling for rejection
     state.dialog_approval_state.message_tool_calls = None;
@@ -5,7 +5,7 @@
 use std::path::{Path, PathBuf};
 
 use crate::app::{AppState, AttachedImage, InputEvent, OutputEvent, PendingUserMessage};
-use crate::constants::MAX_PASTE_CHAR_COUNT;
+use crate::constants::{MAX_PASTE_CHAR_COUNT, MAX_PASTE_LINE_COUNT};
 use crate::services::auto_approve::AutoApprovePolicy;
 use crate::services::clipboard_paste::{normalize_pasted_path, paste_image_to_temp_png};
 use crate::services::commands::{CommandContext, execute_command};
@@ -15,6 +15,7 @@ use crate::services::helper_block::{
     push_clear_message, push_error_message, push_styled_message, render_system_message,
 };
 use crate::services::message::{BubbleColors, Message, MessageContent};
+use crate::services::toast::Toast;
 use ratatui::style::{Color, Style};
 use stakpak_shared::models::llm::LLMTokenUsage;
 use tokio::sync::mpsc::Sender;
@@ -77,6 +78,14 @@ pub fn handle_input_submitted_event(
         let _ = input_tx.try_send(InputEvent::ProfileSwitcherSelect);
         return;
     }
+    if state.messages_scrolling_state.show_collapsed_messages {
+        super::popup::handle_toggle_selected_collapsed_message(state);
+        return;
+    }
+    if state.tool_call_state.pending_retry.is_some() {
+        super::tool::submit_tool_call_retry(state, output_tx);
+        return;
+    }
     if state.shortcuts_panel_state.is_visible {
         match state.shortcuts_panel_state.mode {
             crate::app::ShortcutsPopupMode::Commands => {
@@ -87,11 +96,11 @@ pub fn handle_input_submitted_event(
             }
             crate::app::ShortcutsPopupMode::Sessions => {
                 // Select the session and resume it
-                if !state.sessions_state.sessions.is_empty()
-                    && state.sessions_state.session_selected < state.sessions_state.sessions.len()
+                // Only resume a session that is visible under the current search
+                if let Some(selected) = state
+                    .sessions_state
+                    .selected_session(&state.command_palette_state.search)
                 {
-                    let selected =
-                        &state.sessions_state.sessions[state.sessions_state.session_selected];
                     let selected_id = selected.id.to_string();
                     let selected_title = selected.title.clone();
                     let _ = output_tx.try_send(OutputEvent::SwitchToSession(selected_id));
@@ -418,6 +427,9 @@ fn handle_input_submitted(
         return;
     }
 
+    // Long pastes stay collapsed to their placeholder in the history
+    state.input_state.history.push(state.input());
+
     if state.input().trim() == "clear" {
         push_clear_message(state);
         return;
@@ -538,7 +550,9 @@ fn handle_input_submitted(
         let command_word = input.split_once(' ').map(|(cmd, _)| cmd).unwrap_or(&input);
 
         let command_with_args: Option<&str> = match command_word {
-            "/editor" | "/toggle_auto_approve" if input.contains(' ') => Some(command_word),
+            "/editor" | "/toggle_auto_approve" | "/model" if input.contains(' ') => {
+                Some(command_word)
+            }
             _ => None,
         };
 
@@ -746,12 +760,11 @@ fn handle_input_submitted(
         }
 
         // Process any pending pastes first
-        for (placeholder, long_text) in state.input_state.pending_pastes.drain(..) {
-            if final_input.contains(&placeholder) {
-                final_input = final_input.replace(&placeholder, &long_text);
-                state.input_state.text_area.set_text(&final_input);
-                break; // Only process the first matching paste
-            }
+        let pastes = std::mem::take(&mut state.input_state.pending_pastes);
+        let expanded_input = expand_pastes(&final_input, &pastes);
+        if expanded_input != final_input {
+            final_input = expanded_input;
+            state.input_state.text_area.set_text(&final_input);
         }
 
         // Also handle the existing pasted_placeholder system
@@ -961,6 +974,49 @@ pub fn handle_input_submitted_with(
     }
 }
 
+/// Insert `placeholder` in place of a large paste, keeping `text` to be
+/// expanded on demand or on submit. Placeholders are numbered when the same
+/// one is already pending, so each stands for exactly one paste.
+fn collapse_paste(state: &mut AppState, placeholder: String, text: String) {
+    let mut unique = placeholder.clone();
+    let mut n = 1;
+    while state
+        .input_state
+        .pending_pastes
+        .iter()
+        .any(|(existing, _)| *existing == unique)
+    {
+        n += 1;
+        unique = format!("{} #{n}]", placeholder.trim_end_matches(']'));
+    }
+    state.input_state.text_area.insert_element(&unique);
+    // Store the redacted version (with placeholders) for later expansion
+    state.input_state.pending_pastes.push((unique, text));
+    state.toast = Some(Toast::info("Pasted content collapsed · Alt+E to expand"));
+}
+
+/// Replace each paste placeholder in `input` with the text it stands for.
+fn expand_pastes(input: &str, pastes: &[(String, String)]) -> String {
+    pastes
+        .iter()
+        .fold(input.to_string(), |text, (placeholder, long_text)| {
+            text.replacen(placeholder.as_str(), long_text, 1)
+        })
+}
+
+/// Handle Alt+E: replace the collapsed paste placeholders in the input with
+/// the full pasted text so it can be reviewed and edited.
+pub fn handle_expand_pasted_content(state: &mut AppState) {
+    for (placeholder, long_text) in std::mem::take(&mut state.input_state.pending_pastes) {
+        if let Some(start) = state.input_state.text_area.text().find(&placeholder) {
+            state
+                .input_state
+                .text_area
+                .replace_range(start..start + placeholder.len(), &long_text);
+        }
+    }
+}
+
 /// Handle text paste (Event::Paste), including large text and image *paths*.
 pub fn handle_paste(state: &mut AppState, pasted: String) -> bool {
     // Normalize line endings: many terminals convert newlines to \r when pasting,
@@ -985,25 +1041,36 @@ pub fn handle_paste(state: &mut AppState, pasted: String) -> bool {
         }
     }
 
+    insert_pasted_text(state, &normalized_pasted)
+}
+
+/// Insert pasted text into the input, collapsing large pastes into a
+/// placeholder and inserting image paths for conversion.
+pub fn insert_pasted_text(state: &mut AppState, normalized_pasted: &str) -> bool {
     // Detect and redact secrets in pasted content
     // This allows users to paste API keys, passwords, etc. and have them automatically
     // redacted with placeholders that the agent can use
     let redacted_pasted = state
         .configuration_state
         .secret_manager
-        .redact_and_store_secrets(&normalized_pasted, None);
+        .redact_and_store_secrets(normalized_pasted, None);
 
     // Also check if the pasted text itself contains file paths
     // (e.g., user pastes "check this out /path/to/image.png")
     let char_count = redacted_pasted.chars().count();
-    if char_count > MAX_PASTE_CHAR_COUNT {
-        let placeholder = format!("[Pasted Content {char_count} chars]");
-        state.input_state.text_area.insert_element(&placeholder);
-        // Store the redacted version (with placeholders) for later expansion
-        state
-            .input_state
-            .pending_pastes
-            .push((placeholder, redacted_pasted));
+    let line_count = redacted_pasted.lines().count();
+    if line_count > MAX_PASTE_LINE_COUNT {
+        collapse_paste(
+            state,
+            format!("[Pasted {line_count} lines]"),
+            redacted_pasted,
+        );
+    } else if char_count > MAX_PASTE_CHAR_COUNT {
+        collapse_paste(
+            state,
+            format!("[Pasted Content {char_count} chars]"),
+            redacted_pasted,
+        );
     } else if char_count > 1 && handle_paste_image_path(state, redacted_pasted.clone()) {
         // Path inserted - conversion will happen when user types or hits Enter
     } else {
@@ -331,11 +331,11 @@ pub fn handle_set_sessions(state: &mut AppState, sessions: Vec<crate::app::Sessi
     state.shell_popup_state.waiting_for_shell_input = false;
     state.input_state.text_area.set_shell_mode(false);
 
-    state.sessions_state.sessions = sessions;
-    state.sessions_state.session_selected = 0; // Reset selection to first item
+    state.sessions_state.set_sessions(sessions);
     // Open unified popup at Sessions tab instead of separate sessions dialog
     state.shortcuts_panel_state.is_visible = true;
     state.shortcuts_panel_state.mode = crate::app::ShortcutsPopupMode::Sessions;
+    state.shortcuts_panel_state.reset_scroll();
 }
 
 /// Handle set banner message event
@@ -232,6 +232,10 @@ pub fn update(
                 popup::handle_auto_approve_popup_apply(state);
                 return;
             }
+            InputEvent::InputChanged(' ') => {
+                popup::handle_auto_approve_popup_toggle(state);
+                return;
+            }
             InputEvent::InputChanged(c) => {
                 state.tool_approval_popup_state.filter_text.push(c);
                 state.tool_approval_popup_state.apply_filter();
@@ -752,7 +756,7 @@ pub fn update(
     }
 
     // Intercept keys for Approval Bar (inline approval)
-    // Controls: ←→ navigate, Space toggle, Enter confirm all, Esc reject all
+    // Controls: ←→ navigate, Space toggle, A always allow, Enter confirm all, Esc reject all
     // Don't intercept if collapsed messages popup is showing
     if state.dialog_approval_state.approval_bar.is_visible()
         && !state.messages_scrolling_state.show_collapsed_messages
@@ -857,6 +861,11 @@ pub fn update(
                 tool::handle_approval_bar_toggle_selected(state, input_tx);
                 return;
             }
+            InputEvent::InputChanged('a') => {
+                // A: approve selected and allow this exact call for the rest of the session
+                tool::handle_approval_bar_allow_exact_for_session(state, output_tx);
+                return;
+            }
             InputEvent::CursorLeft => {
                 // Left arrow: select previous tab and update message display
                 tool::handle_approval_bar_prev_action(state, input_tx);
@@ -1065,6 +1074,9 @@ pub fn update(
         InputEvent::HandleClipboardImagePaste => {
             input::handle_clipboard_image_paste(state);
         }
+        InputEvent::ExpandPastedContent => {
+            input::handle_expand_pasted_content(state);
+        }
         InputEvent::InputDelete => {
             input::handle_input_delete(state);
         }
@@ -1092,20 +1104,30 @@ pub fn update(
 
         // Navigation handlers
         InputEvent::Up => {
-            navigation::handle_up_navigation(state);
+            navigation::handle_up_navigation(state, true);
         }
         InputEvent::Down => {
-            navigation::handle_down_navigation(state, message_area_height, message_area_width);
+            navigation::handle_down_navigation(
+                state,
+                message_area_height,
+                message_area_width,
+                true,
+            );
         }
         InputEvent::ScrollUp => {
-            navigation::handle_up_navigation(state);
+            navigation::handle_up_navigation(state, false);
             // Extend selection when scrolling during active selection
             if state.message_interaction_state.selection.active {
                 text_selection::handle_scroll_during_selection(state, -1, message_area_height);
             }
         }
         InputEvent::ScrollDown => {
-            navigation::handle_down_navigation(state, message_area_height, message_area_width);
+            navigation::handle_down_navigation(
+                state,
+                message_area_height,
+                message_area_width,
+                false,
+            );
             // Extend selection when scrolling during active selection
             if state.message_interaction_state.selection.active {
                 text_selection::handle_scroll_during_selection(state, 1, message_area_height);
@@ -1160,7 +1182,11 @@ pub fn update(
             tool::handle_stream_tool_call_progress(state, infos);
         }
         InputEvent::RetryLastToolCall => {
-            tool::handle_retry_tool_call(state, input_tx, cancel_tx);
+            if state.messages_scrolling_state.show_collapsed_messages {
+                tool::handle_retry_selected_tool_call(state);
+            } else {
+                tool::handle_retry_tool_call(state, input_tx, cancel_tx);
+            }
         }
         InputEvent::InteractiveStallDetected(command) => {
             tool::handle_interactive_stall_detected(state, command, input_tx);
@@ -1205,6 +1231,15 @@ pub fn update(
         InputEvent::ApprovalBarCollapse => {
             tool::handle_approval_bar_collapse(state);
         }
+        InputEvent::ApprovalBarAllowExactForSession => {
+            tool::handle_approval_bar_allow_exact_for_session(state, output_tx);
+        }
+        InputEvent::SessionApprovalsLoaded(approvals) => {
+            state
+                .configuration_state
+                .auto_approve_manager
+                .set_session_exact_approvals(approvals);
+        }
         // Shell handlers
         InputEvent::RunShellCommand(command) => {
             shell::handle_run_shell_command(state, command, input_tx);
@@ -1315,6 +1350,9 @@ pub fn update(
         InputEvent::ShortcutsCancel => {
             popup::handle_shortcuts_cancel(state);
         }
+        InputEvent::ShortcutsSelectTab(mode) => {
+            popup::handle_shortcuts_select_tab(state, mode);
+        }
         InputEvent::ToggleCollapsedMessages => {
             popup::handle_toggle_collapsed_messages(state, message_area_height, message_area_width);
         }
@@ -1380,6 +1418,9 @@ pub fn update(
         InputEvent::HasUserMessage => {
             message::handle_has_user_message(state);
         }
+        InputEvent::AssistantTurnComplete => {
+            // Only used by the headless frontend
+        }
         InputEvent::StreamUsage(usage) => {
             message::handle_stream_usage(state, usage);
         }
@@ -1465,6 +1506,9 @@ pub fn update(
         InputEvent::GetStatus(account_info) => {
             misc::handle_get_status(state, account_info);
         }
+        InputEvent::SwitchModel(model_id, restore) => {
+            popup::handle_switch_model(state, model_id, restore, output_tx);
+        }
         InputEvent::StreamModel(model) => {
             misc::handle_stream_model(state, model);
         }
@@ -1477,7 +1521,11 @@ pub fn update(
             // so we don't need to call it again to avoid double-counting file changes.
         }
         InputEvent::ApprovalPopupSubmit => {}
-        InputEvent::MouseClick(col, row) | InputEvent::MouseDragStart(col, row) => {
+        InputEvent::MouseClick(_, _) => {
+            // A click on an overlay without its own mouse handling; it must
+            // not reach the banner, side panel or messages under it.
+        }
+        InputEvent::MouseDragStart(col, row) => {
             handle_banner_mouse_click(state, col, row, input_tx, output_tx);
             if state.messages_scrolling_state.show_collapsed_messages {
                 // When collapsed popup is open, route directly to text selection
@@ -1616,12 +1664,14 @@ pub fn update(
 mod tests {
     use super::*;
     use crate::app::{AppStateOptions, LoadingOperation};
-    use crate::services::message::MessageContent;
+    use crate::services::auto_approve::AutoApprovePolicy;
+    use crate::services::message::{Message, MessageContent};
     use ratatui::layout::Size;
     use stakai::Model;
     use stakpak_shared::models::integrations::openai::{
         ContentPart, FunctionCall, ToolCall, ToolCallResult, ToolCallResultStatus,
     };
+    use stakpak_shared::models::llm::LLMTokenUsage;
     use tokio::sync::mpsc;
 
     fn build_state() -> AppState {
@@ -2199,4 +2249,630 @@ mod tests {
             "Down should navigate even when scrolled up"
         );
     }
+
+    fn session(id: &str, title: &str, updated_at: &str) -> crate::app::SessionInfo {
+        crate::app::SessionInfo {
+            title: title.to_string(),
+            id: id.to_string(),
+            updated_at: updated_at.to_string(),
+            checkpoints: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn shortcuts_popup_restores_scroll_per_tab() {
+        let mut state = build_state();
+        popup::handle_show_shortcuts(&mut state);
+        navigation::handle_down_navigation(&mut state, 20, 80, true);
+        navigation::handle_down_navigation(&mut state, 20, 80, true);
+        let shortcuts_scroll = state.shortcuts_panel_state.scroll();
+        assert!(shortcuts_scroll > 0);
+
+        // Shortcuts -> Sessions starts at the top of the other tab.
+        misc::handle_tab(&mut state, 20, 80);
+        assert_eq!(
+            state.shortcuts_panel_state.mode,
+            crate::app::ShortcutsPopupMode::Sessions
+        );
+        assert_eq!(state.shortcuts_panel_state.scroll(), 0);
+        state.shortcuts_panel_state.set_scroll(1);
+
+        // Sessions -> Commands -> Shortcuts restores where we left off.
+        misc::handle_tab(&mut state, 20, 80);
+        misc::handle_tab(&mut state, 20, 80);
+        assert_eq!(
+            state.shortcuts_panel_state.mode,
+            crate::app::ShortcutsPopupMode::Shortcuts
+        );
+        assert_eq!(state.shortcuts_panel_state.scroll(), shortcuts_scroll);
+
+        // Changing the search changes every tab's content.
+        popup::handle_command_palette_search_input_changed(&mut state, 'a');
+        assert_eq!(state.shortcuts_panel_state.scroll(), 0);
+    }
+
+    #[tokio::test]
+    async fn status_bar_reflects_model_and_usage_updates() {
+        let mut state = build_state();
+        misc::handle_stream_model(
+            &mut state,
+            Model::new(
+                "claude-sonnet-4-5",
+                "Claude Sonnet 4.5",
+                "anthropic",
+                true,
+                Some(stakai::ModelCost::new(3.0, 15.0)),
+                stakai::ModelLimit::default(),
+            ),
+        );
+        message::handle_stream_usage(
+            &mut state,
+            LLMTokenUsage {
+                prompt_tokens: 12_000,
+                completion_tokens: 500,
+                total_tokens: 12_500,
+                prompt_tokens_details: None,
+            },
+        );
+        message::handle_total_usage(
+            &mut state,
+            LLMTokenUsage {
+                prompt_tokens: 40_000,
+                completion_tokens: 5_678,
+                total_tokens: 45_678,
+                prompt_tokens_details: None,
+            },
+        );
+
+        assert_eq!(
+            crate::services::status_bar::status_bar_text(&state),
+            "Claude Sonnet 4.5 · ctx 12,000 · 45,678 tokens · $0.21"
+        );
+    }
+
+    #[tokio::test]
+    async fn selecting_filtered_session_emits_switch_event() {
+        let mut state = build_state();
+        misc::handle_set_sessions(
+            &mut state,
+            vec![
+                session("old", "Fix terraform drift", "2025-01-10T09:00:00Z"),
+                session("new", "Deploy k8s ingress", "2025-03-02T14:30:00Z"),
+            ],
+        );
+        assert_eq!(
+            state.shortcuts_panel_state.mode,
+            crate::app::ShortcutsPopupMode::Sessions
+        );
+
+        for c in "terraform".chars() {
+            popup::handle_command_palette_search_input_changed(&mut state, c);
+        }
+
+        let (input_tx, _input_rx) = mpsc::channel(8);
+        let (shell_tx, _shell_rx) = mpsc::channel(8);
+        let (output_tx, mut output_rx) = mpsc::channel(8);
+        input::handle_input_submitted_event(&mut state, 20, &output_tx, &input_tx, &shell_tx, None);
+
+        match output_rx.try_recv() {
+            Ok(OutputEvent::SwitchToSession(id)) => assert_eq!(id, "old"),
+            other => panic!("expected SwitchToSession, got {other:?}"),
+        }
+        assert!(!state.shortcuts_panel_state.is_visible);
+    }
+
+    #[tokio::test]
+    async fn model_command_switches_model_before_next_request() {
+        let mut state = build_state();
+        let (input_tx, mut input_rx) = mpsc::channel(8);
+        let (shell_tx, _shell_rx) = mpsc::channel(8);
+        let (output_tx, mut output_rx) = mpsc::channel(8);
+        let mut outputs = Vec::new();
+
+        state.input_state.text_area.set_text("/model openai/gpt-5");
+        input::handle_input_submitted_event(&mut state, 20, &output_tx, &input_tx, &shell_tx, None);
+        let model_id = match input_rx.try_recv() {
+            Ok(InputEvent::SwitchModel(model_id, false)) => model_id,
+            other => panic!("expected SwitchModel, got {other:?}"),
+        };
+
+        // The registry isn't loaded yet, so the switch waits for it.
+        popup::handle_switch_model(&mut state, model_id, false, &output_tx);
+        popup::handle_available_models_loaded(
+            &mut state,
+            vec![
+                Model::custom("claude-sonnet-4-5", "anthropic"),
+                Model::custom("gpt-5", "openai"),
+            ],
+            &output_tx,
+        );
+        while let Ok(event) = output_rx.try_recv() {
+            outputs.push(event);
+        }
+
+        state.input_state.text_area.set_text("hello");
+        input::handle_input_submitted_event(&mut state, 20, &output_tx, &input_tx, &shell_tx, None);
+        while let Ok(event) = output_rx.try_recv() {
+            outputs.push(event);
+        }
+
+        let position = |matches: fn(&OutputEvent) -> bool| outputs.iter().position(matches);
+        let requested_models =
+            position(|event| matches!(event, OutputEvent::RequestAvailableModels));
+        let switched = position(
+            |event| matches!(event, OutputEvent::SwitchToModel(model, false) if model.id == "gpt-5"),
+        );
+        let user_message = position(|event| matches!(event, OutputEvent::UserMessage(..)));
+        assert!(requested_models.is_some());
+        assert!(switched.is_some());
+        assert!(switched < user_message, "events: {outputs:?}");
+        assert_eq!(
+            state
+                .model_switcher_state
+                .current_model
+                .as_ref()
+                .map(|model| model.id.as_str()),
+            Some("gpt-5")
+        );
+    }
+
+    #[tokio::test]
+    async fn model_command_rejects_unknown_model() {
+        let mut state = build_state();
+        let (output_tx, mut output_rx) = mpsc::channel(8);
+        popup::handle_available_models_loaded(
+            &mut state,
+            vec![Model::custom("gpt-5", "openai")],
+            &output_tx,
+        );
+
+        popup::handle_switch_model(&mut state, "gpt-404".to_string(), false, &output_tx);
+
+        assert!(state.model_switcher_state.current_model.is_none());
+        while let Ok(event) = output_rx.try_recv() {
+            assert!(!matches!(event, OutputEvent::SwitchToModel(..)));
+        }
+    }
+
+    #[tokio::test]
+    async fn restoring_session_model_leaves_recent_models_alone() {
+        let mut state = build_state();
+        let (output_tx, mut output_rx) = mpsc::channel(8);
+        state.model_switcher_state.recent_models = vec!["anthropic/claude-sonnet-4-5".to_string()];
+
+        popup::handle_switch_model(&mut state, "openai/gpt-5".to_string(), true, &output_tx);
+        popup::handle_available_models_loaded(
+            &mut state,
+            vec![
+                Model::custom("claude-sonnet-4-5", "anthropic"),
+                Model::custom("gpt-5", "openai"),
+            ],
+            &output_tx,
+        );
+
+        let mut outputs = Vec::new();
+        while let Ok(event) = output_rx.try_recv() {
+            outputs.push(event);
+        }
+        assert!(
+            outputs
+                .iter()
+                .any(|event| matches!(event, OutputEvent::SwitchToModel(model, true) if model.id == "gpt-5")),
+            "events: {outputs:?}"
+        );
+        assert!(
+            !outputs
+                .iter()
+                .any(|event| matches!(event, OutputEvent::SaveRecentModels(_))),
+            "events: {outputs:?}"
+        );
+        assert_eq!(
+            state.model_switcher_state.recent_models,
+            vec!["anthropic/claude-sonnet-4-5".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn model_command_switches_between_providers_serving_the_same_id() {
+        let mut state = build_state();
+        let (output_tx, mut output_rx) = mpsc::channel(8);
+        popup::handle_available_models_loaded(
+            &mut state,
+            vec![
+                Model::custom("gpt-5", "openai"),
+                Model::custom("gpt-5", "openrouter"),
+            ],
+            &output_tx,
+        );
+        state.model_switcher_state.current_model = Some(Model::custom("gpt-5", "openai"));
+        while output_rx.try_recv().is_ok() {}
+
+        popup::handle_switch_model(
+            &mut state,
+            "openrouter/gpt-5".to_string(),
+            false,
+            &output_tx,
+        );
+
+        assert!(matches!(
+            output_rx.try_recv(),
+            Ok(OutputEvent::SwitchToModel(model, false)) if model.provider == "openrouter"
+        ));
+    }
+
+    #[tokio::test]
+    async fn selecting_with_no_matching_session_emits_nothing() {
+        let mut state = build_state();
+        misc::handle_set_sessions(
+            &mut state,
+            vec![session(
+                "only",
+                "Deploy k8s ingress",
+                "2025-03-02T14:30:00Z",
+            )],
+        );
+        for c in "zzz".chars() {
+            popup::handle_command_palette_search_input_changed(&mut state, c);
+        }
+
+        let (input_tx, _input_rx) = mpsc::channel(8);
+        let (shell_tx, _shell_rx) = mpsc::channel(8);
+        let (output_tx, mut output_rx) = mpsc::channel(8);
+        input::handle_input_submitted_event(&mut state, 20, &output_tx, &input_tx, &shell_tx, None);
+
+        assert!(output_rx.try_recv().is_err());
+    }
+
+    fn long_tool_result(id: &str) -> ToolCallResult {
+        let mut result = make_tool_result(id);
+        result.call.function.name = "search_docs".to_string();
+        result.result = (1..=10)
+            .map(|i| format!("output line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        result
+    }
+
+    /// Push a tool result the way the event loop does: preview in the main view,
+    /// full content in the collapsed popup. Returns the preview message ID.
+    fn push_tool_result(state: &mut AppState, result: ToolCallResult) -> uuid::Uuid {
+        let preview = Message::render_collapsed_command_message(result.clone());
+        let preview_id = preview.id;
+        state.messages_scrolling_state.messages.push(preview);
+        state
+            .messages_scrolling_state
+            .messages
+            .push(Message::render_full_content_message(result));
+        preview_id
+    }
+
+    fn rendered_text(state: &mut AppState) -> String {
+        crate::services::message::get_wrapped_message_lines_cached(state, 100)
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[tokio::test]
+    async fn clicking_tool_result_toggles_full_output() {
+        let mut state = build_state();
+        let preview_id = push_tool_result(&mut state, long_tool_result("t1"));
+
+        // Preview only shows the tail of the output
+        let collapsed = rendered_text(&mut state);
+        assert!(collapsed.contains("output line 10"));
+        assert!(!collapsed.contains("output line 2"));
+
+        let row = state
+            .messages_scrolling_state
+            .line_to_message_map
+            .iter()
+            .find(|(_, _, id, ..)| *id == preview_id)
+            .map(|(start, ..)| *start as u16)
+            .expect("tool result should be clickable");
+        state.message_interaction_state.message_area_y = 0;
+        state.message_interaction_state.message_area_height = 50;
+
+        text_selection::handle_drag_start(&mut state, 4, row);
+        text_selection::handle_drag_end(&mut state, 4, row);
+        assert!(
+            state
+                .messages_scrolling_state
+                .expanded_tool_results
+                .contains(&preview_id)
+        );
+        assert!(rendered_text(&mut state).contains("output line 2"));
+
+        text_selection::handle_drag_start(&mut state, 4, row);
+        text_selection::handle_drag_end(&mut state, 4, row);
+        assert!(
+            state
+                .messages_scrolling_state
+                .expanded_tool_results
+                .is_empty()
+        );
+        assert!(!rendered_text(&mut state).contains("output line 2"));
+
+        // The full result stays on the message while it is collapsed
+        let retained = state
+            .messages_scrolling_state
+            .messages
+            .iter()
+            .find(|m| m.id == preview_id)
+            .and_then(|m| m.tool_result_preview())
+            .map(|r| r.result.clone());
+        assert_eq!(retained, Some(long_tool_result("t1").result));
+    }
+
+    #[tokio::test]
+    async fn enter_in_collapsed_popup_expands_selected_tool_result_inline() {
+        let mut state = build_state();
+        let first_id = push_tool_result(&mut state, long_tool_result("t1"));
+        let second_id = push_tool_result(&mut state, long_tool_result("t2"));
+
+        popup::handle_toggle_collapsed_messages(&mut state, 20, 100);
+        assert!(state.messages_scrolling_state.show_collapsed_messages);
+        // Opening the popup selects the most recent entry
+        assert_eq!(
+            state.messages_scrolling_state.collapsed_messages_selected,
+            1
+        );
+
+        let (input_tx, _input_rx) = mpsc::channel(8);
+        let (shell_tx, _shell_rx) = mpsc::channel(8);
+        let (output_tx, mut output_rx) = mpsc::channel(8);
+        input::handle_input_submitted_event(&mut state, 20, &output_tx, &input_tx, &shell_tx, None);
+
+        assert!(!state.messages_scrolling_state.show_collapsed_messages);
+        let expanded = &state.messages_scrolling_state.expanded_tool_results;
+        assert!(expanded.contains(&second_id));
+        assert!(!expanded.contains(&first_id));
+        assert!(output_rx.try_recv().is_err());
+    }
+
+    fn failed_tool_result(id: &str, path: &str) -> ToolCallResult {
+        let mut result = make_tool_result(id);
+        result.call.function.name = "view".to_string();
+        result.call.function.arguments = format!(r#"{{"path":"{path}"}}"#);
+        result.status = ToolCallResultStatus::Error;
+        result
+    }
+
+    fn retry_selected(state: &mut AppState) {
+        let (input_tx, _input_rx) = mpsc::channel(8);
+        let (output_tx, _output_rx) = mpsc::channel(8);
+        let (shell_tx, _shell_rx) = mpsc::channel(8);
+        update(
+            state,
+            InputEvent::RetryLastToolCall,
+            20,
+            100,
+            &input_tx,
+            &output_tx,
+            None,
+            &shell_tx,
+            Size::new(100, 40),
+        );
+    }
+
+    #[tokio::test]
+    async fn retry_in_collapsed_popup_targets_selected_failed_call() {
+        let mut state = build_state();
+        push_tool_result(&mut state, failed_tool_result("t1", "/etc/first"));
+        push_tool_result(&mut state, failed_tool_result("t2", "/etc/second"));
+        push_tool_result(&mut state, make_tool_result("t3"));
+
+        // Opening the popup selects the latest result, which succeeded
+        popup::handle_toggle_collapsed_messages(&mut state, 20, 100);
+        retry_selected(&mut state);
+        assert!(state.tool_call_state.pending_retry.is_none());
+        assert!(state.messages_scrolling_state.show_collapsed_messages);
+
+        state.messages_scrolling_state.collapsed_messages_selected = 0;
+        retry_selected(&mut state);
+
+        let pending = state.tool_call_state.pending_retry.as_ref();
+        assert_eq!(pending.map(|call| call.id.as_str()), Some("t1"));
+        assert!(state.input().contains("/etc/first"));
+        assert!(!state.messages_scrolling_state.show_collapsed_messages);
+    }
+
+    #[tokio::test]
+    async fn retry_reissues_call_with_edited_args() {
+        let mut state = build_state();
+        push_tool_result(&mut state, failed_tool_result("t1", "/etc/first"));
+        push_tool_result(&mut state, make_tool_result("t2"));
+        popup::handle_toggle_collapsed_messages(&mut state, 20, 100);
+        state.messages_scrolling_state.collapsed_messages_selected = 0;
+        retry_selected(&mut state);
+
+        let (input_tx, _input_rx) = mpsc::channel(8);
+        let (shell_tx, _shell_rx) = mpsc::channel(8);
+        let (output_tx, mut output_rx) = mpsc::channel(8);
+
+        // Arguments that are not JSON keep the retry open for another edit
+        state.input_state.text_area.set_text("{\"path\": ");
+        input::handle_input_submitted_event(&mut state, 20, &output_tx, &input_tx, &shell_tx, None);
+        assert!(output_rx.try_recv().is_err());
+        assert!(state.tool_call_state.pending_retry.is_some());
+
+        state
+            .input_state
+            .text_area
+            .set_text("{\"path\": \"/etc/fixed\"}");
+        input::handle_input_submitted_event(&mut state, 20, &output_tx, &input_tx, &shell_tx, None);
+
+        match output_rx.try_recv() {
+            Ok(OutputEvent::RetryToolCall(call)) => {
+                assert_eq!(call.function.name, "view");
+                assert_eq!(call.function.arguments, r#"{"path":"/etc/fixed"}"#);
+                assert_ne!(call.id, "t1");
+            }
+            other => panic!("unexpected output event: {:?}", other),
+        }
+        assert!(state.tool_call_state.pending_retry.is_none());
+        assert!(state.input().is_empty());
+    }
+
+    #[tokio::test]
+    async fn up_and_down_recall_submitted_inputs_from_empty_input() {
+        let mut state = build_state();
+        let (input_tx, _input_rx) = mpsc::channel(32);
+        let (output_tx, _output_rx) = mpsc::channel(32);
+        let (shell_tx, _shell_rx) = mpsc::channel(8);
+        for text in ["first prompt", "second prompt"] {
+            state.input_state.text_area.set_text(text);
+            input::handle_input_submitted_event(
+                &mut state, 20, &output_tx, &input_tx, &shell_tx, None,
+            );
+        }
+        assert!(state.input().is_empty());
+
+        let press = |state: &mut AppState, event: InputEvent| {
+            update(
+                state,
+                event,
+                20,
+                80,
+                &input_tx,
+                &output_tx,
+                None,
+                &shell_tx,
+                Size::new(80, 24),
+            );
+            state.input().to_string()
+        };
+        assert_eq!(press(&mut state, InputEvent::Up), "second prompt");
+        assert_eq!(press(&mut state, InputEvent::Up), "first prompt");
+        // The scroll wheel keeps scrolling messages
+        assert_eq!(press(&mut state, InputEvent::ScrollUp), "first prompt");
+        assert_eq!(press(&mut state, InputEvent::Down), "second prompt");
+        assert_eq!(press(&mut state, InputEvent::Down), "");
+    }
+
+    fn rendered_input(state: &AppState) -> String {
+        let area = ratatui::layout::Rect::new(0, 0, 60, 4);
+        let mut buf = ratatui::buffer::Buffer::empty(area);
+        ratatui::widgets::WidgetRef::render_ref(&&state.input_state.text_area, area, &mut buf);
+        (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn numbered_lines(prefix: &str, count: usize) -> String {
+        (1..=count)
+            .map(|n| format!("{prefix} line {n}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[tokio::test]
+    async fn multi_line_paste_is_collapsed_and_submitted_in_full() {
+        let mut state = build_state();
+        let (input_tx, _input_rx) = mpsc::channel(32);
+        let (output_tx, mut output_rx) = mpsc::channel(32);
+        let (shell_tx, _shell_rx) = mpsc::channel(8);
+        let first = numbered_lines("first", 30);
+        let second = numbered_lines("second", 30);
+
+        input::insert_pasted_text(&mut state, &first);
+        state.input_state.text_area.insert_str(" and ");
+        input::insert_pasted_text(&mut state, &second);
+
+        let rendered = rendered_input(&state);
+        assert!(rendered.contains("[Pasted 30 lines] and [Pasted 30 lines #2]"));
+        assert!(!rendered.contains("first line 1"));
+
+        input::handle_input_submitted_event(&mut state, 20, &output_tx, &input_tx, &shell_tx, None);
+        match output_rx.try_recv() {
+            Ok(OutputEvent::UserMessage(text, ..)) => {
+                assert_eq!(text, format!("{first} and {second}"));
+            }
+            other => panic!("unexpected output event: {:?}", other),
+        }
+        assert!(state.input_state.pending_pastes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn alt_e_expands_collapsed_paste_in_place() {
+        let mut state = build_state();
+        let pasted = numbered_lines("log", 12);
+        state.input_state.text_area.insert_str("explain: ");
+        input::insert_pasted_text(&mut state, &pasted);
+        assert_eq!(state.input(), "explain: [Pasted 12 lines]");
+
+        input::handle_expand_pasted_content(&mut state);
+        assert_eq!(state.input(), format!("explain: {pasted}"));
+        assert!(state.input_state.pending_pastes.is_empty());
+
+        // Short pastes are inserted as-is
+        input::insert_pasted_text(&mut state, "\none\ntwo");
+        assert!(state.input().ends_with("log line 12\none\ntwo"));
+    }
+
+    #[tokio::test]
+    async fn toggling_auto_approve_entry_changes_whether_tool_auto_approves() {
+        let mut state = build_state();
+        let (input_tx, _input_rx) = mpsc::channel(32);
+        let (output_tx, _output_rx) = mpsc::channel(32);
+        let (shell_tx, _shell_rx) = mpsc::channel(8);
+        let manager = &mut state.configuration_state.auto_approve_manager;
+        manager.config.enabled = true;
+        let _ = manager.update_tool_policy("custom_deploy_tool", AutoApprovePolicy::Auto);
+        let mut call = make_tool_result("t1").call;
+        call.function.name = "custom_deploy_tool".to_string();
+
+        let press = |state: &mut AppState, event: InputEvent| {
+            update(
+                state,
+                event,
+                20,
+                80,
+                &input_tx,
+                &output_tx,
+                None,
+                &shell_tx,
+                Size::new(80, 24),
+            );
+        };
+        let toggle_entry = |state: &mut AppState| {
+            press(state, InputEvent::ShowAutoApprovePopup);
+            for c in "custom_deploy".chars() {
+                press(state, InputEvent::InputChanged(c));
+            }
+            press(state, InputEvent::InputChanged(' '));
+            press(state, InputEvent::InputSubmitted);
+        };
+
+        toggle_entry(&mut state);
+        assert!(!state.tool_approval_popup_state.is_visible);
+        let manager = &state.configuration_state.auto_approve_manager;
+        assert!(!manager.should_auto_approve(&call));
+        assert_eq!(
+            manager
+                .get_prompt_tool_calls(std::slice::from_ref(&call))
+                .len(),
+            1
+        );
+
+        toggle_entry(&mut state);
+        let manager = &state.configuration_state.auto_approve_manager;
+        assert!(manager.should_auto_approve(&call));
+        assert!(
+            manager
+                .get_prompt_tool_calls(std::slice::from_ref(&call))
+                .is_empty()
+        );
+    }
 }
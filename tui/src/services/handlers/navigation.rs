@@ -109,8 +109,10 @@ pub fn handle_dropdown_down(state: &mut AppState) {
     }
 }
 
-/// Handles upward navigation with approval popup check
-pub fn handle_up_navigation(state: &mut AppState) {
+/// Handles upward navigation with approval popup check. With
+/// `recall_history` (the Up key, not the scroll wheel), Up in the message
+/// view recalls the previous input while the input field is empty.
+pub fn handle_up_navigation(state: &mut AppState, recall_history: bool) {
     if state.profile_switcher_state.show_profile_switcher {
         if state.profile_switcher_state.selected_index > 0 {
             state.profile_switcher_state.selected_index -= 1;
@@ -138,25 +140,17 @@ pub fn handle_up_navigation(state: &mut AppState) {
             }
             crate::app::ShortcutsPopupMode::Shortcuts => {
                 // Scroll shortcuts content
-                state.shortcuts_panel_state.scroll = state
+                let scroll = state
                     .shortcuts_panel_state
-                    .scroll
+                    .scroll()
                     .saturating_sub(SCROLL_LINES);
+                state.shortcuts_panel_state.set_scroll(scroll);
             }
             crate::app::ShortcutsPopupMode::Sessions => {
                 // Navigate filtered sessions list
-                let search_lower = state.command_palette_state.search.to_lowercase();
-                let filtered_indices: Vec<usize> = state
+                let filtered_indices = state
                     .sessions_state
-                    .sessions
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, s)| {
-                        state.command_palette_state.search.is_empty()
-                            || s.title.to_lowercase().contains(&search_lower)
-                    })
-                    .map(|(i, _)| i)
-                    .collect();
+                    .filtered_indices(&state.command_palette_state.search);
 
                 if !filtered_indices.is_empty() {
                     // Find current position in filtered list
@@ -203,16 +197,27 @@ pub fn handle_up_navigation(state: &mut AppState) {
             // Wrap to the last option
             state.dialog_approval_state.dialog_selected = 2;
         }
+    } else if recall_history
+        && !state.messages_scrolling_state.show_collapsed_messages
+        && let Some(entry) = state
+            .input_state
+            .history
+            .previous(state.input_state.text_area.text())
+            .map(str::to_string)
+    {
+        recall_input(state, &entry);
     } else {
         handle_scroll_up(state);
     }
 }
 
-/// Handles downward navigation with approval popup check
+/// Handles downward navigation with approval popup check. With
+/// `recall_history`, Down walks back towards the newest recalled input.
 pub fn handle_down_navigation(
     state: &mut AppState,
     message_area_height: usize,
     message_area_width: usize,
+    recall_history: bool,
 ) {
     if state.profile_switcher_state.show_profile_switcher {
         if state.profile_switcher_state.selected_index
@@ -244,25 +249,17 @@ pub fn handle_down_navigation(
             }
             crate::app::ShortcutsPopupMode::Shortcuts => {
                 // Scroll shortcuts content
-                state.shortcuts_panel_state.scroll = state
+                let scroll = state
                     .shortcuts_panel_state
-                    .scroll
+                    .scroll()
                     .saturating_add(SCROLL_LINES);
+                state.shortcuts_panel_state.set_scroll(scroll);
             }
             crate::app::ShortcutsPopupMode::Sessions => {
                 // Navigate filtered sessions list
-                let search_lower = state.command_palette_state.search.to_lowercase();
-                let filtered_indices: Vec<usize> = state
+                let filtered_indices = state
                     .sessions_state
-                    .sessions
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, s)| {
-                        state.command_palette_state.search.is_empty()
-                            || s.title.to_lowercase().contains(&search_lower)
-                    })
-                    .map(|(i, _)| i)
-                    .collect();
+                    .filtered_indices(&state.command_palette_state.search);
 
                 if !filtered_indices.is_empty() {
                     // Find current position in filtered list
@@ -311,11 +308,26 @@ pub fn handle_down_navigation(
             // Wrap to the first option
             state.dialog_approval_state.dialog_selected = 0;
         }
+    } else if recall_history
+        && !state.messages_scrolling_state.show_collapsed_messages
+        && let Some(entry) = state
+            .input_state
+            .history
+            .next(state.input_state.text_area.text())
+            .map(str::to_string)
+    {
+        recall_input(state, &entry);
     } else {
         handle_scroll_down(state, message_area_height, message_area_width);
     }
 }
 
+/// Replace the input with a recalled history entry, cursor at the end
+fn recall_input(state: &mut AppState, entry: &str) {
+    state.input_state.text_area.set_text(entry);
+    state.input_state.text_area.set_cursor(entry.len());
+}
+
 /// Handle scroll up
 fn handle_scroll_up(state: &mut AppState) {
     if state.messages_scrolling_state.show_collapsed_messages {
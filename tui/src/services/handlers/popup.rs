@@ -430,6 +430,7 @@ pub fn handle_show_command_palette(state: &mut AppState) {
 
     state.shortcuts_panel_state.is_visible = true;
     state.shortcuts_panel_state.mode = crate::app::ShortcutsPopupMode::Commands;
+    state.shortcuts_panel_state.reset_scroll();
     state.command_palette_state.is_selected = 0;
     state.command_palette_state.scroll = 0;
     state.command_palette_state.search = String::new();
@@ -440,19 +441,13 @@ pub fn handle_command_palette_search_input_changed(state: &mut AppState, c: char
     if state.shortcuts_panel_state.is_visible {
         state.command_palette_state.search.push(c);
         state.command_palette_state.is_selected = 0;
+        // The search filters every tab, so their scroll positions are stale.
+        state.shortcuts_panel_state.reset_scroll();
         // Also reset session selection to first matching result
         if state.shortcuts_panel_state.mode == crate::app::ShortcutsPopupMode::Sessions {
-            let search_lower = state.command_palette_state.search.to_lowercase();
-            if let Some(first_match) = state
+            state
                 .sessions_state
-                .sessions
-                .iter()
-                .enumerate()
-                .find(|(_, s)| s.title.to_lowercase().contains(&search_lower))
-                .map(|(i, _)| i)
-            {
-                state.sessions_state.session_selected = first_match;
-            }
+                .select_first_match(&state.command_palette_state.search);
         }
     }
 }
@@ -462,22 +457,12 @@ pub fn handle_command_palette_search_backspace(state: &mut AppState) {
     if state.shortcuts_panel_state.is_visible && !state.command_palette_state.search.is_empty() {
         state.command_palette_state.search.pop();
         state.command_palette_state.is_selected = 0;
+        state.shortcuts_panel_state.reset_scroll();
         // Also reset session selection to first matching result
         if state.shortcuts_panel_state.mode == crate::app::ShortcutsPopupMode::Sessions {
-            let search_lower = state.command_palette_state.search.to_lowercase();
-            if let Some(first_match) = state
+            state
                 .sessions_state
-                .sessions
-                .iter()
-                .enumerate()
-                .find(|(_, s)| {
-                    state.command_palette_state.search.is_empty()
-                        || s.title.to_lowercase().contains(&search_lower)
-                })
-                .map(|(i, _)| i)
-            {
-                state.sessions_state.session_selected = first_match;
-            }
+                .select_first_match(&state.command_palette_state.search);
         }
     }
 }
@@ -497,7 +482,7 @@ pub fn handle_show_shortcuts(state: &mut AppState) {
 
     state.shortcuts_panel_state.is_visible = true;
     state.shortcuts_panel_state.mode = crate::app::ShortcutsPopupMode::Shortcuts;
-    state.shortcuts_panel_state.scroll = 0;
+    state.shortcuts_panel_state.reset_scroll();
 }
 
 /// Handle shortcuts cancel event
@@ -505,6 +490,13 @@ pub fn handle_shortcuts_cancel(state: &mut AppState) {
     state.shortcuts_panel_state.is_visible = false;
 }
 
+/// Handle a click on a shortcuts popup tab
+pub fn handle_shortcuts_select_tab(state: &mut AppState, mode: crate::app::ShortcutsPopupMode) {
+    if state.shortcuts_panel_state.is_visible {
+        state.shortcuts_panel_state.mode = mode;
+    }
+}
+
 /// Handle toggle more shortcuts event
 pub fn handle_toggle_more_shortcuts(state: &mut AppState) {
     state.dialog_approval_state.show_shortcuts = !state.dialog_approval_state.show_shortcuts;
@@ -556,6 +548,53 @@ pub fn handle_toggle_collapsed_messages(
     }
 }
 
+/// Toggle inline expansion of a tool result in the main message view
+pub fn handle_toggle_tool_result_expanded(state: &mut AppState, message_id: uuid::Uuid) {
+    if state
+        .messages_scrolling_state
+        .toggle_tool_result_expanded(message_id)
+        .is_some()
+    {
+        // Rebuild even while streaming: the user asked for this message to change height
+        state.messages_scrolling_state.assembled_lines_cache = None;
+        state.messages_scrolling_state.visible_lines_cache = None;
+    }
+}
+
+/// Handle Enter in the collapsed messages popup: toggle the selected tool result
+/// inline in the main view and close the popup so it is visible there
+pub fn handle_toggle_selected_collapsed_message(state: &mut AppState) {
+    let selected = state.messages_scrolling_state.collapsed_messages_selected;
+    let Some(call_id) = state
+        .messages_scrolling_state
+        .messages
+        .iter()
+        .filter(|m| m.is_collapsed == Some(true))
+        .nth(selected)
+        .and_then(|m| m.tool_result_call_id())
+        .map(str::to_string)
+    else {
+        return;
+    };
+
+    let Some(target_id) = state
+        .messages_scrolling_state
+        .messages
+        .iter()
+        .find(|m| {
+            m.tool_result_preview()
+                .is_some_and(|result| result.call.id == call_id)
+        })
+        .map(|m| m.id)
+    else {
+        return;
+    };
+
+    handle_toggle_tool_result_expanded(state, target_id);
+    state.message_interaction_state.selection = SelectionState::default();
+    state.messages_scrolling_state.show_collapsed_messages = false;
+}
+
 // ========== Side Panel Handlers ==========
 
 /// Handle toggle side panel event
@@ -654,6 +693,18 @@ pub fn handle_side_panel_mouse_click(state: &mut AppState, col: u16, row: u16) {
                     handle_show_file_changes_popup(state);
                 }
             }
+        } else if section == crate::services::changeset::SidePanelSection::Context
+            && state
+                .side_panel_state
+                .areas
+                .get(&section)
+                .is_some_and(|area| {
+                    row.saturating_sub(area.y)
+                        >= crate::services::side_panel::CONTEXT_AUTO_APPROVE_ROW
+                })
+        {
+            // The Auto-approve row and tool names open the popup to toggle them
+            handle_show_auto_approve_popup(state);
         } else {
             let current = state
                 .side_panel_state
@@ -791,6 +842,22 @@ pub fn handle_auto_approve_popup_right(state: &mut AppState) {
     }
 }
 
+/// Switch the selected tool between auto-approved and asking first. Like the
+/// policy cycling, the change applies to the session once saved with Enter.
+pub fn handle_auto_approve_popup_toggle(state: &mut AppState) {
+    if let Some(row_idx) = state
+        .tool_approval_popup_state
+        .get_row_index(state.tool_approval_popup_state.row_selected)
+        && let Some(row) = state.tool_approval_popup_state.rows.get_mut(row_idx)
+    {
+        row.policy = if row.policy == AutoApprovePolicy::Auto {
+            AutoApprovePolicy::Prompt
+        } else {
+            AutoApprovePolicy::Auto
+        };
+    }
+}
+
 pub fn handle_auto_approve_popup_apply(state: &mut AppState) {
     let changes: Vec<(String, AutoApprovePolicy)> = state
         .tool_approval_popup_state
@@ -1295,9 +1362,17 @@ pub fn handle_available_models_loaded(
     // Add custom models from recent_models that aren't in available_models
     ensure_custom_models_in_available(state);
 
+    let pending_model_id = state.model_switcher_state.pending_model_id.take();
+    let restoring = pending_model_id
+        .as_ref()
+        .is_some_and(|(_, restore)| *restore);
+
     // Add the current/default model to recent_models if not already there.
     // Always use normalized "provider/short_name" format for storage.
-    let recent_id_to_add = if let Some(current) = &state.model_switcher_state.current_model {
+    // A resumed session's registry load leaves the list alone.
+    let recent_id_to_add = if restoring {
+        None
+    } else if let Some(current) = &state.model_switcher_state.current_model {
         Some(format_recent_model_id(&current.provider, &current.id))
     } else {
         // Use the configured default model (state.configuration_state.model)
@@ -1359,7 +1434,7 @@ pub fn handle_available_models_loaded(
             .model_switcher_state
             .available_models
             .iter()
-            .position(|m| m.id == current.id)
+            .position(|m| m.provider == current.provider && m.id == current.id)
         {
             if filtered.contains(&idx) {
                 state.model_switcher_state.is_selected = idx;
@@ -1373,6 +1448,84 @@ pub fn handle_available_models_loaded(
     } else {
         state.model_switcher_state.is_selected = filtered.first().copied().unwrap_or(0);
     }
+
+    if let Some((model_id, restore)) = pending_model_id {
+        switch_to_model_id(state, &model_id, restore, output_tx);
+    }
+}
+
+/// Handle a request to switch models by id, loading the registry first if
+/// it hasn't been fetched yet. `restore` marks a resumed session's model.
+pub fn handle_switch_model(
+    state: &mut AppState,
+    model_id: String,
+    restore: bool,
+    output_tx: &Sender<OutputEvent>,
+) {
+    if state.model_switcher_state.available_models.is_empty() {
+        state.model_switcher_state.pending_model_id = Some((model_id, restore));
+        let _ = output_tx.try_send(OutputEvent::RequestAvailableModels);
+    } else {
+        switch_to_model_id(state, &model_id, restore, output_tx);
+    }
+}
+
+fn switch_to_model_id(
+    state: &mut AppState,
+    model_id: &str,
+    restore: bool,
+    output_tx: &Sender<OutputEvent>,
+) {
+    let Some(model) = crate::services::model_switcher::find_model(
+        &state.model_switcher_state.available_models,
+        model_id,
+    )
+    .cloned() else {
+        push_error_message(
+            state,
+            &format!("Unknown model '{model_id}'. Run /model to pick from the available models."),
+            None,
+        );
+        return;
+    };
+    switch_to_model(state, model, restore, output_tx);
+}
+
+/// Whether `model` is already the active model. Providers can serve the same
+/// id, so both have to match.
+fn is_current_model(state: &AppState, model: &Model) -> bool {
+    state
+        .model_switcher_state
+        .current_model
+        .as_ref()
+        .is_some_and(|current| current.provider == model.provider && current.id == model.id)
+}
+
+/// Make `model` the active model for the following turns and confirm it in
+/// the message list
+fn switch_to_model(
+    state: &mut AppState,
+    model: Model,
+    restore: bool,
+    output_tx: &Sender<OutputEvent>,
+) {
+    if is_current_model(state, &model) {
+        return;
+    }
+
+    push_styled_message(
+        state,
+        &format!(
+            "Switched model to {} ({})",
+            model.name,
+            format_recent_model_id(&model.provider, &model.id)
+        ),
+        ThemeColors::text(),
+        "✓ ",
+        ThemeColors::green(),
+    );
+    state.model_switcher_state.current_model = Some(model.clone());
+    let _ = output_tx.try_send(OutputEvent::SwitchToModel(model, restore));
 }
 
 /// Handle model switcher select event
@@ -1398,26 +1551,17 @@ pub fn handle_model_switcher_select(state: &mut AppState, output_tx: &Sender<Out
             .clone();
 
         // Don't switch if already on this model
-        if state
-            .model_switcher_state
-            .current_model
-            .as_ref()
-            .is_some_and(|m| m.id == selected_model.id)
-        {
+        if is_current_model(state, &selected_model) {
             state.model_switcher_state.is_visible = false;
             state.model_switcher_state.search.clear();
             return;
         }
 
-        // Update current model
-        state.model_switcher_state.current_model = Some(selected_model.clone());
-
         // Close the switcher and clear search
         state.model_switcher_state.is_visible = false;
         state.model_switcher_state.search.clear();
 
-        // Send request to switch model
-        let _ = output_tx.try_send(OutputEvent::SwitchToModel(selected_model.clone()));
+        switch_to_model(state, selected_model, false, output_tx);
     }
 }
 
@@ -7,8 +7,10 @@
 //! - Extracting clean text (excluding borders, decorations)
 //! - Cursor positioning in input area on click
 //! - Showing message action popup on user message click
+//! - Expanding or collapsing tool results on click
 
 use crate::app::AppState;
+use crate::services::message::find_tool_result_at_line;
 use crate::services::message_action_popup::find_user_message_at_line;
 use crate::services::text_selection::{
     SelectionState, copy_to_clipboard, extract_selected_text, extract_selected_text_from_collapsed,
@@ -336,6 +338,9 @@ pub fn handle_drag_end(state: &mut AppState, col: u16, row: u16) {
                 .message_interaction_state
                 .message_action_target_message_id = Some(msg_id);
             state.message_interaction_state.message_action_target_text = Some(msg_text);
+        } else if let Some(msg_id) = find_tool_result_at_line(state, absolute_line) {
+            // Clicking a tool result expands or collapses its full output
+            super::popup::handle_toggle_tool_result_expanded(state, msg_id);
         }
 
         return;
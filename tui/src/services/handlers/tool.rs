@@ -5,10 +5,10 @@
 use crate::app::{AppState, InputEvent, OutputEvent, ToolCallStatus};
 use crate::services::commands::{CommandAction, CommandContext, execute_command, filter_commands};
 use crate::services::helper_block::push_error_message;
-use crate::services::message::{Message, invalidate_message_lines_cache};
+use crate::services::message::{Message, MessageContent, invalidate_message_lines_cache};
 use stakpak_shared::models::integrations::openai::{
-    ProgressType, ToolCall, ToolCallResult, ToolCallResultProgress, ToolCallResultStatus,
-    ToolCallStreamInfo,
+    FunctionCall, ProgressType, ToolCall, ToolCallResult, ToolCallResultProgress,
+    ToolCallResultStatus, ToolCallStreamInfo,
 };
 use stakpak_shared::utils::strip_tool_name;
 use tokio::sync::mpsc::Sender;
@@ -429,6 +429,97 @@ pub fn handle_retry_mechanism(state: &mut AppState) {
     }
 }
 
+/// Failed tool call behind the entry selected in the collapsed messages popup
+pub fn selected_failed_tool_call(state: &AppState) -> Option<ToolCall> {
+    let selected = state.messages_scrolling_state.collapsed_messages_selected;
+    state
+        .messages_scrolling_state
+        .messages
+        .iter()
+        .filter(|m| m.is_collapsed == Some(true))
+        .nth(selected)
+        .and_then(|m| match &m.content {
+            MessageContent::RenderFullContentMessage(result)
+                if result.status == ToolCallResultStatus::Error =>
+            {
+                Some(result.call.clone())
+            }
+            _ => None,
+        })
+}
+
+/// Handle Ctrl+R in the collapsed messages popup: put the arguments of the
+/// selected failed tool call in the input field so they can be edited before
+/// Enter re-issues the call
+pub fn handle_retry_selected_tool_call(state: &mut AppState) {
+    let Some(tool_call) = selected_failed_tool_call(state) else {
+        push_error_message(state, "Only failed tool calls can be retried", None);
+        return;
+    };
+    if state.loading_state.is_loading || state.dialog_approval_state.approval_bar.is_visible() {
+        push_error_message(
+            state,
+            "Wait for the current turn to finish before retrying a tool call",
+            None,
+        );
+        return;
+    }
+
+    let arguments = serde_json::from_str::<serde_json::Value>(&tool_call.function.arguments)
+        .ok()
+        .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        .unwrap_or_else(|| tool_call.function.arguments.clone());
+    state.input_state.text_area.set_text(&arguments);
+
+    state.messages_scrolling_state.show_collapsed_messages = false;
+    state.message_interaction_state.selection =
+        crate::services::text_selection::SelectionState::default();
+    state.messages_scrolling_state.messages.push(Message::info(
+        format!(
+            "Retrying {}: edit its arguments and press Enter to run it again, Esc to cancel",
+            strip_tool_name(&tool_call.function.name)
+        ),
+        None,
+    ));
+    state.tool_call_state.pending_retry = Some(tool_call);
+    invalidate_message_lines_cache(state);
+}
+
+/// Re-issue the tool call being retried with the arguments in the input field
+pub fn submit_tool_call_retry(state: &mut AppState, output_tx: &Sender<OutputEvent>) {
+    let Some(original) = state.tool_call_state.pending_retry.clone() else {
+        return;
+    };
+    let arguments = match serde_json::from_str::<serde_json::Value>(state.input()) {
+        Ok(arguments) => arguments,
+        Err(e) => {
+            push_error_message(state, &format!("Invalid tool call arguments: {e}"), None);
+            return;
+        }
+    };
+
+    let tool_call = ToolCall {
+        id: format!("retry_{}", uuid::Uuid::new_v4().simple()),
+        r#type: original.r#type,
+        function: FunctionCall {
+            name: original.function.name,
+            arguments: arguments.to_string(),
+        },
+        // Provider metadata belongs to the model's original call
+        metadata: None,
+    };
+    state.tool_call_state.pending_retry = None;
+    state.input_state.text_area.set_text("");
+    let _ = output_tx.try_send(OutputEvent::RetryToolCall(tool_call));
+}
+
+/// Drop the tool call being retried and its arguments in the input field
+pub fn cancel_tool_call_retry(state: &mut AppState) {
+    if state.tool_call_state.pending_retry.take().is_some() {
+        state.input_state.text_area.set_text("");
+    }
+}
+
 /// Handle interactive stall detection - automatically switch to shell mode and run the command
 pub fn handle_interactive_stall_detected(
     state: &mut AppState,
@@ -961,6 +1052,30 @@ pub fn handle_approval_bar_reject_selected(state: &mut AppState) {
     state.dialog_approval_state.approval_bar.reject_selected();
 }
 
+/// Handle "always allow" for the selected action: approve it and auto-approve
+/// identical calls for the rest of the session
+pub fn handle_approval_bar_allow_exact_for_session(
+    state: &mut AppState,
+    output_tx: &Sender<OutputEvent>,
+) {
+    let Some(tool_call) = state
+        .dialog_approval_state
+        .approval_bar
+        .selected_tool_call()
+        .cloned()
+    else {
+        return;
+    };
+
+    state.dialog_approval_state.approval_bar.approve_selected();
+    let approval = state
+        .configuration_state
+        .auto_approve_manager
+        .allow_exact_for_session(&tool_call);
+    let _ = output_tx.try_send(OutputEvent::AllowToolCallForSession(approval));
+    update_pending_tool_display(state);
+}
+
 /// Handle toggle selected action (space key)
 pub fn handle_approval_bar_toggle_selected(state: &mut AppState, _input_tx: &Sender<InputEvent>) {
     state.dialog_approval_state.approval_bar.toggle_selected();
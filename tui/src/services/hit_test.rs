@@ -0,0 +1,108 @@
+//! Clickable screen regions recorded while drawing a frame.
+//!
+//! `view()` clears the map and registers every tab, button and message area
+//! it draws, and blocks every overlay, so the input thread can turn a left
+//! click into the event the widget under the cursor stands for. Clicks on an
+//! overlay never reach the widgets drawn under it.
+
+use crate::app::{InputEvent, ShortcutsPopupMode};
+use ratatui::layout::{Position, Rect};
+use std::sync::{Arc, RwLock};
+
+/// The map shared between the render loop and the input thread.
+pub type SharedHitTestMap = Arc<RwLock<HitTestMap>>;
+
+/// A widget that reacts to being clicked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickTarget {
+    /// A tab in the command palette header.
+    ShortcutsTab(ShortcutsPopupMode),
+    /// A tool call button in the inline approval bar.
+    ApprovalAction(usize),
+    /// The message list, where a click starts a text selection.
+    Messages,
+    /// An overlay that swallows clicks meant for the widgets under it.
+    Overlay,
+}
+
+impl ClickTarget {
+    /// The event a left click at `(col, row)` on this target stands for.
+    pub fn event(self, col: u16, row: u16) -> InputEvent {
+        match self {
+            ClickTarget::ShortcutsTab(mode) => InputEvent::ShortcutsSelectTab(mode),
+            ClickTarget::ApprovalAction(index) => InputEvent::ApprovalBarSelectAction(index),
+            ClickTarget::Messages => InputEvent::MouseDragStart(col, row),
+            ClickTarget::Overlay => InputEvent::MouseClick(col, row),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HitTestMap {
+    /// Regions in draw order.
+    regions: Vec<(Rect, ClickTarget)>,
+}
+
+impl HitTestMap {
+    pub fn clear(&mut self) {
+        self.regions.clear();
+    }
+
+    pub fn register(&mut self, area: Rect, target: ClickTarget) {
+        self.regions.push((area, target));
+    }
+
+    /// Hide the regions registered so far under `area`, e.g. for a popup.
+    pub fn block(&mut self, area: Rect) {
+        self.register(area, ClickTarget::Overlay);
+    }
+
+    /// The target under `(col, row)`. Regions registered later are drawn on
+    /// top, so they win.
+    pub fn resolve(&self, col: u16, row: u16) -> Option<ClickTarget> {
+        let position = Position::new(col, row);
+        self.regions
+            .iter()
+            .rev()
+            .find(|(area, _)| area.contains(position))
+            .map(|(_, target)| *target)
+    }
+
+    /// Like [`HitTestMap::resolve`], for a map shared with the render loop.
+    pub fn resolve_shared(map: &SharedHitTestMap, col: u16, row: u16) -> Option<ClickTarget> {
+        map.read().ok()?.resolve(col, row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn later_regions_win_and_blocks_hide_earlier_ones() {
+        let mut map = HitTestMap::default();
+        map.register(Rect::new(0, 10, 20, 1), ClickTarget::ApprovalAction(0));
+        map.register(Rect::new(20, 10, 20, 1), ClickTarget::ApprovalAction(1));
+
+        assert_eq!(map.resolve(5, 10), Some(ClickTarget::ApprovalAction(0)));
+        assert_eq!(map.resolve(20, 10), Some(ClickTarget::ApprovalAction(1)));
+        assert_eq!(map.resolve(40, 10), None);
+        assert_eq!(map.resolve(5, 11), None);
+
+        map.block(Rect::new(0, 5, 30, 10));
+        map.register(
+            Rect::new(2, 6, 10, 1),
+            ClickTarget::ShortcutsTab(ShortcutsPopupMode::Sessions),
+        );
+
+        assert_eq!(map.resolve(5, 10), Some(ClickTarget::Overlay));
+        assert_eq!(map.resolve(35, 10), Some(ClickTarget::ApprovalAction(1)));
+        assert_eq!(
+            map.resolve(2, 6),
+            Some(ClickTarget::ShortcutsTab(ShortcutsPopupMode::Sessions))
+        );
+
+        map.clear();
+        assert_eq!(map.resolve(2, 6), None);
+    }
+}
@@ -0,0 +1,211 @@
+//! Previously submitted inputs, recalled with Up/Down from an empty input.
+//!
+//! Entries are kept oldest first, consecutive duplicates are dropped and the
+//! oldest entries fall off past [`MAX_INPUT_HISTORY`]. Secrets are redacted
+//! before an entry is recorded, and long pastes are kept as their collapsed
+//! placeholder. The TUI loads the project's history from
+//! [`crate::constants::INPUT_HISTORY_PATH`] and saves it after every
+//! submission.
+
+use stakpak_shared::secrets::redact_secrets;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub const MAX_INPUT_HISTORY: usize = 500;
+
+#[derive(Debug, Clone)]
+pub struct InputHistory {
+    entries: Vec<String>,
+    max_entries: usize,
+    /// File the history is saved to; `None` keeps it in memory only.
+    path: Option<PathBuf>,
+    /// Index of the entry currently recalled into the input field.
+    cursor: Option<usize>,
+    privacy_mode: bool,
+}
+
+impl Default for InputHistory {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            max_entries: MAX_INPUT_HISTORY,
+            path: None,
+            cursor: None,
+            privacy_mode: false,
+        }
+    }
+}
+
+impl InputHistory {
+    /// Load the history saved at `path`, starting empty if it is missing or
+    /// unreadable. New entries are saved back to `path`.
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let mut entries: Vec<String> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let overflow = entries.len().saturating_sub(MAX_INPUT_HISTORY);
+        entries.drain(..overflow);
+        Self {
+            entries,
+            path: Some(path),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    /// Also redact the values `--privacy-mode` hides, such as IP addresses.
+    pub fn with_privacy_mode(mut self, privacy_mode: bool) -> Self {
+        self.privacy_mode = privacy_mode;
+        self
+    }
+
+    /// Entries oldest first.
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Record a submitted input, with secrets redacted, and stop browsing.
+    /// Blank inputs and repeats of the latest entry are not recorded.
+    pub fn push(&mut self, input: &str) {
+        self.cursor = None;
+        if input.trim().is_empty() {
+            return;
+        }
+        let entry = redact_secrets(input, None, &HashMap::new(), self.privacy_mode).redacted_string;
+        if self.entries.last().is_some_and(|last| *last == entry) {
+            return;
+        }
+        self.entries.push(entry);
+        let overflow = self.entries.len().saturating_sub(self.max_entries);
+        self.entries.drain(..overflow);
+        if let Some(path) = &self.path {
+            let _ = save(path, &self.entries);
+        }
+    }
+
+    /// The entry older than the one shown in `input`, or the latest entry
+    /// when `input` is empty. `None` when `input` is text the user typed,
+    /// so Up goes on to scroll the messages.
+    pub fn previous(&mut self, input: &str) -> Option<&str> {
+        let index = match self.browsing(input) {
+            Some(index) => index.saturating_sub(1),
+            None if input.is_empty() => self.entries.len().checked_sub(1)?,
+            None => return None,
+        };
+        self.cursor = Some(index);
+        self.entries.get(index).map(String::as_str)
+    }
+
+    /// The entry newer than the one shown in `input`, or an empty input when
+    /// stepping past the latest one. `None` when not browsing the history.
+    pub fn next(&mut self, input: &str) -> Option<&str> {
+        let index = self.browsing(input)? + 1;
+        if index < self.entries.len() {
+            self.cursor = Some(index);
+            self.entries.get(index).map(String::as_str)
+        } else {
+            self.cursor = None;
+            Some("")
+        }
+    }
+
+    /// Cursor of the entry shown in `input`, if it is still unedited.
+    fn browsing(&mut self, input: &str) -> Option<usize> {
+        let cursor = self
+            .cursor
+            .filter(|&index| self.entries.get(index).is_some_and(|entry| entry == input));
+        self.cursor = cursor;
+        cursor
+    }
+}
+
+fn save(path: &Path, entries: &[String]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(entries)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(entries: &[&str]) -> InputHistory {
+        let mut history = InputHistory::default();
+        for entry in entries {
+            history.push(entry);
+        }
+        history
+    }
+
+    #[test]
+    fn up_recalls_newest_first_and_down_walks_back() {
+        let mut history = history(&["deploy staging", "check logs", "roll back"]);
+
+        assert_eq!(history.previous(""), Some("roll back"));
+        assert_eq!(history.previous("roll back"), Some("check logs"));
+        assert_eq!(history.previous("check logs"), Some("deploy staging"));
+        // The oldest entry stays put
+        assert_eq!(history.previous("deploy staging"), Some("deploy staging"));
+
+        assert_eq!(history.next("deploy staging"), Some("check logs"));
+        assert_eq!(history.next("check logs"), Some("roll back"));
+        assert_eq!(history.next("roll back"), Some(""));
+        assert_eq!(history.next(""), None);
+    }
+
+    #[test]
+    fn typed_or_edited_input_is_not_replaced() {
+        let mut history = history(&["deploy staging", "check logs"]);
+
+        assert_eq!(history.previous("draft"), None);
+        assert_eq!(history.next("draft"), None);
+
+        assert_eq!(history.previous(""), Some("check logs"));
+        assert_eq!(history.previous("check logs please"), None);
+        assert_eq!(history.next("check logs please"), None);
+    }
+
+    #[test]
+    fn consecutive_duplicates_and_blank_inputs_are_dropped() {
+        let history = history(&["ls", "ls", "  ", "pwd", "pwd", "ls"]);
+        assert_eq!(history.entries(), ["ls", "pwd", "ls"]);
+    }
+
+    #[test]
+    fn history_is_capped_and_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session").join("input_history.json");
+
+        let mut history = InputHistory::load(&path).with_max_entries(2);
+        for entry in ["one", "two", "three"] {
+            history.push(entry);
+        }
+        assert_eq!(history.entries(), ["two", "three"]);
+
+        let mut reloaded = InputHistory::load(&path);
+        assert_eq!(reloaded.entries(), ["two", "three"]);
+        assert_eq!(reloaded.previous(""), Some("three"));
+    }
+
+    #[test]
+    fn secrets_are_redacted_before_saving() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("input_history.json");
+        let secret = ["AKIA", "IOSFODNN7EX23PLE"].concat();
+
+        let mut history = InputHistory::load(&path);
+        history.push(&format!("export AWS_ACCESS_KEY_ID={secret}"));
+
+        assert!(!history.entries()[0].contains(&secret));
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains(&secret), "{saved}");
+        assert!(saved.contains("export AWS_ACCESS_KEY_ID="));
+    }
+}
@@ -1,7 +1,11 @@
 // Shared layout utilities for TUI popup and overlay positioning.
 // All popup rendering modules should import from here instead of defining their own copies.
 
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    text::Text,
+    widgets::{Paragraph, Wrap},
+};
 
 /// Return a centered rectangle of the given percentage dimensions within `r`.
 ///
@@ -30,3 +34,141 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+/// How a popup is placed on screen. Sizes include the popup's border.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopupPosition {
+    /// A fixed size, centred.
+    Centered { width: u16, height: u16 },
+    /// Sized to the wrapped content within the bounds, centred. Content
+    /// taller than `max_height` scrolls.
+    AutoFit {
+        max_width: u16,
+        max_height: u16,
+        min_width: u16,
+    },
+}
+
+/// The area a popup occupies and whether its content needs to scroll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PopupPlacement {
+    pub area: Rect,
+    /// Rows the content takes once wrapped to the popup's inner width.
+    pub content_height: u16,
+    pub scrollable: bool,
+}
+
+/// Rows and columns taken by the popup border.
+const POPUP_BORDER: u16 = 2;
+
+impl PopupPosition {
+    /// Place a popup showing `content` (wrapped without trimming) within `screen`.
+    pub fn place(&self, content: &Text<'_>, screen: Rect) -> PopupPlacement {
+        let paragraph = Paragraph::new(content.clone()).wrap(Wrap { trim: false });
+
+        let (width, max_height) = match *self {
+            PopupPosition::Centered { width, height } => (width, height),
+            PopupPosition::AutoFit {
+                max_width,
+                max_height,
+                min_width,
+            } => {
+                let content_width = u16::try_from(paragraph.line_width()).unwrap_or(u16::MAX);
+                let width = content_width
+                    .saturating_add(POPUP_BORDER)
+                    .min(max_width)
+                    .max(min_width);
+                (width, max_height)
+            }
+        };
+        let width = width.min(screen.width);
+        let inner_width = width.saturating_sub(POPUP_BORDER);
+
+        let content_height =
+            u16::try_from(paragraph.line_count(inner_width.max(1))).unwrap_or(u16::MAX);
+        let height = match *self {
+            PopupPosition::Centered { .. } => max_height,
+            PopupPosition::AutoFit { .. } => {
+                content_height.saturating_add(POPUP_BORDER).min(max_height)
+            }
+        }
+        .min(screen.height);
+
+        PopupPlacement {
+            area: Rect {
+                x: screen.x + (screen.width - width) / 2,
+                y: screen.y + (screen.height - height) / 2,
+                width,
+                height,
+            },
+            content_height,
+            scrollable: content_height > height.saturating_sub(POPUP_BORDER),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 100,
+        height: 40,
+    };
+
+    const AUTO_FIT: PopupPosition = PopupPosition::AutoFit {
+        max_width: 60,
+        max_height: 12,
+        min_width: 20,
+    };
+
+    #[test]
+    fn auto_fit_shrinks_to_short_content() {
+        let placement = AUTO_FIT.place(
+            &Text::from("Delete this session?\nIt cannot be undone."),
+            SCREEN,
+        );
+
+        assert_eq!(placement.area.width, 22);
+        assert_eq!(placement.area.height, 4);
+        assert_eq!(placement.area.x, 39);
+        assert_eq!(placement.area.y, 18);
+        assert!(!placement.scrollable);
+
+        // Very short content still gets the minimum width.
+        let placement = AUTO_FIT.place(&Text::from("Ok?"), SCREEN);
+        assert_eq!(placement.area.width, 20);
+    }
+
+    #[test]
+    fn auto_fit_wraps_wide_content_and_scrolls_tall_content() {
+        let wide = "word ".repeat(30);
+        let placement = AUTO_FIT.place(&Text::from(wide.trim_end().to_string()), SCREEN);
+        assert_eq!(placement.area.width, 60);
+        assert_eq!(placement.content_height, 3);
+        assert_eq!(placement.area.height, 5);
+        assert!(!placement.scrollable);
+
+        let tall = (1..=30)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let placement = AUTO_FIT.place(&Text::from(tall), SCREEN);
+        assert_eq!(placement.area.height, 12);
+        assert_eq!(placement.content_height, 30);
+        assert!(placement.scrollable);
+    }
+
+    #[test]
+    fn centered_keeps_its_fixed_size() {
+        let position = PopupPosition::Centered {
+            width: 50,
+            height: 10,
+        };
+        let placement = position.place(&Text::from("hi"), SCREEN);
+        assert_eq!(placement.area, Rect::new(25, 15, 50, 10));
+        assert!(!placement.scrollable);
+    }
+}
@@ -506,6 +506,38 @@ impl Message {
         }
     }
 
+    /// Full tool result behind a truncated tool result preview in the main view
+    pub fn tool_result_preview(&self) -> Option<&ToolCallResult> {
+        match &self.content {
+            MessageContent::RenderCommandCollapsedResult(result)
+            | MessageContent::RenderResultBorderBlock(result)
+                if self.is_collapsed.is_none() =>
+            {
+                Some(result)
+            }
+            _ => None,
+        }
+    }
+
+    /// Tool call ID of a tool result message, preview or full content
+    pub fn tool_result_call_id(&self) -> Option<&str> {
+        match &self.content {
+            MessageContent::RenderCommandCollapsedResult(result)
+            | MessageContent::RenderResultBorderBlock(result)
+            | MessageContent::RenderFullContentMessage(result) => Some(&result.call.id),
+            _ => None,
+        }
+    }
+
+    /// Expanded form of a tool result preview, rendered in the main view with the full result
+    pub(crate) fn expanded_tool_result(&self) -> Option<Message> {
+        self.tool_result_preview().map(|result| Message {
+            id: self.id,
+            content: MessageContent::RenderFullContentMessage(result.clone()),
+            is_collapsed: None,
+        })
+    }
+
     pub fn render_collapsed_command_message(tool_call_result: ToolCallResult) -> Self {
         Message {
             id: Uuid::new_v4(),
@@ -970,6 +1002,18 @@ fn compute_cache_key(state: &AppState, width: usize) -> u64 {
     hasher.finish()
 }
 
+/// Find the tool result message rendered at an absolute line in the main view
+pub fn find_tool_result_at_line(state: &AppState, absolute_line: usize) -> Option<Uuid> {
+    state
+        .messages_scrolling_state
+        .line_to_message_map
+        .iter()
+        .find(|(start, end, _, is_user, _, _)| {
+            !*is_user && absolute_line >= *start && absolute_line < *end
+        })
+        .map(|(_, _, id, _, _, _)| *id)
+}
+
 /// Get the total number of cached lines without cloning.
 /// This is useful for scroll calculations where we only need the count.
 #[allow(dead_code)]
@@ -1147,6 +1191,19 @@ pub fn get_wrapped_message_lines_cached(state: &mut AppState, width: usize) -> V
 
     // Process each message, using cache when available
     for msg in &message_refs {
+        // Tool results the user expanded render their full result instead of the preview
+        let expanded = if state
+            .messages_scrolling_state
+            .expanded_tool_results
+            .contains(&msg.id)
+        {
+            msg.expanded_tool_result()
+        } else {
+            None
+        };
+        let is_tool_result = msg.tool_result_preview().is_some();
+        let msg: &Message = expanded.as_ref().unwrap_or(*msg);
+
         let content_hash = hash_message_content(&msg.content);
         let start_line = all_processed_lines.len();
 
@@ -1190,7 +1247,7 @@ pub fn get_wrapped_message_lines_cached(state: &mut AppState, width: usize) -> V
 
         let end_line = all_processed_lines.len();
 
-        // Only track user messages in the map (for efficiency)
+        // Only track clickable messages in the map (for efficiency)
         if is_user_message && end_line > start_line {
             line_to_message_map.push((
                 start_line,
@@ -1200,6 +1257,8 @@ pub fn get_wrapped_message_lines_cached(state: &mut AppState, width: usize) -> V
                 message_text,
                 user_message_counter,
             ));
+        } else if is_tool_result && end_line > start_line {
+            line_to_message_map.push((start_line, end_line, msg.id, false, String::new(), 0));
         }
     }
 
@@ -1290,7 +1349,7 @@ pub fn get_wrapped_message_lines_cached(state: &mut AppState, width: usize) -> V
 
 /// Render a single message to lines.
 /// This is extracted to allow per-message caching.
-fn render_single_message(msg: &Message, width: usize) -> Vec<Line<'static>> {
+pub(crate) fn render_single_message(msg: &Message, width: usize) -> Vec<Line<'static>> {
     use crate::services::message_pattern::spans_to_string;
 
     // Render the message using the internal function
@@ -2760,6 +2819,8 @@ pub fn get_command_type_name(tool_call: &ToolCall) -> String {
         "delete_file" => "Delete file".to_string(),
         "list_directory" => "List directory".to_string(),
         "search_files" => "Search files".to_string(),
+        "git_status" => "Git status".to_string(),
+        "git_diff" => "Git diff".to_string(),
         "dynamic_subagent_task" => {
             let args =
                 serde_json::from_str::<serde_json::Value>(&tool_call.function.arguments).ok();
@@ -7,6 +7,7 @@ pub mod board_tasks;
 pub mod changeset;
 pub mod clipboard_paste;
 pub mod commands;
+pub mod confirm_popup;
 pub mod custom_commands;
 pub mod detect_term;
 pub mod editor;
@@ -17,7 +18,9 @@ pub mod handlers;
 pub mod helper_block;
 pub mod helper_dropdown;
 pub mod hint_helper;
+pub mod hit_test;
 pub mod image_upload;
+pub mod input_history;
 pub mod layout;
 pub mod markdown_renderer;
 pub mod message;
@@ -35,11 +38,14 @@ pub mod shell_mode;
 pub mod shell_popup;
 pub mod shortcuts_popup;
 pub mod side_panel;
+pub mod status_bar;
 pub mod syntax_highlighter;
+pub mod tab_header;
 pub mod text_selection;
 pub mod textarea;
 pub mod toast;
 pub mod todo_extractor;
+pub mod transcript_log;
 pub mod update;
 pub mod widget_selection;
 pub mod wrapping;
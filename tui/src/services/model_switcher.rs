@@ -26,6 +26,20 @@ fn matches_recent_id(model: &Model, recent_id: &str) -> bool {
     normalized == recent_id
 }
 
+/// Find the model named in `/model <id>`: the registry id itself, the
+/// `provider/short_name` form used for recent models, or the bare short name.
+pub fn find_model<'a>(models: &'a [Model], id: &str) -> Option<&'a Model> {
+    models
+        .iter()
+        .find(|model| model.id == id)
+        .or_else(|| models.iter().find(|model| matches_recent_id(model, id)))
+        .or_else(|| {
+            models
+                .iter()
+                .find(|model| model.id.rsplit('/').next() == Some(id))
+        })
+}
+
 /// Filter models based on mode and search query
 /// Returns indices into the original available_models vec that match the filter
 pub fn filter_models(models: &[Model], mode: ModelSwitcherMode, search: &str) -> Vec<usize> {
@@ -5,8 +5,10 @@
 //! - Shortcuts section: Read-only keyboard shortcuts grouped by category
 //! - Sessions section: List of previous sessions to resume
 
-use crate::services::detect_term::ThemeColors;
+use crate::services::detect_term::{ThemeColors, should_use_rgb_colors};
+use crate::services::hit_test::{ClickTarget, HitTestMap};
 use crate::services::layout::centered_rect;
+use crate::services::tab_header::{Tab, tab_regions};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
@@ -56,6 +58,7 @@ pub fn get_all_shortcuts() -> Vec<Shortcut> {
         Shortcut::new("Ctrl+W", "Delete previous word", "Text Input"),
         Shortcut::new("Ctrl+H", "Delete previous character", "Text Input"),
         Shortcut::new("Ctrl+J", "Insert newline", "Text Input"),
+        Shortcut::new("Alt+E", "Expand pasted content", "Text Input"),
         Shortcut::new("Enter", "Submit input", "Text Input"),
         Shortcut::new("Backspace", "Delete previous character", "Text Input"),
         // Tool Management
@@ -78,6 +81,7 @@ pub fn get_all_shortcuts() -> Vec<Shortcut> {
         Shortcut::new("/sessions", "List available sessions", "Commands"),
         Shortcut::new("/resume", "Resume last session", "Commands"),
         Shortcut::new("/model", "Switch model", "Commands"),
+        Shortcut::new("/retitle", "Regenerate session title", "Commands"),
         Shortcut::new(
             "/summarize",
             "Summarize session into summary.md",
@@ -95,6 +99,7 @@ pub fn get_all_shortcuts() -> Vec<Shortcut> {
             "Commands",
         ),
         Shortcut::new("/mouse_capture", "Toggle mouse capture", "Commands"),
+        Shortcut::new("/status_bar", "Toggle status bar", "Commands"),
         Shortcut::new("/profiles", "Switch profile", "Commands"),
         Shortcut::new("/quit", "Quit application", "Commands"),
         // File Search
@@ -192,9 +197,37 @@ pub fn get_shortcuts_count() -> usize {
     get_all_shortcuts().len()
 }
 
-pub fn render_shortcuts_popup(f: &mut Frame, state: &mut crate::app::AppState) {
+const TAB_DIVIDER: &str = " | ";
+
+/// Make each header tab clickable.
+fn register_tab_regions(
+    hit_test: &mut HitTestMap,
+    tabs: &[Tab],
+    use_fallback_colors: bool,
+    area: Rect,
+) {
+    let modes = [
+        ShortcutsPopupMode::Commands,
+        ShortcutsPopupMode::Shortcuts,
+        ShortcutsPopupMode::Sessions,
+    ];
+    let divider_width = TAB_DIVIDER.len() as u16;
+    for (region, mode) in tab_regions(tabs, use_fallback_colors, area, divider_width)
+        .into_iter()
+        .zip(modes)
+    {
+        hit_test.register(region, ClickTarget::ShortcutsTab(mode));
+    }
+}
+
+pub fn render_shortcuts_popup(
+    f: &mut Frame,
+    state: &mut crate::app::AppState,
+    hit_test: &mut HitTestMap,
+) {
     // Calculate popup size (60% width, fit height to content)
     let area = centered_rect(60, 80, f.area());
+    hit_test.block(area);
 
     f.render_widget(ratatui::widgets::Clear, area);
 
@@ -220,12 +253,18 @@ pub fn render_shortcuts_popup(f: &mut Frame, state: &mut crate::app::AppState) {
     let title_paragraph = Paragraph::new(title_line);
 
     // Render tabs
-    let tab_titles = vec![" Commands ", " Shortcuts ", " Sessions "];
-    let selected_tab = match state.shortcuts_panel_state.mode {
-        ShortcutsPopupMode::Commands => 0,
-        ShortcutsPopupMode::Shortcuts => 1,
-        ShortcutsPopupMode::Sessions => 2,
-    };
+    let use_fallback_colors = !should_use_rgb_colors();
+    let session_count = state.sessions_state.sessions.len();
+    let mut sessions_tab = Tab::new("Sessions");
+    if session_count > 0 {
+        sessions_tab = sessions_tab.badge(session_count.to_string());
+    }
+    let tab_list = [Tab::new("Commands"), Tab::new("Shortcuts"), sessions_tab];
+    let tab_titles = tab_list
+        .iter()
+        .map(|tab| tab.header(use_fallback_colors))
+        .collect::<Vec<_>>();
+    let selected_tab = state.shortcuts_panel_state.mode.index();
     let tabs = Tabs::new(tab_titles)
         .select(selected_tab)
         .style(Style::default().fg(ThemeColors::muted()))
@@ -234,7 +273,7 @@ pub fn render_shortcuts_popup(f: &mut Frame, state: &mut crate::app::AppState) {
                 .fg(ThemeColors::accent())
                 .add_modifier(Modifier::BOLD),
         )
-        .divider(" | ");
+        .divider(TAB_DIVIDER);
 
     // Render content based on mode with mode-specific layouts
     match state.shortcuts_panel_state.mode {
@@ -254,6 +293,7 @@ pub fn render_shortcuts_popup(f: &mut Frame, state: &mut crate::app::AppState) {
 
             f.render_widget(title_paragraph, chunks[0]);
             f.render_widget(tabs, chunks[1]);
+            register_tab_regions(hit_test, &tab_list, use_fallback_colors, chunks[1]);
             render_commands_section(f, state, chunks[2], chunks[3], chunks[4], chunks[5], area);
         }
         ShortcutsPopupMode::Shortcuts => {
@@ -273,6 +313,7 @@ pub fn render_shortcuts_popup(f: &mut Frame, state: &mut crate::app::AppState) {
 
             f.render_widget(title_paragraph, chunks[0]);
             f.render_widget(tabs, chunks[1]);
+            register_tab_regions(hit_test, &tab_list, use_fallback_colors, chunks[1]);
             // spacer at chunks[2] is empty
             render_shortcuts_section(f, state, chunks[3], chunks[4], chunks[5], chunks[6], area);
         }
@@ -294,6 +335,7 @@ pub fn render_shortcuts_popup(f: &mut Frame, state: &mut crate::app::AppState) {
 
             f.render_widget(title_paragraph, chunks[0]);
             f.render_widget(tabs, chunks[1]);
+            register_tab_regions(hit_test, &tab_list, use_fallback_colors, chunks[1]);
             // spacer at chunks[2] is empty
             // spacer at chunks[4] is empty (between search and list)
             render_sessions_section(f, state, chunks[3], chunks[5], chunks[6], chunks[7]);
@@ -608,8 +650,8 @@ fn render_shortcuts_section(
     // Calculate scroll position (similar to collapsed messages)
     let max_scroll = total_lines.saturating_sub(height.saturating_sub(SCROLL_BUFFER_LINES));
 
-    state.shortcuts_panel_state.scroll = state.shortcuts_panel_state.scroll.min(max_scroll);
-    let scroll = state.shortcuts_panel_state.scroll;
+    let scroll = state.shortcuts_panel_state.scroll().min(max_scroll);
+    state.shortcuts_panel_state.set_scroll(scroll);
 
     // Add top arrow indicator if there are hidden items above
     let mut visible_lines = Vec::new();
@@ -739,13 +781,11 @@ fn render_sessions_section(
     f.render_widget(Paragraph::new(Line::from(search_spans)), search_area);
 
     // Filter sessions by search term
-    let search_lower = search_term.to_lowercase();
     let filtered_sessions: Vec<(usize, &crate::app::SessionInfo)> = state
         .sessions_state
-        .sessions
-        .iter()
-        .enumerate()
-        .filter(|(_, s)| search_term.is_empty() || s.title.to_lowercase().contains(&search_lower))
+        .filtered_indices(search_term)
+        .into_iter()
+        .map(|i| (i, &state.sessions_state.sessions[i]))
         .collect();
 
     let total_filtered = filtered_sessions.len();
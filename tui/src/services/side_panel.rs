@@ -2,7 +2,7 @@
 //!
 //! This module handles rendering the side panel with its sections:
 //! - Plan: Plan mode status, title, and version (visible only during plan mode)
-//! - Context: Token usage, credits, session time, model
+//! - Context: Token usage, credits, session time, model, auto-approve
 //! - Billing: Subscription plan and credit balance
 //! - Tasks: Task list from agent-board cards
 //! - Changeset: Files modified with edit history
@@ -21,6 +21,14 @@ use ratatui::{
 /// Left padding for content inside the side panel
 const LEFT_PADDING: &str = "  ";
 
+/// Auto-approved tools listed by name in the Context section; the rest are
+/// folded into a "+N more" line.
+const MAX_AUTO_APPROVE_ENTRIES: usize = 3;
+
+/// Row of the Auto-approve line in the Context section, the header being row 0.
+/// Clicking it or the tool names below opens the auto-approve popup.
+pub const CONTEXT_AUTO_APPROVE_ROW: u16 = 5;
+
 /// Render the complete side panel
 pub fn render_side_panel(f: &mut Frame, state: &mut AppState, area: Rect) {
     // Clear the area first
@@ -92,7 +100,8 @@ pub fn render_side_panel(f: &mut Frame, state: &mut AppState, area: Rect) {
     let context_height = if context_collapsed {
         collapsed_height
     } else {
-        6 // Header + Tokens + Model + Provider + Profile
+        // Header + Tokens + Model + Provider + Profile + Auto-approve + tool names
+        7 + auto_approve_entries(state).len() as u16
     };
 
     // Billing section is hidden when billing_info is None (local mode)
@@ -370,10 +379,67 @@ fn render_context_section(f: &mut Frame, state: &AppState, area: Rect, collapsed
         ThemeColors::dark_gray(),
     ));
 
+    // Auto-approved tools this session, toggled with Space in the auto-approve
+    // popup (opened with /toggle_auto_approve or by clicking these rows).
+    // `*` marks changes not saved to the profile or project yet.
+    let auto_approve = &state.configuration_state.auto_approve_manager;
+    let (auto_approve_value, auto_approve_color) = if auto_approve.is_enabled() {
+        let count = auto_approve.get_auto_approved_tool_names().len();
+        let unsaved = if auto_approve.has_unsaved_changes() {
+            " *"
+        } else {
+            ""
+        };
+        (format!("{count} tools{unsaved}"), ThemeColors::green())
+    } else {
+        ("Off".to_string(), ThemeColors::dark_gray())
+    };
+    lines.push(make_row(
+        "Auto-approve",
+        auto_approve_value,
+        auto_approve_color,
+    ));
+
+    // Indented under the Auto-approve label
+    let entry_indent = format!("{}    ", LEFT_PADDING);
+    let entry_width = (area.width as usize).saturating_sub(entry_indent.len() + 2);
+    for entry in auto_approve_entries(state) {
+        let color = if entry.starts_with('+') {
+            ThemeColors::dark_gray()
+        } else {
+            ThemeColors::green()
+        };
+        lines.push(Line::from(vec![
+            Span::raw(entry_indent.clone()),
+            Span::styled(
+                truncate_string(&entry, entry_width),
+                Style::default().fg(color),
+            ),
+        ]));
+    }
+
     let paragraph = Paragraph::new(lines);
     f.render_widget(paragraph, area);
 }
 
+/// Lines listing the auto-approved tools under the Auto-approve row: up to
+/// [`MAX_AUTO_APPROVE_ENTRIES`] names, sorted, then "+N more". Empty when
+/// auto-approve is off.
+fn auto_approve_entries(state: &AppState) -> Vec<String> {
+    let auto_approve = &state.configuration_state.auto_approve_manager;
+    if !auto_approve.is_enabled() {
+        return Vec::new();
+    }
+    let mut names = auto_approve.get_auto_approved_tool_names();
+    names.sort();
+    let hidden = names.len().saturating_sub(MAX_AUTO_APPROVE_ENTRIES);
+    names.truncate(MAX_AUTO_APPROVE_ENTRIES);
+    if hidden > 0 {
+        names.push(format!("+{hidden} more"));
+    }
+    names
+}
+
 /// Render the Billing section
 fn render_billing_section(f: &mut Frame, state: &AppState, area: Rect, collapsed: bool) {
     let focused = state.side_panel_state.focused_section == SidePanelSection::Billing;
@@ -1002,3 +1068,93 @@ fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
         lines
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::AppStateOptions;
+    use crate::services::auto_approve::AutoApprovePolicy;
+    use ratatui::{Terminal, backend::TestBackend};
+    use stakai::Model;
+
+    fn build_state() -> AppState {
+        AppState::new(AppStateOptions {
+            task_manager_handle: None,
+            latest_version: None,
+            redact_secrets: false,
+            privacy_mode: false,
+            is_git_repo: false,
+            auto_approve_tools: None,
+            allowed_tools: None,
+            input_tx: None,
+            model: Model::default(),
+            editor_command: None,
+            auth_display_info: (None, None, None),
+            board_agent_id: None,
+            init_prompt_content: None,
+            recent_models: Vec::new(),
+        })
+    }
+
+    fn render_context(state: &AppState) -> Vec<String> {
+        let height = 7 + auto_approve_entries(state).len() as u16;
+        let mut terminal = Terminal::new(TestBackend::new(32, height)).unwrap();
+        terminal
+            .draw(|f| render_context_section(f, state, f.area(), false))
+            .unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .chunks(32)
+            .map(|row| {
+                let line: String = row.iter().map(|cell| cell.symbol()).collect();
+                line.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn context_lists_auto_approved_tools_and_folds_the_rest() {
+        let mut state = build_state();
+        let manager = &mut state.configuration_state.auto_approve_manager;
+        manager.config.enabled = true;
+        manager.config.tools.clear();
+        for tool in [
+            "view",
+            "search_docs",
+            "create",
+            "str_replace",
+            "run_command",
+        ] {
+            let _ = manager.update_tool_policy(tool, AutoApprovePolicy::Auto);
+        }
+        let _ = manager.update_tool_policy("delete_file", AutoApprovePolicy::Prompt);
+
+        let lines = render_context(&state);
+        let row = CONTEXT_AUTO_APPROVE_ROW as usize;
+        assert!(lines[row].contains("Auto-approve"), "{lines:?}");
+        assert!(lines[row].contains("5 tools"), "{lines:?}");
+        let entries: Vec<&str> = lines[row + 1..]
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        assert_eq!(entries, ["create", "run_command", "search_docs", "+2 more"]);
+    }
+
+    #[test]
+    fn context_lists_no_tools_when_auto_approve_is_off() {
+        let mut state = build_state();
+        let manager = &mut state.configuration_state.auto_approve_manager;
+        manager.config.enabled = false;
+        let _ = manager.update_tool_policy("view", AutoApprovePolicy::Auto);
+
+        let lines = render_context(&state);
+        assert_eq!(lines.len(), CONTEXT_AUTO_APPROVE_ROW as usize + 2);
+        assert!(
+            lines[CONTEXT_AUTO_APPROVE_ROW as usize].ends_with("Off"),
+            "{lines:?}"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Footer with the active model, session token usage and estimated cost.
+//!
+//! Rendered on every frame from the usage tracking state, so it follows
+//! `StreamModel`, `StreamUsage` and `TotalUsage` updates as they arrive.
+//! Toggled with `/status_bar`.
+
+use crate::app::AppState;
+use crate::services::detect_term::ThemeColors;
+use crate::services::helper_block::format_number_with_separator;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use stakai::Model;
+use stakpak_shared::models::llm::LLMTokenUsage;
+
+const SEPARATOR: &str = " · ";
+
+/// Estimated cost in USD of `usage` at `model`'s pricing, or `None` when
+/// the model has no pricing information.
+///
+/// Cached prompt tokens are billed at the cache rates when the usage breaks
+/// them out; otherwise every prompt token is billed as regular input.
+pub fn estimate_cost(model: &Model, usage: &LLMTokenUsage) -> Option<f64> {
+    let cost = model.cost.as_ref()?;
+    let completion = u64::from(usage.completion_tokens);
+    let Some(details) = &usage.prompt_tokens_details else {
+        return Some(cost.calculate(u64::from(usage.prompt_tokens), completion));
+    };
+
+    let cache_read = details.cache_read_input_tokens.unwrap_or(0);
+    let cache_write = details.cache_write_input_tokens.unwrap_or(0);
+    let input = details.input_tokens.unwrap_or_else(|| {
+        usage
+            .prompt_tokens
+            .saturating_sub(cache_read)
+            .saturating_sub(cache_write)
+    });
+    Some(cost.calculate_with_cache(
+        u64::from(input),
+        completion,
+        u64::from(cache_read),
+        u64::from(cache_write),
+    ))
+}
+
+fn format_cost(cost: f64) -> String {
+    if cost > 0.0 && cost < 0.01 {
+        "<$0.01".to_string()
+    } else {
+        format!("${cost:.2}")
+    }
+}
+
+/// The status bar contents, e.g.
+/// `Claude Sonnet 4.5 · ctx 12,000 · 45,678 tokens · $0.12`.
+pub fn status_bar_text(state: &AppState) -> String {
+    let model = state
+        .model_switcher_state
+        .current_model
+        .as_ref()
+        .unwrap_or(&state.configuration_state.model);
+    let usage = &state.usage_tracking_state;
+
+    let mut parts = vec![model.display_name().to_string()];
+    let context_tokens = usage.current_message_usage.prompt_tokens;
+    if context_tokens > 0 {
+        parts.push(format!(
+            "ctx {}",
+            format_number_with_separator(context_tokens)
+        ));
+    }
+    parts.push(format!(
+        "{} tokens",
+        format_number_with_separator(usage.total_session_usage.total_tokens)
+    ));
+    if let Some(cost) = estimate_cost(model, &usage.total_session_usage) {
+        parts.push(format_cost(cost));
+    }
+    parts.join(SEPARATOR)
+}
+
+pub fn render_status_bar(f: &mut Frame, state: &AppState, area: Rect) {
+    let line = Line::from(vec![
+        Span::raw(" "),
+        Span::styled(
+            status_bar_text(state),
+            Style::default().fg(ThemeColors::muted()),
+        ),
+    ]);
+    f.render_widget(Paragraph::new(line), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stakai::{ModelCost, ModelLimit};
+    use stakpak_shared::models::llm::PromptTokensDetails;
+
+    fn priced_model() -> Model {
+        Model::new(
+            "claude-sonnet-4-5",
+            "Claude Sonnet 4.5",
+            "anthropic",
+            true,
+            Some(ModelCost::with_cache(3.0, 15.0, 0.30, 3.75)),
+            ModelLimit::default(),
+        )
+    }
+
+    #[test]
+    fn cache_tokens_are_billed_at_cache_rates() {
+        let usage = LLMTokenUsage {
+            prompt_tokens: 1_300_000,
+            completion_tokens: 100_000,
+            total_tokens: 1_400_000,
+            prompt_tokens_details: Some(PromptTokensDetails {
+                input_tokens: None,
+                output_tokens: None,
+                cache_read_input_tokens: Some(1_000_000),
+                cache_write_input_tokens: Some(200_000),
+            }),
+        };
+        // 100k input + 100k output + 1M cache read + 200k cache write
+        let cost = estimate_cost(&priced_model(), &usage).unwrap_or_default();
+        assert!((cost - (0.3 + 1.5 + 0.3 + 0.75)).abs() < 1e-9);
+
+        assert_eq!(
+            estimate_cost(&Model::custom("local", "ollama"), &usage),
+            None
+        );
+    }
+}
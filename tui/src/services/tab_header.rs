@@ -0,0 +1,147 @@
+//! Tab headers for popups: a title with an optional icon and count badge,
+//! e.g. `⚠ Errors (3)`.
+
+use crate::services::detect_term::ThemeColors;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tab {
+    pub title: String,
+    pub badge: Option<String>,
+    pub icon: Option<char>,
+}
+
+impl Tab {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            badge: None,
+            icon: None,
+        }
+    }
+
+    /// Show `badge` in parentheses after the title, e.g. a count.
+    pub fn badge(mut self, badge: impl Into<String>) -> Self {
+        self.badge = Some(badge.into());
+        self
+    }
+
+    /// Show `icon` before the title. Omitted in fallback mode, where the
+    /// terminal may not render the glyph.
+    pub fn icon(mut self, icon: char) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// The header line, padded with one space on each side like the other
+    /// popup tabs.
+    pub fn header(&self, use_fallback_colors: bool) -> Line<'static> {
+        let mut spans = vec![Span::raw(" ")];
+        if let Some(icon) = self.icon.filter(|_| !use_fallback_colors) {
+            spans.push(Span::raw(format!("{icon} ")));
+        }
+        spans.push(Span::raw(self.title.clone()));
+        if let Some(badge) = &self.badge {
+            let badge_color = if use_fallback_colors {
+                Color::Yellow
+            } else {
+                ThemeColors::accent()
+            };
+            spans.push(Span::styled(
+                format!(" ({badge})"),
+                Style::default().fg(badge_color),
+            ));
+        }
+        spans.push(Span::raw(" "));
+        Line::from(spans)
+    }
+
+    /// Display width of [`Tab::header`] in terminal cells.
+    pub fn header_width(&self, use_fallback_colors: bool) -> usize {
+        self.header(use_fallback_colors)
+            .spans
+            .iter()
+            .map(|span| span.content.width())
+            .sum()
+    }
+}
+
+/// Screen area of each tab when `tabs` are drawn into `area` by a ratatui
+/// `Tabs` widget with its default one-space padding and a divider of
+/// `divider_width` cells. Tabs cut off by the right edge are clipped or left
+/// out.
+pub fn tab_regions(
+    tabs: &[Tab],
+    use_fallback_colors: bool,
+    area: Rect,
+    divider_width: u16,
+) -> Vec<Rect> {
+    let mut regions = Vec::with_capacity(tabs.len());
+    let mut x = area.x;
+    for tab in tabs {
+        if x >= area.right() {
+            break;
+        }
+        let width = u16::try_from(tab.header_width(use_fallback_colors) + 2).unwrap_or(u16::MAX);
+        let width = width.min(area.right() - x);
+        regions.push(Rect::new(x, area.y, width, 1));
+        x = x.saturating_add(width).saturating_add(divider_width);
+    }
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_width_accounts_for_badge_and_icon() {
+        let plain = Tab::new("Errors");
+        assert_eq!(plain.header_width(false), " Errors ".len());
+
+        let badged = Tab::new("Errors").badge("3");
+        assert_eq!(badged.header_width(false), " Errors (3) ".len());
+
+        let full = Tab::new("Errors").badge("3").icon('!');
+        assert_eq!(full.header_width(false), " ! Errors (3) ".len());
+
+        let wide = Tab::new("Errors").icon('⚠');
+        assert_eq!(
+            wide.header_width(false),
+            "  Errors ".len() + '⚠'.to_string().width()
+        );
+    }
+
+    #[test]
+    fn fallback_mode_omits_icon_but_keeps_badge() {
+        let tab = Tab::new("Errors").badge("3").icon('!');
+        let header: String = tab
+            .header(true)
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+
+        assert_eq!(header, " Errors (3) ");
+        assert_eq!(tab.header_width(true), header.len());
+    }
+
+    #[test]
+    fn tab_regions_follow_padding_and_divider() {
+        let tabs = [Tab::new("One"), Tab::new("Three").badge("3")];
+        let regions = tab_regions(&tabs, false, Rect::new(4, 2, 30, 1), 3);
+
+        // " One " plus one cell of padding on each side
+        assert_eq!(regions[0], Rect::new(4, 2, 7, 1));
+        // " Three (3) " starts after the " | " divider
+        assert_eq!(regions[1], Rect::new(14, 2, 13, 1));
+
+        let clipped = tab_regions(&tabs, false, Rect::new(0, 0, 12, 1), 3);
+        assert_eq!(clipped, vec![Rect::new(0, 0, 7, 1), Rect::new(10, 0, 2, 1)]);
+    }
+}
@@ -51,7 +51,6 @@ impl Toast {
     }
 
     /// Create an info toast
-    #[allow(dead_code)]
     pub fn info(message: impl Into<String>) -> Self {
         Self {
             message: message.into(),
@@ -0,0 +1,330 @@
+//! Plain-text copy of the TUI transcript for debugging.
+//!
+//! With `--debug` the event loop hands the messages in `AppState` to a
+//! [`TranscriptLog`] whenever the agent settles, which renders each one as it
+//! would appear in the message area, redacts secrets and appends it to a log
+//! file. Messages are updated in place while a turn runs (streamed responses,
+//! tool blocks, shell output), so only their settled content is logged, and a
+//! message that changes after being logged is logged again. Tool results are
+//! written in full rather than as their truncated preview. The file is
+//! rotated once it grows past a size limit.
+//!
+//! Each session logs to its own file so concurrent runs don't interleave,
+//! and only the most recent sessions' files are kept.
+
+use crate::app::AppState;
+use crate::services::message::{Message, render_single_message};
+use crate::services::message_pattern::spans_to_string;
+use stakpak_shared::secrets::redact_secrets;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Width messages are rendered at, independent of the terminal size.
+const RENDER_WIDTH: usize = 120;
+/// Size after which the log is rotated.
+pub const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+/// Number of rotated files kept next to the log, `<log>.1` being the newest.
+const MAX_ROTATED_FILES: usize = 3;
+/// Session transcripts kept in the log directory, including a new one.
+const MAX_SESSION_TRANSCRIPTS: usize = 10;
+const TRANSCRIPT_PREFIX: &str = "transcript-";
+
+pub struct TranscriptLog {
+    path: PathBuf,
+    file: File,
+    /// Bytes in the current file.
+    written: u64,
+    max_bytes: u64,
+    privacy_mode: bool,
+    /// Hash of the entry last logged for each message.
+    logged: HashMap<Uuid, u64>,
+    /// Message count at the last sync; `None` after [`TranscriptLog::mark_busy`].
+    synced_len: Option<usize>,
+}
+
+impl TranscriptLog {
+    /// Open `path` for appending, creating it and its directory if needed.
+    pub fn open(path: impl Into<PathBuf>, privacy_mode: bool) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = open_append(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            written,
+            max_bytes: DEFAULT_MAX_BYTES,
+            privacy_mode,
+            logged: HashMap::new(),
+            synced_len: None,
+        })
+    }
+
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Record that the agent is working, so messages may be changing in
+    /// place and the next [`TranscriptLog::sync`] re-checks all of them.
+    pub fn mark_busy(&mut self) {
+        self.synced_len = None;
+    }
+
+    /// Append the messages that are new or changed since they were last
+    /// logged, in order. Call it only while [`is_settled`] holds.
+    pub fn sync(&mut self, messages: &[Message]) -> io::Result<()> {
+        if self.synced_len == Some(messages.len()) {
+            return Ok(());
+        }
+        for message in messages {
+            // Full tool results backing a preview are logged through the preview
+            if message.is_collapsed == Some(true) {
+                continue;
+            }
+            let entry = self.render(message);
+            let mut hasher = DefaultHasher::new();
+            entry.hash(&mut hasher);
+            let hash = hasher.finish();
+            if self.logged.insert(message.id, hash) == Some(hash) {
+                continue;
+            }
+            self.append(&entry)?;
+        }
+        self.synced_len = Some(messages.len());
+        Ok(())
+    }
+
+    /// Append every new or changed message, settled or not, e.g. on exit.
+    pub fn flush(&mut self, messages: &[Message]) -> io::Result<()> {
+        self.mark_busy();
+        self.sync(messages)?;
+        self.file.flush()
+    }
+
+    fn render(&self, message: &Message) -> String {
+        let expanded = message.expanded_tool_result();
+        let message = expanded.as_ref().unwrap_or(message);
+        let mut text = render_single_message(message, RENDER_WIDTH)
+            .iter()
+            .map(|line| spans_to_string(line).trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        text.push_str("\n\n");
+        redact_secrets(&text, None, &HashMap::new(), self.privacy_mode).redacted_string
+    }
+
+    fn append(&mut self, entry: &str) -> io::Result<()> {
+        let len = entry.len() as u64;
+        if self.written > 0 && self.written + len > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(entry.as_bytes())?;
+        self.written += len;
+        Ok(())
+    }
+
+    /// Shift `<log>.N` to `<log>.N+1`, dropping the oldest, and start a new
+    /// file at `<log>`.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        for index in (1..MAX_ROTATED_FILES).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                std::fs::rename(&from, rotated_path(&self.path, index + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        self.file = open_append(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+/// Whether the messages have stopped changing: no response is streaming and
+/// no tool call, approval, question or shell command is in flight.
+pub fn is_settled(state: &AppState) -> bool {
+    !state.loading_state.is_loading
+        && !state.dialog_approval_state.is_dialog_open
+        && !state.dialog_approval_state.approval_bar.is_visible()
+        && !state.ask_user_state.is_visible
+        && state.shell_popup_state.active_shell_command.is_none()
+}
+
+/// Path for a new session's transcript in `dir`, named by start time and
+/// process id. Transcripts of older sessions beyond the most recent few are
+/// deleted, along with their rotated files.
+pub fn new_session_path(dir: &Path) -> PathBuf {
+    // Best effort: a transcript that can't be removed only costs disk space
+    let _ = prune_transcripts(dir, MAX_SESSION_TRANSCRIPTS - 1);
+    dir.join(format!(
+        "{TRANSCRIPT_PREFIX}{}-{}.log",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        std::process::id()
+    ))
+}
+
+/// Delete every session transcript in `dir` but the newest `keep`.
+fn prune_transcripts(dir: &Path, keep: usize) -> io::Result<()> {
+    let mut files: Vec<(String, PathBuf)> = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        // `<session>.log` and its rotations `<session>.log.N` share a session
+        if name.starts_with(TRANSCRIPT_PREFIX)
+            && let Some((session, _)) = name.split_once(".log")
+        {
+            files.push((session.to_string(), path.clone()));
+        }
+    }
+
+    // Names start with the session's start time, so they sort oldest first
+    let mut sessions: Vec<&str> = files.iter().map(|(session, _)| session.as_str()).collect();
+    sessions.sort_unstable();
+    sessions.dedup();
+    let stale = &sessions[..sessions.len().saturating_sub(keep)];
+    for (session, path) in &files {
+        if stale.contains(&session.as_str()) {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{index}"));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(path: &Path) -> String {
+        std::fs::read_to_string(path).unwrap_or_default()
+    }
+
+    #[test]
+    fn messages_are_logged_in_order_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join("transcript.log");
+        let mut log = TranscriptLog::open(&path, false).unwrap();
+
+        let mut messages = vec![
+            Message::user("first question", None),
+            Message::info("second note", None),
+        ];
+        log.sync(&messages).unwrap();
+        log.mark_busy();
+        log.sync(&messages).unwrap();
+
+        messages.push(Message::info("third note", None));
+        log.sync(&messages).unwrap();
+        log.flush(&messages).unwrap();
+
+        let contents = read(&path);
+        let positions: Vec<usize> = ["first question", "second note", "third note"]
+            .iter()
+            .map(|text| {
+                assert_eq!(contents.matches(text).count(), 1, "{text} logged once");
+                contents.find(text).unwrap()
+            })
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn messages_changed_in_place_are_logged_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("transcript.log");
+        let mut log = TranscriptLog::open(&path, false).unwrap();
+
+        let mut messages = vec![Message::info("command running", None)];
+        log.sync(&messages).unwrap();
+
+        let id = messages[0].id;
+        messages[0] = Message::info("command finished", None);
+        messages[0].id = id;
+        // Unchanged length, so nothing is re-checked until the agent was busy
+        log.sync(&messages).unwrap();
+        assert!(!read(&path).contains("command finished"));
+
+        log.mark_busy();
+        log.sync(&messages).unwrap();
+        let contents = read(&path);
+        assert_eq!(contents.matches("command running").count(), 1);
+        assert_eq!(contents.matches("command finished").count(), 1);
+    }
+
+    #[test]
+    fn log_rotates_past_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("transcript.log");
+        let mut log = TranscriptLog::open(&path, false).unwrap().with_max_bytes(1);
+
+        let messages = vec![
+            Message::info("one", None),
+            Message::info("two", None),
+            Message::info("three", None),
+        ];
+        log.flush(&messages).unwrap();
+
+        assert!(read(&path).contains("three"));
+        assert!(read(&rotated_path(&path, 1)).contains("two"));
+        assert!(read(&rotated_path(&path, 2)).contains("one"));
+    }
+
+    #[test]
+    fn only_the_newest_session_transcripts_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "transcript-20260101-090000-11.log",
+            "transcript-20260101-090000-11.log.1",
+            "transcript-20260102-090000-12.log",
+            "transcript-20260103-090000-13.log",
+            "transcript-20260103-090000-13.log.1",
+            "agent.log",
+        ] {
+            std::fs::write(dir.path().join(name), "x").unwrap();
+        }
+
+        prune_transcripts(dir.path(), 2).unwrap();
+
+        let mut remaining: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            vec![
+                "agent.log",
+                "transcript-20260102-090000-12.log",
+                "transcript-20260103-090000-13.log",
+                "transcript-20260103-090000-13.log.1",
+            ]
+        );
+
+        let path = new_session_path(dir.path());
+        assert!(
+            path.file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with(TRANSCRIPT_PREFIX)
+        );
+    }
+}
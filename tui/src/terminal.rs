@@ -1,8 +1,102 @@
+//! Terminal setup undo for the TUI.
+//!
+//! [`TerminalGuard`] restores the terminal when the event loop returns and,
+//! through a panic hook, when the agent panics mid-run, so a crash does not
+//! leave the shell in raw mode with mouse capture on. The hook only touches
+//! the terminal while a guard is alive; panics before or after the TUI go
+//! straight to the previous hook.
+
+use crossterm::{
+    cursor::Show,
+    event::{DisableBracketedPaste, DisableMouseCapture},
+    terminal::LeaveAlternateScreen,
+};
+use std::io::{self, Write};
+use std::sync::Once;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static PANIC_HOOK: Once = Once::new();
+/// Set while a [`TerminalGuard`] owns the terminal.
+static IN_TUI: AtomicBool = AtomicBool::new(false);
+
 pub struct TerminalGuard;
 
+impl TerminalGuard {
+    /// Create the guard and install the panic hook that restores the terminal
+    /// before the previous hook prints the panic message.
+    pub fn new() -> Self {
+        PANIC_HOOK.call_once(|| {
+            let previous = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                // Only the first of the hook and the guard's drop restores
+                if IN_TUI.swap(false, Ordering::SeqCst) {
+                    Self::restore();
+                }
+                previous(info);
+            }));
+        });
+        IN_TUI.store(true, Ordering::SeqCst);
+        Self
+    }
+
+    /// Leave raw mode and undo the screen, mouse, paste and cursor changes the
+    /// TUI makes. Failures are ignored: this runs on the way out.
+    pub fn restore() {
+        let _ = crossterm::terminal::disable_raw_mode();
+        let _ = Self::restore_to(&mut io::stdout());
+    }
+
+    /// Write the commands that undo the TUI's terminal setup to `out`.
+    pub fn restore_to(out: &mut impl Write) -> io::Result<()> {
+        crossterm::execute!(
+            out,
+            DisableMouseCapture,
+            DisableBracketedPaste,
+            LeaveAlternateScreen,
+            Show
+        )
+    }
+}
+
+impl Default for TerminalGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Drop for TerminalGuard {
     fn drop(&mut self) {
-        let _ = crossterm::terminal::disable_raw_mode();
-        let _ = crossterm::execute!(std::io::stdout(), crossterm::terminal::LeaveAlternateScreen);
+        if IN_TUI.swap(false, Ordering::SeqCst) {
+            Self::restore();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::Command;
+
+    fn ansi(command: impl Command) -> String {
+        let mut out = String::new();
+        let _ = command.write_ansi(&mut out);
+        out
+    }
+
+    #[test]
+    fn restore_disables_mouse_capture_and_shows_cursor() {
+        let mut out = Vec::new();
+        TerminalGuard::restore_to(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        for expected in [
+            ansi(DisableMouseCapture),
+            ansi(DisableBracketedPaste),
+            ansi(LeaveAlternateScreen),
+            ansi(Show),
+        ] {
+            assert!(!expected.is_empty());
+            assert!(out.contains(&expected), "missing {expected:?} in {out:?}");
+        }
     }
 }
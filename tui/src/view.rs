@@ -1,8 +1,9 @@
 use crate::app::AppState;
 use crate::constants::{DROPDOWN_MAX_HEIGHT, SCROLL_BUFFER_LINES};
-use crate::services::detect_term::ThemeColors;
+use crate::services::detect_term::{ThemeColors, downsample_buffer, should_use_rgb_colors};
 use crate::services::helper_dropdown::{render_file_search_dropdown, render_helper_dropdown};
 use crate::services::hint_helper::render_hint_or_shortcuts;
+use crate::services::hit_test::{ClickTarget, HitTestMap};
 use crate::services::message::{
     get_wrapped_collapsed_message_lines_cached, get_wrapped_message_lines_cached,
 };
@@ -20,6 +21,9 @@ use ratatui::{
 };
 
 pub fn view(f: &mut Frame, state: &mut AppState) {
+    // Clickable regions of this frame, published for the input thread at the end
+    let mut hit_test = HitTestMap::default();
+
     // Full-width banner at the top (height=0 when no active message)
     let banner_h = banner::banner_height(state);
     let vertical_chunks = Layout::default()
@@ -138,7 +142,9 @@ pub fn view(f: &mut Frame, state: &mut AppState) {
         dropdown_height
     };
 
-    // Layout: [messages][loading_line][shell_popup][approval_bar][queue][input][dropdown][hint]
+    let status_bar_height = u16::from(state.terminal_ui_state.show_status_bar);
+
+    // Layout: [messages][loading_line][shell_popup][approval_bar][queue][input][dropdown][hint][status_bar]
     let effective_approval_bar_height = if approval_bar_visible {
         approval_bar_height
     } else {
@@ -154,6 +160,7 @@ pub fn view(f: &mut Frame, state: &mut AppState) {
         Constraint::Length(effective_input_height), // input (0 when approval bar visible)
         Constraint::Length(effective_dropdown_height), // dropdown (0 when approval bar visible)
         Constraint::Length(hint_height), // hint
+        Constraint::Length(status_bar_height), // status bar (0 if toggled off)
     ];
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -168,6 +175,7 @@ pub fn view(f: &mut Frame, state: &mut AppState) {
     let input_area = chunks[5];
     let dropdown_area = chunks[6];
     let hint_area = chunks[7];
+    let status_bar_area = chunks[8];
 
     // Create padded message area for content rendering
     let padded_message_area = Rect {
@@ -193,6 +201,7 @@ pub fn view(f: &mut Frame, state: &mut AppState) {
         message_area_width,
         message_area_height,
     );
+    hit_test.register(message_area, ClickTarget::Messages);
 
     // Render approval bar in its dedicated area (if visible)
     if approval_bar_visible {
@@ -205,7 +214,7 @@ pub fn view(f: &mut Frame, state: &mut AppState) {
         state
             .dialog_approval_state
             .approval_bar
-            .render(f, padded_approval_bar_area);
+            .render(f, padded_approval_bar_area, &mut hit_test);
     }
 
     // Render shell popup above input area (if visible)
@@ -238,7 +247,7 @@ pub fn view(f: &mut Frame, state: &mut AppState) {
     }
 
     if state.messages_scrolling_state.show_collapsed_messages {
-        render_collapsed_messages_popup(f, state);
+        render_collapsed_messages_popup(f, state, &mut hit_test);
     } else if state.dialog_approval_state.is_dialog_open {
     } else if state.shell_popup_state.is_visible && state.shell_popup_state.is_expanded {
         // Don't render input when popup is expanded - popup takes over input
@@ -263,50 +272,64 @@ pub fn view(f: &mut Frame, state: &mut AppState) {
         render_hint_or_shortcuts(f, state, padded_hint_area);
     }
 
+    if state.terminal_ui_state.show_status_bar {
+        crate::services::status_bar::render_status_bar(f, state, status_bar_area);
+    }
+
     // === POPUPS - rendered last to appear on top of side panel ===
+    // Modal popups handle their own mouse input (or none), so each one blocks
+    // the whole screen for the clickable regions registered before it.
 
     // Render profile switcher
     if state.profile_switcher_state.show_profile_switcher {
         crate::services::profile_switcher::render_profile_switcher_popup(f, state);
+        hit_test.block(f.area());
     }
 
     // Render file changes popup
     if state.file_changes_popup_state.is_visible {
         crate::services::file_changes_popup::render_file_changes_popup(f, state);
+        hit_test.block(f.area());
     }
 
     // Render shortcuts popup (now includes commands)
     if state.shortcuts_panel_state.is_visible {
-        crate::services::shortcuts_popup::render_shortcuts_popup(f, state);
+        crate::services::shortcuts_popup::render_shortcuts_popup(f, state, &mut hit_test);
     }
     // Render rulebook switcher
     if state.rulebook_switcher_state.show_rulebook_switcher {
         crate::services::rulebook_switcher::render_rulebook_switcher_popup(f, state);
+        hit_test.block(f.area());
     }
 
     // Render message action popup
     if state.message_interaction_state.show_message_action_popup {
         crate::services::message_action_popup::render_message_action_popup(f, state);
+        hit_test.block(f.area());
     }
 
     // Render model switcher
     if state.model_switcher_state.is_visible {
         crate::services::model_switcher::render_model_switcher_popup(f, state);
+        hit_test.block(f.area());
     }
 
     // Render auto-approve popup
     if state.tool_approval_popup_state.is_visible {
         crate::services::auto_approve_popup::render_auto_approve_popup(f, state);
+        hit_test.block(f.area());
     }
 
     // Render policy persistence modal (unsaved changes on quit)
     if state.approval_settings_persistence_state.is_visible {
         crate::services::policy_persistence_popup::render_policy_persistence_popup(f, state);
+        hit_test.block(f.area());
     }
 
     // Render profile switch overlay
     if state.profile_switcher_state.switching_in_progress {
         crate::services::profile_switcher::render_profile_switch_overlay(f, state);
+        hit_test.block(f.area());
     }
 
     // Render toast notification (highest z-index, always on top)
@@ -315,11 +338,22 @@ pub fn view(f: &mut Frame, state: &mut AppState) {
     // Render "existing plan found" modal
     if state.plan_mode_state.existing_prompt.is_some() {
         render_existing_plan_modal(f, state);
+        hit_test.block(f.area());
     }
 
     // Render plan review overlay (full-screen, on top of everything)
     if state.plan_review_state.is_visible {
         crate::services::plan_review::render_plan_review(f, state, f.area());
+        hit_test.block(f.area());
+    }
+
+    // Terminals without truecolor get every RGB style mapped to the 256-color palette
+    if !should_use_rgb_colors() {
+        downsample_buffer(f.buffer_mut());
+    }
+
+    if let Ok(mut shared) = state.terminal_ui_state.hit_test.write() {
+        *shared = hit_test;
     }
 }
 
@@ -592,7 +626,7 @@ fn render_messages(f: &mut Frame, state: &mut AppState, area: Rect, width: usize
     f.render_widget(message_widget, area);
 }
 
-fn render_collapsed_messages_popup(f: &mut Frame, state: &mut AppState) {
+fn render_collapsed_messages_popup(f: &mut Frame, state: &mut AppState, hit_test: &mut HitTestMap) {
     let screen = f.area();
     // Create a full-screen popup
     let popup_area = Rect {
@@ -611,7 +645,7 @@ fn render_collapsed_messages_popup(f: &mut Frame, state: &mut AppState) {
         .border_style(ratatui::style::Style::default().fg(ThemeColors::magenta()))
         .style(ratatui::style::Style::default())
         .title(ratatui::text::Span::styled(
-            "Expanded Messages (ctrl+t to close, tab to previous message, ↑/↓ to scroll)",
+            "Expanded Messages (ctrl+t to close, tab to previous message, enter to expand inline, ctrl+r to retry a failed call, ↑/↓ to scroll)",
             ratatui::style::Style::default()
                 .fg(ThemeColors::magenta())
                 .add_modifier(ratatui::style::Modifier::BOLD),
@@ -627,6 +661,8 @@ fn render_collapsed_messages_popup(f: &mut Frame, state: &mut AppState) {
 
     // Render the block with background
     f.render_widget(block, popup_area);
+    hit_test.block(popup_area);
+    hit_test.register(content_area, ClickTarget::Messages);
 
     // Render collapsed messages using the same logic as render_messages
     render_collapsed_messages_content(f, state, content_area);